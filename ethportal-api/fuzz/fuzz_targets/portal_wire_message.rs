@@ -0,0 +1,12 @@
+//! Decodes arbitrary bytes as an incoming portal wire message, the same way the overlay does.
+//!
+//! Run with `cargo +nightly fuzz run portal_wire_message` from the `ethportal-api` directory.
+
+#![no_main]
+
+use ethportal_api::types::portal_wire::decode_like_overlay;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = decode_like_overlay(data);
+});
@@ -1,4 +1,4 @@
-use alloy::primitives::B256;
+use alloy::primitives::{Bytes, B256};
 use discv5::enr::NodeId;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
@@ -6,19 +6,32 @@ use crate::{
     consensus::header::BeaconBlockHeader,
     light_client::store::LightClientStore,
     types::{
-        consensus::light_client::{
-            finality_update::LightClientFinalityUpdate,
-            optimistic_update::LightClientOptimisticUpdate,
+        consensus::{
+            historical_summaries::HistoricalSummariesWithProof,
+            light_client::{
+                finality_update::LightClientFinalityUpdate,
+                optimistic_update::LightClientOptimisticUpdate,
+            },
         },
         content_key::beacon::BeaconContentKey,
-        enr::Enr,
+        enr::{Enr, RpcEnr},
         portal::{
-            AcceptInfo, DataRadius, FindContentInfo, FindNodesInfo, GetContentInfo,
-            PaginateLocalContentInfo, PongInfo, TraceContentInfo, TraceGossipInfo,
+            AcceptInfo, AcceptSnapshotInfo, ActiveLookupInfo, BandwidthLimitInfo,
+            BeaconContentTypeCounts, ContentInspectionInfo, ContentOrClosestInfo,
+            ContentValidationInfo, FetchAndStoreInfo, FindContentInfo, FindNodesInfo,
+            GcOrphansInfo, GetContentInfo, GetEnrInfo, HeaderUpdateTimesInfo, LocalContentInfo,
+            LookupEnrInfo, NearestStoredKeyInfo, OfferAndVerifyInfo, OfferManyInfo,
+            OverlayNetworkInfo, PaginateLocalContentInfo, PingRawInfo, PinnedContentInfo, PongInfo,
+            PrefetchStatusInfo, PruneEstimateInfo, RangeCoverageInfo, RecentOfferersInfo,
+            SelfTestInfo, StorageBackendInfo, StorageInfo, StoreLatencyStats, TraceAnalysisInfo,
+            TraceContentInfo, TraceGossipInfo, UtpLimitInfo, ValidationMode, ValidationModeInfo,
+            ValidationReportInfo, VerifyPeersInfo,
         },
         portal_wire::OfferTrace,
+        query_trace::QueryTrace,
     },
-    RawContentValue, RoutingTableInfo,
+    BucketRefreshTimesInfo, ContentValueFormat, ContentValueParam, KeyspaceDensityInfo,
+    RawContentValue, ReplacementCacheInfo, RoutingTableInfo,
 };
 
 /// Portal Beacon JSON-RPC endpoints
@@ -28,17 +41,58 @@ pub trait BeaconNetworkApi {
     #[method(name = "beaconRoutingTableInfo")]
     async fn routing_table_info(&self) -> RpcResult<RoutingTableInfo>;
 
-    /// Returns the node data radios
+    /// Returns, per bucket of the overlay routing table, the ENRs in its replacement cache that are
+    /// waiting to be promoted into the bucket.
+    #[method(name = "beaconReplacementCache")]
+    async fn replacement_cache(&self) -> RpcResult<ReplacementCacheInfo>;
+
+    /// Returns, per bucket of the overlay routing table, how many peers it has relative to the
+    /// number of nodes expected at its distance, to find the regions of the node id space that the
+    /// node knows too few peers in.
+    #[method(name = "beaconKeyspaceDensity")]
+    async fn keyspace_density(&self) -> RpcResult<KeyspaceDensityInfo>;
+
+    /// Returns, per bucket of the overlay routing table, how long ago it was last refreshed by a
+    /// FINDNODE lookup, to find the buckets that are going stale.
+    #[method(name = "beaconBucketRefreshTimes")]
+    async fn bucket_refresh_times(&self) -> RpcResult<BucketRefreshTimesInfo>;
+
+    /// Returns the node data radius, as a 0x-prefixed, 32-byte hex string
     #[method(name = "beaconRadius")]
-    async fn radius(&self) -> RpcResult<DataRadius>;
+    async fn radius(&self) -> RpcResult<B256>;
 
-    /// Write an Ethereum Node Record to the overlay routing table.
+    /// Write an Ethereum Node Record to the overlay routing table. ENRs without a reachable
+    /// address are rejected, unless `allow_unreachable` is set.
     #[method(name = "beaconAddEnr")]
-    async fn add_enr(&self, enr: Enr) -> RpcResult<bool>;
+    async fn add_enr(&self, enr: RpcEnr, allow_unreachable: Option<bool>) -> RpcResult<bool>;
 
     /// Fetch the latest ENR associated with the given node ID.
+    /// The multiaddrs of the ENR are included if `include_multiaddrs` is set.
     #[method(name = "beaconGetEnr")]
-    async fn get_enr(&self, node_id: NodeId) -> RpcResult<Enr>;
+    async fn get_enr(
+        &self,
+        node_id: NodeId,
+        include_multiaddrs: Option<bool>,
+    ) -> RpcResult<LookupEnrInfo>;
+
+    /// Fetch the latest ENR associated with the given node ID, along with where it was found.
+    /// The multiaddrs of the ENR are included if `include_multiaddrs` is set.
+    #[method(name = "beaconGetEnrInfo")]
+    async fn get_enr_info(
+        &self,
+        node_id: NodeId,
+        include_multiaddrs: Option<bool>,
+    ) -> RpcResult<GetEnrInfo>;
+
+    /// Fetch the decoded value of a field of the latest ENR associated with the given node ID, or
+    /// null if the ENR doesn't have the field. The values of fields with unknown types are
+    /// returned as hex.
+    #[method(name = "beaconGetEnrField")]
+    async fn get_enr_field(
+        &self,
+        node_id: NodeId,
+        key: String,
+    ) -> RpcResult<Option<serde_json::Value>>;
 
     /// Delete Node ID from the overlay routing table.
     #[method(name = "beaconDeleteEnr")]
@@ -48,18 +102,41 @@ pub trait BeaconNetworkApi {
     #[method(name = "beaconLightClientStore")]
     async fn light_client_store(&self) -> RpcResult<LightClientStore>;
 
+    /// Returns how long ago the light client last updated its optimistic and finalized headers.
+    #[method(name = "beaconHeaderUpdateTimes")]
+    async fn header_update_times(&self) -> RpcResult<HeaderUpdateTimesInfo>;
+
     /// Fetch the ENR representation associated with the given Node ID.
+    /// The multiaddrs of the ENR are included if `include_multiaddrs` is set.
     #[method(name = "beaconLookupEnr")]
-    async fn lookup_enr(&self, node_id: NodeId) -> RpcResult<Enr>;
+    async fn lookup_enr(
+        &self,
+        node_id: NodeId,
+        include_multiaddrs: Option<bool>,
+    ) -> RpcResult<LookupEnrInfo>;
 
     /// Send a PING message to the designated node and wait for a PONG response
     #[method(name = "beaconPing")]
-    async fn ping(&self, enr: Enr) -> RpcResult<PongInfo>;
+    async fn ping(&self, enr: RpcEnr) -> RpcResult<PongInfo>;
+
+    /// Send a PING message to the designated node, and return the wire bytes of the PING and of
+    /// the response, along with the decoded PONG
+    #[method(name = "beaconPingRaw")]
+    async fn ping_raw(&self, enr: RpcEnr) -> RpcResult<PingRawInfo>;
+
+    /// Send a raw discv5 TALKREQ message with the given protocol id and payload to the designated
+    /// node, and return the raw response. Meant for testing protocol extensions.
+    #[method(name = "beaconTalkReq")]
+    async fn talk_req(&self, enr: RpcEnr, protocol_id: Bytes, payload: Bytes) -> RpcResult<Bytes>;
 
     /// Get the finalized state root of the finalized beacon header.
     #[method(name = "beaconFinalizedStateRoot")]
     async fn finalized_state_root(&self) -> RpcResult<B256>;
 
+    /// Get the latest historical summaries with proof that were accepted by the node.
+    #[method(name = "beaconHistoricalSummaries")]
+    async fn historical_summaries(&self) -> RpcResult<HistoricalSummariesWithProof>;
+
     /// Get the finalized beacon header
     #[method(name = "beaconFinalizedHeader")]
     async fn finalized_header(&self) -> RpcResult<BeaconBlockHeader>;
@@ -73,9 +150,17 @@ pub trait BeaconNetworkApi {
     async fn optimistic_update(&self) -> RpcResult<LightClientOptimisticUpdate>;
 
     /// Send a FINDNODES request for nodes that fall within the given set of distances, to the
-    /// designated peer and wait for a response
+    /// designated peer and wait for a response. When `max_results` is given, at most that many
+    /// of the returned ENRs are kept, in the order that the peer returned them. The ENRs are
+    /// dropped once the whole response has been received, so this doesn't bound the size of the
+    /// response that the peer sends.
     #[method(name = "beaconFindNodes")]
-    async fn find_nodes(&self, enr: Enr, distances: Vec<u16>) -> RpcResult<FindNodesInfo>;
+    async fn find_nodes(
+        &self,
+        enr: RpcEnr,
+        distances: Vec<u16>,
+        max_results: Option<u64>,
+    ) -> RpcResult<FindNodesInfo>;
 
     /// Lookup a target node within in the network
     #[method(name = "beaconRecursiveFindNodes")]
@@ -89,10 +174,20 @@ pub trait BeaconNetworkApi {
     #[method(name = "beaconFindContent")]
     async fn find_content(
         &self,
-        enr: Enr,
+        enr: RpcEnr,
         content_key: BeaconContentKey,
     ) -> RpcResult<FindContentInfo>;
 
+    /// Send FINDCONTENT message to get the content with a content key, and store the content
+    /// after validating it. Content outside our radius is stored only if `ignore_radius` is set.
+    #[method(name = "beaconFetchAndStore")]
+    async fn fetch_and_store(
+        &self,
+        enr: RpcEnr,
+        content_key: BeaconContentKey,
+        ignore_radius: Option<bool>,
+    ) -> RpcResult<FetchAndStoreInfo>;
+
     /// First checks local storage if content is not found lookup a target content key in the
     /// network
     #[method(name = "beaconGetContent")]
@@ -104,6 +199,16 @@ pub trait BeaconNetworkApi {
     async fn trace_get_content(&self, content_key: BeaconContentKey)
         -> RpcResult<TraceContentInfo>;
 
+    /// Same as `beaconTraceGetContent`, but if the content isn't found before the lookup is
+    /// exhausted or `timeout_ms` elapses, returns the closest peers to the content that were
+    /// discovered during the lookup instead of an error.
+    #[method(name = "beaconGetContentOrClosest")]
+    async fn get_content_or_closest(
+        &self,
+        content_key: BeaconContentKey,
+        timeout_ms: Option<u64>,
+    ) -> RpcResult<ContentOrClosestInfo>;
+
     /// Pagination of local content keys
     #[method(name = "beaconPaginateLocalContentKeys")]
     async fn paginate_local_content_keys(
@@ -134,33 +239,266 @@ pub trait BeaconNetworkApi {
     /// response. Does not store the content locally.
     /// Returns the content keys bitlist upon successful content transmission or empty bitlist
     /// receive.
+    /// The content values are hex encoded SSZ bytes, unless `format` is `json`, in which case
+    /// they are JSON objects that the node SSZ encodes itself.
+    /// If `include_accept_bitfield` is set, the response also contains the SSZ encoded accept
+    /// bitlist, as sent on the wire.
     #[method(name = "beaconOffer")]
     async fn offer(
         &self,
-        enr: Enr,
-        content_items: Vec<(BeaconContentKey, RawContentValue)>,
+        enr: RpcEnr,
+        content_items: Vec<(BeaconContentKey, ContentValueParam)>,
+        format: Option<ContentValueFormat>,
+        include_accept_bitfield: Option<bool>,
     ) -> RpcResult<AcceptInfo>;
 
+    /// Send an OFFER request with given ContentItems, to the designated peer and wait for a
+    /// response. Does not store the content locally.
+    /// Returns which of the offered content keys the peer accepted and which it declined, in the
+    /// order they were offered.
+    #[method(name = "beaconOfferMany")]
+    async fn offer_many(
+        &self,
+        enr: RpcEnr,
+        content_items: Vec<(BeaconContentKey, RawContentValue)>,
+    ) -> RpcResult<OfferManyInfo<BeaconContentKey>>;
+
     /// Send an OFFER request with given ContentItems, to the designated peer.
     /// Does not store the content locally.
     /// Returns trace info for the offer.
     #[method(name = "beaconTraceOffer")]
     async fn trace_offer(
         &self,
-        enr: Enr,
+        enr: RpcEnr,
         content_key: BeaconContentKey,
         content_value: RawContentValue,
     ) -> RpcResult<OfferTrace>;
 
+    /// Send an OFFER request with the content to the designated peer, and then a FINDCONTENT
+    /// request for it, to verify that the peer stored the content and serves it.
+    /// Does not store the content locally.
+    #[method(name = "beaconOfferAndVerify")]
+    async fn offer_and_verify(
+        &self,
+        enr: RpcEnr,
+        content_key: BeaconContentKey,
+        content_value: RawContentValue,
+    ) -> RpcResult<OfferAndVerifyInfo>;
+
     /// Store content key with a content data to the local database.
+    /// The content value is hex encoded SSZ bytes, unless `format` is `json`, in which case it's
+    /// a JSON object that the node SSZ encodes itself.
+    ///
+    /// If the signature is set, the content is only stored if it's a signature of a trusted
+    /// signer over the content key followed by the SSZ encoded content value. Beacon content
+    /// isn't stored with metadata, so it isn't marked as trusted.
     #[method(name = "beaconStore")]
     async fn store(
         &self,
         content_key: BeaconContentKey,
-        content_value: RawContentValue,
+        content_value: ContentValueParam,
+        format: Option<ContentValueFormat>,
+        signature: Option<Bytes>,
     ) -> RpcResult<bool>;
 
-    /// Get a content from the local database
+    /// Get a content value from the local database. Its metadata, i.e. whether it was stored with
+    /// a signature of a trusted signer, is included if `include_meta` is set.
     #[method(name = "beaconLocalContent")]
-    async fn local_content(&self, content_key: BeaconContentKey) -> RpcResult<RawContentValue>;
+    async fn local_content(
+        &self,
+        content_key: BeaconContentKey,
+        include_meta: Option<bool>,
+    ) -> RpcResult<LocalContentInfo>;
+
+    /// Returns the size of the locally stored content, and how much is saved by content dedup.
+    #[method(name = "beaconStorageInfo")]
+    async fn storage_info(&self) -> RpcResult<StorageInfo>;
+
+    /// Returns the number of locally stored entries per content type.
+    #[method(name = "beaconContentTypeBreakdown")]
+    async fn content_type_breakdown(&self) -> RpcResult<BeaconContentTypeCounts>;
+
+    /// Returns the type, path and metadata of the database the content is stored in. If
+    /// `redact_path` is set, only the file name of the database is returned.
+    #[method(name = "beaconStorageBackendInfo")]
+    async fn storage_backend_info(
+        &self,
+        redact_path: Option<bool>,
+    ) -> RpcResult<StorageBackendInfo>;
+
+    /// Estimate how soon the locally stored content will be pruned or replaced. This is a
+    /// heuristic, based on how often newer content was stored recently.
+    #[method(name = "beaconPruneEstimate")]
+    async fn prune_estimate(&self, content_key: BeaconContentKey) -> RpcResult<PruneEstimateInfo>;
+
+    /// Return the p50/p95/p99 latencies of reading from and writing to the local store, over the
+    /// most recent operations since the node started.
+    #[method(name = "beaconStoreLatencyStats")]
+    async fn store_latency_stats(&self) -> RpcResult<StoreLatencyStats>;
+
+    /// Recompute the node's data radius from the current storage usage, and return it as a
+    /// 0x-prefixed, 32-byte hex string.
+    #[method(name = "beaconRecomputeRadius")]
+    async fn recompute_radius(&self) -> RpcResult<B256>;
+
+    /// Ping up to `limit` peers from the routing table and return which of them responded.
+    /// If `evict` is set, the peers that didn't respond are removed from the routing table.
+    #[method(name = "beaconVerifyPeers")]
+    async fn verify_peers(&self, limit: u64, evict: bool) -> RpcResult<VerifyPeersInfo>;
+
+    /// Return up to `limit` peers that recently offered us content, the most recent first, with
+    /// how many offers they sent and how many of the offered content keys we accepted.
+    #[method(name = "beaconRecentOfferers")]
+    async fn recent_offerers(&self, limit: u64) -> RpcResult<RecentOfferersInfo>;
+
+    /// Report the current status of the peers in a previously recorded query trace: whether they
+    /// are in the routing table and whether they respond to a ping. No new lookup is performed.
+    #[method(name = "beaconAnalyzeTrace")]
+    async fn analyze_trace(&self, trace: QueryTrace) -> RpcResult<TraceAnalysisInfo>;
+
+    /// Set the max rate, in bytes per second, at which uTP packets are sent, shared by all the
+    /// transfers of all the subnetworks. This replaces the limit set by `--utp-max-up-kbps`.
+    /// `None` removes the limit. Returns the current limit.
+    #[method(name = "beaconSetBandwidthLimit")]
+    async fn set_bandwidth_limit(
+        &self,
+        bytes_per_sec: Option<u64>,
+    ) -> RpcResult<BandwidthLimitInfo>;
+
+    /// Return the max number of uTP transfers that run at once, and the number of transfers that
+    /// are running.
+    #[method(name = "beaconGetUtpLimit")]
+    async fn get_utp_limit(&self) -> RpcResult<UtpLimitInfo>;
+
+    /// Set the max number of uTP transfers that run at once. The transfers above the limit wait
+    /// for a running transfer to finish, rather than fail. Returns the new limit, and the number
+    /// of transfers that are running.
+    #[method(name = "beaconSetUtpLimit")]
+    async fn set_utp_limit(&self, limit: u32) -> RpcResult<UtpLimitInfo>;
+
+    /// Force-close the uTP transfer with the peer over the connection with the given id, as
+    /// exchanged in the Portal wire messages. Returns an error if no such transfer is in progress.
+    #[method(name = "beaconCloseTransfer")]
+    async fn close_transfer(&self, node_id: NodeId, connection_id: u16) -> RpcResult<bool>;
+
+    /// Look up the content of the given keys on the network in the background, and store the
+    /// content that is found locally. Returns the id of the prefetch job immediately, or an error
+    /// if too many prefetch jobs are running.
+    #[method(name = "beaconPrefetch")]
+    async fn prefetch(&self, content_keys: Vec<BeaconContentKey>) -> RpcResult<u64>;
+
+    /// Return the progress of the prefetch job.
+    #[method(name = "beaconPrefetchStatus")]
+    async fn prefetch_status(&self, job_id: u64) -> RpcResult<PrefetchStatusInfo>;
+
+    /// Cancel the prefetch job, if it is still running, and return its status.
+    #[method(name = "beaconCancelPrefetch")]
+    async fn cancel_prefetch(&self, job_id: u64) -> RpcResult<PrefetchStatusInfo>;
+
+    /// Return the radius and uTP transfer limit that offers are currently accepted by, with a
+    /// version that is bumped whenever they change.
+    #[method(name = "beaconAcceptSnapshot")]
+    async fn accept_snapshot(&self) -> RpcResult<AcceptSnapshotInfo>;
+
+    /// Check the local store, the content id derivation and the radius computation with synthetic
+    /// data, which is removed afterwards, and return whether each check passed and how long it
+    /// took.
+    #[method(name = "beaconSelfTest")]
+    async fn self_test(&self) -> RpcResult<SelfTestInfo>;
+
+    /// Set how strictly the content accepted from other nodes is validated: `strict` rejects
+    /// content that fails any check, `lenient` accepts content that fails only non-critical
+    /// checks (e.g. an invalid proof) and tags it, and `off` skips validation. Returns the mode
+    /// and the content recently tagged in lenient mode.
+    #[method(name = "beaconSetValidationMode")]
+    async fn set_validation_mode(
+        &self,
+        mode: ValidationMode,
+    ) -> RpcResult<ValidationModeInfo<BeaconContentKey>>;
+
+    /// Return the `limit` locally stored content keys whose content ids are the closest to the
+    /// content id, ordered by increasing XOR distance. The limit is at most 1024.
+    #[method(name = "beaconNearestStoredKeys")]
+    async fn nearest_stored_keys(
+        &self,
+        content_id: B256,
+        limit: u64,
+    ) -> RpcResult<Vec<NearestStoredKeyInfo<BeaconContentKey>>>;
+
+    /// Return how many locally stored content ids are within the range `[start_id, end_id]`, the
+    /// fraction of the stored content that they make up, and the fraction of the content id space
+    /// that the range spans. The start id must not be greater than the end id.
+    #[method(name = "beaconRangeCoverage")]
+    async fn range_coverage(&self, start_id: B256, end_id: B256) -> RpcResult<RangeCoverageInfo>;
+
+    /// Remove the stored content values that are no longer referenced by any content key, when
+    /// content dedup is enabled. Return how many were removed and the bytes reclaimed.
+    #[method(name = "beaconGcOrphans")]
+    async fn gc_orphans(&self) -> RpcResult<GcOrphansInfo>;
+
+    /// Keep the content regardless of the storage capacity, within the separate budget of the
+    /// pinned content. The content doesn't have to be stored yet, and stays pinned across
+    /// restarts. Only bootstraps and light client updates can be pinned, as the other content is
+    /// only replaced by newer content.
+    #[method(name = "beaconPinContent")]
+    async fn pin_content(&self, content_key: BeaconContentKey) -> RpcResult<PinnedContentInfo>;
+
+    /// Stop keeping the content regardless of the storage capacity, so that it can be pruned.
+    #[method(name = "beaconUnpinContent")]
+    async fn unpin_content(&self, content_key: BeaconContentKey) -> RpcResult<PinnedContentInfo>;
+
+    /// Return the metrics of the beacon subnetwork in the Prometheus text exposition format.
+    #[method(name = "beaconMetricsText")]
+    async fn metrics_text(&self) -> RpcResult<String>;
+
+    /// Pause or resume gossiping content to other nodes, e.g. during maintenance. While paused,
+    /// gossip requests fail with a "gossip disabled" error, and content accepted from other nodes
+    /// or found by lookups isn't gossiped either, while offers from other nodes are still
+    /// accepted. The number of content items that weren't gossiped is logged once gossip
+    /// resumes. Returns whether gossip is enabled.
+    #[method(name = "beaconSetGossipEnabled")]
+    async fn set_gossip_enabled(&self, enabled: bool) -> RpcResult<bool>;
+
+    /// Forget the content recently found by lookups, which is returned to repeated lookups while
+    /// the lookup cache is enabled. Returns the number of forgotten entries.
+    #[method(name = "beaconClearLookupCache")]
+    async fn clear_lookup_cache(&self) -> RpcResult<u64>;
+
+    /// Return the content lookups that are currently running, e.g. to find a lookup that hangs.
+    /// Lookups drop off the list once they complete.
+    #[method(name = "beaconActiveLookups")]
+    async fn active_lookups(&self) -> RpcResult<Vec<ActiveLookupInfo>>;
+
+    /// Validate the content items concurrently, without storing them, e.g. to verify content
+    /// before importing it. Content is validated strictly, regardless of the validation mode.
+    /// Returns the result of each item, in the order of the items. At most 1024 items are
+    /// validated at once.
+    #[method(name = "beaconValidateContentBatch")]
+    async fn validate_content_batch(
+        &self,
+        content_items: Vec<(BeaconContentKey, RawContentValue)>,
+    ) -> RpcResult<Vec<ContentValidationInfo>>;
+
+    /// Validate the stored content again, strictly, and return the outcome of each check and the
+    /// roots computed for them. The content isn't changed, even if it's invalid.
+    #[method(name = "beaconRevalidateDetailed")]
+    async fn revalidate_detailed(
+        &self,
+        content_key: BeaconContentKey,
+    ) -> RpcResult<ValidationReportInfo>;
+
+    /// Decode the content value, without validating or storing it, and return it along with its
+    /// raw bytes and the content id of the key, e.g. to debug the encoding of content. If the
+    /// content value can't be decoded, the error is returned instead of the decoded value.
+    #[method(name = "beaconInspectContent")]
+    async fn inspect_content(
+        &self,
+        content_key: BeaconContentKey,
+        content_value: RawContentValue,
+    ) -> RpcResult<ContentInspectionInfo>;
+
+    /// Return the portal network, the subnetwork and the protocol id that the overlay is
+    /// configured with, to confirm which overlay handles the requests.
+    #[method(name = "beaconNetworkInfo")]
+    async fn network_info(&self) -> RpcResult<OverlayNetworkInfo>;
 }
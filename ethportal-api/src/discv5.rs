@@ -3,15 +3,16 @@ use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
 use crate::types::{
     discv5::{NodeInfo, RoutingTableInfo},
-    enr::Enr,
+    enr::{Enr, RpcEnr},
 };
 
 /// Discv5 JSON-RPC endpoints
 #[rpc(client, server, namespace = "discv5")]
 pub trait Discv5Api {
-    /// Returns ENR and Node ID information of the local discv5 node.
+    /// Returns ENR and Node ID information of the local discv5 node. The multiaddrs of the ENR
+    /// are included if `include_multiaddrs` is set.
     #[method(name = "nodeInfo")]
-    async fn node_info(&self) -> RpcResult<NodeInfo>;
+    async fn node_info(&self, include_multiaddrs: Option<bool>) -> RpcResult<NodeInfo>;
 
     /// Update the socket address of the local node record.
     #[method(name = "updateNodeInfo")]
@@ -27,7 +28,7 @@ pub trait Discv5Api {
 
     /// Write an Ethereum Node Record to the routing table.
     #[method(name = "addEnr")]
-    async fn add_enr(&self, enr: Enr) -> RpcResult<bool>;
+    async fn add_enr(&self, enr: RpcEnr) -> RpcResult<bool>;
 
     /// Fetch the latest ENR associated with the given node ID.
     #[method(name = "getEnr")]
@@ -12,9 +12,11 @@ mod dashboard;
 pub mod discv5;
 mod eth;
 mod history;
+mod portal;
 mod state;
 #[cfg(test)]
 mod test_utils;
+pub mod test_vectors;
 pub mod types;
 pub mod utils;
 pub mod version;
@@ -26,6 +28,7 @@ pub use eth::{EthApiClient, EthApiServer};
 pub use history::{HistoryNetworkApiClient, HistoryNetworkApiServer};
 // Re-exports jsonrpsee crate
 pub use jsonrpsee;
+pub use portal::{PortalApiClient, PortalApiServer};
 pub use state::{StateNetworkApiClient, StateNetworkApiServer};
 pub use types::{
     consensus,
@@ -39,7 +42,7 @@ pub use types::{
     },
     content_value::{
         beacon::BeaconContentValue, error::ContentValueError, history::HistoryContentValue,
-        state::StateContentValue, ContentValue,
+        state::StateContentValue, ContentValue, ContentValueFormat, ContentValueParam,
     },
     discv5::*,
     enr::*,
@@ -0,0 +1,28 @@
+use discv5::enr::NodeId;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+use crate::types::portal::{AddBootnodeInfo, NodeOverview, UtpStatsInfo};
+
+/// Portal JSON-RPC endpoints that aren't specific to a subnetwork
+#[rpc(client, server, namespace = "portal")]
+pub trait PortalApi {
+    /// Bond with the given bootnode (an ENR, with or without the `enr:` prefix) on every enabled
+    /// subnetwork, and persist it so it is used after a restart. The bootnode is only persisted if
+    /// it bonded on at least one subnetwork, unless `force` is set.
+    #[method(name = "addBootnode")]
+    async fn add_bootnode(&self, enr: String, force: Option<bool>) -> RpcResult<AddBootnodeInfo>;
+
+    /// Remove the persisted bootnode with the given node id. The node is not evicted from the
+    /// routing tables. Returns whether such bootnode was persisted.
+    #[method(name = "removeBootnode")]
+    async fn remove_bootnode(&self, node_id: NodeId) -> RpcResult<bool>;
+
+    /// Returns an overview of the node: client version, network, ENR and enabled subnetworks.
+    #[method(name = "nodeOverview")]
+    async fn node_overview(&self) -> RpcResult<NodeOverview>;
+
+    /// Returns the uTP traffic of all the subnetworks and how much of the upload and download rate
+    /// limits it uses.
+    #[method(name = "utpStats")]
+    async fn utp_stats(&self) -> RpcResult<UtpStatsInfo>;
+}
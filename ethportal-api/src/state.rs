@@ -1,13 +1,20 @@
+use alloy::primitives::{Bytes, B256};
 use discv5::enr::NodeId;
-use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use jsonrpsee::{
+    core::{RpcResult, SubscriptionResult},
+    proc_macros::rpc,
+};
 
 use crate::{
     types::{
         content_key::state::StateContentKey,
-        enr::Enr,
+        enr::{Enr, RpcEnr},
         portal::{
-            AcceptInfo, DataRadius, FindContentInfo, FindNodesInfo, GetContentInfo,
-            PaginateLocalContentInfo, PongInfo, TraceContentInfo, TraceGossipInfo,
+            AcceptInfo, ContentProvenance, ContentProviderInfo, ContentRecord, ExportContentItem,
+            FetchAndStoreInfo, FindContentInfo, FindNodesInfo, GetContentInfo, GetEnrInfo,
+            ImportContentInfo, LocalContentInfo, LookupEnrInfo, OfferManyInfo,
+            PaginateLocalContentInfo, PongInfo, SimulatedOfferInfo, StreamLocalContentKeysItem,
+            TraceContentInfo, TraceGossipInfo,
         },
         portal_wire::OfferTrace,
     },
@@ -21,34 +28,62 @@ pub trait StateNetworkApi {
     #[method(name = "stateRoutingTableInfo")]
     async fn routing_table_info(&self) -> RpcResult<RoutingTableInfo>;
 
-    /// Returns the node data radios
+    /// Returns the node data radius, as a 0x-prefixed, 32-byte hex string
     #[method(name = "stateRadius")]
-    async fn radius(&self) -> RpcResult<DataRadius>;
+    async fn radius(&self) -> RpcResult<B256>;
 
-    /// Write an Ethereum Node Record to the overlay routing table.
+    /// Write an Ethereum Node Record to the overlay routing table. ENRs without a reachable
+    /// address are rejected, unless `allow_unreachable` is set.
     #[method(name = "stateAddEnr")]
-    async fn add_enr(&self, enr: Enr) -> RpcResult<bool>;
+    async fn add_enr(&self, enr: RpcEnr, allow_unreachable: Option<bool>) -> RpcResult<bool>;
 
     /// Fetch the latest ENR associated with the given node ID.
+    /// The multiaddrs of the ENR are included if `include_multiaddrs` is set.
     #[method(name = "stateGetEnr")]
-    async fn get_enr(&self, node_id: NodeId) -> RpcResult<Enr>;
+    async fn get_enr(
+        &self,
+        node_id: NodeId,
+        include_multiaddrs: Option<bool>,
+    ) -> RpcResult<LookupEnrInfo>;
+
+    /// Fetch the latest ENR associated with the given node ID, along with where it was found.
+    /// The multiaddrs of the ENR are included if `include_multiaddrs` is set.
+    #[method(name = "stateGetEnrInfo")]
+    async fn get_enr_info(
+        &self,
+        node_id: NodeId,
+        include_multiaddrs: Option<bool>,
+    ) -> RpcResult<GetEnrInfo>;
 
     /// Delete Node ID from the overlay routing table.
     #[method(name = "stateDeleteEnr")]
     async fn delete_enr(&self, node_id: NodeId) -> RpcResult<bool>;
 
     /// Fetch the ENR representation associated with the given Node ID.
+    /// The multiaddrs of the ENR are included if `include_multiaddrs` is set.
     #[method(name = "stateLookupEnr")]
-    async fn lookup_enr(&self, node_id: NodeId) -> RpcResult<Enr>;
+    async fn lookup_enr(
+        &self,
+        node_id: NodeId,
+        include_multiaddrs: Option<bool>,
+    ) -> RpcResult<LookupEnrInfo>;
 
     /// Send a PING message to the designated node and wait for a PONG response
     #[method(name = "statePing")]
-    async fn ping(&self, enr: Enr) -> RpcResult<PongInfo>;
+    async fn ping(&self, enr: RpcEnr) -> RpcResult<PongInfo>;
 
     /// Send a FINDNODES request for nodes that fall within the given set of distances, to the
-    /// designated peer and wait for a response
+    /// designated peer and wait for a response. When `max_results` is given, at most that many
+    /// of the returned ENRs are kept, in the order that the peer returned them. The ENRs are
+    /// dropped once the whole response has been received, so this doesn't bound the size of the
+    /// response that the peer sends.
     #[method(name = "stateFindNodes")]
-    async fn find_nodes(&self, enr: Enr, distances: Vec<u16>) -> RpcResult<FindNodesInfo>;
+    async fn find_nodes(
+        &self,
+        enr: RpcEnr,
+        distances: Vec<u16>,
+        max_results: Option<u64>,
+    ) -> RpcResult<FindNodesInfo>;
 
     /// Lookup a target node within in the network
     #[method(name = "stateRecursiveFindNodes")]
@@ -58,10 +93,20 @@ pub trait StateNetworkApi {
     #[method(name = "stateFindContent")]
     async fn find_content(
         &self,
-        enr: Enr,
+        enr: RpcEnr,
         content_key: StateContentKey,
     ) -> RpcResult<FindContentInfo>;
 
+    /// Send FINDCONTENT message to get the content with a content key, and store the content
+    /// after validating it. Content outside our radius is stored only if `ignore_radius` is set.
+    #[method(name = "stateFetchAndStore")]
+    async fn fetch_and_store(
+        &self,
+        enr: RpcEnr,
+        content_key: StateContentKey,
+        ignore_radius: Option<bool>,
+    ) -> RpcResult<FetchAndStoreInfo>;
+
     /// First checks local storage if content is not found lookup a target content key in the
     /// network
     #[method(name = "stateGetContent")]
@@ -72,16 +117,64 @@ pub trait StateNetworkApi {
     #[method(name = "stateTraceGetContent")]
     async fn trace_get_content(&self, content_key: StateContentKey) -> RpcResult<TraceContentInfo>;
 
-    /// Pagination of local content keys
+    /// Pagination of local content keys. Only the content that got into the local database the
+    /// given way is listed, if `provenance` is set, and only the content stored with the source,
+    /// if `source` is set.
+    ///
+    /// Skipping the offset gets slower the larger it is, so `statePaginateLocalContentKeysAfter`
+    /// should be preferred to list all content.
     #[method(name = "statePaginateLocalContentKeys")]
     async fn paginate_local_content_keys(
         &self,
         offset: u64,
         limit: u64,
+        provenance: Option<ContentProvenance>,
+        source: Option<String>,
     ) -> RpcResult<PaginateLocalContentInfo<StateContentKey>>;
 
-    /// Send the provided content value to interested peers. Clients may choose to send to some or
-    /// all peers. Return the number of peers that the content was gossiped to.
+    /// Cursor-based pagination of local content keys, ordered by content id. Lists the content
+    /// after the `cursor`, which is the `nextCursor` of the previous page, or from the start if
+    /// it isn't set. The content is filtered like in `statePaginateLocalContentKeys`, but the
+    /// filtered content is only counted in `totalEntries` of the first page.
+    #[method(name = "statePaginateLocalContentKeysAfter")]
+    async fn paginate_local_content_keys_after(
+        &self,
+        cursor: Option<B256>,
+        limit: u64,
+        provenance: Option<ContentProvenance>,
+        source: Option<String>,
+    ) -> RpcResult<PaginateLocalContentInfo<StateContentKey>>;
+
+    /// Streams all locally stored content, ordered by content id, as `{contentKey, contentValue}`
+    /// notifications, followed by the summary of the export. Content is read from the database in
+    /// batches of the given size.
+    #[subscription(
+        name = "stateExportContent",
+        unsubscribe = "stateUnsubscribeExportContent",
+        item = ExportContentItem<StateContentKey>
+    )]
+    async fn export_content(&self, batch_size: Option<u64>) -> SubscriptionResult;
+
+    /// Streams all locally stored content keys, ordered by content id, as `{contentKeys}`
+    /// notifications of at most the given batch size, followed by the summary once all keys were
+    /// streamed.
+    #[subscription(
+        name = "stateStreamLocalContentKeys",
+        unsubscribe = "stateUnsubscribeStreamLocalContentKeys",
+        item = StreamLocalContentKeysItem<StateContentKey>
+    )]
+    async fn stream_local_content_keys(&self, batch_size: Option<u64>) -> SubscriptionResult;
+
+    /// Store the content records (e.g. exported from another node) to the local database.
+    #[method(name = "stateImportContent")]
+    async fn import_content(
+        &self,
+        content_records: Vec<ContentRecord<StateContentKey>>,
+    ) -> RpcResult<ImportContentInfo<StateContentKey>>;
+
+    /// Send the provided content value to interested peers, storing it locally too if it's within
+    /// the radius. Clients may choose to send to some or all peers. Return the number of peers that
+    /// the content was gossiped to.
     #[method(name = "stateGossip")]
     async fn gossip(
         &self,
@@ -89,8 +182,9 @@ pub trait StateNetworkApi {
         content_value: RawContentValue,
     ) -> RpcResult<u32>;
 
-    /// Send the provided content value to interested peers. Clients may choose to send to some or
-    /// all peers. Return tracing info detailing the gossip propagation.
+    /// Send the provided content value to interested peers, storing it locally too if it's within
+    /// the radius. Clients may choose to send to some or all peers. Return tracing info detailing
+    /// the gossip propagation.
     #[method(name = "stateTraceGossip")]
     async fn trace_gossip(
         &self,
@@ -102,33 +196,84 @@ pub trait StateNetworkApi {
     /// response. Does not store the content locally.
     /// Returns the content keys bitlist upon successful content transmission or empty bitlist
     /// receive.
+    /// If `include_accept_bitfield` is set, the response also contains the SSZ encoded accept
+    /// bitlist, as sent on the wire.
     #[method(name = "stateOffer")]
     async fn offer(
         &self,
-        enr: Enr,
+        enr: RpcEnr,
         content_items: Vec<(StateContentKey, RawContentValue)>,
+        include_accept_bitfield: Option<bool>,
     ) -> RpcResult<AcceptInfo>;
 
+    /// Send an OFFER request with given ContentItems, to the designated peer and wait for a
+    /// response. Does not store the content locally.
+    /// Returns which of the offered content keys the peer accepted and which it declined, in the
+    /// order they were offered.
+    #[method(name = "stateOfferMany")]
+    async fn offer_many(
+        &self,
+        enr: RpcEnr,
+        content_items: Vec<(StateContentKey, RawContentValue)>,
+    ) -> RpcResult<OfferManyInfo<StateContentKey>>;
+
     /// Send an OFFER request with given ContentItems, to the designated peer.
     /// Does not store the content locally.
     /// Returns trace info for offer.
     #[method(name = "stateTraceOffer")]
     async fn trace_offer(
         &self,
-        enr: Enr,
+        enr: RpcEnr,
         content_key: StateContentKey,
         content_value: RawContentValue,
     ) -> RpcResult<OfferTrace>;
 
-    /// Store content key with a content data to the local database.
+    /// Store content key with a content data to the local database. The content is labeled with
+    /// the source, if it is set, replacing the source of the content if it was already stored.
+    ///
+    /// If the signature is set, the content is only stored if it's a signature of a trusted
+    /// signer over the content key followed by the content value, and it's marked as trusted.
     #[method(name = "stateStore")]
     async fn store(
         &self,
         content_key: StateContentKey,
         content_value: RawContentValue,
+        source: Option<String>,
+        signature: Option<Bytes>,
     ) -> RpcResult<bool>;
 
-    /// Get a content from the local database
+    /// Delete all the content stored with the source from the local database. Return the number
+    /// of deleted content items.
+    #[method(name = "stateDeleteContentBySource")]
+    async fn delete_content_by_source(&self, source: String) -> RpcResult<u64>;
+
+    /// Get a content from the local database. Its metadata, e.g. how it got into the local
+    /// database, is included if `include_meta` is set, and its rank among all stored content by
+    /// distance to the local node if `include_rank` is set.
     #[method(name = "stateLocalContent")]
-    async fn local_content(&self, content_key: StateContentKey) -> RpcResult<RawContentValue>;
+    async fn local_content(
+        &self,
+        content_key: StateContentKey,
+        include_meta: Option<bool>,
+        include_rank: Option<bool>,
+    ) -> RpcResult<LocalContentInfo>;
+
+    /// Return the peers that provided the locally stored content, i.e. the peer that offered it,
+    /// if it was offered or gossiped to us, along with when it was stored.
+    #[method(name = "stateContentProviders")]
+    async fn content_providers(
+        &self,
+        content_key: StateContentKey,
+    ) -> RpcResult<Vec<ContentProviderInfo>>;
+
+    /// Check whether `sample_size` synthetic content ids, spread evenly across the keyspace,
+    /// would be accepted if they were offered, and return the acceptance rate and the radius
+    /// used. The sample size is at most 10000.
+    #[method(name = "stateSimulateIncomingOffer")]
+    async fn simulate_incoming_offer(&self, sample_size: u64) -> RpcResult<SimulatedOfferInfo>;
+
+    /// Forget the content recently found by lookups, which is returned to repeated lookups while
+    /// the lookup cache is enabled. Returns the number of forgotten entries.
+    #[method(name = "stateClearLookupCache")]
+    async fn clear_lookup_cache(&self) -> RpcResult<u64>;
 }
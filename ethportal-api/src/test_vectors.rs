@@ -0,0 +1,268 @@
+//! Loading and producing content test vectors in the format of the
+//! [portal-spec-tests](https://github.com/ethereum/portal-spec-tests) fixtures.
+//!
+//! A test vector file is a YAML (or JSON) file containing either a single vector or a list of
+//! vectors. Every vector has a hex encoded `content_key`, and optionally the `content_id` and hex
+//! encoded `content_value`. All other fields (e.g. decoded fields of the key and value) are kept
+//! as they are.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use alloy::primitives::B256;
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use thiserror::Error;
+
+use crate::{
+    types::portal::{RawContentKey, RawContentValue},
+    ContentKeyError, ContentValue, ContentValueError, OverlayContentKey,
+};
+
+/// The field that every test vector has.
+const CONTENT_KEY_FIELD: &str = "content_key";
+
+/// An error loading or checking a test vector.
+#[derive(Debug, Error)]
+pub enum TestVectorError {
+    #[error("unable to read test vector file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("unable to parse YAML test vector: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[error("unable to parse JSON test vector: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("unable to decode content key: {0}")]
+    ContentKey(#[from] ContentKeyError),
+
+    #[error("unable to decode content value: {0}")]
+    ContentValue(#[from] ContentValueError),
+
+    #[error("{field} mismatch: expected {expected}, got {actual}")]
+    Mismatch {
+        field: &'static str,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// A content test vector.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ContentTestVector {
+    pub content_key: RawContentKey,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_id: Option<B256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_value: Option<RawContentValue>,
+    /// All other fields of the test vector, e.g. the decoded fields of the key and value.
+    #[serde(flatten)]
+    pub fields: Mapping,
+}
+
+impl ContentTestVector {
+    /// Creates the test vector of the given content key and (optional) content value.
+    pub fn new<V: ContentValue>(content_key: &V::TContentKey, content_value: Option<&V>) -> Self {
+        Self {
+            content_key: content_key.to_bytes(),
+            content_id: Some(B256::from(content_key.content_id())),
+            content_value: content_value.map(ContentValue::encode),
+            fields: Mapping::new(),
+        }
+    }
+
+    /// Decodes the content key and value of the test vector, and checks that re-encoding them
+    /// results in the same bytes, and that the content id matches.
+    pub fn check_round_trip<V: ContentValue>(&self) -> Result<(), TestVectorError> {
+        let content_key = V::TContentKey::try_from_bytes(&self.content_key)?;
+        check_field("content_key", &self.content_key, &content_key.to_bytes())?;
+
+        if let Some(content_id) = &self.content_id {
+            let actual_content_id = B256::from(content_key.content_id());
+            check_field("content_id", content_id, &actual_content_id)?;
+        }
+
+        if let Some(content_value) = &self.content_value {
+            let decoded = V::decode(&content_key, content_value)?;
+            check_field("content_value", content_value, &decoded.encode())?;
+        }
+
+        Ok(())
+    }
+
+    /// Serializes the test vector to YAML.
+    pub fn to_yaml(&self) -> Result<String, TestVectorError> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+}
+
+fn check_field<T: PartialEq + std::fmt::Display>(
+    field: &'static str,
+    expected: &T,
+    actual: &T,
+) -> Result<(), TestVectorError> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(TestVectorError::Mismatch {
+            field,
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+        })
+    }
+}
+
+/// Loads the test vectors from a YAML or JSON file.
+///
+/// The file can contain a single test vector or a list of them. Entries without a content key
+/// are skipped, as they aren't content test vectors.
+pub fn load_test_vectors_from_file(
+    path: impl AsRef<Path>,
+) -> Result<Vec<ContentTestVector>, TestVectorError> {
+    let path = path.as_ref();
+    let file = fs::read_to_string(path)?;
+    let value: Value = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => serde_json::from_str(&file)?,
+        _ => serde_yaml::from_str(&file)?,
+    };
+
+    let entries = match value {
+        Value::Sequence(entries) => entries,
+        value => vec![value],
+    };
+    entries
+        .into_iter()
+        .filter(|entry| entry.get(CONTENT_KEY_FIELD).is_some())
+        .map(|entry| Ok(serde_yaml::from_value(entry)?))
+        .collect()
+}
+
+/// Recursively loads the test vectors from all YAML and JSON files in the directory, along with
+/// the path of the file they were loaded from.
+pub fn load_test_vectors(
+    dir: impl AsRef<Path>,
+) -> Result<Vec<(PathBuf, ContentTestVector)>, TestVectorError> {
+    let mut paths = vec![];
+    collect_test_vector_files(dir.as_ref(), &mut paths)?;
+    paths.sort();
+
+    let mut test_vectors = vec![];
+    for path in paths {
+        for test_vector in load_test_vectors_from_file(&path)? {
+            test_vectors.push((path.clone(), test_vector));
+        }
+    }
+    Ok(test_vectors)
+}
+
+fn collect_test_vector_files(dir: &Path, paths: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_test_vector_files(&path, paths)?;
+        } else if matches!(
+            path.extension().and_then(|extension| extension.to_str()),
+            Some("yaml" | "yml" | "json")
+        ) {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{keccak256, Address};
+    use rstest::rstest;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::{
+        test_utils::constants::PORTAL_SPEC_TESTS_SUBMODULE_PATH,
+        types::{content_key::state::ContractBytecodeKey, content_value::state::ContractBytecode},
+        BeaconContentValue, HistoryContentValue, StateContentKey, StateContentValue,
+    };
+
+    #[rstest]
+    #[case::beacon("tests/mainnet/beacon_chain")]
+    #[case::history("tests/mainnet/history")]
+    #[case::state("tests/mainnet/state")]
+    fn spec_test_vectors_round_trip(#[case] dir: &str) {
+        let test_vectors =
+            load_test_vectors(PathBuf::from(PORTAL_SPEC_TESTS_SUBMODULE_PATH).join(dir)).unwrap();
+        assert!(!test_vectors.is_empty(), "No test vectors found in {dir}");
+
+        for (path, test_vector) in test_vectors {
+            let result = if dir.ends_with("beacon_chain") {
+                test_vector.check_round_trip::<BeaconContentValue>()
+            } else if dir.ends_with("history") {
+                test_vector.check_round_trip::<HistoryContentValue>()
+            } else {
+                test_vector.check_round_trip::<StateContentValue>()
+            };
+            if let Err(err) = result {
+                panic!("Test vector from {} failed: {err}", path.display());
+            }
+        }
+    }
+
+    #[test]
+    fn generated_test_vector_round_trip() {
+        let code = vec![0x60, 0x80, 0x60, 0x40];
+        let content_key = StateContentKey::ContractBytecode(ContractBytecodeKey {
+            address_hash: keccak256(Address::random()),
+            code_hash: keccak256(&code),
+        });
+        let content_value =
+            StateContentValue::ContractBytecode(ContractBytecode { code: code.into() });
+        let test_vector = ContentTestVector::new(&content_key, Some(&content_value));
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("contract_bytecode.yaml");
+        fs::write(&path, test_vector.to_yaml().unwrap()).unwrap();
+
+        let loaded = load_test_vectors(dir.path()).unwrap();
+        assert_eq!(loaded, vec![(path, test_vector)]);
+        loaded[0].1.check_round_trip::<StateContentValue>().unwrap();
+    }
+
+    #[test]
+    fn mismatched_content_id_fails() {
+        let content_key = StateContentKey::ContractBytecode(ContractBytecodeKey {
+            address_hash: B256::random(),
+            code_hash: B256::random(),
+        });
+        let mut test_vector = ContentTestVector::new::<StateContentValue>(&content_key, None);
+        test_vector.content_id = Some(B256::random());
+        assert!(matches!(
+            test_vector.check_round_trip::<StateContentValue>(),
+            Err(TestVectorError::Mismatch {
+                field: "content_id",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn load_list_of_test_vectors_skips_entries_without_content_key() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("vectors.yaml");
+        fs::write(
+            &path,
+            "- content_key: \"0x01\"\n  block_number: 1\n- block_number: 2\n- content_key: \"0x02\"\n",
+        )
+        .unwrap();
+
+        let test_vectors = load_test_vectors_from_file(&path).unwrap();
+        assert_eq!(test_vectors.len(), 2);
+        assert_eq!(test_vectors[0].content_key, RawContentKey::from([0x01]));
+        assert_eq!(
+            test_vectors[0].fields.get("block_number"),
+            Some(&Value::from(1))
+        );
+        assert_eq!(test_vectors[1].content_key, RawContentKey::from([0x02]));
+    }
+}
@@ -1,4 +1,12 @@
-use std::{env, ffi::OsString, fmt, net::SocketAddr, path::PathBuf, str::FromStr, sync::Arc};
+use std::{
+    env,
+    ffi::OsString,
+    fmt,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
 
 use alloy::primitives::B256;
 use clap::{
@@ -11,7 +19,13 @@ use url::Url;
 
 use crate::{
     build_info,
-    types::{bootnodes::Bootnodes, network::Subnetwork},
+    types::{
+        bootnodes::Bootnodes,
+        consensus::chain_config::{ChainConfig, MAINNET_CHAIN_CONFIG, SEPOLIA_CHAIN_CONFIG},
+        network::Subnetwork,
+        portal::MAX_CONTENT_KEYS_PER_OFFER,
+    },
+    utils::content_signature::{parse_public_key, PublicKey},
 };
 
 pub const DEFAULT_WEB3_IPC_PATH: &str = "/tmp/trin-jsonrpc.ipc";
@@ -20,8 +34,22 @@ pub const DEFAULT_WEB3_HTTP_PORT: u16 = 8545;
 pub const DEFAULT_WEB3_WS_PORT: u16 = 8546;
 pub const DEFAULT_DISCOVERY_PORT: u16 = 9009;
 pub const DEFAULT_UTP_TRANSFER_LIMIT: usize = 50;
+pub const DEFAULT_RECORD_WIRE_MAX_MB: u64 = 100;
+pub const DEFAULT_FAILED_LOOKUP_CACHE_TTL_SECS: u64 = 5;
+pub const DEFAULT_FAILED_LOOKUP_CACHE_SIZE: usize = 256;
+pub const DEFAULT_LOOKUP_CACHE_MAX_MB: u64 = 16;
+pub const DEFAULT_REBROADCAST_OFFERS_PER_MINUTE: u64 = 64;
+pub const DEFAULT_GOSSIP_MAX_PEERS: usize = 8;
+pub const DEFAULT_GOSSIP_LARGE_CONTENT_THRESHOLD: usize = 1_000_000;
+pub const DEFAULT_RPC_SLOW_CALL_THRESHOLD_MS: u64 = 1000;
+pub const DEFAULT_RPC_MAX_BATCH_SIZE: u32 = 100;
+pub const DEFAULT_RPC_MAX_IN_FLIGHT_CALLS: u64 = 64;
+pub const DEFAULT_RPC_MAX_PAGINATE_LIMIT: u64 = 1000;
+pub const DEFAULT_STORAGE_RECENT_WRITES: usize = 64;
+pub const DEFAULT_STORAGE_BEACON_PINNED_MB: u32 = 2;
 const DEFAULT_SUBNETWORKS: &str = "history";
 pub const DEFAULT_NETWORK: &str = "mainnet";
+pub const DEFAULT_BEACON_CHAIN: &str = "mainnet";
 pub const DEFAULT_STORAGE_CAPACITY_MB: &str = "1000";
 pub const DEFAULT_WEB3_TRANSPORT: &str = "ipc";
 
@@ -55,6 +83,39 @@ impl FromStr for Web3TransportType {
     }
 }
 
+/// The policy for choosing which content to evict from the storage once it is full.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum StorageEvictionPolicy {
+    /// Evict the content farthest from the local node.
+    #[default]
+    Distance,
+    /// Evict the least recently read content among the content farthest from the local node.
+    DistanceLru,
+}
+
+impl fmt::Display for StorageEvictionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Distance => write!(f, "distance"),
+            Self::DistanceLru => write!(f, "distance-lru"),
+        }
+    }
+}
+
+impl FromStr for StorageEvictionPolicy {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "distance" => Ok(Self::Distance),
+            "distance-lru" => Ok(Self::DistanceLru),
+            _ => {
+                Err("Invalid storage eviction policy. Expected either 'distance' or 'distance-lru'")
+            }
+        }
+    }
+}
+
 const APP_NAME: &str = "trin";
 const VERSION: &str = const_format::formatcp!(
     "{version}-{hash} {build_os} {rust_version}",
@@ -121,6 +182,12 @@ pub struct TrinConfig {
     )]
     pub discovery_port: u16,
 
+    #[arg(
+        long = "utp-port",
+        help = "The UDP port to listen on for uTP packets, which is advertised to peers (in an ENR). If not set, uTP packets share the discovery port, wrapped in discv5 TALKREQ messages. Packets are only exchanged on the port with the peers that advertise their own uTP port, and through TALKREQ messages with the others."
+    )]
+    pub utp_port: Option<u16>,
+
     #[arg(
         default_value = "default",
         long = "bootnodes",
@@ -162,6 +229,13 @@ pub struct TrinConfig {
     )]
     pub trusted_block_root: Option<B256>,
 
+    #[arg(
+        long = "beacon-seed-from",
+        help = "HTTP url of a trusted Beacon API provider to seed the beacon content from at startup",
+        long_help = "HTTP url of a trusted Beacon API provider to seed the beacon content from at startup.\nThe bootstrap for the trusted block root, the latest finality and optimistic updates and the updates of the last sync committee periods are fetched, validated and stored, so that the node can serve them and start its light client without waiting for the portal network.\nIf no trusted block root is provided, the provider's finalized block root is trusted."
+    )]
+    pub beacon_seed_from: Option<Url>,
+
     #[arg(
         long,
         help = "Choose mainnet or angelfood",
@@ -170,6 +244,23 @@ pub struct TrinConfig {
     )]
     pub network: Arc<NetworkSpec>,
 
+    #[arg(
+        long = "beacon-chain",
+        help = "The beacon chain that the beacon network serves content of: mainnet or sepolia",
+        default_value = DEFAULT_BEACON_CHAIN,
+        value_parser = beacon_chain_parser
+    )]
+    pub beacon_chain: Arc<ChainConfig>,
+
+    #[arg(
+        long = "chain-config-file",
+        help = "Path to a JSON file with the chain config of a custom beacon chain, e.g. a devnet",
+        long_help = "Path to a JSON file with the chain config of a custom beacon chain, e.g. a devnet, used instead of the --beacon-chain preset.\nThe file has the chain_id, genesis_time, seconds_per_slot, genesis_validators_root and fork_schedule fields, where the fork schedule has the epoch and the version of the genesis, altair, bellatrix, capella, deneb and electra forks.\nThe electra fork is optional, for a chain that hasn't scheduled it yet, and the forks after electra aren't supported yet.",
+        value_parser = chain_config_file_parser,
+        conflicts_with = "beacon_chain"
+    )]
+    pub chain_config_file: Option<Arc<ChainConfig>>,
+
     #[arg(
         long,
         help = "Comma-separated list of which portal subnetworks to activate",
@@ -195,10 +286,19 @@ pub struct TrinConfig {
         id = "storage.beacon",
         long = "storage.beacon",
         help = "Maximum storage capacity (in megabytes) used by beacon subnetwork",
-        long_help = "Maximum storage capacity (in megabytes) used by beacon subnetwork.\nCan't be used in combination with 'storage.total' flag.\nThe actual storage can be higher than specified, due to overhead."
+        long_help = "Maximum storage capacity (in megabytes) used by beacon subnetwork.\nCan't be used in combination with 'storage.total' flag.\nThe actual storage can be higher than specified, due to overhead.\nOnce it's full, the oldest bootstraps and light client updates are pruned, unless they are pinned. The bootstrap of the trusted block root is pinned."
     )]
     pub storage_beacon: Option<u32>,
 
+    #[arg(
+        id = "storage.beacon-pinned",
+        long = "storage.beacon-pinned",
+        default_value_t = DEFAULT_STORAGE_BEACON_PINNED_MB,
+        help = "Maximum storage capacity (in megabytes) used by pinned beacon content",
+        long_help = "Maximum storage capacity (in megabytes) used by pinned beacon content, which is kept regardless of the 'storage.beacon' capacity.\nThe default is enough for dozens of bootstraps. Content that is pinned once it no longer fits is unpinned."
+    )]
+    pub storage_beacon_pinned: u32,
+
     #[arg(
         id = "storage.history",
         long = "storage.history",
@@ -215,6 +315,59 @@ pub struct TrinConfig {
     )]
     pub storage_state: Option<u32>,
 
+    #[arg(
+        id = "storage.dedup",
+        long = "storage.dedup",
+        help = "Store a single copy of identical beacon content values that are reachable under different content keys",
+        long_help = "Store a single copy of identical beacon content values that are reachable under different content keys.\nThis changes how new beacon content is laid out in the database. Content stored without this flag remains readable."
+    )]
+    pub storage_dedup: bool,
+
+    #[arg(
+        id = "storage.verify-on-read",
+        long = "storage.verify-on-read",
+        help = "Check that stored content still matches its content key when it is read",
+        long_help = "Check that stored content still matches its content key when it is read.\nHistory and state content is checked against its content id and the hash of the value it was stored with, and beacon content is decoded and checked against its content key.\nCorrupted content is logged and reported as an error instead of being served. Off by default, as it slows down every read."
+    )]
+    pub storage_verify_on_read: bool,
+
+    #[arg(
+        id = "storage.eviction-policy",
+        long = "storage.eviction-policy",
+        default_value_t = StorageEvictionPolicy::Distance,
+        help = "The policy for choosing which history and state content to evict once the storage is full: 'distance' or 'distance-lru'",
+        long_help = "The policy for choosing which history and state content to evict once the storage is full.\n'distance' evicts the content farthest from the node id.\n'distance-lru' evicts the least recently read content among the farthest content, so that content that is served often is kept longer."
+    )]
+    pub storage_eviction_policy: StorageEvictionPolicy,
+
+    #[arg(
+        id = "storage.recent-writes",
+        long = "storage.recent-writes",
+        value_parser = clap::value_parser!(u64).range(1..),
+        default_value_t = DEFAULT_STORAGE_RECENT_WRITES as u64,
+        help = "The number of most recently stored history and state content items, which are served from memory instead of the database",
+        long_help = "The number of most recently stored history and state content items, which are served from memory instead of the database.\nContent is often requested right after it is stored, e.g. when the node gossips it, and serving it from memory avoids reading the database under load."
+    )]
+    pub storage_recent_writes: u64,
+
+    #[arg(
+        id = "storage.archive-path",
+        long = "storage.archive-path",
+        requires = "storage.archive-mb",
+        help = "The directory of a secondary database, which keeps the history and state content that is evicted from the primary storage",
+        long_help = "The directory of a secondary database, which keeps the history and state content that is evicted from the primary storage.\nContent read from the secondary database is moved back to the primary storage. The radius of the node is defined by the capacity of both.\nMust be used together with 'storage.archive-mb'."
+    )]
+    pub storage_archive_path: Option<PathBuf>,
+
+    #[arg(
+        id = "storage.archive-mb",
+        long = "storage.archive-mb",
+        requires = "storage.archive-path",
+        help = "Maximum storage capacity (in megabytes) of the secondary database, shared between the history and state subnetworks",
+        long_help = "Maximum storage capacity (in megabytes) of the secondary database, shared between the history and state subnetworks like the primary storage.\nMust be used together with 'storage.archive-path'."
+    )]
+    pub storage_archive_mb: Option<u32>,
+
     #[arg(
         long = "enable-metrics-with-url",
         help = "Enable prometheus metrics reporting (provide local IP/Port from which your Prometheus server is configured to fetch metrics)"
@@ -258,6 +411,205 @@ pub struct TrinConfig {
     )]
     pub utp_transfer_limit: usize,
 
+    #[arg(
+        long = "utp-max-up-kbps",
+        help = "The max rate, in kilobits per second, at which uTP packets are sent, shared by all the transfers of all the subnetworks. 0 means unlimited.",
+        default_value_t = 0
+    )]
+    pub utp_max_up_kbps: u64,
+
+    #[arg(
+        long = "utp-max-down-kbps",
+        help = "The max rate, in kilobits per second, at which uTP packets are received, shared by all the transfers of all the subnetworks. 0 means unlimited.",
+        default_value_t = 0
+    )]
+    pub utp_max_down_kbps: u64,
+
+    #[arg(
+        long = "record-wire",
+        help = "Record the portal wire requests and responses exchanged with peers to the given file, for replaying them with `trin replay`",
+        long_help = "Record the portal wire requests and responses exchanged with peers to the given file, for replaying them with `trin replay`.\nThe recording contains the node ids and ENRs of peers. Recording stops once the file reaches --record-wire-max-mb."
+    )]
+    pub record_wire: Option<PathBuf>,
+
+    #[arg(
+        long = "record-wire-max-mb",
+        help = "Maximum size (in megabytes) of the wire recording",
+        default_value_t = DEFAULT_RECORD_WIRE_MAX_MB,
+        requires = "record_wire"
+    )]
+    pub record_wire_max_mb: u64,
+
+    #[arg(
+        long = "failed-lookup-cache-ttl",
+        help = "The time, in seconds, for which a content lookup that didn't find the content is remembered, and returned to repeated requests for the same content without querying the network. 0 disables the cache.",
+        default_value_t = DEFAULT_FAILED_LOOKUP_CACHE_TTL_SECS,
+    )]
+    pub failed_lookup_cache_ttl: u64,
+
+    #[arg(
+        long = "failed-lookup-cache-size",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "The max number of failed content lookups that are remembered for each subnetwork.",
+        default_value_t = DEFAULT_FAILED_LOOKUP_CACHE_SIZE as u64,
+    )]
+    pub failed_lookup_cache_size: u64,
+
+    #[arg(
+        long = "lookup-cache-ttl",
+        help = "The time, in seconds, for which content found by a lookup is remembered, and returned to repeated lookups of the same content without querying the network. 0 disables the cache.",
+        default_value_t = 0
+    )]
+    pub lookup_cache_ttl: u64,
+
+    #[arg(
+        long = "lookup-cache-max-mb",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "The max total size, in megabytes, of the content found by lookups that is remembered for each subnetwork.",
+        default_value_t = DEFAULT_LOOKUP_CACHE_MAX_MB,
+    )]
+    pub lookup_cache_max_mb: u64,
+
+    #[arg(
+        long = "rebroadcast-offers-per-peer",
+        value_parser = clap::value_parser!(u64).range(0..=MAX_CONTENT_KEYS_PER_OFFER as u64),
+        help = "The max number of locally stored content items offered to each peer newly added to the routing table, if they are within its radius. 0 disables the rebroadcast.",
+        default_value_t = 0,
+    )]
+    pub rebroadcast_offers_per_peer: u64,
+
+    #[arg(
+        long = "rebroadcast-offers-per-minute",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "The max number of locally stored content items offered to new peers per minute, for each subnetwork.",
+        default_value_t = DEFAULT_REBROADCAST_OFFERS_PER_MINUTE,
+    )]
+    pub rebroadcast_offers_per_minute: u64,
+
+    #[arg(
+        id = "gossip.beacon.max-peers",
+        long = "gossip.beacon.max-peers",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "The max number of peers that each gossiped beacon content item is offered to",
+        default_value_t = DEFAULT_GOSSIP_MAX_PEERS as u64,
+    )]
+    pub gossip_beacon_max_peers: u64,
+
+    #[arg(
+        id = "gossip.beacon.prefer-radius-coverage",
+        long = "gossip.beacon.prefer-radius-coverage",
+        help = "Offer gossiped beacon content to the peers whose radius is known to cover it before the peers whose radius is unknown"
+    )]
+    pub gossip_beacon_prefer_radius_coverage: bool,
+
+    #[arg(
+        id = "gossip.beacon.exclude-far-peers",
+        long = "gossip.beacon.exclude-far-peers",
+        help = "Only offer gossiped beacon content to the interested peers closest to it, instead of offering half of it to randomly selected interested peers that are farther away"
+    )]
+    pub gossip_beacon_exclude_far_peers: bool,
+
+    #[arg(
+        id = "gossip.history.max-peers",
+        long = "gossip.history.max-peers",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "The max number of peers that each gossiped history content item is offered to",
+        default_value_t = DEFAULT_GOSSIP_MAX_PEERS as u64,
+    )]
+    pub gossip_history_max_peers: u64,
+
+    #[arg(
+        id = "gossip.history.prefer-radius-coverage",
+        long = "gossip.history.prefer-radius-coverage",
+        help = "Offer gossiped history content to the peers whose radius is known to cover it before the peers whose radius is unknown"
+    )]
+    pub gossip_history_prefer_radius_coverage: bool,
+
+    #[arg(
+        id = "gossip.history.exclude-far-peers",
+        long = "gossip.history.exclude-far-peers",
+        help = "Only offer gossiped history content to the interested peers closest to it, instead of offering half of it to randomly selected interested peers that are farther away"
+    )]
+    pub gossip_history_exclude_far_peers: bool,
+
+    #[arg(
+        id = "gossip.state.max-peers",
+        long = "gossip.state.max-peers",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "The max number of peers that each gossiped state content item is offered to",
+        default_value_t = DEFAULT_GOSSIP_MAX_PEERS as u64,
+    )]
+    pub gossip_state_max_peers: u64,
+
+    #[arg(
+        id = "gossip.state.prefer-radius-coverage",
+        long = "gossip.state.prefer-radius-coverage",
+        help = "Offer gossiped state content to the peers whose radius is known to cover it before the peers whose radius is unknown"
+    )]
+    pub gossip_state_prefer_radius_coverage: bool,
+
+    #[arg(
+        id = "gossip.state.exclude-far-peers",
+        long = "gossip.state.exclude-far-peers",
+        help = "Only offer gossiped state content to the interested peers closest to it, instead of offering half of it to randomly selected interested peers that are farther away"
+    )]
+    pub gossip_state_exclude_far_peers: bool,
+
+    #[arg(
+        id = "gossip.large-content-threshold",
+        long = "gossip.large-content-threshold",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "The size, in bytes, from which gossiped content is offered to its peers one at a time, so that only one transfer of it to a peer that accepted it is in progress at a time. Smaller content is offered to all peers at once.",
+        default_value_t = DEFAULT_GOSSIP_LARGE_CONTENT_THRESHOLD as u64,
+    )]
+    pub gossip_large_content_threshold: u64,
+
+    #[arg(
+        long = "rpc-slow-call-threshold",
+        help = "The duration, in milliseconds, from which JSON-RPC calls are logged at info level. Faster calls are logged at debug level.",
+        default_value_t = DEFAULT_RPC_SLOW_CALL_THRESHOLD_MS,
+    )]
+    pub rpc_slow_call_threshold: u64,
+
+    #[arg(
+        long = "rpc-log-methods",
+        help = "Comma-separated list of JSON-RPC methods whose calls are always logged at info level, regardless of their duration. E.g. 'portal_historyStore,portal_stateStore'",
+        value_delimiter = ','
+    )]
+    pub rpc_log_methods: Vec<String>,
+
+    #[arg(
+        long = "rpc-max-batch-size",
+        help = "The max number of calls in a JSON-RPC batch request. Larger batches are rejected with an error.",
+        value_parser = clap::value_parser!(u32).range(1..),
+        default_value_t = DEFAULT_RPC_MAX_BATCH_SIZE,
+    )]
+    pub rpc_max_batch_size: u32,
+
+    #[arg(
+        long = "rpc-max-in-flight-calls",
+        help = "The max number of JSON-RPC calls that are handled at once, across all transports. The calls of a batch request are handled concurrently, and the calls above the limit wait for the others to finish.",
+        value_parser = clap::value_parser!(u64).range(1..),
+        default_value_t = DEFAULT_RPC_MAX_IN_FLIGHT_CALLS,
+    )]
+    pub rpc_max_in_flight_calls: u64,
+
+    #[arg(
+        long = "rpc-max-paginate-limit",
+        help = "The max number of content keys that the PaginateLocalContentKeys JSON-RPC methods list at once. Larger limits are rejected with an error.",
+        value_parser = clap::value_parser!(u64).range(1..),
+        default_value_t = DEFAULT_RPC_MAX_PAGINATE_LIMIT,
+    )]
+    pub rpc_max_paginate_limit: u64,
+
+    #[arg(
+        long = "trusted-content-signers",
+        help = "Comma-separated list of hex encoded secp256k1 public keys (with 0x prefix). Content stored over JSON-RPC with a signature of one of these keys is marked as trusted, and content with any other signature is rejected.",
+        value_parser = parse_public_key,
+        value_delimiter = ','
+    )]
+    pub trusted_content_signers: Vec<PublicKey>,
+
     #[command(subcommand)]
     pub command: Option<TrinConfigCommands>,
 }
@@ -271,18 +623,29 @@ impl Default for TrinConfig {
                 .expect("Parsing static DEFAULT_WEB3_HTTP_ADDRESS to work"),
             web3_ipc_path: PathBuf::from(DEFAULT_WEB3_IPC_PATH),
             discovery_port: DEFAULT_DISCOVERY_PORT,
+            utp_port: None,
             bootnodes: Bootnodes::Default,
             external_addr: None,
             no_stun: false,
             no_upnp: false,
             private_key: None,
             trusted_block_root: None,
+            beacon_seed_from: None,
+            beacon_chain: MAINNET_CHAIN_CONFIG.clone(),
+            chain_config_file: None,
             portal_subnetworks: subnetwork_parser(DEFAULT_SUBNETWORKS)
                 .expect("Parsing static DEFAULT_SUBNETWORKS to work"),
             storage_total: DEFAULT_STORAGE_CAPACITY_MB.parse().ok(),
             storage_beacon: None,
+            storage_beacon_pinned: DEFAULT_STORAGE_BEACON_PINNED_MB,
             storage_history: None,
             storage_state: None,
+            storage_dedup: false,
+            storage_verify_on_read: false,
+            storage_eviction_policy: StorageEvictionPolicy::Distance,
+            storage_recent_writes: DEFAULT_STORAGE_RECENT_WRITES as u64,
+            storage_archive_path: None,
+            storage_archive_mb: None,
             enable_metrics_with_url: None,
             data_dir: None,
             ephemeral: false,
@@ -291,6 +654,32 @@ impl Default for TrinConfig {
             ws_port: DEFAULT_WEB3_WS_PORT,
             command: None,
             utp_transfer_limit: DEFAULT_UTP_TRANSFER_LIMIT,
+            utp_max_up_kbps: 0,
+            utp_max_down_kbps: 0,
+            record_wire: None,
+            record_wire_max_mb: DEFAULT_RECORD_WIRE_MAX_MB,
+            failed_lookup_cache_ttl: DEFAULT_FAILED_LOOKUP_CACHE_TTL_SECS,
+            failed_lookup_cache_size: DEFAULT_FAILED_LOOKUP_CACHE_SIZE as u64,
+            lookup_cache_ttl: 0,
+            lookup_cache_max_mb: DEFAULT_LOOKUP_CACHE_MAX_MB,
+            rebroadcast_offers_per_peer: 0,
+            rebroadcast_offers_per_minute: DEFAULT_REBROADCAST_OFFERS_PER_MINUTE,
+            gossip_beacon_max_peers: DEFAULT_GOSSIP_MAX_PEERS as u64,
+            gossip_beacon_prefer_radius_coverage: false,
+            gossip_beacon_exclude_far_peers: false,
+            gossip_history_max_peers: DEFAULT_GOSSIP_MAX_PEERS as u64,
+            gossip_history_prefer_radius_coverage: false,
+            gossip_history_exclude_far_peers: false,
+            gossip_state_max_peers: DEFAULT_GOSSIP_MAX_PEERS as u64,
+            gossip_state_prefer_radius_coverage: false,
+            gossip_state_exclude_far_peers: false,
+            gossip_large_content_threshold: DEFAULT_GOSSIP_LARGE_CONTENT_THRESHOLD as u64,
+            rpc_slow_call_threshold: DEFAULT_RPC_SLOW_CALL_THRESHOLD_MS,
+            rpc_log_methods: vec![],
+            rpc_max_batch_size: DEFAULT_RPC_MAX_BATCH_SIZE,
+            rpc_max_in_flight_calls: DEFAULT_RPC_MAX_IN_FLIGHT_CALLS,
+            rpc_max_paginate_limit: DEFAULT_RPC_MAX_PAGINATE_LIMIT,
+            trusted_content_signers: vec![],
             network: MAINNET.clone(),
         }
     }
@@ -350,6 +739,13 @@ impl TrinConfig {
             }
         }
 
+        if config.utp_port == Some(config.discovery_port) {
+            return Err(Error::raw(
+                ErrorKind::ArgumentConflict,
+                "--utp-port must be different from --discovery-port",
+            ));
+        }
+
         if config.portal_subnetworks.contains(&Subnetwork::State)
             && !config.portal_subnetworks.contains(&Subnetwork::History)
         {
@@ -430,6 +826,13 @@ impl TrinConfig {
             },
         }
     }
+
+    /// Returns the config of the beacon chain, read from the chain config file if there is one.
+    pub fn chain_config(&self) -> Arc<ChainConfig> {
+        self.chain_config_file
+            .clone()
+            .unwrap_or_else(|| self.beacon_chain.clone())
+    }
 }
 
 pub fn check_private_key_length(private_key: &str) -> Result<B256, String> {
@@ -452,6 +855,22 @@ pub fn network_parser(network_string: &str) -> Result<Arc<NetworkSpec>, String>
     }
 }
 
+pub fn beacon_chain_parser(chain: &str) -> Result<Arc<ChainConfig>, String> {
+    match chain {
+        "mainnet" => Ok(MAINNET_CHAIN_CONFIG.clone()),
+        "sepolia" => Ok(SEPOLIA_CHAIN_CONFIG.clone()),
+        _ => Err(format!(
+            "Not a valid beacon chain: {chain}, must be 'mainnet' or 'sepolia'"
+        )),
+    }
+}
+
+fn chain_config_file_parser(path: &str) -> Result<Arc<ChainConfig>, String> {
+    ChainConfig::from_file(Path::new(path))
+        .map(Arc::new)
+        .map_err(|err| err.to_string())
+}
+
 pub fn subnetwork_parser(subnetwork_string: &str) -> Result<Arc<Vec<Subnetwork>>, String> {
     let subnetworks = subnetwork_string
         .split(',')
@@ -499,9 +918,14 @@ impl fmt::Display for TrinConfig {
             .field("storage.beacon", &self.storage_beacon)
             .field("storage.history", &self.storage_history)
             .field("storage.state", &self.storage_state)
+            .field("storage.archive_mb", &self.storage_archive_mb)
             .field("ephemeral", &self.ephemeral)
+            .field("utp_port", &self.utp_port)
+            .field("utp_max_up_kbps", &self.utp_max_up_kbps)
+            .field("utp_max_down_kbps", &self.utp_max_down_kbps)
             .field("json_rpc_url", &json_rpc_url)
             .field("metrics_enabled", &self.enable_metrics_with_url.is_some())
+            .field("record_wire", &self.record_wire)
             .finish()
     }
 }
@@ -510,6 +934,38 @@ impl fmt::Display for TrinConfig {
 #[allow(clippy::enum_variant_names)]
 pub enum TrinConfigCommands {
     CreateDashboard(DashboardConfig),
+    /// Replay the inbound requests of a recording made with --record-wire against a fresh node,
+    /// and report the responses that differ from the recorded ones
+    Replay(ReplayConfig),
+    /// Back up the node key, the persisted bootnodes and the content databases of the node in the
+    /// data dir into a zstd compressed tar archive. The node doesn't need to be stopped.
+    Backup(BackupConfig),
+    /// Restore a backup made with the backup subcommand into the data dir
+    Restore(RestoreConfig),
+}
+
+#[derive(Args, Debug, Default, Clone, PartialEq)]
+pub struct ReplayConfig {
+    /// Path to the wire recording
+    pub path: PathBuf,
+}
+
+#[derive(Args, Debug, Default, Clone, PartialEq)]
+pub struct BackupConfig {
+    /// Path of the archive to create, e.g. trin-backup.tar.zst
+    pub path: PathBuf,
+}
+
+#[derive(Args, Debug, Default, Clone, PartialEq)]
+pub struct RestoreConfig {
+    /// Path to the backup archive
+    pub path: PathBuf,
+
+    #[arg(
+        long,
+        help = "Restore the backup even if its schema version is incompatible, or if it replaces the node key or the content database in the data dir"
+    )]
+    pub force: bool,
 }
 
 #[derive(Args, Debug, Default, Clone, PartialEq)]
@@ -665,6 +1121,37 @@ mod tests {
         assert_eq!(actual_config.discovery_port, expected_config.discovery_port);
     }
 
+    #[test]
+    fn test_custom_utp_port() {
+        let actual_config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(actual_config.utp_port, None);
+        let actual_config = TrinConfig::new_from(["trin", "--utp-port", "9010"]).unwrap();
+        assert_eq!(actual_config.utp_port, Some(9010));
+    }
+
+    #[test]
+    fn test_utp_rate_limits() {
+        let actual_config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(actual_config.utp_max_up_kbps, 0);
+        assert_eq!(actual_config.utp_max_down_kbps, 0);
+        let actual_config = TrinConfig::new_from([
+            "trin",
+            "--utp-max-up-kbps",
+            "800",
+            "--utp-max-down-kbps",
+            "1600",
+        ])
+        .unwrap();
+        assert_eq!(actual_config.utp_max_up_kbps, 800);
+        assert_eq!(actual_config.utp_max_down_kbps, 1600);
+    }
+
+    #[test]
+    #[should_panic(expected = "--utp-port must be different from --discovery-port")]
+    fn test_utp_port_must_differ_from_discovery_port() {
+        TrinConfig::new_from(["trin", "--discovery-port", "9010", "--utp-port", "9010"]).unwrap();
+    }
+
     #[test]
     fn test_manual_external_addr_v4() {
         let actual_config =
@@ -777,6 +1264,221 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn test_beacon_seed_from() {
+        let config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(config.beacon_seed_from, None);
+
+        let config =
+            TrinConfig::new_from(["trin", "--beacon-seed-from", "http://localhost:5052"]).unwrap();
+        assert_eq!(
+            config.beacon_seed_from,
+            Some(Url::parse("http://localhost:5052").unwrap())
+        );
+
+        let err = TrinConfig::new_from(["trin", "--beacon-seed-from", "localhost"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_beacon_chain() {
+        let config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(config.chain_config(), MAINNET_CHAIN_CONFIG.clone());
+
+        let config = TrinConfig::new_from(["trin", "--beacon-chain", "sepolia"]).unwrap();
+        assert_eq!(config.chain_config(), SEPOLIA_CHAIN_CONFIG.clone());
+
+        let err = TrinConfig::new_from(["trin", "--beacon-chain", "holesky"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_chain_config_file() {
+        let mut chain_config = SEPOLIA_CHAIN_CONFIG.as_ref().clone();
+        chain_config.genesis_time += 1;
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), serde_json::to_string(&chain_config).unwrap()).unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let config = TrinConfig::new_from(["trin", "--chain-config-file", path]).unwrap();
+        assert_eq!(config.chain_config().as_ref(), &chain_config);
+
+        let err = TrinConfig::new_from([
+            "trin",
+            "--chain-config-file",
+            path,
+            "--beacon-chain",
+            "sepolia",
+        ])
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+
+        let err = TrinConfig::new_from(["trin", "--chain-config-file", "/nonexistent/chain.json"])
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_rpc_logging() {
+        let config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(
+            config.rpc_slow_call_threshold,
+            DEFAULT_RPC_SLOW_CALL_THRESHOLD_MS
+        );
+        assert!(config.rpc_log_methods.is_empty());
+
+        let config = TrinConfig::new_from([
+            "trin",
+            "--rpc-slow-call-threshold",
+            "250",
+            "--rpc-log-methods",
+            "portal_historyStore,portal_stateStore",
+        ])
+        .unwrap();
+        assert_eq!(config.rpc_slow_call_threshold, 250);
+        assert_eq!(
+            config.rpc_log_methods,
+            vec!["portal_historyStore", "portal_stateStore"]
+        );
+    }
+
+    #[test]
+    fn test_rpc_max_batch_size() {
+        let config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(config.rpc_max_batch_size, DEFAULT_RPC_MAX_BATCH_SIZE);
+
+        let config = TrinConfig::new_from(["trin", "--rpc-max-batch-size", "10"]).unwrap();
+        assert_eq!(config.rpc_max_batch_size, 10);
+
+        let err = TrinConfig::new_from(["trin", "--rpc-max-batch-size", "0"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_rpc_max_in_flight_calls() {
+        let config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(
+            config.rpc_max_in_flight_calls,
+            DEFAULT_RPC_MAX_IN_FLIGHT_CALLS
+        );
+
+        let config = TrinConfig::new_from(["trin", "--rpc-max-in-flight-calls", "8"]).unwrap();
+        assert_eq!(config.rpc_max_in_flight_calls, 8);
+
+        let err = TrinConfig::new_from(["trin", "--rpc-max-in-flight-calls", "0"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_rpc_max_paginate_limit() {
+        let config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(
+            config.rpc_max_paginate_limit,
+            DEFAULT_RPC_MAX_PAGINATE_LIMIT
+        );
+
+        let config = TrinConfig::new_from(["trin", "--rpc-max-paginate-limit", "50"]).unwrap();
+        assert_eq!(config.rpc_max_paginate_limit, 50);
+
+        let err = TrinConfig::new_from(["trin", "--rpc-max-paginate-limit", "0"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_gossip_config() {
+        let config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(
+            config.gossip_history_max_peers,
+            DEFAULT_GOSSIP_MAX_PEERS as u64
+        );
+        assert!(!config.gossip_history_prefer_radius_coverage);
+        assert!(!config.gossip_history_exclude_far_peers);
+
+        let config = TrinConfig::new_from([
+            "trin",
+            "--gossip.history.max-peers",
+            "16",
+            "--gossip.history.prefer-radius-coverage",
+            "--gossip.state.exclude-far-peers",
+        ])
+        .unwrap();
+        assert_eq!(config.gossip_history_max_peers, 16);
+        assert!(config.gossip_history_prefer_radius_coverage);
+        assert!(!config.gossip_history_exclude_far_peers);
+        assert_eq!(
+            config.gossip_state_max_peers,
+            DEFAULT_GOSSIP_MAX_PEERS as u64
+        );
+        assert!(!config.gossip_state_prefer_radius_coverage);
+        assert!(config.gossip_state_exclude_far_peers);
+
+        assert!(TrinConfig::new_from(["trin", "--gossip.beacon.max-peers", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_storage_beacon_pinned() {
+        let config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(
+            config.storage_beacon_pinned,
+            DEFAULT_STORAGE_BEACON_PINNED_MB
+        );
+        let config = TrinConfig::new_from(["trin", "--storage.beacon-pinned", "16"]).unwrap();
+        assert_eq!(config.storage_beacon_pinned, 16);
+    }
+
+    #[test]
+    fn test_storage_recent_writes() {
+        let config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(
+            config.storage_recent_writes,
+            DEFAULT_STORAGE_RECENT_WRITES as u64
+        );
+        let config = TrinConfig::new_from(["trin", "--storage.recent-writes", "8"]).unwrap();
+        assert_eq!(config.storage_recent_writes, 8);
+        assert!(TrinConfig::new_from(["trin", "--storage.recent-writes", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_gossip_large_content_threshold() {
+        let config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(
+            config.gossip_large_content_threshold,
+            DEFAULT_GOSSIP_LARGE_CONTENT_THRESHOLD as u64
+        );
+
+        let config =
+            TrinConfig::new_from(["trin", "--gossip.large-content-threshold", "50000"]).unwrap();
+        assert_eq!(config.gossip_large_content_threshold, 50000);
+
+        let err =
+            TrinConfig::new_from(["trin", "--gossip.large-content-threshold", "0"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_trusted_content_signers() {
+        let config = TrinConfig::new_from(["trin"]).unwrap();
+        assert!(config.trusted_content_signers.is_empty());
+
+        let public_key_1 = "0x0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let public_key_2 = "0x02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+        let config = TrinConfig::new_from([
+            "trin",
+            "--trusted-content-signers",
+            &format!("{public_key_1},{public_key_2}"),
+        ])
+        .unwrap();
+        assert_eq!(
+            config.trusted_content_signers,
+            vec![
+                parse_public_key(public_key_1).unwrap(),
+                parse_public_key(public_key_2).unwrap()
+            ]
+        );
+
+        assert!(TrinConfig::new_from(["trin", "--trusted-content-signers", "0x0102"]).is_err());
+    }
+
     #[test]
     fn test_trin_with_create_dashboard() {
         let config = TrinConfig::try_parse_from([
@@ -804,6 +1506,176 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_trin_with_replay() {
+        let config = TrinConfig::try_parse_from(["trin", "replay", "/tmp/wire.rec"]).unwrap();
+        assert_eq!(
+            config.command,
+            Some(TrinConfigCommands::Replay(ReplayConfig {
+                path: PathBuf::from("/tmp/wire.rec"),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_trin_with_backup_and_restore() {
+        let config = TrinConfig::try_parse_from([
+            "trin",
+            "--data-dir",
+            "/tmp/trin",
+            "backup",
+            "/tmp/backup.tar.zst",
+        ])
+        .unwrap();
+        assert_eq!(config.data_dir, Some(PathBuf::from("/tmp/trin")));
+        assert_eq!(
+            config.command,
+            Some(TrinConfigCommands::Backup(BackupConfig {
+                path: PathBuf::from("/tmp/backup.tar.zst"),
+            }))
+        );
+
+        let config =
+            TrinConfig::try_parse_from(["trin", "restore", "/tmp/backup.tar.zst", "--force"])
+                .unwrap();
+        assert_eq!(
+            config.command,
+            Some(TrinConfigCommands::Restore(RestoreConfig {
+                path: PathBuf::from("/tmp/backup.tar.zst"),
+                force: true,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_record_wire() {
+        let config = TrinConfig::new_from(["trin", "--record-wire", "/tmp/wire.rec"]).unwrap();
+        assert_eq!(config.record_wire, Some(PathBuf::from("/tmp/wire.rec")));
+        assert_eq!(config.record_wire_max_mb, DEFAULT_RECORD_WIRE_MAX_MB);
+
+        let err = TrinConfig::new_from(["trin", "--record-wire-max-mb", "10"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn test_failed_lookup_cache() {
+        let config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(
+            config.failed_lookup_cache_ttl,
+            DEFAULT_FAILED_LOOKUP_CACHE_TTL_SECS
+        );
+        assert_eq!(
+            config.failed_lookup_cache_size,
+            DEFAULT_FAILED_LOOKUP_CACHE_SIZE as u64
+        );
+
+        let config = TrinConfig::new_from([
+            "trin",
+            "--failed-lookup-cache-ttl",
+            "0",
+            "--failed-lookup-cache-size",
+            "16",
+        ])
+        .unwrap();
+        assert_eq!(config.failed_lookup_cache_ttl, 0);
+        assert_eq!(config.failed_lookup_cache_size, 16);
+
+        let err = TrinConfig::new_from(["trin", "--failed-lookup-cache-size", "0"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_lookup_cache() {
+        let config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(config.lookup_cache_ttl, 0);
+        assert_eq!(config.lookup_cache_max_mb, DEFAULT_LOOKUP_CACHE_MAX_MB);
+
+        let config = TrinConfig::new_from([
+            "trin",
+            "--lookup-cache-ttl",
+            "30",
+            "--lookup-cache-max-mb",
+            "4",
+        ])
+        .unwrap();
+        assert_eq!(config.lookup_cache_ttl, 30);
+        assert_eq!(config.lookup_cache_max_mb, 4);
+
+        let err = TrinConfig::new_from(["trin", "--lookup-cache-max-mb", "0"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_rebroadcast() {
+        let config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(config.rebroadcast_offers_per_peer, 0);
+        assert_eq!(
+            config.rebroadcast_offers_per_minute,
+            DEFAULT_REBROADCAST_OFFERS_PER_MINUTE
+        );
+
+        let config = TrinConfig::new_from([
+            "trin",
+            "--rebroadcast-offers-per-peer",
+            "8",
+            "--rebroadcast-offers-per-minute",
+            "16",
+        ])
+        .unwrap();
+        assert_eq!(config.rebroadcast_offers_per_peer, 8);
+        assert_eq!(config.rebroadcast_offers_per_minute, 16);
+
+        let err =
+            TrinConfig::new_from(["trin", "--rebroadcast-offers-per-peer", "65"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_storage_eviction_policy() {
+        let config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(
+            config.storage_eviction_policy,
+            StorageEvictionPolicy::Distance
+        );
+
+        let config =
+            TrinConfig::new_from(["trin", "--storage.eviction-policy", "distance-lru"]).unwrap();
+        assert_eq!(
+            config.storage_eviction_policy,
+            StorageEvictionPolicy::DistanceLru
+        );
+
+        let err = TrinConfig::new_from(["trin", "--storage.eviction-policy", "lru"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_storage_archive() {
+        let config = TrinConfig::new_from(["trin"]).unwrap();
+        assert_eq!(config.storage_archive_path, None);
+        assert_eq!(config.storage_archive_mb, None);
+
+        let config = TrinConfig::new_from([
+            "trin",
+            "--storage.archive-path",
+            "/tmp/archive",
+            "--storage.archive-mb",
+            "500",
+        ])
+        .unwrap();
+        assert_eq!(
+            config.storage_archive_path,
+            Some(PathBuf::from("/tmp/archive"))
+        );
+        assert_eq!(config.storage_archive_mb, Some(500));
+
+        let err =
+            TrinConfig::new_from(["trin", "--storage.archive-path", "/tmp/archive"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+        let err = TrinConfig::new_from(["trin", "--storage.archive-mb", "500"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
     #[test]
     #[should_panic(expected = "Invalid web3-transport arg. Expected either 'http' or 'ipc'")]
     fn test_invalid_web3_transport_argument() {
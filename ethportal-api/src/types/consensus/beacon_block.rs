@@ -65,6 +65,9 @@ impl BeaconBlock {
             ForkName::Bellatrix => BeaconBlockBellatrix::from_ssz_bytes(bytes).map(Self::Bellatrix),
             ForkName::Capella => BeaconBlockCapella::from_ssz_bytes(bytes).map(Self::Capella),
             ForkName::Deneb => BeaconBlockDeneb::from_ssz_bytes(bytes).map(Self::Deneb),
+            ForkName::Electra => Err(ssz::DecodeError::BytesInvalid(
+                "Electra beacon blocks aren't supported yet".to_string(),
+            )),
         }
     }
 }
@@ -198,7 +201,7 @@ mod test {
     use serde_json::Value;
 
     use super::*;
-    use crate::consensus::fork::ForkName;
+    use crate::consensus::{fork::ForkName, serde::quote_proposer_slashing_numbers};
 
     #[rstest]
     #[case("case_0")]
@@ -208,9 +211,10 @@ mod test {
             "../test_assets/beacon/bellatrix/SignedBeaconBlock/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: SignedBeaconBlockBellatrix = serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_proposer_slashing_numbers(&mut value["message"]["body"]);
         assert_eq!(serialized, value);
     }
 
@@ -243,9 +247,10 @@ mod test {
             "../test_assets/beacon/capella/SignedBeaconBlock/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: SignedBeaconBlockCapella = serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_proposer_slashing_numbers(&mut value["message"]["body"]);
         assert_eq!(serialized, value);
     }
 
@@ -277,9 +282,10 @@ mod test {
             "../test_assets/beacon/deneb/SignedBeaconBlock/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: SignedBeaconBlockDeneb = serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_proposer_slashing_numbers(&mut value["message"]["body"]);
         assert_eq!(serialized, value);
     }
 
@@ -321,9 +327,10 @@ mod test {
             "../test_assets/beacon/bellatrix/BeaconBlock/ssz_random/case_0/value.yaml",
         )
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: BeaconBlockBellatrix = serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_proposer_slashing_numbers(&mut value["body"]);
         assert_eq!(serialized, value);
     }
 
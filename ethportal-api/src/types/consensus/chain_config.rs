@@ -0,0 +1,287 @@
+use std::{
+    path::Path,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use alloy::primitives::{b256, FixedBytes, B256};
+use anyhow::anyhow;
+use ethereum_hashing::hash32_concat;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    types::consensus::fork::{ForkDigest, ForkName, ParseForkNameError},
+    utils::bytes::hex_encode,
+};
+
+pub const SLOTS_PER_EPOCH: u64 = 32;
+
+/// The epoch of a fork that isn't scheduled yet.
+pub const FAR_FUTURE_EPOCH: u64 = u64::MAX;
+
+/// The parameters of the beacon chain that the beacon network serves content of, e.g. to compute
+/// the current slot or which fork a slot belongs to.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainConfig {
+    /// The id of the execution chain, as reported to the light client
+    pub chain_id: u64,
+    /// The unix timestamp of the genesis, in seconds
+    pub genesis_time: u64,
+    pub seconds_per_slot: u64,
+    pub genesis_validators_root: B256,
+    pub fork_schedule: ForkSchedule,
+}
+
+/// The forks of the beacon chain, in the order that they activate.
+///
+/// The forks after Electra aren't supported yet: the content of their slots has a fork digest that
+/// isn't in the schedule, so it's rejected when decoded.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkSchedule {
+    pub genesis: Fork,
+    pub altair: Fork,
+    pub bellatrix: Fork,
+    pub capella: Fork,
+    pub deneb: Fork,
+    /// Unscheduled if it's missing, so that the chain config files written before Electra are
+    /// still valid.
+    #[serde(default = "Fork::unscheduled")]
+    pub electra: Fork,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fork {
+    /// The epoch that the fork activates at
+    pub epoch: u64,
+    pub version: FixedBytes<4>,
+}
+
+impl Fork {
+    fn new(epoch: u64, version: [u8; 4]) -> Self {
+        Self {
+            epoch,
+            version: version.into(),
+        }
+    }
+
+    fn unscheduled() -> Self {
+        Self::new(FAR_FUTURE_EPOCH, [0x00, 0x00, 0x00, 0x00])
+    }
+
+    /// Returns whether the fork has an activation epoch.
+    pub fn is_scheduled(&self) -> bool {
+        self.epoch != FAR_FUTURE_EPOCH
+    }
+}
+
+pub static MAINNET_CHAIN_CONFIG: Lazy<Arc<ChainConfig>> = Lazy::new(|| {
+    Arc::new(ChainConfig {
+        chain_id: 1,
+        genesis_time: 1606824023,
+        seconds_per_slot: 12,
+        genesis_validators_root: b256!(
+            "4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95"
+        ),
+        fork_schedule: ForkSchedule {
+            genesis: Fork::new(0, [0x00, 0x00, 0x00, 0x00]),
+            altair: Fork::new(74240, [0x01, 0x00, 0x00, 0x00]),
+            bellatrix: Fork::new(144896, [0x02, 0x00, 0x00, 0x00]),
+            capella: Fork::new(194048, [0x03, 0x00, 0x00, 0x00]),
+            deneb: Fork::new(269568, [0x04, 0x00, 0x00, 0x00]),
+            electra: Fork::new(364032, [0x05, 0x00, 0x00, 0x00]),
+        },
+    })
+});
+
+pub static SEPOLIA_CHAIN_CONFIG: Lazy<Arc<ChainConfig>> = Lazy::new(|| {
+    Arc::new(ChainConfig {
+        chain_id: 11155111,
+        genesis_time: 1655733600,
+        seconds_per_slot: 12,
+        genesis_validators_root: b256!(
+            "d8ea171f3c94aea21ebc42a1ed61052acf3f9209c00e4efbaaddac09ed9b8078"
+        ),
+        fork_schedule: ForkSchedule {
+            genesis: Fork::new(0, [0x90, 0x00, 0x00, 0x69]),
+            altair: Fork::new(50, [0x90, 0x00, 0x00, 0x70]),
+            bellatrix: Fork::new(100, [0x90, 0x00, 0x00, 0x71]),
+            capella: Fork::new(56832, [0x90, 0x00, 0x00, 0x72]),
+            deneb: Fork::new(132608, [0x90, 0x00, 0x00, 0x73]),
+            electra: Fork::new(222464, [0x90, 0x00, 0x00, 0x74]),
+        },
+    })
+});
+
+impl ChainConfig {
+    /// Reads the chain config of a custom chain from a JSON file.
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let file = std::fs::read_to_string(path)
+            .map_err(|err| anyhow!("Unable to read chain config file {path:?}: {err}"))?;
+        serde_json::from_str(&file)
+            .map_err(|err| anyhow!("Invalid chain config file {path:?}: {err}"))
+    }
+
+    /// Returns the slot at the given time, or 0 before the genesis.
+    pub fn slot_at(&self, time: SystemTime) -> u64 {
+        let since_unix_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        since_unix_epoch.as_secs().saturating_sub(self.genesis_time) / self.seconds_per_slot
+    }
+
+    /// Returns the slot that is expected to be the head of the chain now.
+    pub fn current_slot(&self) -> u64 {
+        self.slot_at(SystemTime::now())
+    }
+
+    /// Returns the unix timestamp, in seconds, at which the slot starts.
+    pub fn slot_timestamp(&self, slot: u64) -> u64 {
+        self.genesis_time + slot * self.seconds_per_slot
+    }
+
+    pub fn fork(&self, fork_name: ForkName) -> &Fork {
+        match fork_name {
+            ForkName::Bellatrix => &self.fork_schedule.bellatrix,
+            ForkName::Capella => &self.fork_schedule.capella,
+            ForkName::Deneb => &self.fork_schedule.deneb,
+            ForkName::Electra => &self.fork_schedule.electra,
+        }
+    }
+
+    /// Returns the fork that the slot belongs to. The slots before Bellatrix, which the beacon
+    /// network doesn't serve content of, belong to Bellatrix.
+    pub fn fork_at_slot(&self, slot: u64) -> ForkName {
+        let epoch = slot / SLOTS_PER_EPOCH;
+        [ForkName::Electra, ForkName::Deneb, ForkName::Capella]
+            .into_iter()
+            .find(|fork_name| epoch >= self.fork(*fork_name).epoch)
+            .unwrap_or(ForkName::Bellatrix)
+    }
+
+    /// Returns the fork digest that prefixes the content values of the fork.
+    ///
+    /// The digest is derived from the fork version and the genesis validators root, except for
+    /// Bellatrix, whose content values are prefixed with zeros in the beacon network.
+    pub fn fork_digest(&self, fork_name: ForkName) -> ForkDigest {
+        if fork_name == ForkName::Bellatrix {
+            return [0x00, 0x00, 0x00, 0x00];
+        }
+        // The digest is the prefix of the root of the fork data container, which consists of the
+        // fork version and the genesis validators root.
+        let mut version_chunk = [0u8; 32];
+        version_chunk[..4].copy_from_slice(self.fork(fork_name).version.as_slice());
+        let fork_data_root = hash32_concat(&version_chunk, self.genesis_validators_root.as_slice());
+        let mut fork_digest = [0u8; 4];
+        fork_digest.copy_from_slice(&fork_data_root[..4]);
+        fork_digest
+    }
+
+    /// Returns the fork of the content values that are prefixed with the fork digest. The forks
+    /// that aren't scheduled have no content values.
+    pub fn fork_name_from_digest(
+        &self,
+        fork_digest: ForkDigest,
+    ) -> Result<ForkName, ParseForkNameError> {
+        ForkName::ALL
+            .into_iter()
+            .filter(|fork_name| self.fork(*fork_name).is_scheduled())
+            .find(|fork_name| self.fork_digest(*fork_name) == fork_digest)
+            .ok_or_else(|| ParseForkNameError(hex_encode(fork_digest)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mainnet_fork_digests() {
+        for fork_name in ForkName::ALL {
+            assert_eq!(
+                MAINNET_CHAIN_CONFIG.fork_digest(fork_name),
+                fork_name.as_fork_digest()
+            );
+        }
+        assert_eq!(
+            SEPOLIA_CHAIN_CONFIG.fork_digest(ForkName::Deneb),
+            [0xd3, 0x1f, 0x61, 0x91]
+        );
+        assert_eq!(
+            SEPOLIA_CHAIN_CONFIG.fork_digest(ForkName::Electra),
+            [0x14, 0x04, 0x5b, 0x5a]
+        );
+        assert!(SEPOLIA_CHAIN_CONFIG
+            .fork_name_from_digest(ForkName::Deneb.as_fork_digest())
+            .is_err());
+    }
+
+    #[test]
+    fn fork_at_slot() {
+        let capella_slot = MAINNET_CHAIN_CONFIG.fork_schedule.capella.epoch * SLOTS_PER_EPOCH;
+        let deneb_slot = MAINNET_CHAIN_CONFIG.fork_schedule.deneb.epoch * SLOTS_PER_EPOCH;
+        let electra_slot = MAINNET_CHAIN_CONFIG.fork_schedule.electra.epoch * SLOTS_PER_EPOCH;
+        assert_eq!(
+            MAINNET_CHAIN_CONFIG.fork_at_slot(capella_slot - 1),
+            ForkName::Bellatrix
+        );
+        assert_eq!(
+            MAINNET_CHAIN_CONFIG.fork_at_slot(capella_slot),
+            ForkName::Capella
+        );
+        assert_eq!(
+            MAINNET_CHAIN_CONFIG.fork_at_slot(deneb_slot - 1),
+            ForkName::Capella
+        );
+        assert_eq!(
+            MAINNET_CHAIN_CONFIG.fork_at_slot(deneb_slot),
+            ForkName::Deneb
+        );
+        assert_eq!(
+            MAINNET_CHAIN_CONFIG.fork_at_slot(electra_slot - 1),
+            ForkName::Deneb
+        );
+        assert_eq!(
+            MAINNET_CHAIN_CONFIG.fork_at_slot(electra_slot),
+            ForkName::Electra
+        );
+    }
+
+    #[test]
+    fn slot_at() {
+        let slot_start =
+            UNIX_EPOCH + std::time::Duration::from_secs(MAINNET_CHAIN_CONFIG.slot_timestamp(1_000));
+        assert_eq!(MAINNET_CHAIN_CONFIG.slot_at(slot_start), 1_000);
+        assert_eq!(
+            MAINNET_CHAIN_CONFIG.slot_at(slot_start - std::time::Duration::from_secs(1)),
+            999
+        );
+        assert_eq!(MAINNET_CHAIN_CONFIG.slot_at(UNIX_EPOCH), 0);
+        // A clock set before 1970 is treated as before the genesis.
+        assert_eq!(
+            MAINNET_CHAIN_CONFIG.slot_at(UNIX_EPOCH - std::time::Duration::from_secs(1)),
+            0
+        );
+    }
+
+    #[test]
+    fn chain_config_json_roundtrip() {
+        let json = serde_json::to_value(SEPOLIA_CHAIN_CONFIG.as_ref()).unwrap();
+        assert_eq!(json["fork_schedule"]["deneb"]["version"], "0x90000073");
+        let chain_config: ChainConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(&chain_config, SEPOLIA_CHAIN_CONFIG.as_ref());
+    }
+
+    #[test]
+    fn chain_config_without_electra() {
+        let mut json = serde_json::to_value(SEPOLIA_CHAIN_CONFIG.as_ref()).unwrap();
+        json["fork_schedule"]
+            .as_object_mut()
+            .unwrap()
+            .remove("electra");
+        let chain_config: ChainConfig = serde_json::from_value(json).unwrap();
+        assert!(!chain_config.fork_schedule.electra.is_scheduled());
+        assert_eq!(chain_config.fork_at_slot(u64::MAX), ForkName::Deneb);
+        assert!(chain_config
+            .fork_name_from_digest(SEPOLIA_CHAIN_CONFIG.fork_digest(ForkName::Electra))
+            .is_err());
+    }
+}
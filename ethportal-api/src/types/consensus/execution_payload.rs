@@ -85,7 +85,10 @@ impl ExecutionPayload {
                 ExecutionPayloadBellatrix::from_ssz_bytes(bytes).map(Self::Bellatrix)
             }
             ForkName::Capella => ExecutionPayloadCapella::from_ssz_bytes(bytes).map(Self::Capella),
-            ForkName::Deneb => ExecutionPayloadDeneb::from_ssz_bytes(bytes).map(Self::Deneb),
+            // The execution payload is unchanged in Electra.
+            ForkName::Deneb | ForkName::Electra => {
+                ExecutionPayloadDeneb::from_ssz_bytes(bytes).map(Self::Deneb)
+            }
         }
     }
 }
@@ -167,16 +170,16 @@ pub struct ExecutionPayloadHeader {
     #[superstruct(getter(copy))]
     pub prev_randao: B256,
     #[superstruct(getter(copy))]
-    #[serde(deserialize_with = "as_u64")]
+    #[serde(with = "serde_utils::quoted_u64")]
     pub block_number: u64,
     #[superstruct(getter(copy))]
-    #[serde(deserialize_with = "as_u64")]
+    #[serde(with = "serde_utils::quoted_u64")]
     pub gas_limit: u64,
     #[superstruct(getter(copy))]
-    #[serde(deserialize_with = "as_u64")]
+    #[serde(with = "serde_utils::quoted_u64")]
     pub gas_used: u64,
     #[superstruct(getter(copy))]
-    #[serde(deserialize_with = "as_u64")]
+    #[serde(with = "serde_utils::quoted_u64")]
     pub timestamp: u64,
     #[serde(with = "hex_var_list")]
     pub extra_data: ExtraData,
@@ -193,11 +196,11 @@ pub struct ExecutionPayloadHeader {
     pub withdrawals_root: B256,
     #[superstruct(only(Deneb))]
     #[superstruct(getter(copy))]
-    #[serde(deserialize_with = "as_u64")]
+    #[serde(with = "serde_utils::quoted_u64")]
     pub blob_gas_used: u64,
     #[superstruct(only(Deneb))]
     #[superstruct(getter(copy))]
-    #[serde(deserialize_with = "as_u64")]
+    #[serde(with = "serde_utils::quoted_u64")]
     pub excess_blob_gas: u64,
 }
 
@@ -210,7 +213,9 @@ impl ExecutionPayloadHeader {
             ForkName::Capella => {
                 ExecutionPayloadHeaderCapella::from_ssz_bytes(bytes).map(Self::Capella)
             }
-            ForkName::Deneb => ExecutionPayloadHeaderDeneb::from_ssz_bytes(bytes).map(Self::Deneb),
+            ForkName::Deneb | ForkName::Electra => {
+                ExecutionPayloadHeaderDeneb::from_ssz_bytes(bytes).map(Self::Deneb)
+            }
         }
     }
 }
@@ -225,6 +230,7 @@ mod test {
     use serde_json::Value;
 
     use super::*;
+    use crate::consensus::serde::quote_numbers;
 
     #[rstest]
     #[case("case_0")]
@@ -278,9 +284,10 @@ mod test {
             "../test_assets/beacon/bellatrix/ExecutionPayloadHeader/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let body: ExecutionPayloadHeaderBellatrix = serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(body).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
@@ -319,9 +326,10 @@ mod test {
             "../test_assets/beacon/capella/ExecutionPayloadHeader/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let body: ExecutionPayloadHeaderCapella = serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(body).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
@@ -357,9 +365,10 @@ mod test {
             "../test_assets/beacon/deneb/ExecutionPayloadHeader/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let body: ExecutionPayloadHeaderDeneb = serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(body).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
@@ -11,7 +11,7 @@ use crate::utils::bytes::hex_encode;
 /// Error thrown when failed to parse a valid [`ForkName`].
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
 #[error("Unknown fork for digest: {0}")]
-pub struct ParseForkNameError(String);
+pub struct ParseForkNameError(pub(crate) String);
 
 pub type ForkDigest = [u8; 4];
 
@@ -20,6 +20,7 @@ pub enum ForkName {
     Bellatrix,
     Capella,
     Deneb,
+    Electra,
 }
 
 impl TryFrom<ForkDigest> for ForkName {
@@ -30,17 +31,27 @@ impl TryFrom<ForkDigest> for ForkName {
             [0x0, 0x0, 0x0, 0x0] => Ok(ForkName::Bellatrix),
             [0xbb, 0xa4, 0xda, 0x96] => Ok(ForkName::Capella),
             [0x6a, 0x95, 0xa1, 0xa9] => Ok(ForkName::Deneb),
+            [0xad, 0x53, 0x2c, 0xeb] => Ok(ForkName::Electra),
             _ => Err(ParseForkNameError(hex_encode(fork_digest))),
         }
     }
 }
 
 impl ForkName {
+    /// The forks that the beacon network serves content of, in the order that they activate.
+    pub const ALL: [ForkName; 4] = [
+        ForkName::Bellatrix,
+        ForkName::Capella,
+        ForkName::Deneb,
+        ForkName::Electra,
+    ];
+
     pub fn as_fork_digest(&self) -> [u8; 4] {
         match self {
             ForkName::Bellatrix => [0x0, 0x0, 0x0, 0x0],
             ForkName::Capella => [0xbb, 0xa4, 0xda, 0x96],
             ForkName::Deneb => [0x6a, 0x95, 0xa1, 0xa9],
+            ForkName::Electra => [0xad, 0x53, 0x2c, 0xeb],
         }
     }
 }
@@ -53,6 +64,7 @@ impl FromStr for ForkName {
             "bellatrix" | "merge" => ForkName::Bellatrix,
             "capella" => ForkName::Capella,
             "deneb" => ForkName::Deneb,
+            "electra" => ForkName::Electra,
             _ => return Err(format!("unknown fork name: {fork_name}")),
         })
     }
@@ -64,6 +76,7 @@ impl Display for ForkName {
             ForkName::Bellatrix => "bellatrix".fmt(f),
             ForkName::Capella => "capella".fmt(f),
             ForkName::Deneb => "deneb".fmt(f),
+            ForkName::Electra => "electra".fmt(f),
         }
     }
 }
@@ -94,5 +107,7 @@ mod test {
         assert_eq!(ForkName::Bellatrix.to_string(), "bellatrix");
         assert_eq!(ForkName::Capella.to_string(), "capella");
         assert_eq!(ForkName::Deneb.to_string(), "deneb");
+        assert_eq!(ForkName::from_str("electra"), Ok(ForkName::Electra));
+        assert_eq!(ForkName::Electra.to_string(), "electra");
     }
 }
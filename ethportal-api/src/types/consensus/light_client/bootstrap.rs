@@ -2,12 +2,15 @@ use alloy::primitives::B256;
 use serde::{Deserialize, Serialize};
 use ssz::Decode;
 use ssz_derive::{Decode, Encode};
-use ssz_types::{typenum::U5, FixedVector};
+use ssz_types::{
+    typenum::{U5, U6},
+    FixedVector,
+};
 use superstruct::superstruct;
 
 use crate::{
     consensus::header::BeaconBlockHeader,
-    light_client::header::LightClientHeaderDeneb,
+    light_client::header::{LightClientHeaderDeneb, LightClientHeaderElectra},
     types::consensus::{
         fork::ForkName,
         light_client::header::{LightClientHeaderBellatrix, LightClientHeaderCapella},
@@ -16,11 +19,14 @@ use crate::{
 };
 
 pub type CurrentSyncCommitteeProofLen = U5;
+/// The sync committee proofs are one level deeper from Electra on, as the beacon state has more
+/// fields.
+pub type CurrentSyncCommitteeProofLenElectra = U6;
 
 /// `LightClientBootstrap` object for the configured trusted block root.
 /// The bootstrap object is used to generate a local `LightClientStore`.
 #[superstruct(
-    variants(Bellatrix, Capella, Deneb),
+    variants(Bellatrix, Capella, Deneb, Electra),
     variant_attributes(
         derive(Debug, Clone, Serialize, PartialEq, Deserialize, Encode, Decode,),
         serde(deny_unknown_fields),
@@ -37,9 +43,20 @@ pub struct LightClientBootstrap {
     pub header: LightClientHeaderCapella,
     #[superstruct(only(Deneb), partial_getter(rename = "header_deneb"))]
     pub header: LightClientHeaderDeneb,
+    #[superstruct(only(Electra), partial_getter(rename = "header_electra"))]
+    pub header: LightClientHeaderElectra,
     /// Current sync committee corresponding to `header.beacon.state_root`
     pub current_sync_committee: SyncCommittee,
+    #[superstruct(
+        only(Bellatrix, Capella, Deneb),
+        partial_getter(rename = "current_sync_committee_branch_altair")
+    )]
     pub current_sync_committee_branch: FixedVector<B256, CurrentSyncCommitteeProofLen>,
+    #[superstruct(
+        only(Electra),
+        partial_getter(rename = "current_sync_committee_branch_electra")
+    )]
+    pub current_sync_committee_branch: FixedVector<B256, CurrentSyncCommitteeProofLenElectra>,
 }
 
 impl LightClientBootstrap {
@@ -52,6 +69,9 @@ impl LightClientBootstrap {
                 LightClientBootstrapCapella::from_ssz_bytes(bytes).map(Self::Capella)
             }
             ForkName::Deneb => LightClientBootstrapDeneb::from_ssz_bytes(bytes).map(Self::Deneb),
+            ForkName::Electra => {
+                LightClientBootstrapElectra::from_ssz_bytes(bytes).map(Self::Electra)
+            }
         }
     }
 
@@ -61,6 +81,7 @@ impl LightClientBootstrap {
             LightClientBootstrap::Bellatrix(bootstrap) => bootstrap.header.beacon,
             LightClientBootstrap::Capella(bootstrap) => bootstrap.header.beacon,
             LightClientBootstrap::Deneb(bootstrap) => bootstrap.header.beacon,
+            LightClientBootstrap::Electra(bootstrap) => bootstrap.header.beacon,
         }
     }
 }
@@ -73,6 +94,7 @@ mod test {
     use serde_json::Value;
 
     use super::*;
+    use crate::consensus::serde::quote_numbers;
 
     #[rstest]
     #[case("case_0")]
@@ -85,9 +107,10 @@ mod test {
             "../test_assets/beacon/bellatrix/LightClientBootstrap/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: LightClientBootstrapBellatrix = serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
@@ -126,9 +149,10 @@ mod test {
             "../test_assets/beacon/capella/LightClientBootstrap/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: LightClientBootstrapCapella = serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
@@ -164,9 +188,10 @@ mod test {
             "../test_assets/beacon/deneb/LightClientBootstrap/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: LightClientBootstrapDeneb = serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
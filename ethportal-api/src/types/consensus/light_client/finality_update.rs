@@ -1,19 +1,18 @@
 use alloy::primitives::B256;
 use serde::{Deserialize, Serialize};
-use serde_this_or_that::as_u64;
 use ssz::Decode;
 use ssz_derive::{Decode, Encode};
 use ssz_types::FixedVector;
 use superstruct::superstruct;
 
 use crate::{
-    light_client::header::LightClientHeaderDeneb,
+    light_client::header::{LightClientHeaderDeneb, LightClientHeaderElectra},
     types::consensus::{
         body::SyncAggregate,
         fork::ForkName,
         light_client::{
             header::{LightClientHeaderBellatrix, LightClientHeaderCapella},
-            update::FinalizedRootProofLen,
+            update::{FinalizedRootProofLen, FinalizedRootProofLenElectra},
         },
     },
 };
@@ -21,13 +20,14 @@ use crate::{
 /// A LightClientFinalityUpdate is the update that
 /// signal a new finalized beacon block header for the light client sync protocol.
 #[superstruct(
-    variants(Bellatrix, Capella, Deneb),
+    variants(Bellatrix, Capella, Deneb, Electra),
     variant_attributes(
         derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode,),
         serde(deny_unknown_fields),
     )
 )]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode)]
+#[serde(untagged)]
 #[ssz(enum_behaviour = "transparent")]
 pub struct LightClientFinalityUpdate {
     /// The last `LightClientHeader` from the last attested block by the sync committee.
@@ -37,6 +37,8 @@ pub struct LightClientFinalityUpdate {
     pub attested_header: LightClientHeaderCapella,
     #[superstruct(only(Deneb), partial_getter(rename = "attested_header_deneb"))]
     pub attested_header: LightClientHeaderDeneb,
+    #[superstruct(only(Electra), partial_getter(rename = "attested_header_electra"))]
+    pub attested_header: LightClientHeaderElectra,
     /// The last `LightClientHeader` from the last attested finalized block (end of epoch).
     #[superstruct(only(Bellatrix), partial_getter(rename = "finalized_header_bellatrix"))]
     pub finalized_header: LightClientHeaderBellatrix,
@@ -44,12 +46,20 @@ pub struct LightClientFinalityUpdate {
     pub finalized_header: LightClientHeaderCapella,
     #[superstruct(only(Deneb), partial_getter(rename = "finalized_header_deneb"))]
     pub finalized_header: LightClientHeaderDeneb,
+    #[superstruct(only(Electra), partial_getter(rename = "finalized_header_electra"))]
+    pub finalized_header: LightClientHeaderElectra,
     /// Merkle proof attesting finalized header.
+    #[superstruct(
+        only(Bellatrix, Capella, Deneb),
+        partial_getter(rename = "finality_branch_altair")
+    )]
     pub finality_branch: FixedVector<B256, FinalizedRootProofLen>,
+    #[superstruct(only(Electra), partial_getter(rename = "finality_branch_electra"))]
+    pub finality_branch: FixedVector<B256, FinalizedRootProofLenElectra>,
     /// current sync aggregate
     pub sync_aggregate: SyncAggregate,
     /// Slot of the sync aggregated signature
-    #[serde(deserialize_with = "as_u64")]
+    #[serde(with = "serde_utils::quoted_u64")]
     pub signature_slot: u64,
 }
 
@@ -65,6 +75,19 @@ impl LightClientFinalityUpdate {
             ForkName::Deneb => {
                 LightClientFinalityUpdateDeneb::from_ssz_bytes(bytes).map(Self::Deneb)
             }
+            ForkName::Electra => {
+                LightClientFinalityUpdateElectra::from_ssz_bytes(bytes).map(Self::Electra)
+            }
+        }
+    }
+
+    /// Returns the slot of the finalized header, whichever fork the update is of.
+    pub fn finalized_slot(&self) -> u64 {
+        match self {
+            Self::Bellatrix(update) => update.finalized_header.beacon.slot,
+            Self::Capella(update) => update.finalized_header.beacon.slot,
+            Self::Deneb(update) => update.finalized_header.beacon.slot,
+            Self::Electra(update) => update.finalized_header.beacon.slot,
         }
     }
 }
@@ -77,6 +100,7 @@ mod test {
     use serde_json::Value;
 
     use super::*;
+    use crate::consensus::serde::quote_numbers;
 
     #[rstest]
     #[case("case_0")]
@@ -89,10 +113,11 @@ mod test {
             "../test_assets/beacon/bellatrix/LightClientFinalityUpdate/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: LightClientFinalityUpdateBellatrix =
             serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
@@ -131,10 +156,11 @@ mod test {
             "../test_assets/beacon/capella/LightClientFinalityUpdate/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: LightClientFinalityUpdateCapella =
             serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
@@ -170,10 +196,11 @@ mod test {
             "../test_assets/beacon/deneb/LightClientFinalityUpdate/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: LightClientFinalityUpdateDeneb =
             serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
@@ -16,7 +16,7 @@ use crate::{
 pub type ExecutionBranchLen = U4;
 
 #[superstruct(
-    variants(Bellatrix, Capella, Deneb),
+    variants(Bellatrix, Capella, Deneb, Electra),
     variant_attributes(
         derive(
             Debug,
@@ -33,15 +33,17 @@ pub type ExecutionBranchLen = U4;
     )
 )]
 #[derive(Debug, Clone, Serialize, Deserialize, Encode, TreeHash)]
+#[serde(untagged)]
 #[ssz(enum_behaviour = "transparent")]
 #[tree_hash(enum_behaviour = "transparent")]
 pub struct LightClientHeader {
     pub beacon: BeaconBlockHeader,
     #[superstruct(only(Capella), partial_getter(rename = "execution_capella"))]
     pub execution: ExecutionPayloadHeaderCapella,
-    #[superstruct(only(Deneb), partial_getter(rename = "execution_deneb"))]
+    /// The execution payload header is unchanged in Electra.
+    #[superstruct(only(Deneb, Electra), partial_getter(rename = "execution_deneb"))]
     pub execution: ExecutionPayloadHeaderDeneb,
-    #[superstruct(only(Capella, Deneb))]
+    #[superstruct(only(Capella, Deneb, Electra))]
     pub execution_branch: FixedVector<B256, ExecutionBranchLen>,
 }
 
@@ -59,6 +61,7 @@ impl LightClientHeader {
             }
             ForkName::Capella => LightClientHeaderCapella::from_ssz_bytes(bytes).map(Self::Capella),
             ForkName::Deneb => LightClientHeaderDeneb::from_ssz_bytes(bytes).map(Self::Deneb),
+            ForkName::Electra => LightClientHeaderElectra::from_ssz_bytes(bytes).map(Self::Electra),
         }
     }
 }
@@ -71,6 +74,7 @@ mod test {
     use serde_json::Value;
 
     use super::*;
+    use crate::consensus::serde::quote_numbers;
 
     #[rstest]
     #[case("case_0")]
@@ -83,9 +87,10 @@ mod test {
             "../test_assets/beacon/bellatrix/LightClientHeader/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: LightClientHeaderBellatrix = serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
@@ -124,9 +129,10 @@ mod test {
             "../test_assets/beacon/capella/LightClientHeader/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: LightClientHeaderCapella = serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
@@ -162,9 +168,10 @@ mod test {
             "../test_assets/beacon/deneb/LightClientHeader/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: LightClientHeaderDeneb = serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
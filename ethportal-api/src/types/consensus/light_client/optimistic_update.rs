@@ -1,11 +1,10 @@
 use serde::{Deserialize, Serialize};
-use serde_this_or_that::as_u64;
 use ssz::Decode;
 use ssz_derive::{Decode, Encode};
 use superstruct::superstruct;
 
 use crate::{
-    light_client::header::LightClientHeaderDeneb,
+    light_client::header::{LightClientHeaderDeneb, LightClientHeaderElectra},
     types::consensus::{
         body::SyncAggregate,
         fork::ForkName,
@@ -16,13 +15,14 @@ use crate::{
 /// A LightClientOptimisticUpdate is the update we receive on each slot,
 /// it is based off the current unfinalized epoch and it is verified only against BLS signature.
 #[superstruct(
-    variants(Bellatrix, Capella, Deneb),
+    variants(Bellatrix, Capella, Deneb, Electra),
     variant_attributes(
         derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode,),
         serde(deny_unknown_fields),
     )
 )]
 #[derive(Debug, Clone, Serialize, PartialEq, Deserialize, Encode)]
+#[serde(untagged)]
 #[ssz(enum_behaviour = "transparent")]
 pub struct LightClientOptimisticUpdate {
     /// The last `LightClientHeader` from the last attested block by the sync committee.
@@ -32,10 +32,12 @@ pub struct LightClientOptimisticUpdate {
     pub attested_header: LightClientHeaderCapella,
     #[superstruct(only(Deneb), partial_getter(rename = "attested_header_deneb"))]
     pub attested_header: LightClientHeaderDeneb,
+    #[superstruct(only(Electra), partial_getter(rename = "attested_header_electra"))]
+    pub attested_header: LightClientHeaderElectra,
     /// current sync aggregate
     pub sync_aggregate: SyncAggregate,
     /// Slot of the sync aggregated signature
-    #[serde(deserialize_with = "as_u64")]
+    #[serde(with = "serde_utils::quoted_u64")]
     pub signature_slot: u64,
 }
 
@@ -51,6 +53,9 @@ impl LightClientOptimisticUpdate {
             ForkName::Deneb => {
                 LightClientOptimisticUpdateDeneb::from_ssz_bytes(bytes).map(Self::Deneb)
             }
+            ForkName::Electra => {
+                LightClientOptimisticUpdateElectra::from_ssz_bytes(bytes).map(Self::Electra)
+            }
         }
     }
 }
@@ -63,6 +68,7 @@ mod test {
     use serde_json::Value;
 
     use super::*;
+    use crate::consensus::serde::quote_numbers;
 
     #[rstest]
     #[case("case_0")]
@@ -75,10 +81,11 @@ mod test {
             "../test_assets/beacon/bellatrix/LightClientOptimisticUpdate/ssz_random/{case}/value.yaml"
         ))
             .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: LightClientOptimisticUpdateBellatrix =
             serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
@@ -117,10 +124,11 @@ mod test {
             "../test_assets/beacon/capella/LightClientOptimisticUpdate/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: LightClientOptimisticUpdateCapella =
             serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
@@ -156,10 +164,11 @@ mod test {
             "../test_assets/beacon/deneb/LightClientOptimisticUpdate/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: LightClientOptimisticUpdateDeneb =
             serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
@@ -1,16 +1,15 @@
 use alloy::primitives::B256;
 use serde::{Deserialize, Serialize};
-use serde_this_or_that::as_u64;
 use ssz::Decode;
 use ssz_derive::{Decode, Encode};
 use ssz_types::{
-    typenum::{U5, U6},
+    typenum::{U5, U6, U7},
     FixedVector,
 };
 use superstruct::superstruct;
 
 use crate::{
-    light_client::header::LightClientHeaderDeneb,
+    light_client::header::{LightClientHeaderDeneb, LightClientHeaderElectra},
     types::consensus::{
         body::SyncAggregate,
         fork::ForkName,
@@ -21,15 +20,19 @@ use crate::{
 
 type NextSyncCommitteeProofLen = U5;
 pub type FinalizedRootProofLen = U6;
+/// The proofs are one level deeper from Electra on, as the beacon state has more fields.
+type NextSyncCommitteeProofLenElectra = U6;
+pub type FinalizedRootProofLenElectra = U7;
 
 #[superstruct(
-    variants(Bellatrix, Capella, Deneb),
+    variants(Bellatrix, Capella, Deneb, Electra),
     variant_attributes(
         derive(Debug, Clone, Serialize, PartialEq, Deserialize, Encode, Decode),
         serde(deny_unknown_fields),
     )
 )]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode)]
+#[serde(untagged)]
 #[ssz(enum_behaviour = "transparent")]
 pub struct LightClientUpdate {
     /// The last `LightClientHeader` from the last attested block by the sync committee.
@@ -39,10 +42,21 @@ pub struct LightClientUpdate {
     pub attested_header: LightClientHeaderCapella,
     #[superstruct(only(Deneb), partial_getter(rename = "attested_header_deneb"))]
     pub attested_header: LightClientHeaderDeneb,
+    #[superstruct(only(Electra), partial_getter(rename = "attested_header_electra"))]
+    pub attested_header: LightClientHeaderElectra,
     /// The `SyncCommittee` used in the next period.
     pub next_sync_committee: SyncCommittee,
     /// Merkle proof for next sync committee
+    #[superstruct(
+        only(Bellatrix, Capella, Deneb),
+        partial_getter(rename = "next_sync_committee_branch_altair")
+    )]
     pub next_sync_committee_branch: FixedVector<B256, NextSyncCommitteeProofLen>,
+    #[superstruct(
+        only(Electra),
+        partial_getter(rename = "next_sync_committee_branch_electra")
+    )]
+    pub next_sync_committee_branch: FixedVector<B256, NextSyncCommitteeProofLenElectra>,
     /// The last `LightClientHeader` from the last attested finalized block (end of epoch).
     #[superstruct(only(Bellatrix), partial_getter(rename = "finalized_header_bellatrix"))]
     pub finalized_header: LightClientHeaderBellatrix,
@@ -50,12 +64,20 @@ pub struct LightClientUpdate {
     pub finalized_header: LightClientHeaderCapella,
     #[superstruct(only(Deneb), partial_getter(rename = "finalized_header_deneb"))]
     pub finalized_header: LightClientHeaderDeneb,
+    #[superstruct(only(Electra), partial_getter(rename = "finalized_header_electra"))]
+    pub finalized_header: LightClientHeaderElectra,
     /// Merkle proof attesting finalized header.
+    #[superstruct(
+        only(Bellatrix, Capella, Deneb),
+        partial_getter(rename = "finality_branch_altair")
+    )]
     pub finality_branch: FixedVector<B256, FinalizedRootProofLen>,
+    #[superstruct(only(Electra), partial_getter(rename = "finality_branch_electra"))]
+    pub finality_branch: FixedVector<B256, FinalizedRootProofLenElectra>,
     /// current sync aggregate
     pub sync_aggregate: SyncAggregate,
     /// Slot of the sync aggregated signature
-    #[serde(deserialize_with = "as_u64")]
+    #[serde(with = "serde_utils::quoted_u64")]
     pub signature_slot: u64,
 }
 
@@ -67,6 +89,7 @@ impl LightClientUpdate {
             }
             ForkName::Capella => LightClientUpdateCapella::from_ssz_bytes(bytes).map(Self::Capella),
             ForkName::Deneb => LightClientUpdateDeneb::from_ssz_bytes(bytes).map(Self::Deneb),
+            ForkName::Electra => LightClientUpdateElectra::from_ssz_bytes(bytes).map(Self::Electra),
         }
     }
 }
@@ -79,6 +102,7 @@ mod test {
     use serde_json::Value;
 
     use super::*;
+    use crate::consensus::serde::quote_numbers;
 
     #[rstest]
     #[case("case_0")]
@@ -91,9 +115,10 @@ mod test {
             "../test_assets/beacon/bellatrix/LightClientUpdate/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: LightClientUpdateBellatrix = serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
@@ -132,9 +157,10 @@ mod test {
             "../test_assets/beacon/capella/LightClientUpdate/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: LightClientUpdateCapella = serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
@@ -170,9 +196,10 @@ mod test {
             "../test_assets/beacon/deneb/LightClientUpdate/ssz_random/{case}/value.yaml"
         ))
         .expect("cannot find test asset");
-        let value: Value = serde_yaml::from_str(&value).unwrap();
+        let mut value: Value = serde_yaml::from_str(&value).unwrap();
         let content: LightClientUpdateDeneb = serde_json::from_value(value.clone()).unwrap();
         let serialized = serde_json::to_value(content).unwrap();
+        quote_numbers(&mut value);
         assert_eq!(serialized, value);
     }
 
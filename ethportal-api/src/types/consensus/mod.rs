@@ -1,6 +1,7 @@
 pub mod beacon_block;
 pub mod beacon_state;
 pub mod body;
+pub mod chain_config;
 pub mod execution_payload;
 pub mod fork;
 pub mod header;
@@ -52,3 +52,26 @@ where
     }
     Ok(txs)
 }
+
+/// Quotes the numbers of a consensus-spec test vector, as the beacon API encodes the integers as
+/// decimal strings.
+#[cfg(test)]
+pub fn quote_numbers(value: &mut Value) {
+    match value {
+        Value::Number(number) => *value = Value::String(number.to_string()),
+        Value::Array(values) => values.iter_mut().for_each(quote_numbers),
+        Value::Object(values) => values.values_mut().for_each(quote_numbers),
+        _ => {}
+    }
+}
+
+/// Quotes the numbers of the block headers in the proposer slashings of a block body test vector.
+#[cfg(test)]
+pub fn quote_proposer_slashing_numbers(body: &mut Value) {
+    if let Some(slashings) = body["proposer_slashings"].as_array_mut() {
+        for slashing in slashings {
+            quote_numbers(&mut slashing["signed_header_1"]["message"]);
+            quote_numbers(&mut slashing["signed_header_2"]["message"]);
+        }
+    }
+}
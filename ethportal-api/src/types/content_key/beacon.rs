@@ -1,12 +1,16 @@
 use std::{fmt, hash::Hash};
 
+use alloy::primitives::B256;
 use bytes::{BufMut, BytesMut};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use ssz::{Decode, DecodeError, Encode};
 use ssz_derive::{Decode, Encode};
 
 use crate::{
-    types::content_key::{error::ContentKeyError, overlay::OverlayContentKey},
+    types::content_key::{
+        error::ContentKeyError,
+        overlay::{deserialize_content_key, OverlayContentKey},
+    },
     utils::bytes::hex_encode_compact,
     RawContentKey,
 };
@@ -19,6 +23,10 @@ pub const LIGHT_CLIENT_FINALITY_UPDATE_KEY_PREFIX: u8 = 0x12;
 pub const LIGHT_CLIENT_OPTIMISTIC_UPDATE_KEY_PREFIX: u8 = 0x13;
 pub const HISTORICAL_SUMMARIES_WITH_PROOF_KEY_PREFIX: u8 = 0x14;
 
+/// The maximum number of light client updates that can be requested by range:
+/// https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/light-client/p2p-interface.md#configuration
+pub const MAX_REQUEST_LIGHT_CLIENT_UPDATES: u64 = 128;
+
 /// A content key in the beacon chain network.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum BeaconContentKey {
@@ -112,6 +120,49 @@ impl fmt::Display for BeaconContentKey {
     }
 }
 
+// The max sizes of the beacon content values aren't defined by the specs. They are local limits,
+// with a margin over the sizes of the values of the current forks, that reject values that can't
+// be valid before they are offered.
+
+/// The max size of a fork versioned light client bootstrap. Bootstraps are about 26 KB, most of
+/// which is the current sync committee (512 BLS public keys).
+pub const MAX_LIGHT_CLIENT_BOOTSTRAP_SIZE: usize = 32 * 1024;
+
+/// The max size of a fork versioned light client update. Updates are about 28 KB, most of which
+/// is the next sync committee (512 BLS public keys).
+pub const MAX_LIGHT_CLIENT_UPDATE_SIZE: usize = 32 * 1024;
+
+/// The max size of a fork versioned light client finality or optimistic update. They only consist
+/// of headers, proofs and a sync aggregate, which are about 3 KB.
+pub const MAX_LIGHT_CLIENT_HEADER_UPDATE_SIZE: usize = 4 * 1024;
+
+/// The max size of fork versioned historical summaries with their proof. The summaries grow by 64
+/// bytes every 8192 slots, so this leaves room for decades of summaries.
+pub const MAX_HISTORICAL_SUMMARIES_WITH_PROOF_SIZE: usize = 1024 * 1024;
+
+impl BeaconContentKey {
+    /// Returns the max size of the content value of the key.
+    ///
+    /// Light client updates by range are SSZ lists of at most
+    /// [MAX_REQUEST_LIGHT_CLIENT_UPDATES] updates, each preceded by its SSZ offset.
+    pub fn max_content_value_size(&self) -> usize {
+        match self {
+            BeaconContentKey::LightClientBootstrap(_) => MAX_LIGHT_CLIENT_BOOTSTRAP_SIZE,
+            BeaconContentKey::LightClientUpdatesByRange(key) => {
+                let count = key.count.min(MAX_REQUEST_LIGHT_CLIENT_UPDATES) as usize;
+                count * (ssz::BYTES_PER_LENGTH_OFFSET + MAX_LIGHT_CLIENT_UPDATE_SIZE)
+            }
+            BeaconContentKey::LightClientFinalityUpdate(_)
+            | BeaconContentKey::LightClientOptimisticUpdate(_) => {
+                MAX_LIGHT_CLIENT_HEADER_UPDATE_SIZE
+            }
+            BeaconContentKey::HistoricalSummariesWithProof(_) => {
+                MAX_HISTORICAL_SUMMARIES_WITH_PROOF_SIZE
+            }
+        }
+    }
+}
+
 impl OverlayContentKey for BeaconContentKey {
     fn to_bytes(&self) -> RawContentKey {
         let mut bytes;
@@ -201,14 +252,105 @@ impl<'de> Deserialize<'de> for BeaconContentKey {
     where
         D: Deserializer<'de>,
     {
-        let bytes = RawContentKey::deserialize(deserializer)?;
-        Self::try_from_bytes(bytes).map_err(serde::de::Error::custom)
+        deserialize_content_key::<D, Self, StructuredBeaconContentKey>(deserializer)
+    }
+}
+
+/// The structured form of a [BeaconContentKey], accepted as an alternative to the hex encoded key.
+///
+/// For example: `{"lightClientUpdatesByRange": {"startPeriod": 800, "count": 4}}`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase",
+    deny_unknown_fields
+)]
+pub enum StructuredBeaconContentKey {
+    LightClientBootstrap { block_hash: B256 },
+    LightClientUpdatesByRange { start_period: u64, count: u64 },
+    LightClientFinalityUpdate { finalized_slot: u64 },
+    LightClientOptimisticUpdate { signature_slot: u64 },
+    HistoricalSummariesWithProof { epoch: u64 },
+}
+
+impl From<&BeaconContentKey> for StructuredBeaconContentKey {
+    fn from(content_key: &BeaconContentKey) -> Self {
+        match content_key {
+            BeaconContentKey::LightClientBootstrap(key) => Self::LightClientBootstrap {
+                block_hash: B256::from(key.block_hash),
+            },
+            BeaconContentKey::LightClientUpdatesByRange(key) => Self::LightClientUpdatesByRange {
+                start_period: key.start_period,
+                count: key.count,
+            },
+            BeaconContentKey::LightClientFinalityUpdate(key) => Self::LightClientFinalityUpdate {
+                finalized_slot: key.finalized_slot,
+            },
+            BeaconContentKey::LightClientOptimisticUpdate(key) => {
+                Self::LightClientOptimisticUpdate {
+                    signature_slot: key.signature_slot,
+                }
+            }
+            BeaconContentKey::HistoricalSummariesWithProof(key) => {
+                Self::HistoricalSummariesWithProof { epoch: key.epoch }
+            }
+        }
+    }
+}
+
+impl TryFrom<StructuredBeaconContentKey> for BeaconContentKey {
+    type Error = ContentKeyError;
+
+    fn try_from(content_key: StructuredBeaconContentKey) -> Result<Self, Self::Error> {
+        let content_key = match content_key {
+            StructuredBeaconContentKey::LightClientBootstrap { block_hash } => {
+                Self::LightClientBootstrap(LightClientBootstrapKey {
+                    block_hash: block_hash.0,
+                })
+            }
+            StructuredBeaconContentKey::LightClientUpdatesByRange {
+                start_period,
+                count,
+            } => {
+                if !(1..=MAX_REQUEST_LIGHT_CLIENT_UPDATES).contains(&count) {
+                    return Err(ContentKeyError::invalid_field(
+                        "lightClientUpdatesByRange.count",
+                        format!(
+                            "must be between 1 and {MAX_REQUEST_LIGHT_CLIENT_UPDATES}, got {count}"
+                        ),
+                    ));
+                }
+                if start_period.checked_add(count).is_none() {
+                    return Err(ContentKeyError::invalid_field(
+                        "lightClientUpdatesByRange.startPeriod",
+                        format!("range of {count} periods starting at {start_period} overflows"),
+                    ));
+                }
+                Self::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                    start_period,
+                    count,
+                })
+            }
+            StructuredBeaconContentKey::LightClientFinalityUpdate { finalized_slot } => {
+                Self::LightClientFinalityUpdate(LightClientFinalityUpdateKey { finalized_slot })
+            }
+            StructuredBeaconContentKey::LightClientOptimisticUpdate { signature_slot } => {
+                Self::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey { signature_slot })
+            }
+            StructuredBeaconContentKey::HistoricalSummariesWithProof { epoch } => {
+                Self::HistoricalSummariesWithProof(HistoricalSummariesWithProofKey { epoch })
+            }
+        };
+        Ok(content_key)
     }
 }
 
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test {
+    use rstest::rstest;
+    use serde_json::{json, Value};
+
     use super::*;
     use crate::utils::bytes::hex_decode;
 
@@ -322,4 +464,82 @@ mod test {
         );
         assert_eq!(content_key.to_hex(), KEY_STR);
     }
+
+    #[rstest]
+    #[case::light_client_bootstrap(
+        json!({"lightClientBootstrap": {
+            "blockHash": "0xbd9f42d9a42d972bdaf4dee84e5b419dd432b52867258acb7bcc7f567b6e3af1"
+        }}),
+        "0x10bd9f42d9a42d972bdaf4dee84e5b419dd432b52867258acb7bcc7f567b6e3af1"
+    )]
+    #[case::light_client_updates_by_range(
+        json!({"lightClientUpdatesByRange": {"startPeriod": 816, "count": 4}}),
+        "0x1130030000000000000400000000000000"
+    )]
+    #[case::light_client_finality_update(
+        json!({"lightClientFinalityUpdate": {"finalizedSlot": 7271362}}),
+        "0x12c2f36e0000000000"
+    )]
+    #[case::light_client_optimistic_update(
+        json!({"lightClientOptimisticUpdate": {"signatureSlot": 7271362}}),
+        "0x13c2f36e0000000000"
+    )]
+    #[case::historical_summaries_with_proof(
+        json!({"historicalSummariesWithProof": {"epoch": 450508969718611630u64}}),
+        "0x14ae7e346485874006"
+    )]
+    fn ser_de_structured(#[case] structured: Value, #[case] key_str: &str) {
+        let expected_content_key = BeaconContentKey::try_from_hex(key_str).unwrap();
+
+        let content_key: BeaconContentKey = serde_json::from_value(structured.clone()).unwrap();
+        assert_eq!(content_key, expected_content_key);
+        assert_eq!(serde_json::to_value(&content_key).unwrap(), json!(key_str));
+
+        // round trip through the structured form
+        let reencoded =
+            serde_json::to_value(StructuredBeaconContentKey::from(&content_key)).unwrap();
+        assert_eq!(reencoded, structured);
+        assert_eq!(
+            serde_json::from_value::<BeaconContentKey>(reencoded).unwrap(),
+            expected_content_key
+        );
+    }
+
+    #[rstest]
+    #[case::missing_field(
+        json!({"lightClientUpdatesByRange": {"startPeriod": 800}}),
+        "missing field `count`"
+    )]
+    #[case::unknown_field(
+        json!({"lightClientFinalityUpdate": {"finalizedSlot": 1, "slot": 1}}),
+        "unknown field `slot`, expected `finalizedSlot`"
+    )]
+    #[case::negative_field(
+        json!({"historicalSummariesWithProof": {"epoch": -1}}),
+        "invalid value: integer `-1`, expected u64"
+    )]
+    #[case::zero_count(
+        json!({"lightClientUpdatesByRange": {"startPeriod": 800, "count": 0}}),
+        "Invalid value for field `lightClientUpdatesByRange.count`: must be between 1 and 128, got 0"
+    )]
+    #[case::count_too_large(
+        json!({"lightClientUpdatesByRange": {"startPeriod": 800, "count": 129}}),
+        "Invalid value for field `lightClientUpdatesByRange.count`: must be between 1 and 128, got 129"
+    )]
+    #[case::range_overflow(
+        json!({"lightClientUpdatesByRange": {"startPeriod": u64::MAX, "count": 1}}),
+        "Invalid value for field `lightClientUpdatesByRange.startPeriod`: range of 1 periods starting at 18446744073709551615 overflows"
+    )]
+    #[case::invalid_block_hash(
+        json!({"lightClientBootstrap": {"blockHash": "0x1234"}}),
+        "invalid string length"
+    )]
+    #[case::unknown_variant(json!({"blockBody": {}}), "unknown variant `blockBody`")]
+    fn de_structured_failure(#[case] structured: Value, #[case] expected_error: &str) {
+        let error = serde_json::from_value::<BeaconContentKey>(structured).unwrap_err();
+        assert!(
+            error.to_string().starts_with(expected_error),
+            "unexpected error: {error}"
+        );
+    }
 }
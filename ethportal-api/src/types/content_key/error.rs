@@ -13,6 +13,9 @@ pub enum ContentKeyError {
 
     #[error("Input Vec has length {received}, expected {expected})")]
     InvalidLength { received: usize, expected: usize },
+
+    #[error("Invalid value for field `{field}`: {message}")]
+    InvalidField { field: String, message: String },
 }
 
 impl ContentKeyError {
@@ -22,4 +25,11 @@ impl ContentKeyError {
             input: hex_encode(input),
         }
     }
+
+    pub fn invalid_field(field: &str, message: impl ToString) -> Self {
+        Self::InvalidField {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use std::{fmt, hash::Hash};
 
+use alloy::primitives::B256;
 use bytes::{BufMut, BytesMut};
 use rand::{seq::SliceRandom, RngCore};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -7,7 +8,10 @@ use ssz::{Decode, DecodeError, Encode};
 use ssz_derive::{Decode, Encode};
 
 use crate::{
-    types::content_key::{error::ContentKeyError, overlay::OverlayContentKey},
+    types::content_key::{
+        error::ContentKeyError,
+        overlay::{deserialize_content_key, OverlayContentKey},
+    },
     utils::bytes::hex_encode_compact,
     RawContentKey,
 };
@@ -96,8 +100,62 @@ impl<'de> Deserialize<'de> for HistoryContentKey {
     where
         D: Deserializer<'de>,
     {
-        let bytes = RawContentKey::deserialize(deserializer)?;
-        Self::try_from_bytes(bytes).map_err(serde::de::Error::custom)
+        deserialize_content_key::<D, Self, StructuredHistoryContentKey>(deserializer)
+    }
+}
+
+/// The structured form of a [HistoryContentKey], accepted as an alternative to the hex encoded
+/// key.
+///
+/// For example: `{"blockHeaderByNumber": {"blockNumber": 12345678}}`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase",
+    deny_unknown_fields
+)]
+pub enum StructuredHistoryContentKey {
+    BlockHeaderByHash { block_hash: B256 },
+    BlockHeaderByNumber { block_number: u64 },
+    BlockBody { block_hash: B256 },
+    BlockReceipts { block_hash: B256 },
+}
+
+impl From<&HistoryContentKey> for StructuredHistoryContentKey {
+    fn from(content_key: &HistoryContentKey) -> Self {
+        match content_key {
+            HistoryContentKey::BlockHeaderByHash(key) => Self::BlockHeaderByHash {
+                block_hash: B256::from(key.block_hash),
+            },
+            HistoryContentKey::BlockHeaderByNumber(key) => Self::BlockHeaderByNumber {
+                block_number: key.block_number,
+            },
+            HistoryContentKey::BlockBody(key) => Self::BlockBody {
+                block_hash: B256::from(key.block_hash),
+            },
+            HistoryContentKey::BlockReceipts(key) => Self::BlockReceipts {
+                block_hash: B256::from(key.block_hash),
+            },
+        }
+    }
+}
+
+impl From<StructuredHistoryContentKey> for HistoryContentKey {
+    fn from(content_key: StructuredHistoryContentKey) -> Self {
+        match content_key {
+            StructuredHistoryContentKey::BlockHeaderByHash { block_hash } => {
+                Self::new_block_header_by_hash(block_hash)
+            }
+            StructuredHistoryContentKey::BlockHeaderByNumber { block_number } => {
+                Self::new_block_header_by_number(block_number)
+            }
+            StructuredHistoryContentKey::BlockBody { block_hash } => {
+                Self::new_block_body(block_hash)
+            }
+            StructuredHistoryContentKey::BlockReceipts { block_hash } => {
+                Self::new_block_receipts(block_hash)
+            }
+        }
     }
 }
 
@@ -222,6 +280,9 @@ impl OverlayContentKey for HistoryContentKey {
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test {
+    use rstest::rstest;
+    use serde_json::{json, Value};
+
     use super::*;
     use crate::{types::content_key::overlay::OverlayContentKey, utils::bytes::hex_decode};
 
@@ -413,4 +474,70 @@ mod test {
             content_key_json
         );
     }
+
+    #[rstest]
+    #[case::block_header_by_hash(
+        json!({"blockHeaderByHash": {
+            "blockHash": "0xd1c390624d3bd4e409a61a858e5dcc5517729a9170d014a6c96530d64dd8621d"
+        }}),
+        "0x00d1c390624d3bd4e409a61a858e5dcc5517729a9170d014a6c96530d64dd8621d"
+    )]
+    #[case::block_header_by_number(
+        json!({"blockHeaderByNumber": {"blockNumber": 12345678}}),
+        "0x034e61bc0000000000"
+    )]
+    #[case::block_body(
+        json!({"blockBody": {
+            "blockHash": "0xd1c390624d3bd4e409a61a858e5dcc5517729a9170d014a6c96530d64dd8621d"
+        }}),
+        "0x01d1c390624d3bd4e409a61a858e5dcc5517729a9170d014a6c96530d64dd8621d"
+    )]
+    #[case::block_receipts(
+        json!({"blockReceipts": {
+            "blockHash": "0xd1c390624d3bd4e409a61a858e5dcc5517729a9170d014a6c96530d64dd8621d"
+        }}),
+        "0x02d1c390624d3bd4e409a61a858e5dcc5517729a9170d014a6c96530d64dd8621d"
+    )]
+    fn ser_de_structured(#[case] structured: Value, #[case] key_str: &str) {
+        let expected_content_key = HistoryContentKey::try_from_hex(key_str).unwrap();
+
+        let content_key: HistoryContentKey = serde_json::from_value(structured.clone()).unwrap();
+        assert_eq!(content_key, expected_content_key);
+        assert_eq!(serde_json::to_value(&content_key).unwrap(), json!(key_str));
+
+        // round trip through the structured form
+        let reencoded =
+            serde_json::to_value(StructuredHistoryContentKey::from(&content_key)).unwrap();
+        assert_eq!(reencoded, structured);
+        assert_eq!(
+            serde_json::from_value::<HistoryContentKey>(reencoded).unwrap(),
+            expected_content_key
+        );
+    }
+
+    #[rstest]
+    #[case::missing_field(json!({"blockBody": {}}), "missing field `blockHash`")]
+    #[case::unknown_field(
+        json!({"blockHeaderByNumber": {"blockNumber": 1, "blockHash": "0x00"}}),
+        "unknown field `blockHash`, expected `blockNumber`"
+    )]
+    #[case::negative_field(
+        json!({"blockHeaderByNumber": {"blockNumber": -1}}),
+        "invalid value: integer `-1`, expected u64"
+    )]
+    #[case::invalid_block_hash(
+        json!({"blockReceipts": {"blockHash": "0x1234"}}),
+        "invalid string length"
+    )]
+    #[case::unknown_variant(
+        json!({"lightClientBootstrap": {"blockHash": "0x00"}}),
+        "unknown variant `lightClientBootstrap`"
+    )]
+    fn de_structured_failure(#[case] structured: Value, #[case] expected_error: &str) {
+        let error = serde_json::from_value::<HistoryContentKey>(structured).unwrap_err();
+        assert!(
+            error.to_string().starts_with(expected_error),
+            "unexpected error: {error}"
+        );
+    }
 }
@@ -1,6 +1,10 @@
-use std::{fmt, hash::Hash, ops::Deref, str::FromStr};
+use std::{fmt, hash::Hash, marker::PhantomData, ops::Deref, str::FromStr};
 
 use quickcheck::{Arbitrary, Gen};
+use serde::{
+    de::{self, value::MapAccessDeserializer, MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
 use sha2::{Digest, Sha256};
 
 use crate::{
@@ -42,6 +46,50 @@ pub trait OverlayContentKey:
     }
 }
 
+/// Deserializes a content key either from its "0x" prefixed hex string, or from its structured
+/// form `S`, a JSON object naming the variant and its fields.
+///
+/// Both forms produce identical keys.
+pub(crate) fn deserialize_content_key<'de, D, K, S>(deserializer: D) -> Result<K, D::Error>
+where
+    D: Deserializer<'de>,
+    K: OverlayContentKey,
+    S: Deserialize<'de> + TryInto<K>,
+    S::Error: fmt::Display,
+{
+    struct ContentKeyVisitor<K, S>(PhantomData<(K, S)>);
+
+    impl<'de, K, S> Visitor<'de> for ContentKeyVisitor<K, S>
+    where
+        K: OverlayContentKey,
+        S: Deserialize<'de> + TryInto<K>,
+        S::Error: fmt::Display,
+    {
+        type Value = K;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a hex encoded content key or a structured content key object")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+            let bytes = RawContentKey::from_str(value).map_err(de::Error::custom)?;
+            K::try_from_bytes(bytes).map_err(de::Error::custom)
+        }
+
+        fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+            K::try_from_bytes(value).map_err(de::Error::custom)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+            S::deserialize(MapAccessDeserializer::new(map))?
+                .try_into()
+                .map_err(de::Error::custom)
+        }
+    }
+
+    deserializer.deserialize_any(ContentKeyVisitor(PhantomData))
+}
+
 /// A content key type whose content id is the inner value. Allows for the construction
 /// of a content key with an arbitrary content ID.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
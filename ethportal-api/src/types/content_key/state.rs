@@ -7,7 +7,10 @@ use ssz::{Decode, DecodeError, Encode};
 use ssz_derive::{Decode, Encode};
 
 use crate::{
-    types::{content_key::overlay::OverlayContentKey, state_trie::nibbles::Nibbles},
+    types::{
+        content_key::overlay::{deserialize_content_key, OverlayContentKey},
+        state_trie::nibbles::Nibbles,
+    },
     utils::bytes::hex_encode_compact,
     ContentKeyError, RawContentKey,
 };
@@ -132,8 +135,89 @@ impl<'de> Deserialize<'de> for StateContentKey {
     where
         D: Deserializer<'de>,
     {
-        let bytes = RawContentKey::deserialize(deserializer)?;
-        Self::try_from_bytes(bytes).map_err(serde::de::Error::custom)
+        deserialize_content_key::<D, Self, StructuredStateContentKey>(deserializer)
+    }
+}
+
+/// The structured form of a [StateContentKey], accepted as an alternative to the hex encoded key.
+///
+/// Trie paths are lists of unpacked nibbles. For example:
+/// `{"accountTrieNode": {"path": [1, 2], "nodeHash": "0x..."}}`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase",
+    deny_unknown_fields
+)]
+pub enum StructuredStateContentKey {
+    AccountTrieNode {
+        path: Vec<u8>,
+        node_hash: B256,
+    },
+    ContractStorageTrieNode {
+        address_hash: B256,
+        path: Vec<u8>,
+        node_hash: B256,
+    },
+    ContractBytecode {
+        address_hash: B256,
+        code_hash: B256,
+    },
+}
+
+impl From<&StateContentKey> for StructuredStateContentKey {
+    fn from(content_key: &StateContentKey) -> Self {
+        match content_key {
+            StateContentKey::AccountTrieNode(key) => Self::AccountTrieNode {
+                path: key.path.nibbles().to_vec(),
+                node_hash: key.node_hash,
+            },
+            StateContentKey::ContractStorageTrieNode(key) => Self::ContractStorageTrieNode {
+                address_hash: key.address_hash,
+                path: key.path.nibbles().to_vec(),
+                node_hash: key.node_hash,
+            },
+            StateContentKey::ContractBytecode(key) => Self::ContractBytecode {
+                address_hash: key.address_hash,
+                code_hash: key.code_hash,
+            },
+        }
+    }
+}
+
+impl TryFrom<StructuredStateContentKey> for StateContentKey {
+    type Error = ContentKeyError;
+
+    fn try_from(content_key: StructuredStateContentKey) -> Result<Self, Self::Error> {
+        let content_key = match content_key {
+            StructuredStateContentKey::AccountTrieNode { path, node_hash } => {
+                Self::AccountTrieNode(AccountTrieNodeKey {
+                    path: Nibbles::try_from_unpacked_nibbles(&path).map_err(|err| {
+                        ContentKeyError::invalid_field("accountTrieNode.path", err)
+                    })?,
+                    node_hash,
+                })
+            }
+            StructuredStateContentKey::ContractStorageTrieNode {
+                address_hash,
+                path,
+                node_hash,
+            } => Self::ContractStorageTrieNode(ContractStorageTrieNodeKey {
+                address_hash,
+                path: Nibbles::try_from_unpacked_nibbles(&path).map_err(|err| {
+                    ContentKeyError::invalid_field("contractStorageTrieNode.path", err)
+                })?,
+                node_hash,
+            }),
+            StructuredStateContentKey::ContractBytecode {
+                address_hash,
+                code_hash,
+            } => Self::ContractBytecode(ContractBytecodeKey {
+                address_hash,
+                code_hash,
+            }),
+        };
+        Ok(content_key)
     }
 }
 
@@ -174,6 +258,7 @@ mod test {
     use alloy::primitives::{bytes, keccak256, Address};
     use anyhow::Result;
     use rstest::rstest;
+    use serde_json::json;
     use serde_yaml::Value;
 
     use super::*;
@@ -292,6 +377,83 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn account_trie_node_key_structured() -> Result<()> {
+        let yaml = read_yaml_file("account_trie_node_key.yaml")?;
+        let yaml = yaml.as_mapping().unwrap();
+
+        let structured = json!({"accountTrieNode": {
+            "path": yaml_as_nibbles(&yaml["path"]).nibbles(),
+            "nodeHash": yaml["node_hash"].as_str().unwrap(),
+        }});
+
+        assert_structured_content_key(structured, &yaml["content_key"])
+    }
+
+    #[test]
+    fn contract_storage_trie_node_key_structured() -> Result<()> {
+        let yaml = read_yaml_file("contract_storage_trie_node_key.yaml")?;
+        let yaml = yaml.as_mapping().unwrap();
+
+        let structured = json!({"contractStorageTrieNode": {
+            "addressHash": keccak256(yaml_as_address(&yaml["address"])),
+            "path": yaml_as_nibbles(&yaml["path"]).nibbles(),
+            "nodeHash": yaml["node_hash"].as_str().unwrap(),
+        }});
+
+        assert_structured_content_key(structured, &yaml["content_key"])
+    }
+
+    #[test]
+    fn contract_bytecode_key_structured() -> Result<()> {
+        let yaml = read_yaml_file("contract_bytecode_key.yaml")?;
+        let yaml = yaml.as_mapping().unwrap();
+
+        let structured = json!({"contractBytecode": {
+            "addressHash": keccak256(yaml_as_address(&yaml["address"])),
+            "codeHash": yaml["code_hash"].as_str().unwrap(),
+        }});
+
+        assert_structured_content_key(structured, &yaml["content_key"])
+    }
+
+    #[rstest]
+    #[case::missing_field(
+        json!({"contractBytecode": {"addressHash": B256::ZERO}}),
+        "missing field `codeHash`"
+    )]
+    #[case::unknown_field(
+        json!({"contractBytecode": {"addressHash": B256::ZERO, "codeHash": B256::ZERO, "path": []}}),
+        "unknown field `path`, expected `addressHash` or `codeHash`"
+    )]
+    #[case::invalid_nibble(
+        json!({"accountTrieNode": {"path": [1, 16], "nodeHash": B256::ZERO}}),
+        "Invalid value for field `accountTrieNode.path`: Invalid nibble: 16"
+    )]
+    #[case::too_many_nibbles(
+        json!({"contractStorageTrieNode": {
+            "addressHash": B256::ZERO,
+            "path": vec![0u8; 65],
+            "nodeHash": B256::ZERO,
+        }}),
+        "Invalid value for field `contractStorageTrieNode.path`: Nibbles"
+    )]
+    #[case::nibble_out_of_u8_range(
+        json!({"accountTrieNode": {"path": [256], "nodeHash": B256::ZERO}}),
+        "invalid value: integer `256`, expected u8"
+    )]
+    #[case::unknown_variant(
+        json!({"blockBody": {"blockHash": B256::ZERO}}),
+        "unknown variant `blockBody`"
+    )]
+    fn de_structured_failure(#[case] structured: serde_json::Value, #[case] expected_error: &str) {
+        let error = serde_json::from_value::<StateContentKey>(structured).unwrap_err();
+        assert!(
+            error.to_string().starts_with(expected_error),
+            "unexpected error: {error}"
+        );
+    }
+
     fn read_yaml_file(filename: &str) -> anyhow::Result<Value> {
         let path = PathBuf::from(TEST_DATA_DIRECTORY).join(filename);
         let file = read_file_from_tests_submodule(path)?;
@@ -334,4 +496,25 @@ mod test {
 
         Ok(())
     }
+
+    fn assert_structured_content_key(structured: serde_json::Value, value: &Value) -> Result<()> {
+        let expected_content_key = StateContentKey::deserialize(value)?;
+
+        assert_eq!(
+            serde_json::from_value::<StateContentKey>(structured.clone())?,
+            expected_content_key,
+            "deserialization from {structured} didn't match expected key {expected_content_key:?}"
+        );
+
+        // round trip through the structured form
+        let reencoded =
+            serde_json::to_value(StructuredStateContentKey::from(&expected_content_key))?;
+        assert_eq!(reencoded, structured);
+        assert_eq!(
+            serde_json::from_value::<StateContentKey>(reencoded)?,
+            expected_content_key
+        );
+
+        Ok(())
+    }
 }
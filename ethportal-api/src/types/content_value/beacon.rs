@@ -1,8 +1,11 @@
-use std::ops::Deref;
+use std::{ops::Deref, str::FromStr};
 
-use serde::{Serialize, Serializer};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use ssz::{Decode, DecodeError, Encode};
-use ssz_types::{typenum::U128, VariableList};
+use ssz_types::{
+    typenum::{Unsigned, U128},
+    VariableList,
+};
 
 use crate::{
     light_client::{
@@ -11,22 +14,26 @@ use crate::{
     },
     types::{
         consensus::{
+            chain_config::{ChainConfig, MAINNET_CHAIN_CONFIG},
             fork::{ForkDigest, ForkName},
             historical_summaries::HistoricalSummariesWithProof,
             light_client::{
                 bootstrap::{
                     LightClientBootstrap, LightClientBootstrapBellatrix,
-                    LightClientBootstrapCapella,
+                    LightClientBootstrapCapella, LightClientBootstrapElectra,
                 },
                 finality_update::{
                     LightClientFinalityUpdate, LightClientFinalityUpdateBellatrix,
-                    LightClientFinalityUpdateCapella,
+                    LightClientFinalityUpdateCapella, LightClientFinalityUpdateElectra,
                 },
                 optimistic_update::{
                     LightClientOptimisticUpdate, LightClientOptimisticUpdateBellatrix,
-                    LightClientOptimisticUpdateCapella,
+                    LightClientOptimisticUpdateCapella, LightClientOptimisticUpdateElectra,
+                },
+                update::{
+                    LightClientUpdate, LightClientUpdateBellatrix, LightClientUpdateCapella,
+                    LightClientUpdateElectra,
                 },
-                update::{LightClientUpdate, LightClientUpdateBellatrix, LightClientUpdateCapella},
             },
         },
         content_value::ContentValue,
@@ -61,22 +68,34 @@ impl From<LightClientBootstrapDeneb> for ForkVersionedLightClientBootstrap {
     }
 }
 
+impl From<LightClientBootstrapElectra> for ForkVersionedLightClientBootstrap {
+    fn from(bootstrap: LightClientBootstrapElectra) -> Self {
+        Self {
+            fork_name: ForkName::Electra,
+            bootstrap: LightClientBootstrap::Electra(bootstrap),
+        }
+    }
+}
+
 impl ForkVersionedLightClientBootstrap {
     pub fn encode(&self) -> Vec<u8> {
-        let fork_digest = self.fork_name.as_fork_digest();
+        self.encode_for_chain(&MAINNET_CHAIN_CONFIG)
+    }
 
-        let mut data = fork_digest.to_vec();
+    /// Encodes the value prefixed with the fork digest of the chain's fork schedule.
+    pub fn encode_for_chain(&self, chain_config: &ChainConfig) -> Vec<u8> {
+        let mut data = chain_config.fork_digest(self.fork_name).to_vec();
         data.extend(self.bootstrap.as_ssz_bytes());
         data
     }
 
     pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
-        let fork_digest = ForkDigest::try_from(&bytes[0..4]).map_err(|err| {
-            DecodeError::BytesInvalid(format!("Unable to decode fork digest: {err:?}"))
-        })?;
-        let fork_name = ForkName::try_from(fork_digest).map_err(|_| {
-            DecodeError::BytesInvalid(format!("Unable to decode fork name: {fork_digest:?}"))
-        })?;
+        Self::decode_for_chain(bytes, &MAINNET_CHAIN_CONFIG)
+    }
+
+    /// Decodes the value prefixed with a fork digest of the chain's fork schedule.
+    pub fn decode_for_chain(bytes: &[u8], chain_config: &ChainConfig) -> Result<Self, DecodeError> {
+        let fork_name = decode_fork_name(bytes, chain_config)?;
 
         let light_client_bootstrap = match fork_name {
             ForkName::Bellatrix => LightClientBootstrap::Bellatrix(
@@ -88,6 +107,9 @@ impl ForkVersionedLightClientBootstrap {
             ForkName::Deneb => {
                 LightClientBootstrap::Deneb(LightClientBootstrapDeneb::from_ssz_bytes(&bytes[4..])?)
             }
+            ForkName::Electra => LightClientBootstrap::Electra(
+                LightClientBootstrapElectra::from_ssz_bytes(&bytes[4..])?,
+            ),
         };
 
         Ok(Self {
@@ -96,12 +118,33 @@ impl ForkVersionedLightClientBootstrap {
         })
     }
 
+    /// Decodes the `{"version": <fork name>, "data": <bootstrap>}` JSON object.
+    pub fn from_json(value: serde_json::Value) -> Result<Self, ContentValueError> {
+        let (fork_name, data) = split_fork_versioned_json(value)?;
+        let bootstrap = match fork_name {
+            ForkName::Bellatrix => LightClientBootstrap::Bellatrix(from_json_data(data)?),
+            ForkName::Capella => LightClientBootstrap::Capella(from_json_data(data)?),
+            ForkName::Deneb => LightClientBootstrap::Deneb(from_json_data(data)?),
+            ForkName::Electra => LightClientBootstrap::Electra(from_json_data(data)?),
+        };
+        Ok(Self {
+            fork_name,
+            bootstrap,
+        })
+    }
+
+    /// Encodes the `{"version": <fork name>, "data": <bootstrap>}` JSON object.
+    pub fn to_json(&self) -> serde_json::Value {
+        fork_versioned_json(self.fork_name, &self.bootstrap)
+    }
+
     /// Get the slot of the `LightClientBootstrap`
     pub fn get_slot(&self) -> u64 {
         match &self.bootstrap {
             LightClientBootstrap::Bellatrix(bootstrap) => bootstrap.header.beacon.slot,
             LightClientBootstrap::Capella(bootstrap) => bootstrap.header.beacon.slot,
             LightClientBootstrap::Deneb(bootstrap) => bootstrap.header.beacon.slot,
+            LightClientBootstrap::Electra(bootstrap) => bootstrap.header.beacon.slot,
         }
     }
 }
@@ -139,20 +182,23 @@ pub struct ForkVersionedLightClientUpdate {
 
 impl ForkVersionedLightClientUpdate {
     pub fn encode(&self) -> Vec<u8> {
-        let fork_digest = self.fork_name.as_fork_digest();
+        self.encode_for_chain(&MAINNET_CHAIN_CONFIG)
+    }
 
-        let mut data = fork_digest.to_vec();
+    /// Encodes the value prefixed with the fork digest of the chain's fork schedule.
+    pub fn encode_for_chain(&self, chain_config: &ChainConfig) -> Vec<u8> {
+        let mut data = chain_config.fork_digest(self.fork_name).to_vec();
         data.extend(self.update.as_ssz_bytes());
         data
     }
 
     pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
-        let fork_digest = ForkDigest::try_from(&bytes[0..4]).map_err(|err| {
-            DecodeError::BytesInvalid(format!("Unable to decode fork digest: {err:?}"))
-        })?;
-        let fork_name = ForkName::try_from(fork_digest).map_err(|_| {
-            DecodeError::BytesInvalid(format!("Unable to decode fork name: {fork_digest:?}"))
-        })?;
+        Self::decode_for_chain(bytes, &MAINNET_CHAIN_CONFIG)
+    }
+
+    /// Decodes the value prefixed with a fork digest of the chain's fork schedule.
+    pub fn decode_for_chain(bytes: &[u8], chain_config: &ChainConfig) -> Result<Self, DecodeError> {
+        let fork_name = decode_fork_name(bytes, chain_config)?;
 
         let light_client_update = match fork_name {
             ForkName::Bellatrix => LightClientUpdate::Bellatrix(
@@ -164,6 +210,9 @@ impl ForkVersionedLightClientUpdate {
             ForkName::Deneb => {
                 LightClientUpdate::Deneb(LightClientUpdateDeneb::from_ssz_bytes(&bytes[4..])?)
             }
+            ForkName::Electra => {
+                LightClientUpdate::Electra(LightClientUpdateElectra::from_ssz_bytes(&bytes[4..])?)
+            }
         };
 
         Ok(Self {
@@ -171,6 +220,23 @@ impl ForkVersionedLightClientUpdate {
             update: light_client_update,
         })
     }
+
+    /// Decodes the `{"version": <fork name>, "data": <update>}` JSON object.
+    pub fn from_json(value: serde_json::Value) -> Result<Self, ContentValueError> {
+        let (fork_name, data) = split_fork_versioned_json(value)?;
+        let update = match fork_name {
+            ForkName::Bellatrix => LightClientUpdate::Bellatrix(from_json_data(data)?),
+            ForkName::Capella => LightClientUpdate::Capella(from_json_data(data)?),
+            ForkName::Deneb => LightClientUpdate::Deneb(from_json_data(data)?),
+            ForkName::Electra => LightClientUpdate::Electra(from_json_data(data)?),
+        };
+        Ok(Self { fork_name, update })
+    }
+
+    /// Encodes the `{"version": <fork name>, "data": <update>}` JSON object.
+    pub fn to_json(&self) -> serde_json::Value {
+        fork_versioned_json(self.fork_name, &self.update)
+    }
 }
 
 impl Decode for ForkVersionedLightClientUpdate {
@@ -197,19 +263,6 @@ impl Encode for ForkVersionedLightClientUpdate {
     }
 }
 
-impl Serialize for ForkVersionedLightClientUpdate {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let fork_digest = self.fork_name.as_fork_digest();
-
-        let mut data = fork_digest.to_vec();
-        data.extend(self.update.as_ssz_bytes());
-        serializer.serialize_str(&hex_encode(data))
-    }
-}
-
 /// Maximum number of `LightClientUpdate` instances in a single request is 128;
 /// Defined in https://github.com/ethereum/consensus-specs/blob/48143056b9be031ec810912ffc3227f7443eccd9/specs/altair/light-client/p2p-interface.md#configuration
 #[derive(Clone, Debug, PartialEq)]
@@ -223,6 +276,28 @@ impl Deref for LightClientUpdatesByRange {
     }
 }
 
+impl LightClientUpdatesByRange {
+    /// Decodes the JSON list of `{"version": <fork name>, "data": <update>}` objects.
+    pub fn from_json(value: serde_json::Value) -> Result<Self, ContentValueError> {
+        let updates = from_json_data::<Vec<serde_json::Value>>(value)?
+            .into_iter()
+            .map(ForkVersionedLightClientUpdate::from_json)
+            .collect::<Result<Vec<_>, _>>()?;
+        VariableList::new(updates)
+            .map(Self)
+            .map_err(|err| decode_json_error(format!("{err:?}")))
+    }
+
+    /// Encodes the JSON list of `{"version": <fork name>, "data": <update>}` objects.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::Value::Array(
+            self.iter()
+                .map(ForkVersionedLightClientUpdate::to_json)
+                .collect(),
+        )
+    }
+}
+
 impl Encode for LightClientUpdatesByRange {
     fn is_ssz_fixed_len() -> bool {
         false
@@ -246,8 +321,32 @@ impl Decode for LightClientUpdatesByRange {
     }
 
     fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
-        let updates = VariableList::<ForkVersionedLightClientUpdate, U128>::from_ssz_bytes(bytes)?;
-        Ok(Self(updates))
+        Self::decode_for_chain(bytes, &MAINNET_CHAIN_CONFIG)
+    }
+}
+
+impl LightClientUpdatesByRange {
+    /// Decodes the updates, each of which is prefixed with a fork digest of the chain's fork
+    /// schedule, so that the updates on both sides of a fork boundary are decoded as the
+    /// containers of their fork.
+    pub fn decode_for_chain(bytes: &[u8], chain_config: &ChainConfig) -> Result<Self, DecodeError> {
+        let updates: Vec<Vec<u8>> =
+            ssz::decode_list_of_variable_length_items(bytes, Some(U128::to_usize()))?;
+        let updates = updates
+            .iter()
+            .map(|update| ForkVersionedLightClientUpdate::decode_for_chain(update, chain_config))
+            .collect::<Result<Vec<_>, _>>()?;
+        VariableList::new(updates)
+            .map(Self)
+            .map_err(|err| DecodeError::BytesInvalid(format!("{err:?}")))
+    }
+
+    /// Encodes the updates, each prefixed with the fork digest of the chain's fork schedule.
+    pub fn encode_for_chain(&self, chain_config: &ChainConfig) -> Vec<u8> {
+        self.iter()
+            .map(|update| update.encode_for_chain(chain_config))
+            .collect::<Vec<_>>()
+            .as_ssz_bytes()
     }
 }
 
@@ -276,23 +375,34 @@ impl From<LightClientOptimisticUpdateDeneb> for ForkVersionedLightClientOptimist
     }
 }
 
+impl From<LightClientOptimisticUpdateElectra> for ForkVersionedLightClientOptimisticUpdate {
+    fn from(update: LightClientOptimisticUpdateElectra) -> Self {
+        Self {
+            fork_name: ForkName::Electra,
+            update: LightClientOptimisticUpdate::Electra(update),
+        }
+    }
+}
+
 impl ForkVersionedLightClientOptimisticUpdate {
     fn encode(&self) -> Vec<u8> {
-        let fork_digest = self.fork_name.as_fork_digest();
+        self.encode_for_chain(&MAINNET_CHAIN_CONFIG)
+    }
 
-        let mut data = fork_digest.to_vec();
+    /// Encodes the value prefixed with the fork digest of the chain's fork schedule.
+    pub fn encode_for_chain(&self, chain_config: &ChainConfig) -> Vec<u8> {
+        let mut data = chain_config.fork_digest(self.fork_name).to_vec();
         data.extend(self.update.as_ssz_bytes());
         data
     }
 
     fn decode(buf: &[u8]) -> Result<Self, DecodeError> {
-        let fork_digest = ForkDigest::try_from(&buf[0..4]).map_err(|err| {
-            DecodeError::BytesInvalid(format!("Unable to decode fork digest: {err:?}"))
-        })?;
+        Self::decode_for_chain(buf, &MAINNET_CHAIN_CONFIG)
+    }
 
-        let fork_name = ForkName::try_from(fork_digest).map_err(|_| {
-            DecodeError::BytesInvalid(format!("Unable to decode fork name: {fork_digest:?}"))
-        })?;
+    /// Decodes the value prefixed with a fork digest of the chain's fork schedule.
+    pub fn decode_for_chain(buf: &[u8], chain_config: &ChainConfig) -> Result<Self, DecodeError> {
+        let fork_name = decode_fork_name(buf, chain_config)?;
 
         let content = match fork_name {
             ForkName::Bellatrix => LightClientOptimisticUpdate::Bellatrix(
@@ -304,6 +414,9 @@ impl ForkVersionedLightClientOptimisticUpdate {
             ForkName::Deneb => LightClientOptimisticUpdate::Deneb(
                 LightClientOptimisticUpdateDeneb::from_ssz_bytes(&buf[4..])?,
             ),
+            ForkName::Electra => LightClientOptimisticUpdate::Electra(
+                LightClientOptimisticUpdateElectra::from_ssz_bytes(&buf[4..])?,
+            ),
         };
 
         Ok(Self {
@@ -311,6 +424,23 @@ impl ForkVersionedLightClientOptimisticUpdate {
             update: content,
         })
     }
+
+    /// Decodes the `{"version": <fork name>, "data": <optimistic update>}` JSON object.
+    pub fn from_json(value: serde_json::Value) -> Result<Self, ContentValueError> {
+        let (fork_name, data) = split_fork_versioned_json(value)?;
+        let update = match fork_name {
+            ForkName::Bellatrix => LightClientOptimisticUpdate::Bellatrix(from_json_data(data)?),
+            ForkName::Capella => LightClientOptimisticUpdate::Capella(from_json_data(data)?),
+            ForkName::Deneb => LightClientOptimisticUpdate::Deneb(from_json_data(data)?),
+            ForkName::Electra => LightClientOptimisticUpdate::Electra(from_json_data(data)?),
+        };
+        Ok(Self { fork_name, update })
+    }
+
+    /// Encodes the `{"version": <fork name>, "data": <optimistic update>}` JSON object.
+    pub fn to_json(&self) -> serde_json::Value {
+        fork_versioned_json(self.fork_name, &self.update)
+    }
 }
 
 impl Decode for ForkVersionedLightClientOptimisticUpdate {
@@ -361,23 +491,34 @@ impl From<LightClientFinalityUpdateDeneb> for ForkVersionedLightClientFinalityUp
     }
 }
 
+impl From<LightClientFinalityUpdateElectra> for ForkVersionedLightClientFinalityUpdate {
+    fn from(update: LightClientFinalityUpdateElectra) -> Self {
+        Self {
+            fork_name: ForkName::Electra,
+            update: LightClientFinalityUpdate::Electra(update),
+        }
+    }
+}
+
 impl ForkVersionedLightClientFinalityUpdate {
     fn encode(&self) -> Vec<u8> {
-        let fork_digest = self.fork_name.as_fork_digest();
+        self.encode_for_chain(&MAINNET_CHAIN_CONFIG)
+    }
 
-        let mut data = fork_digest.to_vec();
+    /// Encodes the value prefixed with the fork digest of the chain's fork schedule.
+    pub fn encode_for_chain(&self, chain_config: &ChainConfig) -> Vec<u8> {
+        let mut data = chain_config.fork_digest(self.fork_name).to_vec();
         data.extend(self.update.as_ssz_bytes());
         data
     }
 
     fn decode(buf: &[u8]) -> Result<Self, DecodeError> {
-        let fork_digest = ForkDigest::try_from(&buf[0..4]).map_err(|err| {
-            DecodeError::BytesInvalid(format!("Unable to decode fork digest: {err:?}"))
-        })?;
+        Self::decode_for_chain(buf, &MAINNET_CHAIN_CONFIG)
+    }
 
-        let fork_name = ForkName::try_from(fork_digest).map_err(|_| {
-            DecodeError::BytesInvalid(format!("Unable to decode fork name: {fork_digest:?}"))
-        })?;
+    /// Decodes the value prefixed with a fork digest of the chain's fork schedule.
+    pub fn decode_for_chain(buf: &[u8], chain_config: &ChainConfig) -> Result<Self, DecodeError> {
+        let fork_name = decode_fork_name(buf, chain_config)?;
 
         let content = match fork_name {
             ForkName::Bellatrix => LightClientFinalityUpdate::Bellatrix(
@@ -389,6 +530,9 @@ impl ForkVersionedLightClientFinalityUpdate {
             ForkName::Deneb => LightClientFinalityUpdate::Deneb(
                 LightClientFinalityUpdateDeneb::from_ssz_bytes(&buf[4..])?,
             ),
+            ForkName::Electra => LightClientFinalityUpdate::Electra(
+                LightClientFinalityUpdateElectra::from_ssz_bytes(&buf[4..])?,
+            ),
         };
 
         Ok(Self {
@@ -397,12 +541,30 @@ impl ForkVersionedLightClientFinalityUpdate {
         })
     }
 
+    /// Decodes the `{"version": <fork name>, "data": <finality update>}` JSON object.
+    pub fn from_json(value: serde_json::Value) -> Result<Self, ContentValueError> {
+        let (fork_name, data) = split_fork_versioned_json(value)?;
+        let update = match fork_name {
+            ForkName::Bellatrix => LightClientFinalityUpdate::Bellatrix(from_json_data(data)?),
+            ForkName::Capella => LightClientFinalityUpdate::Capella(from_json_data(data)?),
+            ForkName::Deneb => LightClientFinalityUpdate::Deneb(from_json_data(data)?),
+            ForkName::Electra => LightClientFinalityUpdate::Electra(from_json_data(data)?),
+        };
+        Ok(Self { fork_name, update })
+    }
+
+    /// Encodes the `{"version": <fork name>, "data": <finality update>}` JSON object.
+    pub fn to_json(&self) -> serde_json::Value {
+        fork_versioned_json(self.fork_name, &self.update)
+    }
+
     /// Get the finalized slot of the `LightClientFinalityUpdate`
     pub fn get_finalized_slot(&self) -> u64 {
         match &self.update {
             LightClientFinalityUpdate::Bellatrix(update) => update.finalized_header.beacon.slot,
             LightClientFinalityUpdate::Capella(update) => update.finalized_header.beacon.slot,
             LightClientFinalityUpdate::Deneb(update) => update.finalized_header.beacon.slot,
+            LightClientFinalityUpdate::Electra(update) => update.finalized_header.beacon.slot,
         }
     }
 }
@@ -440,20 +602,23 @@ pub struct ForkVersionedHistoricalSummariesWithProof {
 
 impl ForkVersionedHistoricalSummariesWithProof {
     pub fn encode(&self) -> Vec<u8> {
-        let fork_digest = self.fork_name.as_fork_digest();
+        self.encode_for_chain(&MAINNET_CHAIN_CONFIG)
+    }
 
-        let mut data = fork_digest.to_vec();
+    /// Encodes the value prefixed with the fork digest of the chain's fork schedule.
+    pub fn encode_for_chain(&self, chain_config: &ChainConfig) -> Vec<u8> {
+        let mut data = chain_config.fork_digest(self.fork_name).to_vec();
         data.extend(self.historical_summaries_with_proof.as_ssz_bytes());
         data
     }
 
     pub fn decode(buf: &[u8]) -> Result<Self, DecodeError> {
-        let fork_digest = ForkDigest::try_from(&buf[0..4]).map_err(|err| {
-            DecodeError::BytesInvalid(format!("Unable to decode fork digest: {err:?}"))
-        })?;
-        let fork_name = ForkName::try_from(fork_digest).map_err(|_| {
-            DecodeError::BytesInvalid(format!("Unable to decode fork name: {fork_digest:?}"))
-        })?;
+        Self::decode_for_chain(buf, &MAINNET_CHAIN_CONFIG)
+    }
+
+    /// Decodes the value prefixed with a fork digest of the chain's fork schedule.
+    pub fn decode_for_chain(buf: &[u8], chain_config: &ChainConfig) -> Result<Self, DecodeError> {
+        let fork_name = decode_fork_name(buf, chain_config)?;
         let summaries_with_proof = HistoricalSummariesWithProof::from_ssz_bytes(&buf[4..])?;
 
         Ok(Self {
@@ -461,6 +626,22 @@ impl ForkVersionedHistoricalSummariesWithProof {
             historical_summaries_with_proof: summaries_with_proof,
         })
     }
+
+    /// Decodes the `{"version": <fork name>, "data": <historical summaries with proof>}` JSON
+    /// object.
+    pub fn from_json(value: serde_json::Value) -> Result<Self, ContentValueError> {
+        let (fork_name, data) = split_fork_versioned_json(value)?;
+        Ok(Self {
+            fork_name,
+            historical_summaries_with_proof: from_json_data(data)?,
+        })
+    }
+
+    /// Encodes the `{"version": <fork name>, "data": <historical summaries with proof>}` JSON
+    /// object.
+    pub fn to_json(&self) -> serde_json::Value {
+        fork_versioned_json(self.fork_name, &self.historical_summaries_with_proof)
+    }
 }
 
 impl Decode for ForkVersionedHistoricalSummariesWithProof {
@@ -512,47 +693,228 @@ impl ContentValue for BeaconContentValue {
     }
 
     fn decode(key: &Self::TContentKey, buf: &[u8]) -> Result<Self, ContentValueError> {
+        Self::decode_for_chain(key, buf, &MAINNET_CHAIN_CONFIG)
+    }
+
+    fn is_valid_for_key(&self, key: &Self::TContentKey) -> bool {
         match key {
             BeaconContentKey::LightClientBootstrap(_) => {
-                if let Ok(value) = ForkVersionedLightClientBootstrap::from_ssz_bytes(buf) {
+                matches!(self, Self::LightClientBootstrap(_))
+            }
+            BeaconContentKey::LightClientUpdatesByRange(_) => {
+                matches!(self, Self::LightClientUpdatesByRange(_))
+            }
+            BeaconContentKey::LightClientFinalityUpdate(_) => {
+                matches!(self, Self::LightClientFinalityUpdate(_))
+            }
+            BeaconContentKey::LightClientOptimisticUpdate(_) => {
+                matches!(self, Self::LightClientOptimisticUpdate(_))
+            }
+            BeaconContentKey::HistoricalSummariesWithProof(_) => {
+                matches!(self, Self::HistoricalSummariesWithProof(_))
+            }
+        }
+    }
+}
+
+impl BeaconContentValue {
+    /// Encodes the content value of the chain, like [ContentValue::encode] does for mainnet.
+    ///
+    /// A value decoded with `decode_for_chain` is re-encoded to the same bytes, as each fork of
+    /// the chain has its own fork digest.
+    pub fn encode_for_chain(&self, chain_config: &ChainConfig) -> RawContentValue {
+        match self {
+            Self::HistoricalSummariesWithProof(value) => value.encode_for_chain(chain_config),
+            Self::LightClientBootstrap(value) => value.encode_for_chain(chain_config),
+            Self::LightClientUpdatesByRange(value) => value.encode_for_chain(chain_config),
+            Self::LightClientOptimisticUpdate(value) => value.encode_for_chain(chain_config),
+            Self::LightClientFinalityUpdate(value) => value.encode_for_chain(chain_config),
+        }
+        .into()
+    }
+
+    /// Decodes the content value of the chain, like [ContentValue::decode] does for mainnet.
+    ///
+    /// The container of each value is picked by its fork digest, so the values of past forks are
+    /// decoded as well. A value of a fork that isn't in the chain's fork schedule, e.g. a future
+    /// fork, is rejected with [ContentValueError::UnknownForkDigest].
+    pub fn decode_for_chain(
+        key: &BeaconContentKey,
+        buf: &[u8],
+        chain_config: &ChainConfig,
+    ) -> Result<Self, ContentValueError> {
+        match key {
+            BeaconContentKey::LightClientBootstrap(_) => {
+                if let Ok(value) =
+                    ForkVersionedLightClientBootstrap::decode_for_chain(buf, chain_config)
+                {
                     return Ok(Self::LightClientBootstrap(value));
                 }
             }
             BeaconContentKey::LightClientUpdatesByRange(_) => {
-                if let Ok(value) = LightClientUpdatesByRange::from_ssz_bytes(buf) {
+                if let Ok(value) = LightClientUpdatesByRange::decode_for_chain(buf, chain_config) {
                     return Ok(Self::LightClientUpdatesByRange(value));
                 }
             }
             BeaconContentKey::LightClientFinalityUpdate(_) => {
-                if let Ok(value) = ForkVersionedLightClientFinalityUpdate::from_ssz_bytes(buf) {
+                if let Ok(value) =
+                    ForkVersionedLightClientFinalityUpdate::decode_for_chain(buf, chain_config)
+                {
                     return Ok(Self::LightClientFinalityUpdate(value));
                 }
             }
             BeaconContentKey::LightClientOptimisticUpdate(_) => {
-                if let Ok(value) = ForkVersionedLightClientOptimisticUpdate::from_ssz_bytes(buf) {
+                if let Ok(value) =
+                    ForkVersionedLightClientOptimisticUpdate::decode_for_chain(buf, chain_config)
+                {
                     return Ok(Self::LightClientOptimisticUpdate(value));
                 }
             }
             BeaconContentKey::HistoricalSummariesWithProof(_) => {
-                if let Ok(value) = ForkVersionedHistoricalSummariesWithProof::from_ssz_bytes(buf) {
+                if let Ok(value) =
+                    ForkVersionedHistoricalSummariesWithProof::decode_for_chain(buf, chain_config)
+                {
                     return Ok(Self::HistoricalSummariesWithProof(value));
                 }
             }
         }
+        if let Some(fork_digest) = unknown_fork_digest(key, buf, chain_config) {
+            return Err(ContentValueError::UnknownForkDigest {
+                bytes: hex_encode(fork_digest),
+                subnetwork: Subnetwork::Beacon,
+            });
+        }
         Err(ContentValueError::UnknownContent {
             bytes: hex_encode(buf),
             subnetwork: Subnetwork::Beacon,
         })
     }
+
+    /// Decodes the JSON representation of the content value, as an alternative to SSZ bytes.
+    ///
+    /// Fork versioned values are expected in the `{"version": <fork name>, "data": <object>}` form,
+    /// as returned by the Beacon API, while `LightClientUpdatesByRange` is a list of such objects.
+    pub fn from_json(
+        key: &BeaconContentKey,
+        value: serde_json::Value,
+    ) -> Result<Self, ContentValueError> {
+        Ok(match key {
+            BeaconContentKey::LightClientBootstrap(_) => {
+                Self::LightClientBootstrap(ForkVersionedLightClientBootstrap::from_json(value)?)
+            }
+            BeaconContentKey::LightClientUpdatesByRange(_) => {
+                Self::LightClientUpdatesByRange(LightClientUpdatesByRange::from_json(value)?)
+            }
+            BeaconContentKey::LightClientFinalityUpdate(_) => Self::LightClientFinalityUpdate(
+                ForkVersionedLightClientFinalityUpdate::from_json(value)?,
+            ),
+            BeaconContentKey::LightClientOptimisticUpdate(_) => Self::LightClientOptimisticUpdate(
+                ForkVersionedLightClientOptimisticUpdate::from_json(value)?,
+            ),
+            BeaconContentKey::HistoricalSummariesWithProof(_) => {
+                Self::HistoricalSummariesWithProof(
+                    ForkVersionedHistoricalSummariesWithProof::from_json(value)?,
+                )
+            }
+        })
+    }
+
+    /// Encodes the content value as its JSON representation, the inverse of `from_json`.
+    ///
+    /// The objects follow the Beacon API encoding, with the integers as decimal strings and the
+    /// roots, keys and signatures as 0x-prefixed hex strings.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Self::HistoricalSummariesWithProof(value) => value.to_json(),
+            Self::LightClientBootstrap(value) => value.to_json(),
+            Self::LightClientUpdatesByRange(value) => value.to_json(),
+            Self::LightClientOptimisticUpdate(value) => value.to_json(),
+            Self::LightClientFinalityUpdate(value) => value.to_json(),
+        }
+    }
+}
+
+/// Decodes the fork digest that prefixes the value into the fork of the chain.
+fn decode_fork_name(bytes: &[u8], chain_config: &ChainConfig) -> Result<ForkName, DecodeError> {
+    let fork_digest = ForkDigest::try_from(bytes.get(0..4).unwrap_or_default()).map_err(|err| {
+        DecodeError::BytesInvalid(format!("Unable to decode fork digest: {err:?}"))
+    })?;
+    chain_config
+        .fork_name_from_digest(fork_digest)
+        .map_err(|_| {
+            DecodeError::BytesInvalid(format!("Unable to decode fork name: {fork_digest:?}"))
+        })
+}
+
+/// Returns the fork digest that prefixes the value, or one of the updates of a range, if it isn't
+/// the digest of any fork of the chain.
+fn unknown_fork_digest(
+    key: &BeaconContentKey,
+    buf: &[u8],
+    chain_config: &ChainConfig,
+) -> Option<ForkDigest> {
+    let values: Vec<Vec<u8>> = match key {
+        BeaconContentKey::LightClientUpdatesByRange(_) => {
+            ssz::decode_list_of_variable_length_items(buf, Some(U128::to_usize())).ok()?
+        }
+        _ => vec![buf.to_vec()],
+    };
+    values
+        .iter()
+        .filter_map(|value| ForkDigest::try_from(value.get(0..4)?).ok())
+        .find(|fork_digest| chain_config.fork_name_from_digest(*fork_digest).is_err())
+}
+
+/// Splits the `{"version": <fork name>, "data": <object>}` JSON object into its fork name and data.
+fn split_fork_versioned_json(
+    value: serde_json::Value,
+) -> Result<(ForkName, serde_json::Value), ContentValueError> {
+    #[derive(Deserialize)]
+    struct ForkVersionedJson {
+        version: String,
+        data: serde_json::Value,
+    }
+
+    let ForkVersionedJson { version, data } = from_json_data(value)?;
+    let fork_name = ForkName::from_str(&version).map_err(decode_json_error)?;
+    Ok((fork_name, data))
+}
+
+/// Encodes the `{"version": <fork name>, "data": <object>}` JSON object.
+fn fork_versioned_json<T: Serialize>(fork_name: ForkName, data: &T) -> serde_json::Value {
+    serde_json::json!({ "version": fork_name.to_string(), "data": data })
+}
+
+fn from_json_data<T: DeserializeOwned>(data: serde_json::Value) -> Result<T, ContentValueError> {
+    serde_json::from_value(data).map_err(|err| decode_json_error(err.to_string()))
+}
+
+fn decode_json_error(message: String) -> ContentValueError {
+    ContentValueError::DecodeJson {
+        message,
+        subnetwork: Subnetwork::Beacon,
+    }
 }
 
 #[cfg(test)]
 mod test {
     use std::fs;
 
+    use alloy::primitives::B256;
     use serde::Deserialize;
+    use ssz_types::FixedVector;
 
     use super::*;
+    use crate::types::{
+        consensus::{
+            chain_config::{Fork, ForkSchedule, FAR_FUTURE_EPOCH, SEPOLIA_CHAIN_CONFIG},
+            light_client::header::{LightClientHeaderDeneb, LightClientHeaderElectra},
+        },
+        content_key::beacon::{
+            LightClientBootstrapKey, LightClientFinalityUpdateKey, LightClientOptimisticUpdateKey,
+            LightClientUpdatesByRangeKey,
+        },
+    };
 
     #[test]
     fn light_client_bootstrap_encode_decode() {
@@ -705,6 +1067,418 @@ mod test {
         assert_str_roundtrip(content_key, beacon_content);
     }
 
+    #[test]
+    fn from_json() {
+        for file_name in [
+            "light_client_bootstrap.json",
+            "light_client_updates_by_range.json",
+            "light_client_optimistic_update.json",
+            "light_client_finality_update.json",
+        ] {
+            let file = fs::read_to_string(format!(
+                "../test_assets/portalnet/content/beacon/{file_name}"
+            ))
+            .unwrap();
+            let json: serde_json::Value = serde_json::from_str(&file).unwrap();
+            for obj in json.as_object().unwrap().values() {
+                let content_key = BeaconContentKey::deserialize(&obj["content_key"]).unwrap();
+                let content_bytes = RawContentValue::deserialize(&obj["content_value"]).unwrap();
+                let beacon_content =
+                    BeaconContentValue::decode(&content_key, &content_bytes).unwrap();
+
+                assert_eq!(
+                    BeaconContentValue::from_json(&content_key, beacon_content.to_json()).unwrap(),
+                    beacon_content
+                );
+            }
+        }
+    }
+
+    /// The light client objects were captured from the Beacon API of a beacon node, so the JSON
+    /// encoding must match them exactly.
+    #[test]
+    fn to_json_matches_beacon_api() {
+        for (file_name, content_key) in [
+            (
+                "LightClientBootstrap/json/7358656.json",
+                BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey {
+                    block_hash: [0; 32],
+                }),
+            ),
+            (
+                "LightClientUpdate/json/7357099.json",
+                BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                    start_period: 0,
+                    count: 1,
+                }),
+            ),
+            (
+                "LightClientFinalityUpdate/json/7358726.json",
+                BeaconContentKey::LightClientFinalityUpdate(LightClientFinalityUpdateKey::new(0)),
+            ),
+            (
+                "LightClientOptimisticUpdate/json/7358726.json",
+                BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey::new(
+                    0,
+                )),
+            ),
+        ] {
+            let file =
+                fs::read_to_string(format!("../test_assets/beacon/deneb/{file_name}")).unwrap();
+            let json: serde_json::Value = serde_json::from_str(&file).unwrap();
+            let beacon_content = BeaconContentValue::from_json(&content_key, json.clone()).unwrap();
+            assert_eq!(beacon_content.to_json(), json, "{file_name}");
+        }
+    }
+
+    #[test]
+    fn from_json_invalid_fork_name() {
+        let content_key =
+            BeaconContentKey::LightClientFinalityUpdate(LightClientFinalityUpdateKey::new(0));
+        let json = serde_json::json!({ "version": "altair", "data": {} });
+        assert!(matches!(
+            BeaconContentValue::from_json(&content_key, json),
+            Err(ContentValueError::DecodeJson { .. })
+        ));
+    }
+
+    #[test]
+    fn is_valid_for_key() {
+        let mut content = [
+            "light_client_bootstrap.json",
+            "light_client_updates_by_range.json",
+            "light_client_optimistic_update.json",
+            "light_client_finality_update.json",
+        ]
+        .map(|file_name| {
+            let file = fs::read_to_string(format!(
+                "../test_assets/portalnet/content/beacon/{file_name}"
+            ))
+            .unwrap();
+            let json: serde_json::Value = serde_json::from_str(&file).unwrap();
+            let obj = json.as_object().unwrap().values().next().unwrap();
+            let content_key = BeaconContentKey::deserialize(&obj["content_key"]).unwrap();
+            let content_bytes = RawContentValue::deserialize(&obj["content_value"]).unwrap();
+            let content_value = BeaconContentValue::decode(&content_key, &content_bytes).unwrap();
+            (content_key, content_value)
+        })
+        .to_vec();
+        let file = fs::read_to_string("./../portal-spec-tests/tests/mainnet/beacon_chain/historical_summaries_with_proof/deneb/historical_summaries_with_proof.yaml").unwrap();
+        let value: serde_yaml::Value = serde_yaml::from_str(&file).unwrap();
+        let content_key = BeaconContentKey::deserialize(&value["content_key"]).unwrap();
+        let content_bytes = RawContentValue::deserialize(&value["content_value"]).unwrap();
+        let content_value = BeaconContentValue::decode(&content_key, &content_bytes).unwrap();
+        content.push((content_key, content_value));
+
+        // Each content value is only valid for the key that it was decoded with.
+        for (key_index, (content_key, _)) in content.iter().enumerate() {
+            for (value_index, (_, content_value)) in content.iter().enumerate() {
+                assert_eq!(
+                    content_value.is_valid_for_key(content_key),
+                    key_index == value_index,
+                    "{content_value:?} for {content_key}"
+                );
+            }
+        }
+        let (bootstrap_key, _) = &content[0];
+        let (_, updates_value) = &content[1];
+        assert_eq!(
+            updates_value.ensure_valid_for_key(bootstrap_key),
+            Err(ContentValueError::KeyMismatch {
+                content_key: bootstrap_key.to_hex(),
+            })
+        );
+    }
+
+    #[test]
+    fn light_client_updates_by_range_decode_for_chain() {
+        let read_update = |fork: &str| {
+            let update = fs::read(format!(
+                "../test_assets/beacon/{fork}/LightClientUpdate/ssz_random/case_0/serialized.ssz_snappy"
+            ))
+            .unwrap();
+            snap::raw::Decoder::new().decompress_vec(&update).unwrap()
+        };
+        let capella_update = read_update("capella");
+        let deneb_update = read_update("deneb");
+
+        // The updates on both sides of the Deneb fork of a custom chain.
+        let chain_config = SEPOLIA_CHAIN_CONFIG.as_ref();
+        let prefixed = |fork_name: ForkName, update: &[u8]| {
+            let mut data = chain_config.fork_digest(fork_name).to_vec();
+            data.extend_from_slice(update);
+            data
+        };
+        let content_bytes = vec![
+            prefixed(ForkName::Capella, &capella_update),
+            prefixed(ForkName::Deneb, &deneb_update),
+        ]
+        .as_ssz_bytes();
+
+        let updates =
+            LightClientUpdatesByRange::decode_for_chain(&content_bytes, chain_config).unwrap();
+        assert_eq!(updates[0].fork_name, ForkName::Capella);
+        assert_eq!(
+            updates[0].update,
+            LightClientUpdate::Capella(
+                LightClientUpdateCapella::from_ssz_bytes(&capella_update).unwrap()
+            )
+        );
+        assert_eq!(updates[1].fork_name, ForkName::Deneb);
+        assert_eq!(
+            updates[1].update,
+            LightClientUpdate::Deneb(
+                LightClientUpdateDeneb::from_ssz_bytes(&deneb_update).unwrap()
+            )
+        );
+
+        // The fork digests of the custom chain aren't those of mainnet.
+        assert!(LightClientUpdatesByRange::from_ssz_bytes(&content_bytes).is_err());
+        let content_key =
+            BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                start_period: 0,
+                count: 2,
+            });
+        assert!(BeaconContentValue::decode(&content_key, &content_bytes).is_err());
+        assert!(
+            BeaconContentValue::decode_for_chain(&content_key, &content_bytes, chain_config)
+                .is_ok()
+        );
+    }
+
+    /// Each fork digest of the chain is decoded as the containers of its fork, and re-encoded with
+    /// the same digest.
+    #[test]
+    fn decode_for_chain_encode_for_chain_roundtrip_across_forks() {
+        for chain_config in [MAINNET_CHAIN_CONFIG.as_ref(), SEPOLIA_CHAIN_CONFIG.as_ref()] {
+            for fork_name in ForkName::ALL {
+                let prefixed = |type_name: &str| {
+                    let mut data = chain_config.fork_digest(fork_name).to_vec();
+                    data.extend(read_ssz_fixture(fork_name, type_name));
+                    data
+                };
+                let content = [
+                    (
+                        BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey {
+                            block_hash: [0; 32],
+                        }),
+                        prefixed("LightClientBootstrap"),
+                    ),
+                    (
+                        BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                            start_period: 0,
+                            count: 1,
+                        }),
+                        vec![prefixed("LightClientUpdate")].as_ssz_bytes(),
+                    ),
+                    (
+                        BeaconContentKey::LightClientFinalityUpdate(
+                            LightClientFinalityUpdateKey::new(0),
+                        ),
+                        prefixed("LightClientFinalityUpdate"),
+                    ),
+                    (
+                        BeaconContentKey::LightClientOptimisticUpdate(
+                            LightClientOptimisticUpdateKey::new(0),
+                        ),
+                        prefixed("LightClientOptimisticUpdate"),
+                    ),
+                ];
+                for (content_key, content_bytes) in content {
+                    let content_value = BeaconContentValue::decode_for_chain(
+                        &content_key,
+                        &content_bytes,
+                        chain_config,
+                    )
+                    .unwrap_or_else(|err| panic!("{fork_name} {content_key}: {err}"));
+                    let decoded_fork_name = match &content_value {
+                        BeaconContentValue::HistoricalSummariesWithProof(value) => value.fork_name,
+                        BeaconContentValue::LightClientBootstrap(value) => value.fork_name,
+                        BeaconContentValue::LightClientUpdatesByRange(value) => value[0].fork_name,
+                        BeaconContentValue::LightClientOptimisticUpdate(value) => value.fork_name,
+                        BeaconContentValue::LightClientFinalityUpdate(value) => value.fork_name,
+                    };
+                    assert_eq!(decoded_fork_name, fork_name, "{content_key}");
+                    assert_eq!(
+                        content_value.encode_for_chain(chain_config),
+                        RawContentValue::from(content_bytes),
+                        "{fork_name} {content_key}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn decode_unknown_fork_digest() {
+        // The digest of a fork that the chain doesn't have, e.g. a future fork.
+        let unknown_fork_digest = [0xff; 4];
+        let update = read_ssz_fixture(ForkName::Deneb, "LightClientFinalityUpdate");
+        let finality_update_key =
+            BeaconContentKey::LightClientFinalityUpdate(LightClientFinalityUpdateKey::new(0));
+        let content_bytes = [unknown_fork_digest.as_slice(), update.as_slice()].concat();
+        assert_eq!(
+            BeaconContentValue::decode(&finality_update_key, &content_bytes),
+            Err(ContentValueError::UnknownForkDigest {
+                bytes: hex_encode(unknown_fork_digest),
+                subnetwork: Subnetwork::Beacon,
+            })
+        );
+
+        // A single update of an unknown fork makes the whole range unknown.
+        let update = read_ssz_fixture(ForkName::Deneb, "LightClientUpdate");
+        let content_key =
+            BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                start_period: 0,
+                count: 2,
+            });
+        let content_bytes = vec![
+            [
+                ForkName::Deneb.as_fork_digest().as_slice(),
+                update.as_slice(),
+            ]
+            .concat(),
+            [unknown_fork_digest.as_slice(), update.as_slice()].concat(),
+        ]
+        .as_ssz_bytes();
+        assert_eq!(
+            BeaconContentValue::decode(&content_key, &content_bytes),
+            Err(ContentValueError::UnknownForkDigest {
+                bytes: hex_encode(unknown_fork_digest),
+                subnetwork: Subnetwork::Beacon,
+            })
+        );
+
+        // A known fork digest with invalid content is still unknown content.
+        let content_bytes = ForkName::Deneb.as_fork_digest().to_vec();
+        assert!(matches!(
+            BeaconContentValue::decode(&finality_update_key, &content_bytes),
+            Err(ContentValueError::UnknownContent { .. })
+        ));
+    }
+
+    #[test]
+    fn decode_electra_content() {
+        let content_key =
+            BeaconContentKey::LightClientFinalityUpdate(LightClientFinalityUpdateKey::new(0));
+        let mut content_bytes = ForkName::Electra.as_fork_digest().to_vec();
+        content_bytes.extend(read_ssz_fixture(
+            ForkName::Electra,
+            "LightClientFinalityUpdate",
+        ));
+
+        let content_value = BeaconContentValue::decode(&content_key, &content_bytes).unwrap();
+        let BeaconContentValue::LightClientFinalityUpdate(update) = &content_value else {
+            panic!("Invalid beacon content type!");
+        };
+        assert_eq!(update.fork_name, ForkName::Electra);
+        assert_eq!(update.update.finality_branch_electra().unwrap().len(), 7);
+        assert_eq!(
+            content_value.encode(),
+            RawContentValue::from(content_bytes.clone())
+        );
+
+        // A chain whose fork schedule doesn't have Electra yet doesn't know its fork digest.
+        let chain_config = ChainConfig {
+            fork_schedule: ForkSchedule {
+                electra: Fork {
+                    epoch: FAR_FUTURE_EPOCH,
+                    version: [0x05, 0x00, 0x00, 0x00].into(),
+                },
+                ..MAINNET_CHAIN_CONFIG.fork_schedule.clone()
+            },
+            ..MAINNET_CHAIN_CONFIG.as_ref().clone()
+        };
+        assert_eq!(
+            BeaconContentValue::decode_for_chain(&content_key, &content_bytes, &chain_config),
+            Err(ContentValueError::UnknownForkDigest {
+                bytes: hex_encode(ForkName::Electra.as_fork_digest()),
+                subnetwork: Subnetwork::Beacon,
+            })
+        );
+    }
+
+    /// Reads the SSZ bytes of the first random test case of the light client type of the fork.
+    ///
+    /// There are no test cases of Electra, so its light client types are built from the Deneb
+    /// ones, with a branch that is one level deeper for the proofs into the beacon state.
+    fn read_ssz_fixture(fork_name: ForkName, type_name: &str) -> Vec<u8> {
+        if fork_name == ForkName::Electra {
+            return electra_ssz_fixture(type_name);
+        }
+        let bytes = fs::read(format!(
+            "../test_assets/beacon/{fork_name}/{type_name}/ssz_random/case_0/serialized.ssz_snappy"
+        ))
+        .unwrap();
+        snap::raw::Decoder::new().decompress_vec(&bytes).unwrap()
+    }
+
+    fn electra_ssz_fixture(type_name: &str) -> Vec<u8> {
+        let deneb_bytes = read_ssz_fixture(ForkName::Deneb, type_name);
+        let header = |header: LightClientHeaderDeneb| LightClientHeaderElectra {
+            beacon: header.beacon,
+            execution: header.execution,
+            execution_branch: header.execution_branch,
+        };
+        match type_name {
+            "LightClientBootstrap" => {
+                let bootstrap = LightClientBootstrapDeneb::from_ssz_bytes(&deneb_bytes).unwrap();
+                LightClientBootstrapElectra {
+                    header: header(bootstrap.header),
+                    current_sync_committee: bootstrap.current_sync_committee,
+                    current_sync_committee_branch: deeper_branch(
+                        bootstrap.current_sync_committee_branch,
+                    ),
+                }
+                .as_ssz_bytes()
+            }
+            "LightClientUpdate" => {
+                let update = LightClientUpdateDeneb::from_ssz_bytes(&deneb_bytes).unwrap();
+                LightClientUpdateElectra {
+                    attested_header: header(update.attested_header),
+                    next_sync_committee: update.next_sync_committee,
+                    next_sync_committee_branch: deeper_branch(update.next_sync_committee_branch),
+                    finalized_header: header(update.finalized_header),
+                    finality_branch: deeper_branch(update.finality_branch),
+                    sync_aggregate: update.sync_aggregate,
+                    signature_slot: update.signature_slot,
+                }
+                .as_ssz_bytes()
+            }
+            "LightClientFinalityUpdate" => {
+                let update = LightClientFinalityUpdateDeneb::from_ssz_bytes(&deneb_bytes).unwrap();
+                LightClientFinalityUpdateElectra {
+                    attested_header: header(update.attested_header),
+                    finalized_header: header(update.finalized_header),
+                    finality_branch: deeper_branch(update.finality_branch),
+                    sync_aggregate: update.sync_aggregate,
+                    signature_slot: update.signature_slot,
+                }
+                .as_ssz_bytes()
+            }
+            "LightClientOptimisticUpdate" => {
+                let update =
+                    LightClientOptimisticUpdateDeneb::from_ssz_bytes(&deneb_bytes).unwrap();
+                LightClientOptimisticUpdateElectra {
+                    attested_header: header(update.attested_header),
+                    sync_aggregate: update.sync_aggregate,
+                    signature_slot: update.signature_slot,
+                }
+                .as_ssz_bytes()
+            }
+            _ => panic!("Unknown light client type: {type_name}"),
+        }
+    }
+
+    /// Appends a node to the branch, for a proof that is one level deeper.
+    fn deeper_branch<N: Unsigned, M: Unsigned>(
+        branch: FixedVector<B256, N>,
+    ) -> FixedVector<B256, M> {
+        let mut branch = branch.to_vec();
+        branch.push(B256::repeat_byte(0xee));
+        FixedVector::new(branch).unwrap()
+    }
+
     fn assert_str_roundtrip(content_key: BeaconContentKey, content_value: BeaconContentValue) {
         let hex_str = content_value.to_hex();
         assert_eq!(
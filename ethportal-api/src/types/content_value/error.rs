@@ -1,7 +1,12 @@
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::types::network::Subnetwork;
 
+/// The start of the message of [ContentValueError::KeyMismatch].
+pub const CONTENT_KEY_MISMATCH_MESSAGE: &str =
+    "content value doesn't match the type of content key";
+
 /// An error decoding a portal network content value.
 #[derive(Clone, Debug, Error, PartialEq)]
 pub enum ContentValueError {
@@ -36,4 +41,44 @@ pub enum ContentValueError {
         bytes: String,
         subnetwork: Subnetwork,
     },
+    #[error("unable to decode JSON content value from {subnetwork:?} subnetwork: {message}")]
+    DecodeJson {
+        message: String,
+        subnetwork: Subnetwork,
+    },
+    /// The content value is of another type than the content key refers to, e.g. a block body
+    /// for a receipts key.
+    #[error("{CONTENT_KEY_MISMATCH_MESSAGE} {content_key}")]
+    KeyMismatch { content_key: String },
+}
+
+impl ContentValueError {
+    /// Returns the error in the format passed from the subnetwork JSON-RPC handlers to the rpc
+    /// server. A key mismatch is passed as a [ContentKeyMismatchJsonError], so that the rpc server
+    /// reports it as invalid params, and the other errors as their message.
+    pub fn to_json_string(&self) -> String {
+        match self {
+            Self::KeyMismatch { content_key } => serde_json::json!(ContentKeyMismatchJsonError {
+                mismatched_content_key: content_key.clone(),
+            })
+            .to_string(),
+            err => err.to_string(),
+        }
+    }
+}
+
+/// The JSON format of [ContentValueError::KeyMismatch], as passed from the subnetwork JSON-RPC
+/// handlers to the rpc server.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ContentKeyMismatchJsonError {
+    pub mismatched_content_key: String,
+}
+
+impl From<ContentKeyMismatchJsonError> for ContentValueError {
+    fn from(err: ContentKeyMismatchJsonError) -> Self {
+        Self::KeyMismatch {
+            content_key: err.mismatched_content_key,
+        }
+    }
 }
@@ -53,6 +53,16 @@ impl ContentValue for HistoryContentValue {
             subnetwork: Subnetwork::History,
         })
     }
+
+    fn is_valid_for_key(&self, key: &Self::TContentKey) -> bool {
+        match key {
+            HistoryContentKey::BlockHeaderByHash(_) | HistoryContentKey::BlockHeaderByNumber(_) => {
+                matches!(self, Self::BlockHeaderWithProof(_))
+            }
+            HistoryContentKey::BlockBody(_) => matches!(self, Self::BlockBody(_)),
+            HistoryContentKey::BlockReceipts(_) => matches!(self, Self::Receipts(_)),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -62,7 +72,10 @@ mod test {
     use serde_json::Value;
 
     use super::*;
-    use crate::{utils::bytes::hex_decode, HistoryContentValue};
+    use crate::{
+        types::content_value::error::CONTENT_KEY_MISMATCH_MESSAGE, utils::bytes::hex_decode,
+        HistoryContentValue,
+    };
 
     #[test]
     fn header_with_proof_encode_decode_fluffy() {
@@ -85,6 +98,51 @@ mod test {
         }
     }
 
+    #[test]
+    fn is_valid_for_key() {
+        let read_content = |content_key: &HistoryContentKey, file_name: &str| {
+            let bytes = fs::read(format!(
+                "../test_assets/mainnet/large_content/15040641/{file_name}"
+            ))
+            .unwrap();
+            HistoryContentValue::decode(content_key, &bytes).unwrap()
+        };
+        let header_by_hash_key = HistoryContentKey::new_block_header_by_hash([0; 32]);
+        let header_by_number_key = HistoryContentKey::new_block_header_by_number(15040641);
+        let body_key = HistoryContentKey::new_block_body([0; 32]);
+        let receipts_key = HistoryContentKey::new_block_receipts([0; 32]);
+        let header = read_content(&header_by_hash_key, "header.bin");
+        let body = read_content(&body_key, "body.bin");
+        let receipts = read_content(&receipts_key, "receipts.bin");
+
+        // The content values, with the keys that they are valid for
+        for (content_value, valid_keys) in [
+            (&header, vec![&header_by_hash_key, &header_by_number_key]),
+            (&body, vec![&body_key]),
+            (&receipts, vec![&receipts_key]),
+        ] {
+            for content_key in [
+                &header_by_hash_key,
+                &header_by_number_key,
+                &body_key,
+                &receipts_key,
+            ] {
+                assert_eq!(
+                    content_value.is_valid_for_key(content_key),
+                    valid_keys.contains(&content_key),
+                    "{content_value:?} for {content_key}"
+                );
+            }
+        }
+        assert_eq!(
+            receipts
+                .ensure_valid_for_key(&body_key)
+                .unwrap_err()
+                .to_string(),
+            format!("{CONTENT_KEY_MISMATCH_MESSAGE} {}", body_key.to_hex())
+        );
+    }
+
     #[test]
     fn content_value_deserialization_failure_displays_debuggable_data() {
         let key = HistoryContentKey::random().unwrap();
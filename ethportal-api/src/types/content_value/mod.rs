@@ -1,3 +1,6 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
 use crate::{
     utils::bytes::{hex_decode, hex_encode},
     ContentValueError, OverlayContentKey, RawContentValue,
@@ -24,6 +27,20 @@ pub trait ContentValue: Sized {
     /// Decodes `buf` into a content value.
     fn decode(key: &Self::TContentKey, buf: &[u8]) -> Result<Self, ContentValueError>;
 
+    /// Returns whether the content value is of a type that the content key can refer to.
+    fn is_valid_for_key(&self, key: &Self::TContentKey) -> bool;
+
+    /// Returns an error if the content value isn't of a type that the content key can refer to.
+    fn ensure_valid_for_key(&self, key: &Self::TContentKey) -> Result<(), ContentValueError> {
+        if self.is_valid_for_key(key) {
+            Ok(())
+        } else {
+            Err(ContentValueError::KeyMismatch {
+                content_key: key.to_hex(),
+            })
+        }
+    }
+
     /// Encodes the content as "0x"-prefixed hex string.
     fn to_hex(&self) -> String {
         hex_encode(self.encode())
@@ -34,3 +51,57 @@ pub trait ContentValue: Sized {
         Ok(Self::decode(key, &hex_decode(data)?)?)
     }
 }
+
+/// The format of the content value that is passed to the JSON-RPC endpoints.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ContentValueFormat {
+    /// The hex encoded SSZ bytes of the content value.
+    #[default]
+    Ssz,
+    /// The JSON object of the content value, which the node SSZ encodes itself.
+    Json,
+}
+
+/// A content value passed to the JSON-RPC endpoints, in one of the [ContentValueFormat]s.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ContentValueParam {
+    /// The hex encoded SSZ bytes of the content value.
+    Ssz(RawContentValue),
+    /// The JSON object of the content value.
+    Json(Map<String, Value>),
+}
+
+impl ContentValueParam {
+    /// Returns the format of the content value.
+    pub fn format(&self) -> ContentValueFormat {
+        match self {
+            Self::Ssz(_) => ContentValueFormat::Ssz,
+            Self::Json(_) => ContentValueFormat::Json,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn content_value_param_deserializes_either_format() {
+        let param: ContentValueParam = serde_json::from_value(json!("0x0102")).unwrap();
+        assert_eq!(param, ContentValueParam::Ssz(RawContentValue::from([1, 2])));
+        assert_eq!(param.format(), ContentValueFormat::Ssz);
+
+        let value = json!({ "version": "deneb", "data": {} });
+        let param: ContentValueParam = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(param.format(), ContentValueFormat::Json);
+        assert_eq!(serde_json::to_value(param).unwrap(), value);
+
+        assert!(serde_json::from_value::<ContentValueParam>(json!("0xzz")).is_err());
+        assert!(serde_json::from_value::<ContentValueParam>(json!([1, 2])).is_err());
+    }
+}
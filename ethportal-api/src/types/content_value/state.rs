@@ -71,6 +71,24 @@ impl ContentValue for StateContentValue {
             subnetwork: Subnetwork::State,
         })
     }
+
+    fn is_valid_for_key(&self, key: &Self::TContentKey) -> bool {
+        match key {
+            StateContentKey::AccountTrieNode(_) => {
+                matches!(self, Self::TrieNode(_) | Self::AccountTrieNodeWithProof(_))
+            }
+            StateContentKey::ContractStorageTrieNode(_) => matches!(
+                self,
+                Self::TrieNode(_) | Self::ContractStorageTrieNodeWithProof(_)
+            ),
+            StateContentKey::ContractBytecode(_) => {
+                matches!(
+                    self,
+                    Self::ContractBytecode(_) | Self::ContractBytecodeWithProof(_)
+                )
+            }
+        }
+    }
 }
 
 /// A content value type, used when retrieving a trie node.
@@ -272,6 +290,42 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn is_valid_for_key() -> Result<()> {
+        let read_key = |filename: &str| -> Result<StateContentKey> {
+            Ok(StateContentKey::deserialize(
+                &read_yaml_file(filename)?["content_key"],
+            )?)
+        };
+        let account_key = read_key("account_trie_node_key.yaml")?;
+        let storage_key = read_key("contract_storage_trie_node_key.yaml")?;
+        let bytecode_key = read_key("contract_bytecode_key.yaml")?;
+
+        // The content values, with the keys that they are valid for
+        for (value_filename, valid_keys) in [
+            ("trie_node.yaml", vec![&account_key, &storage_key]),
+            ("account_trie_node_with_proof.yaml", vec![&account_key]),
+            (
+                "contract_storage_trie_node_with_proof.yaml",
+                vec![&storage_key],
+            ),
+            ("contract_bytecode.yaml", vec![&bytecode_key]),
+            ("contract_bytecode_with_proof.yaml", vec![&bytecode_key]),
+        ] {
+            let value = read_yaml_file(value_filename)?;
+            let content_value_bytes = RawContentValue::deserialize(&value["content_value"])?;
+            let content_value = StateContentValue::decode(valid_keys[0], &content_value_bytes)?;
+            for key in [&account_key, &storage_key, &bytecode_key] {
+                assert_eq!(
+                    content_value.is_valid_for_key(key),
+                    valid_keys.contains(&key),
+                    "{value_filename} for {key}"
+                );
+            }
+        }
+        Ok(())
+    }
+
     fn read_yaml_file(filename: &str) -> Result<Value> {
         let path = PathBuf::from(TEST_DATA_DIRECTORY).join(filename);
         let file = read_file_from_tests_submodule(path)?;
@@ -25,6 +25,9 @@ pub struct NodeInfo {
     pub enr: Enr,
     pub node_id: NodeId,
     pub ip: Option<String>,
+    /// The addresses of the ENR as multiaddrs. Only included on request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multiaddrs: Option<Vec<String>>,
 }
 
 /// Information about a discv5/overlay network's routing table.
@@ -33,6 +36,98 @@ pub struct NodeInfo {
 pub struct RoutingTableInfo {
     pub local_node_id: NodeId,
     pub buckets: KBucketsTable,
+    /// The protocol version negotiated with each peer in the table. Only overlay networks
+    /// negotiate protocol versions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub protocol_versions: Vec<PeerProtocolVersion>,
+    /// The peers in the table that are disconnected, e.g. because they failed too many liveness
+    /// pings. They are evicted once a newly connected peer needs their place. Only overlay
+    /// networks track the connection state of peers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disconnected_peers: Vec<NodeId>,
+}
+
+/// The protocol version negotiated with a peer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerProtocolVersion {
+    pub node_id: NodeId,
+    pub protocol_version: u8,
+}
+
+/// The nodes in the replacement cache of a bucket, waiting to replace disconnected nodes in the
+/// bucket.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ReplacementBucket {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub enrs: Vec<Enr>,
+}
+
+/// Represents the replacement caches of all buckets in a kbuckets table
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ReplacementCache {
+    pub buckets: Vec<ReplacementBucket>,
+}
+
+/// Information about the replacement cache of an overlay network's routing table.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacementCacheInfo {
+    pub local_node_id: NodeId,
+    pub buckets: ReplacementCache,
+}
+
+/// How densely the buckets of an overlay network's routing table cover the node id space.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyspaceDensityInfo {
+    pub local_node_id: NodeId,
+    /// The number of nodes in the network, estimated from the peers in the buckets that aren't
+    /// full
+    pub estimated_network_size: u64,
+    /// The buckets in which peers are known or expected, by increasing log2 distance
+    pub buckets: Vec<BucketDensityInfo>,
+}
+
+/// How densely a bucket covers the node ids at its log2 distance from the local node.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketDensityInfo {
+    pub log2_distance: u16,
+    /// The number of peers in the bucket
+    pub peer_count: u64,
+    /// The fraction of the node id space that is at the log2 distance
+    pub keyspace_fraction: f64,
+    /// The number of peers that the bucket would have if it knew all nodes at the log2 distance,
+    /// up to the bucket capacity
+    pub expected_peers: f64,
+    /// The number of peers relative to the expected number
+    pub density: f64,
+    /// Whether the bucket knows too few of the nodes at the log2 distance, i.e. the node is blind
+    /// to that region of the node id space
+    pub sparse: bool,
+}
+
+/// When the buckets of an overlay network's routing table were last refreshed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketRefreshTimesInfo {
+    pub local_node_id: NodeId,
+    /// The buckets that have peers or were refreshed, by increasing log2 distance
+    pub buckets: Vec<BucketRefreshInfo>,
+}
+
+/// When a bucket was last refreshed, by a FINDNODE lookup of a random node id at its distance.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketRefreshInfo {
+    pub log2_distance: u16,
+    /// The number of peers in the bucket
+    pub peer_count: u64,
+    /// Seconds since the last refresh of the bucket, or `None` if it was never refreshed
+    pub last_refresh_secs_ago: Option<u64>,
 }
 
 impl<TVal: Eq> From<discv5::kbucket::KBucketsTable<NodeId, TVal>> for KBucketsTable {
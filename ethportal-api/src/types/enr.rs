@@ -1,4 +1,5 @@
 use std::{
+    fmt,
     net::Ipv4Addr,
     ops::{Deref, DerefMut},
     str::FromStr,
@@ -7,9 +8,10 @@ use std::{
 use discv5::enr::{CombinedKey, Enr as Discv5Enr};
 use rand::Rng;
 use rlp::Encodable;
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
 use ssz::DecodeError;
+use thiserror::Error;
 use validator::ValidationError;
 
 pub type Enr = Discv5Enr<CombinedKey>;
@@ -84,6 +86,110 @@ impl ssz::Encode for SszEnr {
     }
 }
 
+/// An ENR passed as a JSON-RPC parameter.
+///
+/// Parsing is more tolerant than for [Enr]: surrounding whitespace is ignored and the `enr:` prefix
+/// is optional. Inputs that are something other than an ENR, like an enode URL or a node id, fail
+/// with an error that says so.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RpcEnr(pub Enr);
+
+/// An error parsing an [RpcEnr].
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum RpcEnrError {
+    #[error("ENR is empty")]
+    Empty,
+    #[error("This looks like an enode URL, portal requires an ENR")]
+    Enode,
+    #[error("This is a node id, use LookupEnr first to find the ENR of the node")]
+    NodeId,
+    #[error("Invalid ENR: {0}")]
+    Invalid(String),
+}
+
+impl FromStr for RpcEnr {
+    type Err = RpcEnrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(RpcEnrError::Empty);
+        }
+        if s.starts_with("enode://") {
+            return Err(RpcEnrError::Enode);
+        }
+        let maybe_node_id = s.strip_prefix("0x").unwrap_or(s);
+        if maybe_node_id.len() == 64 && maybe_node_id.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(RpcEnrError::NodeId);
+        }
+        Enr::from_str(s.strip_prefix("enr:").unwrap_or(s))
+            .map(Self)
+            .map_err(RpcEnrError::Invalid)
+    }
+}
+
+impl fmt::Display for RpcEnr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for RpcEnr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RpcEnr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+impl From<Enr> for RpcEnr {
+    fn from(enr: Enr) -> Self {
+        Self(enr)
+    }
+}
+
+impl From<RpcEnr> for Enr {
+    fn from(rpc_enr: RpcEnr) -> Self {
+        rpc_enr.0
+    }
+}
+
+impl Deref for RpcEnr {
+    type Target = Enr;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Returns the multiaddrs of the addresses in the ENR, e.g. `/ip4/10.0.0.1/udp/9009`, for
+/// interop with tools that address peers by multiaddr.
+pub fn enr_multiaddrs(enr: &Enr) -> Vec<String> {
+    let mut multiaddrs = vec![];
+    if let Some(ip) = enr.ip4() {
+        if let Some(port) = enr.udp4() {
+            multiaddrs.push(format!("/ip4/{ip}/udp/{port}"));
+        }
+        if let Some(port) = enr.tcp4() {
+            multiaddrs.push(format!("/ip4/{ip}/tcp/{port}"));
+        }
+    }
+    if let Some(ip) = enr.ip6() {
+        if let Some(port) = enr.udp6() {
+            multiaddrs.push(format!("/ip6/{ip}/udp/{port}"));
+        }
+        if let Some(port) = enr.tcp6() {
+            multiaddrs.push(format!("/ip6/{ip}/tcp/{port}"));
+        }
+    }
+    multiaddrs
+}
+
 pub fn generate_random_remote_enr() -> (CombinedKey, Enr) {
     let key = CombinedKey::generate_secp256k1();
 
@@ -104,11 +210,89 @@ mod test {
     use discv5::enr::NodeId;
     use test_log::test;
 
+    use super::*;
     use crate::{
         generate_random_node_id,
         types::distance::{Metric, XorMetric},
     };
 
+    fn parse_rpc_enr(param: &str) -> Result<RpcEnr, String> {
+        serde_json::from_value(Value::String(param.to_string())).map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn rpc_enr_accepts_strict_form() {
+        let (_, enr) = generate_random_remote_enr();
+        assert_eq!(parse_rpc_enr(&enr.to_base64()).unwrap().0, enr);
+    }
+
+    #[test]
+    fn rpc_enr_is_tolerant() {
+        let (_, enr) = generate_random_remote_enr();
+        let base64 = enr.to_base64();
+        let without_prefix = base64.strip_prefix("enr:").unwrap();
+        for param in [
+            format!("  {base64}\n"),
+            without_prefix.to_string(),
+            format!("\t{without_prefix} "),
+        ] {
+            assert_eq!(parse_rpc_enr(&param).unwrap().0, enr, "param: {param:?}");
+        }
+    }
+
+    #[test]
+    fn rpc_enr_explains_malformed_input() {
+        let node_id = NodeId::random();
+        let cases = [
+            ("", RpcEnrError::Empty),
+            ("  ", RpcEnrError::Empty),
+            (
+                "enode://6f8a80d14311c39f35f516fa664deaaaa13e85b2f7493f37f6144d86991ec012937307647bd3b9a82abe2974e1407241d54947bbb39763a4cac9f77166ad92a0@10.3.58.6:30303",
+                RpcEnrError::Enode,
+            ),
+            (&hex::encode(node_id.raw()), RpcEnrError::NodeId),
+            (&format!("0x{}", hex::encode(node_id.raw())), RpcEnrError::NodeId),
+        ];
+        for (param, expected) in cases {
+            assert_eq!(parse_rpc_enr(param).unwrap_err(), expected.to_string());
+        }
+        let err = parse_rpc_enr("enr:not-an-enr").unwrap_err();
+        assert!(err.starts_with("Invalid ENR: "), "{err}");
+    }
+
+    #[test]
+    fn rpc_enr_serializes_as_enr() {
+        let (_, enr) = generate_random_remote_enr();
+        assert_eq!(
+            serde_json::to_value(RpcEnr(enr.clone())).unwrap(),
+            serde_json::to_value(enr).unwrap()
+        );
+    }
+
+    #[test]
+    fn enr_multiaddrs_of_each_address() {
+        let key = CombinedKey::generate_secp256k1();
+        let enr = Discv5Enr::builder()
+            .ip4(Ipv4Addr::new(10, 0, 0, 1))
+            .udp4(9009)
+            .tcp4(30303)
+            .ip6("::1".parse().unwrap())
+            .udp6(9010)
+            .build(&key)
+            .unwrap();
+        assert_eq!(
+            enr_multiaddrs(&enr),
+            vec![
+                "/ip4/10.0.0.1/udp/9009",
+                "/ip4/10.0.0.1/tcp/30303",
+                "/ip6/::1/udp/9010"
+            ]
+        );
+
+        let enr = Discv5Enr::builder().udp4(9009).build(&key).unwrap();
+        assert!(enr_multiaddrs(&enr).is_empty());
+    }
+
     #[test]
     fn test_generate_random_node_id_1() {
         let target_bucket_idx: u8 = 5;
@@ -41,12 +41,14 @@ impl Encodable for BlockBody {
 }
 
 impl Decodable for BlockBody {
+    /// Decodes a body with withdrawals as Shanghai, and a body without them as Legacy.
+    ///
+    /// Merge bodies are encoded like legacy bodies without uncles, so the variant can't be told
+    /// from the encoding alone, and pre-merge bodies without uncles must stay Legacy.
     fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
-        if let Ok(val) = BlockBodyLegacy::decode(buf) {
+        if let Some(val) = rlp_decode_variant::<BlockBodyLegacy>(buf) {
             Ok(BlockBody::Legacy(val))
-        } else if let Ok(val) = BlockBodyMerge::decode(buf) {
-            Ok(BlockBody::Merge(val))
-        } else if let Ok(val) = BlockBodyShanghai::decode(buf) {
+        } else if let Some(val) = rlp_decode_variant::<BlockBodyShanghai>(buf) {
             Ok(BlockBody::Shanghai(val))
         } else {
             Err(rlp::Error::Custom("Invalid block body rlp"))
@@ -54,6 +56,14 @@ impl Decodable for BlockBody {
     }
 }
 
+/// Decodes a block body variant from a copy of the input, only advancing the input on success.
+fn rlp_decode_variant<T: Decodable>(buf: &mut &[u8]) -> Option<T> {
+    let mut variant_buf = *buf;
+    let val = T::decode(&mut variant_buf).ok()?;
+    *buf = variant_buf;
+    Some(val)
+}
+
 impl ssz::Decode for BlockBody {
     fn is_ssz_fixed_len() -> bool {
         false
@@ -63,11 +73,10 @@ impl ssz::Decode for BlockBody {
         0
     }
 
+    // Same variant selection as rlp decoding.
     fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
         if let Ok(val) = BlockBodyLegacy::from_ssz_bytes(bytes) {
             Ok(BlockBody::Legacy(val))
-        } else if let Ok(val) = BlockBodyMerge::from_ssz_bytes(bytes) {
-            Ok(BlockBody::Merge(val))
         } else if let Ok(val) = BlockBodyShanghai::from_ssz_bytes(bytes) {
             Ok(BlockBody::Shanghai(val))
         } else {
@@ -96,18 +105,19 @@ impl BlockBody {
                 header.transactions_root
             );
         }
-        if let Self::Shanghai(_) = self {
-            // Validate withdrawals root
-            let actual = self.withdrawals_root()?;
-            let expected = match header.withdrawals_root {
-                Some(val) => val,
-                None => bail!("Block header does not have withdrawals root"),
-            };
-            if actual != expected {
-                bail!(
-                    "Block body withdrawals root doesn't match header withdrawals root: {actual:?} - {expected:?}",
-                );
+        // Validate withdrawals root
+        match (self.withdrawals(), header.withdrawals_root) {
+            (Some(_), Some(expected)) => {
+                let actual = self.withdrawals_root()?;
+                if actual != expected {
+                    bail!(
+                        "Block body withdrawals root doesn't match header withdrawals root: {actual:?} - {expected:?}",
+                    );
+                }
             }
+            (Some(_), None) => bail!("Block header does not have withdrawals root"),
+            (None, Some(_)) => bail!("Block body does not have withdrawals"),
+            (None, None) => {}
         }
         Ok(())
     }
@@ -170,6 +180,17 @@ fn rlp_decode_transaction_list_with_header(buf: &mut &[u8]) -> rlp::Result<Vec<T
     Ok(txs.into_iter().map(|tx| tx.0).collect())
 }
 
+/// Decodes the uncles of a post-merge block body, which are expected to be empty.
+fn rlp_decode_empty_uncles(buf: &mut &[u8]) -> rlp::Result<()> {
+    let uncles = Vec::<Header>::decode(buf)?;
+    if !uncles.is_empty() {
+        return Err(rlp::Error::Custom(
+            "Post-merge block body should not have uncles",
+        ));
+    }
+    Ok(())
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
 pub struct BlockBodyLegacy {
     pub txs: Vec<Transaction>,
@@ -269,6 +290,7 @@ impl Encodable for BlockBodyMerge {
     fn encode(&self, out: &mut dyn bytes::BufMut) {
         let mut list = Vec::<u8>::new();
         rlp_encode_transaction_list_with_header(&mut list, &self.txs);
+        Vec::<Header>::new().encode(&mut list);
         let header = rlp::Header {
             list: true,
             payload_length: list.len(),
@@ -284,6 +306,7 @@ impl Decodable for BlockBodyMerge {
         let payload_length = payload_view.remaining();
 
         let txs = rlp_decode_transaction_list_with_header(&mut payload_view)?;
+        rlp_decode_empty_uncles(&mut payload_view)?;
 
         if payload_view.has_remaining() {
             let consumed = payload_length - payload_view.remaining();
@@ -364,6 +387,7 @@ impl Encodable for BlockBodyShanghai {
     fn encode(&self, out: &mut dyn bytes::BufMut) {
         let mut list = Vec::<u8>::new();
         rlp_encode_transaction_list_with_header(&mut list, &self.txs);
+        Vec::<Header>::new().encode(&mut list);
         self.withdrawals.encode(&mut list);
         let header = rlp::Header {
             list: true,
@@ -380,6 +404,7 @@ impl Decodable for BlockBodyShanghai {
         let payload_length = payload_view.remaining();
 
         let txs = rlp_decode_transaction_list_with_header(&mut payload_view)?;
+        rlp_decode_empty_uncles(&mut payload_view)?;
         let withdrawals = Vec::<Withdrawal>::decode(&mut payload_view)?;
 
         if payload_view.has_remaining() {
@@ -476,7 +501,13 @@ mod tests {
     use ssz::{Decode, Encode};
 
     use super::*;
-    use crate::utils::bytes::{hex_decode, hex_encode};
+    use crate::{
+        types::{
+            consensus::beacon_block::SignedBeaconBlockBellatrix,
+            execution::header_with_proof::HeaderWithProof,
+        },
+        utils::bytes::{hex_decode, hex_encode},
+    };
 
     // tx data from: https://etherscan.io/txs?block=14764013
     #[rstest]
@@ -635,6 +666,171 @@ mod tests {
         // and without the typed transactions seems like a good start for now.
     }
 
+    #[test_log::test]
+    fn merge_block_body_round_trip() {
+        // block 15537397
+        let raw = std::fs::read(
+            "../test_assets/beacon/bellatrix/ValidSignedBeaconBlock/signed_beacon_block_15537397.ssz",
+        )
+        .unwrap();
+        let beacon_block = SignedBeaconBlockBellatrix::from_ssz_bytes(&raw).unwrap();
+        let txs: Vec<Transaction> = beacon_block
+            .message
+            .body
+            .execution_payload
+            .transactions
+            .iter()
+            .map(|tx| Decodable::decode(&mut tx.to_vec().as_slice()))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let block_body = BlockBody::Merge(BlockBodyMerge { txs });
+        assert_eq!(
+            hex_encode(block_body.transactions_root().unwrap()),
+            "0x165a029503ae62a153a3b9589a09db72646749266b2b5fa9ba3198eef453e670"
+        );
+        assert_eq!(
+            hex_encode(block_body.uncles_root()),
+            "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347"
+        );
+        // merge bodies are encoded like legacy bodies without uncles, and decoded as such
+        let legacy_body = BlockBody::Legacy(BlockBodyLegacy {
+            txs: block_body.transactions().to_vec(),
+            uncles: vec![],
+        });
+        assert_eq!(block_body.as_ssz_bytes(), legacy_body.as_ssz_bytes());
+        assert_eq!(
+            alloy::rlp::encode(&block_body),
+            alloy::rlp::encode(&legacy_body)
+        );
+        assert_round_trip(&legacy_body);
+    }
+
+    #[test_log::test]
+    fn pre_merge_block_body_without_uncles_is_legacy() {
+        // block 15040641
+        let raw = std::fs::read("../test_assets/mainnet/large_content/15040641/body.bin").unwrap();
+        let block_body = BlockBody::from_ssz_bytes(&raw).unwrap();
+        assert!(matches!(block_body, BlockBody::Legacy(_)));
+        assert!(block_body.uncles().is_empty());
+        let header =
+            std::fs::read("../test_assets/mainnet/large_content/15040641/header.bin").unwrap();
+        let header = HeaderWithProof::from_ssz_bytes(&header).unwrap().header;
+        block_body.validate_against_header(&header).unwrap();
+        assert_round_trip(&block_body);
+    }
+
+    #[rstest]
+    // shanghai
+    #[case("17034871")]
+    // shanghai without withdrawals
+    #[case("17034873")]
+    // cancun without blob txs
+    #[case("19433902")]
+    // cancun with blob txs
+    #[case("19433903")]
+    fn post_shanghai_block_body(#[case] block_number: &str) {
+        let (header, block_body) = get_block_from_json(block_number);
+        assert!(matches!(block_body, BlockBody::Shanghai(_)));
+        block_body.validate_against_header(&header).unwrap();
+        assert_round_trip(&block_body);
+    }
+
+    #[test_log::test]
+    fn block_body_invalidates_withdrawals_root() {
+        let (header, block_body) = get_block_from_json("19433903");
+        let BlockBody::Shanghai(mut body) = block_body else {
+            panic!("Expected a Shanghai block body");
+        };
+        body.withdrawals[0].amount += 1;
+        let err = BlockBody::Shanghai(body)
+            .validate_against_header(&header)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Block body withdrawals root doesn't match header withdrawals root"));
+    }
+
+    #[test_log::test]
+    fn block_body_without_withdrawals_is_invalid_post_shanghai() {
+        let (header, block_body) = get_block_from_json("17034871");
+        let block_body = BlockBody::Merge(BlockBodyMerge {
+            txs: block_body.transactions().to_vec(),
+        });
+        let err = block_body.validate_against_header(&header).unwrap_err();
+        assert_eq!(err.to_string(), "Block body does not have withdrawals");
+    }
+
+    #[test_log::test]
+    fn rlp_decoding_rejects_post_merge_uncles() {
+        let (_, block_body) = get_block_from_json("17034871");
+        let mut list = Vec::<u8>::new();
+        rlp_encode_transaction_list_with_header(&mut list, block_body.transactions());
+        get_14764013_block_body()
+            .uncles()
+            .to_vec()
+            .encode(&mut list);
+        block_body.withdrawals().unwrap().to_vec().encode(&mut list);
+        let mut encoded = Vec::<u8>::new();
+        rlp::Header {
+            list: true,
+            payload_length: list.len(),
+        }
+        .encode(&mut encoded);
+        encoded.extend_from_slice(&list);
+        assert!(BlockBodyShanghai::decode(&mut encoded.as_slice()).is_err());
+    }
+
+    #[test_log::test]
+    fn legacy_block_body_round_trip() {
+        assert_round_trip(&get_14764013_block_body());
+    }
+
+    #[test_log::test]
+    fn rlp_decoding_only_consumes_the_block_body() {
+        let (_, block_body) = get_block_from_json("17034871");
+        let mut encoded = alloy::rlp::encode(&block_body);
+        encoded.extend_from_slice(&[0x01, 0x02]);
+        let mut buf = encoded.as_slice();
+        assert_eq!(BlockBody::decode(&mut buf).unwrap(), block_body);
+        assert_eq!(buf, [0x01, 0x02]);
+    }
+
+    /// Asserts that the block body is decoded to the same variant and re-encoded to the same
+    /// bytes, in both ssz and rlp.
+    fn assert_round_trip(block_body: &BlockBody) {
+        let encoded = block_body.as_ssz_bytes();
+        let decoded = BlockBody::from_ssz_bytes(&encoded).unwrap();
+        assert_eq!(block_body, &decoded);
+        assert_eq!(hex_encode(&encoded), hex_encode(decoded.as_ssz_bytes()));
+
+        let encoded = alloy::rlp::encode(block_body);
+        let mut buf = encoded.as_slice();
+        let decoded = BlockBody::decode(&mut buf).unwrap();
+        assert!(buf.is_empty());
+        assert_eq!(block_body, &decoded);
+        assert_eq!(
+            hex_encode(&encoded),
+            hex_encode(alloy::rlp::encode(decoded))
+        );
+    }
+
+    /// Returns the header and block body of the mainnet block, from its `eth_getBlockByNumber`
+    /// response.
+    fn get_block_from_json(block_number: &str) -> (Header, BlockBody) {
+        let response = std::fs::read_to_string(format!(
+            "../test_assets/mainnet/block_{block_number}_value.json"
+        ))
+        .unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        let block = &response["result"];
+        let header: Header = serde_json::from_value(block.clone()).unwrap();
+        let block_body = BlockBody::Shanghai(BlockBodyShanghai {
+            txs: serde_json::from_value(block["transactions"].clone()).unwrap(),
+            withdrawals: serde_json::from_value(block["withdrawals"].clone()).unwrap(),
+        });
+        (header, block_body)
+    }
+
     fn shanghai_withdrawals() -> String {
         r#"[{"index":"0x196f2d","validatorIndex":"0x771aa","address":"0x2c885c22321746ab958980a5d060be90cd3fa79b","amount":"0xbc501e"},{"index":"0x196f2e","validatorIndex":"0x771ab","address":"0xa578c8a6fbddbdff3646ea05a7998bb251c2e972","amount":"0xbcc397"},{"index":"0x196f2f","validatorIndex":"0x771ac","address":"0xa578c8a6fbddbdff3646ea05a7998bb251c2e972","amount":"0xbd2f54"},{"index":"0x196f30","validatorIndex":"0x771ad","address":"0xa578c8a6fbddbdff3646ea05a7998bb251c2e972","amount":"0xbc2316"},{"index":"0x196f31","validatorIndex":"0x771ae","address":"0xa578c8a6fbddbdff3646ea05a7998bb251c2e972","amount":"0xbb358a"},{"index":"0x196f32","validatorIndex":"0x771af","address":"0xa578c8a6fbddbdff3646ea05a7998bb251c2e972","amount":"0xbc4c7e"},{"index":"0x196f33","validatorIndex":"0x771b0","address":"0xa578c8a6fbddbdff3646ea05a7998bb251c2e972","amount":"0xbce826"},{"index":"0x196f34","validatorIndex":"0x771b1","address":"0x2c885c22321746ab958980a5d060be90cd3fa79b","amount":"0xbcb59b"},{"index":"0x196f35","validatorIndex":"0x771b2","address":"0xa578c8a6fbddbdff3646ea05a7998bb251c2e972","amount":"0xbca420"},{"index":"0x196f36","validatorIndex":"0x771b3","address":"0xa578c8a6fbddbdff3646ea05a7998bb251c2e972","amount":"0xbc1dd0"},{"index":"0x196f37","validatorIndex":"0x771b4","address":"0x2c885c22321746ab958980a5d060be90cd3fa79b","amount":"0xbbe0da"},{"index":"0x196f38","validatorIndex":"0x771b5","address":"0xa578c8a6fbddbdff3646ea05a7998bb251c2e972","amount":"0xbb714c"},{"index":"0x196f39","validatorIndex":"0x771b6","address":"0x2c885c22321746ab958980a5d060be90cd3fa79b","amount":"0xbc3c1d"},{"index":"0x196f3a","validatorIndex":"0x771b7","address":"0xa578c8a6fbddbdff3646ea05a7998bb251c2e972","amount":"0xbc2726"},{"index":"0x196f3b","validatorIndex":"0x771b8","address":"0xa1c52afa77d87796b8cd34f4801e062fb54e7df6","amount":"0xad94c3"},{"index":"0x196f3c","validatorIndex":"0x771b9","address":"0xa578c8a6fbddbdff3646ea05a7998bb251c2e972","amount":"0xbad3ed"}]"#.to_string()
     }
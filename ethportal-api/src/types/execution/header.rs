@@ -509,5 +509,15 @@ mod tests {
         let expected_hash =
             B256::from_slice(&hex_decode(response["result"]["hash"].as_str().unwrap()).unwrap());
         assert_eq!(header.hash(), expected_hash);
+        assert!(header.withdrawals_root.is_some());
+        assert!(header.blob_gas_used.is_some());
+        assert!(header.excess_blob_gas.is_some());
+        assert!(header.parent_beacon_block_root.is_some());
+
+        // test rlp roundtrip
+        let encoded = rlp::encode(&header);
+        let decoded: Header = Decodable::decode(&mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded, header);
+        assert_eq!(rlp::encode(&decoded), encoded);
     }
 }
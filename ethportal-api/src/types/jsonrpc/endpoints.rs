@@ -1,8 +1,16 @@
+use std::time::Duration;
+
+use alloy::primitives::{Bytes, B256};
 use discv5::enr::NodeId;
 
 use crate::{
-    types::enr::Enr, BeaconContentKey, BeaconContentValue, HistoryContentKey, HistoryContentValue,
-    StateContentKey, StateContentValue,
+    types::{
+        enr::Enr,
+        portal::{ContentProvenance, ValidationMode},
+        query_trace::QueryTrace,
+    },
+    BeaconContentKey, BeaconContentValue, HistoryContentKey, HistoryContentValue, StateContentKey,
+    StateContentValue,
 };
 
 /// Discv5 JSON-RPC endpoints. Start with "discv5_" prefix
@@ -19,59 +27,96 @@ pub enum StateEndpoint {
     RoutingTableInfo,
     /// params: [enr]
     Ping(Enr),
-    /// params: [enr]
-    AddEnr(Enr),
+    /// params: [enr, allow_unreachable]
+    AddEnr(Enr, bool),
     /// params: [node_id]
     DeleteEnr(NodeId),
     /// params: [node_id]
     GetEnr(NodeId),
     /// params: [node_id]
+    GetEnrInfo(NodeId),
+    /// params: [node_id]
     LookupEnr(NodeId),
-    /// params: [enr, distances]
-    FindNodes(Enr, Vec<u16>),
+    /// params: [enr, distances, max_results]
+    FindNodes(Enr, Vec<u16>, Option<u64>),
     /// params: [node_id]
     RecursiveFindNodes(NodeId),
     /// params: None
     DataRadius,
+    /// params: [content_key, include_meta, include_rank]
+    LocalContent(StateContentKey, bool, bool),
     /// params: content_key
-    LocalContent(StateContentKey),
+    ContentProviders(StateContentKey),
+    /// params: sample_size
+    SimulateIncomingOffer(u64),
     /// params: [enr, content_key]
     FindContent(Enr, StateContentKey),
+    /// params: [enr, content_key, ignore_radius]
+    FetchAndStore(Enr, StateContentKey, bool),
     /// params: content_key
     GetContent(StateContentKey),
     /// params: content_key
     TraceGetContent(StateContentKey),
-    /// params: [content_key, content_value]
-    Store(StateContentKey, StateContentValue),
+    /// params: [content_key, content_value, source, signature]
+    Store(
+        StateContentKey,
+        StateContentValue,
+        Option<String>,
+        Option<Bytes>,
+    ),
+    /// params: source
+    DeleteContentBySource(String),
     /// params: [enr, Vec<(content_key, content_value>)]
     Offer(Enr, Vec<(StateContentKey, StateContentValue)>),
+    /// params: [enr, Vec<(content_key, content_value)>]
+    OfferMany(Enr, Vec<(StateContentKey, StateContentValue)>),
     /// params: [enr, content_key, content_value]
     TraceOffer(Enr, StateContentKey, StateContentValue),
     /// params: [enr, content_key, content_value]
     Gossip(StateContentKey, StateContentValue),
     /// params: [content_key, content_value]
     TraceGossip(StateContentKey, StateContentValue),
-    /// params: [offset, limit]
-    PaginateLocalContentKeys(u64, u64),
+    /// params: [offset, limit, provenance, source]
+    PaginateLocalContentKeys(u64, u64, Option<ContentProvenance>, Option<String>),
+    /// params: [cursor, limit, provenance, source]
+    PaginateLocalContentKeysAfter(Option<B256>, u64, Option<ContentProvenance>, Option<String>),
+    /// params: [after, limit]
+    ExportContentBatch(Option<B256>, u64),
+    /// params: Vec<(content_key, content_value)>
+    ImportContent(Vec<(StateContentKey, StateContentValue)>),
+    /// params: None
+    ClearLookupCache,
 }
 
 /// History network JSON-RPC endpoints. Start with "portal_history" prefix
 #[derive(Debug, PartialEq, Clone)]
 pub enum HistoryEndpoint {
-    /// params: [enr]
-    AddEnr(Enr),
+    /// params: [enr, allow_unreachable]
+    AddEnr(Enr, bool),
     /// params: None
     DataRadius,
     /// params: [node_id]
     DeleteEnr(NodeId),
     /// params: [enr, content_key]
     FindContent(Enr, HistoryContentKey),
-    /// params: [enr, distances]
-    FindNodes(Enr, Vec<u16>),
+    /// params: [enr, content_key, ignore_radius]
+    FetchAndStore(Enr, HistoryContentKey, bool),
+    /// params: [enr, distances, max_results]
+    FindNodes(Enr, Vec<u16>, Option<u64>),
     /// params: [node_id]
     GetEnr(NodeId),
+    /// params: [node_id]
+    GetEnrInfo(NodeId),
+    /// params: [content_key, include_meta, include_rank]
+    LocalContent(HistoryContentKey, bool, bool),
     /// params: content_key
-    LocalContent(HistoryContentKey),
+    ContentProviders(HistoryContentKey),
+    /// params: sample_size
+    SimulateIncomingOffer(u64),
+    /// params: None
+    ContentSummary,
+    /// params: header_with_proof
+    VerifyHeader(RawContentValue),
     /// params: [node_id]
     LookupEnr(NodeId),
     /// params: [content_key, content_value]
@@ -80,6 +125,8 @@ pub enum HistoryEndpoint {
     TraceGossip(HistoryContentKey, HistoryContentValue),
     /// params: [enr, Vec<(content_key, content_value)>]
     Offer(Enr, Vec<(HistoryContentKey, HistoryContentValue)>),
+    /// params: [enr, Vec<(content_key, content_value)>]
+    OfferMany(Enr, Vec<(HistoryContentKey, HistoryContentValue)>),
     /// params: [enr, content_key, content_value]
     TraceOffer(Enr, HistoryContentKey, HistoryContentValue),
     /// params: [enr]
@@ -88,22 +135,37 @@ pub enum HistoryEndpoint {
     GetContent(HistoryContentKey),
     /// params: content_key
     TraceGetContent(HistoryContentKey),
-    /// params: [content_key, content_value]
-    Store(HistoryContentKey, HistoryContentValue),
+    /// params: [content_key, content_value, source, signature]
+    Store(
+        HistoryContentKey,
+        HistoryContentValue,
+        Option<String>,
+        Option<Bytes>,
+    ),
+    /// params: source
+    DeleteContentBySource(String),
     /// params: None
     RoutingTableInfo,
     // This endpoint is not History network specific
-    /// params: [offset, limit]
-    PaginateLocalContentKeys(u64, u64),
+    /// params: [offset, limit, provenance, source]
+    PaginateLocalContentKeys(u64, u64, Option<ContentProvenance>, Option<String>),
+    /// params: [cursor, limit, provenance, source]
+    PaginateLocalContentKeysAfter(Option<B256>, u64, Option<ContentProvenance>, Option<String>),
+    /// params: [after, limit]
+    ExportContentBatch(Option<B256>, u64),
+    /// params: Vec<(content_key, content_value)>
+    ImportContent(Vec<(HistoryContentKey, HistoryContentValue)>),
     /// params: [node_id]
     RecursiveFindNodes(NodeId),
+    /// params: None
+    ClearLookupCache,
 }
 
 /// Beacon network JSON-RPC endpoints. Start with "portal_beacon" prefix
 #[derive(Debug, PartialEq, Clone)]
 pub enum BeaconEndpoint {
-    /// params: enr
-    AddEnr(Enr),
+    /// params: [enr, allow_unreachable]
+    AddEnr(Enr, bool),
     /// params: None
     DataRadius,
     /// params: node_id
@@ -112,22 +174,32 @@ pub enum BeaconEndpoint {
     OptimisticStateRoot,
     /// params: [enr, content_key]
     FindContent(Enr, BeaconContentKey),
-    /// params: [enr, distances]
-    FindNodes(Enr, Vec<u16>),
+    /// params: [enr, content_key, ignore_radius]
+    FetchAndStore(Enr, BeaconContentKey, bool),
+    /// params: [enr, distances, max_results]
+    FindNodes(Enr, Vec<u16>, Option<u64>),
     /// params: None
     FinalizedHeader,
     /// params: None
     FinalizedStateRoot,
     /// params: None
+    HistoricalSummaries,
+    /// params: None
     FinalityUpdate,
     /// params: None
     OptimisticUpdate,
     /// params: node_id
     GetEnr(NodeId),
+    /// params: node_id
+    GetEnrInfo(NodeId),
+    /// params: [node_id, key]
+    GetEnrField(NodeId, String),
     /// params: None
     LightClientStore,
-    /// params: content_key
-    LocalContent(BeaconContentKey),
+    /// params: None
+    HeaderUpdateTimes,
+    /// params: [content_key, include_meta]
+    LocalContent(BeaconContentKey, bool),
     /// params: node_id
     LookupEnr(NodeId),
     /// params: [content_key, content_value]
@@ -136,22 +208,102 @@ pub enum BeaconEndpoint {
     TraceGossip(BeaconContentKey, BeaconContentValue),
     /// params: [enr, Vec<(content_key, content_value>)]
     Offer(Enr, Vec<(BeaconContentKey, BeaconContentValue)>),
+    /// params: [enr, Vec<(content_key, content_value)>]
+    OfferMany(Enr, Vec<(BeaconContentKey, BeaconContentValue)>),
     /// params: [enr, content_key, content_value]
     TraceOffer(Enr, BeaconContentKey, BeaconContentValue),
+    /// params: [enr, content_key, content_value]
+    OfferAndVerify(Enr, BeaconContentKey, BeaconContentValue),
     /// params: enr
     Ping(Enr),
+    /// params: enr
+    PingRaw(Enr),
+    /// params: [enr, protocol_id, payload]
+    TalkReq(Enr, Bytes, Bytes),
     /// params: content_key
     GetContent(BeaconContentKey),
     /// params: content_key
     TraceGetContent(BeaconContentKey),
-    /// params: [content_key, content_value]
-    Store(BeaconContentKey, BeaconContentValue),
+    /// params: [content_key, timeout]
+    GetContentOrClosest(BeaconContentKey, Option<Duration>),
+    /// params: [content_key, content_value, signature]
+    Store(BeaconContentKey, BeaconContentValue, Option<Bytes>),
     /// params: None
     RoutingTableInfo,
+    /// params: None
+    ReplacementCache,
+    /// params: None
+    KeyspaceDensity,
+    /// params: None
+    BucketRefreshTimes,
     /// params: [offset, limit]
     PaginateLocalContentKeys(u64, u64),
     /// params: [node_id]
     RecursiveFindNodes(NodeId),
+    /// params: bytes_per_sec
+    SetBandwidthLimit(Option<u64>),
+    /// params: None
+    GetUtpLimit,
+    /// params: limit
+    SetUtpLimit(u32),
+    /// params: [node_id, connection_id]
+    CloseTransfer(NodeId, u16),
+    /// params: [limit, evict]
+    VerifyPeers(u64, bool),
+    /// params: limit
+    RecentOfferers(u64),
+    /// params: None
+    StorageInfo,
+    /// params: None
+    ContentTypeBreakdown,
+    /// params: redact_path
+    StorageBackendInfo(bool),
+    /// params: content_key
+    PruneEstimate(BeaconContentKey),
+    /// params: None
+    StoreLatencyStats,
+    /// params: None
+    RecomputeRadius,
+    /// params: trace
+    AnalyzeTrace(QueryTrace),
+    /// params: content_keys
+    Prefetch(Vec<BeaconContentKey>),
+    /// params: job_id
+    PrefetchStatus(u64),
+    /// params: job_id
+    CancelPrefetch(u64),
+    /// params: None
+    AcceptSnapshot,
+    /// params: None
+    SelfTest,
+    /// params: mode
+    SetValidationMode(ValidationMode),
+    /// params: [content_id, limit]
+    NearestStoredKeys(B256, u64),
+    /// params: [start_id, end_id]
+    RangeCoverage(B256, B256),
+    /// params: None
+    GcOrphans,
+    /// params: content_key
+    PinContent(BeaconContentKey),
+    /// params: content_key
+    UnpinContent(BeaconContentKey),
+    /// params: None
+    MetricsText,
+    /// params: enabled
+    SetGossipEnabled(bool),
+    /// params: None
+    ClearLookupCache,
+    /// params: None
+    ActiveLookups,
+    /// params: content_items
+    ValidateContentBatch(Vec<(BeaconContentKey, RawContentValue)>),
+    /// params: content_key
+    RevalidateDetailed(BeaconContentKey),
+    /// params: [content_key, content_value]
+    InspectContent(BeaconContentKey, RawContentValue),
+    /// params: None
+    NetworkInfo,
 }
 
 /// The common functionality of subnetwork endpoints.
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::sync::mpsc;
+use uuid::Uuid;
 use validator::{Validate, ValidationError};
 
 use super::{
@@ -49,6 +50,20 @@ impl Default for JsonRequest {
 pub struct JsonRpcRequest<T> {
     pub endpoint: T,
     pub resp: Responder<Value, String>,
+    /// Identifies the request in the log lines produced while handling it, and in its error
+    /// response.
+    pub request_id: String,
+}
+
+impl<T> JsonRpcRequest<T> {
+    /// Creates a request with a new random request id.
+    pub fn new(endpoint: T, resp: Responder<Value, String>) -> Self {
+        Self {
+            endpoint,
+            resp,
+            request_id: Uuid::new_v4().to_string(),
+        }
+    }
 }
 
 /// History network JSON-RPC request
@@ -1,9 +1,19 @@
-use alloy::primitives::{Bytes, U256};
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+};
+
+use alloy::primitives::{Bytes, B256, U256};
+use discv5::enr::NodeId;
 use serde::{Deserialize, Serialize};
 use ssz_types::{typenum, BitList};
 
-use super::query_trace::QueryTrace;
-use crate::{types::enr::Enr, OverlayContentKey};
+use super::query_trace::{QueryFailureKind, QueryTrace};
+use crate::{
+    types::{enr::Enr, execution::header_with_proof::BlockHeaderProof},
+    utils::serde::hex_u256,
+    OverlayContentKey,
+};
 
 /// The SSZ encoded representation of content key.
 ///
@@ -50,21 +60,190 @@ pub type Distance = U256;
 
 /// Response for Ping endpoint
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", into = "PongInfoJson")]
 pub struct PongInfo {
     pub enr_seq: u64,
+    /// Serialized as a 0x-prefixed, 32-byte hex string
+    #[serde(with = "hex_u256")]
     pub data_radius: DataRadius,
+    /// Whether the peer has a newer ENR than the one known locally. The newer ENR is then
+    /// requested from the peer, to update its routing table entry.
+    #[serde(default)]
+    pub new_enr_detected: bool,
+}
+
+/// The serialized [PongInfo], which also has the data radius in its previous format.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PongInfoJson {
+    enr_seq: u64,
+    #[serde(with = "hex_u256")]
+    data_radius: DataRadius,
+    /// Deprecated: the data radius as a hex string without leading zeros. Will be removed in the
+    /// next release.
+    data_radius_legacy: DataRadius,
+    new_enr_detected: bool,
+}
+
+impl From<PongInfo> for PongInfoJson {
+    fn from(pong_info: PongInfo) -> Self {
+        Self {
+            enr_seq: pong_info.enr_seq,
+            data_radius: pong_info.data_radius,
+            data_radius_legacy: pong_info.data_radius,
+            new_enr_detected: pong_info.new_enr_detected,
+        }
+    }
+}
+
+/// Response for PingRaw endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PingRawInfo {
+    /// The SSZ encoded PING message, as sent in the TALKREQ
+    pub request: Bytes,
+    /// The TALKRESP payload, as received
+    pub response: Bytes,
+    /// The decoded PONG, if the response is a valid one
+    pub pong: Option<PongInfo>,
+    /// Why the response couldn't be decoded as a PONG
+    pub error: Option<String>,
 }
 
 pub type FindNodesInfo = Vec<Enr>;
 
+/// Response for GetEnrInfo endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetEnrInfo {
+    pub enr: Enr,
+    /// Where the ENR was found
+    pub source: EnrSource,
+    /// The addresses of the ENR as multiaddrs. Only included on request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multiaddrs: Option<Vec<String>>,
+}
+
+/// Response for GetEnr and LookupEnr endpoints. The ENR alone, unless its multiaddrs are requested.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LookupEnrInfo {
+    Enr(Enr),
+    #[serde(rename_all = "camelCase")]
+    WithMultiaddrs {
+        enr: Enr,
+        multiaddrs: Vec<String>,
+    },
+}
+
+impl LookupEnrInfo {
+    /// Returns the found ENR.
+    pub fn enr(&self) -> &Enr {
+        match self {
+            LookupEnrInfo::Enr(enr) | LookupEnrInfo::WithMultiaddrs { enr, .. } => enr,
+        }
+    }
+}
+
+/// The source of a known ENR
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EnrSource {
+    /// The ENR of the local node
+    LocalNode,
+    /// The overlay routing table
+    RoutingTable,
+    /// The ENRs known to the underlying discv5 service
+    Discv5,
+}
+
 pub const MAX_CONTENT_KEYS_PER_OFFER: usize = 64;
 
+/// The max number of content items that are validated by a single ValidateContentBatch request.
+pub const MAX_CONTENT_ITEMS_PER_VALIDATION_BATCH: usize = 1024;
+
+/// The max size of an offered content value. Larger values are rejected before they are offered,
+/// instead of failing during the uTP transfer.
+pub const MAX_OFFER_CONTENT_VALUE_SIZE: usize = 16 * 1024 * 1024;
+
 /// Response for Offer endpoint
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AcceptInfo {
     pub content_keys: BitList<typenum::U64>,
+    /// The content keys field of the ACCEPT message, exactly as the peer encoded it: the SSZ
+    /// encoded accept bitlist, or the accept codes with protocol versions that use them. Only
+    /// included on request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accept_bitfield: Option<Bytes>,
+}
+
+impl AcceptInfo {
+    pub fn new(content_keys: BitList<typenum::U64>) -> Self {
+        Self {
+            content_keys,
+            accept_bitfield: None,
+        }
+    }
+
+    /// Includes the content keys field of the ACCEPT message, as received on the wire, in the
+    /// response.
+    pub fn with_accept_bitfield(mut self, accept_bitfield: impl Into<Bytes>) -> Self {
+        self.accept_bitfield = Some(accept_bitfield.into());
+        self
+    }
+}
+
+/// Response for OfferMany endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfferManyInfo<TContentKey: OverlayContentKey> {
+    /// The offered content keys that the peer accepted, in the order they were offered
+    pub accepted: Vec<TContentKey>,
+    /// The offered content keys that the peer declined, in the order they were offered
+    pub declined: Vec<TContentKey>,
+}
+
+impl<TContentKey: OverlayContentKey> OfferManyInfo<TContentKey> {
+    /// Splits the offered content keys into accepted and declined ones, according to the content
+    /// keys bitlist of the ACCEPT response. Keys that aren't covered by the bitlist are declined.
+    pub fn new(content_keys: Vec<TContentKey>, accepted_keys: &BitList<typenum::U64>) -> Self {
+        let (accepted, declined): (Vec<_>, Vec<_>) = content_keys
+            .into_iter()
+            .enumerate()
+            .partition(|(i, _)| accepted_keys.get(*i).unwrap_or(false));
+        Self {
+            accepted: accepted.into_iter().map(|(_, key)| key).collect(),
+            declined: declined.into_iter().map(|(_, key)| key).collect(),
+        }
+    }
+}
+
+/// Response for OfferAndVerify endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfferAndVerifyInfo {
+    /// Whether the peer accepted the offered content
+    pub accepted: bool,
+    /// Whether the peer served the same content afterwards
+    pub verified: bool,
+    /// Whether the content that the peer served afterwards passed local validation, or `None` if
+    /// the peer didn't serve any content
+    #[serde(default)]
+    pub valid: Option<bool>,
+}
+
+/// Response for NetworkInfo endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayNetworkInfo {
+    /// The portal network of the overlay, e.g. "mainnet"
+    pub network: String,
+    /// The subnetwork of the overlay, e.g. "beacon"
+    pub subnetwork: String,
+    /// The hex encoded protocol id of the subnetwork on the network, which the overlay's TALKREQ
+    /// messages are sent with
+    pub protocol_id: String,
 }
 
 /// Response for TraceGossip endpoint
@@ -77,6 +256,20 @@ pub struct TraceGossipInfo {
     pub accepted: Vec<String>,
     // List of all ENRs to whom the content was successfully transferred
     pub transferred: Vec<String>,
+    // List of all ENRs that responded to the offer without accepting the content
+    #[serde(default)]
+    pub declined: Vec<String>,
+    // List of all ENRs that were offered the content, but didn't respond to the offer
+    #[serde(default)]
+    pub failed: Vec<String>,
+    // The number of connected peers that weren't offered the content, because their radius
+    // excludes it
+    #[serde(default)]
+    pub skipped: usize,
+    // The number of peers that were selected to be offered the content, at most the max number
+    // configured for the subnetwork
+    #[serde(default)]
+    pub fanout: usize,
 }
 
 /// Response for the FindContent endpoint
@@ -111,10 +304,1091 @@ pub struct TraceContentInfo {
     pub trace: QueryTrace,
 }
 
+/// Response for GetContentOrClosest endpoint. If the content wasn't found, includes the closest
+/// peers to the content that responded during the lookup instead.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentOrClosestInfo {
+    pub content: Option<RawContentValue>,
+    pub utp_transfer: bool,
+    /// The closest peers to the content, ordered by increasing distance. Empty if the content was
+    /// found.
+    pub closest_enrs: Vec<Enr>,
+    pub trace: QueryTrace,
+}
+
 /// Response for PaginateLocalContentKeys endpoint
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PaginateLocalContentInfo<TContentKey: OverlayContentKey> {
     pub content_keys: Vec<TContentKey>,
-    pub total_entries: u64,
+    /// The total count of the local content that matches the filters. The cursor-based pagination
+    /// of filtered content only counts it for the first page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_entries: Option<u64>,
+    /// The cursor that fetches the next page, if there may be more content. Only set by the
+    /// cursor-based pagination.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<B256>,
+}
+
+/// How content got into the local store
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentProvenance {
+    /// Stored with the Store endpoint
+    RpcStore,
+    /// Gossiped with the Gossip endpoint, and stored because it's within the radius
+    Gossip,
+    /// Offered by a peer, but fetched from a fallback peer after the offered content failed to be
+    /// transferred or validated
+    OfferFallback,
+    /// Accepted from an offer of a peer
+    Offer,
+    /// Found while looking up content on the network
+    FoundViaLookup,
+    /// Imported with the ImportContent endpoint
+    Import,
+    /// Fetched from a peer with the FetchAndStore endpoint
+    Fetch,
+    /// Stored before the provenance was recorded
+    Unknown,
+}
+
+impl ContentProvenance {
+    /// Returns the name of the provenance, as it is serialized.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentProvenance::RpcStore => "rpc_store",
+            ContentProvenance::Gossip => "gossip",
+            ContentProvenance::OfferFallback => "offer_fallback",
+            ContentProvenance::Offer => "offer",
+            ContentProvenance::FoundViaLookup => "found_via_lookup",
+            ContentProvenance::Import => "import",
+            ContentProvenance::Fetch => "fetch",
+            ContentProvenance::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::str::FromStr for ContentProvenance {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rpc_store" => Ok(ContentProvenance::RpcStore),
+            "gossip" => Ok(ContentProvenance::Gossip),
+            "offer_fallback" => Ok(ContentProvenance::OfferFallback),
+            "offer" => Ok(ContentProvenance::Offer),
+            "found_via_lookup" => Ok(ContentProvenance::FoundViaLookup),
+            "import" => Ok(ContentProvenance::Import),
+            "fetch" => Ok(ContentProvenance::Fetch),
+            "unknown" => Ok(ContentProvenance::Unknown),
+            _ => Err(format!("Unknown content provenance: {s}")),
+        }
+    }
+}
+
+/// Metadata of locally stored content
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalContentMeta {
+    pub provenance: ContentProvenance,
+    /// The peer that offered the content, if it was offered or gossiped to us.
+    pub offered_by: Option<NodeId>,
+    /// When the content was stored, in seconds since the unix epoch. Unknown for content stored
+    /// before it was recorded.
+    pub stored_at: Option<u64>,
+    /// The label of the source that the content was stored from, if it was stored with one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Whether the content was stored with a signature of a trusted signer.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub trusted: bool,
+}
+
+impl LocalContentMeta {
+    /// Returns the metadata of content stored before it was recorded.
+    pub fn unknown() -> Self {
+        Self {
+            provenance: ContentProvenance::Unknown,
+            offered_by: None,
+            stored_at: None,
+            source: None,
+            trusted: false,
+        }
+    }
+
+    /// Returns the peers that provided the content, i.e. the peer that offered it, if any.
+    pub fn providers(&self) -> Vec<ContentProviderInfo> {
+        self.offered_by
+            .map(|node_id| ContentProviderInfo {
+                node_id,
+                provenance: self.provenance,
+                stored_at: self.stored_at,
+            })
+            .into_iter()
+            .collect()
+    }
+}
+
+/// A single entry of the ContentProviders endpoint response, i.e. a peer that provided the
+/// locally stored content
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentProviderInfo {
+    pub node_id: NodeId,
+    pub provenance: ContentProvenance,
+    /// When the content was stored, in seconds since the unix epoch.
+    pub stored_at: Option<u64>,
+}
+
+/// Response for LocalContent endpoint. The content value alone, unless its metadata is requested.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LocalContentInfo {
+    Content(RawContentValue),
+    #[serde(rename_all = "camelCase")]
+    WithMeta {
+        content: RawContentValue,
+        meta: LocalContentMeta,
+        /// The rank of the content by distance to the local node, if requested.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        distance_rank: Option<ContentDistanceRank>,
+    },
+}
+
+/// The rank of locally stored content among all stored content, by distance to the local node.
+///
+/// Content is pruned starting with the farthest, so the closer the rank is to the total, the
+/// sooner the content is pruned once the storage is full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentDistanceRank {
+    /// The number of stored content items closer to the local node, i.e. 0 for the closest one
+    pub rank: u64,
+    /// The number of stored content items
+    pub total: u64,
+}
+
+impl LocalContentInfo {
+    /// Returns the content value.
+    pub fn content(&self) -> &RawContentValue {
+        match self {
+            LocalContentInfo::Content(content) | LocalContentInfo::WithMeta { content, .. } => {
+                content
+            }
+        }
+    }
+}
+
+/// A single content entry, as streamed by ExportContent and accepted by ImportContent endpoints
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentRecord<TContentKey: OverlayContentKey> {
+    pub content_key: TContentKey,
+    pub content_value: RawContentValue,
+}
+
+/// Final record of the ExportContent subscription
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportContentSummary {
+    /// The number of exported content entries
+    pub count: u64,
+    /// The total size of the exported content values, in bytes
+    pub total_bytes: u64,
+    /// How long the export took, in milliseconds
+    pub duration_ms: u64,
+}
+
+/// Notification of the ExportContent subscription: a content entry, or the summary once all
+/// content was exported
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ExportContentItem<TContentKey: OverlayContentKey> {
+    Content(ContentRecord<TContentKey>),
+    Summary(ExportContentSummary),
+}
+
+/// Final record of the StreamLocalContentKeys subscription
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamLocalContentKeysSummary {
+    /// The number of streamed content keys
+    pub count: u64,
+    /// How long the enumeration took, in milliseconds
+    pub duration_ms: u64,
+}
+
+/// Notification of the StreamLocalContentKeys subscription: a batch of content keys, or the
+/// summary once all content keys were streamed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StreamLocalContentKeysItem<TContentKey: OverlayContentKey> {
+    #[serde(rename_all = "camelCase")]
+    ContentKeys {
+        content_keys: Vec<TContentKey>,
+    },
+    Summary(StreamLocalContentKeysSummary),
+}
+
+/// Response for ImportContent endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportContentInfo<TContentKey: OverlayContentKey> {
+    /// The number of stored content entries
+    pub stored: u64,
+    /// The content keys that couldn't be stored (e.g. because they are outside our radius)
+    pub failed: Vec<TContentKey>,
+}
+
+/// Response for FetchAndStore endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchAndStoreInfo {
+    /// Whether the fetched content was stored
+    pub stored: bool,
+    /// The length of the fetched content in bytes
+    pub content_length: u64,
+    /// Whether the content was transferred over uTP
+    pub utp_transfer: bool,
+    /// Why the fetched content wasn't stored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Response for HeaderUpdateTimes endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeaderUpdateTimesInfo {
+    /// How long ago the light client last updated its optimistic header, in milliseconds, or
+    /// None if it never did
+    pub optimistic_updated_ms_ago: Option<u64>,
+    /// How long ago the light client last updated its finalized header, in milliseconds, or None
+    /// if it never did
+    pub finalized_updated_ms_ago: Option<u64>,
+}
+
+/// Response for SetBandwidthLimit endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthLimitInfo {
+    /// The current uTP upload rate limit, in bytes per second. `None` if unlimited.
+    pub bytes_per_sec: Option<u64>,
+}
+
+/// Response for GetUtpLimit and SetUtpLimit endpoints
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UtpLimitInfo {
+    /// The max number of uTP transfers that run at once. The transfers above it wait for their
+    /// turn.
+    pub limit: u32,
+    /// The number of uTP transfers that are running.
+    pub active: u32,
+}
+
+/// Response for utpStats endpoint
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UtpStatsInfo {
+    pub upload: UtpThrottleStats,
+    pub download: UtpThrottleStats,
+}
+
+/// The rate limiting of the uTP packets in one direction, across all the subnetworks.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UtpThrottleStats {
+    /// The max rate, in kilobits per second, rounded up. `None` if unlimited.
+    pub max_kbps: Option<u64>,
+    /// The number of bytes of uTP packets so far.
+    pub bytes: u64,
+    /// The number of data packets that were delayed to stay under the max rate.
+    pub throttled_packets: u64,
+    /// How much of the allowed burst is currently used, from 0 (idle) to 1 (packets are delayed).
+    /// Always 0 if unlimited.
+    pub utilization: f64,
+}
+
+/// How strictly content is validated before it is accepted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidationMode {
+    /// Content that fails any check is rejected.
+    #[default]
+    Strict,
+    /// Content that fails only non-critical checks, e.g. a missing or invalid proof, is accepted
+    /// but tagged. Content that can't be decoded or doesn't match its content key is rejected.
+    Lenient,
+    /// Content is only checked to decode, it isn't validated.
+    Off,
+}
+
+/// Response for SetValidationMode endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationModeInfo<TContentKey: OverlayContentKey> {
+    /// The current validation mode
+    pub mode: ValidationMode,
+    /// The content recently accepted despite failing non-critical checks, the most recent first
+    pub tagged: Vec<TaggedContentInfo<TContentKey>>,
+}
+
+/// Content that was accepted in lenient validation mode despite failing non-critical checks.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaggedContentInfo<TContentKey: OverlayContentKey> {
+    pub content_key: TContentKey,
+    /// The check that the content failed
+    pub reason: String,
+}
+
+/// The max number of stored content keys returned by the NearestStoredKeys endpoint.
+pub const MAX_NEAREST_STORED_KEYS: u64 = 1024;
+
+/// Response for NearestStoredKeys endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NearestStoredKeyInfo<TContentKey: OverlayContentKey> {
+    pub content_key: TContentKey,
+    pub content_id: B256,
+    /// The XOR distance between the content id and the target content id
+    pub distance: B256,
+}
+
+/// Response for RangeCoverage endpoint
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RangeCoverageInfo {
+    /// The number of stored content items whose content ids are within the range
+    pub content_count: u64,
+    /// The fraction of the stored content items whose content ids are within the range
+    pub stored_fraction: f64,
+    /// The fraction of the content id space that the range spans
+    pub range_fraction: f64,
+}
+
+/// Response for VerifyPeers endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyPeersInfo {
+    /// The pinged peers that responded
+    pub responded: Vec<NodeId>,
+    /// The pinged peers that didn't respond
+    pub unresponsive: Vec<NodeId>,
+    /// The unresponsive peers that were removed from the routing table
+    pub evicted: Vec<NodeId>,
+}
+
+/// Response for RecentOfferers endpoint
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentOfferersInfo {
+    /// The peers that recently offered us content, the most recent first
+    pub offerers: Vec<RecentOffererInfo>,
+}
+
+/// The offers we recently received from a single peer
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentOffererInfo {
+    pub node_id: NodeId,
+    /// The number of OFFER messages received from the peer
+    pub offers: u64,
+    /// The number of content keys offered by the peer
+    pub offered_keys: u64,
+    /// The number of offered content keys that we accepted
+    pub accepted_keys: u64,
+    /// The fraction of offered content keys that we accepted
+    pub accept_rate: f64,
+    /// Seconds since the last offer from the peer
+    pub last_offer_secs_ago: u64,
+}
+
+/// Response for StorageInfo endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageInfo {
+    /// Whether identical content values are stored once and shared between content keys
+    pub content_dedup: bool,
+    /// The total size of the stored content values, as seen through their content keys
+    pub content_bytes: u64,
+    /// The number of bytes saved by sharing identical content values between content keys
+    pub dedup_savings_bytes: u64,
+}
+
+/// Response for GcOrphans endpoint
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GcOrphansInfo {
+    /// The number of removed content blobs that no content key referenced
+    pub removed_blobs: u64,
+    /// The number of bytes that the removed content blobs used
+    pub reclaimed_bytes: u64,
+}
+
+/// Response for PinContent & UnpinContent endpoints
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedContentInfo {
+    /// The number of pinned content keys, including the ones that aren't stored yet
+    pub pinned_keys: u64,
+    /// The number of bytes that the stored pinned content uses
+    pub pinned_bytes: u64,
+    /// The budget of the pinned content, which is separate from the storage capacity
+    pub pinned_capacity_bytes: u64,
+}
+
+/// Response for ContentTypeBreakdown endpoint
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BeaconContentTypeCounts {
+    pub light_client_bootstrap: u64,
+    /// The number of stored light client updates, one per sync committee period
+    pub light_client_update: u64,
+    /// The latest optimistic update is kept in memory, so this is at most 1
+    pub light_client_optimistic_update: u64,
+    /// The latest finality update is kept in memory, so this is at most 1
+    pub light_client_finality_update: u64,
+    /// Only the latest historical summaries are kept, so this is at most 1
+    pub historical_summaries_with_proof: u64,
+}
+
+/// Response for StorageBackendInfo endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageBackendInfo {
+    /// The type of database the content is stored in
+    pub backend: StorageBackend,
+    /// The path of the database file, or only its file name if redacted. `None` for in-memory
+    /// databases
+    pub path: Option<String>,
+    /// The page size of the database, in bytes
+    pub page_size: u64,
+    /// Whether the database uses write-ahead logging
+    pub wal_enabled: bool,
+}
+
+/// The type of database the content is stored in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageBackend {
+    /// A SQLite database file
+    Sqlite,
+    /// An in-memory SQLite database
+    Memory,
+}
+
+/// Response for AddBootnode endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddBootnodeInfo {
+    /// The bond result on the history subnetwork, if enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<BootnodeBond>,
+    /// The bond result on the state subnetwork, if enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<BootnodeBond>,
+    /// The bond result on the beacon subnetwork, if enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beacon: Option<BootnodeBond>,
+    /// Whether the bootnode was persisted, to be used after a restart
+    pub persisted: bool,
+}
+
+/// Response for NodeOverview endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeOverview {
+    pub client_version: String,
+    /// The network that the node is on, e.g. mainnet or angelfood
+    pub network: String,
+    pub node_id: NodeId,
+    pub enr: Enr,
+    /// The enabled subnetworks
+    pub subnetworks: Vec<String>,
+    /// The socket address that the ENR advertises, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub advertised_address: Option<SocketAddr>,
+    /// When the advertised address last changed, in seconds since the Unix epoch, if it changed
+    /// since the node started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address_changed_at: Option<u64>,
+}
+
+/// The result of bonding with a bootnode on a single subnetwork
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootnodeBond {
+    pub bonded: bool,
+    /// The reason why bonding failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response for AnalyzeTrace endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceAnalysisInfo {
+    /// The current status of every peer in the trace, except the local node
+    pub peers: HashMap<NodeId, TracedPeerStatus>,
+}
+
+/// The current status of a peer from a query trace, along with how it behaved in the query
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TracedPeerStatus {
+    /// Whether the peer responded during the traced query
+    pub responded_in_trace: bool,
+    /// How the peer failed during the traced query, if it did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_in_trace: Option<QueryFailureKind>,
+    /// Whether the peer is currently in the routing table
+    pub in_routing_table: bool,
+    /// Whether the peer currently responds to a ping
+    pub reachable: bool,
+}
+
+/// Response for PruneEstimate endpoint.
+///
+/// This is a heuristic: it extrapolates from recently stored content and may be far off if the
+/// ingress rate changes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneEstimateInfo {
+    /// The estimated time until the content is pruned or replaced
+    pub estimate: PruneEstimate,
+    /// Human readable explanation of how the estimate was made
+    pub reason: String,
+}
+
+/// Estimated time until stored content is pruned or replaced
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PruneEstimate {
+    /// The content is not stored locally
+    NotStored,
+    /// The content is unlikely to be pruned
+    Unlikely,
+    /// Not enough content was stored recently to make an estimate
+    Unknown,
+    /// The content is expected to be pruned or replaced within the given number of seconds
+    WithinSeconds(u64),
+}
+
+/// Response for StoreLatencyStats endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreLatencyStats {
+    /// The latency of reading content from the local store
+    pub get: LatencyPercentiles,
+    /// The latency of writing content to the local store
+    pub put: LatencyPercentiles,
+}
+
+/// Response for SelfTest endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestInfo {
+    /// Whether all the checks passed
+    pub passed: bool,
+    /// The checks that were run, in order
+    pub checks: Vec<SelfTestCheck>,
+}
+
+/// The result of checking a single subsystem during a self-test.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestCheck {
+    pub subsystem: SelfTestSubsystem,
+    pub passed: bool,
+    /// How long the check took, in microseconds
+    pub duration_us: u64,
+    /// Why the check failed, if it did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The subsystems checked by a self-test.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SelfTestSubsystem {
+    /// Writing content to the local store
+    StorePut,
+    /// Reading content back from the local store
+    StoreGet,
+    /// Deriving content ids from content keys
+    ContentId,
+    /// Computing the data radius
+    Radius,
+    /// Removing the synthetic content from the local store
+    Cleanup,
+}
+
+/// Latency percentiles over the most recent operations, in microseconds. The percentiles are
+/// `None` if no operation was recorded since the node started.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyPercentiles {
+    /// The number of operations that the percentiles are computed over
+    pub samples: u64,
+    pub p50_us: Option<u64>,
+    pub p95_us: Option<u64>,
+    pub p99_us: Option<u64>,
+}
+
+/// Response for AcceptSnapshot endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptSnapshotInfo {
+    /// Bumped whenever the radius or the transfer limit changes, so that accept decisions made
+    /// with the same version are still valid
+    pub version: u64,
+    /// The radius that offered content must be within to be accepted
+    pub radius: DataRadius,
+    /// The max number of concurrent inbound uTP transfers, offers are declined while it is reached
+    pub utp_transfer_limit: u64,
+}
+
+/// The max number of synthetic content ids sampled by the SimulateIncomingOffer endpoint.
+pub const MAX_SIMULATED_OFFER_SAMPLE_SIZE: u64 = 10_000;
+
+/// Response for SimulateIncomingOffer endpoint
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedOfferInfo {
+    /// The number of synthetic content ids, spread evenly across the keyspace
+    pub sampled: u64,
+    /// The number of sampled content ids that would be accepted
+    pub accepted: u64,
+    /// The number of sampled content ids that are within the radius, but already stored
+    pub already_stored: u64,
+    /// The share of the sampled content ids that would be accepted, from 0 to 1
+    pub acceptance_rate: f64,
+    /// The radius that the sampled content ids were checked against
+    pub radius: DataRadius,
+}
+
+/// The type of the proof of a block header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HeaderProofType {
+    None,
+    PreMergeAccumulator,
+    HistoricalRoots,
+    HistoricalSummaries,
+}
+
+impl From<&BlockHeaderProof> for HeaderProofType {
+    fn from(proof: &BlockHeaderProof) -> Self {
+        match proof {
+            BlockHeaderProof::None(_) => Self::None,
+            BlockHeaderProof::PreMergeAccumulatorProof(_) => Self::PreMergeAccumulator,
+            BlockHeaderProof::HistoricalRootsBlockProof(_) => Self::HistoricalRoots,
+            BlockHeaderProof::HistoricalSummariesBlockProof(_) => Self::HistoricalSummaries,
+        }
+    }
+}
+
+/// Response for VerifyHeader endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderVerificationInfo {
+    pub block_number: u64,
+    pub block_hash: B256,
+    pub proof_type: HeaderProofType,
+    /// Whether the header would be accepted if it was gossiped
+    pub accepted: bool,
+    /// Whether the proof was verified. Post-Capella headers are verified against the historical
+    /// summaries of the beacon network, and rejected if they can't be
+    pub proof_verified: bool,
+    /// The epoch hash of the pre-merge accumulator, the historical root or the block summary root
+    /// of the historical summary that the proof is checked against, if any
+    pub root: Option<B256>,
+    /// Why the header isn't accepted
+    pub error: Option<String>,
+}
+
+/// Response for HistoryContentSummary endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryContentSummaryInfo {
+    /// The lowest block number of the stored block headers, `None` if no header is stored
+    pub lowest_block_number: Option<u64>,
+    /// The highest block number of the stored block headers, `None` if no header is stored
+    pub highest_block_number: Option<u64>,
+    /// The number of distinct block numbers of the stored block headers
+    pub header_block_count: u64,
+    /// The number of stored entries per content type
+    pub content_type_counts: HistoryContentTypeCounts,
+    /// The largest ranges of block numbers without a stored header, between the lowest and the
+    /// highest block numbers, largest first
+    pub largest_gaps: Vec<BlockNumberGap>,
+}
+
+/// The number of stored history entries per content type.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryContentTypeCounts {
+    pub block_header_by_hash: u64,
+    pub block_header_by_number: u64,
+    pub block_body: u64,
+    pub block_receipts: u64,
+}
+
+/// A range of consecutive block numbers, inclusive.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockNumberGap {
+    pub start: u64,
+    pub end: u64,
+    /// The number of block numbers in the range
+    pub length: u64,
+}
+
+/// Response for PrefetchStatus endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrefetchStatusInfo {
+    pub job_id: u64,
+    pub state: PrefetchJobState,
+    /// The number of content keys to prefetch
+    pub total: u64,
+    /// The number of content keys whose content was found and stored
+    pub found: u64,
+    /// The number of content keys whose content wasn't found on the network
+    pub not_found: u64,
+    /// The number of content keys whose lookup or storage failed
+    pub failed: u64,
+}
+
+impl PrefetchStatusInfo {
+    /// Returns the number of content keys that weren't prefetched yet.
+    pub fn pending(&self) -> u64 {
+        self.total - self.found - self.not_found - self.failed
+    }
+}
+
+/// The state of a prefetch job
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PrefetchJobState {
+    Running,
+    Completed,
+    Cancelled,
+}
+
+/// Response for ActiveLookups endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveLookupInfo {
+    /// The id of the content being looked up
+    pub content_id: B256,
+    /// How long ago the lookup started, in milliseconds
+    pub started_ms_ago: u64,
+    /// The number of peers that the lookup sent a request to so far
+    pub peers_contacted: u64,
+    /// Whether the lookup is querying peers, or transferring or validating the content that a
+    /// peer returned
+    pub stage: LookupStage,
+}
+
+/// The stage of an active lookup
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LookupStage {
+    /// Querying peers for the content
+    Querying,
+    /// Receiving the content that a peer returned over uTP
+    UtpTransfer,
+    /// Validating the content that a peer returned
+    Validating,
+}
+
+/// Response for ValidateContentBatch endpoint, for a single content item
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentValidationInfo {
+    /// Whether the content is valid
+    pub valid: bool,
+    /// Why the content is invalid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response for InspectContent endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentInspectionInfo {
+    /// The content value, as it was passed
+    pub raw: RawContentValue,
+    /// The JSON representation of the decoded content value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<serde_json::Value>,
+    /// The content id derived from the content key
+    pub content_id: B256,
+    /// Why the content value can't be decoded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response for RevalidateDetailed endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReportInfo {
+    /// Whether none of the checks failed
+    pub valid: bool,
+    /// The checks, in the order that they ran. The checks after a failed one don't run.
+    pub steps: Vec<ValidationStepInfo>,
+    /// The roots computed while validating, by name
+    pub roots: BTreeMap<String, B256>,
+}
+
+/// A single check of a validation report
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationStepInfo {
+    pub name: String,
+    pub status: ValidationStepStatus,
+    /// Whether the content is rejected if the check fails, regardless of the validation mode
+    pub critical: bool,
+    /// Why the check failed or was skipped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// The outcome of a single check of a validation report
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationStepStatus {
+    Passed,
+    Failed,
+    /// The check didn't run, as its input isn't available, e.g. the finalized state root
+    Skipped,
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::B256;
+    use serde_json::json;
+
+    use super::*;
+    use crate::IdentityContentKey;
+
+    #[test]
+    fn content_provenance_names_match_serialization() {
+        for provenance in [
+            ContentProvenance::RpcStore,
+            ContentProvenance::Gossip,
+            ContentProvenance::OfferFallback,
+            ContentProvenance::Offer,
+            ContentProvenance::FoundViaLookup,
+            ContentProvenance::Import,
+            ContentProvenance::Fetch,
+            ContentProvenance::Unknown,
+        ] {
+            assert_eq!(
+                serde_json::to_value(provenance).unwrap(),
+                json!(provenance.as_str())
+            );
+            assert_eq!(provenance.as_str().parse(), Ok(provenance));
+        }
+        assert!("store".parse::<ContentProvenance>().is_err());
+    }
+
+    #[test]
+    fn header_update_times_info_uses_snake_case() {
+        let header_update_times = HeaderUpdateTimesInfo {
+            optimistic_updated_ms_ago: Some(3),
+            finalized_updated_ms_ago: None,
+        };
+        assert_eq!(
+            serde_json::to_value(header_update_times).unwrap(),
+            json!({ "optimistic_updated_ms_ago": 3, "finalized_updated_ms_ago": null })
+        );
+    }
+
+    #[test]
+    fn active_lookup_info_uses_snake_case() {
+        let active_lookup = ActiveLookupInfo {
+            content_id: B256::ZERO,
+            started_ms_ago: 1,
+            peers_contacted: 2,
+            stage: LookupStage::UtpTransfer,
+        };
+        assert_eq!(
+            serde_json::to_value(active_lookup).unwrap(),
+            json!({
+                "content_id": B256::ZERO,
+                "started_ms_ago": 1,
+                "peers_contacted": 2,
+                "stage": "utp_transfer",
+            })
+        );
+    }
+
+    #[test]
+    fn pong_info_serialization() {
+        let pong_info = PongInfo {
+            enr_seq: 3,
+            data_radius: U256::from(0xabcdu64),
+            new_enr_detected: true,
+        };
+        let serialized = serde_json::to_value(&pong_info).unwrap();
+        assert_eq!(
+            serialized,
+            json!({
+                "enrSeq": 3,
+                "dataRadius": format!("0x{}abcd", "0".repeat(60)),
+                "dataRadiusLegacy": "0xabcd",
+                "newEnrDetected": true,
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<PongInfo>(serialized).unwrap(),
+            pong_info
+        );
+
+        // Pongs of older nodes, that don't pad the data radius, are still accepted.
+        assert_eq!(
+            serde_json::from_value::<PongInfo>(json!({ "enrSeq": 1, "dataRadius": "0xff" }))
+                .unwrap()
+                .data_radius,
+            U256::from(0xffu64)
+        );
+    }
+
+    #[test]
+    fn local_content_info_with_meta() {
+        let content = RawContentValue::from(vec![1, 2, 3]);
+        let info = LocalContentInfo::Content(content.clone());
+        assert_eq!(serde_json::to_value(&info).unwrap(), json!("0x010203"));
+
+        let info = LocalContentInfo::WithMeta {
+            content: content.clone(),
+            meta: LocalContentMeta {
+                provenance: ContentProvenance::Offer,
+                offered_by: Some(NodeId::new(&[1; 32])),
+                stored_at: Some(1_700_000_000),
+                source: None,
+                trusted: false,
+            },
+            distance_rank: None,
+        };
+        let json = json!({
+            "content": "0x010203",
+            "meta": {
+                "provenance": "offer",
+                "offeredBy": NodeId::new(&[1; 32]),
+                "storedAt": 1_700_000_000,
+            },
+        });
+        assert_eq!(serde_json::to_value(&info).unwrap(), json);
+        assert_eq!(
+            serde_json::from_value::<LocalContentInfo>(json).unwrap(),
+            info
+        );
+        assert_eq!(info.content(), &content);
+
+        let info = LocalContentInfo::WithMeta {
+            content,
+            meta: LocalContentMeta::unknown(),
+            distance_rank: Some(ContentDistanceRank { rank: 2, total: 10 }),
+        };
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["distanceRank"], json!({ "rank": 2, "total": 10 }));
+        assert_eq!(
+            serde_json::from_value::<LocalContentInfo>(json).unwrap(),
+            info
+        );
+    }
+
+    #[test]
+    fn offer_many_info_preserves_order() {
+        let content_keys: Vec<_> = (0..4)
+            .map(|_| IdentityContentKey::new(B256::random().0))
+            .collect();
+        let mut accepted_keys = BitList::<typenum::U64>::with_capacity(3).unwrap();
+        accepted_keys.set(0, true).unwrap();
+        accepted_keys.set(2, true).unwrap();
+
+        // The last key isn't covered by the bitlist, so it's declined.
+        let info = OfferManyInfo::new(content_keys.clone(), &accepted_keys);
+        assert_eq!(
+            info.accepted,
+            vec![content_keys[0].clone(), content_keys[2].clone()]
+        );
+        assert_eq!(
+            info.declined,
+            vec![content_keys[1].clone(), content_keys[3].clone()]
+        );
+    }
+
+    #[test]
+    fn accept_info_with_accept_bitfield() {
+        let mut content_keys = BitList::<typenum::U64>::with_capacity(2).unwrap();
+        content_keys.set(0, true).unwrap();
+
+        let info = AcceptInfo::new(content_keys);
+        assert!(serde_json::to_value(&info)
+            .unwrap()
+            .get("acceptBitfield")
+            .is_none());
+
+        let info = info.with_accept_bitfield(vec![0x00, 0x01]);
+        assert_eq!(
+            serde_json::to_value(&info).unwrap()["acceptBitfield"],
+            "0x0001"
+        );
+        assert_eq!(
+            serde_json::from_value::<AcceptInfo>(serde_json::to_value(&info).unwrap()).unwrap(),
+            info
+        );
+    }
+
+    #[test]
+    fn export_content_item_ser_de() {
+        let content = ExportContentItem::Content(ContentRecord {
+            content_key: IdentityContentKey::new(B256::random().0),
+            content_value: RawContentValue::from_static(&[1, 2, 3]),
+        });
+        let summary = ExportContentItem::<IdentityContentKey>::Summary(ExportContentSummary {
+            count: 1,
+            total_bytes: 3,
+            duration_ms: 10,
+        });
+
+        for item in [content, summary] {
+            let json = serde_json::to_string(&item).unwrap();
+            assert_eq!(
+                serde_json::from_str::<ExportContentItem<_>>(&json).unwrap(),
+                item
+            );
+        }
+    }
+
+    #[test]
+    fn stream_local_content_keys_item_ser_de() {
+        let content_keys = StreamLocalContentKeysItem::ContentKeys {
+            content_keys: vec![IdentityContentKey::new(B256::random().0)],
+        };
+        let summary = StreamLocalContentKeysItem::<IdentityContentKey>::Summary(
+            StreamLocalContentKeysSummary {
+                count: 1,
+                duration_ms: 10,
+            },
+        );
+
+        assert!(serde_json::to_value(&content_keys)
+            .unwrap()
+            .get("contentKeys")
+            .is_some());
+        for item in [content_keys, summary] {
+            let json = serde_json::to_string(&item).unwrap();
+            assert_eq!(
+                serde_json::from_str::<StreamLocalContentKeysItem<_>>(&json).unwrap(),
+                item
+            );
+        }
+    }
 }
@@ -5,7 +5,7 @@ use std::{
     sync::Arc,
 };
 
-use alloy::primitives::U256;
+use alloy::primitives::{B256, U256};
 use anyhow::anyhow;
 use bimap::BiHashMap;
 use once_cell::sync::Lazy;
@@ -14,7 +14,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use ssz::{Decode, DecodeError, Encode};
 use ssz_derive::{Decode, Encode};
-use ssz_types::{typenum, BitList};
+use ssz_types::{typenum, BitList, VariableList};
 use thiserror::Error;
 use validator::ValidationError;
 
@@ -26,12 +26,16 @@ use crate::{
         network::{Network, Subnetwork},
     },
     utils::bytes::{hex_decode, hex_encode},
-    RawContentKey, RawContentValue,
+    BeaconContentKey, HistoryContentKey, OverlayContentKey, RawContentKey, RawContentValue,
+    StateContentKey,
 };
 
 /// The maximum size of a Discv5 packet.
 pub(crate) const MAX_DISCV5_PACKET_SIZE: usize = 1280;
 
+/// The maximum assumed size of a Discv5 talk request protocol.
+pub const MAX_DISCV5_TALK_REQ_PROTOCOL_SIZE: usize = 8;
+
 /// The maximum size of a Discv5 talk request payload.
 ///
 /// Discv5 talk request overhead:
@@ -42,8 +46,8 @@ pub(crate) const MAX_DISCV5_PACKET_SIZE: usize = 1280;
 ///   * (max) talk request ID length: 8
 ///   * (max assumed) talk request protocol length: 8
 ///   * RLP byte array overhead: 6
-pub(crate) const MAX_DISCV5_TALK_REQ_PAYLOAD_SIZE: usize =
-    MAX_DISCV5_PACKET_SIZE - 16 - 23 - 32 - 16 - 8 - 8 - 6;
+pub const MAX_DISCV5_TALK_REQ_PAYLOAD_SIZE: usize =
+    MAX_DISCV5_PACKET_SIZE - 16 - 23 - 32 - 16 - 8 - MAX_DISCV5_TALK_REQ_PROTOCOL_SIZE - 6;
 
 // NOTE: The wire constants below rely on the following SSZ constants:
 //   * `ssz::BYTES_PER_UNION_SELECTOR`: 1
@@ -104,10 +108,20 @@ impl From<Vec<u8>> for CustomPayload {
     }
 }
 
-impl From<CustomPayload> for Distance {
-    fn from(val: CustomPayload) -> Self {
+impl TryFrom<CustomPayload> for Distance {
+    type Error = DecodeError;
+
+    fn try_from(val: CustomPayload) -> Result<Self, Self::Error> {
         let bytes = val.payload;
-        U256::from_le_slice(bytes.deref()).into()
+        // Bytes past the size of a distance are tolerated, as long as they are zero.
+        let (distance, overflow) = bytes.split_at(bytes.len().min(U256::BYTES));
+        if overflow.iter().any(|byte| *byte != 0) {
+            return Err(DecodeError::BytesInvalid(format!(
+                "custom payload doesn't fit in a distance: {}",
+                hex_encode(bytes.deref())
+            )));
+        }
+        Ok(U256::from_le_slice(distance).into())
     }
 }
 
@@ -170,6 +184,26 @@ impl NetworkSpec {
         self.network
     }
 
+    /// Returns the spec of the given network.
+    pub fn from_network(network: Network) -> Arc<Self> {
+        match network {
+            Network::Mainnet => MAINNET.clone(),
+            Network::Angelfood => ANGELFOOD.clone(),
+        }
+    }
+
+    /// Returns the other known network that the protocol identifier belongs to, if it isn't a
+    /// protocol identifier of this network.
+    pub fn foreign_network_of_protocol_identifier(&self, hex: &str) -> Option<Network> {
+        if self.portal_subnetworks.contains_right(hex) {
+            return None;
+        }
+        [&MAINNET, &ANGELFOOD]
+            .into_iter()
+            .find(|spec| spec.portal_subnetworks.contains_right(hex))
+            .map(|spec| spec.network)
+    }
+
     pub fn get_subnetwork_from_protocol_identifier(&self, hex: &str) -> anyhow::Result<Subnetwork> {
         self.portal_subnetworks
             .get_by_right(hex)
@@ -387,7 +421,7 @@ impl From<Pong> for Value {
                 result.insert("enrSeq".to_owned(), Value::String(val.enr_seq.to_string()));
                 result.insert(
                     "dataRadius".to_owned(),
-                    Value::String(data_radius.to_string()),
+                    Value::String(B256::from(data_radius).to_string()),
                 );
 
                 Value::Object(result)
@@ -583,19 +617,164 @@ impl From<Accept> for Value {
     }
 }
 
+/// The message id of ACCEPT messages, i.e. their selector in the [Message] union.
+const ACCEPT_MESSAGE_ID: u8 = 0x07;
+
+/// The code of an accepted content key, in ACCEPT messages with codes.
+pub const ACCEPT_CODE_ACCEPTED: u8 = 0;
+
+/// The code of a content key that is declined for an unspecified reason, in ACCEPT messages with
+/// codes.
+pub const ACCEPT_CODE_DECLINED: u8 = 1;
+
+/// An ACCEPT message of a protocol version that carries a code per offered content key, instead
+/// of a bitlist. Every code but [ACCEPT_CODE_ACCEPTED] declines its content key.
+#[derive(Debug, PartialEq, Clone, Encode, Decode)]
+pub struct AcceptWithCodes {
+    pub connection_id: u16,
+    pub content_keys: VariableList<u8, typenum::U64>,
+}
+
+impl From<&Accept> for AcceptWithCodes {
+    fn from(accept: &Accept) -> Self {
+        let codes = accept
+            .content_keys
+            .iter()
+            .map(|accepted| {
+                if accepted {
+                    ACCEPT_CODE_ACCEPTED
+                } else {
+                    ACCEPT_CODE_DECLINED
+                }
+            })
+            .collect::<Vec<_>>();
+        Self {
+            connection_id: accept.connection_id,
+            // The bitlist holds at most 64 bits, so the codes fit.
+            content_keys: VariableList::from(codes),
+        }
+    }
+}
+
+impl TryFrom<AcceptWithCodes> for Accept {
+    type Error = DecodeError;
+
+    fn try_from(accept: AcceptWithCodes) -> Result<Self, Self::Error> {
+        let mut content_keys = BitList::with_capacity(accept.content_keys.len())
+            .map_err(|err| DecodeError::BytesInvalid(format!("Invalid accept codes: {err:?}")))?;
+        for (index, code) in accept.content_keys.iter().enumerate() {
+            content_keys
+                .set(index, *code == ACCEPT_CODE_ACCEPTED)
+                .map_err(|err| {
+                    DecodeError::BytesInvalid(format!("Invalid accept codes: {err:?}"))
+                })?;
+        }
+        Ok(Self {
+            connection_id: accept.connection_id,
+            content_keys,
+        })
+    }
+}
+
+/// Encodes the response, with a code per content key in ACCEPT messages if `accept_codes` is set,
+/// i.e. if the peer's protocol version supports them.
+pub fn encode_response(response: Response, accept_codes: bool) -> Vec<u8> {
+    match response {
+        Response::Accept(accept) if accept_codes => {
+            let mut bytes = vec![ACCEPT_MESSAGE_ID];
+            bytes.extend(AcceptWithCodes::from(&accept).as_ssz_bytes());
+            bytes
+        }
+        response => Message::from(response).as_ssz_bytes(),
+    }
+}
+
+/// Decodes a response message, expecting a code per content key in ACCEPT messages if
+/// `accept_codes` is set, i.e. if the peer's protocol version supports them.
+pub fn decode_response(bytes: &[u8], accept_codes: bool) -> Result<Message, MessageDecodeError> {
+    match bytes.split_first() {
+        Some((&ACCEPT_MESSAGE_ID, payload)) if accept_codes => {
+            AcceptWithCodes::from_ssz_bytes(payload)
+                .and_then(Accept::try_from)
+                .map(Message::Accept)
+                .map_err(|err| MessageDecodeError::Ssz { decode_err: err })
+        }
+        _ => Message::try_from(bytes.to_vec()),
+    }
+}
+
+/// Returns the content keys field of an encoded ACCEPT message, exactly as sent: the accept
+/// bitlist, or the accept codes with protocol versions that use them. Returns `None` if the bytes
+/// aren't an ACCEPT message.
+pub fn accept_content_keys_bytes(bytes: &[u8]) -> Option<&[u8]> {
+    let (&ACCEPT_MESSAGE_ID, payload) = bytes.split_first()? else {
+        return None;
+    };
+    // The fixed part of the container is the connection id, followed by the offset of the content
+    // keys, which run to the end of the message.
+    let offset = payload.get(2..6)?;
+    let offset = u32::from_le_bytes(offset.try_into().ok()?) as usize;
+    payload.get(offset..)
+}
+
+/// Decodes the bytes of an incoming talk request or response the same way the overlay does,
+/// including the payloads of the message: the custom payload of pings and pongs, and the content
+/// keys of FINDCONTENT and OFFER requests. Returns the decoded message, if any.
+///
+/// It's the entry point of the property tests and of the fuzz target, which check that decoding
+/// arbitrary bytes fails cleanly rather than panicking.
+#[doc(hidden)]
+pub fn decode_like_overlay(bytes: &[u8]) -> Option<Message> {
+    let _ = decode_response(bytes, /* accept_codes= */ true);
+    let message = Message::try_from(bytes.to_vec()).ok()?;
+    match &message {
+        Message::Ping(Ping { custom_payload, .. }) | Message::Pong(Pong { custom_payload, .. }) => {
+            let _ = Distance::try_from(custom_payload.clone());
+        }
+        Message::FindContent(FindContent { content_key }) => decode_content_key(content_key),
+        Message::Offer(Offer { content_keys }) => content_keys.iter().for_each(decode_content_key),
+        _ => {}
+    }
+    let _ = Request::try_from(message.clone());
+    let _ = Response::try_from(message.clone());
+    Some(message)
+}
+
+fn decode_content_key(content_key: &RawContentKey) {
+    let _ = HistoryContentKey::try_from_bytes(content_key);
+    let _ = BeaconContentKey::try_from_bytes(content_key);
+    let _ = StateContentKey::try_from_bytes(content_key);
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test {
     use std::str::FromStr;
 
+    use alloy::primitives::B256;
     use alloy::{
         hex::FromHex,
         primitives::{bytes, Bytes},
     };
+    use proptest::{collection::vec, prelude::*};
     use ssz_types::Error::OutOfBounds;
     use test_log::test;
 
     use super::*;
+    use crate::{
+        types::{
+            content_key::{
+                beacon::{
+                    HistoricalSummariesWithProofKey, LightClientBootstrapKey,
+                    LightClientFinalityUpdateKey, LightClientOptimisticUpdateKey,
+                    LightClientUpdatesByRangeKey,
+                },
+                state::{AccountTrieNodeKey, ContractBytecodeKey, ContractStorageTrieNodeKey},
+            },
+            state_trie::nibbles::Nibbles,
+        },
+        BeaconContentValue, ContentValue, HistoryContentValue, StateContentValue,
+    };
 
     #[test]
     fn subnetwork_invalid() {
@@ -615,6 +794,32 @@ mod test {
         assert_eq!(hex, expected_hex);
     }
 
+    #[test]
+    fn foreign_network_of_protocol_identifier() {
+        assert_eq!(
+            MAINNET.foreign_network_of_protocol_identifier("0x504B"),
+            Some(Network::Angelfood)
+        );
+        assert_eq!(
+            ANGELFOOD.foreign_network_of_protocol_identifier("0x500B"),
+            Some(Network::Mainnet)
+        );
+        // Protocol identifiers of the network itself, including the shared uTP one.
+        assert_eq!(
+            MAINNET.foreign_network_of_protocol_identifier("0x500B"),
+            None
+        );
+        assert_eq!(
+            MAINNET.foreign_network_of_protocol_identifier("0x757470"),
+            None
+        );
+        // Unknown protocol identifiers.
+        assert_eq!(
+            MAINNET.foreign_network_of_protocol_identifier("0x1234"),
+            None
+        );
+    }
+
     // Wire message test vectors available in Ethereum Portal Network specs repo:
     // github.com/ethereum/portal-network-specs
     #[test]
@@ -800,6 +1005,55 @@ mod test {
 
         let decoded = Message::try_from(hex_decode(&encoded).unwrap()).unwrap();
         assert_eq!(decoded, accept);
+        assert_eq!(
+            accept_content_keys_bytes(&hex_decode(&encoded).unwrap()),
+            Some([0x01, 0x01].as_slice())
+        );
+    }
+
+    #[test]
+    fn message_encoding_accept_with_codes() {
+        let connection_id = u16::from_le_bytes([0x01, 0x02]);
+        let mut content_keys = BitList::with_capacity(3).unwrap();
+        content_keys.set(0, true).unwrap();
+        content_keys.set(2, true).unwrap();
+        let accept = Accept {
+            connection_id,
+            content_keys,
+        };
+
+        let encoded = encode_response(Response::Accept(accept.clone()), true);
+        assert_eq!(hex_encode(&encoded), "0x07010206000000000100");
+        assert_eq!(
+            decode_response(&encoded, true).unwrap(),
+            Message::Accept(accept.clone())
+        );
+        assert_eq!(
+            accept_content_keys_bytes(&encoded),
+            Some([0x00, 0x01, 0x00].as_slice())
+        );
+
+        // Peers without accept codes get, and send, the bitlist.
+        let encoded = encode_response(Response::Accept(accept.clone()), false);
+        assert_eq!(encoded, Message::Accept(accept.clone()).as_ssz_bytes());
+        assert_eq!(
+            decode_response(&encoded, false).unwrap(),
+            Message::Accept(accept)
+        );
+
+        // Other messages are encoded the same either way.
+        let pong = Response::Pong(Pong {
+            enr_seq: 1,
+            custom_payload: CustomPayload::from(vec![]),
+        });
+        assert_eq!(
+            encode_response(pong.clone(), true),
+            encode_response(pong.clone(), false)
+        );
+        assert_eq!(
+            accept_content_keys_bytes(&encode_response(pong, true)),
+            None
+        );
     }
 
     #[test]
@@ -857,4 +1111,211 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn custom_payload_larger_than_distance() {
+        let custom_payload = CustomPayload::from(vec![0xff; 33]);
+        assert!(Distance::try_from(custom_payload).is_err());
+
+        // Trailing zeros still fit in a distance
+        let mut bytes = U256::MAX.as_ssz_bytes();
+        bytes.push(0);
+        assert_eq!(
+            Distance::try_from(CustomPayload::from(bytes)).unwrap(),
+            Distance::from(U256::MAX)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn decoding_arbitrary_bytes_never_panics(bytes in vec(any::<u8>(), 0..2048)) {
+            check_decoding(&bytes)?;
+        }
+
+        #[test]
+        fn decoding_mutated_messages_never_panics(
+            message_index in 0..VALID_MESSAGE_COUNT,
+            mutations in vec(any::<Mutation>(), 1..=8),
+        ) {
+            let mut bytes = valid_messages()[message_index].as_ssz_bytes();
+            for mutation in mutations {
+                mutation.apply(&mut bytes);
+            }
+            check_decoding(&bytes)?;
+        }
+
+        #[test]
+        fn decoding_arbitrary_content_values_never_panics(value in vec(any::<u8>(), 0..4096)) {
+            decode_content_value(&value);
+        }
+    }
+
+    /// Decodes the bytes like the overlay does, checking that the decoded message doesn't take
+    /// more space than the bytes it was decoded from. Decoding errors are expected, panics are
+    /// not.
+    fn check_decoding(bytes: &[u8]) -> Result<(), TestCaseError> {
+        if let Some(message) = decode_like_overlay(bytes) {
+            prop_assert!(message.as_ssz_bytes().len() <= bytes.len());
+        }
+        Ok(())
+    }
+
+    fn decode_content_value(value: &[u8]) {
+        let b256 = B256::from([0x01; 32]);
+        for key in [
+            HistoryContentKey::new_block_header_by_hash(b256),
+            HistoryContentKey::new_block_header_by_number(1),
+            HistoryContentKey::new_block_body(b256),
+            HistoryContentKey::new_block_receipts(b256),
+        ] {
+            let _ = HistoryContentValue::decode(&key, value);
+        }
+        for key in [
+            BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey { block_hash: b256.0 }),
+            BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                start_period: 1,
+                count: 2,
+            }),
+            BeaconContentKey::LightClientFinalityUpdate(LightClientFinalityUpdateKey {
+                finalized_slot: 1,
+            }),
+            BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey {
+                signature_slot: 1,
+            }),
+            BeaconContentKey::HistoricalSummariesWithProof(HistoricalSummariesWithProofKey {
+                epoch: 1,
+            }),
+        ] {
+            let _ = BeaconContentValue::decode(&key, value);
+        }
+        let path = Nibbles::try_from_unpacked_nibbles(&[0x01, 0x02]).unwrap();
+        for key in [
+            StateContentKey::AccountTrieNode(AccountTrieNodeKey {
+                path: path.clone(),
+                node_hash: b256,
+            }),
+            StateContentKey::ContractStorageTrieNode(ContractStorageTrieNodeKey {
+                address_hash: b256,
+                path,
+                node_hash: b256,
+            }),
+            StateContentKey::ContractBytecode(ContractBytecodeKey {
+                address_hash: b256,
+                code_hash: b256,
+            }),
+        ] {
+            let _ = StateContentValue::decode(&key, value);
+        }
+    }
+
+    const VALID_MESSAGE_COUNT: usize = 11;
+
+    /// A valid message of each type, to be mutated.
+    fn valid_messages() -> [Message; VALID_MESSAGE_COUNT] {
+        let enr = SszEnr(Enr::from_str("enr:-HW4QBzimRxkmT18hMKaAL3IcZF1UcfTMPyi3Q1pxwZZbcZVRI8DC5infUAB_UauARLOJtYTxaagKoGmIjzQxO2qUygBgmlkgnY0iXNlY3AyNTZrMaEDymNMrg1JrLQB2KTGtv6MVbcNEVv0AHacwUAPMljNMTg").unwrap());
+        let custom_payload = CustomPayload::from(U256::MAX.as_ssz_bytes());
+        let content_key = HistoryContentKey::new_block_body(B256::from([0x01; 32])).to_bytes();
+        [
+            Message::Ping(Ping {
+                enr_seq: 1,
+                custom_payload: custom_payload.clone(),
+            }),
+            Message::Pong(Pong {
+                enr_seq: 1,
+                custom_payload,
+            }),
+            Message::FindNodes(FindNodes {
+                distances: vec![256, 255],
+            }),
+            Message::Nodes(Nodes {
+                total: 1,
+                enrs: vec![enr.clone(), enr.clone()],
+            }),
+            Message::FindContent(FindContent {
+                content_key: content_key.clone(),
+            }),
+            Message::Content(Content::ConnectionId(1)),
+            Message::Content(Content::Content(RawContentValue::from([0x01; 64]))),
+            Message::Content(Content::Enrs(vec![enr])),
+            Message::Offer(Offer {
+                content_keys: vec![content_key.clone(), content_key],
+            }),
+            Message::Accept(Accept {
+                connection_id: 1,
+                content_keys: BitList::with_capacity(2).unwrap(),
+            }),
+            Message::Accept(Accept {
+                connection_id: 1,
+                content_keys: BitList::with_capacity(0).unwrap(),
+            }),
+        ]
+    }
+
+    /// A random mutation of an encoded message.
+    #[derive(Clone, Debug)]
+    enum Mutation {
+        Replace {
+            index: usize,
+            byte: u8,
+        },
+        Insert {
+            index: usize,
+            byte: u8,
+        },
+        Remove {
+            index: usize,
+        },
+        /// Overwrites what could be a length prefix or offset.
+        OverwriteU32 {
+            index: usize,
+            value: u32,
+        },
+        Truncate {
+            index: usize,
+        },
+    }
+
+    impl Arbitrary for Mutation {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            prop_oneof![
+                (any::<usize>(), any::<u8>())
+                    .prop_map(|(index, byte)| Mutation::Replace { index, byte }),
+                (any::<usize>(), any::<u8>())
+                    .prop_map(|(index, byte)| Mutation::Insert { index, byte }),
+                any::<usize>().prop_map(|index| Mutation::Remove { index }),
+                (any::<usize>(), any::<u32>())
+                    .prop_map(|(index, value)| Mutation::OverwriteU32 { index, value }),
+                any::<usize>().prop_map(|index| Mutation::Truncate { index }),
+            ]
+            .boxed()
+        }
+    }
+
+    impl Mutation {
+        /// Applies the mutation, at its index modulo the length of the bytes.
+        fn apply(self, bytes: &mut Vec<u8>) {
+            let position = |index: usize, len: usize| index % (len + 1);
+            match self {
+                Mutation::Replace { index, byte } if !bytes.is_empty() => {
+                    let index = index % bytes.len();
+                    bytes[index] = byte;
+                }
+                Mutation::Insert { index, byte } => {
+                    bytes.insert(position(index, bytes.len()), byte);
+                }
+                Mutation::Remove { index } if !bytes.is_empty() => {
+                    bytes.remove(index % bytes.len());
+                }
+                Mutation::OverwriteU32 { index, value } if bytes.len() >= 4 => {
+                    let index = index % (bytes.len() - 3);
+                    bytes[index..index + 4].copy_from_slice(&value.to_le_bytes());
+                }
+                Mutation::Truncate { index } => bytes.truncate(position(index, bytes.len())),
+                _ => {}
+            }
+        }
+    }
 }
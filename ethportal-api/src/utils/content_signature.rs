@@ -0,0 +1,159 @@
+//! Signatures over content, which attest to its provenance when it's stored over JSON-RPC.
+//!
+//! The content is signed with a recoverable secp256k1 ECDSA signature over the keccak256 hash of
+//! [CONTENT_SIGNATURE_DOMAIN], followed by the content key and the content value, each prefixed with
+//! its length as a big-endian u64. The signature is 65 bytes long: `r || s || recovery_id`.
+
+use alloy::primitives::{keccak256, Bytes, B256};
+use secp256k1::{
+    ecdsa::{RecoverableSignature, RecoveryId},
+    Message, SECP256K1,
+};
+pub use secp256k1::{PublicKey, SecretKey};
+
+use crate::utils::bytes::hex_decode;
+
+/// The length of a content signature, in bytes.
+pub const CONTENT_SIGNATURE_LEN: usize = 65;
+
+/// The domain tag that is hashed before the content, so that content signatures can't be reused
+/// as signatures over other messages.
+pub const CONTENT_SIGNATURE_DOMAIN: &[u8] = b"portal-content-signature-v1";
+
+/// Returns the hash that is signed for the content.
+pub fn content_signing_hash(content_key: &[u8], content_value: &[u8]) -> B256 {
+    let mut preimage = Vec::with_capacity(
+        CONTENT_SIGNATURE_DOMAIN.len() + 16 + content_key.len() + content_value.len(),
+    );
+    preimage.extend_from_slice(CONTENT_SIGNATURE_DOMAIN);
+    preimage.extend_from_slice(&(content_key.len() as u64).to_be_bytes());
+    preimage.extend_from_slice(content_key);
+    preimage.extend_from_slice(&(content_value.len() as u64).to_be_bytes());
+    preimage.extend_from_slice(content_value);
+    keccak256(preimage)
+}
+
+/// Signs the content with the secret key.
+pub fn sign_content(secret_key: &SecretKey, content_key: &[u8], content_value: &[u8]) -> Bytes {
+    let message = Message::from_digest(content_signing_hash(content_key, content_value).0);
+    let (recovery_id, signature) = SECP256K1
+        .sign_ecdsa_recoverable(&message, secret_key)
+        .serialize_compact();
+    let mut bytes = signature.to_vec();
+    bytes.push(recovery_id.to_i32() as u8);
+    bytes.into()
+}
+
+/// Returns the public key that signed the content.
+pub fn recover_content_signer(
+    content_key: &[u8],
+    content_value: &[u8],
+    signature: &[u8],
+) -> Result<PublicKey, String> {
+    if signature.len() != CONTENT_SIGNATURE_LEN {
+        return Err(format!(
+            "Invalid content signature length: {}, expected {CONTENT_SIGNATURE_LEN}",
+            signature.len()
+        ));
+    }
+    let recovery_id = RecoveryId::from_i32(signature[64] as i32)
+        .map_err(|err| format!("Invalid content signature recovery id: {err}"))?;
+    let signature = RecoverableSignature::from_compact(&signature[..64], recovery_id)
+        .map_err(|err| format!("Invalid content signature: {err}"))?;
+    let message = Message::from_digest(content_signing_hash(content_key, content_value).0);
+    SECP256K1
+        .recover_ecdsa(&message, &signature)
+        .map_err(|err| format!("Unable to recover content signer: {err}"))
+}
+
+/// Verifies that the content is signed by one of the trusted signers.
+pub fn verify_content_signature(
+    trusted_signers: &[PublicKey],
+    content_key: &[u8],
+    content_value: &[u8],
+    signature: &[u8],
+) -> Result<(), String> {
+    let signer = recover_content_signer(content_key, content_value, signature)?;
+    if !trusted_signers.contains(&signer) {
+        return Err(format!("Content is signed by untrusted signer {signer}"));
+    }
+    Ok(())
+}
+
+/// Parses a hex encoded public key, with 0x prefix, in compressed or uncompressed form.
+pub fn parse_public_key(public_key: &str) -> Result<PublicKey, String> {
+    let bytes = hex_decode(public_key).map_err(|err| format!("Invalid public key hex: {err}"))?;
+    PublicKey::from_slice(&bytes).map_err(|err| format!("Invalid public key: {err}"))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::utils::bytes::hex_encode;
+
+    fn keypair(seed: u8) -> (SecretKey, PublicKey) {
+        let secret_key = SecretKey::from_slice(&[seed; 32]).unwrap();
+        (
+            secret_key,
+            PublicKey::from_secret_key(SECP256K1, &secret_key),
+        )
+    }
+
+    #[test]
+    fn signed_content_is_verified() {
+        let (secret_key, public_key) = keypair(1);
+        let (_, other_public_key) = keypair(2);
+        let signature = sign_content(&secret_key, &[0, 1], &[2, 3]);
+        assert_eq!(signature.len(), CONTENT_SIGNATURE_LEN);
+
+        assert_eq!(
+            recover_content_signer(&[0, 1], &[2, 3], &signature),
+            Ok(public_key)
+        );
+        assert!(verify_content_signature(&[public_key], &[0, 1], &[2, 3], &signature).is_ok());
+        assert!(
+            verify_content_signature(&[other_public_key], &[0, 1], &[2, 3], &signature)
+                .unwrap_err()
+                .contains("untrusted signer")
+        );
+        assert!(verify_content_signature(&[], &[0, 1], &[2, 3], &signature).is_err());
+        // The signature doesn't verify against other content.
+        assert!(verify_content_signature(&[public_key], &[0, 1], &[2, 4], &signature).is_err());
+    }
+
+    #[test]
+    fn signing_hash_separates_key_and_value() {
+        // Moving a byte between the content key and the content value changes the hash.
+        assert_ne!(
+            content_signing_hash(&[0, 1], &[2, 3]),
+            content_signing_hash(&[0], &[1, 2, 3])
+        );
+        // The hash isn't the plain hash of the concatenated content.
+        assert_ne!(
+            content_signing_hash(&[0, 1], &[2, 3]),
+            keccak256([0, 1, 2, 3])
+        );
+    }
+
+    #[test]
+    fn invalid_signature_is_rejected() {
+        let err = recover_content_signer(&[0], &[1], &[0; 64]).unwrap_err();
+        assert_eq!(
+            err,
+            "Invalid content signature length: 64, expected 65".to_string()
+        );
+        assert!(recover_content_signer(&[0], &[1], &[0; 65]).is_err());
+    }
+
+    #[test]
+    fn public_key_is_parsed() {
+        let (_, public_key) = keypair(1);
+        let compressed = hex_encode(public_key.serialize());
+        let uncompressed = hex_encode(public_key.serialize_uncompressed());
+        assert_eq!(parse_public_key(&compressed), Ok(public_key));
+        assert_eq!(parse_public_key(&uncompressed), Ok(public_key));
+        assert!(parse_public_key("0x0102").is_err());
+        assert!(parse_public_key("not hex").is_err());
+    }
+}
@@ -1,3 +1,4 @@
 pub mod bytes;
+pub mod content_signature;
 pub mod roots;
 pub mod serde;
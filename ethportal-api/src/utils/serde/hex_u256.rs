@@ -0,0 +1,19 @@
+//! Serializes a [U256] as a 0x-prefixed, 32-byte hex string, e.g. a data radius. Any hex or
+//! decimal string is accepted when deserializing.
+
+use alloy::primitives::{B256, U256};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub fn serialize<S>(value: &U256, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    B256::from(*value).serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<U256, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    U256::deserialize(deserializer)
+}
@@ -1,2 +1,3 @@
 pub mod hex_fixed_vec;
+pub mod hex_u256;
 pub mod hex_var_list;
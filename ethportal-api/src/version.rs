@@ -1,4 +1,12 @@
+use crate::types::network::Network;
+
 /// Returns the trin version and git revision.
 pub const fn get_trin_version() -> &'static str {
     crate::build_info::short_commit()
 }
+
+/// Returns the client version, as reported by `web3_clientVersion`, including the network that
+/// the node is on.
+pub fn get_client_version(network: Network) -> String {
+    format!("trin v{} ({network})", get_trin_version())
+}
@@ -50,7 +50,7 @@ async fn launch_node(trin_config: TrinConfig) -> anyhow::Result<PeertestNode> {
         .unwrap();
 
     Ok(PeertestNode {
-        enr: ipc_client.node_info().await.unwrap().enr,
+        enr: ipc_client.node_info(None).await.unwrap().enr,
         ipc_client,
         rpc_handle,
     })
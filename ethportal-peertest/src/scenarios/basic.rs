@@ -1,11 +1,21 @@
+use std::net::Ipv4Addr;
+
 use alloy::primitives::{B256, U256};
+use discv5::enr::CombinedKey;
 use ethportal_api::{
-    types::{distance::Distance, network::Subnetwork},
-    version::get_trin_version,
+    types::{
+        distance::Distance,
+        enr::{enr_multiaddrs, Enr},
+        network::{Network, Subnetwork},
+        portal::{EnrSource, LookupEnrInfo},
+    },
+    version::get_client_version,
     BeaconNetworkApiClient, ContentValue, Discv5ApiClient, HistoryContentKey,
-    HistoryNetworkApiClient, StateNetworkApiClient, Web3ApiClient,
+    HistoryNetworkApiClient, PortalApiClient, StateNetworkApiClient, Web3ApiClient,
 };
-use jsonrpsee::async_client::Client;
+use jsonrpsee::{async_client::Client, core::ClientError};
+use portalnet::discovery::ENR_PORTAL_NETWORK_KEY;
+use rpc::{TIMEOUT_ERROR_CODE, UNREACHABLE_ADDRESS_ERROR_CODE};
 use ssz::Encode;
 use tracing::info;
 
@@ -14,26 +24,40 @@ use crate::{utils::fixture_header_by_hash, Peertest, PeertestNode};
 pub async fn test_web3_client_version(target: &Client) {
     info!("Testing web3_clientVersion");
     let result = target.client_version().await.unwrap();
-    let expected_version = format!("trin v{}", get_trin_version());
+    let expected_version = get_client_version(Network::Mainnet);
     assert_eq!(result, expected_version);
 }
 
+pub async fn test_portal_node_overview(target: &Client) {
+    info!("Testing portal_nodeOverview");
+    let result = target.node_overview().await.unwrap();
+    let node_info = target.node_info(None).await.unwrap();
+    assert_eq!(result.client_version, get_client_version(Network::Mainnet));
+    assert_eq!(result.network, Network::Mainnet.to_string());
+    assert_eq!(result.node_id, node_info.node_id);
+    assert!(result
+        .subnetworks
+        .contains(&Subnetwork::History.to_cli_arg()));
+    // The external address of the test nodes doesn't change.
+    assert!(result.address_changed_at.is_none());
+}
+
 pub async fn test_discv5_node_info(peertest: &Peertest) {
     info!("Testing discv5_nodeInfo");
-    let result = peertest.bootnode.ipc_client.node_info().await.unwrap();
+    let result = peertest.bootnode.ipc_client.node_info(None).await.unwrap();
     assert_eq!(result.enr, peertest.bootnode.enr);
 }
 
 pub async fn test_discv5_routing_table_info(target: &Client) {
     info!("Testing discv5_routingTableInfo");
-    let node_info = target.node_info().await.unwrap();
+    let node_info = target.node_info(None).await.unwrap();
     let result = Discv5ApiClient::routing_table_info(target).await.unwrap();
     assert_eq!(result.local_node_id, node_info.node_id);
 }
 
 pub async fn test_routing_table_info(subnetwork: Subnetwork, target: &Client) {
     info!("Testing routing_table_info for {subnetwork}");
-    let node_info = target.node_info().await.unwrap();
+    let node_info = target.node_info(None).await.unwrap();
     let result = match subnetwork {
         Subnetwork::Beacon => BeaconNetworkApiClient::routing_table_info(target),
         Subnetwork::History => HistoryNetworkApiClient::routing_table_info(target),
@@ -45,6 +69,20 @@ pub async fn test_routing_table_info(subnetwork: Subnetwork, target: &Client) {
     assert_eq!(result.local_node_id, node_info.node_id);
 }
 
+pub async fn test_beacon_replacement_cache(target: &Client) {
+    info!("Testing portal_beaconReplacementCache");
+    let node_info = target.node_info(None).await.unwrap();
+    let routing_table = BeaconNetworkApiClient::routing_table_info(target)
+        .await
+        .unwrap();
+    let result = target.replacement_cache().await.unwrap();
+    assert_eq!(result.local_node_id, node_info.node_id);
+    assert_eq!(
+        result.buckets.buckets.len(),
+        routing_table.buckets.buckets.len()
+    );
+}
+
 pub async fn test_radius(subnetwork: Subnetwork, target: &Client) {
     info!("Testing radius for {subnetwork}");
     let result = match subnetwork {
@@ -57,7 +95,7 @@ pub async fn test_radius(subnetwork: Subnetwork, target: &Client) {
     .unwrap();
     assert_eq!(
         result,
-        U256::from_be_slice(Distance::MAX.as_ssz_bytes().as_slice())
+        B256::from(U256::from_be_slice(Distance::MAX.as_ssz_bytes().as_slice()))
     );
 }
 
@@ -65,9 +103,9 @@ pub async fn test_add_enr(subnetwork: Subnetwork, target: &Client, peertest: &Pe
     info!("Testing add_enr for {subnetwork}");
     let bootnode_enr = peertest.bootnode.enr.clone();
     let result = match subnetwork {
-        Subnetwork::Beacon => BeaconNetworkApiClient::add_enr(target, bootnode_enr),
-        Subnetwork::History => HistoryNetworkApiClient::add_enr(target, bootnode_enr),
-        Subnetwork::State => StateNetworkApiClient::add_enr(target, bootnode_enr),
+        Subnetwork::Beacon => BeaconNetworkApiClient::add_enr(target, bootnode_enr.into(), None),
+        Subnetwork::History => HistoryNetworkApiClient::add_enr(target, bootnode_enr.into(), None),
+        Subnetwork::State => StateNetworkApiClient::add_enr(target, bootnode_enr.into(), None),
         _ => panic!("Unexpected subnetwork: {subnetwork}"),
     }
     .await
@@ -79,14 +117,32 @@ pub async fn test_get_enr(subnetwork: Subnetwork, target: &Client, peertest: &Pe
     info!("Testing get_enr for {subnetwork}");
     let node_id = peertest.bootnode.enr.node_id();
     let result = match subnetwork {
-        Subnetwork::Beacon => BeaconNetworkApiClient::get_enr(target, node_id),
-        Subnetwork::History => HistoryNetworkApiClient::get_enr(target, node_id),
-        Subnetwork::State => StateNetworkApiClient::get_enr(target, node_id),
+        Subnetwork::Beacon => BeaconNetworkApiClient::get_enr(target, node_id, Some(true)),
+        Subnetwork::History => HistoryNetworkApiClient::get_enr(target, node_id, Some(true)),
+        Subnetwork::State => StateNetworkApiClient::get_enr(target, node_id, Some(true)),
+        _ => panic!("Unexpected subnetwork: {subnetwork}"),
+    }
+    .await
+    .unwrap();
+    assert_eq!(
+        result,
+        LookupEnrInfo::WithMultiaddrs {
+            enr: peertest.bootnode.enr.clone(),
+            multiaddrs: enr_multiaddrs(&peertest.bootnode.enr),
+        }
+    );
+
+    let result = match subnetwork {
+        Subnetwork::Beacon => BeaconNetworkApiClient::get_enr_info(target, node_id, None),
+        Subnetwork::History => HistoryNetworkApiClient::get_enr_info(target, node_id, None),
+        Subnetwork::State => StateNetworkApiClient::get_enr_info(target, node_id, None),
         _ => panic!("Unexpected subnetwork: {subnetwork}"),
     }
     .await
     .unwrap();
-    assert_eq!(result, peertest.bootnode.enr);
+    assert_eq!(result.enr, peertest.bootnode.enr);
+    assert_eq!(result.source, EnrSource::RoutingTable);
+    assert_eq!(result.multiaddrs, None);
 }
 
 pub async fn test_delete_enr(subnetwork: Subnetwork, target: &Client, peertest: &Peertest) {
@@ -108,14 +164,14 @@ pub async fn test_lookup_enr(subnetwork: Subnetwork, peertest: &Peertest) {
     let target = &peertest.bootnode.ipc_client;
     let node_id = peertest.nodes[0].enr.node_id();
     let result = match subnetwork {
-        Subnetwork::Beacon => BeaconNetworkApiClient::lookup_enr(target, node_id),
-        Subnetwork::History => HistoryNetworkApiClient::lookup_enr(target, node_id),
-        Subnetwork::State => StateNetworkApiClient::lookup_enr(target, node_id),
+        Subnetwork::Beacon => BeaconNetworkApiClient::lookup_enr(target, node_id, None),
+        Subnetwork::History => HistoryNetworkApiClient::lookup_enr(target, node_id, None),
+        Subnetwork::State => StateNetworkApiClient::lookup_enr(target, node_id, None),
         _ => panic!("Unexpected subnetwork: {subnetwork}"),
     }
     .await
     .unwrap();
-    assert_eq!(result, peertest.nodes[0].enr);
+    assert_eq!(result, LookupEnrInfo::Enr(peertest.nodes[0].enr.clone()));
 }
 
 pub async fn test_ping(subnetwork: Subnetwork, target: &Client, peertest: &Peertest) {
@@ -123,9 +179,9 @@ pub async fn test_ping(subnetwork: Subnetwork, target: &Client, peertest: &Peert
     let bootnode_enr = peertest.bootnode.enr.clone();
     let bootnode_sequence = bootnode_enr.seq();
     let result = match subnetwork {
-        Subnetwork::Beacon => BeaconNetworkApiClient::ping(target, bootnode_enr),
-        Subnetwork::History => HistoryNetworkApiClient::ping(target, bootnode_enr),
-        Subnetwork::State => StateNetworkApiClient::ping(target, bootnode_enr),
+        Subnetwork::Beacon => BeaconNetworkApiClient::ping(target, bootnode_enr.into()),
+        Subnetwork::History => HistoryNetworkApiClient::ping(target, bootnode_enr.into()),
+        Subnetwork::State => StateNetworkApiClient::ping(target, bootnode_enr.into()),
         _ => panic!("Unexpected subnetwork: {subnetwork}"),
     }
     .await
@@ -137,21 +193,145 @@ pub async fn test_ping(subnetwork: Subnetwork, target: &Client, peertest: &Peert
     assert_eq!(result.enr_seq, bootnode_sequence);
 }
 
+pub async fn test_ping_errors(subnetwork: Subnetwork, target: &Client) {
+    info!("Testing ping errors for {subnetwork}");
+    let ping = |enr: Enr| async move {
+        match subnetwork {
+            Subnetwork::Beacon => BeaconNetworkApiClient::ping(target, enr.into()).await,
+            Subnetwork::History => HistoryNetworkApiClient::ping(target, enr.into()).await,
+            Subnetwork::State => StateNetworkApiClient::ping(target, enr.into()).await,
+            _ => panic!("Unexpected subnetwork: {subnetwork}"),
+        }
+        .unwrap_err()
+    };
+
+    // ENR without an ip address and udp port
+    let enr_without_address = Enr::builder()
+        .build(&CombinedKey::generate_secp256k1())
+        .unwrap();
+    assert_error_code(
+        ping(enr_without_address).await,
+        UNREACHABLE_ADDRESS_ERROR_CODE,
+    );
+
+    // ENR of a peer that doesn't exist
+    let nonexistent_enr = Enr::builder()
+        .ip4(Ipv4Addr::LOCALHOST)
+        .udp4(8997)
+        .build(&CombinedKey::generate_secp256k1())
+        .unwrap();
+    assert_error_code(ping(nonexistent_enr).await, TIMEOUT_ERROR_CODE);
+}
+
+fn assert_error_code(error: ClientError, expected_code: i32) {
+    match error {
+        ClientError::Call(error) => assert_eq!(error.code(), expected_code, "{error}"),
+        error => panic!("Expected call error with code {expected_code}, got: {error}"),
+    }
+}
+
 pub async fn test_ping_cross_network(mainnet_target: &Client, angelfood_node: &PeertestNode) {
     info!("Testing ping for history cross mainnet and angelfood discv5 protocol id");
     let angelfood_enr = angelfood_node.enr.clone();
-    if let Ok(pong) = HistoryNetworkApiClient::ping(mainnet_target, angelfood_enr).await {
+    if let Ok(pong) = HistoryNetworkApiClient::ping(mainnet_target, angelfood_enr.into()).await {
         panic!("Expected ping to fail as mainnet/angelfood history nodes shouldn't be able to communicate {pong:?}");
     };
 }
 
+pub async fn test_add_bootnode(target: &Client, peertest: &Peertest) {
+    info!("Testing portal_addBootnode");
+    // ENR of a peer that doesn't exist isn't persisted, unless forced
+    let unreachable_enr = Enr::builder()
+        .ip4(Ipv4Addr::LOCALHOST)
+        .udp4(8996)
+        .build(&CombinedKey::generate_secp256k1())
+        .unwrap();
+    let result = target
+        .add_bootnode(unreachable_enr.to_base64(), None)
+        .await
+        .unwrap();
+    assert!(!result.persisted);
+    let bonds = [&result.history, &result.state, &result.beacon];
+    assert!(bonds.iter().any(|bond| bond.is_some()));
+    assert!(bonds.into_iter().flatten().all(|bond| !bond.bonded));
+    assert!(!target
+        .remove_bootnode(unreachable_enr.node_id())
+        .await
+        .unwrap());
+
+    let result = target
+        .add_bootnode(unreachable_enr.to_base64(), Some(true))
+        .await
+        .unwrap();
+    assert!(result.persisted);
+    assert!(target
+        .remove_bootnode(unreachable_enr.node_id())
+        .await
+        .unwrap());
+
+    // ENR that advertises a different network is refused
+    let angelfood_enr = Enr::builder()
+        .ip4(Ipv4Addr::LOCALHOST)
+        .udp4(8996)
+        .add_value(
+            ENR_PORTAL_NETWORK_KEY,
+            &Network::Angelfood.to_string().as_bytes(),
+        )
+        .build(&CombinedKey::generate_secp256k1())
+        .unwrap();
+    assert!(target
+        .add_bootnode(angelfood_enr.to_base64(), Some(true))
+        .await
+        .is_err());
+    assert!(
+        HistoryNetworkApiClient::add_enr(target, angelfood_enr.clone().into(), None)
+            .await
+            .is_err()
+    );
+
+    // ENR without the `enr:` prefix is accepted
+    let bootnode_enr = peertest.bootnode.enr.clone();
+    let result = target
+        .add_bootnode(bootnode_enr.to_base64().replacen("enr:", "", 1), None)
+        .await
+        .unwrap();
+    assert!(result.persisted);
+    assert!(result.history.unwrap().bonded);
+    assert!(target
+        .remove_bootnode(bootnode_enr.node_id())
+        .await
+        .unwrap());
+}
+
+pub async fn test_beacon_verify_peers(target: &Client, peertest: &Peertest) {
+    info!("Testing portal_beaconVerifyPeers");
+    let bootnode_enr = peertest.bootnode.enr.clone();
+    assert!(
+        BeaconNetworkApiClient::add_enr(target, bootnode_enr.clone().into(), None)
+            .await
+            .unwrap()
+    );
+    let result = BeaconNetworkApiClient::verify_peers(target, 16, false)
+        .await
+        .unwrap();
+    assert!(result.responded.contains(&bootnode_enr.node_id()));
+    assert!(!result.unresponsive.contains(&bootnode_enr.node_id()));
+    assert!(result.evicted.is_empty());
+}
+
 pub async fn test_find_nodes(subnetwork: Subnetwork, target: &Client, peertest: &Peertest) {
     info!("Testing find_nodes for {subnetwork}");
     let bootnode_enr = peertest.bootnode.enr.clone();
     let result = match subnetwork {
-        Subnetwork::Beacon => BeaconNetworkApiClient::find_nodes(target, bootnode_enr, vec![256]),
-        Subnetwork::History => HistoryNetworkApiClient::find_nodes(target, bootnode_enr, vec![256]),
-        Subnetwork::State => StateNetworkApiClient::find_nodes(target, bootnode_enr, vec![256]),
+        Subnetwork::Beacon => {
+            BeaconNetworkApiClient::find_nodes(target, bootnode_enr.into(), vec![256], None)
+        }
+        Subnetwork::History => {
+            HistoryNetworkApiClient::find_nodes(target, bootnode_enr.into(), vec![256], None)
+        }
+        Subnetwork::State => {
+            StateNetworkApiClient::find_nodes(target, bootnode_enr.into(), vec![256], None)
+        }
         _ => panic!("Unexpected subnetwork: {subnetwork}"),
     }
     .await
@@ -167,9 +347,15 @@ pub async fn test_find_nodes_zero_distance(
     info!("Testing find_nodes with zero distance for {subnetwork}");
     let bootnode_enr = peertest.bootnode.enr.clone();
     let result = match subnetwork {
-        Subnetwork::Beacon => BeaconNetworkApiClient::find_nodes(target, bootnode_enr, vec![0]),
-        Subnetwork::History => HistoryNetworkApiClient::find_nodes(target, bootnode_enr, vec![0]),
-        Subnetwork::State => StateNetworkApiClient::find_nodes(target, bootnode_enr, vec![0]),
+        Subnetwork::Beacon => {
+            BeaconNetworkApiClient::find_nodes(target, bootnode_enr.into(), vec![0], None)
+        }
+        Subnetwork::History => {
+            HistoryNetworkApiClient::find_nodes(target, bootnode_enr.into(), vec![0], None)
+        }
+        Subnetwork::State => {
+            StateNetworkApiClient::find_nodes(target, bootnode_enr.into(), vec![0], None)
+        }
         _ => panic!("Unexpected subnetwork: {subnetwork}"),
     }
     .await
@@ -180,16 +366,17 @@ pub async fn test_find_nodes_zero_distance(
 pub async fn test_history_store(target: &Client) {
     info!("Testing portal_historyStore");
     let (content_key, content_value) = fixture_header_by_hash();
-    let result = HistoryNetworkApiClient::store(target, content_key, content_value.encode())
-        .await
-        .unwrap();
+    let result =
+        HistoryNetworkApiClient::store(target, content_key, content_value.encode(), None, None)
+            .await
+            .unwrap();
     assert!(result);
 }
 
 pub async fn test_history_local_content_absent(target: &Client) {
     info!("Testing portal_historyLocalContent absent");
     let content_key = HistoryContentKey::new_block_header_by_hash(B256::random());
-    let error = HistoryNetworkApiClient::local_content(target, content_key)
+    let error = HistoryNetworkApiClient::local_content(target, content_key, None, None)
         .await
         .unwrap_err();
     assert!(error
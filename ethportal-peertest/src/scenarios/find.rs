@@ -51,11 +51,12 @@ pub async fn test_find_content_return_enr(target: &Client, peertest: &Peertest)
     match HistoryNetworkApiClient::get_enr(
         &peertest.bootnode.ipc_client,
         peertest.nodes[0].enr.node_id(),
+        None,
     )
     .await
     {
         Ok(response) => {
-            if response != peertest.nodes[0].enr.clone() {
+            if *response.enr() != peertest.nodes[0].enr {
                 panic!("Response from GetEnr didn't return expected Enr");
             }
         }
@@ -64,7 +65,7 @@ pub async fn test_find_content_return_enr(target: &Client, peertest: &Peertest)
 
     let result = HistoryNetworkApiClient::find_content(
         target,
-        peertest.bootnode.enr.clone(),
+        peertest.bootnode.enr.clone().into(),
         content_key.clone(),
     )
     .await;
@@ -88,6 +89,8 @@ pub async fn test_trace_get_content(peertest: &Peertest) {
         &peertest.bootnode.ipc_client,
         content_key.clone(),
         content_value.encode(),
+        None,
+        None,
     )
     .await
     .unwrap();
@@ -161,6 +164,8 @@ pub async fn test_trace_get_content_local_db(peertest: &Peertest) {
         &peertest.bootnode.ipc_client,
         content_key.clone(),
         content_value.encode(),
+        None,
+        None,
     )
     .await
     .unwrap();
@@ -2,7 +2,6 @@ pub mod basic;
 pub mod bridge;
 pub mod eth_rpc;
 pub mod find;
-pub mod gossip;
 pub mod offer_accept;
 pub mod paginate;
 pub mod state;
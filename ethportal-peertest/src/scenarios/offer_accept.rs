@@ -31,8 +31,11 @@ pub async fn test_offer(peertest: &Peertest, target: &Client) {
     let (content_key, content_value) = fixture_header_by_hash();
     let result = target
         .offer(
-            Enr::from_str(&peertest.bootnode.enr.to_base64()).unwrap(),
+            Enr::from_str(&peertest.bootnode.enr.to_base64())
+                .unwrap()
+                .into(),
             vec![(content_key.clone(), content_value.encode())],
+            None,
         )
         .await
         .unwrap();
@@ -47,6 +50,41 @@ pub async fn test_offer(peertest: &Peertest, target: &Client) {
     );
 }
 
+pub async fn test_offer_many(peertest: &Peertest, target: &Client) {
+    info!("Testing OfferMany/ACCEPT flow");
+
+    // The bootnode already has the first header, so it only accepts the second one
+    let (stored_key, stored_value) = fixture_header_by_hash_with_proof_15040641();
+    assert!(peertest
+        .bootnode
+        .ipc_client
+        .store(stored_key.clone(), stored_value.encode(), None, None)
+        .await
+        .unwrap());
+    let (content_key, content_value) = fixture_header_by_hash_with_proof_15040708();
+
+    let result = target
+        .offer_many(
+            Enr::from_str(&peertest.bootnode.enr.to_base64())
+                .unwrap()
+                .into(),
+            vec![
+                (stored_key.clone(), stored_value.encode()),
+                (content_key.clone(), content_value.encode()),
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.accepted, vec![content_key.clone()]);
+    assert_eq!(result.declined, vec![stored_key]);
+
+    // Check if the stored content value in bootnode's DB matches the offered
+    assert_eq!(
+        content_value,
+        wait_for_history_content(&peertest.bootnode.ipc_client, content_key).await,
+    );
+}
+
 pub async fn test_offer_with_trace(peertest: &Peertest, target: &Client) {
     info!("Testing Offer/ACCEPT flow with trace");
 
@@ -55,7 +93,7 @@ pub async fn test_offer_with_trace(peertest: &Peertest, target: &Client) {
     let store_result = peertest
         .bootnode
         .ipc_client
-        .store(content_key.clone(), content_value.encode())
+        .store(content_key.clone(), content_value.encode(), None, None)
         .await
         .unwrap();
     assert!(store_result);
@@ -64,7 +102,9 @@ pub async fn test_offer_with_trace(peertest: &Peertest, target: &Client) {
     let (content_key, content_value) = fixture_block_body();
     let result = target
         .trace_offer(
-            Enr::from_str(&peertest.bootnode.enr.to_base64()).unwrap(),
+            Enr::from_str(&peertest.bootnode.enr.to_base64())
+                .unwrap()
+                .into(),
             content_key.clone(),
             content_value.encode(),
         )
@@ -93,8 +133,9 @@ pub async fn test_offer_propagates_gossip(peertest: &Peertest, target: &Client)
     // use offer which means content will *not* be stored in the target's local db
     target
         .offer(
-            peertest.bootnode.enr.clone(),
+            peertest.bootnode.enr.clone().into(),
             vec![(content_key.clone(), content_value.encode())],
+            None,
         )
         .await
         .unwrap();
@@ -123,14 +164,22 @@ pub async fn test_offer_propagates_gossip_with_large_content(peertest: &Peertest
 
     // Store content to offer in the testnode db
     let store_result = target
-        .store(header_key, header_value.encode())
+        .store(header_key, header_value.encode(), None, None)
         .await
         .unwrap();
     assert!(store_result);
     target
         .offer(
-            peertest.bootnode.ipc_client.node_info().await.unwrap().enr,
+            peertest
+                .bootnode
+                .ipc_client
+                .node_info(None)
+                .await
+                .unwrap()
+                .enr
+                .into(),
             vec![(body_key.clone(), body_value.encode())],
+            None,
         )
         .await
         .unwrap();
@@ -164,8 +213,9 @@ pub async fn test_offer_propagates_gossip_multiple_content_values(
     // offer header content for validation later
     target
         .offer(
-            peertest.bootnode.enr.clone(),
+            peertest.bootnode.enr.clone().into(),
             vec![(header_key.clone(), header_value.encode())],
+            None,
         )
         .await
         .unwrap();
@@ -187,11 +237,19 @@ pub async fn test_offer_propagates_gossip_multiple_content_values(
     // here everythings stored in target
     target
         .offer(
-            peertest.bootnode.ipc_client.node_info().await.unwrap().enr,
+            peertest
+                .bootnode
+                .ipc_client
+                .node_info(None)
+                .await
+                .unwrap()
+                .enr
+                .into(),
             vec![
                 (body_key.clone(), body_value.encode()),
                 (receipts_key.clone(), receipts_value.encode()),
             ],
+            None,
         )
         .await
         .unwrap();
@@ -238,7 +296,7 @@ pub async fn test_offer_propagates_gossip_multiple_large_content_values(
 
     // Store content to offer in the testnode db
     let store_result = target
-        .store(header_key_1.clone(), header_value_1.encode())
+        .store(header_key_1.clone(), header_value_1.encode(), None, None)
         .await
         .unwrap();
     assert!(store_result);
@@ -249,20 +307,28 @@ pub async fn test_offer_propagates_gossip_multiple_large_content_values(
 
     // Store content to offer in the testnode db
     let store_result = target
-        .store(header_key_2.clone(), header_value_2.encode())
+        .store(header_key_2.clone(), header_value_2.encode(), None, None)
         .await
         .unwrap();
     assert!(store_result);
 
     target
         .offer(
-            peertest.bootnode.ipc_client.node_info().await.unwrap().enr,
+            peertest
+                .bootnode
+                .ipc_client
+                .node_info(None)
+                .await
+                .unwrap()
+                .enr
+                .into(),
             vec![
                 (body_key_1.clone(), body_value_1.encode()),
                 (receipts_key_1.clone(), receipts_value_1.encode()),
                 (body_key_2.clone(), body_value_2.encode()),
                 (receipts_key_2.clone(), receipts_value_2.encode()),
             ],
+            None,
         )
         .await
         .unwrap();
@@ -360,7 +426,7 @@ pub async fn test_offer_concurrent_utp_transfer_limit(peertest: &Peertest, targe
         let store_result = peertest
             .bootnode
             .ipc_client
-            .store(header_key.clone(), header_value.encode())
+            .store(header_key.clone(), header_value.encode(), None, None)
             .await
             .unwrap();
         assert!(store_result);
@@ -378,7 +444,13 @@ pub async fn test_offer_concurrent_utp_transfer_limit(peertest: &Peertest, targe
     }
 
     // send offers
-    let peer_enr = peertest.bootnode.ipc_client.node_info().await.unwrap().enr;
+    let peer_enr = peertest
+        .bootnode
+        .ipc_client
+        .node_info(None)
+        .await
+        .unwrap()
+        .enr;
     let mut handles = vec![];
     for (key, value) in test_data {
         let peer_enr_clone = peer_enr.clone();
@@ -386,7 +458,7 @@ pub async fn test_offer_concurrent_utp_transfer_limit(peertest: &Peertest, targe
         let body_handle = tokio::spawn(async move {
             let _result = HistoryNetworkApiClient::trace_offer(
                 &target_clone,
-                peer_enr_clone,
+                peer_enr_clone.into(),
                 key.clone(),
                 value,
             )
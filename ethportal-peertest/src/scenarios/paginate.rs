@@ -6,8 +6,11 @@ use crate::{utils::fixture_header_by_hash, Peertest};
 pub async fn test_paginate_local_storage(peertest: &Peertest) {
     let ipc_client = &peertest.bootnode.ipc_client;
     // Test paginate with empty storage
-    let result = ipc_client.paginate_local_content_keys(0, 1).await.unwrap();
-    assert_eq!(result.total_entries, 0);
+    let result = ipc_client
+        .paginate_local_content_keys(0, 1, None, None)
+        .await
+        .unwrap();
+    assert_eq!(result.total_entries, Some(0));
     assert_eq!(result.content_keys.len(), 0);
 
     let mut content_keys: Vec<String> = (0..20_u8)
@@ -23,6 +26,8 @@ pub async fn test_paginate_local_storage(peertest: &Peertest) {
             .store(
                 serde_json::from_str(&content_key).unwrap(),
                 content_value.encode(),
+                None,
+                None,
             )
             .await
             .unwrap();
@@ -32,8 +37,11 @@ pub async fn test_paginate_local_storage(peertest: &Peertest) {
     content_keys.sort();
 
     // Test paginate
-    let result = ipc_client.paginate_local_content_keys(0, 1).await.unwrap();
-    assert_eq!(result.total_entries, 20);
+    let result = ipc_client
+        .paginate_local_content_keys(0, 1, None, None)
+        .await
+        .unwrap();
+    assert_eq!(result.total_entries, Some(20));
 
     let paginated_content_keys: Vec<String> = result
         .content_keys
@@ -43,8 +51,11 @@ pub async fn test_paginate_local_storage(peertest: &Peertest) {
     assert_eq!(paginated_content_keys, &content_keys[0..1]);
 
     // Test paginate with different offset & limit
-    let result = ipc_client.paginate_local_content_keys(5, 10).await.unwrap();
-    assert_eq!(result.total_entries, 20);
+    let result = ipc_client
+        .paginate_local_content_keys(5, 10, None, None)
+        .await
+        .unwrap();
+    assert_eq!(result.total_entries, Some(20));
     let paginated_content_keys: Vec<String> = result
         .content_keys
         .iter()
@@ -54,11 +65,11 @@ pub async fn test_paginate_local_storage(peertest: &Peertest) {
 
     // Test paginate with out of bounds limit
     let result = ipc_client
-        .paginate_local_content_keys(19, 20)
+        .paginate_local_content_keys(19, 20, None, None)
         .await
         .unwrap();
 
-    assert_eq!(result.total_entries, 20);
+    assert_eq!(result.total_entries, Some(20));
     let paginated_content_keys: Vec<String> = result
         .content_keys
         .iter()
@@ -68,10 +79,10 @@ pub async fn test_paginate_local_storage(peertest: &Peertest) {
 
     // Test paginate with out of bounds offset
     let result = ipc_client
-        .paginate_local_content_keys(21, 10)
+        .paginate_local_content_keys(21, 10, None, None)
         .await
         .unwrap();
-    assert_eq!(result.total_entries, 20);
+    assert_eq!(result.total_entries, Some(20));
     assert!(result
         .content_keys
         .iter()
@@ -56,6 +56,8 @@ async fn test_state_offer(fixture: &StateFixture, target: &Client, peer: &Peerte
         &peer.ipc_client,
         history_content_key,
         history_content_value.encode(),
+        None,
+        None,
     )
     .await
     .unwrap();
@@ -63,7 +65,7 @@ async fn test_state_offer(fixture: &StateFixture, target: &Client, peer: &Peerte
     // Offer state network content to peer
     StateNetworkApiClient::offer(
         target,
-        peer.enr.clone(),
+        peer.enr.clone().into(),
         vec![(fixture.key.clone(), fixture.raw_offer_value.clone())],
     )
     .await
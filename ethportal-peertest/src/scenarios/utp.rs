@@ -17,7 +17,7 @@ pub async fn test_recursive_utp(peertest: &Peertest) {
     let (content_key, content_value) = fixture_header_by_hash();
     let store_result = peertest.nodes[0]
         .ipc_client
-        .store(content_key.clone(), content_value.encode())
+        .store(content_key.clone(), content_value.encode(), None, None)
         .await
         .unwrap();
     assert!(store_result);
@@ -27,7 +27,7 @@ pub async fn test_recursive_utp(peertest: &Peertest) {
     let store_result = peertest
         .bootnode
         .ipc_client
-        .store(content_key.clone(), content_value.encode())
+        .store(content_key.clone(), content_value.encode(), None, None)
         .await
         .unwrap();
 
@@ -53,7 +53,7 @@ pub async fn test_trace_recursive_utp(peertest: &Peertest) {
     let (content_key, content_value) = fixture_header_by_hash();
     let store_result = peertest.nodes[0]
         .ipc_client
-        .store(content_key.clone(), content_value.encode())
+        .store(content_key.clone(), content_value.encode(), None, None)
         .await
         .unwrap();
 
@@ -64,7 +64,7 @@ pub async fn test_trace_recursive_utp(peertest: &Peertest) {
     let store_result = peertest
         .bootnode
         .ipc_client
-        .store(content_key.clone(), content_value.encode())
+        .store(content_key.clone(), content_value.encode(), None, None)
         .await
         .unwrap();
 
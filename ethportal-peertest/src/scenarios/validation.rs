@@ -24,7 +24,7 @@ pub async fn test_validate_pre_merge_header_by_hash(peertest: &Peertest, target:
     let store_result = peertest
         .bootnode
         .ipc_client
-        .store(content_key.clone(), content_value.encode())
+        .store(content_key.clone(), content_value.encode(), None, None)
         .await
         .unwrap();
 
@@ -33,7 +33,9 @@ pub async fn test_validate_pre_merge_header_by_hash(peertest: &Peertest, target:
     // calling find_content since it only returns the found data if validation was successful
     let result = target
         .find_content(
-            Enr::from_str(&peertest.bootnode.enr.to_base64()).unwrap(),
+            Enr::from_str(&peertest.bootnode.enr.to_base64())
+                .unwrap()
+                .into(),
             content_key.clone(),
         )
         .await
@@ -60,7 +62,7 @@ pub async fn test_validate_pre_merge_header_by_number(peertest: &Peertest, targe
     let store_result = peertest
         .bootnode
         .ipc_client
-        .store(content_key.clone(), content_value.encode())
+        .store(content_key.clone(), content_value.encode(), None, None)
         .await
         .unwrap();
 
@@ -69,7 +71,9 @@ pub async fn test_validate_pre_merge_header_by_number(peertest: &Peertest, targe
     // calling find_content since it only returns the found data if validation was successful
     let result = target
         .find_content(
-            Enr::from_str(&peertest.bootnode.enr.to_base64()).unwrap(),
+            Enr::from_str(&peertest.bootnode.enr.to_base64())
+                .unwrap()
+                .into(),
             content_key.clone(),
         )
         .await
@@ -97,7 +101,12 @@ pub async fn test_invalidate_header_by_hash(peertest: &Peertest, target: &Client
     let store_result = peertest
         .bootnode
         .ipc_client
-        .store(invalid_content_key.clone(), content_value.encode())
+        .store(
+            invalid_content_key.clone(),
+            content_value.encode(),
+            None,
+            None,
+        )
         .await
         .unwrap();
     assert!(store_result);
@@ -105,7 +114,9 @@ pub async fn test_invalidate_header_by_hash(peertest: &Peertest, target: &Client
     // calling find_content since it only returns the found data if validation was successful
     if let Err(msg) = target
         .find_content(
-            Enr::from_str(&peertest.bootnode.enr.to_base64()).unwrap(),
+            Enr::from_str(&peertest.bootnode.enr.to_base64())
+                .unwrap()
+                .into(),
             invalid_content_key.clone(),
         )
         .await
@@ -121,7 +132,7 @@ pub async fn test_validate_pre_merge_block_body(peertest: &Peertest, target: &Cl
     // store header_with_proof to validate block body
     let (content_key, content_value) = fixture_header_by_hash();
     let store_result = target
-        .store(content_key, content_value.encode())
+        .store(content_key, content_value.encode(), None, None)
         .await
         .unwrap();
     assert!(store_result);
@@ -132,7 +143,7 @@ pub async fn test_validate_pre_merge_block_body(peertest: &Peertest, target: &Cl
     let store_result = peertest
         .bootnode
         .ipc_client
-        .store(content_key.clone(), content_value.encode())
+        .store(content_key.clone(), content_value.encode(), None, None)
         .await
         .unwrap();
 
@@ -141,7 +152,9 @@ pub async fn test_validate_pre_merge_block_body(peertest: &Peertest, target: &Cl
     // calling find_content since it only returns the found data if validation was successful
     let result = target
         .find_content(
-            Enr::from_str(&peertest.bootnode.enr.to_base64()).unwrap(),
+            Enr::from_str(&peertest.bootnode.enr.to_base64())
+                .unwrap()
+                .into(),
             content_key.clone(),
         )
         .await
@@ -164,7 +177,7 @@ pub async fn test_validate_pre_merge_receipts(peertest: &Peertest, target: &Clie
     // store header_with_proof to validate block body
     let (content_key, content_value) = fixture_header_by_hash();
     let store_result = target
-        .store(content_key, content_value.encode())
+        .store(content_key, content_value.encode(), None, None)
         .await
         .unwrap();
     assert!(store_result);
@@ -175,7 +188,7 @@ pub async fn test_validate_pre_merge_receipts(peertest: &Peertest, target: &Clie
     let store_result = peertest
         .bootnode
         .ipc_client
-        .store(content_key.clone(), content_value.encode())
+        .store(content_key.clone(), content_value.encode(), None, None)
         .await
         .unwrap();
 
@@ -184,7 +197,9 @@ pub async fn test_validate_pre_merge_receipts(peertest: &Peertest, target: &Clie
     // calling find_content since it only returns the found data if validation was successful
     let result = target
         .find_content(
-            Enr::from_str(&peertest.bootnode.enr.to_base64()).unwrap(),
+            Enr::from_str(&peertest.bootnode.enr.to_base64())
+                .unwrap()
+                .into(),
             content_key.clone(),
         )
         .await
@@ -50,10 +50,11 @@ pub async fn wait_for_history_content<P: HistoryNetworkApiClient + std::marker::
     wait_for_successful_result(|| {
         let content_key = content_key.clone();
         ipc_client
-            .local_content(content_key.clone())
+            .local_content(content_key.clone(), None, None)
             .map_err(anyhow::Error::from)
             .and_then(|content| async move {
-                HistoryContentValue::decode(&content_key, &content).map_err(anyhow::Error::from)
+                HistoryContentValue::decode(&content_key, content.content())
+                    .map_err(anyhow::Error::from)
             })
     })
     .await
@@ -67,10 +68,11 @@ pub async fn wait_for_beacon_content<P: BeaconNetworkApiClient + std::marker::Sy
     wait_for_successful_result(|| {
         let content_key = content_key.clone();
         ipc_client
-            .local_content(content_key.clone())
+            .local_content(content_key.clone(), None)
             .map_err(anyhow::Error::from)
             .and_then(|content| async move {
-                BeaconContentValue::decode(&content_key, &content).map_err(anyhow::Error::from)
+                BeaconContentValue::decode(&content_key, content.content())
+                    .map_err(anyhow::Error::from)
             })
     })
     .await
@@ -84,10 +86,11 @@ pub async fn wait_for_state_content<P: StateNetworkApiClient + std::marker::Sync
     wait_for_successful_result(|| {
         let content_key = content_key.clone();
         ipc_client
-            .local_content(content_key.clone())
+            .local_content(content_key.clone(), None, None)
             .map_err(anyhow::Error::from)
             .and_then(|content| async move {
-                StateContentValue::decode(&content_key, &content).map_err(anyhow::Error::from)
+                StateContentValue::decode(&content_key, content.content())
+                    .map_err(anyhow::Error::from)
             })
     })
     .await
@@ -13,7 +13,9 @@ use tokio::{spawn, sync::RwLock, time::sleep};
 
 use crate::{
     config::{client_config::Config, CheckpointFallback, Network},
-    consensus::{errors::ConsensusError, rpc::ConsensusRpc, ConsensusLightClient},
+    consensus::{
+        errors::ConsensusError, rpc::ConsensusRpc, ConsensusLightClient, HeaderUpdateTimes,
+    },
     database::Database,
     errors::NodeError,
     node::Node,
@@ -476,4 +478,8 @@ impl<DB: Database, R: ConsensusRpc + 'static> Client<DB, R> {
     pub async fn get_light_client_store(&self) -> Result<LightClientStore> {
         self.node.read().await.get_light_client_store()
     }
+
+    pub async fn get_header_update_times(&self) -> HeaderUpdateTimes {
+        self.node.read().await.get_header_update_times()
+    }
 }
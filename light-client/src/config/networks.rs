@@ -1,3 +1,6 @@
+use ethportal_api::consensus::chain_config::{
+    ChainConfig as PortalChainConfig, Fork as PortalFork, MAINNET_CHAIN_CONFIG,
+};
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter};
 
@@ -35,37 +38,35 @@ pub fn mainnet() -> BaseConfig {
             "0x766647f3c4e1fc91c0db9a9374032ae038778411fbff222974e11f2e3ce7dadf",
         )
         .expect("should be a valid hex str"),
-        rpc_port: 8545,
         consensus_rpc: Some("https://www.lightclientdata.org".to_string()),
+        ..from_chain_config(&MAINNET_CHAIN_CONFIG)
+    }
+}
+
+/// Returns the base config for the beacon chain that the portal network serves content of, e.g. a
+/// testnet or a devnet. It has no default checkpoint or consensus rpc.
+pub fn from_chain_config(chain_config: &PortalChainConfig) -> BaseConfig {
+    let fork = |fork: &PortalFork| Fork {
+        epoch: fork.epoch,
+        fork_version: fork.version.to_vec(),
+    };
+    let fork_schedule = &chain_config.fork_schedule;
+    BaseConfig {
+        default_checkpoint: vec![],
+        rpc_port: 8545,
+        consensus_rpc: None,
         chain: ChainConfig {
-            chain_id: 1,
-            genesis_time: 1606824023,
-            genesis_root: hex_str_to_bytes(
-                "0x4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95",
-            )
-            .expect("should be a valid hex str"),
+            chain_id: chain_config.chain_id,
+            genesis_time: chain_config.genesis_time,
+            seconds_per_slot: chain_config.seconds_per_slot,
+            genesis_root: chain_config.genesis_validators_root.to_vec(),
         },
         forks: Forks {
-            genesis: Fork {
-                epoch: 0,
-                fork_version: hex_str_to_bytes("0x00000000").expect("should be a valid hex str"),
-            },
-            altair: Fork {
-                epoch: 74240,
-                fork_version: hex_str_to_bytes("0x01000000").expect("should be a valid hex str"),
-            },
-            bellatrix: Fork {
-                epoch: 144896,
-                fork_version: hex_str_to_bytes("0x02000000").expect("should be a valid hex str"),
-            },
-            capella: Fork {
-                epoch: 194048,
-                fork_version: hex_str_to_bytes("0x03000000").expect("should be a valid hex str"),
-            },
-            deneb: Fork {
-                epoch: 269568,
-                fork_version: hex_str_to_bytes("0x04000000").expect("should be a valid hex str"),
-            },
+            genesis: fork(&fork_schedule.genesis),
+            altair: fork(&fork_schedule.altair),
+            bellatrix: fork(&fork_schedule.bellatrix),
+            capella: fork(&fork_schedule.capella),
+            deneb: fork(&fork_schedule.deneb),
         },
         max_checkpoint_age: 1_209_600, // 14 days
     }
@@ -6,6 +6,8 @@ use crate::config::utils::{bytes_deserialize, bytes_serialize};
 pub struct ChainConfig {
     pub chain_id: u64,
     pub genesis_time: u64,
+    #[serde(default = "default_seconds_per_slot")]
+    pub seconds_per_slot: u64,
     #[serde(
         deserialize_with = "bytes_deserialize",
         serialize_with = "bytes_serialize"
@@ -13,6 +15,10 @@ pub struct ChainConfig {
     pub genesis_root: Vec<u8>,
 }
 
+fn default_seconds_per_slot() -> u64 {
+    12
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct Forks {
     pub genesis: Fork,
@@ -1,7 +1,7 @@
 use std::{
     cmp,
     sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
 
 use alloy::primitives::B256;
@@ -26,14 +26,19 @@ use tree_hash::TreeHash;
 
 use super::{errors::ConsensusError, rpc::ConsensusRpc, types::*, utils::*};
 use crate::{
-    config::client_config::Config,
-    consensus::{
-        constants::MAX_REQUEST_LIGHT_CLIENT_UPDATES, rpc::portal_rpc::expected_current_slot,
-    },
-    types::Bytes32,
-    utils::bytes_to_bytes32,
+    config::client_config::Config, consensus::constants::MAX_REQUEST_LIGHT_CLIENT_UPDATES,
+    types::Bytes32, utils::bytes_to_bytes32,
 };
 
+/// When the light client last updated its headers.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HeaderUpdateTimes {
+    /// When the optimistic header was last updated.
+    pub optimistic: Option<Instant>,
+    /// When the finalized header was last updated.
+    pub finalized: Option<Instant>,
+}
+
 // https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/light-client/sync-protocol.md
 // does not implement force updates
 
@@ -41,6 +46,7 @@ use crate::{
 pub struct ConsensusLightClient<R: ConsensusRpc> {
     rpc: R,
     store: LightClientStore,
+    header_update_times: HeaderUpdateTimes,
     initial_checkpoint: Vec<u8>,
     pub last_checkpoint: Option<Vec<u8>>,
     pub config: Arc<Config>,
@@ -57,6 +63,7 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
         Ok(ConsensusLightClient {
             rpc,
             store: LightClientStore::default(),
+            header_update_times: HeaderUpdateTimes::default(),
             last_checkpoint: None,
             config,
             initial_checkpoint: checkpoint_block_root.to_vec(),
@@ -67,6 +74,7 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
         ConsensusLightClient {
             rpc,
             store: LightClientStore::default(),
+            header_update_times: HeaderUpdateTimes::default(),
             last_checkpoint: None,
             config,
             initial_checkpoint: checkpoint_block_root.to_vec(),
@@ -109,6 +117,12 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
         &self.store
     }
 
+    /// Returns when the optimistic and finalized headers were last updated, including by the
+    /// bootstrap, if ever.
+    pub fn get_header_update_times(&self) -> HeaderUpdateTimes {
+        self.header_update_times
+    }
+
     pub async fn sync(&mut self) -> Result<()> {
         self.bootstrap().await?;
 
@@ -119,7 +133,7 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
         // If we are using the portal network, we need to request updates for all periods one by one
         if &self.rpc.name() == "portal" {
             // Get expected current period
-            let current_perriod = calc_sync_period(expected_current_slot());
+            let current_perriod = calc_sync_period(self.expected_current_slot());
 
             // Create a range of periods to request updates for
             let periods = bootstrap_period..current_perriod;
@@ -245,13 +259,18 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
             previous_max_active_participants: 0,
             current_max_active_participants: 0,
         };
+        let now = Instant::now();
+        self.header_update_times = HeaderUpdateTimes {
+            optimistic: Some(now),
+            finalized: Some(now),
+        };
 
         Ok(())
     }
 
     fn verify_update(&self, update: &LightClientUpdateDeneb) -> Result<()> {
         let update = GenericUpdate::from(update);
-        let expected_current_slot = expected_current_slot();
+        let expected_current_slot = self.expected_current_slot();
         let genesis_root = &self.config.chain.genesis_root;
         verify_generic_update(
             &self.store,
@@ -264,7 +283,7 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
 
     fn verify_finality_update(&self, update: &LightClientFinalityUpdateDeneb) -> Result<()> {
         let update = GenericUpdate::from(update);
-        let expected_current_slot = expected_current_slot();
+        let expected_current_slot = self.expected_current_slot();
         let genesis_root = &self.config.chain.genesis_root;
         verify_generic_update(
             &self.store,
@@ -277,7 +296,7 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
 
     fn verify_optimistic_update(&self, update: &LightClientOptimisticUpdateDeneb) -> Result<()> {
         let update = GenericUpdate::from(update);
-        let expected_current_slot = expected_current_slot();
+        let expected_current_slot = self.expected_current_slot();
         let genesis_root = &self.config.chain.genesis_root;
         verify_generic_update(
             &self.store,
@@ -301,6 +320,7 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
 
         if should_update_optimistic {
             self.store.optimistic_header = update.attested_header.clone();
+            self.header_update_times.optimistic = Some(Instant::now());
             self.log_optimistic_update(update);
         }
 
@@ -358,6 +378,7 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
                     .as_ref()
                     .expect("`update_finalized_slot` > 0, so it's expected to exist")
                     .clone();
+                self.header_update_times.finalized = Some(Instant::now());
                 self.log_finality_update(update);
 
                 if self.store.finalized_header.slot % 32 == 0 {
@@ -367,6 +388,7 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
 
                 if self.store.finalized_header.slot > self.store.optimistic_header.slot {
                     self.store.optimistic_header = self.store.finalized_header.clone();
+                    self.header_update_times.optimistic = self.header_update_times.finalized;
                 }
             }
         }
@@ -453,11 +475,11 @@ impl<R: ConsensusRpc> ConsensusLightClient<R> {
         let genesis_time = self.config.chain.genesis_time;
         let since_genesis = now - std::time::Duration::from_secs(genesis_time);
 
-        since_genesis.as_secs() / 12
+        since_genesis.as_secs() / self.config.chain.seconds_per_slot
     }
 
     fn slot_timestamp(&self, slot: u64) -> u64 {
-        slot * 12 + self.config.chain.genesis_time
+        slot * self.config.chain.seconds_per_slot + self.config.chain.genesis_time
     }
 
     /// Gets the duration until the next update
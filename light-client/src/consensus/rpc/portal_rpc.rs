@@ -1,9 +1,9 @@
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
 
 use anyhow::{anyhow, bail};
 use async_trait::async_trait;
 use ethportal_api::{
-    consensus::light_client::bootstrap::LightClientBootstrap,
+    consensus::{chain_config::ChainConfig, light_client::bootstrap::LightClientBootstrap},
     light_client::{
         bootstrap::LightClientBootstrapDeneb, finality_update::LightClientFinalityUpdateDeneb,
         optimistic_update::LightClientOptimisticUpdateDeneb, update::LightClientUpdateDeneb,
@@ -15,26 +15,42 @@ use ethportal_api::{
         },
         content_key::beacon::{LightClientFinalityUpdateKey, LightClientOptimisticUpdateKey},
     },
-    BeaconContentKey, BeaconContentValue, ContentValue, LightClientBootstrapKey,
-    LightClientUpdatesByRangeKey,
+    BeaconContentKey, BeaconContentValue, LightClientBootstrapKey, LightClientUpdatesByRangeKey,
 };
 use futures::channel::oneshot;
 use portalnet::overlay::command::OverlayCommand;
 use tokio::sync::mpsc::UnboundedSender;
-use tracing::warn;
+use tracing::{warn, Span};
+use tree_hash::TreeHash;
 
 use crate::consensus::rpc::ConsensusRpc;
 
-pub const BEACON_GENESIS_TIME: u64 = 1606824023;
-
 #[derive(Clone, Debug)]
 pub struct PortalRpc {
     overlay_tx: UnboundedSender<OverlayCommand<BeaconContentKey>>,
+    // the chain that the content is looked up for
+    chain_config: Arc<ChainConfig>,
+    // the bootstrap that is available locally, and isn't looked up on the network
+    bootstrap: Option<LightClientBootstrapDeneb>,
 }
 
 impl PortalRpc {
-    pub fn with_portal(overlay_tx: UnboundedSender<OverlayCommand<BeaconContentKey>>) -> Self {
-        Self { overlay_tx }
+    pub fn with_portal(
+        overlay_tx: UnboundedSender<OverlayCommand<BeaconContentKey>>,
+        chain_config: Arc<ChainConfig>,
+    ) -> Self {
+        Self {
+            overlay_tx,
+            chain_config,
+            bootstrap: None,
+        }
+    }
+
+    /// Uses the locally available bootstrap when it is requested, instead of looking it up on the
+    /// network.
+    pub fn with_bootstrap(mut self, bootstrap: LightClientBootstrapDeneb) -> Self {
+        self.bootstrap = Some(bootstrap);
+        self
     }
 }
 
@@ -48,6 +64,12 @@ impl ConsensusRpc for PortalRpc {
         &self,
         block_root: &'_ [u8],
     ) -> anyhow::Result<LightClientBootstrapDeneb> {
+        if let Some(bootstrap) = &self.bootstrap {
+            if bootstrap.header.beacon.tree_hash_root().as_slice() == block_root {
+                return Ok(bootstrap.clone());
+            }
+        }
+
         let bootstrap_key = BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey {
             block_hash: <[u8; 32]>::try_from(block_root)?,
         });
@@ -58,6 +80,7 @@ impl ConsensusRpc for PortalRpc {
             target: bootstrap_key.clone(),
             callback: tx,
             config: Default::default(),
+            span: Span::current(),
         };
 
         if let Err(err) = self.overlay_tx.send(overlay_command) {
@@ -72,7 +95,11 @@ impl ConsensusRpc for PortalRpc {
         match rx.await {
             Ok(result) => {
                 let bootstrap = match result {
-                    Ok(result) => BeaconContentValue::decode(&bootstrap_key, &result.0)?,
+                    Ok(result) => BeaconContentValue::decode_for_chain(
+                        &bootstrap_key,
+                        &result.0,
+                        &self.chain_config,
+                    )?,
                     Err(err) => {
                         bail!("LightClientBootstrap content not found on the network: {err}")
                     }
@@ -115,6 +142,7 @@ impl ConsensusRpc for PortalRpc {
             target: updates_key.clone(),
             callback: tx,
             config: Default::default(),
+            span: Span::current(),
         };
 
         if let Err(err) = self.overlay_tx.send(overlay_command) {
@@ -129,7 +157,11 @@ impl ConsensusRpc for PortalRpc {
         match rx.await {
             Ok(result) => {
                 let content_value = match result {
-                    Ok(result) => BeaconContentValue::decode(&updates_key, &result.0)?,
+                    Ok(result) => BeaconContentValue::decode_for_chain(
+                        &updates_key,
+                        &result.0,
+                        &self.chain_config,
+                    )?,
                     Err(err) => {
                         return Err(anyhow!("LightClientUpdatesByRange period={period}, count={count} not found on the network: {err}"));
                     }
@@ -186,6 +218,7 @@ impl ConsensusRpc for PortalRpc {
             target: finality_update_key.clone(),
             callback: tx,
             config: Default::default(),
+            span: Span::current(),
         };
 
         if let Err(err) = self.overlay_tx.send(overlay_command) {
@@ -200,7 +233,11 @@ impl ConsensusRpc for PortalRpc {
         match rx.await {
             Ok(result) => {
                 let finality_update = match result {
-                    Ok(result) => BeaconContentValue::decode(&finality_update_key, &result.0)?,
+                    Ok(result) => BeaconContentValue::decode_for_chain(
+                        &finality_update_key,
+                        &result.0,
+                        &self.chain_config,
+                    )?,
                     Err(err) => {
                         return Err(anyhow!("LightClientFinalityUpdate content with finalized slot 0 not found on the network: {err}"));
                     }
@@ -235,7 +272,7 @@ impl ConsensusRpc for PortalRpc {
     }
 
     async fn get_optimistic_update(&self) -> anyhow::Result<LightClientOptimisticUpdateDeneb> {
-        let expected_current_slot = expected_current_slot();
+        let expected_current_slot = self.chain_config.current_slot();
         let optimistic_update_key =
             BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey {
                 signature_slot: expected_current_slot,
@@ -247,6 +284,7 @@ impl ConsensusRpc for PortalRpc {
             target: optimistic_update_key.clone(),
             callback: tx,
             config: Default::default(),
+            span: Span::current(),
         };
 
         if let Err(err) = self.overlay_tx.send(overlay_command) {
@@ -261,7 +299,11 @@ impl ConsensusRpc for PortalRpc {
         match rx.await {
             Ok(result) => {
                 let optimistic_update = match result {
-                    Ok(result) => BeaconContentValue::decode(&optimistic_update_key, &result.0)?,
+                    Ok(result) => BeaconContentValue::decode_for_chain(
+                        &optimistic_update_key,
+                        &result.0,
+                        &self.chain_config,
+                    )?,
                     Err(err) => {
                         return Err(anyhow!("LightClientOptimisticUpdate content with signature slot {expected_current_slot} not found on the network: {err}"));
                     }
@@ -296,18 +338,10 @@ impl ConsensusRpc for PortalRpc {
     }
 
     async fn chain_id(&self) -> anyhow::Result<u64> {
-        Ok(1)
+        Ok(self.chain_config.chain_id)
     }
 
     fn name(&self) -> String {
         "portal".to_string()
     }
 }
-
-pub fn expected_current_slot() -> u64 {
-    let now = SystemTime::now();
-    let now = now.duration_since(UNIX_EPOCH).expect("Time went backwards");
-    let since_genesis = now - Duration::from_secs(BEACON_GENESIS_TIME);
-
-    since_genesis.as_secs() / 12
-}
@@ -11,7 +11,7 @@ use ethportal_api::{
 
 use crate::{
     config::client_config::Config,
-    consensus::{rpc::ConsensusRpc, ConsensusLightClient},
+    consensus::{rpc::ConsensusRpc, ConsensusLightClient, HeaderUpdateTimes},
     errors::NodeError,
 };
 
@@ -90,6 +90,10 @@ impl<R: ConsensusRpc> Node<R> {
         Ok(self.consensus.get_light_client_store().clone())
     }
 
+    pub fn get_header_update_times(&self) -> HeaderUpdateTimes {
+        self.consensus.get_header_update_times()
+    }
+
     pub fn get_header(&self) -> Result<BeaconBlockHeader> {
         self.check_head_age()?;
         Ok(self.consensus.get_header().clone())
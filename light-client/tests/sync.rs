@@ -24,8 +24,15 @@ async fn setup() -> ConsensusLightClient<MockRpc> {
 #[tokio::test]
 async fn test_sync() {
     let mut client = setup().await;
+    assert!(client.get_header_update_times().optimistic.is_none());
+    assert!(client.get_header_update_times().finalized.is_none());
     client.sync().await.unwrap();
 
+    let update_times = client.get_header_update_times();
+    assert!(update_times.optimistic.is_some());
+    assert!(update_times.finalized.is_some());
+    assert!(update_times.optimistic >= update_times.finalized);
+
     let head = client.get_header();
     assert_eq!(head.slot, 7358726);
 
@@ -536,7 +536,7 @@ impl StateBridge {
                     SERVE_BLOCK_TIMEOUT,
                     StateNetworkApiClient::trace_offer(
                         &portal_client,
-                        enr.clone(),
+                        enr.clone().into(),
                         content_key.clone(),
                         encoded_content_value,
                     ),
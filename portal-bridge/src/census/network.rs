@@ -279,9 +279,9 @@ impl Network {
         ensure!(self.is_eligible(enr), "ping: peer is filtered out");
 
         match self.subnetwork {
-            Subnetwork::History => HistoryNetworkApiClient::ping(&self.client, enr.clone()),
-            Subnetwork::State => StateNetworkApiClient::ping(&self.client, enr.clone()),
-            Subnetwork::Beacon => BeaconNetworkApiClient::ping(&self.client, enr.clone()),
+            Subnetwork::History => HistoryNetworkApiClient::ping(&self.client, enr.clone().into()),
+            Subnetwork::State => StateNetworkApiClient::ping(&self.client, enr.clone().into()),
+            Subnetwork::Beacon => BeaconNetworkApiClient::ping(&self.client, enr.clone().into()),
             _ => unreachable!("ping: unsupported subnetwork: {}", self.subnetwork),
         }
         .await
@@ -309,15 +309,24 @@ impl Network {
 
     async fn find_nodes(&self, enr: &Enr, distances: Vec<u16>) -> anyhow::Result<Vec<Enr>> {
         match self.subnetwork {
-            Subnetwork::History => {
-                HistoryNetworkApiClient::find_nodes(&self.client, enr.clone(), distances)
-            }
-            Subnetwork::State => {
-                StateNetworkApiClient::find_nodes(&self.client, enr.clone(), distances)
-            }
-            Subnetwork::Beacon => {
-                BeaconNetworkApiClient::find_nodes(&self.client, enr.clone(), distances)
-            }
+            Subnetwork::History => HistoryNetworkApiClient::find_nodes(
+                &self.client,
+                enr.clone().into(),
+                distances,
+                /* max_results= */ None,
+            ),
+            Subnetwork::State => StateNetworkApiClient::find_nodes(
+                &self.client,
+                enr.clone().into(),
+                distances,
+                /* max_results= */ None,
+            ),
+            Subnetwork::Beacon => BeaconNetworkApiClient::find_nodes(
+                &self.client,
+                enr.clone().into(),
+                distances,
+                /* max_results= */ None,
+            ),
             _ => unreachable!("find_nodes: unsupported subnetwork: {}", self.subnetwork),
         }
         .await
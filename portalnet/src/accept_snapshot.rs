@@ -0,0 +1,60 @@
+use ethportal_api::types::{distance::Distance, portal::AcceptSnapshotInfo};
+
+/// The inputs of the decision whether to accept offered content.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct AcceptInputs {
+    radius: Distance,
+    utp_transfer_limit: usize,
+}
+
+/// Versions the inputs of the accept decision, so that callers can tell whether an accept decision
+/// they cached is still current.
+///
+/// The version is bumped whenever the inputs differ from the previous snapshot.
+#[derive(Default)]
+pub struct AcceptSnapshots {
+    version: u64,
+    last_inputs: Option<AcceptInputs>,
+}
+
+impl AcceptSnapshots {
+    /// Returns a snapshot of the given accept inputs, with the version of these inputs.
+    pub fn snapshot(&mut self, radius: Distance, utp_transfer_limit: usize) -> AcceptSnapshotInfo {
+        let inputs = AcceptInputs {
+            radius,
+            utp_transfer_limit,
+        };
+        if self.last_inputs != Some(inputs) {
+            if self.last_inputs.is_some() {
+                self.version += 1;
+            }
+            self.last_inputs = Some(inputs);
+        }
+        AcceptSnapshotInfo {
+            version: self.version,
+            radius: *radius,
+            utp_transfer_limit: utp_transfer_limit as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_bumps_only_on_change() {
+        let mut snapshots = AcceptSnapshots::default();
+
+        let first = snapshots.snapshot(Distance::MAX, 50);
+        assert_eq!(first.version, 0);
+        assert_eq!(first.radius, *Distance::MAX);
+        assert_eq!(first.utp_transfer_limit, 50);
+        assert_eq!(snapshots.snapshot(Distance::MAX, 50), first);
+
+        assert_eq!(snapshots.snapshot(Distance::ZERO, 50).version, 1);
+        assert_eq!(snapshots.snapshot(Distance::ZERO, 50).version, 1);
+        assert_eq!(snapshots.snapshot(Distance::ZERO, 10).version, 2);
+        assert_eq!(snapshots.snapshot(Distance::MAX, 50).version, 3);
+    }
+}
@@ -1,11 +1,22 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 use alloy::primitives::B256;
-use ethportal_api::types::{
-    bootnodes::Bootnodes,
-    cli::{TrinConfig, DEFAULT_UTP_TRANSFER_LIMIT},
-    enr::Enr,
-    network::Network,
+use ethportal_api::{
+    consensus::chain_config::{ChainConfig, MAINNET_CHAIN_CONFIG},
+    types::{
+        bootnodes::Bootnodes,
+        cli::{TrinConfig, DEFAULT_UTP_TRANSFER_LIMIT},
+        enr::Enr,
+        network::Network,
+    },
+    utils::content_signature::PublicKey,
+};
+use url::Url;
+
+use crate::{
+    find::{failed_lookups::FailedLookupCacheConfig, lookup_results::LookupCacheConfig},
+    gossip::GossipConfig,
+    poke::RebroadcastConfig,
 };
 
 /// Capacity of the cache for observed `NodeAddress` values.
@@ -18,14 +29,38 @@ pub struct PortalnetConfig {
     pub external_addr: Option<SocketAddr>,
     pub private_key: B256,
     pub listen_port: u16,
+    // the port that uTP packets are exchanged on directly, instead of through discv5 TALKREQ
+    // messages on the listen port (shared with discv5 if None)
+    pub utp_port: Option<u16>,
     pub bootnodes: Vec<Enr>,
     pub no_stun: bool,
     pub no_upnp: bool,
     pub node_addr_cache_capacity: usize,
     pub disable_poke: bool,
     pub trusted_block_root: Option<B256>,
+    // the Beacon API provider that the beacon content is seeded from at startup (not seeded if
+    // None)
+    pub beacon_seed_from: Option<Url>,
+    // the beacon chain that the beacon content belongs to
+    pub beacon_chain: Arc<ChainConfig>,
     // the max number of concurrent utp transfers
     pub utp_transfer_limit: usize,
+    /// The max rates, in kilobits per second, at which uTP packets are sent and received, across
+    /// all the subnetworks. 0 means unlimited.
+    pub utp_max_up_kbps: u64,
+    pub utp_max_down_kbps: u64,
+    // the cache of recently failed content lookups (disabled if None)
+    pub failed_lookup_cache: Option<FailedLookupCacheConfig>,
+    // the cache of recently found content (disabled if None)
+    pub lookup_cache: Option<LookupCacheConfig>,
+    // the rebroadcast of stored content to new peers (disabled if None)
+    pub rebroadcast: Option<RebroadcastConfig>,
+    // which of the interested peers gossiped content is offered to, for each subnetwork
+    pub beacon_gossip: GossipConfig,
+    pub history_gossip: GossipConfig,
+    pub state_gossip: GossipConfig,
+    // the public keys whose signatures mark content stored over JSON-RPC as trusted
+    pub trusted_content_signers: Vec<PublicKey>,
 }
 
 // to be used inside test code only
@@ -35,13 +70,25 @@ impl Default for PortalnetConfig {
             external_addr: None,
             private_key: B256::random(),
             listen_port: 4242,
+            utp_port: None,
             bootnodes: Bootnodes::default().to_enrs(Network::Mainnet),
             no_stun: false,
             no_upnp: false,
             node_addr_cache_capacity: NODE_ADDR_CACHE_CAPACITY,
             disable_poke: false,
             trusted_block_root: None,
+            beacon_seed_from: None,
+            beacon_chain: MAINNET_CHAIN_CONFIG.clone(),
             utp_transfer_limit: DEFAULT_UTP_TRANSFER_LIMIT,
+            utp_max_up_kbps: 0,
+            utp_max_down_kbps: 0,
+            failed_lookup_cache: Some(FailedLookupCacheConfig::default()),
+            lookup_cache: None,
+            rebroadcast: None,
+            beacon_gossip: GossipConfig::default(),
+            history_gossip: GossipConfig::default(),
+            state_gossip: GossipConfig::default(),
+            trusted_content_signers: vec![],
         }
     }
 }
@@ -52,13 +99,51 @@ impl PortalnetConfig {
             external_addr: trin_config.external_addr,
             private_key,
             listen_port: trin_config.discovery_port,
+            utp_port: trin_config.utp_port,
             bootnodes: trin_config.bootnodes.to_enrs(trin_config.network.network()),
             no_stun: trin_config.no_stun,
             no_upnp: trin_config.no_upnp,
             node_addr_cache_capacity: NODE_ADDR_CACHE_CAPACITY,
             disable_poke: trin_config.disable_poke,
             trusted_block_root: trin_config.trusted_block_root,
+            beacon_seed_from: trin_config.beacon_seed_from.clone(),
+            beacon_chain: trin_config.chain_config(),
             utp_transfer_limit: trin_config.utp_transfer_limit,
+            utp_max_up_kbps: trin_config.utp_max_up_kbps,
+            utp_max_down_kbps: trin_config.utp_max_down_kbps,
+            failed_lookup_cache: (trin_config.failed_lookup_cache_ttl > 0).then(|| {
+                FailedLookupCacheConfig {
+                    ttl: Duration::from_secs(trin_config.failed_lookup_cache_ttl),
+                    capacity: trin_config.failed_lookup_cache_size as usize,
+                }
+            }),
+            lookup_cache: (trin_config.lookup_cache_ttl > 0).then(|| LookupCacheConfig {
+                ttl: Duration::from_secs(trin_config.lookup_cache_ttl),
+                max_bytes: trin_config.lookup_cache_max_mb * 1_000_000,
+            }),
+            rebroadcast: (trin_config.rebroadcast_offers_per_peer > 0).then(|| RebroadcastConfig {
+                offers_per_peer: trin_config.rebroadcast_offers_per_peer as usize,
+                offers_per_minute: trin_config.rebroadcast_offers_per_minute as usize,
+            }),
+            beacon_gossip: GossipConfig {
+                max_peers: trin_config.gossip_beacon_max_peers as usize,
+                prefer_radius_coverage: trin_config.gossip_beacon_prefer_radius_coverage,
+                include_far_peers: !trin_config.gossip_beacon_exclude_far_peers,
+                large_content_threshold: trin_config.gossip_large_content_threshold as usize,
+            },
+            history_gossip: GossipConfig {
+                max_peers: trin_config.gossip_history_max_peers as usize,
+                prefer_radius_coverage: trin_config.gossip_history_prefer_radius_coverage,
+                include_far_peers: !trin_config.gossip_history_exclude_far_peers,
+                large_content_threshold: trin_config.gossip_large_content_threshold as usize,
+            },
+            state_gossip: GossipConfig {
+                max_peers: trin_config.gossip_state_max_peers as usize,
+                prefer_radius_coverage: trin_config.gossip_state_prefer_radius_coverage,
+                include_far_peers: !trin_config.gossip_state_exclude_far_peers,
+                large_content_threshold: trin_config.gossip_large_content_threshold as usize,
+            },
+            trusted_content_signers: trin_config.trusted_content_signers.clone(),
         }
     }
 }
@@ -1,11 +1,12 @@
 use std::{
+    collections::VecDeque,
     fmt,
     hash::{Hash, Hasher},
     io,
-    net::{Ipv4Addr, SocketAddr},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
     str::FromStr,
-    sync::Arc,
-    time::Duration,
+    sync::{Arc, Weak},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::anyhow;
@@ -16,20 +17,34 @@ use discv5::{
     ConfigBuilder, Discv5, Event, ListenConfig, RequestError, TalkRequest,
 };
 use ethportal_api::{
-    types::{discv5::RoutingTableInfo, enr::Enr, network::Subnetwork, portal_wire::NetworkSpec},
-    utils::bytes::hex_decode,
+    types::{
+        discv5::RoutingTableInfo,
+        enr::Enr,
+        network::{Network, Subnetwork},
+        portal_wire::NetworkSpec,
+    },
+    utils::bytes::{hex_decode, hex_encode},
     version::get_trin_version,
     NodeInfo,
 };
+use futures::future::join_all;
 use lru::LruCache;
-use parking_lot::RwLock;
-use tokio::sync::{mpsc, RwLock as TokioRwLock};
+use parking_lot::{Mutex, RwLock};
+use rand::seq::IteratorRandom;
+use serde_json::{json, Value};
+use tokio::{
+    net::UdpSocket,
+    sync::{broadcast, mpsc, RwLock as TokioRwLock},
+};
 use tracing::{debug, info, warn};
+use trin_metrics::portalnet::PORTALNET_METRICS;
 use trin_validation::oracle::HeaderOracle;
 use utp_rs::{cid::ConnectionPeer, udp::AsyncUdpSocket};
 
 use super::config::PortalnetConfig;
-use crate::socket;
+use crate::{
+    socket, types::protocol_version::SUPPORTED_PROTOCOL_VERSIONS, utp_throttle::UtpThrottle,
+};
 
 /// Size of the buffer of the Discv5 TALKREQ channel.
 const TALKREQ_CHANNEL_BUFFER: usize = 100;
@@ -37,6 +52,38 @@ const TALKREQ_CHANNEL_BUFFER: usize = 100;
 /// ENR key for portal network client version.
 pub const ENR_PORTAL_CLIENT_KEY: &str = "c";
 
+/// ENR key for the portal network (e.g. mainnet, angelfood) that the node is on.
+pub const ENR_PORTAL_NETWORK_KEY: &str = "n";
+
+/// ENR key for the portal wire protocol versions that the node supports, one version per byte.
+pub const ENR_PORTAL_VERSIONS_KEY: &str = "pv";
+
+/// ENR key for the UDP port that the node exchanges uTP packets on directly, if uTP packets don't
+/// share the discv5 port.
+pub const ENR_UTP_PORT_KEY: &str = "utp";
+
+/// The max number of peers whose uTP address is remembered, to identify the sender of the uTP
+/// packets that are received directly.
+const UTP_PEERS_CAPACITY: usize = 1024;
+
+/// Size of the buffer of the channel of uTP packets that are received directly.
+const UTP_PACKET_CHANNEL_BUFFER: usize = 1024;
+
+/// The max size of a uTP packet that is received directly.
+const MAX_UTP_PACKET_SIZE: usize = 2048;
+
+/// The max number of uTP data packets that wait for the download rate limit, and for the upload
+/// rate limit. The received data packets above it are dropped, and retransmitted by their senders,
+/// while the sent ones wait until there is room for them.
+const MAX_THROTTLED_PACKETS: usize = 1024;
+
+/// The max number of routing table peers that are pinged when the external address changes, so
+/// that they learn the new ENR. Each overlay pings a sample of its own routing table as well.
+pub(crate) const ADDRESS_CHANGE_PING_SAMPLE: usize = 16;
+
+/// Size of the buffer of the channel of local ENR updates.
+const ENR_UPDATE_CHANNEL_BUFFER: usize = 16;
+
 pub type ProtocolRequest = Vec<u8>;
 
 /// The contact info for a remote node.
@@ -48,6 +95,15 @@ pub struct NodeAddress {
     pub socket_addr: SocketAddr,
 }
 
+/// The socket address that the local ENR advertises, and when it last changed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AdvertisedAddress {
+    pub socket_addr: Option<SocketAddr>,
+    /// When the external address last changed, in seconds since the Unix epoch, if it changed
+    /// since the node started
+    pub changed_at: Option<u64>,
+}
+
 /// Base Node Discovery Protocol v5 layer
 pub struct Discovery {
     /// The inner Discv5 service.
@@ -60,6 +116,18 @@ pub struct Discovery {
     pub listen_socket: SocketAddr,
     /// The Portal Network to Protocal Id Map etc MAINNET, ANGELFOOD
     network_spec: Arc<NetworkSpec>,
+    /// Limits the rates of the uTP packets, which are all exchanged by this node's uTP socket.
+    utp_throttle: UtpThrottle,
+    /// The socket address that the local ENR advertises.
+    advertised_address: RwLock<AdvertisedAddress>,
+    /// Sends the external addresses that discv5's address voting converged on to the address
+    /// monitor.
+    socket_update_tx: mpsc::UnboundedSender<SocketAddr>,
+    /// The external addresses that discv5's address voting converged on, until the address
+    /// monitor is spawned.
+    socket_update_rx: Mutex<Option<mpsc::UnboundedReceiver<SocketAddr>>>,
+    /// Notifies the overlays of the local ENR after the external address changed.
+    enr_update_tx: broadcast::Sender<Enr>,
 }
 
 impl fmt::Debug for Discovery {
@@ -79,6 +147,8 @@ impl Discovery {
         portal_config: PortalnetConfig,
         network_spec: Arc<NetworkSpec>,
     ) -> Result<Self, String> {
+        let network = network_spec.network();
+
         let listen_all_ips = SocketAddr::new(
             "0.0.0.0"
                 .parse()
@@ -129,7 +199,10 @@ impl Discovery {
             if let Some(ip_address) = enr_address {
                 builder.ip(ip_address);
             }
-            builder.udp4(enr_port);
+            match enr_address {
+                Some(IpAddr::V6(_)) => builder.udp6(enr_port),
+                _ => builder.udp4(enr_port),
+            };
 
             // Set the ENR sequence number to the current timestamp this prevents other nodes from
             // storing outdated Trin Enr's
@@ -144,6 +217,16 @@ impl Discovery {
             let client_info = format!("t {trin_version}");
             // Use "c" as short-hand for "client".
             builder.add_value(ENR_PORTAL_CLIENT_KEY, &client_info.as_bytes());
+            // Use "n" as short-hand for "network".
+            builder.add_value(ENR_PORTAL_NETWORK_KEY, &network.to_string().as_bytes());
+            // Use "pv" as short-hand for "protocol versions".
+            builder.add_value(
+                ENR_PORTAL_VERSIONS_KEY,
+                &SUPPORTED_PROTOCOL_VERSIONS.as_slice(),
+            );
+            if let Some(utp_port) = portal_config.utp_port {
+                builder.add_value(ENR_UTP_PORT_KEY, &utp_port);
+            }
             builder
                 .build(&enr_key)
                 .map_err(|e| format!("When adding key to servers ENR: {e:?}"))?
@@ -161,8 +244,12 @@ impl Discovery {
             .map_err(|e| format!("Failed to create discv5 instance: {e}"))?;
 
         for enr in portal_config.bootnodes {
-            if enr.node_id() == discv5.local_enr().node_id() {
-                warn!("Bootnode ENR is the same as the local ENR. Skipping.");
+            if let Err(err) = check_enr(&enr, discv5.local_enr().node_id(), None, false) {
+                warn!(enr = %enr, "Skipping bootnode: {err}");
+                continue;
+            }
+            if let Err(err) = check_enr_network(&enr, network) {
+                warn!(enr = %enr, "Skipping bootnode: {err}");
                 continue;
             }
             discv5
@@ -172,6 +259,16 @@ impl Discovery {
 
         let node_addr_cache = LruCache::new(portal_config.node_addr_cache_capacity);
         let node_addr_cache = Arc::new(RwLock::new(node_addr_cache));
+        let local_enr = discv5.local_enr();
+        let advertised_address = AdvertisedAddress {
+            socket_addr: local_enr
+                .udp4_socket()
+                .map(SocketAddr::V4)
+                .or_else(|| local_enr.udp6_socket().map(SocketAddr::V6)),
+            changed_at: None,
+        };
+        let (socket_update_tx, socket_update_rx) = mpsc::unbounded_channel();
+        let (enr_update_tx, _) = broadcast::channel(ENR_UPDATE_CHANNEL_BUFFER);
 
         Ok(Self {
             discv5,
@@ -179,9 +276,22 @@ impl Discovery {
             started: false,
             listen_socket: listen_all_ips,
             network_spec,
+            utp_throttle: UtpThrottle::new(
+                portal_config.utp_max_up_kbps,
+                portal_config.utp_max_down_kbps,
+            ),
+            advertised_address: RwLock::new(advertised_address),
+            socket_update_tx,
+            socket_update_rx: Mutex::new(Some(socket_update_rx)),
+            enr_update_tx,
         })
     }
 
+    /// Returns the limiter of the rates of the uTP packets.
+    pub fn utp_throttle(&self) -> &UtpThrottle {
+        &self.utp_throttle
+    }
+
     pub async fn start(&mut self) -> Result<mpsc::Receiver<TalkRequest>, String> {
         info!(enr = %self.local_enr(), "Starting discv5 with");
         debug!(enr = ?self.local_enr(), "Discv5 enr details");
@@ -199,6 +309,7 @@ impl Discovery {
             .map_err(|e| format!("When launching event stream in new discv5: {e:?}"))?;
 
         let (talk_req_tx, talk_req_rx) = mpsc::channel(TALKREQ_CHANNEL_BUFFER);
+        let socket_update_tx = self.socket_update_tx.clone();
 
         let node_addr_cache = Arc::clone(&self.node_addr_cache);
 
@@ -209,6 +320,10 @@ impl Discovery {
                         // Forward all TALKREQ messages.
                         let _ = talk_req_tx.send(talk_req).await;
                     }
+                    Event::SocketUpdated(socket_addr) => {
+                        // The address voting converged on a new external address.
+                        let _ = socket_update_tx.send(socket_addr);
+                    }
                     Event::SessionEstablished(enr, socket_addr) => {
                         // TODO: this is a temporary fix to prevent caching of eth2 nodes
                         // and will be updated to a more stable solution as soon as it
@@ -243,6 +358,86 @@ impl Discovery {
         Ok(talk_req_rx)
     }
 
+    /// Spawns the task that handles the external addresses that discv5's address voting converges
+    /// on, see [Self::handle_socket_update]. Does nothing if the task was already spawned.
+    pub fn spawn_address_monitor(self: &Arc<Self>) {
+        let Some(mut socket_update_rx) = self.socket_update_rx.lock().take() else {
+            return;
+        };
+        let discovery = Arc::downgrade(self);
+        tokio::spawn(async move {
+            while let Some(socket_addr) = socket_update_rx.recv().await {
+                let Some(discovery) = Weak::upgrade(&discovery) else {
+                    break;
+                };
+                discovery.handle_socket_update(socket_addr).await;
+            }
+        });
+    }
+
+    /// Reports an external address to the address monitor, as discv5 does once its address voting
+    /// converges on a new address.
+    pub fn report_socket_update(&self, socket_addr: SocketAddr) {
+        // The receiver is only dropped if the address monitor stopped, along with the node.
+        let _ = self.socket_update_tx.send(socket_addr);
+    }
+
+    /// Returns a receiver of the local ENR, which is sent whenever the external address changes.
+    pub fn subscribe_enr_updates(&self) -> broadcast::Receiver<Enr> {
+        self.enr_update_tx.subscribe()
+    }
+
+    /// Advertises the external address in the local ENR, if it changed, which increments the ENR
+    /// sequence number. Then notifies the overlays, which ping a sample of their routing table
+    /// peers, and pings a sample of the discv5 routing table peers, so that they learn the new
+    /// ENR. Returns the number of discv5 peers that answered.
+    pub async fn handle_socket_update(&self, socket_addr: SocketAddr) -> usize {
+        let old_socket_addr = {
+            let mut advertised_address = self.advertised_address.write();
+            if advertised_address.socket_addr == Some(socket_addr) {
+                return 0;
+            }
+            advertised_address.changed_at = Some(
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+            );
+            advertised_address.socket_addr.replace(socket_addr)
+        };
+        // discv5 already updated the ENR if the address was found by voting, in which case it
+        // isn't updated again.
+        self.discv5.update_local_enr_socket(socket_addr, false);
+        PORTALNET_METRICS
+            .overlay()
+            .external_address_change_total
+            .inc();
+        info!(
+            old = ?old_socket_addr,
+            new = %socket_addr,
+            enr.seq = self.discv5.local_enr().seq(),
+            "External address changed, advertising the new address"
+        );
+        // There are no receivers if no overlay is running.
+        let _ = self.enr_update_tx.send(self.discv5.local_enr());
+
+        let peers = self
+            .discv5
+            .table_entries_enr()
+            .into_iter()
+            .choose_multiple(&mut rand::thread_rng(), ADDRESS_CHANGE_PING_SAMPLE);
+        join_all(peers.into_iter().map(|enr| self.discv5.send_ping(enr)))
+            .await
+            .into_iter()
+            .filter(|result| result.is_ok())
+            .count()
+    }
+
+    /// Returns the socket address that the local ENR advertises, and when it last changed.
+    pub fn advertised_address(&self) -> AdvertisedAddress {
+        *self.advertised_address.read()
+    }
+
     /// Returns number of connected peers in the Discv5 routing table.
     pub fn connected_peers_len(&self) -> usize {
         self.discv5.connected_peers()
@@ -264,6 +459,7 @@ impl Discovery {
                 .local_enr()
                 .ip4()
                 .map_or(Some("None".to_owned()), |ip| Some(ip.to_string())),
+            multiaddrs: None,
         })
     }
 
@@ -273,6 +469,8 @@ impl Discovery {
         RoutingTableInfo {
             local_node_id: self.discv5.local_enr().node_id(),
             buckets: self.discv5.kbuckets().into(),
+            protocol_versions: vec![],
+            disconnected_peers: vec![],
         }
     }
 
@@ -291,6 +489,19 @@ impl Discovery {
         self.discv5.find_enr(node_id)
     }
 
+    /// Returns the portal network that the node is on.
+    pub fn network(&self) -> Network {
+        self.network_spec.network()
+    }
+
+    /// Inserts the key-value pair into the local ENR, which increments its sequence number.
+    pub fn enr_insert(&self, key: &str, value: &[u8]) -> Result<(), String> {
+        self.discv5
+            .enr_insert(key, value)
+            .map(|_| ())
+            .map_err(|err| format!("Unable to update local ENR: {err:?}"))
+    }
+
     /// Adds `enr` to the discv5 routing table.
     pub fn add_enr(&self, enr: Enr) -> Result<(), &'static str> {
         self.discv5.add_enr(enr)
@@ -332,13 +543,75 @@ impl Discovery {
         let response = self.discv5.talk_req(enr, protocol, request).await?;
         Ok(Bytes::from(response))
     }
+
+    /// Sends a TALKREQ message with an arbitrary protocol id to `enr`, and returns the raw
+    /// response. Meant for testing protocol extensions, not for portal subnetwork messages.
+    pub async fn send_raw_talk_req(
+        &self,
+        enr: Enr,
+        protocol: Vec<u8>,
+        request: ProtocolRequest,
+    ) -> Result<Bytes, RequestError> {
+        let response = self.discv5.talk_req(enr, protocol, request).await?;
+        Ok(Bytes::from(response))
+    }
 }
 
+/// The socket that uTP packets are sent and received through.
+///
+/// uTP packets are wrapped in discv5 TALKREQ messages, unless the socket has its own UDP port (see
+/// [`Discv5UdpSocket::with_utp_socket`]). Then, packets to the peers that advertise a uTP port in
+/// their ENR are sent directly to that port, while the other peers are still reached through
+/// TALKREQ messages. Packets from both transports are received by the socket.
+///
+/// Raw uTP packets are never demultiplexed from the discv5 packets on the discv5 port, as discv5
+/// owns that socket: sharing the port always means wrapping the uTP packets in TALKREQ messages.
 pub struct Discv5UdpSocket {
     talk_request_receiver: mpsc::UnboundedReceiver<TalkRequest>,
     discv5: Arc<Discovery>,
     enr_cache: Arc<TokioRwLock<LruCache<NodeId, Enr>>>,
     header_oracle: Arc<TokioRwLock<HeaderOracle>>,
+    direct: Option<DirectUtpSocket>,
+    /// The received uTP data packets that wait for the download rate limit, with the instant at
+    /// which they are handed to their transfer.
+    throttled_packets: VecDeque<(Instant, Vec<u8>, UtpEnr)>,
+    /// The queue of the uTP data packets that wait for the upload rate limit, which is started
+    /// when the first packet is throttled.
+    upload_queue: Option<mpsc::Sender<ThrottledUpload>>,
+}
+
+/// A uTP data packet that waits for the upload rate limit, with the instant at which it is sent.
+struct ThrottledUpload {
+    send_at: Instant,
+    packet: Vec<u8>,
+    destination: PacketDestination,
+}
+
+/// Where a uTP packet is sent to.
+enum PacketDestination {
+    /// The uTP address of the peer, on the uTP socket.
+    Direct(Arc<UdpSocket>, SocketAddr),
+    /// The peer, in a discv5 TALKREQ message.
+    TalkRequest(Enr),
+}
+
+/// A uTP packet that was received, before its sender is looked up.
+enum ReceivedPacket {
+    TalkRequest(TalkRequest),
+    /// A packet received on the uTP socket, with its source address.
+    Direct(Vec<u8>, SocketAddr),
+}
+
+/// The UDP socket that uTP packets are exchanged on directly, without discv5.
+struct DirectUtpSocket {
+    socket: Arc<UdpSocket>,
+    /// Whether the socket is bound to an IPv4 address, so that it only reaches the peers' IPv4
+    /// addresses, or to an IPv6 address.
+    is_ipv4: bool,
+    /// The uTP packets received on the socket, with their source address.
+    packet_receiver: mpsc::Receiver<(Vec<u8>, SocketAddr)>,
+    /// The peers that uTP packets were exchanged with, by their uTP address.
+    peers: LruCache<SocketAddr, UtpEnr>,
 }
 
 impl Discv5UdpSocket {
@@ -355,6 +628,158 @@ impl Discv5UdpSocket {
             talk_request_receiver,
             enr_cache,
             header_oracle,
+            direct: None,
+            throttled_packets: VecDeque::new(),
+            upload_queue: None,
+        }
+    }
+
+    /// Exchanges uTP packets directly on the UDP socket, with the peers that advertise a uTP port.
+    ///
+    /// The port of the socket should be advertised in the local ENR, so that peers send their
+    /// packets to it.
+    pub fn with_utp_socket(mut self, socket: UdpSocket) -> Self {
+        let is_ipv4 = socket.local_addr().map_or(true, |addr| addr.is_ipv4());
+        let socket = Arc::new(socket);
+        let (packet_tx, packet_receiver) = mpsc::channel(UTP_PACKET_CHANNEL_BUFFER);
+        let recv_socket = Arc::clone(&socket);
+        tokio::spawn(async move {
+            let mut buf = [0; MAX_UTP_PACKET_SIZE];
+            loop {
+                let (n, src) = match recv_socket.recv_from(&mut buf).await {
+                    Ok(val) => val,
+                    Err(err) => {
+                        warn!(%err, "unable to receive on the uTP socket");
+                        continue;
+                    }
+                };
+                let packet = &buf[..n];
+                if !socket::is_utp_packet(packet) {
+                    debug!(%src, "dropping non-uTP packet received on the uTP socket");
+                    continue;
+                }
+                if packet_tx.send((packet.to_vec(), src)).await.is_err() {
+                    return;
+                }
+            }
+        });
+        self.direct = Some(DirectUtpSocket {
+            socket,
+            is_ipv4,
+            packet_receiver,
+            peers: LruCache::new(UTP_PEERS_CAPACITY),
+        });
+        self
+    }
+
+    /// Waits for the next uTP packet, from discv5 TALKREQ messages or from the uTP socket. Returns
+    /// `None` if the TALKREQ channel is closed.
+    ///
+    /// No packet is lost if the returned future is dropped before it completes.
+    async fn next_packet(&mut self) -> Option<ReceivedPacket> {
+        let direct = &mut self.direct;
+        let direct_packet = async {
+            match direct {
+                Some(direct) => direct.packet_receiver.recv().await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            talk_req = self.talk_request_receiver.recv() => {
+                talk_req.map(ReceivedPacket::TalkRequest)
+            }
+            Some((packet, src)) = direct_packet => Some(ReceivedPacket::Direct(packet, src)),
+        }
+    }
+
+    /// Returns the content of the received packet, along with its sender.
+    async fn resolve_packet(&mut self, packet: ReceivedPacket) -> io::Result<(Vec<u8>, UtpEnr)> {
+        match packet {
+            ReceivedPacket::TalkRequest(talk_req) => {
+                let src_node_id = talk_req.node_id();
+                let enr = self.find_enr(src_node_id).await?;
+                let packet = talk_req.body().to_vec();
+
+                // respond with empty talk response
+                if let Err(err) = talk_req.respond(vec![]) {
+                    warn!(%err, "failed to respond to uTP talk request");
+                }
+
+                Ok((packet, enr))
+            }
+            ReceivedPacket::Direct(packet, src) => {
+                let enr = self.find_enr_by_utp_addr(src)?;
+                Ok((packet, enr))
+            }
+        }
+    }
+
+    /// Returns where the packets to the peer are sent to. Packets are only sent directly if the
+    /// peer advertises a uTP address of the same IP version as the uTP socket.
+    fn destination(&mut self, target: &UtpEnr) -> PacketDestination {
+        if let Some(direct) = &mut self.direct {
+            let is_ipv4 = direct.is_ipv4;
+            if let Some(utp_addr) = target
+                .utp_sockets()
+                .find(|utp_addr| utp_addr.is_ipv4() == is_ipv4)
+            {
+                direct.peers.put(utp_addr, target.clone());
+                return PacketDestination::Direct(Arc::clone(&direct.socket), utp_addr);
+            }
+        }
+        PacketDestination::TalkRequest(target.0.clone())
+    }
+
+    /// Queues the data packet until it is sent at its turn, without holding up the control packets
+    /// that are sent after it. The queue is drained in order, so that the data packets are sent in
+    /// the order that they were queued.
+    ///
+    /// Waits while the queue is full, as the bytes of the packet are already reserved. Dropping it
+    /// would waste its share of the rate limit, and have uTP retransmit it.
+    async fn queue_upload(&mut self, upload: ThrottledUpload) {
+        let upload_queue = self.upload_queue.get_or_insert_with(|| {
+            let (upload_tx, mut upload_rx) =
+                mpsc::channel::<ThrottledUpload>(MAX_THROTTLED_PACKETS);
+            let discv5 = Arc::clone(&self.discv5);
+            tokio::spawn(async move {
+                while let Some(upload) = upload_rx.recv().await {
+                    tokio::time::sleep_until(upload.send_at.into()).await;
+                    send_packet(&discv5, upload.destination, upload.packet).await;
+                }
+            });
+            upload_tx
+        });
+        if upload_queue.send(upload).await.is_err() {
+            warn!("unable to queue uTP packet, the upload queue is closed");
+        }
+    }
+
+    /// Returns the peer that advertises `utp_addr` as its uTP address.
+    fn find_enr_by_utp_addr(&mut self, utp_addr: SocketAddr) -> io::Result<UtpEnr> {
+        let Some(direct) = &mut self.direct else {
+            return Err(io::Error::from(io::ErrorKind::NotConnected));
+        };
+        if let Some(enr) = direct.peers.get(&utp_addr) {
+            return Ok(enr.clone());
+        }
+        let enr = self
+            .discv5
+            .table_entries_enr()
+            .into_iter()
+            .map(UtpEnr)
+            .find(|enr| enr.utp_sockets().any(|addr| addr == utp_addr));
+        match enr {
+            Some(enr) => {
+                direct.peers.put(utp_addr, enr.clone());
+                Ok(enr)
+            }
+            None => {
+                debug!(%utp_addr, "uTP packet from unknown address");
+                Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "ENR not found for uTP packet source",
+                ))
+            }
         }
     }
 
@@ -405,6 +830,87 @@ impl Discv5UdpSocket {
     }
 }
 
+/// Returns the decoded value of the ENR field, or `None` if the ENR doesn't have the field.
+///
+/// The values of the fields with known types, e.g. addresses and ports, are decoded, while the
+/// values of the other fields, or ones that fail to decode, are returned as hex.
+pub fn decode_enr_field(enr: &Enr, key: &str) -> Option<Value> {
+    let value = enr.get(key)?;
+    let decoded = match key {
+        "id" | ENR_PORTAL_CLIENT_KEY | ENR_PORTAL_NETWORK_KEY => {
+            String::from_utf8(value.to_vec()).ok().map(Value::from)
+        }
+        "ip" => enr.ip4().map(|ip| json!(ip)),
+        "ip6" => enr.ip6().map(|ip| json!(ip)),
+        "tcp" | "udp" | "tcp6" | "udp6" | ENR_UTP_PORT_KEY => {
+            decode_enr_port(value).map(Value::from)
+        }
+        ENR_PORTAL_VERSIONS_KEY => Some(json!(value)),
+        _ => None,
+    };
+    Some(decoded.unwrap_or_else(|| Value::from(hex_encode(value))))
+}
+
+/// Decodes a port of the ENR, which is encoded as a big-endian integer.
+fn decode_enr_port(value: &[u8]) -> Option<u16> {
+    if value.len() > 2 {
+        return None;
+    }
+    Some(
+        value
+            .iter()
+            .fold(0u16, |port, byte| (port << 8) | u16::from(*byte)),
+    )
+}
+
+/// Checks that the ENR doesn't advertise a portal network other than `network`.
+///
+/// ENRs without the network field are accepted, as older clients don't advertise it.
+pub fn check_enr_network(enr: &Enr, network: Network) -> Result<(), String> {
+    let Some(enr_network) = enr.get(ENR_PORTAL_NETWORK_KEY) else {
+        return Ok(());
+    };
+    let enr_network = String::from_utf8_lossy(enr_network);
+    if enr_network == network.to_string() {
+        Ok(())
+    } else {
+        Err(format!(
+            "ENR advertises {enr_network} network, but we are on {network} network"
+        ))
+    }
+}
+
+/// Checks that the ENR may be added to the routing table of the node with `local_node_id`.
+///
+/// The ENR is rejected if its signature doesn't verify, if it is our own ENR, if it has no
+/// reachable address, unless `allow_unreachable` is set, or if it is older than `known_enr`, the
+/// ENR that we already have for the node.
+pub fn check_enr(
+    enr: &Enr,
+    local_node_id: NodeId,
+    known_enr: Option<&Enr>,
+    allow_unreachable: bool,
+) -> Result<(), String> {
+    if !enr.verify() {
+        return Err("ENR signature is invalid".to_string());
+    }
+    if enr.node_id() == local_node_id {
+        return Err("ENR is our own ENR".to_string());
+    }
+    if !allow_unreachable && enr.udp4_socket().is_none() && enr.udp6_socket().is_none() {
+        return Err("ENR has no reachable UDP address".to_string());
+    }
+    if let Some(known_enr) = known_enr {
+        if known_enr.seq() > enr.seq() {
+            return Err(format!(
+                "ENR has sequence number {}, but we already have a newer ENR: {known_enr}",
+                enr.seq(),
+            ));
+        }
+    }
+    Ok(())
+}
+
 /// A wrapper around `Enr` that implements `ConnectionPeer`.
 #[derive(Clone)]
 pub struct UtpEnr(pub Enr);
@@ -419,6 +925,23 @@ impl UtpEnr {
             .get(ENR_PORTAL_CLIENT_KEY)
             .and_then(|v| String::from_utf8(v.to_vec()).ok())
     }
+
+    /// Returns the address that the peer exchanges uTP packets on directly, if it advertises one:
+    /// its IPv4 address if it has one, and its IPv6 address otherwise.
+    pub fn utp_socket(&self) -> Option<SocketAddr> {
+        self.utp_sockets().next()
+    }
+
+    /// Returns the addresses that the peer exchanges uTP packets on directly, if it advertises a
+    /// uTP port: its IPv4 address, then its IPv6 address.
+    fn utp_sockets(&self) -> impl Iterator<Item = SocketAddr> {
+        // The port is encoded like the discv5 UDP port.
+        let port = self.0.get(ENR_UTP_PORT_KEY).and_then(decode_enr_port);
+        let ips = [self.0.ip4().map(IpAddr::V4), self.0.ip6().map(IpAddr::V6)];
+        ips.into_iter()
+            .flatten()
+            .filter_map(move |ip| Some(SocketAddr::new(ip, port?)))
+    }
 }
 
 impl std::fmt::Debug for UtpEnr {
@@ -459,40 +982,262 @@ impl ConnectionPeer for UtpEnr {}
 #[async_trait]
 impl AsyncUdpSocket<UtpEnr> for Discv5UdpSocket {
     async fn send_to(&mut self, buf: &[u8], target: &UtpEnr) -> io::Result<usize> {
-        let discv5 = Arc::clone(&self.discv5);
-        let target = target.0.clone();
-        let data = buf.to_vec();
-        tokio::spawn(async move {
-            match discv5.send_talk_req(target, Subnetwork::Utp, data).await {
-                // We drop the talk response because it is ignored in the uTP protocol.
-                Ok(..) => {}
-                Err(err) => match err {
-                    RequestError::Timeout => debug!("uTP talk request timed out"),
-                    err => warn!(%err, "unable to send uTP talk request"),
-                },
+        let destination = self.destination(target);
+
+        // Data packets that exceed the upload rate limit are queued until their turn, while control
+        // packets, e.g. ACKs, are sent right away. The data packets that are sent after a queued
+        // one are queued as well, as the bucket is in debt until the queued packet is sent.
+        if let Some(delay) = self.discv5.utp_throttle().reserve_upload(buf) {
+            if !delay.is_zero() {
+                self.queue_upload(ThrottledUpload {
+                    send_at: Instant::now() + delay,
+                    packet: buf.to_vec(),
+                    destination,
+                })
+                .await;
+                return Ok(buf.len());
             }
-        });
+        }
 
-        Ok(buf.len())
+        match destination {
+            PacketDestination::Direct(socket, utp_addr) => socket.send_to(buf, utp_addr).await,
+            destination => {
+                send_packet(&self.discv5, destination, buf.to_vec()).await;
+                Ok(buf.len())
+            }
+        }
     }
 
     async fn recv_from(&mut self, buf: &mut [u8]) -> io::Result<(usize, UtpEnr)> {
-        match self.talk_request_receiver.recv().await {
-            Some(talk_req) => {
-                let src_node_id = talk_req.node_id();
-                let enr = self.find_enr(src_node_id).await?;
-                let packet = talk_req.body();
-                let n = std::cmp::min(buf.len(), packet.len());
-                buf[..n].copy_from_slice(&packet[..n]);
-
-                // respond with empty talk response
-                if let Err(err) = talk_req.respond(vec![]) {
-                    warn!(%err, "failed to respond to uTP talk request");
+        loop {
+            let ready_at = self
+                .throttled_packets
+                .front()
+                .map(|(ready_at, ..)| *ready_at);
+            let received = match ready_at {
+                Some(ready_at) if ready_at <= Instant::now() => {
+                    if let Some((_, packet, enr)) = self.throttled_packets.pop_front() {
+                        return Ok((copy_packet(&packet, buf), enr));
+                    }
+                    continue;
                 }
+                Some(ready_at) => tokio::select! {
+                    _ = tokio::time::sleep_until(ready_at.into()) => continue,
+                    received = self.next_packet() => received,
+                },
+                None => self.next_packet().await,
+            };
+            let Some(received) = received else {
+                return Err(io::Error::from(io::ErrorKind::NotConnected));
+            };
+            let (packet, enr) = self.resolve_packet(received).await?;
+
+            // The data packets that don't fit in the queue are dropped before their bytes are
+            // reserved, so that they don't use up the download rate limit.
+            if self.throttled_packets.len() >= MAX_THROTTLED_PACKETS
+                && socket::is_utp_data_packet(&packet)
+            {
+                debug!(peer = ?enr.client(), "dropping uTP packet above the download rate limit");
+                continue;
+            }
+
+            // Control packets skip the data packets that wait for the download rate limit. Data
+            // packets wait behind each other, so that they are handed over in order.
+            let Some(delay) = self.discv5.utp_throttle().reserve_download(&packet) else {
+                return Ok((copy_packet(&packet, buf), enr));
+            };
+            let now = Instant::now();
+            let ready_at = match self.throttled_packets.back() {
+                Some((last_ready_at, ..)) => (now + delay).max(*last_ready_at),
+                None if delay.is_zero() => return Ok((copy_packet(&packet, buf), enr)),
+                None => now + delay,
+            };
+            self.throttled_packets.push_back((ready_at, packet, enr));
+        }
+    }
+}
 
-                Ok((n, enr))
+/// Sends the uTP packet to its destination. Errors are logged, as uTP retransmits lost packets.
+///
+/// TALKREQ messages are sent in the background, as their response, which uTP ignores, isn't
+/// awaited.
+async fn send_packet(discv5: &Arc<Discovery>, destination: PacketDestination, packet: Vec<u8>) {
+    match destination {
+        PacketDestination::Direct(socket, utp_addr) => {
+            if let Err(err) = socket.send_to(&packet, utp_addr).await {
+                warn!(%err, %utp_addr, "unable to send uTP packet");
             }
-            None => Err(io::Error::from(io::ErrorKind::NotConnected)),
         }
+        PacketDestination::TalkRequest(target) => {
+            let discv5 = Arc::clone(discv5);
+            tokio::spawn(async move {
+                match discv5.send_talk_req(target, Subnetwork::Utp, packet).await {
+                    // We drop the talk response because it is ignored in the uTP protocol.
+                    Ok(..) => {}
+                    Err(err) => match err {
+                        RequestError::Timeout => debug!("uTP talk request timed out"),
+                        err => warn!(%err, "unable to send uTP talk request"),
+                    },
+                }
+            });
+        }
+    }
+}
+
+/// Copies the packet into the buffer, truncated to the size of the buffer, and returns the number
+/// of copied bytes.
+fn copy_packet(packet: &[u8], buf: &mut [u8]) -> usize {
+    let n = std::cmp::min(buf.len(), packet.len());
+    buf[..n].copy_from_slice(&packet[..n]);
+    n
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ethportal_api::types::enr::{generate_random_remote_enr, RpcEnr};
+
+    use super::*;
+
+    fn enr_with_seq(key: &CombinedKey, seq: u64) -> Enr {
+        Discv5Enr::builder()
+            .ip4(Ipv4Addr::new(10, 0, 0, 1))
+            .udp4(9009)
+            .seq(seq)
+            .build(key)
+            .unwrap()
+    }
+
+    #[test]
+    fn check_enr_accepts_valid_enr() {
+        let (_, enr) = generate_random_remote_enr();
+        assert!(check_enr(&enr, NodeId::random(), None, false).is_ok());
+        assert!(check_enr(&enr, NodeId::random(), Some(&enr), false).is_ok());
+    }
+
+    #[test]
+    fn check_enr_rejects_own_enr() {
+        let (_, enr) = generate_random_remote_enr();
+        let err = check_enr(&enr, enr.node_id(), None, false).unwrap_err();
+        assert_eq!(err, "ENR is our own ENR");
+    }
+
+    #[test]
+    fn check_enr_rejects_unreachable_enr() {
+        let key = CombinedKey::generate_secp256k1();
+        let enr = Discv5Enr::builder().build(&key).unwrap();
+        let err = check_enr(&enr, NodeId::random(), None, false).unwrap_err();
+        assert_eq!(err, "ENR has no reachable UDP address");
+        assert!(check_enr(&enr, NodeId::random(), None, true).is_ok());
+    }
+
+    #[test]
+    fn check_enr_rejects_older_enr() {
+        let key = CombinedKey::generate_secp256k1();
+        let older = enr_with_seq(&key, 1);
+        let newer = enr_with_seq(&key, 2);
+
+        let err = check_enr(&older, NodeId::random(), Some(&newer), false).unwrap_err();
+        assert!(err.contains(&newer.to_base64()), "{err}");
+        assert!(check_enr(&newer, NodeId::random(), Some(&older), false).is_ok());
+    }
+
+    #[test]
+    fn utp_socket_is_advertised_in_enr() {
+        let key = CombinedKey::generate_secp256k1();
+        let enr = enr_with_seq(&key, 1);
+        assert_eq!(UtpEnr(enr).utp_socket(), None);
+
+        for port in [9010u16, 100] {
+            let enr = Discv5Enr::builder()
+                .ip4(Ipv4Addr::new(10, 0, 0, 1))
+                .udp4(9009)
+                .add_value(ENR_UTP_PORT_KEY, &port)
+                .build(&key)
+                .unwrap();
+            assert_eq!(
+                UtpEnr(enr).utp_socket(),
+                Some(SocketAddr::from(([10, 0, 0, 1], port)))
+            );
+        }
+    }
+
+    #[test]
+    fn utp_socket_of_ipv6_enr() {
+        let key = CombinedKey::generate_secp256k1();
+        let ip6 = "2001:db8::1".parse::<std::net::Ipv6Addr>().unwrap();
+        let enr = Discv5Enr::builder()
+            .ip6(ip6)
+            .udp6(9009)
+            .add_value(ENR_UTP_PORT_KEY, &9010u16)
+            .build(&key)
+            .unwrap();
+        assert_eq!(
+            UtpEnr(enr).utp_socket(),
+            Some(SocketAddr::new(ip6.into(), 9010))
+        );
+
+        // The IPv4 address comes first, but both are uTP addresses of the peer.
+        let enr = Discv5Enr::builder()
+            .ip4(Ipv4Addr::new(10, 0, 0, 1))
+            .udp4(9009)
+            .ip6(ip6)
+            .udp6(9009)
+            .add_value(ENR_UTP_PORT_KEY, &9010u16)
+            .build(&key)
+            .unwrap();
+        assert_eq!(
+            UtpEnr(enr).utp_sockets().collect::<Vec<_>>(),
+            vec![
+                SocketAddr::from(([10, 0, 0, 1], 9010)),
+                SocketAddr::new(ip6.into(), 9010)
+            ]
+        );
+    }
+
+    #[test]
+    fn enr_fields_are_decoded() {
+        let key = CombinedKey::generate_secp256k1();
+        let enr = Discv5Enr::builder()
+            .ip4(Ipv4Addr::new(10, 0, 0, 1))
+            .udp4(9009)
+            .add_value(ENR_PORTAL_CLIENT_KEY, &"t trin".as_bytes())
+            .add_value(ENR_PORTAL_VERSIONS_KEY, &[0u8, 1].as_slice())
+            .add_value("eth2", &[0xab_u8; 16].as_slice())
+            .build(&key)
+            .unwrap();
+
+        assert_eq!(decode_enr_field(&enr, "id"), Some(json!("v4")));
+        assert_eq!(decode_enr_field(&enr, "ip"), Some(json!("10.0.0.1")));
+        assert_eq!(decode_enr_field(&enr, "udp"), Some(json!(9009)));
+        assert_eq!(
+            decode_enr_field(&enr, ENR_PORTAL_CLIENT_KEY),
+            Some(json!("t trin"))
+        );
+        assert_eq!(
+            decode_enr_field(&enr, ENR_PORTAL_VERSIONS_KEY),
+            Some(json!([0, 1]))
+        );
+        assert_eq!(
+            decode_enr_field(&enr, "eth2"),
+            Some(json!(hex_encode([0xab; 16])))
+        );
+        assert_eq!(decode_enr_field(&enr, "tcp"), None);
+    }
+
+    #[test]
+    fn enr_with_invalid_signature_is_rejected() {
+        let (_, enr) = generate_random_remote_enr();
+        assert!(enr.verify());
+
+        // ENRs with an invalid signature already fail to decode, so they can't reach `check_enr`
+        // through the JSON-RPC API.
+        // Change a character of the base64 encoding that falls within the signature, which is the
+        // first item of the record.
+        let mut tampered = enr.to_base64().into_bytes();
+        let index = "enr:".len() + 10;
+        tampered[index] = if tampered[index] == b'A' { b'B' } else { b'A' };
+        let tampered = String::from_utf8(tampered).unwrap();
+        assert!(tampered.parse::<RpcEnr>().is_err());
     }
 }
@@ -5,13 +5,14 @@ use std::{
 
 use discv5::TalkRequest;
 use ethportal_api::{
-    types::{network::Subnetwork, portal_wire::NetworkSpec},
+    types::{distance::Distance, network::Subnetwork, portal_wire::NetworkSpec},
     utils::bytes::{hex_encode, hex_encode_upper},
 };
 use futures::stream::{select_all, StreamExt};
 use tokio::sync::{broadcast, mpsc};
 use tokio_stream::wrappers::BroadcastStream;
 use tracing::{debug, error, trace, warn};
+use trin_metrics::portalnet::PORTALNET_METRICS;
 
 /// Handles for communication between the main event handler and an overlay.
 pub struct OverlayHandle {
@@ -156,12 +157,33 @@ impl PortalnetEvents {
                     );
                 }
             },
-            Err(err) => warn!(
-                "Received TalkRequest on unknown protocol from={} protocol={} body={} err={err}",
-                request.node_id(),
-                hex_encode_upper(request.protocol()),
-                hex_encode(request.body()),
-            ),
+            Err(err) => {
+                let protocol = hex_encode_upper(request.protocol());
+                match self
+                    .network_spec
+                    .foreign_network_of_protocol_identifier(&protocol)
+                {
+                    Some(network) => {
+                        // The peer is configured for a different network. Drop the request, so
+                        // that it doesn't get into our routing tables.
+                        PORTALNET_METRICS
+                            .overlay()
+                            .cross_network_talk_request_total
+                            .with_label_values(&[&network.to_string()])
+                            .inc();
+                        warn!(
+                            "Dropped TalkRequest for {network} network from={} protocol={protocol}, we are on {} network",
+                            request.node_id(),
+                            self.network_spec.network(),
+                        );
+                    }
+                    None => warn!(
+                        "Received TalkRequest on unknown protocol from={} protocol={protocol} body={} err={err}",
+                        request.node_id(),
+                        hex_encode(request.body()),
+                    ),
+                }
+            }
         }
     }
 
@@ -226,6 +248,11 @@ impl PortalnetEvents {
 pub enum OverlayEvent {
     LightClientOptimisticUpdate,
     LightClientFinalityUpdate,
+    /// The data radius of the subnetwork changed.
+    RadiusChanged {
+        old_radius: Distance,
+        new_radius: Distance,
+    },
 }
 
 /// Timestamp of an overlay event.
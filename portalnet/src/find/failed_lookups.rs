@@ -0,0 +1,184 @@
+use std::time::{Duration, Instant};
+
+use ethportal_api::types::cli::{
+    DEFAULT_FAILED_LOOKUP_CACHE_SIZE, DEFAULT_FAILED_LOOKUP_CACHE_TTL_SECS,
+};
+use lru::LruCache;
+
+use crate::overlay::errors::OverlayRequestError;
+
+/// Configuration of the cache of recently failed content lookups.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FailedLookupCacheConfig {
+    /// The time for which a failed lookup is remembered.
+    pub ttl: Duration,
+    /// The max number of failed lookups that are remembered.
+    pub capacity: usize,
+}
+
+impl Default for FailedLookupCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(DEFAULT_FAILED_LOOKUP_CACHE_TTL_SECS),
+            capacity: DEFAULT_FAILED_LOOKUP_CACHE_SIZE,
+        }
+    }
+}
+
+/// A content lookup that didn't find the content.
+struct FailedLookup {
+    /// The error returned by the lookup.
+    error: OverlayRequestError,
+    /// When the lookup started, in milliseconds since the Unix epoch.
+    started_at_ms: u64,
+    /// When the lookup failed.
+    failed_at: Instant,
+}
+
+/// Remembers the content lookups that recently didn't find the content, so that repeated requests
+/// for missing content are answered without querying the network again.
+pub struct FailedLookupCache {
+    ttl: Duration,
+    failed_lookups: LruCache<[u8; 32], FailedLookup>,
+}
+
+impl FailedLookupCache {
+    pub fn new(config: FailedLookupCacheConfig) -> Self {
+        Self {
+            ttl: config.ttl,
+            failed_lookups: LruCache::new(config.capacity),
+        }
+    }
+
+    /// Records that the lookup of the content, started at `started_at_ms`, failed with `error`.
+    ///
+    /// Only failures to find the content are recorded.
+    pub fn insert(
+        &mut self,
+        content_id: [u8; 32],
+        error: &OverlayRequestError,
+        started_at_ms: u64,
+    ) {
+        if !matches!(error, OverlayRequestError::ContentNotFound { .. }) {
+            return;
+        }
+        self.failed_lookups.put(
+            content_id,
+            FailedLookup {
+                error: error.clone(),
+                started_at_ms,
+                failed_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Returns the error of the recent failed lookup of the content, marked as cached, if any.
+    ///
+    /// A failed lookup without a trace doesn't serve requests for a trace, and the trace is only
+    /// returned to requests for one.
+    pub fn get(&mut self, content_id: &[u8; 32], is_trace: bool) -> Option<OverlayRequestError> {
+        let failed_lookup = self.failed_lookups.get(content_id)?;
+        if failed_lookup.failed_at.elapsed() >= self.ttl {
+            self.failed_lookups.pop(content_id);
+            return None;
+        }
+        match failed_lookup.error.clone() {
+            OverlayRequestError::ContentNotFound {
+                message,
+                utp,
+                trace,
+                closest_enrs,
+                ..
+            } => {
+                if is_trace && trace.is_none() {
+                    return None;
+                }
+                Some(OverlayRequestError::ContentNotFound {
+                    message,
+                    utp,
+                    trace: if is_trace { trace } else { None },
+                    closest_enrs,
+                    cached_at_ms: Some(failed_lookup.started_at_ms),
+                })
+            }
+            error => Some(error),
+        }
+    }
+
+    /// Forgets the failed lookup of the content, e.g. because the content was since stored.
+    pub fn invalidate(&mut self, content_id: &[u8; 32]) {
+        self.failed_lookups.pop(content_id);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const CONTENT_ID: [u8; 32] = [1; 32];
+
+    fn content_not_found() -> OverlayRequestError {
+        OverlayRequestError::ContentNotFound {
+            message: "Unable to locate content on the network".to_string(),
+            utp: false,
+            trace: None,
+            closest_enrs: vec![],
+            cached_at_ms: None,
+        }
+    }
+
+    fn cache(ttl: Duration) -> FailedLookupCache {
+        FailedLookupCache::new(FailedLookupCacheConfig {
+            ttl,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn returns_cached_failure() {
+        let mut cache = cache(Duration::from_secs(60));
+        cache.insert(CONTENT_ID, &content_not_found(), 1_000);
+
+        match cache.get(&CONTENT_ID, false) {
+            Some(OverlayRequestError::ContentNotFound { cached_at_ms, .. }) => {
+                assert_eq!(cached_at_ms, Some(1_000))
+            }
+            other => panic!("Unexpected cached failure: {other:?}"),
+        }
+        assert!(cache.get(&[2; 32], false).is_none());
+    }
+
+    #[test]
+    fn ignores_other_failures() {
+        let mut cache = cache(Duration::from_secs(60));
+        cache.insert(CONTENT_ID, &OverlayRequestError::Timeout, 1_000);
+        assert!(cache.get(&CONTENT_ID, false).is_none());
+    }
+
+    #[test]
+    fn untraced_failure_does_not_serve_trace_requests() {
+        let mut cache = cache(Duration::from_secs(60));
+        cache.insert(CONTENT_ID, &content_not_found(), 1_000);
+        assert!(cache.get(&CONTENT_ID, true).is_none());
+        assert!(cache.get(&CONTENT_ID, false).is_some());
+    }
+
+    #[test]
+    fn invalidate() {
+        let mut cache = cache(Duration::from_secs(60));
+        cache.insert(CONTENT_ID, &content_not_found(), 1_000);
+        cache.invalidate(&CONTENT_ID);
+        assert!(cache.get(&CONTENT_ID, false).is_none());
+    }
+
+    #[test]
+    fn expires_after_ttl() {
+        let mut cache = cache(Duration::from_millis(50));
+        cache.insert(CONTENT_ID, &content_not_found(), 1_000);
+        assert!(cache.get(&CONTENT_ID, false).is_some());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(cache.get(&CONTENT_ID, false).is_none());
+    }
+}
@@ -0,0 +1,267 @@
+use std::{collections::HashMap, future::Future, sync::Arc, time::Duration};
+
+use futures::{
+    future::{BoxFuture, Shared, WeakShared},
+    FutureExt,
+};
+use parking_lot::Mutex;
+
+use super::query_info::RecursiveFindContentResult;
+use crate::overlay::{config::FindContentConfig, errors::OverlayRequestError};
+
+/// The result of a lookup, the outer error being a failure to submit the lookup to the overlay
+/// service.
+pub type LookupResult = Result<RecursiveFindContentResult, OverlayRequestError>;
+
+type SharedLookup = Shared<BoxFuture<'static, LookupResult>>;
+
+/// A lookup of content on the network, which other requests for the same content may join.
+struct InFlightLookup {
+    /// Identifies the lookup, so that it is only removed by its own cleanup.
+    id: u64,
+    /// Whether the lookup traces the actions of each peer.
+    is_trace: bool,
+    /// The custom timeout of the lookup, if any.
+    timeout: Option<Duration>,
+    /// The result of the lookup. It is only weakly referenced, so that the lookup is dropped once
+    /// nobody awaits it anymore.
+    result: WeakShared<BoxFuture<'static, LookupResult>>,
+}
+
+impl InFlightLookup {
+    /// Returns whether a request with the given config can be served by this lookup.
+    fn serves(&self, config: &FindContentConfig) -> bool {
+        (self.is_trace || !config.is_trace) && self.timeout == config.timeout
+    }
+}
+
+#[derive(Default)]
+struct Lookups {
+    next_id: u64,
+    by_content_id: HashMap<[u8; 32], InFlightLookup>,
+}
+
+/// Coalesces concurrent lookups of the same content, so that only one of them goes to the network
+/// and all the requesters receive its result.
+///
+/// A request that wants a trace only joins a lookup that is traced. If the in-flight lookup isn't
+/// traced, a new traced lookup is started, which the following requests then join.
+#[derive(Clone, Default)]
+pub struct InFlightLookups {
+    lookups: Arc<Mutex<Lookups>>,
+}
+
+impl InFlightLookups {
+    /// Returns the result of the in-flight lookup of the content, starting a new lookup with
+    /// `start_lookup` if none can serve the request.
+    pub async fn lookup<F, Fut>(
+        &self,
+        content_id: [u8; 32],
+        config: FindContentConfig,
+        start_lookup: F,
+    ) -> LookupResult
+    where
+        F: FnOnce(FindContentConfig) -> Fut,
+        Fut: Future<Output = LookupResult> + Send + 'static,
+    {
+        let is_trace = config.is_trace;
+        let lookup = self.join_or_start(content_id, config, start_lookup);
+        let result = lookup.await;
+        if is_trace {
+            result
+        } else {
+            result.map(|result| {
+                result.map(|(content, utp_transfer, _)| (content, utp_transfer, None))
+            })
+        }
+    }
+
+    fn join_or_start<F, Fut>(
+        &self,
+        content_id: [u8; 32],
+        config: FindContentConfig,
+        start_lookup: F,
+    ) -> SharedLookup
+    where
+        F: FnOnce(FindContentConfig) -> Fut,
+        Fut: Future<Output = LookupResult> + Send + 'static,
+    {
+        let mut lookups = self.lookups.lock();
+        let in_flight = lookups
+            .by_content_id
+            .get(&content_id)
+            .filter(|lookup| lookup.serves(&config))
+            .and_then(|lookup| lookup.result.upgrade());
+        if let Some(lookup) = in_flight {
+            return lookup;
+        }
+
+        let id = lookups.next_id;
+        lookups.next_id += 1;
+        let is_trace = config.is_trace;
+        let timeout = config.timeout;
+        // The guard is dropped along with the lookup future, when the lookup completes or when all
+        // the requesters stop awaiting it.
+        let guard = LookupGuard {
+            lookups: Arc::clone(&self.lookups),
+            content_id,
+            id,
+        };
+        let lookup_future = start_lookup(config);
+        let lookup = async move {
+            let _guard = guard;
+            lookup_future.await
+        }
+        .boxed()
+        .shared();
+        if let Some(result) = lookup.downgrade() {
+            lookups.by_content_id.insert(
+                content_id,
+                InFlightLookup {
+                    id,
+                    is_trace,
+                    timeout,
+                    result,
+                },
+            );
+        }
+        lookup
+    }
+
+    /// Returns the number of in-flight lookups.
+    pub fn len(&self) -> usize {
+        self.lookups.lock().by_content_id.len()
+    }
+
+    /// Returns whether there are no in-flight lookups.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Removes the lookup from the in-flight lookups when dropped.
+struct LookupGuard {
+    lookups: Arc<Mutex<Lookups>>,
+    content_id: [u8; 32],
+    id: u64,
+}
+
+impl Drop for LookupGuard {
+    fn drop(&mut self) {
+        let mut lookups = self.lookups.lock();
+        if lookups
+            .by_content_id
+            .get(&self.content_id)
+            .is_some_and(|lookup| lookup.id == self.id)
+        {
+            lookups.by_content_id.remove(&self.content_id);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use ethportal_api::{
+        types::{enr::generate_random_remote_enr, query_trace::QueryTrace},
+        RawContentValue,
+    };
+
+    use super::*;
+
+    const CONTENT_ID: [u8; 32] = [1; 32];
+
+    /// A mock network, which counts the lookups. Each lookup finds the content after yielding once,
+    /// so that concurrent requests can join it.
+    #[derive(Clone, Default)]
+    struct MockNetwork {
+        queries: Arc<AtomicUsize>,
+    }
+
+    impl MockNetwork {
+        fn start_lookup(
+            &self,
+        ) -> impl FnOnce(FindContentConfig) -> BoxFuture<'static, LookupResult> {
+            let queries = Arc::clone(&self.queries);
+            move |config| {
+                queries.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    tokio::task::yield_now().await;
+                    let trace = config.is_trace.then(|| {
+                        QueryTrace::new(&generate_random_remote_enr().1, CONTENT_ID.into())
+                    });
+                    Ok(Ok((RawContentValue::from(vec![0xab]), false, trace)))
+                }
+                .boxed()
+            }
+        }
+
+        fn queries(&self) -> usize {
+            self.queries.load(Ordering::SeqCst)
+        }
+    }
+
+    fn config(is_trace: bool) -> FindContentConfig {
+        FindContentConfig {
+            is_trace,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_lookups_share_one_query() {
+        let lookups = InFlightLookups::default();
+        let network = MockNetwork::default();
+
+        let (first, second, third) = tokio::join!(
+            lookups.lookup(CONTENT_ID, config(false), network.start_lookup()),
+            lookups.lookup(CONTENT_ID, config(false), network.start_lookup()),
+            lookups.lookup(CONTENT_ID, config(false), network.start_lookup()),
+        );
+
+        assert_eq!(network.queries(), 1);
+        for result in [first, second, third] {
+            let (content, utp_transfer, trace) = result.unwrap().unwrap();
+            assert_eq!(content, RawContentValue::from(vec![0xab]));
+            assert!(!utp_transfer);
+            assert!(trace.is_none());
+        }
+        assert!(lookups.is_empty());
+    }
+
+    #[tokio::test]
+    async fn traced_lookup_is_shared_with_untraced_requests() {
+        let lookups = InFlightLookups::default();
+        let network = MockNetwork::default();
+
+        let (untraced, traced, joined_traced, joined_untraced) = tokio::join!(
+            lookups.lookup(CONTENT_ID, config(false), network.start_lookup()),
+            lookups.lookup(CONTENT_ID, config(true), network.start_lookup()),
+            lookups.lookup(CONTENT_ID, config(true), network.start_lookup()),
+            lookups.lookup(CONTENT_ID, config(false), network.start_lookup()),
+        );
+
+        // The untraced lookup can't serve a request for a trace, so a traced lookup is started,
+        // which serves all the following requests.
+        assert_eq!(network.queries(), 2);
+        assert!(untraced.unwrap().unwrap().2.is_none());
+        assert!(traced.unwrap().unwrap().2.is_some());
+        assert!(joined_traced.unwrap().unwrap().2.is_some());
+        assert!(joined_untraced.unwrap().unwrap().2.is_none());
+        assert!(lookups.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancelled_lookup_is_removed() {
+        let lookups = InFlightLookups::default();
+
+        let lookup = lookups.lookup(CONTENT_ID, config(false), |_| {
+            futures::future::pending().boxed()
+        });
+        let _ = tokio::time::timeout(Duration::from_millis(10), lookup).await;
+
+        assert!(lookups.is_empty());
+    }
+}
@@ -71,7 +71,9 @@ pub enum FindContentQueryPending<TNodeId> {
 
 #[derive(Debug)]
 pub enum FindContentQueryResult<TNodeId> {
-    NoneFound,
+    /// No content returned. Includes the closest peers to the target that responded to the query,
+    /// ordered by increasing distance.
+    NoneFound(Vec<TNodeId>),
     /// Content returned, but not yet validated. Also includes a list of peers that were cancelled
     ValidContent(ValidatedContent<TNodeId>, Vec<TNodeId>),
 }
@@ -362,7 +364,7 @@ where
             Some(validated_content) => {
                 FindContentQueryResult::ValidContent(validated_content, cancelled_peers)
             }
-            None => FindContentQueryResult::NoneFound,
+            None => FindContentQueryResult::NoneFound(self.responded_peers()),
         }
     }
 
@@ -472,6 +474,24 @@ where
             .collect()
     }
 
+    /// Returns the closest peers that responded to the query, ordered by increasing distance.
+    fn responded_peers(&self) -> Vec<TNodeId> {
+        self.closest_peers
+            .values()
+            .filter(|peer| matches!(peer.state(), QueryPeerState::Succeeded))
+            .map(|peer| peer.key().clone().into_preimage())
+            .take(self.config.num_results)
+            .collect()
+    }
+
+    /// Returns the number of peers that the query sent a request to.
+    pub fn num_contacted_peers(&self) -> usize {
+        self.closest_peers
+            .values()
+            .filter(|peer| !matches!(peer.state(), QueryPeerState::NotContacted))
+            .count()
+    }
+
     /// Return a list of peers with whom we have unresolved queries, for use in trace result.
     /// Do not include the source who returned the content.
     pub fn pending_peers(&self, source: TNodeId) -> Vec<TNodeId> {
@@ -561,7 +581,7 @@ mod tests {
 
         let result = query.into_result();
         match result {
-            FindContentQueryResult::NoneFound => {}
+            FindContentQueryResult::NoneFound(closest_peers) => assert!(closest_peers.is_empty()),
             _ => panic!("Unexpected result variant from new query"),
         }
     }
@@ -744,6 +764,7 @@ mod tests {
             // the case if the query finished without content and with fewer than the
             // requested number of results.
             let final_peers = query.closest_peers.clone();
+            let num_results = query.config.num_results;
             let uncontacted: Vec<_> = final_peers
                 .values()
                 .filter(|e| {
@@ -761,13 +782,22 @@ mod tests {
                     let content_peer = content_peer.unwrap();
                     assert_eq!(validated_content.sending_peer, content_peer.into_preimage());
                 }
-                FindContentQueryResult::NoneFound => {
+                FindContentQueryResult::NoneFound(closest_peers) => {
                     // All peers must have been contacted.
                     assert_eq!(
                         uncontacted.len(),
                         0,
                         "Not all peers have been contacted: {uncontacted:?}"
                     );
+
+                    // The closest peers that responded must be returned, ordered by distance.
+                    let expected_closest_peers: Vec<_> = final_peers
+                        .values()
+                        .filter(|peer| matches!(peer.state(), QueryPeerState::Succeeded))
+                        .map(|peer| peer.key().clone().into_preimage())
+                        .take(num_results)
+                        .collect();
+                    assert_eq!(closest_peers, expected_closest_peers);
                 }
             }
         }
@@ -866,7 +896,7 @@ mod tests {
             // The query may be finished if the first peer was the only peer, because there would
             // not be any additional peers to contact.
             match result {
-                FindContentQueryResult::NoneFound => {}
+                FindContentQueryResult::NoneFound(_) => {}
                 r => panic!("Unexpected result: {r:?}"),
             }
             true
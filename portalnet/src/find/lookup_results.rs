@@ -0,0 +1,159 @@
+use std::time::{Duration, Instant};
+
+use ethportal_api::RawContentValue;
+use lru::LruCache;
+
+/// Configuration of the cache of recently found content.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LookupCacheConfig {
+    /// The time for which found content is remembered.
+    pub ttl: Duration,
+    /// The max total size, in bytes, of the remembered content.
+    pub max_bytes: u64,
+}
+
+/// Content found by a recent lookup.
+struct LookupResult {
+    content: RawContentValue,
+    /// Whether the content was transferred over uTP.
+    utp_transfer: bool,
+    found_at: Instant,
+}
+
+/// Remembers the content recently found by lookups, so that repeated lookups of the same content
+/// are answered without querying the network again.
+///
+/// The content isn't stored, it is only kept in memory until it expires, or until it is evicted to
+/// keep the total size of the content under the limit.
+pub struct LookupResultCache {
+    ttl: Duration,
+    max_bytes: u64,
+    /// The total size of the content in the cache.
+    bytes: u64,
+    results: LruCache<[u8; 32], LookupResult>,
+}
+
+impl LookupResultCache {
+    pub fn new(config: LookupCacheConfig) -> Self {
+        Self {
+            ttl: config.ttl,
+            max_bytes: config.max_bytes,
+            bytes: 0,
+            results: LruCache::unbounded(),
+        }
+    }
+
+    /// Records that the lookup of the content found it. Content larger than the size limit isn't
+    /// recorded.
+    pub fn insert(&mut self, content_id: [u8; 32], content: RawContentValue, utp_transfer: bool) {
+        let size = content.len() as u64;
+        if size > self.max_bytes {
+            return;
+        }
+        self.remove(&content_id);
+        while self.bytes + size > self.max_bytes {
+            match self.results.pop_lru() {
+                Some((_, result)) => self.bytes -= result.content.len() as u64,
+                None => break,
+            }
+        }
+        self.bytes += size;
+        self.results.put(
+            content_id,
+            LookupResult {
+                content,
+                utp_transfer,
+                found_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Returns the content recently found by a lookup, and whether it was transferred over uTP,
+    /// if any.
+    pub fn get(&mut self, content_id: &[u8; 32]) -> Option<(RawContentValue, bool)> {
+        let result = self.results.get(content_id)?;
+        if result.found_at.elapsed() >= self.ttl {
+            self.remove(content_id);
+            return None;
+        }
+        Some((result.content.clone(), result.utp_transfer))
+    }
+
+    /// Forgets all the found content, and returns how many entries were forgotten.
+    pub fn clear(&mut self) -> usize {
+        let len = self.results.len();
+        self.results.clear();
+        self.bytes = 0;
+        len
+    }
+
+    fn remove(&mut self, content_id: &[u8; 32]) {
+        if let Some(result) = self.results.pop(content_id) {
+            self.bytes -= result.content.len() as u64;
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const CONTENT_ID: [u8; 32] = [1; 32];
+
+    fn cache(ttl: Duration, max_bytes: u64) -> LookupResultCache {
+        LookupResultCache::new(LookupCacheConfig { ttl, max_bytes })
+    }
+
+    #[test]
+    fn returns_cached_content() {
+        let mut cache = cache(Duration::from_secs(60), 1_000);
+        let content = RawContentValue::from(vec![0xab; 10]);
+        cache.insert(CONTENT_ID, content.clone(), true);
+
+        assert_eq!(cache.get(&CONTENT_ID), Some((content, true)));
+        assert!(cache.get(&[2; 32]).is_none());
+    }
+
+    #[test]
+    fn expires_after_ttl() {
+        let mut cache = cache(Duration::from_millis(50), 1_000);
+        cache.insert(CONTENT_ID, RawContentValue::from(vec![0xab; 10]), false);
+        assert!(cache.get(&CONTENT_ID).is_some());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(cache.get(&CONTENT_ID).is_none());
+        assert_eq!(cache.bytes, 0);
+    }
+
+    #[test]
+    fn evicts_least_recently_used_over_size_limit() {
+        let mut cache = cache(Duration::from_secs(60), 100);
+        cache.insert([1; 32], RawContentValue::from(vec![0; 40]), false);
+        cache.insert([2; 32], RawContentValue::from(vec![0; 40]), false);
+        // Using the first content makes the second one the least recently used.
+        assert!(cache.get(&[1; 32]).is_some());
+        cache.insert([3; 32], RawContentValue::from(vec![0; 40]), false);
+
+        assert!(cache.get(&[1; 32]).is_some());
+        assert!(cache.get(&[2; 32]).is_none());
+        assert!(cache.get(&[3; 32]).is_some());
+        assert_eq!(cache.bytes, 80);
+
+        // Content over the size limit isn't cached.
+        cache.insert([4; 32], RawContentValue::from(vec![0; 101]), false);
+        assert!(cache.get(&[4; 32]).is_none());
+        assert_eq!(cache.bytes, 80);
+    }
+
+    #[test]
+    fn clear() {
+        let mut cache = cache(Duration::from_secs(60), 1_000);
+        cache.insert([1; 32], RawContentValue::from(vec![0; 10]), false);
+        cache.insert([2; 32], RawContentValue::from(vec![0; 10]), false);
+
+        assert_eq!(cache.clear(), 2);
+        assert!(cache.get(&[1; 32]).is_none());
+        assert_eq!(cache.bytes, 0);
+    }
+}
@@ -1,3 +1,6 @@
+pub mod failed_lookups;
+pub mod in_flight_lookups;
 pub mod iterators;
+pub mod lookup_results;
 pub mod query_info;
 pub mod query_pool;
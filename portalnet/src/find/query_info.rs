@@ -8,6 +8,7 @@ use ethportal_api::{
 };
 use futures::channel::oneshot;
 use smallvec::SmallVec;
+use tracing::Span;
 
 use crate::{find::query_pool::TargetKey, overlay::errors::OverlayRequestError};
 
@@ -48,6 +49,10 @@ pub enum QueryType<TContentKey> {
 
         /// The number of distances we request for each peer.
         distances_to_request: usize,
+
+        /// The routing table bucket that the query refreshes, which is recorded as refreshed once
+        /// the query completes.
+        refreshed_bucket: Option<usize>,
     },
     /// The user requested a `FIND_CONTENT` query to be performed.
     FindContent {
@@ -56,6 +61,9 @@ pub enum QueryType<TContentKey> {
 
         /// A callback channel for the result of the query.
         callback: Option<oneshot::Sender<RecursiveFindContentResult>>,
+
+        /// The span of the issuer of the query.
+        span: Span,
     },
 }
 
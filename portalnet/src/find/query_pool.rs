@@ -98,9 +98,9 @@ where
         }
     }
 
-    /// Returns an iterator over the queries in the pool.
-    pub fn iter(&self) -> impl Iterator<Item = &(QueryInfo<TContentKey>, TQuery)> {
-        self.queries.values()
+    /// Returns an iterator over the queries in the pool, along with their ids.
+    pub fn iter(&self) -> impl Iterator<Item = (&QueryId, &(QueryInfo<TContentKey>, TQuery))> {
+        self.queries.iter()
     }
 
     /// Adds a query to the pool.
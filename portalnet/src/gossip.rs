@@ -1,30 +1,134 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 use ethportal_api::{
     types::{
+        cli::{DEFAULT_GOSSIP_LARGE_CONTENT_THRESHOLD, DEFAULT_GOSSIP_MAX_PEERS},
         distance::Metric,
         enr::Enr,
         portal::MAX_CONTENT_KEYS_PER_OFFER,
         portal_wire::{OfferTrace, PopulatedOffer, PopulatedOfferWithResult, Request, Response},
     },
     utils::bytes::{hex_encode, hex_encode_compact},
-    OverlayContentKey, RawContentValue,
+    OverlayContentKey, RawContentKey, RawContentValue,
 };
 use futures::channel::oneshot;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use tokio::sync::mpsc;
-use tracing::{debug, error, trace, warn};
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore};
+use tracing::{debug, error, trace, warn, Instrument, Span};
+use trin_metrics::{labels::GossipOutcomeLabel, overlay::OverlayMetricsReporter};
 
 use crate::{
     overlay::{
         command::OverlayCommand,
         request::{OverlayRequest, RequestDirection},
     },
-    types::kbucket::SharedKBucketsTable,
+    types::{
+        kbucket::{InterestedNodes, SharedKBucketsTable},
+        node::Node,
+    },
     utp_controller::UtpController,
 };
 
+/// Configures which of the peers that are interested in gossiped content it's offered to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GossipConfig {
+    /// The max number of peers that each content item is offered to.
+    pub max_peers: usize,
+    /// Whether the peers whose radius, as of their last pong, covers the content are selected
+    /// before the peers whose radius is unknown.
+    pub prefer_radius_coverage: bool,
+    /// Whether half of the peers are selected randomly from the interested peers that are farther
+    /// from the content, rather than only selecting the closest peers.
+    pub include_far_peers: bool,
+    /// The size, in bytes, from which content is offered to its recipients one at a time, on its
+    /// own, instead of being offered to all of them at once, batched with other content.
+    pub large_content_threshold: usize,
+}
+
+impl Default for GossipConfig {
+    fn default() -> Self {
+        Self {
+            max_peers: DEFAULT_GOSSIP_MAX_PEERS,
+            prefer_radius_coverage: false,
+            include_far_peers: true,
+            large_content_threshold: DEFAULT_GOSSIP_LARGE_CONTENT_THRESHOLD,
+        }
+    }
+}
+
+/// Pauses and resumes the outbound gossip of a subnetwork. It's shared by the overlay protocol and
+/// its service, so that the gossip of offered content, of content found by lookups and of the
+/// content rebroadcast to new peers pauses at once. Incoming offers aren't affected.
+#[derive(Debug)]
+pub struct GossipSwitch {
+    enabled: AtomicBool,
+    /// The number of content items that weren't gossiped since gossip was paused.
+    suppressed: AtomicU64,
+}
+
+impl Default for GossipSwitch {
+    fn default() -> Self {
+        Self {
+            enabled: AtomicBool::new(true),
+            suppressed: AtomicU64::new(0),
+        }
+    }
+}
+
+impl GossipSwitch {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Pauses or resumes gossip. When paused gossip is resumed, returns the number of content
+    /// items that weren't gossiped while it was paused.
+    pub fn set_enabled(&self, enabled: bool) -> Option<u64> {
+        let was_enabled = self.enabled.swap(enabled, Ordering::Relaxed);
+        (enabled && !was_enabled).then(|| self.suppressed.swap(0, Ordering::Relaxed))
+    }
+
+    /// Returns whether the content items can be gossiped, and counts them as suppressed if gossip
+    /// is paused.
+    pub fn allow(&self, content_items: usize) -> bool {
+        let enabled = self.is_enabled();
+        if !enabled {
+            self.suppressed
+                .fetch_add(content_items as u64, Ordering::Relaxed);
+        }
+        enabled
+    }
+}
+
+/// The max number of large content items that are offered in the background at once. The large
+/// content above the limit isn't gossiped, so that the memory held by the pending offers is
+/// bounded.
+pub const MAX_LARGE_CONTENT_GOSSIP: usize = 16;
+
+/// Offers large gossiped content to its recipients in the background, with at most
+/// [MAX_LARGE_CONTENT_GOSSIP] content items in progress at once, and reports the outcome for each
+/// recipient. It's shared by the overlay protocol and its service.
+#[derive(Clone)]
+pub struct LargeContentGossip {
+    semaphore: Arc<Semaphore>,
+    metrics: OverlayMetricsReporter,
+}
+
+impl LargeContentGossip {
+    pub fn new(metrics: OverlayMetricsReporter) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(MAX_LARGE_CONTENT_GOSSIP)),
+            metrics,
+        }
+    }
+}
+
 /// Datatype to store the result of a gossip request.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Default)]
 pub struct GossipResult {
@@ -34,16 +138,108 @@ pub struct GossipResult {
     pub accepted: Vec<Enr>,
     /// List of all ENRs to whom the content was successfully transferred
     pub transferred: Vec<Enr>,
+    /// List of all ENRs that responded to the offer without accepting the content
+    #[serde(default)]
+    pub declined: Vec<Enr>,
+    /// List of all ENRs that were offered the content, but didn't respond to the offer
+    #[serde(default)]
+    pub failed: Vec<Enr>,
+    /// The number of connected peers that weren't offered the content, because their radius
+    /// excludes it
+    #[serde(default)]
+    pub skipped: usize,
+    /// The number of peers that were selected to be offered the content, at most the max number
+    /// configured for the subnetwork
+    #[serde(default)]
+    pub fanout: usize,
+}
+
+/// The number of peers that gossiped content was offered to, and that were skipped.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct GossipCount {
+    /// The number of peers that an offer of content, batched with other content, was sent to
+    pub offered: usize,
+    /// The number of peers that large content is queued to be offered to, one at a time, in the
+    /// background. Their outcomes are reported once each offer completes.
+    pub queued: usize,
+    /// The number of large content items that weren't gossiped, because
+    /// [MAX_LARGE_CONTENT_GOSSIP] items were already being offered
+    pub dropped: usize,
+    /// The number of times that a connected peer wasn't offered a content item, because its
+    /// radius, as of its last pong, excludes it. Peers whose radius is unknown are offered the
+    /// content.
+    pub skipped: usize,
+}
+
+/// The outcome of offering content to a single peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OfferOutcome {
+    /// The offer couldn't be sent to the overlay service
+    NotSent,
+    /// The peer didn't respond to the offer
+    Failed,
+    /// The peer didn't accept the content
+    Declined,
+    /// The peer accepted the content, but it wasn't transferred
+    Accepted,
+    /// The peer accepted the content, and it was transferred
+    Transferred,
+}
+
+/// Offers the content to the peer on its own, and waits for the peer to accept it and for the
+/// content to be transferred.
+async fn offer_and_transfer<TContentKey: OverlayContentKey>(
+    content_item: (RawContentKey, RawContentValue),
+    enr: Enr,
+    command_tx: &mpsc::UnboundedSender<OverlayCommand<TContentKey>>,
+    permit: Option<OwnedSemaphorePermit>,
+) -> OfferOutcome {
+    let (result_tx, mut result_rx) = mpsc::unbounded_channel();
+    let offer_request = Request::PopulatedOfferWithResult(PopulatedOfferWithResult {
+        content_item,
+        result_tx,
+    });
+
+    let (tx, rx) = oneshot::channel();
+    let overlay_request = OverlayRequest::new(
+        offer_request,
+        RequestDirection::Outgoing { destination: enr },
+        Some(tx),
+        None,
+        permit,
+    );
+    if let Err(err) = command_tx.send(OverlayCommand::Request(overlay_request)) {
+        error!(error = %err, "Error sending OFFER message to service");
+        return OfferOutcome::NotSent;
+    }
+    match rx.await {
+        Ok(Ok(Response::Accept(accept))) if !accept.content_keys.is_zero() => {}
+        Ok(Ok(_)) => return OfferOutcome::Declined,
+        Ok(Err(_)) | Err(_) => return OfferOutcome::Failed,
+    }
+    match result_rx.recv().await {
+        Some(OfferTrace::Success(_)) => OfferOutcome::Transferred,
+        _ => OfferOutcome::Accepted,
+    }
 }
 
 /// Propagate gossip in a way that can be used across threads, without &self.
 /// Doesn't trace gossip results
-pub fn propagate_gossip_cross_thread<TContentKey: OverlayContentKey, TMetric: Metric>(
+///
+/// Content that is at least as large as the configured threshold is offered to its recipients one
+/// at a time in a separate task, so that only one transfer of it is in progress at a time. The
+/// outcome for each recipient is reported to the metrics. See [LargeContentGossip].
+pub fn propagate_gossip_cross_thread<
+    TContentKey: 'static + OverlayContentKey + Send + Sync,
+    TMetric: Metric,
+>(
     content: Vec<(TContentKey, RawContentValue)>,
     kbuckets: &SharedKBucketsTable,
     command_tx: mpsc::UnboundedSender<OverlayCommand<TContentKey>>,
     utp_controller: Option<Arc<UtpController>>,
-) -> usize {
+    gossip_config: &GossipConfig,
+    large_content_gossip: &LargeContentGossip,
+) -> GossipCount {
     // Precalculate content ids
     let content = content
         .into_iter()
@@ -59,16 +255,22 @@ pub fn propagate_gossip_cross_thread<TContentKey: OverlayContentKey, TMetric: Me
     );
 
     // Map from content_ids to interested ENRs
-    let mut content_id_to_interested_enrs = kbuckets.batch_interested_enrs::<TMetric>(&content_ids);
+    let mut content_id_to_interested_nodes =
+        kbuckets.batch_interested_nodes::<TMetric>(&content_ids);
 
     // Map from ENRs to content they will gossip
     let mut enrs_and_content: HashMap<Enr, Vec<&(TContentKey, RawContentValue)>> = HashMap::new();
+    // Large content, with the ENRs that it's offered to one at a time
+    let mut large_content = vec![];
+    let mut skipped = 0;
     for (content_id, content_key_value) in &content {
-        let interested_enrs = content_id_to_interested_enrs.remove(content_id).unwrap_or_else(|| {
-            error!("interested_enrs should contain all content ids, even if there are no interested ENRs");
-            vec![]
+        let interested_nodes = content_id_to_interested_nodes.remove(content_id).unwrap_or_else(|| {
+            error!("interested_nodes should contain all content ids, even if there are no interested nodes");
+            InterestedNodes::default()
         });
-        if interested_enrs.is_empty() {
+        skipped += interested_nodes.excluded;
+        let interested_nodes = interested_nodes.nodes;
+        if interested_nodes.is_empty() {
             debug!(
                 content.id = %hex_encode(content_id),
                 "No peers eligible for neighborhood gossip"
@@ -77,7 +279,13 @@ pub fn propagate_gossip_cross_thread<TContentKey: OverlayContentKey, TMetric: Me
         };
 
         // Select gossip recipients
-        for enr in select_gossip_recipients::<TMetric>(content_id, interested_enrs) {
+        let recipients =
+            select_gossip_recipients::<TMetric>(content_id, interested_nodes, gossip_config);
+        if content_key_value.1.len() >= gossip_config.large_content_threshold {
+            large_content.push((content_key_value, recipients));
+            continue;
+        }
+        for enr in recipients {
             enrs_and_content
                 .entry(enr)
                 .or_default()
@@ -85,9 +293,40 @@ pub fn propagate_gossip_cross_thread<TContentKey: OverlayContentKey, TMetric: Me
         }
     }
 
-    let num_propagated_peers = enrs_and_content.len();
+    let mut queued_peers = HashSet::new();
+    let mut num_dropped = 0;
+    for ((content_key, content_value), recipients) in large_content {
+        let Ok(permit) = Arc::clone(&large_content_gossip.semaphore).try_acquire_owned() else {
+            debug!(
+                content.key = %content_key,
+                content.len = content_value.len(),
+                "Too much large content is being gossiped, dropping it",
+            );
+            num_dropped += 1;
+            continue;
+        };
+        queued_peers.extend(recipients.iter().map(Enr::node_id));
+        tokio::spawn(
+            offer_one_at_a_time(
+                (content_key.to_bytes(), content_value.clone()),
+                recipients,
+                command_tx.clone(),
+                utp_controller.clone(),
+                large_content_gossip.metrics.clone(),
+                permit,
+            )
+            .instrument(Span::current()),
+        );
+    }
+    if num_dropped > 0 {
+        large_content_gossip
+            .metrics
+            .report_gossip_outcome(GossipOutcomeLabel::Dropped, num_dropped as u64);
+    }
+    let num_queued_peers = queued_peers.len();
 
     // Create and send OFFER overlay request to the interested nodes
+    let mut num_propagated_peers = 0;
     for (enr, mut interested_content) in enrs_and_content {
         let permit = match utp_controller {
             Some(ref utp_controller) => match utp_controller.get_outbound_semaphore() {
@@ -132,12 +371,83 @@ pub fn propagate_gossip_cross_thread<TContentKey: OverlayContentKey, TMetric: Me
             permit,
         );
 
-        if let Err(err) = command_tx.send(OverlayCommand::Request(overlay_request)) {
-            error!(error = %err, "Error sending OFFER message to service")
+        match command_tx.send(OverlayCommand::Request(overlay_request)) {
+            Ok(()) => num_propagated_peers += 1,
+            Err(err) => error!(error = %err, "Error sending OFFER message to service"),
         }
     }
+    debug!(
+        peers = num_propagated_peers,
+        queued = num_queued_peers,
+        dropped = num_dropped,
+        skipped,
+        "Propagated content, skipped offering it to peers whose radius excludes it",
+    );
+
+    GossipCount {
+        offered: num_propagated_peers,
+        queued: num_queued_peers,
+        dropped: num_dropped,
+        skipped,
+    }
+}
 
-    num_propagated_peers
+/// Offers the large content to the recipients one at a time, waiting for each transfer to finish
+/// before offering it to the next recipient. The permit of the [LargeContentGossip] is held until
+/// the content was offered to all of them.
+///
+/// The transfers and the offers that peers didn't respond to are reported to the metrics, as the
+/// overlay service reports the offers that were declined or accepted.
+async fn offer_one_at_a_time<TContentKey: 'static + OverlayContentKey + Send + Sync>(
+    content_item: (RawContentKey, RawContentValue),
+    recipients: Vec<Enr>,
+    command_tx: mpsc::UnboundedSender<OverlayCommand<TContentKey>>,
+    utp_controller: Option<Arc<UtpController>>,
+    metrics: OverlayMetricsReporter,
+    _permit: OwnedSemaphorePermit,
+) {
+    let content_key = hex_encode_compact(&content_item.0);
+    let (mut transferred, mut declined, mut failed) = (0, 0, 0);
+    for enr in recipients {
+        let permit = match utp_controller {
+            Some(ref utp_controller) => match utp_controller.get_outbound_semaphore() {
+                Some(permit) => Some(permit),
+                None => {
+                    trace!("Permit for gossip not acquired! Skipping gossiping to enr: {enr}");
+                    continue;
+                }
+            },
+            None => None,
+        };
+        let peer = enr.node_id();
+        let outcome = offer_and_transfer(content_item.clone(), enr, &command_tx, permit).await;
+        debug!(
+            content.key = %content_key,
+            content.len = content_item.1.len(),
+            %peer,
+            ?outcome,
+            "Offered large gossiped content",
+        );
+        match outcome {
+            OfferOutcome::Transferred => {
+                transferred += 1;
+                metrics.report_gossip_outcome(GossipOutcomeLabel::Transferred, 1);
+            }
+            OfferOutcome::Declined => declined += 1,
+            OfferOutcome::NotSent | OfferOutcome::Failed => {
+                failed += 1;
+                metrics.report_gossip_outcome(GossipOutcomeLabel::Failed, 1);
+            }
+            OfferOutcome::Accepted => {}
+        }
+    }
+    debug!(
+        content.key = %content_key,
+        transferred,
+        declined,
+        failed,
+        "Finished gossiping large content"
+    );
 }
 
 /// Propagate gossip in a way that can be used across threads, without &self.
@@ -152,93 +462,111 @@ pub async fn trace_propagate_gossip_cross_thread<
     data: RawContentValue,
     kbuckets: &SharedKBucketsTable,
     command_tx: mpsc::UnboundedSender<OverlayCommand<TContentKey>>,
+    gossip_config: &GossipConfig,
 ) -> GossipResult {
     let mut gossip_result = GossipResult::default();
 
     let content_id = content_key.content_id();
 
-    let interested_enrs = kbuckets.interested_enrs::<TMetric>(&content_id);
-    if interested_enrs.is_empty() {
+    let interested_nodes = kbuckets.interested_nodes::<TMetric>(&content_id);
+    gossip_result.skipped = interested_nodes.excluded;
+    let interested_nodes = interested_nodes.nodes;
+    if interested_nodes.is_empty() {
         debug!(content.id = %hex_encode(content_id), "No peers eligible for trace gossip");
         return gossip_result;
     };
 
     // Select ENRs to gossip to, create and send OFFER overlay request to the interested nodes
-    for enr in select_gossip_recipients::<TMetric>(&content_id, interested_enrs) {
-        let (result_tx, mut result_rx) = tokio::sync::mpsc::unbounded_channel();
-        let offer_request = Request::PopulatedOfferWithResult(PopulatedOfferWithResult {
-            content_item: (content_key.clone().to_bytes(), data.clone()),
-            result_tx,
-        });
-
-        let (tx, rx) = oneshot::channel();
-        let responder = Some(tx);
-        let overlay_request = OverlayRequest::new(
-            offer_request,
-            RequestDirection::Outgoing {
-                destination: enr.clone(),
-            },
-            responder,
-            None,
-            None,
-        );
-        if let Err(err) = command_tx.send(OverlayCommand::Request(overlay_request)) {
-            error!(error = %err, "Error sending OFFER message to service");
+    let content_item = (content_key.to_bytes(), data);
+    let recipients =
+        select_gossip_recipients::<TMetric>(&content_id, interested_nodes, gossip_config);
+    gossip_result.fanout = recipients.len();
+    for enr in recipients {
+        let outcome =
+            offer_and_transfer(content_item.clone(), enr.clone(), &command_tx, None).await;
+        // update gossip result with the outcome of the offer to the peer
+        if outcome == OfferOutcome::NotSent {
             continue;
         }
-        // update gossip result with peer marked as being offered the content
         gossip_result.offered.push(enr.clone());
-        match rx.await {
-            Ok(res) => {
-                if let Ok(Response::Accept(accept)) = res {
-                    if !accept.content_keys.is_zero() {
-                        // update gossip result with peer marked as accepting the content
-                        gossip_result.accepted.push(enr.clone());
-                    }
-                } else {
-                    // continue to next peer if no content was accepted
-                    continue;
-                }
+        match outcome {
+            OfferOutcome::Failed => gossip_result.failed.push(enr),
+            OfferOutcome::Declined => gossip_result.declined.push(enr),
+            OfferOutcome::Accepted => gossip_result.accepted.push(enr),
+            OfferOutcome::Transferred => {
+                gossip_result.accepted.push(enr.clone());
+                gossip_result.transferred.push(enr);
             }
-            // continue to next peer if err while waiting for response
-            Err(_) => continue,
-        }
-        if let Some(OfferTrace::Success(_)) = result_rx.recv().await {
-            // update gossip result with peer marked as successfully transferring the content
-            gossip_result.transferred.push(enr);
+            OfferOutcome::NotSent => {}
         }
     }
     gossip_result
 }
 
-const NUM_CLOSEST_NODES: usize = 4;
-const NUM_FARTHER_NODES: usize = 4;
+/// Selects gossip recipients from the interested nodes, according to the config.
+///
+/// If the radius coverage is preferred, the recipients are selected from the nodes whose radius
+/// is known to cover the content first, and only the remaining recipients are selected from the
+/// other nodes.
+fn select_gossip_recipients<TMetric: Metric>(
+    content_id: &[u8; 32],
+    nodes: Vec<Node>,
+    config: &GossipConfig,
+) -> Vec<Enr> {
+    if !config.prefer_radius_coverage {
+        let enrs = nodes.into_iter().map(|node| node.enr).collect();
+        return select_closest_and_far::<TMetric>(content_id, enrs, config);
+    }
+
+    let (covering_nodes, other_nodes): (Vec<_>, Vec<_>) =
+        nodes.into_iter().partition(Node::is_radius_known);
+    let covering_enrs = covering_nodes.into_iter().map(|node| node.enr).collect();
+    let mut enrs = select_closest_and_far::<TMetric>(content_id, covering_enrs, config);
+    let remaining_config = GossipConfig {
+        max_peers: config.max_peers - enrs.len(),
+        ..*config
+    };
+    let other_enrs = other_nodes.into_iter().map(|node| node.enr).collect();
+    enrs.extend(select_closest_and_far::<TMetric>(
+        content_id,
+        other_enrs,
+        &remaining_config,
+    ));
+    enrs
+}
 
-/// Selects gossip recipients from a vec of interested ENRs.
+/// Selects at most `config.max_peers` ENRs.
 ///
-/// If number of ENRs is at most `NUM_CLOSEST_NODES + NUM_FARTHER_NODES`, then all are returned.
-/// Otherwise, ENRs are sorted by distance from `content_id` and then:
+/// If there are more ENRs, then they are sorted by distance from `content_id`, and:
 ///
-/// 1. Closest `NUM_CLOSEST_NODES` ENRs are selected
-/// 2. Random `NUM_FARTHER_NODES` ENRs are selected from the rest
-fn select_gossip_recipients<TMetric: Metric>(
+/// 1. If far peers are included, the closest half of `max_peers` ENRs are selected (rounded up),
+///    and the rest is selected randomly from the farther ENRs
+/// 2. Otherwise, the closest `max_peers` ENRs are selected
+fn select_closest_and_far<TMetric: Metric>(
     content_id: &[u8; 32],
     mut enrs: Vec<Enr>,
+    config: &GossipConfig,
 ) -> Vec<Enr> {
     // Check if we need to do any selection
-    if enrs.len() <= NUM_CLOSEST_NODES + NUM_FARTHER_NODES {
+    if enrs.len() <= config.max_peers {
         return enrs;
     }
 
     // Sort enrs by distance
     enrs.sort_by_cached_key(|enr| TMetric::distance(content_id, &enr.node_id().raw()));
 
-    // Split of at NUM_CLOSEST_NODES
-    let mut farther_enrs = enrs.split_off(NUM_CLOSEST_NODES);
+    if !config.include_far_peers {
+        enrs.truncate(config.max_peers);
+        return enrs;
+    }
+
+    // Split of at the number of closest nodes
+    let num_farther_nodes = config.max_peers / 2;
+    let mut farther_enrs = enrs.split_off(config.max_peers - num_farther_nodes);
 
-    // Select random NUM_FARTHER_NODES
+    // Select random farther nodes
     let mut rng = rand::thread_rng();
-    for _ in 0..NUM_FARTHER_NODES {
+    for _ in 0..num_farther_nodes {
         let enr = farther_enrs.swap_remove(rng.gen_range(0..farther_enrs.len()));
         enrs.push(enr);
     }
@@ -249,25 +577,410 @@ fn select_gossip_recipients<TMetric: Metric>(
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
-    use ethportal_api::types::{distance::XorMetric, enr::generate_random_remote_enr};
+    use std::time::Duration;
+
+    use discv5::kbucket::{ConnectionDirection, ConnectionState, KBucketsTable, NodeStatus};
+    use ethportal_api::{
+        types::{
+            distance::{Distance, XorMetric},
+            enr::generate_random_remote_enr,
+            portal_wire::Accept,
+        },
+        IdentityContentKey,
+    };
     use rand::random;
     use rstest::rstest;
+    use ssz_types::BitList;
+    use trin_metrics::portalnet::PORTALNET_METRICS;
 
     use super::*;
 
+    const NUM_CLOSEST_NODES: usize = DEFAULT_GOSSIP_MAX_PEERS - DEFAULT_GOSSIP_MAX_PEERS / 2;
+
+    /// Returns a routing table with the nodes, all of them connected.
+    fn routing_table(nodes: &[Node]) -> SharedKBucketsTable {
+        let (_, local_enr) = generate_random_remote_enr();
+        let kbuckets = SharedKBucketsTable::new(KBucketsTable::new(
+            local_enr.node_id().into(),
+            Duration::from_secs(60),
+            /* max_incoming_per_bucket= */ 16,
+            /* table_filter= */ None,
+            /* bucket_filter= */ None,
+        ));
+        let status = NodeStatus {
+            state: ConnectionState::Connected,
+            direction: ConnectionDirection::Outgoing,
+        };
+        for node in nodes {
+            let _ = kbuckets.insert_or_update(node.clone(), status);
+        }
+        kbuckets
+    }
+
+    fn large_content_gossip() -> LargeContentGossip {
+        LargeContentGossip::new(OverlayMetricsReporter {
+            overlay_metrics: PORTALNET_METRICS.overlay(),
+            protocol: "test".to_string(),
+        })
+    }
+
+    /// Returns the ENRs of the nodes, sorted by distance from the content id.
+    fn closest_enrs(content_id: &[u8; 32], nodes: &[Node]) -> Vec<Enr> {
+        let mut enrs = nodes.iter().map(Node::enr).collect::<Vec<_>>();
+        enrs.sort_by_cached_key(|enr| XorMetric::distance(content_id, &enr.node_id().raw()));
+        enrs
+    }
+
     #[allow(clippy::zero_repeat_side_effects)]
     #[rstest]
-    #[case(vec![generate_random_remote_enr().1; 0], 0)]
-    #[case(vec![generate_random_remote_enr().1; NUM_CLOSEST_NODES - 1], NUM_CLOSEST_NODES - 1)]
-    #[case(vec![generate_random_remote_enr().1; NUM_CLOSEST_NODES], NUM_CLOSEST_NODES)]
-    #[case(vec![generate_random_remote_enr().1; NUM_CLOSEST_NODES + 1], NUM_CLOSEST_NODES + 1)]
-    #[case(vec![generate_random_remote_enr().1; NUM_CLOSEST_NODES + NUM_FARTHER_NODES], NUM_CLOSEST_NODES + NUM_FARTHER_NODES)]
-    #[case(vec![generate_random_remote_enr().1; 256], NUM_CLOSEST_NODES + NUM_FARTHER_NODES)]
+    #[case(0, 0)]
+    #[case(NUM_CLOSEST_NODES - 1, NUM_CLOSEST_NODES - 1)]
+    #[case(NUM_CLOSEST_NODES, NUM_CLOSEST_NODES)]
+    #[case(NUM_CLOSEST_NODES + 1, NUM_CLOSEST_NODES + 1)]
+    #[case(DEFAULT_GOSSIP_MAX_PEERS, DEFAULT_GOSSIP_MAX_PEERS)]
+    #[case(256, DEFAULT_GOSSIP_MAX_PEERS)]
     fn test_select_gossip_recipients_no_panic(
-        #[case] all_nodes: Vec<Enr>,
+        #[case] node_count: usize,
         #[case] expected_size: usize,
     ) {
-        let gossip_recipients = select_gossip_recipients::<XorMetric>(&random(), all_nodes);
+        let all_nodes = vec![Node::new(generate_random_remote_enr().1, Distance::MAX); node_count];
+        let gossip_recipients =
+            select_gossip_recipients::<XorMetric>(&random(), all_nodes, &GossipConfig::default());
         assert_eq!(gossip_recipients.len(), expected_size);
     }
+
+    #[test]
+    fn gossip_switch_counts_suppressed_content_while_paused() {
+        let switch = GossipSwitch::default();
+        assert!(switch.is_enabled());
+        assert!(switch.allow(3));
+        // Enabling gossip that isn't paused doesn't report anything.
+        assert_eq!(switch.set_enabled(true), None);
+
+        assert_eq!(switch.set_enabled(false), None);
+        assert!(!switch.is_enabled());
+        assert!(!switch.allow(3));
+        assert!(!switch.allow(1));
+        assert_eq!(switch.set_enabled(false), None);
+        assert_eq!(switch.set_enabled(true), Some(4));
+
+        // The count starts over once gossip is paused again.
+        assert!(switch.allow(2));
+        switch.set_enabled(false);
+        assert!(!switch.allow(1));
+        assert_eq!(switch.set_enabled(true), Some(1));
+    }
+
+    #[test]
+    fn select_gossip_recipients_honors_cap() {
+        let content_id = random();
+        let nodes = (0..12)
+            .map(|_| Node::new(generate_random_remote_enr().1, Distance::MAX))
+            .collect::<Vec<_>>();
+        let kbuckets = routing_table(&nodes);
+        let interested_nodes = kbuckets.interested_nodes::<XorMetric>(&content_id).nodes;
+        let closest_enrs = closest_enrs(&content_id, &interested_nodes);
+
+        // Only the closest nodes are selected, if far peers aren't included.
+        let config = GossipConfig {
+            max_peers: 5,
+            include_far_peers: false,
+            ..Default::default()
+        };
+        let recipients =
+            select_gossip_recipients::<XorMetric>(&content_id, interested_nodes.clone(), &config);
+        assert_eq!(recipients, closest_enrs[..5]);
+
+        // Otherwise, the closest half of the nodes (rounded up) are selected, and the rest from
+        // the farther nodes.
+        let config = GossipConfig {
+            include_far_peers: true,
+            ..config
+        };
+        let recipients =
+            select_gossip_recipients::<XorMetric>(&content_id, interested_nodes, &config);
+        assert_eq!(recipients.len(), 5);
+        assert_eq!(recipients[..3], closest_enrs[..3]);
+        assert!(recipients[3..]
+            .iter()
+            .all(|enr| closest_enrs[3..].contains(enr)));
+    }
+
+    #[test]
+    fn select_gossip_recipients_prefers_radius_coverage() {
+        let content_id = random();
+        // The nodes whose radius is unknown are interested in all content.
+        let mut nodes = (0..8)
+            .map(|_| Node::with_unknown_radius(generate_random_remote_enr().1))
+            .collect::<Vec<_>>();
+        let unknown_radius_enrs = closest_enrs(&content_id, &nodes);
+        // The nodes whose radius exactly covers the content, and a node whose radius is the max
+        // radius, which covers all content.
+        let mut covering_nodes = (0..2)
+            .map(|_| {
+                let enr = generate_random_remote_enr().1;
+                let radius = XorMetric::distance(&content_id, &enr.node_id().raw());
+                Node::new(enr, radius)
+            })
+            .collect::<Vec<_>>();
+        covering_nodes.push(Node::new(generate_random_remote_enr().1, Distance::MAX));
+        nodes.extend(covering_nodes.clone());
+        // A node whose radius excludes the content, which is never selected.
+        nodes.push(Node::new(generate_random_remote_enr().1, Distance::ZERO));
+        let kbuckets = routing_table(&nodes);
+        let interested_nodes = kbuckets.interested_nodes::<XorMetric>(&content_id).nodes;
+        assert_eq!(interested_nodes.len(), 11);
+
+        let config = GossipConfig {
+            max_peers: 4,
+            prefer_radius_coverage: true,
+            include_far_peers: false,
+            ..Default::default()
+        };
+        let recipients =
+            select_gossip_recipients::<XorMetric>(&content_id, interested_nodes.clone(), &config);
+        // All the covering nodes are selected, and the closest of the other nodes.
+        assert_eq!(recipients.len(), 4);
+        assert!(covering_nodes
+            .iter()
+            .all(|node| recipients[..3].contains(&node.enr)));
+        assert_eq!(recipients[3], unknown_radius_enrs[0]);
+
+        // The cap also applies to the covering nodes.
+        let config = GossipConfig {
+            max_peers: 2,
+            ..config
+        };
+        let recipients =
+            select_gossip_recipients::<XorMetric>(&content_id, interested_nodes.clone(), &config);
+        assert_eq!(recipients, closest_enrs(&content_id, &covering_nodes)[..2]);
+
+        // Without the preference, the closest nodes are selected, whatever their radius.
+        let config = GossipConfig {
+            max_peers: 4,
+            prefer_radius_coverage: false,
+            include_far_peers: false,
+            ..Default::default()
+        };
+        let recipients =
+            select_gossip_recipients::<XorMetric>(&content_id, interested_nodes.clone(), &config);
+        assert_eq!(
+            recipients,
+            closest_enrs(&content_id, &interested_nodes)[..4]
+        );
+    }
+
+    #[tokio::test]
+    async fn large_content_is_offered_one_peer_at_a_time() {
+        let nodes = (0..2)
+            .map(|_| Node::new(generate_random_remote_enr().1, Distance::MAX))
+            .collect::<Vec<_>>();
+        let kbuckets = routing_table(&nodes);
+        let (command_tx, mut command_rx) = mpsc::unbounded_channel();
+        let config = GossipConfig {
+            large_content_threshold: 2,
+            ..Default::default()
+        };
+        let small_content = (IdentityContentKey::random(), RawContentValue::from([0]));
+        let large_content = (IdentityContentKey::random(), RawContentValue::from([1, 2]));
+
+        let count = propagate_gossip_cross_thread::<_, XorMetric>(
+            vec![small_content.clone(), large_content.clone()],
+            &kbuckets,
+            command_tx,
+            None,
+            &config,
+            &large_content_gossip(),
+        );
+        assert_eq!(count.offered, 2);
+        assert_eq!(count.queued, 2);
+
+        // The small content is offered to both peers at once.
+        for _ in 0..2 {
+            match command_rx.try_recv() {
+                Ok(OverlayCommand::Request(OverlayRequest {
+                    request: Request::PopulatedOffer(offer),
+                    ..
+                })) => assert_eq!(
+                    offer.content_items,
+                    vec![(small_content.0.to_bytes(), small_content.1.clone())]
+                ),
+                _ => panic!("Unexpected overlay command"),
+            }
+        }
+
+        // The large content is only offered to the next peer once the previous one responded.
+        let mut destinations = vec![];
+        for _ in 0..2 {
+            let Some(OverlayCommand::Request(OverlayRequest {
+                request: Request::PopulatedOfferWithResult(offer),
+                direction: RequestDirection::Outgoing { destination },
+                responder: Some(responder),
+                ..
+            })) = command_rx.recv().await
+            else {
+                panic!("Unexpected overlay command");
+            };
+            assert_eq!(
+                offer.content_item,
+                (large_content.0.to_bytes(), large_content.1.clone())
+            );
+            destinations.push(destination);
+            tokio::task::yield_now().await;
+            assert!(command_rx.try_recv().is_err());
+
+            let _ = responder.send(Ok(Response::Accept(Accept {
+                connection_id: 0,
+                content_keys: BitList::with_capacity(1).unwrap(),
+            })));
+        }
+        assert!(command_rx.recv().await.is_none());
+        destinations.sort_by_key(Enr::node_id);
+        let mut enrs = nodes.iter().map(Node::enr).collect::<Vec<_>>();
+        enrs.sort_by_key(Enr::node_id);
+        assert_eq!(destinations, enrs);
+    }
+
+    #[tokio::test]
+    async fn large_content_above_the_limit_is_dropped() {
+        let nodes = vec![Node::new(generate_random_remote_enr().1, Distance::MAX)];
+        let kbuckets = routing_table(&nodes);
+        let (command_tx, mut command_rx) = mpsc::unbounded_channel();
+        let config = GossipConfig {
+            large_content_threshold: 1,
+            ..Default::default()
+        };
+        let large_content_gossip = large_content_gossip();
+        let content = (0..MAX_LARGE_CONTENT_GOSSIP + 2)
+            .map(|_| (IdentityContentKey::random(), RawContentValue::from([0])))
+            .collect();
+
+        let count = propagate_gossip_cross_thread::<_, XorMetric>(
+            content,
+            &kbuckets,
+            command_tx.clone(),
+            None,
+            &config,
+            &large_content_gossip,
+        );
+        assert_eq!(count.queued, 1);
+        assert_eq!(count.dropped, 2);
+
+        // Once the offers of the queued content are answered, new large content is gossiped.
+        for _ in 0..MAX_LARGE_CONTENT_GOSSIP {
+            let Some(OverlayCommand::Request(OverlayRequest {
+                responder: Some(responder),
+                ..
+            })) = command_rx.recv().await
+            else {
+                panic!("Unexpected overlay command");
+            };
+            let _ = responder.send(Ok(Response::Accept(Accept {
+                connection_id: 0,
+                content_keys: BitList::with_capacity(1).unwrap(),
+            })));
+        }
+        while large_content_gossip.semaphore.available_permits() < MAX_LARGE_CONTENT_GOSSIP {
+            tokio::task::yield_now().await;
+        }
+        let count = propagate_gossip_cross_thread::<_, XorMetric>(
+            vec![(IdentityContentKey::random(), RawContentValue::from([0]))],
+            &kbuckets,
+            command_tx,
+            None,
+            &config,
+            &large_content_gossip,
+        );
+        assert_eq!(count.queued, 1);
+        assert_eq!(count.dropped, 0);
+    }
+
+    #[test]
+    fn peers_whose_radius_excludes_content_are_skipped() {
+        let interested_nodes = (0..3)
+            .map(|_| Node::new(generate_random_remote_enr().1, Distance::MAX))
+            .collect::<Vec<_>>();
+        let excluding_nodes = (0..2)
+            .map(|_| Node::new(generate_random_remote_enr().1, Distance::ZERO))
+            .collect::<Vec<_>>();
+        let kbuckets = routing_table(&[interested_nodes, excluding_nodes].concat());
+        let (command_tx, _command_rx) = mpsc::unbounded_channel();
+        let content = (0..2u8)
+            .map(|i| (IdentityContentKey::random(), RawContentValue::from([i])))
+            .collect();
+
+        let count = propagate_gossip_cross_thread::<_, XorMetric>(
+            content,
+            &kbuckets,
+            command_tx,
+            None,
+            &GossipConfig::default(),
+            &large_content_gossip(),
+        );
+        assert_eq!(count.offered, 3);
+        // Each of the excluding peers is skipped for each of the content items.
+        assert_eq!(count.skipped, 4);
+    }
+
+    #[tokio::test]
+    async fn trace_gossip_reports_skipped_and_declined_peers() {
+        let interested_node = Node::new(generate_random_remote_enr().1, Distance::MAX);
+        let excluding_node = Node::new(generate_random_remote_enr().1, Distance::ZERO);
+        let kbuckets = routing_table(&[interested_node.clone(), excluding_node]);
+        let (command_tx, mut command_rx) = mpsc::unbounded_channel();
+
+        let gossip = tokio::spawn(async move {
+            trace_propagate_gossip_cross_thread::<_, XorMetric>(
+                IdentityContentKey::random(),
+                RawContentValue::from([0]),
+                &kbuckets,
+                command_tx,
+                &GossipConfig::default(),
+            )
+            .await
+        });
+        let Some(OverlayCommand::Request(OverlayRequest {
+            direction: RequestDirection::Outgoing { destination },
+            responder: Some(responder),
+            ..
+        })) = command_rx.recv().await
+        else {
+            panic!("Unexpected overlay command");
+        };
+        assert_eq!(destination, interested_node.enr);
+        let _ = responder.send(Ok(Response::Accept(Accept {
+            connection_id: 0,
+            content_keys: BitList::with_capacity(1).unwrap(),
+        })));
+
+        let result = gossip.await.unwrap();
+        assert_eq!(result.skipped, 1);
+        assert_eq!(result.fanout, 1);
+        assert_eq!(result.offered, vec![interested_node.enr.clone()]);
+        assert_eq!(result.declined, vec![interested_node.enr]);
+        assert!(result.accepted.is_empty());
+        assert!(result.failed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn peers_are_only_counted_once_their_offer_is_sent() {
+        let nodes = (0..2)
+            .map(|_| Node::new(generate_random_remote_enr().1, Distance::MAX))
+            .collect::<Vec<_>>();
+        let kbuckets = routing_table(&nodes);
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        drop(command_rx);
+        let content = (IdentityContentKey::random(), RawContentValue::from([0]));
+
+        let count = propagate_gossip_cross_thread::<_, XorMetric>(
+            vec![content],
+            &kbuckets,
+            command_tx,
+            None,
+            &GossipConfig::default(),
+            &large_content_gossip(),
+        );
+        assert_eq!(count.offered, 0);
+        assert_eq!(count.queued, 0);
+    }
 }
@@ -2,14 +2,20 @@
 #![warn(clippy::uninlined_format_args)]
 
 pub mod accept_queue;
+pub mod accept_snapshot;
 pub mod config;
 pub mod constants;
 pub mod discovery;
 pub mod events;
 pub mod find;
 pub mod gossip;
+pub mod liveness;
 pub mod overlay;
+pub mod poke;
+pub mod recent_offerers;
 pub mod socket;
 pub mod types;
 pub mod utils;
 pub mod utp_controller;
+pub mod utp_throttle;
+pub mod wire_recorder;
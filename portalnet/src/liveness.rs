@@ -0,0 +1,331 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use discv5::enr::NodeId;
+use rand::Rng;
+
+/// The interval between the liveness checks of each peer, if not configured.
+pub const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The max number of liveness pings waiting for a response at the same time.
+pub const MAX_CONCURRENT_PINGS: usize = 16;
+
+/// The number of consecutive failed liveness pings after which a peer is disconnected.
+pub const MAX_FAILED_PINGS: u32 = 3;
+
+/// The max relative deviation of the time between two liveness checks of a peer from the interval.
+const JITTER: f64 = 0.1;
+
+/// The peers that are due for a liveness ping.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DuePings {
+    /// The peers to ping.
+    pub to_ping: Vec<NodeId>,
+    /// The number of peers that weren't pinged, because they were active recently.
+    pub skipped: usize,
+}
+
+/// Schedules the liveness pings of the peers in the routing table.
+///
+/// The pings are spread across the interval, rather than sent in bursts, and peers that responded
+/// to any request or sent us a request within the interval aren't pinged, as that is evidence
+/// enough of their liveness. The number of pings waiting for a response is capped, so peers that
+/// are due while the cap is reached are pinged once pings complete.
+pub struct LivenessTracker {
+    interval: Duration,
+    /// When each peer is next checked for liveness.
+    next_check: HashMap<NodeId, Instant>,
+    /// When each peer was last active.
+    last_activity: HashMap<NodeId, Instant>,
+    /// The peers whose liveness ping is waiting for a response.
+    in_flight: HashSet<NodeId>,
+    /// The number of consecutive failed liveness pings of each peer.
+    failed_pings: HashMap<NodeId, u32>,
+}
+
+impl LivenessTracker {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next_check: HashMap::new(),
+            last_activity: HashMap::new(),
+            in_flight: HashSet::new(),
+            failed_pings: HashMap::new(),
+        }
+    }
+
+    /// Starts checking the liveness of the peer, if it isn't checked already. The first check is
+    /// at a random time within the interval, so that peers added together are pinged apart.
+    pub fn insert(&mut self, node_id: NodeId) {
+        self.insert_at(node_id, Instant::now())
+    }
+
+    fn insert_at(&mut self, node_id: NodeId, now: Instant) {
+        let first_check = self
+            .interval
+            .mul_f64(rand::thread_rng().gen_range(0.0..1.0));
+        self.next_check.entry(node_id).or_insert(now + first_check);
+    }
+
+    /// Stops checking the liveness of the peer.
+    pub fn remove(&mut self, node_id: &NodeId) {
+        self.next_check.remove(node_id);
+        self.last_activity.remove(node_id);
+        self.in_flight.remove(node_id);
+        self.failed_pings.remove(node_id);
+    }
+
+    /// Returns whether the liveness of the peer is checked.
+    pub fn contains(&self, node_id: &NodeId) -> bool {
+        self.next_check.contains_key(node_id)
+    }
+
+    /// Returns whether the liveness of no peer is checked.
+    pub fn is_empty(&self) -> bool {
+        self.next_check.is_empty()
+    }
+
+    /// Returns whether a liveness ping of the peer is waiting for a response.
+    pub fn is_in_flight(&self, node_id: &NodeId) -> bool {
+        self.in_flight.contains(node_id)
+    }
+
+    /// Records that the peer responded to a request or sent us one.
+    pub fn record_activity(&mut self, node_id: NodeId) {
+        self.record_activity_at(node_id, Instant::now())
+    }
+
+    fn record_activity_at(&mut self, node_id: NodeId, now: Instant) {
+        if self.contains(&node_id) {
+            self.last_activity.insert(node_id, now);
+            self.failed_pings.remove(&node_id);
+        }
+    }
+
+    /// Returns the peers to ping now, and marks their pings as in flight.
+    pub fn due_pings(&mut self) -> DuePings {
+        self.due_pings_at(Instant::now())
+    }
+
+    fn due_pings_at(&mut self, now: Instant) -> DuePings {
+        let mut due: Vec<(Instant, NodeId)> = self
+            .next_check
+            .iter()
+            .filter(|(node_id, next_check)| {
+                **next_check <= now && !self.in_flight.contains(node_id)
+            })
+            .map(|(node_id, next_check)| (*next_check, *node_id))
+            .collect();
+        // The peers that are overdue the longest are pinged first.
+        due.sort();
+
+        let mut due_pings = DuePings::default();
+        for (_, node_id) in due {
+            let last_activity = self.last_activity.get(&node_id).copied();
+            if last_activity.is_some_and(|at| now - at < self.interval) {
+                due_pings.skipped += 1;
+                self.next_check
+                    .insert(node_id, now + self.jittered_interval());
+            } else if self.in_flight.len() < MAX_CONCURRENT_PINGS {
+                due_pings.to_ping.push(node_id);
+                self.in_flight.insert(node_id);
+                self.next_check
+                    .insert(node_id, now + self.jittered_interval());
+            }
+        }
+        due_pings
+    }
+
+    /// Records the response to the liveness ping of the peer. Unlike other interactions, it doesn't
+    /// defer the next liveness check.
+    pub fn on_ping_success(&mut self, node_id: NodeId) {
+        self.in_flight.remove(&node_id);
+        self.failed_pings.remove(&node_id);
+    }
+
+    /// Records the failure of the liveness ping of the peer, and returns whether the peer failed
+    /// too many consecutive pings, in which case its liveness is no longer checked.
+    pub fn on_ping_failure(&mut self, node_id: NodeId) -> bool {
+        self.in_flight.remove(&node_id);
+        if !self.contains(&node_id) {
+            return false;
+        }
+        let failed_pings = self.failed_pings.entry(node_id).or_default();
+        *failed_pings += 1;
+        if *failed_pings < MAX_FAILED_PINGS {
+            return false;
+        }
+        self.remove(&node_id);
+        true
+    }
+
+    fn jittered_interval(&self) -> Duration {
+        self.interval
+            .mul_f64(rand::thread_rng().gen_range(1.0 - JITTER..1.0 + JITTER))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_secs(10);
+    const TICK: Duration = Duration::from_millis(100);
+
+    /// Advances a simulated clock tick by tick over the duration, collecting the times at which
+    /// each peer was pinged. Pings are answered immediately, unless the peer is unresponsive.
+    fn run(
+        tracker: &mut LivenessTracker,
+        start: Instant,
+        duration: Duration,
+        unresponsive: &HashSet<NodeId>,
+    ) -> (HashMap<NodeId, Vec<Instant>>, usize) {
+        let mut pings: HashMap<NodeId, Vec<Instant>> = HashMap::new();
+        let mut skipped = 0;
+        let mut now = start;
+        while now < start + duration {
+            let due_pings = tracker.due_pings_at(now);
+            skipped += due_pings.skipped;
+            for node_id in due_pings.to_ping {
+                pings.entry(node_id).or_default().push(now);
+                if unresponsive.contains(&node_id) {
+                    tracker.on_ping_failure(node_id);
+                } else {
+                    tracker.on_ping_success(node_id);
+                }
+            }
+            now += TICK;
+        }
+        (pings, skipped)
+    }
+
+    #[test]
+    fn spreads_pings_across_interval() {
+        let mut tracker = LivenessTracker::new(INTERVAL);
+        let start = Instant::now();
+        let peers: Vec<NodeId> = (0..100).map(|_| NodeId::random()).collect();
+        for node_id in &peers {
+            tracker.insert_at(*node_id, start);
+        }
+
+        let (pings, skipped) = run(&mut tracker, start, 3 * INTERVAL, &HashSet::new());
+
+        assert_eq!(skipped, 0);
+        for node_id in &peers {
+            let pings = &pings[node_id];
+            // The first ping is within the interval, and the following ones are an interval apart,
+            // give or take the jitter.
+            assert!(pings[0] <= start + INTERVAL);
+            for pair in pings.windows(2) {
+                let elapsed = pair[1] - pair[0];
+                assert!(elapsed >= INTERVAL.mul_f64(1.0 - JITTER));
+                assert!(elapsed <= INTERVAL.mul_f64(1.0 + JITTER) + TICK);
+            }
+        }
+        // The first pings don't all happen at once: no second of the interval has more than a
+        // third of them.
+        for second in 0..INTERVAL.as_secs() {
+            let window_start = start + Duration::from_secs(second);
+            let window_end = window_start + Duration::from_secs(1);
+            let count = peers
+                .iter()
+                .filter(|node_id| (window_start..window_end).contains(&pings[node_id][0]))
+                .count();
+            assert!(count <= peers.len() / 3, "{count} pings in second {second}");
+        }
+    }
+
+    #[test]
+    fn skips_recently_active_peers() {
+        let mut tracker = LivenessTracker::new(INTERVAL);
+        let start = Instant::now();
+        let active = NodeId::random();
+        let idle = NodeId::random();
+        tracker.insert_at(active, start);
+        tracker.insert_at(idle, start);
+
+        let mut now = start;
+        let mut active_pings = 0;
+        let mut idle_pings = 0;
+        let mut skipped = 0;
+        while now < start + 5 * INTERVAL {
+            tracker.record_activity_at(active, now);
+            let due_pings = tracker.due_pings_at(now);
+            skipped += due_pings.skipped;
+            for node_id in due_pings.to_ping {
+                if node_id == active {
+                    active_pings += 1;
+                } else {
+                    idle_pings += 1;
+                }
+                tracker.on_ping_success(node_id);
+            }
+            now += TICK;
+        }
+
+        assert_eq!(active_pings, 0);
+        assert!(idle_pings >= 4);
+        assert!(skipped >= 4);
+    }
+
+    #[test]
+    fn caps_concurrent_pings() {
+        let mut tracker = LivenessTracker::new(INTERVAL);
+        let start = Instant::now();
+        for _ in 0..2 * MAX_CONCURRENT_PINGS {
+            tracker.insert_at(NodeId::random(), start);
+        }
+
+        // None of the pings complete, so only the max number of them is sent.
+        let due_pings = tracker.due_pings_at(start + INTERVAL);
+        assert_eq!(due_pings.to_ping.len(), MAX_CONCURRENT_PINGS);
+        assert!(tracker.due_pings_at(start + INTERVAL).to_ping.is_empty());
+
+        // Completing a ping makes room for a peer that is still due.
+        tracker.on_ping_success(due_pings.to_ping[0]);
+        let next_due_pings = tracker.due_pings_at(start + INTERVAL);
+        assert_eq!(next_due_pings.to_ping.len(), 1);
+        assert!(!due_pings.to_ping.contains(&next_due_pings.to_ping[0]));
+    }
+
+    #[test]
+    fn disconnects_after_consecutive_failed_pings() {
+        let mut tracker = LivenessTracker::new(INTERVAL);
+        let start = Instant::now();
+        let unresponsive = NodeId::random();
+        let flaky = NodeId::random();
+        tracker.insert_at(unresponsive, start);
+        tracker.insert_at(flaky, start);
+
+        for _ in 0..MAX_FAILED_PINGS - 1 {
+            assert!(!tracker.on_ping_failure(unresponsive));
+            assert!(!tracker.on_ping_failure(flaky));
+        }
+        // A successful interaction resets the count of failed pings.
+        tracker.record_activity_at(flaky, start);
+
+        assert!(tracker.on_ping_failure(unresponsive));
+        assert!(!tracker.contains(&unresponsive));
+        assert!(!tracker.on_ping_failure(flaky));
+        assert!(tracker.contains(&flaky));
+
+        // The failures of peers that aren't checked anymore aren't counted.
+        assert!(!tracker.on_ping_failure(unresponsive));
+    }
+
+    #[test]
+    fn unresponsive_peer_is_pinged_until_removed() {
+        let mut tracker = LivenessTracker::new(INTERVAL);
+        let start = Instant::now();
+        let node_id = NodeId::random();
+        tracker.insert_at(node_id, start);
+
+        let unresponsive = HashSet::from([node_id]);
+        let (pings, _) = run(&mut tracker, start, 10 * INTERVAL, &unresponsive);
+
+        assert_eq!(pings[&node_id].len(), MAX_FAILED_PINGS as usize);
+        assert!(tracker.is_empty());
+    }
+}
@@ -1,7 +1,8 @@
 use discv5::enr::NodeId;
-use ethportal_api::types::enr::Enr;
+use ethportal_api::types::{enr::Enr, portal::ActiveLookupInfo};
 use futures::channel::oneshot;
 use tokio::sync::broadcast;
+use tracing::Span;
 
 use super::{config::FindContentConfig, request::OverlayRequest};
 use crate::{events::EventEnvelope, find::query_info::RecursiveFindContentResult};
@@ -28,6 +29,8 @@ pub enum OverlayCommand<TContentKey> {
         callback: oneshot::Sender<RecursiveFindContentResult>,
         /// The configuration for the query.
         config: FindContentConfig,
+        /// The span of the issuer, which the log lines of the query are attributed to.
+        span: Span,
     },
     FindNodeQuery {
         /// The query target.
@@ -37,6 +40,8 @@ pub enum OverlayCommand<TContentKey> {
     },
     /// Sets up an event stream where the overlay server will return various events.
     RequestEventStream(oneshot::Sender<broadcast::Receiver<EventEnvelope>>),
+    /// Returns the content lookups that are currently running.
+    ActiveLookups(oneshot::Sender<Vec<ActiveLookupInfo>>),
     /// Handle an event sent from another overlay.
     Event(EventEnvelope),
 }
@@ -5,7 +5,13 @@ use std::time::Duration;
 use discv5::kbucket::{Filter, MAX_NODES_PER_BUCKET};
 use ethportal_api::types::{cli::DEFAULT_UTP_TRANSFER_LIMIT, enr::Enr};
 
-use crate::{constants::DEFAULT_QUERY_TIMEOUT, types::node::Node};
+use crate::{
+    constants::DEFAULT_QUERY_TIMEOUT,
+    find::{failed_lookups::FailedLookupCacheConfig, lookup_results::LookupCacheConfig},
+    gossip::GossipConfig,
+    poke::RebroadcastConfig,
+    types::node::Node,
+};
 
 /// Configuration parameters for the overlay network.
 #[derive(Clone)]
@@ -23,7 +29,15 @@ pub struct OverlayConfig {
     pub findnodes_query_distances_per_peer: usize,
     pub disable_poke: bool,
     pub gossip_dropped: bool,
+    /// Which of the interested peers gossiped content is offered to.
+    pub gossip: GossipConfig,
     pub utp_transfer_limit: usize,
+    /// The cache of recently failed content lookups, disabled if `None`.
+    pub failed_lookup_cache: Option<FailedLookupCacheConfig>,
+    /// The cache of recently found content, disabled if `None`.
+    pub lookup_cache: Option<LookupCacheConfig>,
+    /// The rebroadcast of stored content to new peers, disabled if `None`.
+    pub rebroadcast: Option<RebroadcastConfig>,
 }
 
 impl Default for OverlayConfig {
@@ -42,7 +56,11 @@ impl Default for OverlayConfig {
             findnodes_query_distances_per_peer: 3,
             disable_poke: false,
             gossip_dropped: false,
+            gossip: GossipConfig::default(),
             utp_transfer_limit: DEFAULT_UTP_TRANSFER_LIMIT,
+            failed_lookup_cache: Some(FailedLookupCacheConfig::default()),
+            lookup_cache: None,
+            rebroadcast: None,
         }
     }
 }
@@ -1,4 +1,5 @@
-use ethportal_api::types::query_trace::QueryTrace;
+use ethportal_api::types::{enr::Enr, query_trace::QueryTrace};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// An overlay request error.
@@ -22,6 +23,7 @@ pub enum OverlayRequestError {
     #[error("Response content failed validation: {0}")]
     FailedValidation(String),
 
+    /// The remote responded with an empty response, e.g. because it doesn't support the protocol.
     #[error("The request returned an empty response")]
     EmptyResponse,
 
@@ -56,14 +58,75 @@ pub enum OverlayRequestError {
     #[error("Received invalid remote discv5 packet")]
     InvalidRemoteDiscv5Packet,
 
+    /// The destination ENR doesn't contain an ip address and udp port.
+    #[error("The ENR doesn't have a reachable address")]
+    UnreachableEnr,
+
     #[error("Content wasn't found on the network: {message}")]
     ContentNotFound {
         message: String,
         utp: bool,
         trace: Option<QueryTrace>,
+        /// The closest peers to the content that responded during the lookup, ordered by
+        /// increasing distance.
+        closest_enrs: Vec<Enr>,
+        /// If the error was served from the cache of recently failed lookups, when the original
+        /// lookup started, in milliseconds since the Unix epoch.
+        cached_at_ms: Option<u64>,
     },
 }
 
+impl OverlayRequestError {
+    /// Returns the kind of the failure.
+    pub fn kind(&self) -> OverlayRequestErrorKind {
+        match self {
+            Self::UnreachableEnr => OverlayRequestErrorKind::UnreachableAddress,
+            Self::Discv5Error(_) | Self::InvalidRemoteDiscv5Packet => {
+                OverlayRequestErrorKind::SessionFailure
+            }
+            Self::Timeout => OverlayRequestErrorKind::Timeout,
+            Self::DecodeError | Self::InvalidResponse => OverlayRequestErrorKind::DecodeFailure,
+            Self::EmptyResponse => OverlayRequestErrorKind::RemoteError,
+            _ => OverlayRequestErrorKind::Other,
+        }
+    }
+}
+
+/// The kind of failure of an overlay request, used to report distinct JSON-RPC errors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OverlayRequestErrorKind {
+    /// The peer's ENR doesn't have a reachable address.
+    UnreachableAddress,
+    /// The discv5 session with the peer couldn't be established or failed.
+    SessionFailure,
+    /// The peer didn't respond in time.
+    Timeout,
+    /// The peer's response couldn't be decoded.
+    DecodeFailure,
+    /// The peer responded with an error.
+    RemoteError,
+    /// Any other failure.
+    Other,
+}
+
+/// The JSON format of a failed overlay request, as passed from the subnetwork JSON-RPC handlers to
+/// the rpc server.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverlayRequestJsonError {
+    pub kind: OverlayRequestErrorKind,
+    pub message: String,
+}
+
+impl OverlayRequestJsonError {
+    pub fn new(request: &str, err: &OverlayRequestError) -> Self {
+        Self {
+            kind: err.kind(),
+            message: format!("{request} request failed: {err}"),
+        }
+    }
+}
+
 impl From<discv5::RequestError> for OverlayRequestError {
     fn from(err: discv5::RequestError) -> Self {
         match err {
@@ -5,6 +5,7 @@ use std::{
     future::Future,
     marker::{PhantomData, Sync},
     sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::anyhow;
@@ -12,38 +13,62 @@ use bytes::Bytes;
 use discv5::{
     enr::NodeId,
     kbucket::{FailureReason, InsertResult, KBucketsTable, NodeStatus},
+    rpc::RequestId,
     ConnectionDirection, ConnectionState, TalkRequest,
 };
 use ethportal_api::{
     types::{
         bootnodes::Bootnode,
-        discv5::RoutingTableInfo,
+        discv5::{
+            BucketRefreshTimesInfo, KeyspaceDensityInfo, ReplacementCacheInfo, RoutingTableInfo,
+        },
         distance::{Distance, Metric},
         enr::Enr,
         network::Subnetwork,
+        portal::{
+            AcceptSnapshotInfo, ActiveLookupInfo, ContentProvenance, EnrSource, FetchAndStoreInfo,
+            PingRawInfo, PongInfo, RecentOfferersInfo, TraceAnalysisInfo, TracedPeerStatus,
+            VerifyPeersInfo,
+        },
         portal_wire::{
-            Accept, Content, CustomPayload, FindContent, FindNodes, Message, Nodes, OfferTrace,
-            Ping, Pong, PopulatedOffer, PopulatedOfferWithResult, Request, Response,
+            accept_content_keys_bytes, encode_response, Accept, Content, CustomPayload,
+            FindContent, FindNodes, Message, Nodes, OfferTrace, Ping, Pong, PopulatedOffer,
+            PopulatedOfferWithResult, Request, Response,
         },
+        query_trace::QueryTrace,
     },
     utils::bytes::hex_encode,
     OverlayContentKey, RawContentKey, RawContentValue,
 };
-use futures::channel::oneshot;
-use parking_lot::RwLock;
+use futures::{channel::oneshot, stream, StreamExt};
+use parking_lot::{Mutex, RwLock};
 use ssz::Encode;
-use tokio::sync::{broadcast, mpsc::UnboundedSender};
-use tracing::{debug, error, info, warn};
-use trin_metrics::{overlay::OverlayMetricsReporter, portalnet::PORTALNET_METRICS};
-use trin_storage::ContentStore;
+use tokio::{
+    sync::{broadcast, mpsc::UnboundedSender},
+    task::JoinHandle,
+};
+use tracing::{debug, error, info, warn, Span};
+use trin_metrics::{
+    labels::GossipOutcomeLabel, overlay::OverlayMetricsReporter, portalnet::PORTALNET_METRICS,
+};
+use trin_storage::{ContentStore, ShouldWeStoreContent};
 use trin_validation::validator::{ValidationResult, Validator};
 use utp_rs::socket::UtpSocket;
 
 use crate::{
-    discovery::{Discovery, UtpEnr},
+    accept_snapshot::AcceptSnapshots,
+    discovery::{check_enr, check_enr_network, Discovery, UtpEnr},
     events::EventEnvelope,
-    find::query_info::{FindContentResult, RecursiveFindContentResult},
-    gossip::{propagate_gossip_cross_thread, trace_propagate_gossip_cross_thread, GossipResult},
+    find::{
+        failed_lookups::FailedLookupCache,
+        in_flight_lookups::InFlightLookups,
+        lookup_results::LookupResultCache,
+        query_info::{FindContentResult, RecursiveFindContentResult},
+    },
+    gossip::{
+        propagate_gossip_cross_thread, trace_propagate_gossip_cross_thread, GossipConfig,
+        GossipCount, GossipResult, GossipSwitch, LargeContentGossip,
+    },
     overlay::{
         command::OverlayCommand,
         config::{FindContentConfig, OverlayConfig},
@@ -51,18 +76,23 @@ use crate::{
         request::{OverlayRequest, RequestDirection},
         service::OverlayService,
     },
+    recent_offerers::RecentOfferers,
     types::{
         kbucket::{Entry, SharedKBucketsTable},
         node::Node,
+        protocol_version::ProtocolFeature,
     },
     utp_controller::UtpController,
+    wire_recorder::{self, WireDirection},
 };
 
+/// The max number of pings in flight while verifying the peers of the routing table.
+const VERIFY_PEERS_CONCURRENCY: usize = 16;
+
 /// Overlay protocol is a layer on top of discv5 that handles all requests from the overlay networks
 /// (state, history etc.) and dispatch them to the discv5 protocol TalkReq. Each network should
 /// implement the overlay protocol and the overlay protocol is where we can encapsulate the logic
 /// for handling common network requests/responses.
-#[derive(Clone)]
 pub struct OverlayProtocol<TContentKey, TMetric, TValidator, TStore> {
     /// Reference to the underlying discv5 protocol
     pub discovery: Arc<Discovery>,
@@ -86,6 +116,35 @@ pub struct OverlayProtocol<TContentKey, TMetric, TValidator, TStore> {
     validator: Arc<TValidator>,
     /// Runtime telemetry metrics for the overlay network.
     metrics: OverlayMetricsReporter,
+    /// The peers that recently offered us content.
+    recent_offerers: Arc<RwLock<RecentOfferers>>,
+    /// The content lookups in progress, which concurrent lookups of the same content join.
+    in_flight_lookups: InFlightLookups,
+    /// The recently failed content lookups, if the cache is enabled.
+    failed_lookups: Option<Arc<Mutex<FailedLookupCache>>>,
+    /// The content recently found by lookups, if the cache is enabled.
+    lookup_results: Option<Mutex<LookupResultCache>>,
+    /// The versioned snapshots of the inputs of the accept decision.
+    accept_snapshots: Mutex<AcceptSnapshots>,
+    /// Whether content is gossiped, shared with the overlay service. Paused gossip doesn't affect
+    /// incoming offers.
+    gossip_switch: Arc<GossipSwitch>,
+    /// Which of the interested peers gossiped content is offered to.
+    gossip_config: GossipConfig,
+    /// The large gossiped content that is offered in the background, shared with the overlay
+    /// service.
+    large_content_gossip: LargeContentGossip,
+    /// The task running the overlay service, which is aborted once the overlay protocol is
+    /// dropped.
+    service_task: JoinHandle<()>,
+}
+
+impl<TContentKey, TMetric, TValidator, TStore> Drop
+    for OverlayProtocol<TContentKey, TMetric, TValidator, TStore>
+{
+    fn drop(&mut self) {
+        self.service_task.abort();
+    }
 }
 
 impl<
@@ -121,25 +180,38 @@ impl<
             utp_socket,
             metrics.clone(),
         ));
-        let command_tx = OverlayService::<TContentKey, TMetric, TValidator, TStore>::spawn(
-            Arc::clone(&discovery),
-            Arc::clone(&store),
-            kbuckets.clone(),
-            config.bootnode_enrs,
-            config.ping_queue_interval,
-            protocol,
-            Arc::clone(&utp_controller),
-            metrics.clone(),
-            Arc::clone(&validator),
-            config.query_timeout,
-            config.query_peer_timeout,
-            config.query_parallelism,
-            config.query_num_results,
-            config.findnodes_query_distances_per_peer,
-            config.disable_poke,
-            config.gossip_dropped,
-        )
-        .await;
+        let recent_offerers = Arc::new(RwLock::new(RecentOfferers::default()));
+        let gossip_switch = Arc::new(GossipSwitch::default());
+        let large_content_gossip = LargeContentGossip::new(metrics.clone());
+        let failed_lookups = config
+            .failed_lookup_cache
+            .map(|config| Arc::new(Mutex::new(FailedLookupCache::new(config))));
+        let (command_tx, service_task) =
+            OverlayService::<TContentKey, TMetric, TValidator, TStore>::spawn(
+                Arc::clone(&discovery),
+                Arc::clone(&store),
+                kbuckets.clone(),
+                config.bootnode_enrs,
+                config.ping_queue_interval,
+                protocol,
+                Arc::clone(&utp_controller),
+                metrics.clone(),
+                Arc::clone(&validator),
+                config.query_timeout,
+                config.query_peer_timeout,
+                config.query_parallelism,
+                config.query_num_results,
+                config.findnodes_query_distances_per_peer,
+                config.disable_poke,
+                config.gossip_dropped,
+                config.gossip,
+                large_content_gossip.clone(),
+                config.rebroadcast,
+                Arc::clone(&recent_offerers),
+                Arc::clone(&gossip_switch),
+                failed_lookups.clone(),
+            )
+            .await;
 
         Self {
             discovery,
@@ -152,6 +224,17 @@ impl<
             _phantom_metric: PhantomData,
             validator,
             metrics,
+            recent_offerers,
+            in_flight_lookups: InFlightLookups::default(),
+            accept_snapshots: Mutex::new(AcceptSnapshots::default()),
+            gossip_switch,
+            gossip_config: config.gossip,
+            large_content_gossip,
+            failed_lookups,
+            lookup_results: config
+                .lookup_cache
+                .map(|config| Mutex::new(LookupResultCache::new(config))),
+            service_task,
         }
     }
 
@@ -170,6 +253,14 @@ impl<
         self.store.read().radius()
     }
 
+    /// Returns the radius and the uTP transfer limit that offers are currently accepted by, with
+    /// a version that changes whenever they do.
+    pub fn accept_snapshot(&self) -> AcceptSnapshotInfo {
+        self.accept_snapshots
+            .lock()
+            .snapshot(self.data_radius(), self.utp_controller.transfer_limit())
+    }
+
     /// Processes a single Discovery v5 TALKREQ message.
     pub async fn process_one_request(
         &self,
@@ -188,6 +279,35 @@ impl<
         };
 
         // Send the request and wait on the response.
+        let response = self.send_overlay_request(request, direction).await;
+        if wire_recorder::is_recording() {
+            wire_recorder::record(
+                WireDirection::Inbound,
+                self.protocol,
+                *talk_request.node_id(),
+                talk_request.body().to_vec(),
+                match &response {
+                    Ok(response) => {
+                        Ok(self.encode_response(*talk_request.node_id(), response.clone()))
+                    }
+                    Err(err) => Err(err.to_string()),
+                },
+            );
+        }
+        response
+    }
+
+    /// Processes a request as if it was received from `source`, without recording it. Used to
+    /// replay recorded wire messages against a fresh node.
+    pub async fn process_replayed_request(
+        &self,
+        request: Request,
+        source: NodeId,
+    ) -> Result<Response, OverlayRequestError> {
+        let direction = RequestDirection::Incoming {
+            id: RequestId(rand::random::<u64>().to_be_bytes().to_vec()),
+            source,
+        };
         self.send_overlay_request(request, direction).await
     }
 
@@ -204,14 +324,44 @@ impl<
         Ok(())
     }
 
-    /// Propagate gossip accepted content via OFFER/ACCEPT, return number of peers propagated
-    pub fn propagate_gossip(&self, content: Vec<(TContentKey, RawContentValue)>) -> usize {
-        propagate_gossip_cross_thread::<_, TMetric>(
+    /// Returns whether content is gossiped, i.e. gossip isn't paused.
+    pub fn is_gossip_enabled(&self) -> bool {
+        self.gossip_switch.is_enabled()
+    }
+
+    /// Pauses or resumes gossip. While paused, no content is gossiped to any peer, neither through
+    /// [Self::propagate_gossip] and [Self::propagate_gossip_trace] nor by the overlay service.
+    pub fn set_gossip_enabled(&self, enabled: bool) {
+        if let Some(suppressed) = self.gossip_switch.set_enabled(enabled) {
+            info!(
+                protocol = %self.protocol,
+                suppressed,
+                "Gossip resumed, content items weren't gossiped while it was paused"
+            );
+        }
+    }
+
+    /// Propagate gossip accepted content via OFFER/ACCEPT, return number of peers propagated and
+    /// of peers skipped because their radius excludes the content
+    pub fn propagate_gossip(&self, content: Vec<(TContentKey, RawContentValue)>) -> GossipCount {
+        for (content_key, _) in &content {
+            self.invalidate_failed_lookup(content_key);
+        }
+        if !self.gossip_switch.allow(content.len()) {
+            debug!(protocol = %self.protocol, "Gossip is disabled, not propagating content");
+            return GossipCount::default();
+        }
+        let count = propagate_gossip_cross_thread::<_, TMetric>(
             content,
             &self.kbuckets,
             self.command_tx.clone(),
             None,
-        )
+            &self.gossip_config,
+            &self.large_content_gossip,
+        );
+        self.metrics
+            .report_gossip_outcome(GossipOutcomeLabel::Skipped, count.skipped as u64);
+        count
     }
 
     /// Propagate gossip accepted content via OFFER/ACCEPT, returns trace detailing outcome of
@@ -221,13 +371,22 @@ impl<
         content_key: TContentKey,
         data: RawContentValue,
     ) -> GossipResult {
-        trace_propagate_gossip_cross_thread::<_, TMetric>(
+        self.invalidate_failed_lookup(&content_key);
+        if !self.gossip_switch.allow(1) {
+            debug!(protocol = %self.protocol, "Gossip is disabled, not propagating content");
+            return GossipResult::default();
+        }
+        let gossip_result = trace_propagate_gossip_cross_thread::<_, TMetric>(
             content_key,
             data,
             &self.kbuckets,
             self.command_tx.clone(),
+            &self.gossip_config,
         )
-        .await
+        .await;
+        self.metrics
+            .report_gossip_outcome(GossipOutcomeLabel::Skipped, gossip_result.skipped as u64);
+        gossip_result
     }
 
     /// Returns a vector of all the ENRs of nodes currently contained in the routing table.
@@ -240,16 +399,72 @@ impl<
         RoutingTableInfo {
             local_node_id: self.local_enr().node_id(),
             buckets: ethportal_api::KBucketsTable::from(&self.kbuckets),
+            protocol_versions: self.kbuckets.protocol_versions(),
+            disconnected_peers: self.kbuckets.disconnected_node_ids(),
         }
     }
 
+    /// Returns how densely the buckets of the routing table cover the node id space.
+    pub fn keyspace_density(&self) -> KeyspaceDensityInfo {
+        self.kbuckets.keyspace_density()
+    }
+
+    /// Returns when the buckets of the routing table were last refreshed.
+    pub fn bucket_refresh_times(&self) -> BucketRefreshTimesInfo {
+        self.kbuckets.bucket_refresh_times()
+    }
+
+    /// Returns whether the protocol version negotiated with the peer has the feature, so that the
+    /// message encodings of the feature can be used with it.
+    ///
+    /// Peers that aren't in the routing table are assumed to use the baseline version.
+    pub fn peer_supports(&self, node_id: NodeId, feature: ProtocolFeature) -> bool {
+        self.kbuckets.peer_supports(node_id, feature)
+    }
+
+    /// Encodes the response to a request of the peer, with the message encodings of the protocol
+    /// version negotiated with it.
+    pub fn encode_response(&self, node_id: NodeId, response: Response) -> Vec<u8> {
+        encode_response(
+            response,
+            self.peer_supports(node_id, ProtocolFeature::AcceptCodes),
+        )
+    }
+
+    /// Returns the node-id and, per k-bucket, the ENRs in its replacement cache that are waiting to
+    /// be promoted into the bucket.
+    pub fn replacement_cache_info(&self) -> ReplacementCacheInfo {
+        ReplacementCacheInfo {
+            local_node_id: self.local_enr().node_id(),
+            buckets: ethportal_api::ReplacementCache::from(&self.kbuckets),
+        }
+    }
+
+    /// Returns up to `limit` peers that recently offered us content, the most recent first.
+    pub fn recent_offerers(&self, limit: usize) -> RecentOfferersInfo {
+        self.recent_offerers.read().info(limit)
+    }
+
     /// `AddEnr` adds requested `enr` to our kbucket.
-    pub fn add_enr(&self, enr: Enr) -> Result<(), OverlayRequestError> {
+    ///
+    /// ENRs without a reachable address are rejected, unless `allow_unreachable` is set.
+    pub fn add_enr(&self, enr: Enr, allow_unreachable: bool) -> Result<(), OverlayRequestError> {
+        let known_enr = self
+            .kbuckets
+            .entry(enr.node_id())
+            .present_or_pending()
+            .map(|node| node.enr);
+        check_enr(
+            &enr,
+            self.local_enr().node_id(),
+            known_enr.as_ref(),
+            allow_unreachable,
+        )
+        .map_err(OverlayRequestError::InvalidRequest)?;
+        check_enr_network(&enr, self.discovery.network())
+            .map_err(OverlayRequestError::InvalidRequest)?;
         match self.kbuckets.insert_or_update(
-            Node {
-                enr,
-                data_radius: Distance::MAX,
-            },
+            Node::with_unknown_radius(enr),
             NodeStatus {
                 state: ConnectionState::Connected,
                 direction: ConnectionDirection::Incoming,
@@ -279,15 +494,26 @@ impl<
         }
     }
 
-    /// `GetEnr` gets requested `enr` from our kbucket.
+    /// `GetEnr` gets requested `enr` from the local node, our kbucket, or the ENRs known to the
+    /// discv5 service, in that order.
     pub fn get_enr(&self, node_id: NodeId) -> Result<Enr, OverlayRequestError> {
+        self.get_enr_with_source(node_id).map(|(enr, _)| enr)
+    }
+
+    /// Same as [Self::get_enr], but also returns where the `enr` was found.
+    pub fn get_enr_with_source(
+        &self,
+        node_id: NodeId,
+    ) -> Result<(Enr, EnrSource), OverlayRequestError> {
         if node_id == self.local_enr().node_id() {
-            return Ok(self.local_enr());
+            return Ok((self.local_enr(), EnrSource::LocalNode));
+        }
+        if let Some(node) = self.kbuckets.entry(node_id).present() {
+            return Ok((node.enr, EnrSource::RoutingTable));
         }
-        self.kbuckets
-            .entry(node_id)
-            .present()
-            .map(|node| node.enr)
+        self.discovery
+            .find_enr(&node_id)
+            .map(|enr| (enr, EnrSource::Discv5))
             .ok_or_else(|| OverlayRequestError::Failure("Couldn't get ENR".to_string()))
     }
 
@@ -296,7 +522,8 @@ impl<
         self.kbuckets.remove(node_id)
     }
 
-    /// `LookupEnr` finds requested `enr` from our kbucket, FindNode, and RecursiveFindNode.
+    /// `LookupEnr` finds requested `enr` from the known ENRs (see [Self::get_enr]), FindNode, and
+    /// RecursiveFindNode.
     pub async fn lookup_enr(&self, node_id: NodeId) -> Result<Enr, OverlayRequestError> {
         if node_id == self.local_enr().node_id() {
             return Ok(self.local_enr());
@@ -332,16 +559,21 @@ impl<
         enr
     }
 
-    /// Sends a `Ping` request to `enr`.
-    pub async fn send_ping(&self, enr: Enr) -> Result<Pong, OverlayRequestError> {
-        // Construct the request.
+    /// Constructs the `Ping` request, with the local ENR sequence number and data radius.
+    fn ping_request(&self) -> Ping {
         let enr_seq = self.discovery.local_enr().seq();
         let data_radius = self.data_radius();
         let custom_payload = CustomPayload::from(data_radius.as_ssz_bytes());
-        let request = Ping {
+        Ping {
             enr_seq,
             custom_payload,
-        };
+        }
+    }
+
+    /// Sends a `Ping` request to `enr`.
+    pub async fn send_ping(&self, enr: Enr) -> Result<Pong, OverlayRequestError> {
+        // Construct the request.
+        let request = self.ping_request();
 
         let direction = RequestDirection::Outgoing { destination: enr };
 
@@ -356,6 +588,73 @@ impl<
         }
     }
 
+    /// Sends a `Ping` request to `enr`, and returns the decoded `Pong`, as returned by the Ping
+    /// endpoints.
+    ///
+    /// If the peer has a newer ENR than the one known locally, the overlay service requests it, and
+    /// updates the routing table with it.
+    pub async fn send_ping_info(&self, enr: Enr) -> Result<PongInfo, OverlayRequestError> {
+        // The known ENR is read before the ping, as the service may update it once the pong is
+        // received.
+        let known_enr_seq = self.known_enr_seq(&enr);
+        let pong = self.send_ping(enr).await?;
+        pong_info(pong, known_enr_seq)
+    }
+
+    /// Returns the sequence number of the locally known ENR of the peer, or of `enr` if it's newer.
+    fn known_enr_seq(&self, enr: &Enr) -> u64 {
+        self.get_enr(enr.node_id())
+            .map_or(enr.seq(), |known_enr| known_enr.seq().max(enr.seq()))
+    }
+
+    /// Sends a `Ping` request to `enr` as a TALKREQ, and returns the exact request and response
+    /// payloads, along with the decoded `Pong`.
+    ///
+    /// The request bypasses the overlay service, so that the response is returned even if it
+    /// can't be decoded. As a consequence, the peer isn't added to the routing table.
+    pub async fn send_ping_raw(&self, enr: Enr) -> Result<PingRawInfo, OverlayRequestError> {
+        let peer = enr.node_id();
+        let known_enr_seq = self.known_enr_seq(&enr);
+        let request = Message::from(Request::Ping(self.ping_request())).as_ssz_bytes();
+        let talk_resp = self
+            .discovery
+            .send_talk_req(enr, self.protocol, request.clone())
+            .await;
+        if wire_recorder::is_recording() {
+            wire_recorder::record(
+                WireDirection::Outbound,
+                self.protocol,
+                peer,
+                request.clone(),
+                talk_resp
+                    .as_ref()
+                    .map(|talk_resp| talk_resp.to_vec())
+                    .map_err(|err| err.to_string()),
+            );
+        }
+        let response = talk_resp?.to_vec();
+
+        let pong = if response.is_empty() {
+            Err(OverlayRequestError::EmptyResponse)
+        } else {
+            match Message::try_from(response.clone()) {
+                Ok(Message::Pong(pong)) => pong_info(pong, known_enr_seq),
+                Ok(_) => Err(OverlayRequestError::InvalidResponse),
+                Err(_) => Err(OverlayRequestError::DecodeError),
+            }
+        };
+        let (pong, error) = match pong {
+            Ok(pong) => (Some(pong), None),
+            Err(err) => (None, Some(err.to_string())),
+        };
+        Ok(PingRawInfo {
+            request: request.into(),
+            response: response.into(),
+            pong,
+            error,
+        })
+    }
+
     /// Sends a `FindNodes` request to `enr`.
     pub async fn send_find_nodes(
         &self,
@@ -384,6 +683,141 @@ impl<
         enr: Enr,
         content_key: RawContentKey,
     ) -> Result<FindContentResult, OverlayRequestError> {
+        self.send_validated_find_content(enr, content_key)
+            .await
+            .map(|(content, utp_transfer, _)| (content, utp_transfer))
+    }
+
+    /// Sends a `FindContent` request for `content_key` to `enr`, and stores the found content.
+    ///
+    /// The content isn't stored if it isn't valid for storing, if it's already stored, or, unless
+    /// `ignore_radius` is set, if it's outside our radius. Content that fails validation isn't
+    /// stored either, and is reported as an error.
+    pub async fn fetch_and_store(
+        &self,
+        enr: Enr,
+        content_key: TContentKey,
+        ignore_radius: bool,
+    ) -> Result<FetchAndStoreInfo, OverlayRequestError> {
+        let (content, utp_transfer, validation_result) = self
+            .send_validated_find_content(enr.clone(), content_key.to_bytes())
+            .await?;
+        let (content, validation_result) = match (content, validation_result) {
+            (Content::Content(content), Some(validation_result)) => (content, validation_result),
+            (Content::Enrs(enrs), _) => {
+                return Err(OverlayRequestError::ContentNotFound {
+                    message: format!("Peer {} doesn't have the content", enr.node_id()),
+                    utp: false,
+                    trace: None,
+                    closest_enrs: enrs.into_iter().map(|enr| enr.into()).collect(),
+                    cached_at_ms: None,
+                })
+            }
+            _ => return Err(OverlayRequestError::InvalidResponse),
+        };
+
+        let content_length = content.len() as u64;
+        let not_stored = |reason: &str| FetchAndStoreInfo {
+            stored: false,
+            content_length,
+            utp_transfer,
+            reason: Some(reason.to_string()),
+        };
+        if !validation_result.valid_for_storing {
+            return Ok(not_stored("Content isn't valid for storing"));
+        }
+        // The check and the store hold the same lock, so that content stored in between, e.g. by
+        // an offer, isn't stored again.
+        let mut store = self.store.write();
+        let should_store = store
+            .is_key_within_radius_and_unavailable(&content_key)
+            .map_err(|err| OverlayRequestError::Failure(err.to_string()))?;
+        match should_store {
+            ShouldWeStoreContent::AlreadyStored => {
+                return Ok(not_stored("Content is already stored"))
+            }
+            ShouldWeStoreContent::NotWithinRadius if !ignore_radius => {
+                return Ok(not_stored("Content is outside our radius"))
+            }
+            ShouldWeStoreContent::Store | ShouldWeStoreContent::NotWithinRadius => {}
+        }
+
+        store
+            .put_with_meta(
+                content_key.clone(),
+                content,
+                ContentProvenance::Fetch,
+                /* offered_by= */ None,
+            )
+            .map_err(|err| OverlayRequestError::Failure(err.to_string()))?;
+        drop(store);
+        self.invalidate_failed_lookup(&content_key);
+        Ok(FetchAndStoreInfo {
+            stored: true,
+            content_length,
+            utp_transfer,
+            reason: None,
+        })
+    }
+
+    /// Sends a `FindContent` request for `content_key` to `enr`, and returns the result of the
+    /// validation of the found content, if any.
+    async fn send_validated_find_content(
+        &self,
+        enr: Enr,
+        content_key: RawContentKey,
+    ) -> Result<(Content, bool, Option<ValidationResult<TContentKey>>), OverlayRequestError> {
+        let (content_key, content, utp_transfer) =
+            self.send_unvalidated_find_content(enr, content_key).await?;
+        match content {
+            Content::Content(content) => {
+                match self.validate_content(&content_key, &content).await {
+                    Ok(validation_result) => Ok((
+                        Content::Content(content),
+                        utp_transfer,
+                        Some(validation_result),
+                    )),
+                    Err(msg) => Err(OverlayRequestError::FailedValidation(format!(
+                        "Network: {:?}, Reason: {msg:?}",
+                        self.protocol
+                    ))),
+                }
+            }
+            content => Ok((content, utp_transfer, None)),
+        }
+    }
+
+    /// Sends a `FindContent` request for `content_key` to `enr`. Unlike
+    /// [Self::send_find_content], content that doesn't pass validation is returned rather than
+    /// reported as an error, along with whether it passed validation.
+    pub async fn send_find_content_with_validity(
+        &self,
+        enr: Enr,
+        content_key: RawContentKey,
+    ) -> Result<(Content, bool, Option<bool>), OverlayRequestError> {
+        let (content_key, content, utp_transfer) =
+            self.send_unvalidated_find_content(enr, content_key).await?;
+        let valid = match &content {
+            Content::Content(content) => {
+                let validation_result = self.validate_content(&content_key, content).await;
+                if let Err(err) = &validation_result {
+                    debug!(%err, "Found content failed validation");
+                }
+                Some(validation_result.is_ok())
+            }
+            _ => None,
+        };
+        Ok((content, utp_transfer, valid))
+    }
+
+    /// Sends a `FindContent` request for `content_key` to `enr`, and receives the content over
+    /// uTP if the peer sends a connection id. Returns the decoded content key, the content or the
+    /// ENRs, and whether the content was transferred over uTP.
+    async fn send_unvalidated_find_content(
+        &self,
+        enr: Enr,
+        content_key: RawContentKey,
+    ) -> Result<(TContentKey, Content, bool), OverlayRequestError> {
         // Construct the request.
         let request = FindContent {
             content_key: content_key.clone(),
@@ -402,34 +836,16 @@ impl<
             .send_overlay_request(Request::FindContent(request), direction)
             .await
         {
-            Ok(Response::Content(found_content)) => {
-                match found_content {
-                    Content::Content(content) => {
-                        match self.validate_content(&content_key, &content).await {
-                            Ok(_) => Ok((Content::Content(content), false)),
-                            Err(msg) => Err(OverlayRequestError::FailedValidation(format!(
-                                "Network: {:?}, Reason: {msg:?}",
-                                self.protocol
-                            ))),
-                        }
-                    }
-                    Content::Enrs(_) => Ok((found_content, false)),
-                    // Init uTP stream if `connection_id` is received
-                    Content::ConnectionId(conn_id) => {
-                        let conn_id = u16::from_be(conn_id);
-                        let content = RawContentValue::from(
-                            self.init_find_content_stream(enr, conn_id).await?,
-                        );
-                        match self.validate_content(&content_key, &content).await {
-                            Ok(_) => Ok((Content::Content(content), true)),
-                            Err(msg) => Err(OverlayRequestError::FailedValidation(format!(
-                                "Network: {:?}, Reason: {msg:?}",
-                                self.protocol
-                            ))),
-                        }
-                    }
+            Ok(Response::Content(found_content)) => match found_content {
+                // Init uTP stream if `connection_id` is received
+                Content::ConnectionId(conn_id) => {
+                    let conn_id = u16::from_be(conn_id);
+                    let content =
+                        RawContentValue::from(self.init_find_content_stream(enr, conn_id).await?);
+                    Ok((content_key, Content::Content(content), true))
                 }
-            }
+                found_content => Ok((content_key, found_content, false)),
+            },
             Ok(_) => Err(OverlayRequestError::InvalidResponse),
             Err(error) => Err(error),
         }
@@ -466,6 +882,8 @@ impl<
                 message: format!("Unable to locate content on the network: {err:?}"),
                 utp: true,
                 trace: None,
+                closest_enrs: vec![],
+                cached_at_ms: None,
             })
     }
 
@@ -490,6 +908,34 @@ impl<
         }
     }
 
+    /// Sends an `Offer` request with the content to `enr`, like [Self::send_offer], and returns the
+    /// `Accept` response along with its content keys field, exactly as the peer encoded it.
+    pub async fn send_offer_with_accept_bytes(
+        &self,
+        enr: Enr,
+        content_items: Vec<(RawContentKey, RawContentValue)>,
+    ) -> Result<(Accept, Vec<u8>), OverlayRequestError> {
+        let request = Request::PopulatedOffer(PopulatedOffer { content_items });
+        let direction = RequestDirection::Outgoing { destination: enr };
+        let (payload_tx, payload_rx) = oneshot::channel();
+
+        let accept = match self
+            .send_overlay_request_with_payload(request, direction, Some(payload_tx))
+            .await
+        {
+            Ok(Response::Accept(accept)) => accept,
+            Ok(_) => return Err(OverlayRequestError::InvalidResponse),
+            Err(error) => return Err(error),
+        };
+        // The payload is sent before the response is decoded, so it's there once the response is.
+        let payload = payload_rx
+            .await
+            .map_err(|err| OverlayRequestError::ChannelFailure(err.to_string()))?;
+        let accept_bytes =
+            accept_content_keys_bytes(&payload).ok_or(OverlayRequestError::InvalidResponse)?;
+        Ok((accept, accept_bytes.to_vec()))
+    }
+
     /// Send Offer request with trace, without storing the content into db
     pub async fn send_offer_trace(
         &self,
@@ -571,38 +1017,76 @@ impl<
 
     /// Performs a content lookup for `target`.
     /// Returns the target content along with the peers traversed during content lookup.
+    ///
+    /// Concurrent lookups of the same content share a single query of the network. If the lookup
+    /// cache is enabled, content found recently is returned without querying the network, unless
+    /// the lookup is traced.
     pub async fn lookup_content(
         &self,
         target: TContentKey,
         config: FindContentConfig,
     ) -> Result<RecursiveFindContentResult, OverlayRequestError> {
-        let (tx, rx) = oneshot::channel();
         let content_id = target.content_id();
+        if let (Some(lookup_results), false) = (&self.lookup_results, config.is_trace) {
+            if let Some((content, utp_transfer)) = lookup_results.lock().get(&content_id) {
+                debug!(
+                    protocol = %self.protocol,
+                    content.id = %hex_encode(content_id),
+                    "Content recently found on the network, returning cached content"
+                );
+                return Ok(Ok((content, utp_transfer, None)));
+            }
+        }
+        if let Some(failed_lookups) = &self.failed_lookups {
+            if let Some(err) = failed_lookups.lock().get(&content_id, config.is_trace) {
+                debug!(
+                    protocol = %self.protocol,
+                    content.id = %hex_encode(content_id),
+                    "Content recently not found on the network, returning cached failure"
+                );
+                return Ok(Err(err));
+            }
+        }
 
-        if let Err(err) = self.command_tx.send(OverlayCommand::FindContentQuery {
-            target,
-            callback: tx,
-            config,
-        }) {
-            warn!(
-                protocol = %self.protocol,
-                error = %err,
-                content.id = %hex_encode(content_id),
-                "Error submitting FindContent query to service"
-            );
-            return Err(OverlayRequestError::ChannelFailure(err.to_string()));
+        let started_at_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let command_tx = self.command_tx.clone();
+        let protocol = self.protocol;
+        let result = self
+            .in_flight_lookups
+            .lookup(content_id, config, move |config| {
+                query_content(command_tx, protocol, target, config)
+            })
+            .await;
+        if let (Some(failed_lookups), Ok(Err(err))) = (&self.failed_lookups, &result) {
+            failed_lookups.lock().insert(content_id, err, started_at_ms);
+        }
+        if let (Some(lookup_results), Ok(Ok((content, utp_transfer, _)))) =
+            (&self.lookup_results, &result)
+        {
+            lookup_results
+                .lock()
+                .insert(content_id, content.clone(), *utp_transfer);
         }
+        result
+    }
 
-        // Wait on the response.
-        rx.await.map_err(|err| {
-            warn!(
-                protocol = %self.protocol,
-                error = %err,
-                content.id = %hex_encode(content_id),
-                "Error receiving FindContent query response"
-            );
-            OverlayRequestError::ChannelFailure(err.to_string())
-        })
+    /// Forgets the content recently found by lookups, and returns how many entries were
+    /// forgotten. Returns 0 if the lookup cache is disabled.
+    pub fn clear_lookup_cache(&self) -> usize {
+        self.lookup_results
+            .as_ref()
+            .map_or(0, |lookup_results| lookup_results.lock().clear())
+    }
+
+    /// Forgets the recent failed lookup of the content, if any, e.g. because the content was since
+    /// stored.
+    pub fn invalidate_failed_lookup(&self, content_key: &TContentKey) {
+        if let Some(failed_lookups) = &self.failed_lookups {
+            failed_lookups.lock().invalidate(&content_key.content_id());
+        }
     }
 
     /// Sends a request through the overlay service.
@@ -611,8 +1095,29 @@ impl<
         request: Request,
         direction: RequestDirection,
     ) -> Result<Response, OverlayRequestError> {
+        self.send_overlay_request_with_payload(request, direction, None)
+            .await
+    }
+
+    /// Sends a request through the overlay service. The payload of the response is sent to
+    /// `payload_tx`, if any, as received on the wire.
+    async fn send_overlay_request_with_payload(
+        &self,
+        request: Request,
+        direction: RequestDirection,
+        payload_tx: Option<oneshot::Sender<Vec<u8>>>,
+    ) -> Result<Response, OverlayRequestError> {
+        if let RequestDirection::Outgoing { destination } = &direction {
+            if destination.udp4_socket().is_none() && destination.udp6_socket().is_none() {
+                return Err(OverlayRequestError::UnreachableEnr);
+            }
+        }
+
         let (tx, rx) = oneshot::channel();
-        let overlay_request = OverlayRequest::new(request, direction, Some(tx), None, None);
+        let mut overlay_request = OverlayRequest::new(request, direction, Some(tx), None, None);
+        if let Some(payload_tx) = payload_tx {
+            overlay_request = overlay_request.with_payload_responder(payload_tx);
+        }
         if let Err(error) = self
             .command_tx
             .send(OverlayCommand::Request(overlay_request))
@@ -678,6 +1183,96 @@ impl<
         }
     }
 
+    /// Pings up to `limit` peers from the routing table, and reports which of them responded.
+    ///
+    /// Peers that didn't respond are removed from the routing table if `evict` is set.
+    pub async fn verify_peers(&self, limit: usize, evict: bool) -> VerifyPeersInfo {
+        let ping_results: Vec<(NodeId, bool)> =
+            stream::iter(self.table_entries_enr().into_iter().take(limit))
+                .map(|enr| async move {
+                    let node_id = enr.node_id();
+                    (node_id, self.send_ping(enr).await.is_ok())
+                })
+                .buffer_unordered(VERIFY_PEERS_CONCURRENCY)
+                .collect()
+                .await;
+
+        let mut responded = vec![];
+        let mut unresponsive = vec![];
+        for (node_id, is_responsive) in ping_results {
+            if is_responsive {
+                responded.push(node_id);
+            } else {
+                unresponsive.push(node_id);
+            }
+        }
+
+        let evicted = if evict {
+            unresponsive
+                .iter()
+                .filter(|node_id| self.delete_enr(**node_id))
+                .copied()
+                .collect()
+        } else {
+            vec![]
+        };
+
+        VerifyPeersInfo {
+            responded,
+            unresponsive,
+            evicted,
+        }
+    }
+
+    /// Reports the current status of every peer in a previously recorded query trace: whether it is
+    /// in the routing table and whether it responds to a ping. No lookup is performed.
+    ///
+    /// The peers are pinged without going through the overlay service, so the analysis doesn't add
+    /// peers to the routing table or update their status in it.
+    pub async fn analyze_trace(&self, trace: &QueryTrace) -> TraceAnalysisInfo {
+        let local_node_id = self.local_enr().node_id();
+        let node_ids: HashSet<NodeId> = trace
+            .metadata
+            .keys()
+            .chain(trace.responses.keys())
+            .chain(trace.failures.keys())
+            .chain(trace.cancelled.iter())
+            .filter(|node_id| **node_id != local_node_id)
+            .copied()
+            .collect();
+
+        let peers = stream::iter(node_ids)
+            .map(|node_id| async move {
+                // Prefer the ENR from the routing table, as it may be newer than the traced one.
+                let routing_table_enr = self.kbuckets.entry(node_id).present().map(|node| node.enr);
+                let in_routing_table = routing_table_enr.is_some();
+                let enr = routing_table_enr
+                    .or_else(|| trace.metadata.get(&node_id).map(|info| info.enr.clone()));
+                let reachable = match enr {
+                    Some(enr) => self
+                        .send_ping_raw(enr)
+                        .await
+                        .is_ok_and(|ping| ping.pong.is_some()),
+                    None => false,
+                };
+                let status = TracedPeerStatus {
+                    responded_in_trace: trace.responses.contains_key(&node_id),
+                    failure_in_trace: trace
+                        .failures
+                        .get(&node_id)
+                        .map(|failure| failure.failure.clone()),
+                    in_routing_table,
+                    reachable,
+                };
+                (node_id, status)
+            })
+            .buffer_unordered(VERIFY_PEERS_CONCURRENCY)
+            .collect()
+            .await;
+
+        TraceAnalysisInfo { peers }
+    }
+
     pub fn get_message_summary(&self) -> String {
         self.metrics.get_message_summary()
     }
@@ -686,6 +1281,53 @@ impl<
         self.metrics.get_utp_summary()
     }
 
+    /// Returns the uTP upload rate limit in bytes per second, or `None` if unlimited. The limit is
+    /// shared by all the subnetworks.
+    pub fn utp_bandwidth_limit(&self) -> Option<u64> {
+        self.discovery.utp_throttle().upload_limit()
+    }
+
+    /// Sets the uTP upload rate limit in bytes per second, for all the subnetworks. `None` removes
+    /// the limit.
+    pub fn set_utp_bandwidth_limit(&self, limit: Option<u64>) {
+        self.discovery.utp_throttle().set_upload_limit(limit)
+    }
+
+    /// Returns the max number of uTP transfers that run at once.
+    pub fn utp_concurrent_transfer_limit(&self) -> usize {
+        self.utp_controller.concurrent_transfer_limit()
+    }
+
+    /// Sets the max number of uTP transfers that run at once. The transfers above the limit wait
+    /// for their turn.
+    pub fn set_utp_concurrent_transfer_limit(&self, limit: usize) {
+        self.utp_controller.set_concurrent_transfer_limit(limit)
+    }
+
+    /// Returns the number of uTP transfers that are running.
+    pub fn active_utp_transfers(&self) -> usize {
+        self.utp_controller.active_transfer_count()
+    }
+
+    /// Force-closes the uTP transfer with the peer over the connection with the given id. Returns
+    /// `false` if no such transfer is in progress.
+    pub fn close_utp_transfer(&self, peer: NodeId, connection_id: u16) -> bool {
+        self.utp_controller.close_transfer(peer, connection_id)
+    }
+
+    /// Returns the content lookups that are currently running on the network. Lookups answered
+    /// from the caches, or that joined another lookup of the same content, aren't listed
+    /// separately.
+    pub async fn active_lookups(&self) -> anyhow::Result<Vec<ActiveLookupInfo>> {
+        let (callback_send, callback_recv) = oneshot::channel();
+        self.command_tx
+            .send(OverlayCommand::ActiveLookups(callback_send))
+            .map_err(|_| anyhow!("The Overlay Service channel has been closed early."))?;
+        callback_recv
+            .await
+            .map_err(|_| anyhow!("The Overlay Service callback channel has been closed early."))
+    }
+
     /// Creates an event stream channel which can be polled to receive overlay events.
     pub fn event_stream(
         &self,
@@ -707,6 +1349,18 @@ impl<
     }
 }
 
+/// Decodes the `Pong` into the response of the Ping endpoints. The peer has a new ENR if its
+/// sequence number is higher than `known_enr_seq`.
+fn pong_info(pong: Pong, known_enr_seq: u64) -> Result<PongInfo, OverlayRequestError> {
+    let data_radius = Distance::try_from(pong.custom_payload)
+        .map_err(|_| OverlayRequestError::InvalidResponse)?;
+    Ok(PongInfo {
+        enr_seq: pong.enr_seq,
+        data_radius: *data_radius,
+        new_enr_detected: pong.enr_seq > known_enr_seq,
+    })
+}
+
 fn validate_find_nodes_distances(distances: &[u16]) -> Result<(), OverlayRequestError> {
     if distances.is_empty() {
         return Err(OverlayRequestError::InvalidRequest(
@@ -733,6 +1387,43 @@ fn validate_find_nodes_distances(distances: &[u16]) -> Result<(), OverlayRequest
     Ok(())
 }
 
+/// Submits a find content query for `target` to the overlay service and waits for its result.
+async fn query_content<TContentKey: OverlayContentKey>(
+    command_tx: UnboundedSender<OverlayCommand<TContentKey>>,
+    protocol: Subnetwork,
+    target: TContentKey,
+    config: FindContentConfig,
+) -> Result<RecursiveFindContentResult, OverlayRequestError> {
+    let (tx, rx) = oneshot::channel();
+    let content_id = target.content_id();
+
+    if let Err(err) = command_tx.send(OverlayCommand::FindContentQuery {
+        target,
+        callback: tx,
+        config,
+        span: Span::current(),
+    }) {
+        warn!(
+            protocol = %protocol,
+            error = %err,
+            content.id = %hex_encode(content_id),
+            "Error submitting FindContent query to service"
+        );
+        return Err(OverlayRequestError::ChannelFailure(err.to_string()));
+    }
+
+    // Wait on the response.
+    rx.await.map_err(|err| {
+        warn!(
+            protocol = %protocol,
+            error = %err,
+            content.id = %hex_encode(content_id),
+            "Error receiving FindContent query response"
+        );
+        OverlayRequestError::ChannelFailure(err.to_string())
+    })
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test {
@@ -7,6 +7,7 @@ use ethportal_api::types::{
 };
 use futures::channel::oneshot;
 use tokio::sync::OwnedSemaphorePermit;
+use tracing::Span;
 
 use super::errors::OverlayRequestError;
 use crate::find::query_pool::QueryId;
@@ -28,6 +29,9 @@ pub type OverlayRequestId = u128;
 /// An overlay request response channel.
 type OverlayResponder = oneshot::Sender<Result<Response, OverlayRequestError>>;
 
+/// A channel for the payload of the response to an outgoing request, as received on the wire.
+type PayloadResponder = oneshot::Sender<Vec<u8>>;
+
 /// A request to pass through the overlay.
 #[derive(Debug)]
 pub struct OverlayRequest {
@@ -40,11 +44,16 @@ pub struct OverlayRequest {
     /// An optional responder to send a result of the request.
     /// The responder may be None if the request was initiated internally.
     pub responder: Option<OverlayResponder>,
+    /// An optional responder to send the payload of the response to, before it is decoded. Only
+    /// used for outgoing requests.
+    pub payload_responder: Option<PayloadResponder>,
     /// ID of query that request's response will advance.
     /// Will be None for requests that are not associated with a query.
     pub query_id: Option<QueryId>,
     /// An optional permit to allow for transfer caps
     pub request_permit: Option<OwnedSemaphorePermit>,
+    /// The span of the issuer, which the log lines of the request are attributed to.
+    pub span: Span,
 }
 
 impl OverlayRequest {
@@ -61,10 +70,18 @@ impl OverlayRequest {
             request,
             direction,
             responder,
+            payload_responder: None,
             query_id,
             request_permit,
+            span: Span::current(),
         }
     }
+
+    /// Sets the responder that the payload of the response is sent to, as received on the wire.
+    pub fn with_payload_responder(mut self, payload_responder: PayloadResponder) -> Self {
+        self.payload_responder = Some(payload_responder);
+        self
+    }
 }
 
 /// An active outgoing overlay request.
@@ -78,6 +95,8 @@ pub struct ActiveOutgoingRequest {
     pub query_id: Option<QueryId>,
     /// An optional permit to allow for transfer caps
     pub request_permit: Option<OwnedSemaphorePermit>,
+    /// The span of the issuer of the request.
+    pub span: Span,
 }
 
 /// A response for a particular overlay request.
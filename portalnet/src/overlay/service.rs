@@ -9,7 +9,6 @@ use std::{
 use anyhow::anyhow;
 use bytes::Bytes;
 use crossbeam_channel::Sender;
-use delay_map::HashSetDelay;
 use discv5::{
     enr::NodeId,
     kbucket::{
@@ -24,9 +23,10 @@ use ethportal_api::{
         distance::{Distance, Metric},
         enr::{Enr, SszEnr},
         network::Subnetwork,
+        portal::{ActiveLookupInfo, ContentProvenance, LookupStage, MAX_CONTENT_KEYS_PER_OFFER},
         portal_wire::{
-            Accept, Content, CustomPayload, FindContent, FindNodes, Message, Nodes, Offer,
-            OfferTrace, Ping, Pong, PopulatedOffer, Request, Response,
+            decode_response, Accept, Content, CustomPayload, FindContent, FindNodes, Message,
+            Nodes, Offer, OfferTrace, Ping, Pong, PopulatedOffer, Request, Response,
             MAX_PORTAL_CONTENT_PAYLOAD_SIZE, MAX_PORTAL_NODES_ENRS_SIZE,
         },
         query_trace::{QueryFailureKind, QueryTrace},
@@ -35,8 +35,8 @@ use ethportal_api::{
     OverlayContentKey, RawContentKey, RawContentValue,
 };
 use futures::{channel::oneshot, future::join_all, prelude::*};
-use parking_lot::RwLock;
-use rand::Rng;
+use parking_lot::{Mutex, RwLock};
+use rand::{seq::IteratorRandom, Rng};
 use smallvec::SmallVec;
 use ssz::Encode;
 use ssz_types::BitList;
@@ -48,17 +48,23 @@ use tokio::{
     },
     task::JoinHandle,
 };
-use tracing::{debug, enabled, error, info, trace, warn, Level};
-use trin_metrics::overlay::OverlayMetricsReporter;
-use trin_storage::{ContentStore, ShouldWeStoreContent};
+use tracing::{debug, enabled, error, info, trace, warn, Instrument, Level, Span};
+use trin_metrics::{
+    labels::{
+        GossipOutcomeLabel, LivenessPingLabel, PeerStateLabel, PokeOutcomeLabel, PokeTriggerLabel,
+    },
+    overlay::OverlayMetricsReporter,
+};
+use trin_storage::{ContentStore, RadiusChange, ShouldWeStoreContent};
 use trin_validation::validator::Validator;
 use utp_rs::cid::ConnectionId;
 
 use crate::{
     accept_queue::AcceptQueue,
-    discovery::{Discovery, UtpEnr},
+    discovery::{check_enr, Discovery, UtpEnr, ADDRESS_CHANGE_PING_SAMPLE},
     events::{EventEnvelope, OverlayEvent},
     find::{
+        failed_lookups::FailedLookupCache,
         iterators::{
             findcontent::{
                 FindContentQuery, FindContentQueryPending, FindContentQueryResponse,
@@ -70,7 +76,8 @@ use crate::{
         query_info::{QueryInfo, QueryType, RecursiveFindContentResult},
         query_pool::{QueryId, QueryPool, QueryPoolState, TargetKey},
     },
-    gossip::propagate_gossip_cross_thread,
+    gossip::{propagate_gossip_cross_thread, GossipConfig, GossipSwitch, LargeContentGossip},
+    liveness::{DuePings, LivenessTracker, DEFAULT_PING_INTERVAL},
     overlay::{
         command::OverlayCommand,
         config::FindContentConfig,
@@ -80,12 +87,16 @@ use crate::{
             RequestDirection,
         },
     },
+    poke::{PokeTracker, RebroadcastConfig},
+    recent_offerers::RecentOfferers,
     types::{
         kbucket::{DiscoveredNodesUpdateResult, Entry, SharedKBucketsTable},
         node::Node,
+        protocol_version::ProtocolFeature,
     },
     utils::portal_wire,
     utp_controller::UtpController,
+    wire_recorder::{self, WireDirection},
 };
 
 pub const FIND_NODES_MAX_NODES: usize = 32;
@@ -101,9 +112,15 @@ const EXPECTED_NON_EMPTY_BUCKETS: usize = 17;
 /// Bucket refresh lookup interval in seconds
 const BUCKET_REFRESH_INTERVAL_SECS: u64 = 60;
 
+/// The period at which peers due for a liveness ping are looked for.
+const LIVENESS_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
 /// The capacity of the event-stream's broadcast channel.
 const EVENT_STREAM_CHANNEL_CAPACITY: usize = 10;
 
+/// The period at which the radius changes of the store are looked for, to emit them as events.
+const RADIUS_CHANGE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 /// The overlay service.
 pub struct OverlayService<TContentKey, TMetric, TValidator, TStore>
 where
@@ -117,11 +134,9 @@ where
     kbuckets: SharedKBucketsTable,
     /// The protocol identifier.
     protocol: Subnetwork,
-    /// A queue of peers that require regular ping to check connectivity.
-    /// Inserted entries expire after a fixed time. Nodes to be pinged are inserted with a timeout
-    /// duration equal to some ping interval, and we continuously poll the queue to check for
-    /// expired entries.
-    peers_to_ping: HashSetDelay<NodeId>,
+    /// The schedule of the liveness pings of the peers that require regular ping to check
+    /// connectivity. It is polled at a fixed period for the peers that are due.
+    liveness: LivenessTracker,
     // TODO: This should probably be a bounded channel.
     /// The receiver half of the service command channel.
     command_rx: UnboundedReceiver<OverlayCommand<TContentKey>>,
@@ -163,12 +178,31 @@ where
     validator: Arc<TValidator>,
     /// A channel that the overlay service emits events on.
     event_stream: broadcast::Sender<EventEnvelope>,
+    /// The number of the last radius change of the store that was emitted as an event.
+    last_radius_change: u64,
     /// Disable poke mechanism
     disable_poke: bool,
     /// Gossip content as it gets dropped from local storage
     gossip_dropped: bool,
+    /// Which of the interested peers gossiped content is offered to.
+    gossip_config: GossipConfig,
+    /// The large gossiped content that is offered in the background, shared with the overlay
+    /// protocol.
+    large_content_gossip: LargeContentGossip,
     /// Accept Queue for inbound content keys
     accept_queue: Arc<RwLock<AcceptQueue<TContentKey>>>,
+    /// The peers that recently offered us content.
+    recent_offerers: Arc<RwLock<RecentOfferers>>,
+    /// The content offered to peers by the poke mechanisms.
+    pokes: Arc<Mutex<PokeTracker>>,
+    /// Whether content is gossiped, shared with the overlay protocol.
+    gossip_switch: Arc<GossipSwitch>,
+    /// The recently failed lookups, shared with the overlay protocol. Stored content is
+    /// invalidated in it, so that lookups for it aren't answered with the cached failure.
+    failed_lookups: Option<Arc<Mutex<FailedLookupCache>>>,
+    /// The stage of the find content queries whose returned content is being transferred over uTP
+    /// or validated, by query id. The other queries are querying peers.
+    lookup_stages: Arc<Mutex<HashMap<QueryId, LookupStage>>>,
 }
 
 impl<
@@ -183,6 +217,8 @@ impl<
     /// The state of the overlay network largely consists of its routing table. The routing table
     /// is updated according to incoming requests and responses as well as autonomous maintenance
     /// processes.
+    ///
+    /// Returns the sender of commands to the service, and the task running it.
     #[allow(clippy::too_many_arguments)]
     pub async fn spawn(
         discovery: Arc<Discovery>,
@@ -201,28 +237,30 @@ impl<
         findnodes_query_distances_per_peer: usize,
         disable_poke: bool,
         gossip_dropped: bool,
-    ) -> UnboundedSender<OverlayCommand<TContentKey>> {
+        gossip_config: GossipConfig,
+        large_content_gossip: LargeContentGossip,
+        rebroadcast: Option<RebroadcastConfig>,
+        recent_offerers: Arc<RwLock<RecentOfferers>>,
+        gossip_switch: Arc<GossipSwitch>,
+        failed_lookups: Option<Arc<Mutex<FailedLookupCache>>>,
+    ) -> (UnboundedSender<OverlayCommand<TContentKey>>, JoinHandle<()>) {
         let (command_tx, command_rx) = mpsc::unbounded_channel();
         let internal_command_tx = command_tx.clone();
 
-        let peers_to_ping = if let Some(interval) = ping_queue_interval {
-            HashSetDelay::new(interval)
-        } else {
-            HashSetDelay::default()
-        };
+        let liveness = LivenessTracker::new(ping_queue_interval.unwrap_or(DEFAULT_PING_INTERVAL));
 
         let (response_tx, response_rx) = mpsc::unbounded_channel();
         let (content_query_trace_events_tx, content_query_trace_events_rx) =
             mpsc::unbounded_channel();
         let (event_stream, _) = broadcast::channel(EVENT_STREAM_CHANNEL_CAPACITY);
 
-        tokio::spawn(async move {
+        let service_task = tokio::spawn(async move {
             let mut service = Self {
                 discovery,
                 store,
                 kbuckets,
                 protocol,
-                peers_to_ping,
+                liveness,
                 command_rx,
                 command_tx: internal_command_tx,
                 active_outgoing_requests: Arc::new(RwLock::new(HashMap::new())),
@@ -243,9 +281,17 @@ impl<
                 metrics,
                 validator,
                 event_stream,
+                last_radius_change: 0,
                 disable_poke,
                 gossip_dropped,
+                gossip_config,
+                large_content_gossip,
                 accept_queue: Arc::new(RwLock::new(AcceptQueue::default())),
+                recent_offerers,
+                pokes: Arc::new(Mutex::new(PokeTracker::new(rebroadcast))),
+                gossip_switch,
+                failed_lookups,
+                lookup_stages: Arc::new(Mutex::new(HashMap::new())),
             };
 
             info!(protocol = %protocol, "Starting overlay service");
@@ -253,7 +299,7 @@ impl<
             service.start().await;
         });
 
-        command_tx
+        (command_tx, service_task)
     }
 
     /// Insert a vector of enrs into the routing table
@@ -267,10 +313,23 @@ impl<
 
         for enr in bootnode_enrs {
             let node_id = enr.node_id();
+            let known_enr = self
+                .kbuckets
+                .entry(node_id)
+                .present_or_pending()
+                .map(|node| node.enr);
+            if let Err(err) = check_enr(&enr, self.local_enr().node_id(), known_enr.as_ref(), false)
+            {
+                warn!(
+                    protocol = %self.protocol,
+                    bootnode = %node_id,
+                    "Skipping bootnode: {err}",
+                );
+                continue;
+            }
 
-            // TODO: Decide default data radius, and define a constant. Or if there is an
-            // associated database, then look for a radius value there.
-            let node = Node::new(enr, Distance::MAX);
+            // TODO: If there is an associated database, then look for a radius value there.
+            let node = Node::with_unknown_radius(enr);
             let state = if set_connected {
                 ConnectionState::Connected
             } else {
@@ -299,7 +358,7 @@ impl<
                     );
 
                     // Queue the node in the ping queue.
-                    self.peers_to_ping.insert(node_id);
+                    self.liveness.insert(node_id);
                 }
             }
         }
@@ -311,11 +370,11 @@ impl<
         let local_node_id = self.local_enr().node_id();
 
         // Begin request for our local node ID.
-        self.init_find_nodes_query(&local_node_id, None);
+        self.init_find_nodes_query(&local_node_id, None, None);
 
         for bucket_index in (255 - EXPECTED_NON_EMPTY_BUCKETS as u8)..255 {
             let target_node_id = generate_random_node_id(bucket_index, self.local_enr().into());
-            self.init_find_nodes_query(&target_node_id, None);
+            self.init_find_nodes_query(&target_node_id, None, Some(bucket_index as usize));
         }
     }
 
@@ -332,10 +391,15 @@ impl<
     /// information relevant to the overlay network.
     ///
     /// Bucket maintenance: Maintain the routing table (more info documented above function).
+    ///
+    /// ENR update: Ping a sample of the routing table after the external address changed.
     async fn start(&mut self) {
         // Construct bucket refresh interval
         let mut bucket_refresh_interval =
             tokio::time::interval(Duration::from_secs(BUCKET_REFRESH_INTERVAL_SECS));
+        let mut liveness_interval = tokio::time::interval(LIVENESS_POLL_INTERVAL);
+        let mut radius_change_interval = tokio::time::interval(RADIUS_CHANGE_POLL_INTERVAL);
+        let mut enr_update_rx = self.discovery.subscribe_enr_updates();
 
         loop {
             tokio::select! {
@@ -343,7 +407,8 @@ impl<
                     match command {
                         OverlayCommand::Request(request) => self.process_request(request),
                         OverlayCommand::Event(event) => self.process_event(event),
-                        OverlayCommand::FindContentQuery { target, callback, config } => {
+                        OverlayCommand::FindContentQuery { target, callback, config, span } => {
+                            let _enter = span.enter();
                             if let Some(query_id) = self.init_find_content_query(target.clone(), Some(callback), config) {
                                 trace!(
                                     query.id = %query_id,
@@ -354,7 +419,7 @@ impl<
                             }
                         }
                         OverlayCommand::FindNodeQuery { target, callback } => {
-                            if let Some(query_id) = self.init_find_nodes_query(&target, Some(callback)) {
+                            if let Some(query_id) = self.init_find_nodes_query(&target, Some(callback), None) {
                                 trace!(
                                     query.id = %query_id,
                                     node.id = %hex_encode_compact(target),
@@ -367,12 +432,18 @@ impl<
                                 error!("Failed to return the event stream channel");
                             }
                         }
+                        OverlayCommand::ActiveLookups(callback) => {
+                            if callback.send(self.active_lookups()).is_err() {
+                                error!("Failed to return the active lookups");
+                            }
+                        }
                     }
                 }
                 Some(response) = self.response_rx.recv() => {
                     // Look up active request that corresponds to the response.
                     let active_request = self.active_outgoing_requests.write().remove(&response.request_id);
                     if let Some(request) = active_request {
+                        let _enter = request.span.enter();
 
                         // Send response to responder if present.
                         if let Some(responder) = request.responder {
@@ -385,19 +456,16 @@ impl<
                                 self.metrics.report_inbound_response(&response);
                                 self.process_response(response, request.destination, request.request, request.query_id, request.request_permit)
                             }
-                            Err(error) => self.process_request_failure(response.request_id, request.destination, error),
+                            Err(error) => self.process_request_failure(response.request_id, request.destination, &request.request, error),
                         }
 
                     } else {
                         warn!(request.id = %hex_encode_compact(response.request_id.to_be_bytes()), "No request found for response");
                     }
                 }
-                Some(Ok(node_id)) = self.peers_to_ping.next() => {
-                    if let Some(node) = self.kbuckets.entry(node_id).present() {
-                        self.ping_node(&node.enr);
-                        self.peers_to_ping.insert(node_id);
-                    }
-                }
+                _ = liveness_interval.tick() => self.ping_due_peers(),
+                _ = radius_change_interval.tick() => self.emit_radius_changes(),
+                Ok(_) = enr_update_rx.recv() => self.ping_peers_on_enr_update(),
                 query_event = OverlayService::<TContentKey, TMetric, TValidator, TStore>::query_event_poll(&mut self.find_node_query_pool) => {
                     self.handle_find_nodes_query_event(query_event);
                 }
@@ -422,7 +490,7 @@ impl<
         // Look at local routing table and select the largest 17 buckets.
         // We only need the 17 bits furthest from our own node ID, because the closest 239 bits of
         // buckets are going to be empty-ish.
-        let target_node_id = {
+        let (bucket, target_node_id) = {
             // This should be 256
             let buckets_count = self.kbuckets.buckets_count();
             // Randomly pick one of the buckets.
@@ -431,7 +499,10 @@ impl<
 
             trace!(protocol = %self.protocol, bucket = %bucket, "Refreshing routing table bucket");
             match u8::try_from(bucket) {
-                Ok(idx) => generate_random_node_id(idx, self.local_enr().into()),
+                Ok(idx) => (
+                    bucket,
+                    generate_random_node_id(idx, self.local_enr().into()),
+                ),
                 Err(err) => {
                     error!(error = %err, "Error downcasting bucket index");
                     return;
@@ -439,7 +510,7 @@ impl<
             }
         };
 
-        self.init_find_nodes_query(&target_node_id, None);
+        self.init_find_nodes_query(&target_node_id, None, Some(bucket));
     }
 
     /// Returns the local ENR of the node.
@@ -590,16 +661,22 @@ impl<
                     }
                 }
                 if let QueryType::FindNode {
-                    callback: Some(callback),
+                    callback,
+                    refreshed_bucket,
                     ..
                 } = query_info.query_type
                 {
-                    if let Err(err) = callback.send(found_enrs.clone()) {
-                        error!(
-                            query.id = %query_id,
-                            error = ?err,
-                            "Error sending FindNode query result to callback",
-                        );
+                    if let Some(callback) = callback {
+                        if let Err(err) = callback.send(found_enrs.clone()) {
+                            error!(
+                                query.id = %query_id,
+                                error = ?err,
+                                "Error sending FindNode query result to callback",
+                            );
+                        }
+                    }
+                    if let Some(bucket) = refreshed_bucket {
+                        self.kbuckets.record_bucket_refresh(bucket);
                     }
                 }
                 trace!(
@@ -663,6 +740,10 @@ impl<
                         valid_content_tx,
                     } => {
                         let utp_processing = UtpProcessing::from(&*self);
+                        let lookup_stages = Arc::clone(&self.lookup_stages);
+                        lookup_stages
+                            .lock()
+                            .insert(query_id, LookupStage::Validating);
                         tokio::spawn(async move {
                             Self::process_received_content(
                                 content,
@@ -676,6 +757,7 @@ impl<
                                 query_trace_events_tx,
                             )
                             .await;
+                            lookup_stages.lock().remove(&query_id);
                         });
                     }
                     FindContentQueryPending::Utp {
@@ -692,6 +774,10 @@ impl<
                             }
                         };
                         let utp_processing = UtpProcessing::from(&*self);
+                        let lookup_stages = Arc::clone(&self.lookup_stages);
+                        lookup_stages
+                            .lock()
+                            .insert(query_id, LookupStage::UtpTransfer);
                         tokio::spawn(async move {
                             let cid = utp_rs::cid::ConnectionId {
                                 recv: connection_id,
@@ -710,6 +796,7 @@ impl<
                                         "Failed to connect to inbound uTP stream for FindContent"
                                     );
                                     // Indicate to the query that the content is invalid
+                                    lookup_stages.lock().remove(&query_id);
                                     let _ = valid_content_tx.send(None);
                                     if let Some(query_trace_events_tx) = query_trace_events_tx {
                                         let _ =
@@ -722,6 +809,9 @@ impl<
                                     return;
                                 }
                             };
+                            lookup_stages
+                                .lock()
+                                .insert(query_id, LookupStage::Validating);
                             Self::process_received_content(
                                 data,
                                 true,
@@ -734,14 +824,16 @@ impl<
                                 query_trace_events_tx,
                             )
                             .await;
+                            lookup_stages.lock().remove(&query_id);
                         });
                     }
                 };
             }
-            QueryEvent::Finished(_, query_info, query)
-            | QueryEvent::TimedOut(_, query_info, query) => {
-                let callback = match query_info.query_type {
-                    QueryType::FindContent { callback, .. } => callback,
+            QueryEvent::Finished(query_id, mut query_info, query)
+            | QueryEvent::TimedOut(query_id, mut query_info, query) => {
+                self.lookup_stages.lock().remove(&query_id);
+                let (callback, span) = match query_info.query_type {
+                    QueryType::FindContent { callback, span, .. } => (callback, span),
                     _ => {
                         error!(
                             "Received wrong QueryType when handling a FindContent Timeout. This is a: {:?}",
@@ -750,8 +842,14 @@ impl<
                         return;
                     }
                 };
+                let _enter = span.enter();
                 match query.into_result() {
                     FindContentQueryResult::ValidContent(valid_content, cancelled_peers) => {
+                        debug!(
+                            protocol = %self.protocol,
+                            query.id = %query_id,
+                            "FindContent query found the content"
+                        );
                         if let Some(responder) = callback {
                             let ValidatedContent {
                                 content,
@@ -777,13 +875,36 @@ impl<
                             }
                         }
                     }
-                    FindContentQueryResult::NoneFound => {
+                    FindContentQueryResult::NoneFound(closest_peers) => {
+                        debug!(
+                            protocol = %self.protocol,
+                            query.id = %query_id,
+                            "FindContent query didn't find the content"
+                        );
                         if let Some(responder) = callback {
+                            // Obtain the ENRs of the closest peers, so the caller can target them.
+                            let closest_enrs = closest_peers
+                                .iter()
+                                .filter_map(|node_id| {
+                                    match query_info
+                                        .untrusted_enrs
+                                        .iter()
+                                        .position(|enr| enr.node_id() == *node_id)
+                                    {
+                                        Some(position) => {
+                                            Some(query_info.untrusted_enrs.swap_remove(position))
+                                        }
+                                        None => self.find_enr(node_id),
+                                    }
+                                })
+                                .collect();
                             let _ = responder.send(Err(OverlayRequestError::ContentNotFound {
                                 message: "Unable to locate content on the network before timeout"
                                     .to_string(),
                                 utp: false,
                                 trace: query_info.trace,
+                                closest_enrs,
+                                cached_at_ms: None,
                             }));
                         }
                     }
@@ -814,7 +935,8 @@ impl<
     }
 
     /// Submits outgoing requests to offer `content` to the closest known nodes whose radius
-    /// contains `content_key`.
+    /// contains `content_key`, unless it was already offered to them.
+    #[allow(clippy::too_many_arguments)]
     fn poke_content(
         kbuckets: &SharedKBucketsTable,
         command_tx: UnboundedSender<OverlayCommand<TContentKey>>,
@@ -822,6 +944,8 @@ impl<
         content: RawContentValue,
         nodes_to_poke: Vec<NodeId>,
         utp_controller: Arc<UtpController>,
+        pokes: &Mutex<PokeTracker>,
+        metrics: &OverlayMetricsReporter,
     ) {
         let content_id = content_key.content_id();
 
@@ -850,6 +974,14 @@ impl<
                     None => continue,
                 };
 
+                let outcome = pokes
+                    .lock()
+                    .poke(*node_id, content_id, PokeTriggerLabel::Lookup);
+                metrics.report_poke(PokeTriggerLabel::Lookup, outcome);
+                if outcome != PokeOutcomeLabel::Offered {
+                    continue;
+                }
+
                 let request = OverlayRequest::new(
                     offer_request,
                     RequestDirection::Outgoing {
@@ -885,6 +1017,8 @@ impl<
 
     /// Processes an overlay request.
     fn process_request(&mut self, request: OverlayRequest) {
+        let span = request.span.clone();
+        let _enter = span.enter();
         // For incoming requests, handle the request, possibly send the response over the channel,
         // and then process the request.
         //
@@ -915,10 +1049,16 @@ impl<
                         request: request.request.clone(),
                         query_id: request.query_id,
                         request_permit: request.request_permit,
+                        span: request.span,
                     },
                 );
                 self.metrics.report_outbound_request(&request.request);
-                self.send_talk_req(request.request, request.id, destination);
+                self.send_talk_req(
+                    request.request,
+                    request.id,
+                    destination,
+                    request.payload_responder,
+                );
             }
         }
     }
@@ -1034,6 +1174,11 @@ impl<
                 if content.len() <= MAX_PORTAL_CONTENT_PAYLOAD_SIZE {
                     Ok(Content::Content(content))
                 } else {
+                    // If we can't open a uTP stream now, send the requester a list of closer ENRs
+                    // rather than making it wait for our turn.
+                    let Some(transfer_permit) = self.utp_controller.get_transfer_permit() else {
+                        return Ok(self.closer_enrs_for_content(&content_key, source));
+                    };
                     // Generate a connection ID for the uTP connection.
                     let enr = self.find_enr(source).ok_or_else(|| {
                         OverlayRequestError::AcceptError(
@@ -1048,7 +1193,8 @@ impl<
                     // over the uTP stream.
                     let utp = Arc::clone(&self.utp_controller);
                     tokio::spawn(async move {
-                        utp.accept_outbound_stream(cid, &content).await;
+                        utp.accept_outbound_stream(cid, &content, transfer_permit)
+                            .await;
                         drop(permit);
                     });
 
@@ -1058,26 +1204,27 @@ impl<
             }
             // If we can't obtain a permit or don't have data to send back, send the requester a
             // list of closer ENRs.
-            (Ok(_), None) | (Ok(None), _) => {
-                let mut enrs = self
-                    .kbuckets
-                    .closest_to_content_id::<TMetric>(
-                        &content_key.content_id(),
-                        FIND_CONTENT_MAX_NODES,
-                    )
-                    .into_iter()
-                    .filter(|enr| &enr.node_id() != source)
-                    .map(SszEnr)
-                    .collect::<Vec<_>>();
-                pop_while_ssz_bytes_len_gt(&mut enrs, MAX_PORTAL_CONTENT_PAYLOAD_SIZE);
-                Ok(Content::Enrs(enrs))
-            }
+            (Ok(_), None) | (Ok(None), _) => Ok(self.closer_enrs_for_content(&content_key, source)),
             (Err(msg), _) => Err(OverlayRequestError::Failure(format!(
                 "Unable to respond to FindContent: {msg}",
             ))),
         }
     }
 
+    /// Builds the `Content` response with the ENRs closest to the content, other than the
+    /// requester's.
+    fn closer_enrs_for_content(&self, content_key: &TContentKey, source: &NodeId) -> Content {
+        let mut enrs = self
+            .kbuckets
+            .closest_to_content_id::<TMetric>(&content_key.content_id(), FIND_CONTENT_MAX_NODES)
+            .into_iter()
+            .filter(|enr| &enr.node_id() != source)
+            .map(SszEnr)
+            .collect::<Vec<_>>();
+        pop_while_ssz_bytes_len_gt(&mut enrs, MAX_PORTAL_CONTENT_PAYLOAD_SIZE);
+        Content::Enrs(enrs)
+    }
+
     /// Attempts to build an `Accept` response for an `Offer` request.
     #[allow(clippy::result_large_err)]
     fn handle_offer(
@@ -1109,10 +1256,18 @@ impl<
         // * filling the `requested_keys` is expensive because it requires calls to disk which
         //   should be avoided.
         // so by trying to acquire the semaphore before the storage call we avoid unnecessary work
+        // The permit to open the uTP stream is also acquired before sending ACCEPT, so that the
+        // offering peer never waits for our turn to accept its connection.
         // **Note:** if we are not accepting any content `requested_keys` should be empty
-        let permit = match self.utp_controller.get_inbound_semaphore() {
-            Some(permit) => permit,
-            None => {
+        let (permit, transfer_permit) = match (
+            self.utp_controller.get_inbound_semaphore(),
+            self.utp_controller.get_transfer_permit(),
+        ) {
+            (Some(permit), Some(transfer_permit)) => (permit, transfer_permit),
+            _ => {
+                self.recent_offerers
+                    .write()
+                    .record_offer(*source, request.content_keys.len(), 0);
                 return Ok(Accept {
                     connection_id: 0,
                     content_keys: requested_keys,
@@ -1166,6 +1321,9 @@ impl<
                 ))
             })?;
         }
+        self.recent_offerers
+            .write()
+            .record_offer(*source, content_keys.len(), accepted_keys.len());
 
         // If no content keys were accepted, then return an Accept with a connection ID value of
         // zero.
@@ -1203,10 +1361,11 @@ impl<
         );
 
         let utp_processing = UtpProcessing::from(self);
+        let offered_by = *source;
         tokio::spawn(async move {
             let data = match utp_processing
                 .utp_controller
-                .accept_inbound_stream(cid.clone())
+                .accept_inbound_stream(cid.clone(), transfer_permit)
                 .await
             {
                 Ok(data) => data,
@@ -1274,6 +1433,8 @@ impl<
                         match Self::validate_and_store_content(
                             key.clone(),
                             value,
+                            ContentProvenance::Offer,
+                            offered_by,
                             utp_processing.clone(),
                         )
                         .await
@@ -1313,12 +1474,19 @@ impl<
                 })
                 .flatten()
                 .collect();
-            propagate_gossip_cross_thread::<_, TMetric>(
-                validated_content,
-                &utp_processing.kbuckets,
-                utp_processing.command_tx.clone(),
-                Some(utp_processing.utp_controller),
-            );
+            if utp_processing.gossip_switch.allow(validated_content.len()) {
+                let count = propagate_gossip_cross_thread::<_, TMetric>(
+                    validated_content,
+                    &utp_processing.kbuckets,
+                    utp_processing.command_tx.clone(),
+                    Some(utp_processing.utp_controller),
+                    &utp_processing.gossip_config,
+                    &utp_processing.large_content_gossip,
+                );
+                utp_processing
+                    .metrics
+                    .report_gossip_outcome(GossipOutcomeLabel::Skipped, count.skipped as u64);
+            }
             // explicitly drop semaphore permit in thread so the permit is moved into the thread
             drop(permit);
         });
@@ -1331,8 +1499,15 @@ impl<
         Ok(accept)
     }
 
-    /// Sends a TALK request via Discovery v5 to some destination node.
-    fn send_talk_req(&self, request: Request, request_id: OverlayRequestId, destination: Enr) {
+    /// Sends a TALK request via Discovery v5 to some destination node. The payload of the response
+    /// is sent to `payload_responder`, if any, before it is decoded.
+    fn send_talk_req(
+        &self,
+        request: Request,
+        request_id: OverlayRequestId,
+        destination: Enr,
+        payload_responder: Option<oneshot::Sender<Vec<u8>>>,
+    ) {
         let discovery = Arc::clone(&self.discovery);
         let response_tx = self.response_tx.clone();
         let protocol = self.protocol;
@@ -1340,12 +1515,36 @@ impl<
         // Spawn a new thread to send the TALK request. Otherwise we would delay processing of
         // other tasks until we receive the response. Send the response over the response channel,
         // which will be received in the main loop.
-        tokio::spawn(async move {
-            let response = match discovery
-                .send_talk_req(destination, protocol, Message::from(request).as_ssz_bytes())
-                .await
-            {
-                Ok(talk_resp) => match Message::try_from(talk_resp.to_vec()) {
+        // The responses of the peer are decoded with the message encodings of the protocol version
+        // negotiated with it.
+        let accept_codes = self
+            .kbuckets
+            .peer_supports(destination.node_id(), ProtocolFeature::AcceptCodes);
+        let send = async move {
+            let peer = destination.node_id();
+            let request = Message::from(request).as_ssz_bytes();
+            let recorded_request = wire_recorder::is_recording().then(|| request.clone());
+            let talk_resp = discovery
+                .send_talk_req(destination, protocol, request)
+                .await;
+            if let Some(request) = recorded_request {
+                wire_recorder::record(
+                    WireDirection::Outbound,
+                    protocol,
+                    peer,
+                    request,
+                    talk_resp
+                        .as_ref()
+                        .map(|talk_resp| talk_resp.to_vec())
+                        .map_err(|err| err.to_string()),
+                );
+            }
+            if let (Some(payload_responder), Ok(talk_resp)) = (payload_responder, &talk_resp) {
+                let _ = payload_responder.send(talk_resp.to_vec());
+            }
+            let response = match talk_resp {
+                Ok(talk_resp) if talk_resp.is_empty() => Err(OverlayRequestError::EmptyResponse),
+                Ok(talk_resp) => match decode_response(&talk_resp, accept_codes) {
                     Ok(message) => match Response::try_from(message) {
                         Ok(response) => Ok(response),
                         Err(_) => Err(OverlayRequestError::InvalidResponse),
@@ -1359,7 +1558,8 @@ impl<
                 request_id,
                 response,
             });
-        });
+        };
+        tokio::spawn(send.instrument(Span::current()));
     }
 
     /// Processes an incoming request from some source node.
@@ -1380,10 +1580,10 @@ impl<
         // we cannot construct a new entry for sure, because we only have the node ID, not the ENR.
         if is_node_in_table {
             match self.update_node_connection_state(source, ConnectionState::Connected) {
-                Ok(_) => {}
+                Ok(_) => self.liveness.record_activity(source),
                 Err(_) => {
                     // If the update fails, then remove the node from the ping queue.
-                    self.peers_to_ping.remove(&source);
+                    self.liveness.remove(&source);
                 }
             }
         } else {
@@ -1391,11 +1591,7 @@ impl<
             // address cache. If an entry is found, then attempt to insert the node as a connected
             // peer.
             if let Some(node_addr) = self.discovery.cached_node_addr(&source) {
-                // TODO: Decide default data radius, and define a constant.
-                let node = Node {
-                    enr: node_addr.enr,
-                    data_radius: Distance::MAX,
-                };
+                let node = Node::with_unknown_radius(node_addr.enr);
                 self.connect_node(node, ConnectionDirection::Incoming);
             }
         }
@@ -1415,9 +1611,19 @@ impl<
                 self.request_node(&node.enr());
             }
 
-            let data_radius: Distance = ping.custom_payload.into();
-            if node.data_radius != data_radius {
-                self.update_node_radius(node.enr(), data_radius);
+            match Distance::try_from(ping.custom_payload) {
+                Ok(data_radius) => {
+                    if !node.is_radius_known() || node.data_radius != data_radius {
+                        self.update_node_radius(node.enr(), data_radius);
+                    }
+                    self.rebroadcast_to_new_peer(node.enr(), data_radius);
+                }
+                Err(err) => warn!(
+                    protocol = %self.protocol,
+                    request.source = %source,
+                    error = ?err,
+                    "Invalid data radius in ping",
+                ),
             }
         }
     }
@@ -1427,6 +1633,7 @@ impl<
         &mut self,
         request_id: OverlayRequestId,
         destination: Enr,
+        request: &Request,
         error: OverlayRequestError,
     ) {
         debug!(
@@ -1437,11 +1644,34 @@ impl<
             "Request failed",
         );
 
-        // Attempt to mark the node as disconnected.
         let node_id = destination.node_id();
-        let _ = self.update_node_connection_state(node_id, ConnectionState::Disconnected);
-        // Remove the node from the ping queue.
-        self.peers_to_ping.remove(&node_id);
+        if matches!(request, Request::Ping(_)) && self.liveness.is_in_flight(&node_id) {
+            self.metrics
+                .report_liveness_ping(LivenessPingLabel::Failed, 1);
+            // The node stays connected until it fails too many consecutive liveness pings.
+            if !self.liveness.on_ping_failure(node_id) {
+                return;
+            }
+        }
+        self.disconnect_node(node_id);
+    }
+
+    /// Attempts to mark the node as disconnected, and removes it from the ping queue. The node
+    /// stays in the routing table until a newly connected node takes its place.
+    fn disconnect_node(&mut self, node_id: NodeId) {
+        let was_connected = matches!(
+            self.kbuckets.entry(node_id),
+            Entry::Present(_, status) if status.is_connected()
+        );
+        if self
+            .update_node_connection_state(node_id, ConnectionState::Disconnected)
+            .is_ok()
+            && was_connected
+        {
+            self.metrics
+                .report_peer_state_transition(PeerStateLabel::Disconnected);
+        }
+        self.liveness.remove(&node_id);
     }
 
     /// Processes a response to an outgoing request from some source node.
@@ -1461,8 +1691,7 @@ impl<
             Entry::Present(node, node_status) => (node, node_status),
             Entry::Pending(node, node_status) => (node, node_status),
             _ => {
-                // TODO: Decide default data radius, and define a constant.
-                let node = Node::new(source.clone(), Distance::MAX);
+                let node = Node::with_unknown_radius(source.clone());
                 let status = NodeStatus {
                     state: ConnectionState::Disconnected,
                     direction: ConnectionDirection::Outgoing,
@@ -1487,12 +1716,20 @@ impl<
                     Ok(_) => {}
                     Err(_) => {
                         // If the update fails, then remove the node from the ping queue.
-                        self.peers_to_ping.remove(&source.node_id());
+                        self.liveness.remove(&source.node_id());
                     }
                 }
             }
         }
 
+        // The response to a liveness ping doesn't postpone the next liveness ping, unlike the
+        // responses to other requests.
+        if matches!(request, Request::Ping(_)) && self.liveness.is_in_flight(&source.node_id()) {
+            self.liveness.on_ping_success(source.node_id());
+        } else {
+            self.liveness.record_activity(source.node_id());
+        }
+
         match response {
             Response::Pong(pong) => self.process_pong(pong, source),
             Response::Nodes(nodes) => self.process_nodes(nodes, source, query_id),
@@ -1515,16 +1752,30 @@ impl<
     ) -> anyhow::Result<Accept> {
         // Check that a valid triggering request was sent
         let mut gossip_result_tx = None;
-        match &offer {
-            Request::Offer(_) => {}
-            Request::PopulatedOffer(_) => {}
+        let is_gossip = match &offer {
+            Request::Offer(_) => false,
+            Request::PopulatedOffer(_) => true,
             Request::PopulatedOfferWithResult(req) => {
-                gossip_result_tx = Some(req.result_tx.clone())
+                gossip_result_tx = Some(req.result_tx.clone());
+                true
             }
             _ => {
                 return Err(anyhow!("Invalid request message paired with ACCEPT"));
             }
         };
+        if is_gossip {
+            debug!(
+                protocol = %self.protocol,
+                peer = %enr.node_id(),
+                accepted = !response.content_keys.is_zero(),
+                "Gossip offer answered"
+            );
+            let outcome = match response.content_keys.is_zero() {
+                true => GossipOutcomeLabel::Declined,
+                false => GossipOutcomeLabel::Accepted,
+            };
+            self.metrics.report_gossip_outcome(outcome, 1);
+        }
 
         // Do not initialize uTP stream if remote node doesn't have interest in the offered content
         // keys
@@ -1546,7 +1797,7 @@ impl<
         let response_clone = response.clone();
 
         let utp_controller = Arc::clone(&self.utp_controller);
-        tokio::spawn(async move {
+        let transfer = async move {
             let content_items = match offer {
                 Request::Offer(offer) => {
                     Self::provide_requested_content(store, &response_clone, offer.content_keys)
@@ -1613,7 +1864,8 @@ impl<
             if let Some(permit) = request_permit {
                 drop(permit);
             }
-        });
+        };
+        tokio::spawn(transfer.instrument(Span::current()));
 
         Ok(response)
     }
@@ -1627,6 +1879,8 @@ impl<
     async fn validate_and_store_content(
         key: TContentKey,
         content_value: RawContentValue,
+        provenance: ContentProvenance,
+        offered_by: NodeId,
         utp_processing: UtpProcessing<TValidator, TStore, TContentKey>,
     ) -> Option<Vec<(TContentKey, RawContentValue)>> {
         // Validate received content
@@ -1677,12 +1931,14 @@ impl<
             .is_key_within_radius_and_unavailable(&key);
         match key_desired {
             Ok(ShouldWeStoreContent::Store) => {
-                match utp_processing
-                    .store
-                    .write()
-                    .put(key.clone(), &content_value)
-                {
+                match utp_processing.store.write().put_with_meta(
+                    key.clone(),
+                    &content_value,
+                    provenance,
+                    Some(offered_by),
+                ) {
                     Ok(dropped_content) => {
+                        utp_processing.invalidate_failed_lookup(&key);
                         if !dropped_content.is_empty() && utp_processing.gossip_dropped {
                             // add dropped content to validation result, so it will be propagated
                             debug!("Dropped {:?} pieces of content after inserting new content, propagating them back into the network.", dropped_content.len());
@@ -1778,6 +2034,8 @@ impl<
         let validated_content = match Self::validate_and_store_content(
             content_key,
             data,
+            ContentProvenance::OfferFallback,
+            fallback_peer.node_id(),
             utp_processing.clone(),
         )
         .await
@@ -1789,12 +2047,19 @@ impl<
             }
         };
 
-        propagate_gossip_cross_thread::<_, TMetric>(
-            validated_content,
-            &utp_processing.kbuckets,
-            utp_processing.command_tx.clone(),
-            Some(utp_processing.utp_controller),
-        );
+        if utp_processing.gossip_switch.allow(validated_content.len()) {
+            let count = propagate_gossip_cross_thread::<_, TMetric>(
+                validated_content,
+                &utp_processing.kbuckets,
+                utp_processing.command_tx.clone(),
+                Some(utp_processing.utp_controller),
+                &utp_processing.gossip_config,
+                &utp_processing.large_content_gossip,
+            );
+            utp_processing
+                .metrics
+                .report_gossip_outcome(GossipOutcomeLabel::Skipped, count.skipped as u64);
+        }
         Ok(())
     }
 
@@ -1818,13 +2083,94 @@ impl<
                 self.request_node(&node.enr());
             }
 
-            let data_radius: Distance = pong.custom_payload.into();
-            if node.data_radius != data_radius {
-                self.update_node_radius(source, data_radius);
+            match Distance::try_from(pong.custom_payload) {
+                Ok(data_radius) => {
+                    if !node.is_radius_known() || node.data_radius != data_radius {
+                        self.update_node_radius(source, data_radius);
+                    }
+                    self.rebroadcast_to_new_peer(node.enr(), data_radius);
+                }
+                Err(err) => warn!(
+                    protocol = %self.protocol,
+                    response.source = %node_id,
+                    error = ?err,
+                    "Invalid data radius in pong",
+                ),
             }
         }
     }
 
+    /// Offers the stored content within its radius to a peer newly inserted into the routing
+    /// table, if the rebroadcast to new peers is enabled.
+    ///
+    /// This is done once the radius of the peer is known, so that content stored before the peer
+    /// joined the network still reaches it.
+    fn rebroadcast_to_new_peer(&self, enr: Enr, data_radius: Distance) {
+        // The peer stays new while gossip is paused, so it's rebroadcast to once gossip resumes.
+        if !self.gossip_switch.is_enabled() {
+            return;
+        }
+        let node_id = enr.node_id();
+        let Some(offers_per_peer) = self.pokes.lock().take_new_peer(&node_id) else {
+            return;
+        };
+        let limit = offers_per_peer.min(MAX_CONTENT_KEYS_PER_OFFER);
+        let entries = match self
+            .store
+            .read()
+            .entries_within_radius(&node_id, data_radius, limit)
+        {
+            Ok(entries) => entries,
+            Err(err) => {
+                warn!(
+                    protocol = %self.protocol,
+                    peer.node_id = %node_id,
+                    %err,
+                    "Failed to look up content to rebroadcast to new peer",
+                );
+                return;
+            }
+        };
+        if entries.is_empty() {
+            return;
+        }
+        let Some(permit) = self.utp_controller.get_outbound_semaphore() else {
+            return;
+        };
+
+        let content_items: Vec<(RawContentKey, RawContentValue)> = {
+            let mut pokes = self.pokes.lock();
+            entries
+                .into_iter()
+                .filter(|(content_key, _)| {
+                    let outcome =
+                        pokes.poke(node_id, content_key.content_id(), PokeTriggerLabel::NewPeer);
+                    self.metrics.report_poke(PokeTriggerLabel::NewPeer, outcome);
+                    outcome == PokeOutcomeLabel::Offered
+                })
+                .map(|(content_key, content)| (content_key.to_bytes(), content))
+                .collect()
+        };
+        if content_items.is_empty() {
+            return;
+        }
+
+        debug!(
+            protocol = %self.protocol,
+            peer.node_id = %node_id,
+            "Rebroadcasting {} stored content items to new peer",
+            content_items.len(),
+        );
+        let request = OverlayRequest::new(
+            Request::PopulatedOffer(PopulatedOffer { content_items }),
+            RequestDirection::Outgoing { destination: enr },
+            None,
+            None,
+            Some(permit),
+        );
+        let _ = self.command_tx.send(OverlayCommand::Request(request));
+    }
+
     /// Update the recorded radius of a node in our routing table.
     fn update_node_radius(&self, enr: Enr, data_radius: Distance) {
         let node_id = enr.node_id();
@@ -1955,12 +2301,14 @@ impl<
                         |val| matches!(val, ShouldWeStoreContent::Store),
                     );
             if should_store {
-                match utp_processing
-                    .store
-                    .write()
-                    .put(content_key.clone(), content.clone())
-                {
+                match utp_processing.store.write().put_with_meta(
+                    content_key.clone(),
+                    content.clone(),
+                    ContentProvenance::FoundViaLookup,
+                    /* offered_by= */ None,
+                ) {
                     Ok(dropped_content) => {
+                        utp_processing.invalidate_failed_lookup(&content_key);
                         let mut content_to_propagate = vec![(content_key.clone(), content.clone())];
                         if let Some(additional_content_to_propagate) =
                             validation_result.additional_content_to_propagate
@@ -1974,12 +2322,23 @@ impl<
                             );
                             content_to_propagate.extend(dropped_content.clone());
                         }
-                        propagate_gossip_cross_thread::<_, TMetric>(
-                            content_to_propagate,
-                            &utp_processing.kbuckets,
-                            utp_processing.command_tx.clone(),
-                            Some(utp_processing.utp_controller.clone()),
-                        );
+                        if utp_processing
+                            .gossip_switch
+                            .allow(content_to_propagate.len())
+                        {
+                            let count = propagate_gossip_cross_thread::<_, TMetric>(
+                                content_to_propagate,
+                                &utp_processing.kbuckets,
+                                utp_processing.command_tx.clone(),
+                                Some(utp_processing.utp_controller.clone()),
+                                &utp_processing.gossip_config,
+                                &utp_processing.large_content_gossip,
+                            );
+                            utp_processing.metrics.report_gossip_outcome(
+                                GossipOutcomeLabel::Skipped,
+                                count.skipped as u64,
+                            );
+                        }
                     }
                     Err(err) => error!(
                         error = %err,
@@ -2002,7 +2361,7 @@ impl<
             warn!("The content query has exited before the returned content could be marked as valid. Perhaps a timeout, or a parallel copy of the content was validated first.");
         }
 
-        if !utp_processing.disable_poke {
+        if !utp_processing.disable_poke && utp_processing.gossip_switch.allow(1) {
             Self::poke_content(
                 &utp_processing.kbuckets,
                 utp_processing.command_tx,
@@ -2010,6 +2369,8 @@ impl<
                 content,
                 nodes_to_poke,
                 utp_processing.utp_controller,
+                &utp_processing.pokes,
+                &utp_processing.metrics,
             );
         }
     }
@@ -2029,10 +2390,10 @@ impl<
         } = self.kbuckets.insert_or_update_discovered_nodes(enrs);
 
         for node_id in inserted_nodes {
-            self.peers_to_ping.insert(node_id);
+            self.liveness.insert(node_id);
         }
         for node_id in removed_nodes {
-            self.peers_to_ping.remove(&node_id);
+            self.liveness.remove(&node_id);
         }
     }
 
@@ -2179,6 +2540,41 @@ impl<
         }
     }
 
+    /// Pings the peers that are due for a liveness check.
+    fn ping_due_peers(&mut self) {
+        let DuePings { to_ping, skipped } = self.liveness.due_pings();
+        self.metrics
+            .report_liveness_ping(LivenessPingLabel::Skipped, skipped as u64);
+        for node_id in to_ping {
+            match self.kbuckets.entry(node_id).present() {
+                Some(node) => {
+                    self.ping_node(&node.enr);
+                    self.metrics
+                        .report_liveness_ping(LivenessPingLabel::Sent, 1);
+                }
+                None => self.liveness.remove(&node_id),
+            }
+        }
+    }
+
+    /// Pings a sample of the routing table peers after the local ENR changed, so that they request
+    /// the new ENR.
+    fn ping_peers_on_enr_update(&self) {
+        let peers = self
+            .kbuckets
+            .enrs()
+            .into_iter()
+            .choose_multiple(&mut rand::thread_rng(), ADDRESS_CHANGE_PING_SAMPLE);
+        debug!(
+            protocol = %self.protocol,
+            peers = peers.len(),
+            "Local ENR changed, pinging a sample of the routing table",
+        );
+        for enr in peers {
+            self.ping_node(&enr);
+        }
+    }
+
     /// Submits a request to ping a destination (target) node.
     ///
     /// This can block the thread, so make sure you are not holding any lock while calling this.
@@ -2240,7 +2636,10 @@ impl<
                     "Node inserted into routing table",
                 );
 
-                self.peers_to_ping.insert(node_id);
+                self.liveness.insert(node_id);
+                self.pokes.lock().add_new_peer(node_id);
+                self.metrics
+                    .report_peer_state_transition(PeerStateLabel::Connected);
             }
             InsertResult::Pending { disconnected } => {
                 // The disconnected node is the least-recently connected entry that is
@@ -2263,12 +2662,14 @@ impl<
                         promoted = %node_id,
                         "Node promoted to connected",
                     );
-                    self.peers_to_ping.insert(node_id);
+                    self.liveness.insert(node_id);
+                    self.metrics
+                        .report_peer_state_transition(PeerStateLabel::Connected);
                 }
             }
             InsertResult::ValueUpdated | InsertResult::UpdatedPending => {}
             InsertResult::Failed(reason) => {
-                self.peers_to_ping.remove(&node_id);
+                self.liveness.remove(&node_id);
                 debug!(
                     protocol = %self.protocol,
                     peer = %node_id,
@@ -2309,11 +2710,13 @@ impl<
         }
     }
 
-    /// Starts a FindNode query to find nodes with IDs closest to `target`.
+    /// Starts a FindNode query to find nodes with IDs closest to `target`. If the query refreshes
+    /// a routing table bucket, the bucket is recorded as refreshed once the query completes.
     fn init_find_nodes_query(
         &mut self,
         target: &NodeId,
         callback: Option<oneshot::Sender<Vec<Enr>>>,
+        refreshed_bucket: Option<usize>,
     ) -> Option<QueryId> {
         let closest_enrs = self
             .kbuckets
@@ -2339,6 +2742,7 @@ impl<
                 target: *target,
                 distances_to_request: self.findnodes_query_distances_per_peer,
                 callback,
+                refreshed_bucket,
             },
             untrusted_enrs: SmallVec::from_vec(closest_enrs),
             trace: None,
@@ -2395,6 +2799,8 @@ impl<
                         .to_string(),
                     utp: false,
                     trace: None,
+                    closest_enrs: vec![],
+                    cached_at_ms: None,
                 }));
             }
             return None;
@@ -2418,7 +2824,11 @@ impl<
         };
 
         let query_info = QueryInfo {
-            query_type: QueryType::FindContent { target, callback },
+            query_type: QueryType::FindContent {
+                target,
+                callback,
+                span: Span::current(),
+            },
             untrusted_enrs: SmallVec::from_vec(closest_enrs),
             trace,
         };
@@ -2427,6 +2837,32 @@ impl<
         Some(self.find_content_query_pool.add_query(query_info, query))
     }
 
+    /// Returns the `FindContentQuery`s that are currently running, including the ones whose
+    /// returned content is being transferred over uTP or validated. A query that hasn't been
+    /// polled yet is reported as just started.
+    fn active_lookups(&self) -> Vec<ActiveLookupInfo> {
+        let lookup_stages = self.lookup_stages.lock();
+        self.find_content_query_pool
+            .iter()
+            .filter_map(
+                |(query_id, (query_info, query))| match &query_info.query_type {
+                    QueryType::FindContent { target, .. } => Some(ActiveLookupInfo {
+                        content_id: target.content_id().into(),
+                        started_ms_ago: query
+                            .started()
+                            .map_or(0, |started| started.elapsed().as_millis() as u64),
+                        peers_contacted: query.num_contacted_peers() as u64,
+                        stage: lookup_stages
+                            .get(query_id)
+                            .copied()
+                            .unwrap_or(LookupStage::Querying),
+                    }),
+                    QueryType::FindNode { .. } => None,
+                },
+            )
+            .collect()
+    }
+
     /// Returns an ENR if one is known for the given NodeId.
     pub fn find_enr(&self, node_id: &NodeId) -> Option<Enr> {
         // Check whether we know this node id in our X's Portal Network's routing table.
@@ -2447,7 +2883,7 @@ impl<
         }
 
         // Check the existing find node queries for the ENR.
-        for (query_info, _) in self.find_node_query_pool.iter() {
+        for (_, (query_info, _)) in self.find_node_query_pool.iter() {
             if let Some(enr) = query_info
                 .untrusted_enrs
                 .iter()
@@ -2459,7 +2895,7 @@ impl<
         }
 
         // Check the existing find content queries for the ENR.
-        for (query_info, _) in self.find_content_query_pool.iter() {
+        for (_, (query_info, _)) in self.find_content_query_pool.iter() {
             if let Some(enr) = query_info
                 .untrusted_enrs
                 .iter()
@@ -2474,8 +2910,30 @@ impl<
         None
     }
 
+    /// Emits a `RadiusChanged` event for each change of the radius of the store since the last
+    /// emitted one. The store records the changes whenever it recomputes its radius, so that it
+    /// doesn't have to emit them while it's locked.
+    fn emit_radius_changes(&mut self) {
+        let changes: Vec<RadiusChange> = match self.store.read().radius_history() {
+            Some(history) => history
+                .changes_after(self.last_radius_change)
+                .copied()
+                .collect(),
+            None => return,
+        };
+        for change in changes {
+            self.last_radius_change = change.seq;
+            self.send_event(
+                OverlayEvent::RadiusChanged {
+                    old_radius: change.old_radius,
+                    new_radius: change.new_radius,
+                },
+                Some(vec![self.protocol]),
+            );
+        }
+    }
+
     /// Send `OverlayEvent` to the event stream.
-    #[allow(dead_code)] // TODO: remove when used
     fn send_event(&self, event: OverlayEvent, to: Option<Vec<Subnetwork>>) {
         trace!(
             "Sending event={:?} to event-stream from protocol {}",
@@ -2536,6 +2994,11 @@ where
     accept_queue: Arc<RwLock<AcceptQueue<TContentKey>>>,
     disable_poke: bool,
     gossip_dropped: bool,
+    gossip_config: GossipConfig,
+    large_content_gossip: LargeContentGossip,
+    pokes: Arc<Mutex<PokeTracker>>,
+    gossip_switch: Arc<GossipSwitch>,
+    failed_lookups: Option<Arc<Mutex<FailedLookupCache>>>,
 }
 
 impl<TContentKey, TMetric, TValidator, TStore>
@@ -2557,6 +3020,11 @@ where
             accept_queue: Arc::clone(&service.accept_queue),
             disable_poke: service.disable_poke,
             gossip_dropped: service.gossip_dropped,
+            gossip_config: service.gossip_config,
+            large_content_gossip: service.large_content_gossip.clone(),
+            pokes: Arc::clone(&service.pokes),
+            gossip_switch: Arc::clone(&service.gossip_switch),
+            failed_lookups: service.failed_lookups.clone(),
         }
     }
 }
@@ -2578,6 +3046,25 @@ where
             accept_queue: Arc::clone(&self.accept_queue),
             disable_poke: self.disable_poke,
             gossip_dropped: self.gossip_dropped,
+            gossip_config: self.gossip_config,
+            large_content_gossip: self.large_content_gossip.clone(),
+            pokes: Arc::clone(&self.pokes),
+            gossip_switch: Arc::clone(&self.gossip_switch),
+            failed_lookups: self.failed_lookups.clone(),
+        }
+    }
+}
+
+impl<TValidator, TStore, TContentKey> UtpProcessing<TValidator, TStore, TContentKey>
+where
+    TContentKey: OverlayContentKey + Send + Sync,
+    TValidator: Validator<TContentKey>,
+    TStore: ContentStore<Key = TContentKey>,
+{
+    /// Removes the failure of the last lookup of stored content from the failed lookup cache.
+    fn invalidate_failed_lookup(&self, content_key: &TContentKey) {
+        if let Some(failed_lookups) = &self.failed_lookups {
+            failed_lookups.lock().invalidate(&content_key.content_id());
         }
     }
 }
@@ -2633,6 +3120,7 @@ mod tests {
     use crate::{
         config::PortalnetConfig,
         discovery::{Discovery, NodeAddress},
+        liveness::MAX_FAILED_PINGS,
         overlay::config::OverlayConfig,
     };
 
@@ -2687,13 +3175,14 @@ mod tests {
 
         let protocol = Subnetwork::History;
         let active_outgoing_requests = Arc::new(RwLock::new(HashMap::new()));
-        let peers_to_ping = HashSetDelay::default();
+        let liveness = LivenessTracker::new(DEFAULT_PING_INTERVAL);
         let (command_tx, command_rx) = mpsc::unbounded_channel();
         let (response_tx, response_rx) = mpsc::unbounded_channel();
         let (content_query_trace_events_tx, content_query_trace_events_rx) =
             mpsc::unbounded_channel();
         let validator = Arc::new(MockValidator {});
         let accept_queue = Arc::new(RwLock::new(AcceptQueue::default()));
+        let large_content_gossip = LargeContentGossip::new(metrics.clone());
 
         OverlayService {
             discovery,
@@ -2701,7 +3190,7 @@ mod tests {
             store,
             kbuckets,
             protocol,
-            peers_to_ping,
+            liveness,
             command_tx,
             command_rx,
             active_outgoing_requests,
@@ -2721,9 +3210,17 @@ mod tests {
             metrics,
             validator,
             event_stream: broadcast::channel(EVENT_STREAM_CHANNEL_CAPACITY).0,
+            last_radius_change: 0,
             disable_poke: false,
             gossip_dropped: false,
+            gossip_config: GossipConfig::default(),
+            large_content_gossip,
             accept_queue,
+            recent_offerers: Arc::new(RwLock::new(RecentOfferers::default())),
+            pokes: Arc::new(Mutex::new(PokeTracker::new(None))),
+            gossip_switch: Arc::new(GossipSwitch::default()),
+            failed_lookups: None,
+            lookup_stages: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -2814,17 +3311,18 @@ mod tests {
         let node = Node::new(destination.clone(), Distance::MAX);
 
         let _ = service.kbuckets.insert_or_update(node, status);
-        service.peers_to_ping.insert(node_id);
+        service.liveness.insert(node_id);
 
-        assert!(service.peers_to_ping.contains_key(&node_id));
+        assert!(service.liveness.contains(&node_id));
 
         assert!(service.kbuckets.entry(node_id).present().is_some());
 
         let request_id = rand::random();
         let error = OverlayRequestError::Timeout;
-        service.process_request_failure(request_id, destination, error);
+        let request = Request::FindNodes(FindNodes { distances: vec![0] });
+        service.process_request_failure(request_id, destination, &request, error);
 
-        assert!(!service.peers_to_ping.contains_key(&node_id));
+        assert!(!service.liveness.contains(&node_id));
 
         match service.kbuckets.entry(node_id) {
             Entry::Present(_, status) => {
@@ -2834,6 +3332,52 @@ mod tests {
         };
     }
 
+    #[test_log::test(tokio::test)]
+    #[serial]
+    async fn failed_liveness_pings_disconnect_node() {
+        let mut service = task::spawn(build_service());
+        // Every peer is due for a liveness ping right away.
+        service.liveness = LivenessTracker::new(Duration::ZERO);
+
+        let (_, destination) = generate_random_remote_enr();
+        let node_id = destination.node_id();
+        let status = NodeStatus {
+            state: ConnectionState::Connected,
+            direction: ConnectionDirection::Outgoing,
+        };
+        let node = Node::new(destination.clone(), Distance::MAX);
+        let _ = service.kbuckets.insert_or_update(node, status);
+        service.liveness.insert(node_id);
+
+        let ping = Request::Ping(Ping {
+            enr_seq: 1,
+            custom_payload: CustomPayload::from(Distance::MAX.as_ssz_bytes()),
+        });
+        for failed_pings in 1..=MAX_FAILED_PINGS {
+            service.ping_due_peers();
+            assert!(service.liveness.is_in_flight(&node_id));
+            service.process_request_failure(
+                rand::random(),
+                destination.clone(),
+                &ping,
+                OverlayRequestError::Timeout,
+            );
+
+            // The node stays connected until it fails too many consecutive pings.
+            let expected_state = if failed_pings < MAX_FAILED_PINGS {
+                ConnectionState::Connected
+            } else {
+                ConnectionState::Disconnected
+            };
+            match service.kbuckets.entry(node_id) {
+                Entry::Present(_, status) => assert_eq!(expected_state, status.state),
+                _ => panic!(),
+            };
+        }
+        assert!(!service.liveness.contains(&node_id));
+        assert_eq!(service.kbuckets.disconnected_node_ids(), vec![node_id]);
+    }
+
     #[test_log::test(tokio::test)]
     #[serial]
     async fn process_pong_source_in_table_higher_enr_seq() {
@@ -2920,7 +3464,7 @@ mod tests {
 
         // Check ping queue for local ENR.
         // Ping queue should be empty.
-        assert!(service.peers_to_ping.is_empty());
+        assert!(service.liveness.is_empty());
     }
 
     #[test_log::test(tokio::test)]
@@ -2957,11 +3501,11 @@ mod tests {
 
         // Check ping queue for first ENR.
         // Key for node should be present.
-        assert!(service.peers_to_ping.contains_key(&enr1.node_id()));
+        assert!(service.liveness.contains(&enr1.node_id()));
 
         // Check ping queue for second ENR.
         // Key for node should be present.
-        assert!(service.peers_to_ping.contains_key(&enr2.node_id()));
+        assert!(service.liveness.contains(&enr2.node_id()));
     }
 
     #[test_log::test(tokio::test)]
@@ -3041,6 +3585,8 @@ mod tests {
             content,
             peer_node_ids,
             service.utp_controller.clone(),
+            &service.pokes,
+            &service.metrics,
         );
         let cmd = assert_ready!(poll_command_rx!(service));
         let cmd = cmd.unwrap();
@@ -3058,6 +3604,140 @@ mod tests {
         assert_pending!(poll_command_rx!(service));
     }
 
+    #[test_log::test(tokio::test)]
+    #[serial]
+    async fn poke_content_once_per_peer() {
+        let mut service = task::spawn(build_service());
+
+        let content_key = IdentityContentKey::new(service.local_enr().node_id().raw());
+        let content = RawContentValue::from_str("0xef").unwrap();
+
+        let status = NodeStatus {
+            state: ConnectionState::Connected,
+            direction: ConnectionDirection::Outgoing,
+        };
+        let (_, enr) = generate_random_remote_enr();
+        let peer = Node::new(enr.clone(), Distance::MAX);
+        let _ = service.kbuckets.insert_or_update(peer.clone(), status);
+
+        // The content is only offered by the first poke, the peer was already offered it by the
+        // second.
+        for _ in 0..2 {
+            OverlayService::<IdentityContentKey, XorMetric, MockValidator, MemoryContentStore>::poke_content(
+                &service.kbuckets,
+                service.command_tx.clone(),
+                content_key.clone(),
+                content.clone(),
+                vec![peer.enr.node_id()],
+                service.utp_controller.clone(),
+                &service.pokes,
+                &service.metrics,
+            );
+        }
+        let cmd = assert_ready!(poll_command_rx!(service));
+        assert!(matches!(
+            cmd,
+            Some(OverlayCommand::Request(OverlayRequest {
+                request: Request::PopulatedOffer { .. },
+                ..
+            }))
+        ));
+        assert_pending!(poll_command_rx!(service));
+    }
+
+    #[test_log::test(tokio::test)]
+    #[serial]
+    async fn rebroadcast_to_new_peer_once() {
+        let mut service = task::spawn(build_service());
+        service.pokes = Arc::new(Mutex::new(PokeTracker::new(Some(RebroadcastConfig {
+            offers_per_peer: 4,
+            offers_per_minute: 64,
+        }))));
+
+        let content_key = IdentityContentKey::new(service.local_enr().node_id().raw());
+        let content = RawContentValue::from_str("0xef").unwrap();
+        service
+            .store
+            .write()
+            .put(content_key.clone(), &content)
+            .unwrap();
+
+        let (_, enr) = generate_random_remote_enr();
+        service.connect_node(
+            Node::new(enr.clone(), Distance::MAX),
+            ConnectionDirection::Outgoing,
+        );
+        let pong = Pong {
+            enr_seq: enr.seq(),
+            custom_payload: CustomPayload::from(Distance::MAX.as_ssz_bytes()),
+        };
+
+        // The stored content is offered once the radius of the new peer is known, and only once.
+        service.process_pong(pong.clone(), enr.clone());
+        let cmd = assert_ready!(poll_command_rx!(service));
+        match cmd {
+            Some(OverlayCommand::Request(OverlayRequest {
+                request: Request::PopulatedOffer(offer),
+                direction: RequestDirection::Outgoing { destination },
+                ..
+            })) => {
+                assert_eq!(destination, enr);
+                assert_eq!(offer.content_items, vec![(content_key.to_bytes(), content)]);
+            }
+            _ => panic!("Unexpected overlay command"),
+        }
+        service.process_pong(pong, enr);
+        assert_pending!(poll_command_rx!(service));
+    }
+
+    #[test_log::test(tokio::test)]
+    #[serial]
+    async fn rebroadcast_to_new_peer_once_gossip_resumes() {
+        let mut service = task::spawn(build_service());
+        service.pokes = Arc::new(Mutex::new(PokeTracker::new(Some(RebroadcastConfig {
+            offers_per_peer: 4,
+            offers_per_minute: 64,
+        }))));
+
+        let content_key = IdentityContentKey::new(service.local_enr().node_id().raw());
+        let content = RawContentValue::from_str("0xef").unwrap();
+        service
+            .store
+            .write()
+            .put(content_key.clone(), &content)
+            .unwrap();
+
+        let (_, enr) = generate_random_remote_enr();
+        service.connect_node(
+            Node::new(enr.clone(), Distance::MAX),
+            ConnectionDirection::Outgoing,
+        );
+        let pong = Pong {
+            enr_seq: enr.seq(),
+            custom_payload: CustomPayload::from(Distance::MAX.as_ssz_bytes()),
+        };
+
+        // Nothing is offered while gossip is paused.
+        service.gossip_switch.set_enabled(false);
+        service.process_pong(pong.clone(), enr.clone());
+        assert_pending!(poll_command_rx!(service));
+
+        service.gossip_switch.set_enabled(true);
+        service.process_pong(pong, enr.clone());
+        let cmd = assert_ready!(poll_command_rx!(service));
+        match cmd {
+            Some(OverlayCommand::Request(OverlayRequest {
+                request: Request::PopulatedOffer(offer),
+                direction: RequestDirection::Outgoing { destination },
+                ..
+            })) => {
+                assert_eq!(destination, enr);
+                assert_eq!(offer.content_items, vec![(content_key.to_bytes(), content)]);
+            }
+            _ => panic!("Unexpected overlay command"),
+        }
+    }
+
     #[test_log::test(tokio::test)]
     #[serial]
     async fn poke_content_unknown_peers() {
@@ -3079,6 +3759,8 @@ mod tests {
             content,
             peer_node_ids,
             service.utp_controller.clone(),
+            &service.pokes,
+            &service.metrics,
         );
         assert_pending!(poll_command_rx!(service));
     }
@@ -3117,6 +3799,8 @@ mod tests {
             content,
             peer_node_ids,
             service.utp_controller.clone(),
+            &service.pokes,
+            &service.metrics,
         );
         let cmd = assert_ready!(poll_command_rx!(service));
         let cmd = cmd.unwrap();
@@ -3209,12 +3893,12 @@ mod tests {
         let node = Node::new(enr, data_radius);
         let connection_direction = ConnectionDirection::Outgoing;
 
-        assert!(!service.peers_to_ping.contains_key(&node_id));
+        assert!(!service.liveness.contains(&node_id));
         assert!(matches!(service.kbuckets.entry(node_id), Entry::Absent));
 
         service.connect_node(node, connection_direction);
 
-        assert!(service.peers_to_ping.contains_key(&node_id));
+        assert!(service.liveness.contains(&node_id));
 
         match service.kbuckets.entry(node_id) {
             Entry::Present(_node, status) => {
@@ -3339,7 +4023,7 @@ mod tests {
         service.add_bootnodes(bootnodes, true);
 
         // Initialize the query and call `poll` so that it starts
-        service.init_find_nodes_query(&target_node_id, None);
+        service.init_find_nodes_query(&target_node_id, None, None);
         let _ = service.find_node_query_pool.poll();
 
         let expected_distances_per_peer = service.findnodes_query_distances_per_peer;
@@ -3376,7 +4060,7 @@ mod tests {
 
         service.add_bootnodes(bootnodes, true);
         service.query_num_results = 3;
-        service.init_find_nodes_query(&target_node_id, None);
+        service.init_find_nodes_query(&target_node_id, None, None);
 
         // Test that the first query event contains a proper query ID and request to the bootnode
         let event = OverlayService::<
@@ -3484,6 +4168,44 @@ mod tests {
         }
     }
 
+    #[test_log::test(tokio::test)]
+    async fn bucket_refresh_is_recorded_when_the_query_completes() {
+        let mut service = build_service();
+        let (_, bootnode) = generate_random_remote_enr();
+        service.add_bootnodes(vec![bootnode.clone()], true);
+
+        let bucket = 250;
+        let target_node_id = generate_random_node_id(bucket as u8, service.local_enr().into());
+        service.init_find_nodes_query(&target_node_id, None, Some(bucket));
+        let refreshed = |kbuckets: &SharedKBucketsTable| {
+            kbuckets.bucket_refresh_times().buckets.iter().any(|info| {
+                info.log2_distance == bucket as u16 + 1 && info.last_refresh_secs_ago.is_some()
+            })
+        };
+
+        let event = OverlayService::<
+            IdentityContentKey,
+            XorMetric,
+            MockValidator,
+            MemoryContentStore,
+        >::query_event_poll(&mut service.find_node_query_pool)
+        .await;
+        assert!(matches!(event, QueryEvent::Waiting(..)));
+        service.advance_find_node_query(bootnode, vec![], QueryId(0));
+        assert!(!refreshed(&service.kbuckets));
+
+        let event = OverlayService::<
+            IdentityContentKey,
+            XorMetric,
+            MockValidator,
+            MemoryContentStore,
+        >::query_event_poll(&mut service.find_node_query_pool)
+        .await;
+        assert!(matches!(event, QueryEvent::Finished(..)));
+        service.handle_find_nodes_query_event(event);
+        assert!(refreshed(&service.kbuckets));
+    }
+
     #[test_log::test(tokio::test)]
     async fn test_find_enrs() {
         let mut service = task::spawn(build_service());
@@ -3497,7 +4219,7 @@ mod tests {
 
         service.add_bootnodes(bootnodes, true);
 
-        service.init_find_nodes_query(&target_node_id, None);
+        service.init_find_nodes_query(&target_node_id, None, None);
 
         let _event = OverlayService::<
             IdentityContentKey,
@@ -3549,10 +4271,7 @@ mod tests {
         let (_, bootnode_enr) = generate_random_remote_enr();
 
         let data_radius = Distance::MAX;
-        let bootnode = Node {
-            enr: bootnode_enr.clone(),
-            data_radius,
-        };
+        let bootnode = Node::new(bootnode_enr.clone(), data_radius);
 
         let connection_direction = ConnectionDirection::Outgoing;
         let status = NodeStatus {
@@ -3583,6 +4302,7 @@ mod tests {
             QueryType::FindContent {
                 target: _target_content_key,
                 callback: None,
+                ..
             }
         ));
 
@@ -3595,6 +4315,59 @@ mod tests {
         assert!(query_info.untrusted_enrs.contains(&bootnode_enr));
     }
 
+    #[tokio::test]
+    async fn active_lookups() {
+        let mut service = task::spawn(build_service());
+
+        let (_, bootnode_enr) = generate_random_remote_enr();
+        let bootnode = Node::new(bootnode_enr, Distance::MAX);
+        let status = NodeStatus {
+            state: ConnectionState::Connected,
+            direction: ConnectionDirection::Outgoing,
+        };
+        let _ = service.kbuckets.insert_or_update(bootnode, status);
+        assert!(service.active_lookups().is_empty());
+
+        let target_content_key = IdentityContentKey::new(NodeId::random().raw());
+        let query_id = service
+            .init_find_content_query(
+                target_content_key.clone(),
+                None,
+                FindContentConfig::default(),
+            )
+            .expect("Query ID for new find content query is `None`");
+        assert_eq!(
+            service.active_lookups(),
+            vec![ActiveLookupInfo {
+                content_id: target_content_key.content_id().into(),
+                started_ms_ago: 0,
+                peers_contacted: 0,
+                stage: LookupStage::Querying,
+            }]
+        );
+
+        // Polling the query sends a request to the bootnode.
+        let (_, query) = service
+            .find_content_query_pool
+            .get_mut(query_id)
+            .expect("Query pool does not contain query");
+        let now = Instant::now();
+        query.start(now);
+        query.poll(now);
+        let active_lookups = service.active_lookups();
+        assert_eq!(active_lookups.len(), 1);
+        assert_eq!(active_lookups[0].peers_contacted, 1);
+
+        // The lookup stays listed while the content that a peer returned is transferred and
+        // validated.
+        for stage in [LookupStage::UtpTransfer, LookupStage::Validating] {
+            service.lookup_stages.lock().insert(query_id, stage);
+            let active_lookups = service.active_lookups();
+            assert_eq!(active_lookups.len(), 1);
+            assert_eq!(active_lookups[0].stage, stage);
+        }
+    }
+
     #[tokio::test]
     async fn test_find_content_no_nodes() {
         let mut service = task::spawn(build_service());
@@ -3619,10 +4392,7 @@ mod tests {
         let (_, bootnode_enr) = generate_random_remote_enr();
 
         let data_radius = Distance::MAX;
-        let bootnode = Node {
-            enr: bootnode_enr.clone(),
-            data_radius,
-        };
+        let bootnode = Node::new(bootnode_enr.clone(), data_radius);
 
         let connection_direction = ConnectionDirection::Outgoing;
         let status = NodeStatus {
@@ -3666,12 +4436,62 @@ mod tests {
         // Query info should contain the "discovered" ENR.
         assert!(query_info.untrusted_enrs.contains(&enr));
 
+        // The bootnode responded without content, so it's the closest peer of the result.
         match query.clone().into_result() {
-            FindContentQueryResult::NoneFound => {}
+            FindContentQueryResult::NoneFound(closest_peers) => {
+                assert_eq!(closest_peers, vec![bootnode_enr.node_id()]);
+            }
             _ => panic!("Unexpected find content query result"),
         }
     }
 
+    #[tokio::test]
+    async fn find_content_query_none_found_returns_closest_enrs() {
+        let mut service = task::spawn(build_service());
+
+        let (_, bootnode_enr) = generate_random_remote_enr();
+        let bootnode = Node::new(bootnode_enr.clone(), Distance::MAX);
+        let status = NodeStatus {
+            state: ConnectionState::Connected,
+            direction: ConnectionDirection::Outgoing,
+        };
+        let _ = service.kbuckets.insert_or_update(bootnode, status);
+
+        let target_content_key = IdentityContentKey::new(NodeId::random().raw());
+        let (tx, rx) = oneshot::channel();
+        let query_id = service
+            .init_find_content_query(target_content_key, Some(tx), FindContentConfig::default())
+            .expect("Query ID for new find content query is `None`");
+
+        {
+            let (_, query) = service
+                .find_content_query_pool
+                .get_mut(query_id)
+                .expect("Query pool does not contain query");
+            query.poll(Instant::now());
+        }
+
+        // The only peer responds without content and without closer peers.
+        service.advance_find_content_query_with_enrs(&query_id, bootnode_enr.clone(), vec![]);
+
+        let event = OverlayService::<
+            IdentityContentKey,
+            XorMetric,
+            MockValidator,
+            MemoryContentStore,
+        >::query_event_poll(&mut service.find_content_query_pool)
+        .await;
+        assert!(matches!(event, QueryEvent::Finished(..)));
+        service.handle_find_content_query_event(event);
+
+        match rx.await.unwrap() {
+            Err(OverlayRequestError::ContentNotFound { closest_enrs, .. }) => {
+                assert_eq!(closest_enrs, vec![bootnode_enr]);
+            }
+            result => panic!("Unexpected find content query result: {result:?}"),
+        }
+    }
+
     #[tokio::test]
     async fn advance_find_content_query_with_content() {
         let mut service = task::spawn(build_service());
@@ -3680,10 +4500,7 @@ mod tests {
         let bootnode_node_id = bootnode_enr.node_id();
 
         let data_radius = Distance::MAX;
-        let bootnode = Node {
-            enr: bootnode_enr.clone(),
-            data_radius,
-        };
+        let bootnode = Node::new(bootnode_enr.clone(), data_radius);
 
         let connection_direction = ConnectionDirection::Outgoing;
         let status = NodeStatus {
@@ -3743,10 +4560,7 @@ mod tests {
         let bootnode_node_id = bootnode_enr.node_id();
 
         let data_radius = Distance::MAX;
-        let bootnode = Node {
-            enr: bootnode_enr.clone(),
-            data_radius,
-        };
+        let bootnode = Node::new(bootnode_enr.clone(), data_radius);
 
         let connection_direction = ConnectionDirection::Outgoing;
         let status = NodeStatus {
@@ -3808,10 +4622,7 @@ mod tests {
         let (_, bootnode_enr) = generate_random_remote_enr();
 
         let data_radius = Distance::MAX;
-        let bootnode = Node {
-            enr: bootnode_enr.clone(),
-            data_radius,
-        };
+        let bootnode = Node::new(bootnode_enr.clone(), data_radius);
 
         let connection_direction = ConnectionDirection::Outgoing;
         let status = NodeStatus {
@@ -3930,4 +4741,37 @@ mod tests {
         let event = receiver.recv().await.unwrap();
         assert_eq!(event.payload, OverlayEvent::LightClientOptimisticUpdate);
     }
+
+    #[tokio::test]
+    async fn radius_changes_are_emitted_once() {
+        let mut service = task::spawn(build_service());
+        let (sender, mut receiver) = broadcast::channel(EVENT_STREAM_CHANNEL_CAPACITY);
+        service.event_stream = sender;
+
+        let radius = Distance::from(U256::from(100));
+        service.store.write().set_radius(radius);
+        service.store.write().set_radius(Distance::MAX);
+        service.emit_radius_changes();
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(
+            event.payload,
+            OverlayEvent::RadiusChanged {
+                old_radius: Distance::MAX,
+                new_radius: radius,
+            }
+        );
+        assert_eq!(event.destination, Some(vec![Subnetwork::History]));
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(
+            event.payload,
+            OverlayEvent::RadiusChanged {
+                old_radius: radius,
+                new_radius: Distance::MAX,
+            }
+        );
+
+        // The changes that were already emitted aren't emitted again.
+        service.emit_radius_changes();
+        assert!(receiver.try_recv().is_err());
+    }
 }
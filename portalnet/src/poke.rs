@@ -0,0 +1,188 @@
+use std::time::{Duration, Instant};
+
+use discv5::enr::NodeId;
+use lru::LruCache;
+use trin_metrics::labels::{PokeOutcomeLabel, PokeTriggerLabel};
+
+/// The max number of (peer, content) pairs that are remembered as offered.
+const POKED_CAPACITY: usize = 16_384;
+
+/// The max number of newly inserted peers that are remembered until their radius is known.
+const NEW_PEERS_CAPACITY: usize = 256;
+
+/// The period over which the number of rebroadcast offers is limited.
+const REBROADCAST_PERIOD: Duration = Duration::from_secs(60);
+
+/// Configuration of the rebroadcast of locally stored content to newly discovered peers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RebroadcastConfig {
+    /// The max number of stored content items offered to each new peer.
+    pub offers_per_peer: usize,
+    /// The max number of stored content items offered per minute, across all the new peers.
+    pub offers_per_minute: usize,
+}
+
+/// Remembers the content that was offered to peers without them asking for it, so that the same
+/// content is offered at most once to each peer, and limits the rate at which locally stored
+/// content is rebroadcast to new peers.
+pub struct PokeTracker {
+    /// The (peer, content id) pairs that were offered.
+    poked: LruCache<(NodeId, [u8; 32]), ()>,
+    /// The peers newly inserted into the routing table, which are offered stored content once
+    /// their radius is known.
+    new_peers: LruCache<NodeId, ()>,
+    /// The rebroadcast to new peers, disabled if `None`.
+    rebroadcast: Option<RebroadcastConfig>,
+    /// When the current rate limiting period started.
+    period_start: Instant,
+    /// The number of rebroadcast offers in the current rate limiting period.
+    period_offers: usize,
+}
+
+impl PokeTracker {
+    pub fn new(rebroadcast: Option<RebroadcastConfig>) -> Self {
+        Self {
+            poked: LruCache::new(POKED_CAPACITY),
+            new_peers: LruCache::new(NEW_PEERS_CAPACITY),
+            rebroadcast,
+            period_start: Instant::now(),
+            period_offers: 0,
+        }
+    }
+
+    /// Records a peer that was newly inserted into the routing table, if the rebroadcast to new
+    /// peers is enabled.
+    pub fn add_new_peer(&mut self, node_id: NodeId) {
+        if self.rebroadcast.is_some() {
+            self.new_peers.put(node_id, ());
+        }
+    }
+
+    /// If the peer is new, stops tracking it as new and returns the max number of stored content
+    /// items to offer it.
+    pub fn take_new_peer(&mut self, node_id: &NodeId) -> Option<usize> {
+        let config = self.rebroadcast?;
+        self.new_peers.pop(node_id)?;
+        Some(config.offers_per_peer)
+    }
+
+    /// Returns whether the content may be offered to the peer, recording the offer if so.
+    pub fn poke(
+        &mut self,
+        node_id: NodeId,
+        content_id: [u8; 32],
+        trigger: PokeTriggerLabel,
+    ) -> PokeOutcomeLabel {
+        self.poke_at(node_id, content_id, trigger, Instant::now())
+    }
+
+    fn poke_at(
+        &mut self,
+        node_id: NodeId,
+        content_id: [u8; 32],
+        trigger: PokeTriggerLabel,
+        now: Instant,
+    ) -> PokeOutcomeLabel {
+        let key = (node_id, content_id);
+        if self.poked.contains(&key) {
+            return PokeOutcomeLabel::AlreadyOffered;
+        }
+        if trigger == PokeTriggerLabel::NewPeer {
+            let Some(config) = self.rebroadcast else {
+                return PokeOutcomeLabel::RateLimited;
+            };
+            if now.duration_since(self.period_start) >= REBROADCAST_PERIOD {
+                self.period_start = now;
+                self.period_offers = 0;
+            }
+            if self.period_offers >= config.offers_per_minute {
+                return PokeOutcomeLabel::RateLimited;
+            }
+            self.period_offers += 1;
+        }
+        self.poked.put(key, ());
+        PokeOutcomeLabel::Offered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT_ID: [u8; 32] = [1; 32];
+
+    fn tracker(offers_per_minute: usize) -> PokeTracker {
+        PokeTracker::new(Some(RebroadcastConfig {
+            offers_per_peer: 4,
+            offers_per_minute,
+        }))
+    }
+
+    #[test]
+    fn pokes_once_per_peer_and_content() {
+        let mut tracker = PokeTracker::new(None);
+        let peer = NodeId::random();
+        assert_eq!(
+            tracker.poke(peer, CONTENT_ID, PokeTriggerLabel::Lookup),
+            PokeOutcomeLabel::Offered
+        );
+        assert_eq!(
+            tracker.poke(peer, CONTENT_ID, PokeTriggerLabel::Lookup),
+            PokeOutcomeLabel::AlreadyOffered
+        );
+        assert_eq!(
+            tracker.poke(peer, [2; 32], PokeTriggerLabel::Lookup),
+            PokeOutcomeLabel::Offered
+        );
+        assert_eq!(
+            tracker.poke(NodeId::random(), CONTENT_ID, PokeTriggerLabel::Lookup),
+            PokeOutcomeLabel::Offered
+        );
+    }
+
+    #[test]
+    fn rate_limits_rebroadcast() {
+        let mut tracker = tracker(2);
+        let start = Instant::now();
+        for content_id in [[1; 32], [2; 32]] {
+            assert_eq!(
+                tracker.poke_at(
+                    NodeId::random(),
+                    content_id,
+                    PokeTriggerLabel::NewPeer,
+                    start
+                ),
+                PokeOutcomeLabel::Offered
+            );
+        }
+        let peer = NodeId::random();
+        assert_eq!(
+            tracker.poke_at(peer, CONTENT_ID, PokeTriggerLabel::NewPeer, start),
+            PokeOutcomeLabel::RateLimited
+        );
+        // Lookup pokes aren't rate limited.
+        assert_eq!(
+            tracker.poke_at(peer, CONTENT_ID, PokeTriggerLabel::Lookup, start),
+            PokeOutcomeLabel::Offered
+        );
+
+        let next_period = start + REBROADCAST_PERIOD;
+        assert_eq!(
+            tracker.poke_at(peer, [3; 32], PokeTriggerLabel::NewPeer, next_period),
+            PokeOutcomeLabel::Offered
+        );
+    }
+
+    #[test]
+    fn new_peers_are_taken_once() {
+        let mut tracker = tracker(2);
+        let peer = NodeId::random();
+        tracker.add_new_peer(peer);
+        assert_eq!(tracker.take_new_peer(&peer), Some(4));
+        assert_eq!(tracker.take_new_peer(&peer), None);
+
+        let mut tracker = PokeTracker::new(None);
+        tracker.add_new_peer(peer);
+        assert_eq!(tracker.take_new_peer(&peer), None);
+    }
+}
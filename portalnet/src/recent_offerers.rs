@@ -0,0 +1,131 @@
+use std::time::Instant;
+
+use discv5::enr::NodeId;
+use ethportal_api::types::portal::{RecentOffererInfo, RecentOfferersInfo};
+use lru::LruCache;
+
+/// The max number of peers whose offers are tracked.
+const RECENT_OFFERERS_CAPACITY: usize = 256;
+
+/// The offers received from a single peer.
+struct OffererStats {
+    /// The number of OFFER messages received.
+    offers: u64,
+    /// The number of content keys offered.
+    offered_keys: u64,
+    /// The number of offered content keys that we accepted.
+    accepted_keys: u64,
+    /// When the last OFFER message was received.
+    last_offer: Instant,
+}
+
+/// In-memory record of the peers that recently sent us OFFER messages.
+///
+/// Only the most recent offerers are kept, peers that haven't offered anything for a while are
+/// evicted once the capacity is reached.
+pub struct RecentOfferers {
+    offerers: LruCache<NodeId, OffererStats>,
+}
+
+impl Default for RecentOfferers {
+    fn default() -> Self {
+        Self::new(RECENT_OFFERERS_CAPACITY)
+    }
+}
+
+impl RecentOfferers {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            offerers: LruCache::new(capacity),
+        }
+    }
+
+    /// Records an OFFER from `peer`, of which we accepted `accepted_keys` out of `offered_keys`.
+    pub fn record_offer(&mut self, peer: NodeId, offered_keys: usize, accepted_keys: usize) {
+        let now = Instant::now();
+        match self.offerers.get_mut(&peer) {
+            Some(stats) => {
+                stats.offers += 1;
+                stats.offered_keys += offered_keys as u64;
+                stats.accepted_keys += accepted_keys as u64;
+                stats.last_offer = now;
+            }
+            None => {
+                self.offerers.put(
+                    peer,
+                    OffererStats {
+                        offers: 1,
+                        offered_keys: offered_keys as u64,
+                        accepted_keys: accepted_keys as u64,
+                        last_offer: now,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Returns up to `limit` peers that offered us content, the most recent first.
+    pub fn info(&self, limit: usize) -> RecentOfferersInfo {
+        let offerers = self
+            .offerers
+            .iter()
+            .take(limit)
+            .map(|(node_id, stats)| RecentOffererInfo {
+                node_id: *node_id,
+                offers: stats.offers,
+                offered_keys: stats.offered_keys,
+                accepted_keys: stats.accepted_keys,
+                accept_rate: if stats.offered_keys == 0 {
+                    0.0
+                } else {
+                    stats.accepted_keys as f64 / stats.offered_keys as f64
+                },
+                last_offer_secs_ago: stats.last_offer.elapsed().as_secs(),
+            })
+            .collect();
+        RecentOfferersInfo { offerers }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_offer() {
+        let mut recent_offerers = RecentOfferers::default();
+        let peer = NodeId::random();
+        recent_offerers.record_offer(peer, 4, 1);
+        recent_offerers.record_offer(peer, 4, 2);
+
+        let info = recent_offerers.info(10);
+        assert_eq!(info.offerers.len(), 1);
+        let offerer = &info.offerers[0];
+        assert_eq!(offerer.node_id, peer);
+        assert_eq!(offerer.offers, 2);
+        assert_eq!(offerer.offered_keys, 8);
+        assert_eq!(offerer.accepted_keys, 3);
+        assert_eq!(offerer.accept_rate, 0.375);
+    }
+
+    #[test]
+    fn test_most_recent_first_and_bounded() {
+        let mut recent_offerers = RecentOfferers::new(2);
+        let peers: Vec<NodeId> = (0..3).map(|_| NodeId::random()).collect();
+        for peer in &peers {
+            recent_offerers.record_offer(*peer, 1, 0);
+        }
+        // the first peer was evicted by the third, and the second peer is now the most recent
+        recent_offerers.record_offer(peers[1], 1, 1);
+
+        let node_ids: Vec<NodeId> = recent_offerers
+            .info(10)
+            .offerers
+            .into_iter()
+            .map(|offerer| offerer.node_id)
+            .collect();
+        assert_eq!(node_ids, vec![peers[1], peers[2]]);
+
+        assert_eq!(recent_offerers.info(1).offerers.len(), 1);
+    }
+}
@@ -16,6 +16,19 @@ const UPNP_MAPPING_DURATION: u32 = 3600;
 /// Renew the external port from being unmapped.
 const UPNP_MAPPING_TIMEOUT: u64 = UPNP_MAPPING_DURATION as u64 / 2;
 
+/// The size of the header of a uTP packet.
+const UTP_HEADER_SIZE: usize = 20;
+
+/// The version of the uTP protocol, in the low nibble of the first byte of the header.
+const UTP_VERSION: u8 = 1;
+
+/// The packet type of the uTP packets that carry a payload (ST_DATA).
+const UTP_DATA_PACKET_TYPE: u8 = 0;
+
+/// The max packet type of the uTP protocol (ST_SYN), in the high nibble of the first byte of the
+/// header.
+const MAX_UTP_PACKET_TYPE: u8 = 4;
+
 /// Ping a STUN server on the public network. This does two things:
 /// - Creates an externally-addressable UDP port, if you are behind a NAT
 /// - Returns the public IP and port that corresponds to your local port
@@ -129,3 +142,83 @@ pub fn upnp_for_external(listen_addr: SocketAddr) -> Option<SocketAddr> {
         }
     }
 }
+
+/// Returns whether the packet starts with a uTP header, so that other packets arriving on the uTP
+/// port (e.g. discv5 packets from misconfigured peers) are dropped before reaching the uTP socket.
+///
+/// Discv5 packets start with a random masking IV, so they are only mistaken for uTP packets with a
+/// low probability, in which case the uTP socket drops them as it does with any invalid packet.
+pub fn is_utp_packet(packet: &[u8]) -> bool {
+    if packet.len() < UTP_HEADER_SIZE {
+        return false;
+    }
+    let packet_type = packet[0] >> 4;
+    let version = packet[0] & 0x0f;
+    version == UTP_VERSION && packet_type <= MAX_UTP_PACKET_TYPE
+}
+
+/// Returns whether the packet is a uTP packet that carries a payload, as opposed to a control
+/// packet, e.g. an ACK.
+pub fn is_utp_data_packet(packet: &[u8]) -> bool {
+    is_utp_packet(packet) && packet[0] >> 4 == UTP_DATA_PACKET_TYPE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a uTP header of the packet type, with arbitrary connection id and sequence numbers.
+    fn utp_header(packet_type: u8) -> Vec<u8> {
+        let mut header = vec![(packet_type << 4) | UTP_VERSION, 0];
+        header.extend_from_slice(&1234u16.to_be_bytes()); // connection id
+        header.extend_from_slice(&[0; 8]); // timestamp and timestamp difference
+        header.extend_from_slice(&1_048_576u32.to_be_bytes()); // window size
+        header.extend_from_slice(&1u16.to_be_bytes()); // sequence number
+        header.extend_from_slice(&0u16.to_be_bytes()); // ack number
+        header
+    }
+
+    #[test]
+    fn accepts_utp_packets() {
+        for packet_type in 0..=MAX_UTP_PACKET_TYPE {
+            assert!(is_utp_packet(&utp_header(packet_type)));
+        }
+        let mut data = utp_header(0);
+        data.extend_from_slice(b"payload");
+        assert!(is_utp_packet(&data));
+    }
+
+    #[test]
+    fn distinguishes_data_packets() {
+        let mut data = utp_header(0);
+        data.extend_from_slice(b"payload");
+        assert!(is_utp_data_packet(&data));
+        for packet_type in 1..=MAX_UTP_PACKET_TYPE {
+            assert!(!is_utp_data_packet(&utp_header(packet_type)));
+        }
+        assert!(!is_utp_data_packet(&[]));
+    }
+
+    #[test]
+    fn rejects_other_packets() {
+        // A discv5 packet starts with a 16 byte masking IV, followed by the masked header.
+        let mut discv5_packet = vec![
+            0x9f, 0x3c, 0x2e, 0x77, 0x10, 0x5a, 0x44, 0xd1, 0x08, 0xee, 0x61, 0x2b, 0x93, 0x05,
+            0xc4, 0x7a,
+        ];
+        discv5_packet.extend_from_slice(&[0xab; 40]);
+        assert!(!is_utp_packet(&discv5_packet));
+
+        // Unknown packet type.
+        assert!(!is_utp_packet(&utp_header(5)));
+
+        // Unknown version.
+        let mut packet = utp_header(0);
+        packet[0] = 0x02;
+        assert!(!is_utp_packet(&packet));
+
+        // Truncated header.
+        assert!(!is_utp_packet(&utp_header(4)[..UTP_HEADER_SIZE - 1]));
+        assert!(!is_utp_packet(&[]));
+    }
+}
@@ -1,19 +1,31 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Instant};
 
 use discv5::{
     enr::NodeId,
     kbucket::{
         AppliedPending, Entry as KBucketEntry, InsertResult, KBucketsTable, NodeStatus,
-        UpdateResult,
+        UpdateResult, MAX_NODES_PER_BUCKET,
     },
     ConnectionDirection, ConnectionState, Enr, Key,
 };
-use ethportal_api::types::distance::{Distance, Metric};
+use ethportal_api::types::{
+    discv5::{
+        BucketDensityInfo, BucketRefreshInfo, BucketRefreshTimesInfo, KeyspaceDensityInfo,
+        PeerProtocolVersion,
+    },
+    distance::Metric,
+};
 use itertools::Itertools;
 use parking_lot::RwLock;
 use tracing::debug;
 
-use super::node::Node;
+use super::{
+    node::Node,
+    protocol_version::{ProtocolFeature, BASELINE_PROTOCOL_VERSION},
+};
+
+/// Buckets with less than this fraction of their expected peers are reported as sparse.
+const SPARSE_BUCKET_DENSITY: f64 = 0.5;
 
 /// Information regarding single entry in the routing table.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -70,6 +82,15 @@ pub struct DiscoveredNodesUpdateResult {
     pub removed_nodes: Vec<NodeId>,
 }
 
+/// The connected nodes that are interested into a content id, as returned by
+/// [SharedKBucketsTable::interested_nodes] and [SharedKBucketsTable::batch_interested_nodes].
+#[derive(Debug, Default)]
+pub struct InterestedNodes {
+    pub nodes: Vec<Node>,
+    /// The number of connected nodes whose radius excludes the content id.
+    pub excluded: usize,
+}
+
 /// The wrapper around [`discv5::kbucket::KBucketsTable`] that is safe for async usage.
 ///
 /// Every function holds the lock only for the duration of the function, and no other blocking
@@ -77,12 +98,15 @@ pub struct DiscoveredNodesUpdateResult {
 #[derive(Clone)]
 pub struct SharedKBucketsTable {
     kbuckets: Arc<RwLock<KBucketsTable<NodeId, Node>>>,
+    /// When each bucket, by index, was last refreshed.
+    refresh_times: Arc<RwLock<HashMap<usize, Instant>>>,
 }
 
 impl SharedKBucketsTable {
     pub fn new(kbuckets: KBucketsTable<NodeId, Node>) -> Self {
         Self {
             kbuckets: Arc::new(RwLock::new(kbuckets)),
+            refresh_times: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -149,9 +173,9 @@ impl SharedKBucketsTable {
             // If the node is not in the routing table, then insert the node in a disconnected state
             // (a subsequent ping will establish connectivity with the node). Ignore insertion
             // failures.
-            if let Some(node) = Entry::from(kbuckets.entry(&key)).present_or_pending() {
+            if let Some(mut node) = Entry::from(kbuckets.entry(&key)).present_or_pending() {
                 if node.enr.seq() < enr.seq() {
-                    let node = Node::new(enr, node.data_radius());
+                    node.set_enr(enr);
 
                     if let UpdateResult::Failed(reason) = kbuckets.update_node(&key, node, None) {
                         // The update removed the node because it would violate the incoming peers
@@ -165,7 +189,7 @@ impl SharedKBucketsTable {
                     }
                 }
             } else {
-                let node = Node::new(enr, Distance::MAX);
+                let node = Node::with_unknown_radius(enr);
                 let status = NodeStatus {
                     state: ConnectionState::Disconnected,
                     direction: ConnectionDirection::Outgoing,
@@ -278,34 +302,27 @@ impl SharedKBucketsTable {
             .collect()
     }
 
-    /// Returns all nodes that are connected and interested into provided content id.
-    pub fn interested_enrs<TMetric: Metric>(&self, content_id: &[u8; 32]) -> Vec<Enr> {
-        self.kbuckets
-            .write()
-            .iter()
-            // Filter out disconnected nodes.
-            .filter(|entry| entry.status.is_connected())
-            .map(|entry| entry.node)
-            // Keep only nodes that are interested in content
-            .filter(|node| {
-                TMetric::distance(content_id, &node.key.preimage().raw()) <= node.value.data_radius
-            })
-            .map(|node| node.value.enr())
-            .collect()
+    /// Returns all nodes that are connected and interested into provided content id, and the
+    /// number of connected nodes whose radius excludes it.
+    pub fn interested_nodes<TMetric: Metric>(&self, content_id: &[u8; 32]) -> InterestedNodes {
+        self.batch_interested_nodes::<TMetric>(&[content_id])
+            .remove(content_id)
+            .unwrap_or_default()
     }
 
-    /// For each content id, returns all ENRs that are connected and interested into it.
+    /// For each content id, returns all nodes that are connected and interested into it, and the
+    /// number of connected nodes whose radius excludes it.
     ///
     /// The keys of the resulting map will always contain all `content_ids`. If none of the nodes is
-    /// interested into specific content id, it will still be present in the result but the value
-    /// associated with it will be empty.
-    pub fn batch_interested_enrs<TMetric: Metric>(
+    /// interested into specific content id, it will still be present in the result but its nodes
+    /// will be empty.
+    pub fn batch_interested_nodes<TMetric: Metric>(
         &self,
         content_ids: &[&[u8; 32]],
-    ) -> HashMap<[u8; 32], Vec<Enr>> {
+    ) -> HashMap<[u8; 32], InterestedNodes> {
         let mut result = content_ids
             .iter()
-            .map(|content_id| (**content_id, vec![]))
+            .map(|content_id| (**content_id, InterestedNodes::default()))
             .collect::<HashMap<_, _>>();
         for entry in self.kbuckets.write().iter() {
             // Skip non-connected nodes
@@ -315,11 +332,11 @@ impl SharedKBucketsTable {
             let node = entry.node;
             for content_id in content_ids {
                 let distance = TMetric::distance(content_id, &node.key.preimage().raw());
+                let interested_nodes = result.entry(**content_id).or_default();
                 if distance <= node.value.data_radius {
-                    result
-                        .entry(**content_id)
-                        .or_default()
-                        .push(node.value.enr());
+                    interested_nodes.nodes.push(node.value.clone());
+                } else {
+                    interested_nodes.excluded += 1;
                 }
             }
         }
@@ -333,6 +350,40 @@ impl SharedKBucketsTable {
         self.kbuckets.write().take_applied_pending()
     }
 
+    /// Returns the ids of the disconnected nodes in the routing table.
+    pub fn disconnected_node_ids(&self) -> Vec<NodeId> {
+        self.kbuckets
+            .write()
+            .iter()
+            .filter(|entry| !entry.status.is_connected())
+            .map(|entry| *entry.node.key.preimage())
+            .collect()
+    }
+
+    /// Returns the protocol version negotiated with each node in the routing table.
+    pub fn protocol_versions(&self) -> Vec<PeerProtocolVersion> {
+        self.kbuckets
+            .write()
+            .iter()
+            .map(|entry| PeerProtocolVersion {
+                node_id: *entry.node.key.preimage(),
+                protocol_version: entry.node.value.protocol_version(),
+            })
+            .collect()
+    }
+
+    /// Returns whether the protocol version negotiated with the node has the feature, so that the
+    /// message encodings of the feature can be used with it.
+    ///
+    /// Nodes that aren't in the routing table are assumed to use the baseline version.
+    pub fn peer_supports(&self, node_id: NodeId, feature: ProtocolFeature) -> bool {
+        let protocol_version = self
+            .entry(node_id)
+            .present_or_pending()
+            .map_or(BASELINE_PROTOCOL_VERSION, |node| node.protocol_version());
+        protocol_version >= feature.min_version()
+    }
+
     /// Returns all ENRs in the routing table.
     ///
     /// Should be used only if all ENRs are desired.
@@ -343,6 +394,115 @@ impl SharedKBucketsTable {
             .map(|entry| entry.node.value.enr())
             .collect()
     }
+
+    /// Returns how densely the buckets cover the node id space.
+    pub fn keyspace_density(&self) -> KeyspaceDensityInfo {
+        let kbuckets = self.kbuckets.read();
+        let peer_counts = kbuckets
+            .buckets_iter()
+            .map(|bucket| bucket.iter().count())
+            .collect::<Vec<_>>();
+        keyspace_density(*kbuckets.local_key().preimage(), &peer_counts)
+    }
+
+    /// Records that the bucket at the index was just refreshed.
+    pub fn record_bucket_refresh(&self, bucket_index: usize) {
+        self.refresh_times
+            .write()
+            .insert(bucket_index, Instant::now());
+    }
+
+    /// Returns when the buckets that have peers or were refreshed were last refreshed.
+    pub fn bucket_refresh_times(&self) -> BucketRefreshTimesInfo {
+        let (local_node_id, peer_counts) = {
+            let kbuckets = self.kbuckets.read();
+            let peer_counts = kbuckets
+                .buckets_iter()
+                .map(|bucket| bucket.iter().count())
+                .collect::<Vec<_>>();
+            (*kbuckets.local_key().preimage(), peer_counts)
+        };
+        let refresh_times = self.refresh_times.read();
+        let buckets = peer_counts
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, peer_count)| {
+                let last_refresh = refresh_times.get(&index);
+                if peer_count == 0 && last_refresh.is_none() {
+                    return None;
+                }
+                Some(BucketRefreshInfo {
+                    log2_distance: index as u16 + 1,
+                    peer_count: peer_count as u64,
+                    last_refresh_secs_ago: last_refresh.map(|instant| instant.elapsed().as_secs()),
+                })
+            })
+            .collect();
+        BucketRefreshTimesInfo {
+            local_node_id,
+            buckets,
+        }
+    }
+}
+
+/// Computes the density of the buckets from their peer counts, ordered by increasing log2
+/// distance.
+///
+/// The bucket at log2 distance `d` covers `2^(d-1)` node ids, i.e. a `2^(d-257)` fraction of the
+/// node id space. A bucket that isn't full presumably knows all nodes at its distance, so the
+/// network size is estimated as the median of `peer_count / keyspace_fraction` over the buckets
+/// that have peers but aren't full. If all of them are full, the largest of these is used, which
+/// underestimates the network size.
+fn keyspace_density(local_node_id: NodeId, peer_counts: &[usize]) -> KeyspaceDensityInfo {
+    let fraction_at = |index: usize| 2f64.powi(index as i32 - 256);
+    let implied_network_sizes = |full: bool| {
+        peer_counts
+            .iter()
+            .enumerate()
+            .filter(|(_, peer_count)| {
+                **peer_count > 0 && (**peer_count >= MAX_NODES_PER_BUCKET) == full
+            })
+            .map(|(index, peer_count)| *peer_count as f64 / fraction_at(index))
+            .sorted_by(f64::total_cmp)
+            .collect::<Vec<_>>()
+    };
+    let not_full_sizes = implied_network_sizes(false);
+    let estimated_network_size = if not_full_sizes.is_empty() {
+        implied_network_sizes(true)
+            .last()
+            .copied()
+            .unwrap_or_default()
+    } else {
+        not_full_sizes[not_full_sizes.len() / 2]
+    };
+
+    let buckets = peer_counts
+        .iter()
+        .enumerate()
+        .filter_map(|(index, peer_count)| {
+            let keyspace_fraction = fraction_at(index);
+            let expected_peers =
+                (estimated_network_size * keyspace_fraction).min(MAX_NODES_PER_BUCKET as f64);
+            if *peer_count == 0 && expected_peers < 1.0 {
+                return None;
+            }
+            let density = *peer_count as f64 / expected_peers;
+            Some(BucketDensityInfo {
+                log2_distance: index as u16 + 1,
+                peer_count: *peer_count as u64,
+                keyspace_fraction,
+                expected_peers,
+                density,
+                sparse: density < SPARSE_BUCKET_DENSITY,
+            })
+        })
+        .collect();
+
+    KeyspaceDensityInfo {
+        local_node_id,
+        estimated_network_size: estimated_network_size as u64,
+        buckets,
+    }
 }
 
 impl From<&SharedKBucketsTable> for ethportal_api::KBucketsTable {
@@ -359,6 +519,26 @@ impl From<&SharedKBucketsTable> for ethportal_api::KBucketsTable {
     }
 }
 
+impl From<&SharedKBucketsTable> for ethportal_api::ReplacementCache {
+    fn from(shared_kbuckets: &SharedKBucketsTable) -> Self {
+        let buckets = shared_kbuckets
+            .kbuckets
+            .read()
+            .buckets_iter()
+            .map(|bucket| ethportal_api::ReplacementBucket {
+                // The pending node only exposes its value mutably, so we read it from a clone.
+                enrs: bucket
+                    .pending()
+                    .cloned()
+                    .map(|mut pending| pending.value_mut().enr())
+                    .into_iter()
+                    .collect(),
+            })
+            .collect();
+        Self { buckets }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{thread::sleep, time::Duration};
@@ -369,7 +549,10 @@ mod tests {
     };
     use ethportal_api::{
         generate_random_remote_enr,
-        types::{cli::DEFAULT_DISCOVERY_PORT, distance::XorMetric},
+        types::{
+            cli::DEFAULT_DISCOVERY_PORT,
+            distance::{Distance, XorMetric},
+        },
     };
     use itertools::chain;
 
@@ -660,6 +843,16 @@ mod tests {
                 InsertResult::Pending { .. }
             ));
 
+            // Check that pending node is in the replacement cache of its bucket
+            let replacement_cache = ethportal_api::ReplacementCache::from(&kbuckets);
+            assert_eq!(replacement_cache.buckets.len(), kbuckets.buckets_count());
+            let replacements = replacement_cache
+                .buckets
+                .into_iter()
+                .flat_map(|bucket| bucket.enrs)
+                .collect_vec();
+            assert_eq!(replacements, vec![pending_enr.clone()]);
+
             // Check that pending node is updated
             pending_enr
                 .set_udp4(DEFAULT_DISCOVERY_PORT, &secret_key)
@@ -850,11 +1043,15 @@ mod tests {
         }
     }
 
-    mod interested_enrs {
+    mod interested_nodes {
         use alloy::primitives::U256;
 
         use super::*;
 
+        fn enrs(nodes: Vec<Node>) -> Vec<Enr> {
+            nodes.into_iter().map(|node| node.enr).collect()
+        }
+
         #[test]
         fn single() {
             let (_local_enr, kbuckets) = create_kbuckets_table();
@@ -886,10 +1083,13 @@ mod tests {
                 .insert_or_update(Node::new(enr_exact_connected.clone(), distance), *CONNECTED);
 
             // Should return only connected nodes with radius that contains content id
+            let interested_nodes = kbuckets.interested_nodes::<XorMetric>(&content_id);
             assert_same_enrs(
-                kbuckets.interested_enrs::<XorMetric>(&content_id),
+                enrs(interested_nodes.nodes),
                 vec![enr_max_connected, enr_exact_connected],
             );
+            // Only the connected node with zero radius is excluded
+            assert_eq!(interested_nodes.excluded, 1);
         }
 
         #[test]
@@ -919,10 +1119,14 @@ mod tests {
             );
 
             let mut result =
-                kbuckets.batch_interested_enrs::<XorMetric>(&[&content_id_1, &content_id_2]);
+                kbuckets.batch_interested_nodes::<XorMetric>(&[&content_id_1, &content_id_2]);
             assert_eq!(result.len(), 2);
-            assert_same_enrs(result.remove(&content_id_1).unwrap(), vec![enr.clone()]);
-            assert_same_enrs(result.remove(&content_id_2).unwrap(), vec![]);
+            let interested_nodes_1 = result.remove(&content_id_1).unwrap();
+            assert_same_enrs(enrs(interested_nodes_1.nodes), vec![enr.clone()]);
+            assert_eq!(interested_nodes_1.excluded, 1);
+            let interested_nodes_2 = result.remove(&content_id_2).unwrap();
+            assert_same_enrs(enrs(interested_nodes_2.nodes), vec![]);
+            assert_eq!(interested_nodes_2.excluded, 2);
 
             // Add connected node with max radius, should always be returned
             let (_, enr_max_connected) = generate_random_remote_enr();
@@ -932,16 +1136,114 @@ mod tests {
             );
 
             let mut result =
-                kbuckets.batch_interested_enrs::<XorMetric>(&[&content_id_1, &content_id_2]);
+                kbuckets.batch_interested_nodes::<XorMetric>(&[&content_id_1, &content_id_2]);
             assert_eq!(result.len(), 2);
+            let interested_nodes_1 = result.remove(&content_id_1).unwrap();
             assert_same_enrs(
-                result.remove(&content_id_1).unwrap(),
+                enrs(interested_nodes_1.nodes),
                 vec![enr, enr_max_connected.clone()],
             );
-            assert_same_enrs(
-                result.remove(&content_id_2).unwrap(),
-                vec![enr_max_connected],
-            );
+            assert_eq!(interested_nodes_1.excluded, 1);
+            let interested_nodes_2 = result.remove(&content_id_2).unwrap();
+            assert_same_enrs(enrs(interested_nodes_2.nodes), vec![enr_max_connected]);
+            assert_eq!(interested_nodes_2.excluded, 2);
+        }
+    }
+
+    mod keyspace_density {
+        use super::*;
+
+        #[test]
+        fn bucket_density() {
+            let (local_enr, _) = create_kbuckets_table();
+            // A network of 1024 nodes, of which the node doesn't know the ones at log2 distance
+            // 248.
+            let mut peer_counts = vec![0; 256];
+            peer_counts[250..].fill(MAX_NODES_PER_BUCKET);
+            peer_counts[249] = 8;
+            peer_counts[248] = 4;
+            peer_counts[246] = 1;
+
+            let info = keyspace_density(local_enr.node_id(), &peer_counts);
+            assert_eq!(info.estimated_network_size, 1024);
+            // The buckets closer than log2 distance 247 are expected to be empty.
+            assert_eq!(info.buckets.len(), 10);
+            assert_eq!(info.buckets[0].log2_distance, 247);
+            assert_eq!(info.buckets[0].expected_peers, 1.0);
+
+            let sparse_buckets = info
+                .buckets
+                .iter()
+                .filter(|bucket| bucket.sparse)
+                .collect::<Vec<_>>();
+            assert_eq!(sparse_buckets.len(), 1);
+            assert_eq!(sparse_buckets[0].log2_distance, 248);
+            assert_eq!(sparse_buckets[0].expected_peers, 2.0);
+            assert_eq!(sparse_buckets[0].density, 0.0);
+
+            let farthest_bucket = info.buckets.last().unwrap();
+            assert_eq!(farthest_bucket.log2_distance, 256);
+            assert_eq!(farthest_bucket.keyspace_fraction, 0.5);
+            assert_eq!(farthest_bucket.expected_peers, MAX_NODES_PER_BUCKET as f64);
+            assert_eq!(farthest_bucket.density, 1.0);
         }
+
+        #[test]
+        fn empty_table() {
+            let (local_enr, kbuckets) = create_kbuckets_table();
+            let info = kbuckets.keyspace_density();
+            assert_eq!(info.local_node_id, local_enr.node_id());
+            assert_eq!(info.estimated_network_size, 0);
+            assert!(info.buckets.is_empty());
+        }
+
+        #[test]
+        fn table_with_peers() {
+            let (local_enr, kbuckets) = create_kbuckets_table();
+            for _ in 0..3 {
+                let enr = generate_random_enr(local_enr.node_id().raw(), 256);
+                let _ = kbuckets.insert_or_update(Node::new(enr, Distance::MAX), *CONNECTED);
+            }
+
+            // The 3 peers at log2 distance 256 imply a network of 6 nodes, of which 1.5 are
+            // expected at log2 distance 255.
+            let info = kbuckets.keyspace_density();
+            assert_eq!(info.estimated_network_size, 6);
+            assert_eq!(info.buckets.len(), 2);
+            assert_eq!(info.buckets[0].log2_distance, 255);
+            assert!(info.buckets[0].sparse);
+            assert_eq!(info.buckets[1].peer_count, 3);
+            assert!(!info.buckets[1].sparse);
+        }
+    }
+
+    #[test]
+    fn bucket_refresh_times() {
+        let (local_enr, kbuckets) = create_kbuckets_table();
+        let info = kbuckets.bucket_refresh_times();
+        assert_eq!(info.local_node_id, local_enr.node_id());
+        assert!(info.buckets.is_empty());
+
+        let enr = generate_random_enr(local_enr.node_id().raw(), 256);
+        let _ = kbuckets.insert_or_update(Node::new(enr, Distance::MAX), *CONNECTED);
+        kbuckets.record_bucket_refresh(250);
+
+        // The bucket with a peer is reported as never refreshed.
+        let info = kbuckets.bucket_refresh_times();
+        assert_eq!(
+            info.buckets,
+            vec![
+                BucketRefreshInfo {
+                    log2_distance: 251,
+                    peer_count: 0,
+                    last_refresh_secs_ago: Some(0),
+                },
+                BucketRefreshInfo {
+                    log2_distance: 256,
+                    peer_count: 1,
+                    last_refresh_secs_ago: None,
+                },
+            ]
+        );
     }
 }
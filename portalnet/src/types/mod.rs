@@ -1,2 +1,3 @@
 pub mod kbucket;
 pub mod node;
+pub mod protocol_version;
@@ -2,6 +2,8 @@ use std::fmt;
 
 use ethportal_api::types::{distance::Distance, enr::Enr};
 
+use super::protocol_version::{negotiate_protocol_version, SUPPORTED_PROTOCOL_VERSIONS};
+
 /// A node in the overlay network routing table.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Node {
@@ -9,12 +11,28 @@ pub struct Node {
     pub enr: Enr,
     /// The node's data radius.
     pub data_radius: Distance,
+    /// Whether the data radius was received from the node, rather than assumed.
+    radius_known: bool,
 }
 
 impl Node {
-    /// Creates a new node.
+    /// Creates a new node, whose data radius was received from it.
     pub fn new(enr: Enr, data_radius: Distance) -> Node {
-        Node { enr, data_radius }
+        Node {
+            enr,
+            data_radius,
+            radius_known: true,
+        }
+    }
+
+    /// Creates a new node, whose data radius isn't known yet. The max radius is assumed until the
+    /// node reports its radius.
+    pub fn with_unknown_radius(enr: Enr) -> Node {
+        Node {
+            enr,
+            data_radius: Distance::MAX,
+            radius_known: false,
+        }
     }
 
     /// Returns the ENR of the node.
@@ -27,6 +45,17 @@ impl Node {
         self.data_radius
     }
 
+    /// Returns whether the data radius was received from the node, rather than assumed.
+    pub fn is_radius_known(&self) -> bool {
+        self.radius_known
+    }
+
+    /// Returns the protocol version negotiated with the node, based on the versions its ENR
+    /// advertises.
+    pub fn protocol_version(&self) -> u8 {
+        negotiate_protocol_version(&SUPPORTED_PROTOCOL_VERSIONS, &self.enr)
+    }
+
     /// Sets the ENR of the node.
     pub fn set_enr(&mut self, enr: Enr) {
         self.enr = enr;
@@ -35,6 +64,7 @@ impl Node {
     /// Sets the data radius of the node.
     pub fn set_data_radius(&mut self, radius: Distance) {
         self.data_radius = radius;
+        self.radius_known = true;
     }
 }
 
@@ -0,0 +1,112 @@
+use ethportal_api::types::enr::Enr;
+
+use crate::discovery::ENR_PORTAL_VERSIONS_KEY;
+
+/// The protocol version of peers that don't advertise the versions they support.
+pub const BASELINE_PROTOCOL_VERSION: u8 = 0;
+
+/// The portal wire protocol versions that we support.
+pub const SUPPORTED_PROTOCOL_VERSIONS: [u8; 1] = [BASELINE_PROTOCOL_VERSION];
+
+/// A protocol feature, which is only used with peers whose negotiated protocol version has it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtocolFeature {
+    /// ACCEPT messages carry a code per offered content key, instead of a bitlist.
+    AcceptCodes,
+}
+
+impl ProtocolFeature {
+    /// Returns the first protocol version that has the feature.
+    pub fn min_version(&self) -> u8 {
+        match self {
+            ProtocolFeature::AcceptCodes => 1,
+        }
+    }
+}
+
+/// Returns the protocol versions that the ENR advertises.
+///
+/// ENRs without the versions field are assumed to support only the baseline version, as older
+/// clients don't advertise it.
+pub fn enr_protocol_versions(enr: &Enr) -> Vec<u8> {
+    match enr.get(ENR_PORTAL_VERSIONS_KEY) {
+        Some(versions) if !versions.is_empty() => versions.to_vec(),
+        _ => vec![BASELINE_PROTOCOL_VERSION],
+    }
+}
+
+/// Returns the highest protocol version that both `local_versions` and the ENR support.
+///
+/// Falls back to the baseline version if the ENR doesn't advertise any version that we support.
+pub fn negotiate_protocol_version(local_versions: &[u8], enr: &Enr) -> u8 {
+    enr_protocol_versions(enr)
+        .into_iter()
+        .filter(|version| local_versions.contains(version))
+        .max()
+        .unwrap_or(BASELINE_PROTOCOL_VERSION)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use discv5::enr::{CombinedKey, Enr as Discv5Enr};
+    use ethportal_api::types::enr::generate_random_remote_enr;
+
+    use super::*;
+
+    fn enr_with_versions(versions: &[u8]) -> Enr {
+        let key = CombinedKey::generate_secp256k1();
+        Discv5Enr::builder()
+            .udp4(8000)
+            .add_value(ENR_PORTAL_VERSIONS_KEY, &versions)
+            .build(&key)
+            .unwrap()
+    }
+
+    #[test]
+    fn absent_versions_default_to_baseline() {
+        let (_, enr) = generate_random_remote_enr();
+        assert_eq!(enr_protocol_versions(&enr), vec![BASELINE_PROTOCOL_VERSION]);
+        assert_eq!(
+            negotiate_protocol_version(&[0, 1], &enr),
+            BASELINE_PROTOCOL_VERSION
+        );
+    }
+
+    #[test]
+    fn negotiates_highest_common_version() {
+        let enr = enr_with_versions(&[0, 1]);
+        assert_eq!(enr_protocol_versions(&enr), vec![0, 1]);
+        assert_eq!(negotiate_protocol_version(&[0, 1], &enr), 1);
+        assert_eq!(negotiate_protocol_version(&[0], &enr), 0);
+
+        let enr = enr_with_versions(&[0]);
+        assert_eq!(negotiate_protocol_version(&[0, 1], &enr), 0);
+    }
+
+    #[test]
+    fn unknown_versions_default_to_baseline() {
+        let enr = enr_with_versions(&[7, 9]);
+        assert_eq!(
+            negotiate_protocol_version(&[0, 1], &enr),
+            BASELINE_PROTOCOL_VERSION
+        );
+
+        let enr = enr_with_versions(&[]);
+        assert_eq!(enr_protocol_versions(&enr), vec![BASELINE_PROTOCOL_VERSION]);
+    }
+
+    #[test]
+    fn feature_requires_negotiated_version() {
+        let feature = ProtocolFeature::AcceptCodes;
+        let v1_peer = enr_with_versions(&[0, 1]);
+        let v0_peer = enr_with_versions(&[0]);
+        assert!(negotiate_protocol_version(&[0, 1], &v1_peer) >= feature.min_version());
+        assert!(negotiate_protocol_version(&[0, 1], &v0_peer) < feature.min_version());
+        // We don't support the feature yet, so it isn't used even with peers that do.
+        assert!(
+            negotiate_protocol_version(&SUPPORTED_PROTOCOL_VERSIONS, &v1_peer)
+                < feature.min_version()
+        );
+    }
+}
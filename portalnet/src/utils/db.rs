@@ -1,6 +1,8 @@
 use std::{
+    collections::BTreeMap,
     env, fs,
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
 use alloy::primitives::B256;
@@ -10,11 +12,14 @@ use ethportal_api::{
     types::network::Network,
     utils::bytes::{hex_decode, hex_encode},
 };
+use tracing::warn;
 use trin_utils::dir::setup_data_dir;
 
 const APP_NAME: &str = "trin";
 const TRIN_DATA_ENV_VAR: &str = "TRIN_DATA_PATH";
-const UNSAFE_PRIVATE_KEY_FILE_NAME: &str = "unsafe_private_key.hex";
+pub const UNSAFE_PRIVATE_KEY_FILE_NAME: &str = "unsafe_private_key.hex";
+pub const BOOTNODES_FILE_NAME: &str = "bootnodes.txt";
+pub const ROUTING_TABLES_FILE_NAME: &str = "routing_tables.json";
 
 pub fn configure_trin_data_dir(
     data_dir: Option<PathBuf>,
@@ -77,6 +82,114 @@ fn get_application_private_key(trin_data_dir: &Path) -> anyhow::Result<CombinedK
     Ok(CombinedKey::secp256k1_from_bytes(&mut private_key)?)
 }
 
+/// Returns the bootnodes that were added at runtime and persisted to the node data dir.
+/// Invalid entries are skipped.
+pub fn read_persisted_bootnodes(node_data_dir: &Path) -> anyhow::Result<Vec<Enr<CombinedKey>>> {
+    let bootnodes_file = node_data_dir.join(BOOTNODES_FILE_NAME);
+    if !bootnodes_file.exists() {
+        return Ok(vec![]);
+    }
+    let bootnodes = fs::read_to_string(bootnodes_file)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| match Enr::from_str(line) {
+            Ok(enr) => Some(enr),
+            Err(err) => {
+                warn!(enr = %line, error = %err, "Skipping invalid persisted bootnode");
+                None
+            }
+        })
+        .collect();
+    Ok(bootnodes)
+}
+
+/// Persists the bootnode to the node data dir, replacing the previously persisted ENR with the
+/// same node id, if any.
+pub fn persist_bootnode(node_data_dir: &Path, enr: &Enr<CombinedKey>) -> anyhow::Result<()> {
+    let mut bootnodes = read_persisted_bootnodes(node_data_dir)?;
+    bootnodes.retain(|bootnode| bootnode.node_id() != enr.node_id());
+    bootnodes.push(enr.clone());
+    write_persisted_bootnodes(node_data_dir, &bootnodes)
+}
+
+/// Removes the persisted bootnode with the given node id from the node data dir.
+/// Returns whether such bootnode was persisted.
+pub fn remove_persisted_bootnode(node_data_dir: &Path, node_id: &NodeId) -> anyhow::Result<bool> {
+    let mut bootnodes = read_persisted_bootnodes(node_data_dir)?;
+    let persisted_count = bootnodes.len();
+    bootnodes.retain(|bootnode| &bootnode.node_id() != node_id);
+    if bootnodes.len() == persisted_count {
+        return Ok(false);
+    }
+    write_persisted_bootnodes(node_data_dir, &bootnodes)?;
+    Ok(true)
+}
+
+fn write_persisted_bootnodes(
+    node_data_dir: &Path,
+    bootnodes: &[Enr<CombinedKey>],
+) -> anyhow::Result<()> {
+    let contents = bootnodes
+        .iter()
+        .map(|enr| enr.to_base64())
+        .collect::<Vec<_>>()
+        .join("\n");
+    fs::write(node_data_dir.join(BOOTNODES_FILE_NAME), contents)?;
+    Ok(())
+}
+
+/// Returns the ENRs of the routing tables that were persisted to the node data dir, by subnetwork.
+/// Invalid entries are skipped.
+pub fn read_persisted_routing_tables(
+    node_data_dir: &Path,
+) -> anyhow::Result<BTreeMap<String, Vec<Enr<CombinedKey>>>> {
+    let routing_tables_file = node_data_dir.join(ROUTING_TABLES_FILE_NAME);
+    if !routing_tables_file.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let routing_tables: BTreeMap<String, Vec<String>> =
+        serde_json::from_slice(&fs::read(routing_tables_file)?)?;
+    let routing_tables = routing_tables
+        .into_iter()
+        .map(|(subnetwork, enrs)| {
+            let enrs = enrs
+                .iter()
+                .filter_map(|enr| match Enr::from_str(enr) {
+                    Ok(enr) => Some(enr),
+                    Err(err) => {
+                        warn!(%subnetwork, %enr, error = %err, "Skipping invalid persisted ENR");
+                        None
+                    }
+                })
+                .collect();
+            (subnetwork, enrs)
+        })
+        .collect();
+    Ok(routing_tables)
+}
+
+/// Persists the ENRs of the routing tables to the node data dir, by subnetwork, replacing the
+/// previously persisted ones. The file is replaced at once, so that it can be copied while the
+/// node is running.
+pub fn persist_routing_tables(
+    node_data_dir: &Path,
+    routing_tables: &BTreeMap<String, Vec<Enr<CombinedKey>>>,
+) -> anyhow::Result<()> {
+    let routing_tables = routing_tables
+        .iter()
+        .map(|(subnetwork, enrs)| {
+            let enrs = enrs.iter().map(|enr| enr.to_base64()).collect::<Vec<_>>();
+            (subnetwork, enrs)
+        })
+        .collect::<BTreeMap<_, _>>();
+    let routing_tables_file = node_data_dir.join(ROUTING_TABLES_FILE_NAME);
+    let temp_file = routing_tables_file.with_extension("json.tmp");
+    fs::write(&temp_file, serde_json::to_vec_pretty(&routing_tables)?)?;
+    fs::rename(temp_file, routing_tables_file)?;
+    Ok(())
+}
+
 #[cfg(test)]
 pub mod test {
     use serial_test::serial;
@@ -126,4 +239,70 @@ pub mod test {
         assert_eq!(app_pk, app_pk_2);
         temp_dir.close().unwrap();
     }
+
+    #[test]
+    fn persisted_bootnodes() {
+        let temp_dir = create_temp_test_dir().unwrap();
+        assert!(read_persisted_bootnodes(temp_dir.path())
+            .unwrap()
+            .is_empty());
+
+        let key = CombinedKey::generate_secp256k1();
+        let enr = Enr::builder().udp4(9000).build(&key).unwrap();
+        let other_enr = Enr::builder()
+            .udp4(9001)
+            .build(&CombinedKey::generate_secp256k1())
+            .unwrap();
+        persist_bootnode(temp_dir.path(), &enr).unwrap();
+        persist_bootnode(temp_dir.path(), &other_enr).unwrap();
+
+        // Persisting a newer ENR of the same node replaces the old one
+        let updated_enr = Enr::builder().udp4(9002).build(&key).unwrap();
+        persist_bootnode(temp_dir.path(), &updated_enr).unwrap();
+        assert_eq!(
+            read_persisted_bootnodes(temp_dir.path()).unwrap(),
+            vec![other_enr.clone(), updated_enr]
+        );
+
+        assert!(remove_persisted_bootnode(temp_dir.path(), &enr.node_id()).unwrap());
+        assert!(!remove_persisted_bootnode(temp_dir.path(), &enr.node_id()).unwrap());
+        assert_eq!(
+            read_persisted_bootnodes(temp_dir.path()).unwrap(),
+            vec![other_enr]
+        );
+        temp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn persisted_routing_tables() {
+        let temp_dir = create_temp_test_dir().unwrap();
+        assert!(read_persisted_routing_tables(temp_dir.path())
+            .unwrap()
+            .is_empty());
+
+        let enr = |port| {
+            Enr::builder()
+                .udp4(port)
+                .build(&CombinedKey::generate_secp256k1())
+                .unwrap()
+        };
+        let routing_tables = BTreeMap::from([
+            ("History".to_string(), vec![enr(9000), enr(9001)]),
+            ("Beacon".to_string(), vec![]),
+        ]);
+        persist_routing_tables(temp_dir.path(), &routing_tables).unwrap();
+        assert_eq!(
+            read_persisted_routing_tables(temp_dir.path()).unwrap(),
+            routing_tables
+        );
+
+        // Persisting the routing tables again replaces them.
+        let routing_tables = BTreeMap::from([("State".to_string(), vec![enr(9002)])]);
+        persist_routing_tables(temp_dir.path(), &routing_tables).unwrap();
+        assert_eq!(
+            read_persisted_routing_tables(temp_dir.path()).unwrap(),
+            routing_tables
+        );
+        temp_dir.close().unwrap();
+    }
 }
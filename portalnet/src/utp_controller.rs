@@ -1,10 +1,15 @@
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, io, sync::Arc, time::Duration};
 
 use anyhow::anyhow;
 use bytes::Bytes;
+use discv5::enr::NodeId;
 use lazy_static::lazy_static;
-use tokio::sync::{OwnedSemaphorePermit, Semaphore};
-use tracing::debug;
+use parking_lot::Mutex;
+use tokio::{
+    sync::{oneshot, OwnedSemaphorePermit, Semaphore},
+    time::timeout,
+};
+use tracing::{debug, warn};
 use trin_metrics::{
     labels::{UtpDirectionLabel, UtpOutcomeLabel},
     overlay::OverlayMetricsReporter,
@@ -12,18 +17,26 @@ use trin_metrics::{
 use utp_rs::{cid::ConnectionId, conn::ConnectionConfig, socket::UtpSocket};
 
 use crate::discovery::UtpEnr;
+
 /// UtpController is meant to be a container which contains all code related to/for managing uTP
 /// streams We are implementing this because we want the utils of controlling uTP connection to be
 /// as contained as it can, instead of extending overlay_service even more.
-/// Currently we are implementing this to control the max utp_transfer_limit
+/// Currently we are implementing this to control the max utp_transfer_limit and the max number of
+/// concurrent uTP streams. The rates of the uTP packets are limited by the `UtpThrottle`.
 /// But in the future this will be where we implement
 /// - thundering herd protection
 /// - killing bad uTP connections which won't send us data or is purposefully keeping the connection
 ///   open
 pub struct UtpController {
+    /// The max number of concurrent inbound and of concurrent outbound transfers.
+    utp_transfer_limit: usize,
     inbound_utp_transfer_semaphore: Arc<Semaphore>,
     outbound_utp_transfer_semaphore: Arc<Semaphore>,
+    /// Caps the number of uTP streams that are open at once, across both directions. The streams
+    /// above the cap wait for their turn.
+    concurrent_transfer_limiter: TransferLimiter,
     utp_socket: Arc<UtpSocket<UtpEnr>>,
+    active_transfers: ActiveTransfers,
     metrics: OverlayMetricsReporter,
 }
 
@@ -38,6 +51,11 @@ lazy_static! {
     };
 }
 
+/// How long a stream that we connect waits at most for its turn to open. The peer has already sent
+/// ACCEPT, or the connection id of the content, and waits for our connection for less than the idle
+/// timeout.
+const CONNECT_TRANSFER_PERMIT_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// An enum for deciding to initiate the uTP connection as connecting or accepting.
 /// The selection is specified in the Portal Wire spec, depending upon whether the
 /// data is being transferred inbound or outbound.
@@ -53,13 +71,53 @@ impl UtpController {
         metrics: OverlayMetricsReporter,
     ) -> Self {
         Self {
+            utp_transfer_limit,
             utp_socket,
             inbound_utp_transfer_semaphore: Arc::new(Semaphore::new(utp_transfer_limit)),
             outbound_utp_transfer_semaphore: Arc::new(Semaphore::new(utp_transfer_limit)),
+            // By default, the cap doesn't restrict the transfers that the inbound and outbound
+            // limits allow.
+            concurrent_transfer_limiter: TransferLimiter::new(2 * utp_transfer_limit),
+            active_transfers: ActiveTransfers::default(),
             metrics,
         }
     }
 
+    /// Returns the max number of concurrent inbound and of concurrent outbound transfers.
+    pub fn transfer_limit(&self) -> usize {
+        self.utp_transfer_limit
+    }
+
+    /// Returns the max number of uTP streams that are open at once.
+    pub fn concurrent_transfer_limit(&self) -> usize {
+        self.concurrent_transfer_limiter.limit()
+    }
+
+    /// Sets the max number of uTP streams that are open at once.
+    ///
+    /// If the limit is lowered below the number of open streams, these streams keep going, and new
+    /// streams wait until enough of them are done.
+    pub fn set_concurrent_transfer_limit(&self, limit: usize) {
+        self.concurrent_transfer_limiter.set_limit(limit)
+    }
+
+    /// Returns the number of uTP streams that are open.
+    pub fn active_transfer_count(&self) -> usize {
+        self.concurrent_transfer_limiter.active()
+    }
+
+    /// Force-closes the transfer with the peer over the uTP connection with the given id, which is
+    /// the connection id exchanged in the Portal wire messages.
+    ///
+    /// Returns `false` if no such transfer is in progress.
+    pub fn close_transfer(&self, peer: NodeId, connection_id: u16) -> bool {
+        let closed = self.active_transfers.close(&(peer, connection_id));
+        if closed {
+            warn!(%peer, connection_id, "Force-closing uTP transfer");
+        }
+        closed
+    }
+
     pub fn cid(&self, peer: UtpEnr, is_initiator: bool) -> ConnectionId<UtpEnr> {
         self.utp_socket.cid(peer, is_initiator)
     }
@@ -92,21 +150,84 @@ impl UtpController {
         }
     }
 
+    /// Non-blocking method to try and acquire a permit to open a uTP stream, within the limit of
+    /// streams that are open at once.
+    ///
+    /// The permit must be acquired before offering the peer a stream to connect to, by sending
+    /// ACCEPT or the connection id of the content, so that the peer never waits for our turn.
+    pub fn get_transfer_permit(&self) -> Option<TransferPermit> {
+        self.concurrent_transfer_limiter.try_acquire()
+    }
+
+    /// Waits for our turn to open a stream that we connect, for a bounded time.
+    async fn connect_transfer_permit(
+        &self,
+        cid: &ConnectionId<UtpEnr>,
+        direction: UtpDirectionLabel,
+    ) -> Option<TransferPermit> {
+        match timeout(
+            CONNECT_TRANSFER_PERMIT_TIMEOUT,
+            self.concurrent_transfer_limiter.acquire(),
+        )
+        .await
+        {
+            Ok(transfer_permit) => Some(transfer_permit),
+            Err(_) => {
+                self.metrics
+                    .report_utp_outcome(direction, UtpOutcomeLabel::FailedConnection);
+                debug!(
+                    cid.send,
+                    cid.recv,
+                    peer = ?cid.peer.client(),
+                    "Timed out waiting for the limit of concurrent uTP transfers",
+                );
+                None
+            }
+        }
+    }
+
     pub async fn connect_inbound_stream(&self, cid: ConnectionId<UtpEnr>) -> anyhow::Result<Bytes> {
-        self.inbound_stream(cid, UtpConnectionSide::Connect).await
+        let transfer_permit = self
+            .connect_transfer_permit(&cid, UtpDirectionLabel::Inbound)
+            .await
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unable to locate content on the network: too many concurrent uTP transfers"
+                )
+            })?;
+        self.inbound_stream(cid, UtpConnectionSide::Connect, transfer_permit)
+            .await
     }
 
-    pub async fn accept_inbound_stream(&self, cid: ConnectionId<UtpEnr>) -> anyhow::Result<Bytes> {
-        self.inbound_stream(cid, UtpConnectionSide::Accept).await
+    /// Accepts the inbound stream, with the permit acquired before sending ACCEPT.
+    pub async fn accept_inbound_stream(
+        &self,
+        cid: ConnectionId<UtpEnr>,
+        transfer_permit: TransferPermit,
+    ) -> anyhow::Result<Bytes> {
+        self.inbound_stream(cid, UtpConnectionSide::Accept, transfer_permit)
+            .await
     }
 
     pub async fn connect_outbound_stream(&self, cid: ConnectionId<UtpEnr>, data: &[u8]) -> bool {
-        self.outbound_stream(cid, data, UtpConnectionSide::Connect)
+        let Some(transfer_permit) = self
+            .connect_transfer_permit(&cid, UtpDirectionLabel::Outbound)
+            .await
+        else {
+            return false;
+        };
+        self.outbound_stream(cid, data, UtpConnectionSide::Connect, transfer_permit)
             .await
     }
 
-    pub async fn accept_outbound_stream(&self, cid: ConnectionId<UtpEnr>, data: &[u8]) -> bool {
-        self.outbound_stream(cid, data, UtpConnectionSide::Accept)
+    /// Accepts the outbound stream, with the permit acquired before sending the connection id.
+    pub async fn accept_outbound_stream(
+        &self,
+        cid: ConnectionId<UtpEnr>,
+        data: &[u8],
+        transfer_permit: TransferPermit,
+    ) -> bool {
+        self.outbound_stream(cid, data, UtpConnectionSide::Accept, transfer_permit)
             .await
     }
 
@@ -114,6 +235,7 @@ impl UtpController {
         &self,
         cid: ConnectionId<UtpEnr>,
         side: UtpConnectionSide,
+        _transfer_permit: TransferPermit,
     ) -> anyhow::Result<Bytes> {
         // Wait for an incoming connection with the given CID. Then, read the data from the uTP
         // stream.
@@ -142,8 +264,17 @@ impl UtpController {
             anyhow!("Unable to locate content on the network: unable to {message}")
         })?;
 
+        let (registration, close_rx) = self.register_transfer(&cid, &side);
         let mut data = vec![];
-        stream.read_to_eof(&mut data).await
+        let read_result = tokio::select! {
+            result = stream.read_to_eof(&mut data) => result,
+            Ok(()) = close_rx => Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "transfer force-closed",
+            )),
+        };
+        drop(registration);
+        read_result
             .map_err(|err| {
                 self.metrics
                     .report_utp_outcome(UtpDirectionLabel::Inbound, UtpOutcomeLabel::FailedDataTx);
@@ -164,6 +295,7 @@ impl UtpController {
         cid: ConnectionId<UtpEnr>,
         data: &[u8],
         side: UtpConnectionSide,
+        _transfer_permit: TransferPermit,
     ) -> bool {
         self.metrics
             .report_utp_active_inc(UtpDirectionLabel::Outbound);
@@ -199,7 +331,16 @@ impl UtpController {
             }
         };
 
-        match stream.write(data).await {
+        let (registration, close_rx) = self.register_transfer(&cid, &side);
+        let write_result = tokio::select! {
+            result = stream.write(data) => result,
+            Ok(()) = close_rx => Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "transfer force-closed",
+            )),
+        };
+        drop(registration);
+        match write_result {
             Ok(write_size) => {
                 if write_size != data.len() {
                     self.metrics.report_utp_outcome(
@@ -247,4 +388,295 @@ impl UtpController {
             .report_utp_outcome(UtpDirectionLabel::Outbound, UtpOutcomeLabel::Success);
         true
     }
+
+    /// Registers a transfer over the connection, so that it can be force-closed until the
+    /// returned registration is dropped. Also returns the receiver of the close signal.
+    fn register_transfer(
+        &self,
+        cid: &ConnectionId<UtpEnr>,
+        side: &UtpConnectionSide,
+    ) -> (TransferRegistration<'_>, oneshot::Receiver<()>) {
+        // The connection id in the Portal wire messages is the one on which the connecting side
+        // receives, and the accepting side sends.
+        let connection_id = match side {
+            UtpConnectionSide::Connect => cid.recv,
+            UtpConnectionSide::Accept => cid.send,
+        };
+        self.active_transfers
+            .register((cid.peer.node_id(), connection_id))
+    }
+}
+
+/// Limits the number of transfers that run at once. The transfers above the limit wait for a
+/// running transfer to finish, rather than fail.
+///
+/// The limit can be changed while transfers run. When it is lowered below the number of running
+/// transfers, the permits of the excess transfers are retired as they finish.
+struct TransferLimiter {
+    semaphore: Arc<Semaphore>,
+    state: Arc<Mutex<TransferLimiterState>>,
+}
+
+struct TransferLimiterState {
+    limit: usize,
+    /// The number of held permits that are retired, rather than returned, once released.
+    excess: usize,
+}
+
+/// A permit to run a transfer, held until the transfer is done.
+pub struct TransferPermit {
+    permit: Option<OwnedSemaphorePermit>,
+    state: Arc<Mutex<TransferLimiterState>>,
+}
+
+impl Drop for TransferPermit {
+    fn drop(&mut self) {
+        let Some(permit) = self.permit.take() else {
+            return;
+        };
+        let mut state = self.state.lock();
+        if state.excess > 0 {
+            state.excess -= 1;
+            permit.forget();
+        }
+    }
+}
+
+impl TransferLimiter {
+    fn new(limit: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit)),
+            state: Arc::new(Mutex::new(TransferLimiterState { limit, excess: 0 })),
+        }
+    }
+
+    fn limit(&self) -> usize {
+        self.state.lock().limit
+    }
+
+    /// Returns the number of held permits.
+    fn active(&self) -> usize {
+        let state = self.state.lock();
+        (state.limit + state.excess).saturating_sub(self.semaphore.available_permits())
+    }
+
+    fn set_limit(&self, limit: usize) {
+        let mut state = self.state.lock();
+        if limit >= state.limit {
+            // The excess permits are kept, rather than retired, before adding new ones.
+            let added = limit - state.limit;
+            let kept = added.min(state.excess);
+            state.excess -= kept;
+            self.semaphore.add_permits(added - kept);
+        } else {
+            let removed = state.limit - limit;
+            let forgotten = self.semaphore.forget_permits(removed);
+            state.excess += removed - forgotten;
+        }
+        state.limit = limit;
+    }
+
+    /// Waits until a transfer can start.
+    async fn acquire(&self) -> TransferPermit {
+        let permit = Arc::clone(&self.semaphore)
+            .acquire_owned()
+            .await
+            .expect("the transfer semaphore is never closed");
+        TransferPermit {
+            permit: Some(permit),
+            state: Arc::clone(&self.state),
+        }
+    }
+
+    /// Returns a permit if a transfer can start now.
+    fn try_acquire(&self) -> Option<TransferPermit> {
+        let permit = Arc::clone(&self.semaphore).try_acquire_owned().ok()?;
+        Some(TransferPermit {
+            permit: Some(permit),
+            state: Arc::clone(&self.state),
+        })
+    }
+}
+
+/// The transfers in progress, by peer and connection id, with the channels used to force-close
+/// them.
+#[derive(Default)]
+struct ActiveTransfers {
+    transfers: Mutex<TransfersByKey>,
+}
+
+/// The registered transfers, with the id of their registration, which is never reused.
+#[derive(Default)]
+struct TransfersByKey {
+    transfers: HashMap<(NodeId, u16), (u64, oneshot::Sender<()>)>,
+    next_registration_id: u64,
+}
+
+/// The registration of a transfer in [ActiveTransfers], which is removed once the transfer is
+/// done, whether it succeeded, failed or was cancelled.
+struct TransferRegistration<'a> {
+    transfers: &'a ActiveTransfers,
+    key: (NodeId, u16),
+    id: u64,
+}
+
+impl Drop for TransferRegistration<'_> {
+    fn drop(&mut self) {
+        // The connection id may have been reused by a later transfer, which must stay registered.
+        let mut transfers = self.transfers.transfers.lock();
+        if matches!(transfers.transfers.get(&self.key), Some((id, _)) if *id == self.id) {
+            transfers.transfers.remove(&self.key);
+        }
+    }
+}
+
+impl ActiveTransfers {
+    fn register(&self, key: (NodeId, u16)) -> (TransferRegistration<'_>, oneshot::Receiver<()>) {
+        let (close_tx, close_rx) = oneshot::channel();
+        let mut transfers = self.transfers.lock();
+        let id = transfers.next_registration_id;
+        transfers.next_registration_id += 1;
+        transfers.transfers.insert(key, (id, close_tx));
+        let registration = TransferRegistration {
+            transfers: self,
+            key,
+            id,
+        };
+        (registration, close_rx)
+    }
+
+    /// Sends the close signal to the transfer, and removes it. Returns `false` if no such transfer
+    /// is registered.
+    fn close(&self, key: &(NodeId, u16)) -> bool {
+        let Some((_, close_tx)) = self.transfers.lock().transfers.remove(key) else {
+            return false;
+        };
+        // The transfer may finish before receiving the signal, in which case there is nothing left
+        // to close.
+        let _ = close_tx.send(());
+        true
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.transfers.lock().transfers.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn transfer_limiter_queues_transfers_above_limit() {
+        let limiter = TransferLimiter::new(1);
+        let first = limiter.acquire().await;
+        assert_eq!(limiter.active(), 1);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), limiter.acquire())
+                .await
+                .is_err(),
+            "The second transfer should wait for the first one"
+        );
+
+        drop(first);
+        let _second = limiter.acquire().await;
+        assert_eq!(limiter.active(), 1);
+    }
+
+    #[tokio::test]
+    async fn transfer_limiter_try_acquire_does_not_wait() {
+        let limiter = TransferLimiter::new(1);
+        let first = limiter
+            .try_acquire()
+            .expect("A transfer should start below the limit");
+        assert!(limiter.try_acquire().is_none());
+        assert_eq!(limiter.active(), 1);
+
+        drop(first);
+        assert!(limiter.try_acquire().is_some());
+
+        // Retired permits don't let new transfers start.
+        let first = limiter
+            .try_acquire()
+            .expect("A transfer should start below the limit");
+        limiter.set_limit(0);
+        drop(first);
+        assert!(limiter.try_acquire().is_none());
+        assert_eq!(limiter.active(), 0);
+    }
+
+    #[tokio::test]
+    async fn transfer_limiter_limit_can_be_changed() {
+        let limiter = TransferLimiter::new(2);
+        let first = limiter.acquire().await;
+        let second = limiter.acquire().await;
+
+        // The running transfers keep going, and their permits are retired once they are done.
+        limiter.set_limit(1);
+        assert_eq!(limiter.limit(), 1);
+        assert_eq!(limiter.active(), 2);
+        drop(first);
+        assert_eq!(limiter.active(), 1);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), limiter.acquire())
+                .await
+                .is_err(),
+            "No transfer should start while the limit is reached"
+        );
+        drop(second);
+        assert_eq!(limiter.active(), 0);
+
+        limiter.set_limit(3);
+        let _permits = [
+            limiter.acquire().await,
+            limiter.acquire().await,
+            limiter.acquire().await,
+        ];
+        assert_eq!(limiter.active(), 3);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), limiter.acquire())
+                .await
+                .is_err(),
+            "No transfer should start while the limit is reached"
+        );
+    }
+
+    #[test]
+    fn finished_transfers_are_unregistered() {
+        let transfers = ActiveTransfers::default();
+        let key = (NodeId::random(), 1);
+        let (registration, _close_rx) = transfers.register(key);
+        assert_eq!(transfers.len(), 1);
+
+        drop(registration);
+        assert_eq!(transfers.len(), 0);
+        assert!(!transfers.close(&key));
+    }
+
+    #[test]
+    fn finished_transfer_keeps_later_transfer_of_same_key() {
+        let transfers = ActiveTransfers::default();
+        let key = (NodeId::random(), 1);
+        let (registration, _close_rx) = transfers.register(key);
+        let (_later_registration, _later_close_rx) = transfers.register(key);
+
+        drop(registration);
+        assert_eq!(transfers.len(), 1);
+        assert!(transfers.close(&key));
+    }
+
+    #[tokio::test]
+    async fn close_signals_and_unregisters_transfer() {
+        let transfers = ActiveTransfers::default();
+        let key = (NodeId::random(), 1);
+        let (_registration, close_rx) = transfers.register(key);
+        let (_other_registration, _other_close_rx) = transfers.register((NodeId::random(), 1));
+
+        assert!(!transfers.close(&(key.0, 2)));
+        assert!(transfers.close(&key));
+        assert!(close_rx.await.is_ok());
+        assert_eq!(transfers.len(), 1);
+        assert!(!transfers.close(&key));
+    }
 }
@@ -0,0 +1,306 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use ethportal_api::types::portal::{UtpStatsInfo, UtpThrottleStats};
+use parking_lot::Mutex;
+
+use crate::socket::is_utp_data_packet;
+
+/// The duration of traffic at the max rate that may pass at once, after an idle period.
+const BURST_DURATION: Duration = Duration::from_millis(100);
+
+/// The min number of bytes that may pass at once, so that a full uTP packet always fits.
+const MIN_BURST_SIZE: f64 = 4096.0;
+
+/// Limits the rates at which uTP packets are sent and received, across all the uTP transfers of
+/// all the subnetworks.
+///
+/// Data packets are delayed to stay under the limits. Control packets, e.g. ACKs, count towards the
+/// limits but are never delayed, so that they don't wait behind bulk data.
+///
+/// The limits are set in kilobits per second on startup, and the upload limit can be changed at
+/// runtime in bytes per second, e.g. with the SetBandwidthLimit endpoint.
+pub struct UtpThrottle {
+    upload: DirectionThrottle,
+    download: DirectionThrottle,
+}
+
+impl UtpThrottle {
+    /// Creates a throttle with the max rates, in kilobits per second. 0 means unlimited.
+    pub fn new(max_up_kbps: u64, max_down_kbps: u64) -> Self {
+        Self {
+            upload: DirectionThrottle::new(kbps_to_bytes_per_sec(max_up_kbps)),
+            download: DirectionThrottle::new(kbps_to_bytes_per_sec(max_down_kbps)),
+        }
+    }
+
+    /// Returns the max upload rate in bytes per second, or `None` if unlimited.
+    pub fn upload_limit(&self) -> Option<u64> {
+        self.upload.limit()
+    }
+
+    /// Sets the max upload rate in bytes per second. `None` or 0 removes the limit.
+    ///
+    /// The new limit applies to the packets that are sent from now on, including the ones of the
+    /// transfers that are already in progress.
+    pub fn set_upload_limit(&self, bytes_per_sec: Option<u64>) {
+        self.upload.set_limit(bytes_per_sec)
+    }
+
+    /// Records the packet as sent, and returns how long it has to wait before it is sent, or
+    /// `None` if it is a control packet, which is sent right away.
+    pub fn reserve_upload(&self, packet: &[u8]) -> Option<Duration> {
+        self.upload.reserve(packet)
+    }
+
+    /// Records the packet as received, and returns how long it has to wait before it is handed to
+    /// the uTP transfer, or `None` if it is a control packet, which is handed over right away.
+    pub fn reserve_download(&self, packet: &[u8]) -> Option<Duration> {
+        self.download.reserve(packet)
+    }
+
+    pub fn stats(&self) -> UtpStatsInfo {
+        UtpStatsInfo {
+            upload: self.upload.stats(),
+            download: self.download.stats(),
+        }
+    }
+}
+
+/// Returns the rate in bytes per second, or `None` if the rate of 0 means unlimited.
+fn kbps_to_bytes_per_sec(kbps: u64) -> Option<u64> {
+    // 1 kilobit is 125 bytes.
+    (kbps > 0).then_some(kbps * 125)
+}
+
+/// Limits the rate of the uTP packets in one direction.
+struct DirectionThrottle {
+    /// The bucket of the bytes that may pass, `None` if unlimited.
+    bucket: Mutex<Option<TokenBucket>>,
+    bytes: AtomicU64,
+    throttled_packets: AtomicU64,
+}
+
+impl DirectionThrottle {
+    fn new(bytes_per_sec: Option<u64>) -> Self {
+        Self {
+            bucket: Mutex::new(new_bucket(bytes_per_sec)),
+            bytes: AtomicU64::new(0),
+            throttled_packets: AtomicU64::new(0),
+        }
+    }
+
+    fn limit(&self) -> Option<u64> {
+        self.bucket.lock().as_ref().map(|bucket| bucket.rate as u64)
+    }
+
+    fn set_limit(&self, bytes_per_sec: Option<u64>) {
+        *self.bucket.lock() = new_bucket(bytes_per_sec);
+    }
+
+    fn reserve(&self, packet: &[u8]) -> Option<Duration> {
+        self.bytes.fetch_add(packet.len() as u64, Ordering::Relaxed);
+        let is_data = is_utp_data_packet(packet);
+        let delay = match self.bucket.lock().as_mut() {
+            Some(bucket) => bucket.take(packet.len(), Instant::now()),
+            None => Duration::ZERO,
+        };
+        if !is_data {
+            return None;
+        }
+        if !delay.is_zero() {
+            self.throttled_packets.fetch_add(1, Ordering::Relaxed);
+        }
+        Some(delay)
+    }
+
+    fn stats(&self) -> UtpThrottleStats {
+        let mut bucket = self.bucket.lock();
+        UtpThrottleStats {
+            // The limits that are set in bytes per second are rounded up to whole kilobits.
+            max_kbps: bucket
+                .as_ref()
+                .map(|bucket| (bucket.rate as u64 * 8).div_ceil(1000)),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            throttled_packets: self.throttled_packets.load(Ordering::Relaxed),
+            utilization: bucket
+                .as_mut()
+                .map_or(0.0, |bucket| bucket.utilization(Instant::now())),
+        }
+    }
+}
+
+/// Returns the bucket of the rate in bytes per second, or `None` if unlimited.
+fn new_bucket(bytes_per_sec: Option<u64>) -> Option<TokenBucket> {
+    bytes_per_sec
+        .filter(|bytes_per_sec| *bytes_per_sec > 0)
+        .map(|bytes_per_sec| TokenBucket::new(bytes_per_sec as f64, Instant::now()))
+}
+
+/// A token bucket, in bytes, which is refilled at a constant rate.
+///
+/// Bytes are always taken, even if the bucket doesn't have enough of them. The bucket then goes
+/// into debt, and the bytes have to wait until the debt is repaid.
+struct TokenBucket {
+    /// The refill rate, in bytes per second.
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, now: Instant) -> Self {
+        let capacity = (rate * BURST_DURATION.as_secs_f64()).max(MIN_BURST_SIZE);
+        Self {
+            rate,
+            capacity,
+            tokens: capacity,
+            updated_at: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.capacity);
+        self.updated_at = now;
+    }
+
+    /// Takes the bytes, and returns how long they have to wait until the bucket is out of debt.
+    fn take(&mut self, bytes: usize, now: Instant) -> Duration {
+        self.refill(now);
+        self.tokens -= bytes as f64;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+
+    /// Returns the share of the bucket that is used, from 0 (full) to 1 (empty or in debt).
+    fn utilization(&mut self, now: Instant) -> f64 {
+        self.refill(now);
+        ((self.capacity - self.tokens) / self.capacity).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    /// Returns a uTP packet of the packet type, with a payload of `payload_size` bytes.
+    fn utp_packet(packet_type: u8, payload_size: usize) -> Vec<u8> {
+        let mut packet = vec![(packet_type << 4) | 1];
+        packet.resize(20 + payload_size, 0);
+        packet
+    }
+
+    fn assert_delay(delay: Duration, expected: Duration) {
+        assert!(
+            delay.abs_diff(expected) < Duration::from_micros(10),
+            "Unexpected delay: {delay:?}, expected {expected:?}"
+        );
+    }
+
+    #[test]
+    fn token_bucket_delays_bytes_above_rate() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(10_000.0, start);
+        assert_eq!(bucket.take(4096, start), Duration::ZERO);
+        assert_eq!(bucket.utilization(start), 1.0);
+        // The bucket is empty, so 1000 more bytes wait for 100ms.
+        assert_delay(bucket.take(1000, start), Duration::from_millis(100));
+        assert_delay(
+            bucket.take(1000, start + Duration::from_millis(50)),
+            Duration::from_millis(150),
+        );
+
+        // Once the debt is repaid, the bucket refills up to its capacity.
+        let later = start + Duration::from_secs(10);
+        assert_eq!(bucket.utilization(later), 0.0);
+        assert_eq!(bucket.take(4096, later), Duration::ZERO);
+    }
+
+    #[test]
+    fn unlimited_throttle_does_not_delay() {
+        let throttle = UtpThrottle::new(0, 0);
+        for _ in 0..100 {
+            assert_eq!(
+                throttle.reserve_upload(&utp_packet(0, 1000)),
+                Some(Duration::ZERO)
+            );
+        }
+        assert_eq!(throttle.reserve_download(&utp_packet(2, 0)), None);
+
+        let stats = throttle.stats();
+        assert_eq!(stats.upload.max_kbps, None);
+        assert_eq!(stats.upload.bytes, 100 * 1020);
+        assert_eq!(stats.upload.throttled_packets, 0);
+        assert_eq!(stats.upload.utilization, 0.0);
+        assert_eq!(stats.download.bytes, 20);
+    }
+
+    #[test]
+    fn control_packets_are_not_delayed() {
+        // 80 kbps is 10 KB/s.
+        let throttle = UtpThrottle::new(80, 0);
+        for _ in 0..4 {
+            throttle.reserve_upload(&utp_packet(0, 1000));
+        }
+        let delay = throttle.reserve_upload(&utp_packet(0, 1000)).unwrap();
+        assert!(delay > Duration::ZERO);
+
+        // ACKs (ST_STATE) and the other control packets pass right away.
+        for packet_type in 1..=4 {
+            assert_eq!(throttle.reserve_upload(&utp_packet(packet_type, 0)), None);
+        }
+        // The download isn't limited by the upload limit.
+        assert_eq!(
+            throttle.reserve_download(&utp_packet(0, 1000)),
+            Some(Duration::ZERO)
+        );
+
+        let stats = throttle.stats();
+        assert_eq!(stats.upload.max_kbps, Some(80));
+        assert!(stats.upload.throttled_packets >= 1);
+        assert_eq!(stats.upload.utilization, 1.0);
+        assert_eq!(stats.download.throttled_packets, 0);
+    }
+
+    #[test]
+    fn upload_limit_can_be_changed() {
+        let throttle = UtpThrottle::new(80, 0);
+        assert_eq!(throttle.upload_limit(), Some(10_000));
+        assert_eq!(throttle.stats().upload.max_kbps, Some(80));
+
+        // Removing the limit stops delaying the data packets right away.
+        throttle.set_upload_limit(None);
+        assert_eq!(throttle.upload_limit(), None);
+        for _ in 0..100 {
+            assert_eq!(
+                throttle.reserve_upload(&utp_packet(0, 1000)),
+                Some(Duration::ZERO)
+            );
+        }
+        assert_eq!(throttle.stats().upload.max_kbps, None);
+
+        // A limit that isn't a whole number of kilobits is reported rounded up.
+        throttle.set_upload_limit(Some(1_000));
+        assert_eq!(throttle.upload_limit(), Some(1_000));
+        assert_eq!(throttle.stats().upload.max_kbps, Some(8));
+        throttle.set_upload_limit(Some(1_001));
+        assert_eq!(throttle.stats().upload.max_kbps, Some(9));
+        for _ in 0..4 {
+            throttle.reserve_upload(&utp_packet(0, 1000));
+        }
+        assert!(throttle.reserve_upload(&utp_packet(0, 1000)).unwrap() > Duration::ZERO);
+
+        throttle.set_upload_limit(Some(0));
+        assert_eq!(throttle.upload_limit(), None);
+        // The download limit is unchanged.
+        assert_eq!(throttle.stats().download.max_kbps, None);
+    }
+}
@@ -0,0 +1,280 @@
+//! Opt-in recording of the portal wire requests and responses exchanged with peers.
+//!
+//! Recordings are meant for reproducing lookup bugs: they can be replayed against a fresh node
+//! with `trin replay`. Each record is stored as a big-endian `u32` length, followed by the record
+//! encoded as JSON. Recorded messages contain the node ids of the peers, and ENRs (e.g. in NODES
+//! responses), but no other sensitive data is expected.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock,
+    },
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use alloy::primitives::Bytes;
+use crossbeam_channel::{Receiver, Sender, TrySendError};
+use discv5::enr::NodeId;
+use ethportal_api::types::{
+    network::Subnetwork,
+    portal_wire::{Message, MessageDecodeError},
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, warn};
+
+/// The max number of records waiting to be written. Records are dropped while the queue is full,
+/// so that recording never slows down the overlay.
+const RECORD_QUEUE_SIZE: usize = 1024;
+
+static WIRE_RECORDER: OnceLock<WireRecorder> = OnceLock::new();
+
+/// Whether a request was received from, or sent to the peer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WireDirection {
+    Inbound,
+    Outbound,
+}
+
+/// A request exchanged with a peer, and its response.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WireRecord {
+    /// Milliseconds since the unix epoch, at which the exchange completed
+    pub timestamp_ms: u64,
+    pub direction: WireDirection,
+    /// The subnetwork, as its cli argument
+    pub subnetwork: String,
+    /// The remote peer
+    pub peer: NodeId,
+    /// The SSZ encoded request message
+    pub request: Bytes,
+    /// The SSZ encoded response message, or why there was none
+    pub response: Result<Bytes, String>,
+}
+
+impl WireRecord {
+    /// Decodes the request message.
+    pub fn request_message(&self) -> Result<Message, MessageDecodeError> {
+        Message::try_from(self.request.to_vec())
+    }
+
+    /// Decodes the response message, if there was one.
+    pub fn response_message(&self) -> Option<Result<Message, MessageDecodeError>> {
+        self.response
+            .as_ref()
+            .ok()
+            .map(|response| Message::try_from(response.to_vec()))
+    }
+}
+
+struct WireRecorder {
+    tx: Sender<WireRecord>,
+    /// Cleared once the recording stops, e.g. because it reached its size cap.
+    recording: Arc<AtomicBool>,
+}
+
+/// Starts recording wire messages to `path` for the lifetime of the process. The recording stops
+/// once `max_bytes` were written.
+pub fn start(path: &Path, max_bytes: u64) -> io::Result<()> {
+    let writer = BufWriter::new(File::create(path)?);
+    let (tx, rx) = crossbeam_channel::bounded(RECORD_QUEUE_SIZE);
+    let recording = Arc::new(AtomicBool::new(true));
+    let recorder = WireRecorder {
+        tx,
+        recording: recording.clone(),
+    };
+    if WIRE_RECORDER.set(recorder).is_err() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "Wire recording was already started",
+        ));
+    }
+    thread::Builder::new()
+        .name("wire-recorder".to_string())
+        .spawn(move || {
+            if let Err(err) = write_records(rx, writer, max_bytes) {
+                error!(%err, "Error writing wire recording, stopping it");
+            }
+            recording.store(false, Ordering::Relaxed);
+        })?;
+    Ok(())
+}
+
+/// Returns whether wire messages are currently recorded. Callers should check this before
+/// encoding the messages to record.
+pub fn is_recording() -> bool {
+    WIRE_RECORDER
+        .get()
+        .is_some_and(|recorder| recorder.recording.load(Ordering::Relaxed))
+}
+
+/// Records a request exchanged with `peer`, and its response. Does nothing if wire messages aren't
+/// recorded.
+pub fn record(
+    direction: WireDirection,
+    subnetwork: Subnetwork,
+    peer: NodeId,
+    request: Vec<u8>,
+    response: Result<Vec<u8>, String>,
+) {
+    let Some(recorder) = WIRE_RECORDER.get() else {
+        return;
+    };
+    let record = WireRecord {
+        timestamp_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+        direction,
+        subnetwork: subnetwork.to_cli_arg(),
+        peer,
+        request: request.into(),
+        response: response.map(Bytes::from),
+    };
+    if let Err(TrySendError::Full(_)) = recorder.tx.try_send(record) {
+        warn!("Wire recording queue is full, dropping record");
+    }
+}
+
+fn write_records(
+    rx: Receiver<WireRecord>,
+    mut writer: impl Write,
+    max_bytes: u64,
+) -> io::Result<()> {
+    let mut written = 0;
+    for record in rx {
+        let encoded = serde_json::to_vec(&record)?;
+        if written + 4 + encoded.len() as u64 > max_bytes {
+            warn!(
+                max_bytes,
+                "Wire recording reached its size cap, stopping it"
+            );
+            return Ok(());
+        }
+        written += write_encoded_record(&mut writer, &encoded)?;
+    }
+    Ok(())
+}
+
+/// Writes a single record in the format of a wire recording. Returns the number of bytes written.
+pub fn write_record(writer: &mut impl Write, record: &WireRecord) -> io::Result<u64> {
+    write_encoded_record(writer, &serde_json::to_vec(record)?)
+}
+
+fn write_encoded_record(writer: &mut impl Write, encoded: &[u8]) -> io::Result<u64> {
+    writer.write_all(&(encoded.len() as u32).to_be_bytes())?;
+    writer.write_all(encoded)?;
+    writer.flush()?;
+    Ok(4 + encoded.len() as u64)
+}
+
+/// Reads all records of a wire recording. A truncated last record, e.g. because the node was
+/// killed while writing it, is ignored.
+pub fn read_records(path: &Path) -> io::Result<Vec<WireRecord>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut records = vec![];
+    loop {
+        let mut len = [0u8; 4];
+        match reader.read_exact(&mut len) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err),
+        }
+        let mut encoded = vec![0u8; u32::from_be_bytes(len) as usize];
+        match reader.read_exact(&mut encoded) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                warn!("Ignoring truncated last record of wire recording");
+                break;
+            }
+            Err(err) => return Err(err),
+        }
+        records.push(serde_json::from_slice(&encoded)?);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ethportal_api::types::portal_wire::{CustomPayload, Ping, Pong};
+    use ssz::Encode;
+
+    use super::*;
+
+    fn ping_record(enr_seq: u64) -> WireRecord {
+        let ping = Message::Ping(Ping {
+            enr_seq,
+            custom_payload: CustomPayload::from(vec![]),
+        });
+        let pong = Message::Pong(Pong {
+            enr_seq: 1,
+            custom_payload: CustomPayload::from(vec![]),
+        });
+        WireRecord {
+            timestamp_ms: 0,
+            direction: WireDirection::Inbound,
+            subnetwork: Subnetwork::History.to_cli_arg(),
+            peer: NodeId::random(),
+            request: ping.as_ssz_bytes().into(),
+            response: Ok(pong.as_ssz_bytes().into()),
+        }
+    }
+
+    fn write_to_file(records: Vec<WireRecord>, max_bytes: u64) -> tempfile::NamedTempFile {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        for record in records {
+            tx.send(record).unwrap();
+        }
+        drop(tx);
+        write_records(rx, file.as_file(), max_bytes).unwrap();
+        file
+    }
+
+    #[test]
+    fn records_round_trip() {
+        let mut failed = ping_record(2);
+        failed.direction = WireDirection::Outbound;
+        failed.response = Err("The request timed out".to_string());
+        let records = vec![ping_record(1), failed];
+
+        let file = write_to_file(records.clone(), u64::MAX);
+        let read = read_records(file.path()).unwrap();
+        assert_eq!(read, records);
+        assert!(matches!(
+            read[0].request_message().unwrap(),
+            Message::Ping(_)
+        ));
+        assert!(matches!(
+            read[0].response_message().unwrap().unwrap(),
+            Message::Pong(_)
+        ));
+        assert!(read[1].response_message().is_none());
+    }
+
+    #[test]
+    fn recording_stops_at_size_cap() {
+        let record_size = 4 + serde_json::to_vec(&ping_record(1)).unwrap().len() as u64;
+        let records = (0..10).map(ping_record).collect::<Vec<_>>();
+
+        let file = write_to_file(records.clone(), 3 * record_size + 1);
+        assert_eq!(read_records(file.path()).unwrap(), records[..3]);
+    }
+
+    #[test]
+    fn truncated_last_record_is_ignored() {
+        let records = vec![ping_record(1), ping_record(2)];
+        let file = write_to_file(records.clone(), u64::MAX);
+        let len = file.as_file().metadata().unwrap().len();
+        file.as_file().set_len(len - 5).unwrap();
+
+        assert_eq!(read_records(file.path()).unwrap(), records[..1]);
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use ethportal_api::types::{
+    jsonrpc::endpoints::BeaconEndpoint, network::Subnetwork, portal::PongInfo,
+};
+use tokio::time::{sleep, Instant};
+use trin_test_utils::{TestNetwork, TestNode};
+
+/// Returns the sequence number of the other node's ENR in the node's beacon routing table.
+fn table_enr_seq(node: &TestNode, other: &TestNode) -> Option<u64> {
+    node.routing_table(Subnetwork::Beacon)
+        .into_iter()
+        .find(|enr| enr.node_id() == other.enr().node_id())
+        .map(|enr| enr.seq())
+}
+
+#[test_log::test(tokio::test)]
+async fn pong_with_newer_enr_seq_updates_routing_table() {
+    let network = TestNetwork::start(2, &[Subnetwork::Beacon]).await.unwrap();
+    let (node, peer) = (network.node(0), network.node(1));
+    let old_enr = peer.enr();
+    assert_eq!(table_enr_seq(node, peer), Some(old_enr.seq()));
+
+    // The peer updates its ENR, and only announces the new sequence number in its pong.
+    peer.discovery.enr_insert("test", &[1]).unwrap();
+    let new_seq = peer.enr().seq();
+    assert!(new_seq > old_enr.seq());
+
+    let pong_info: PongInfo = serde_json::from_value(
+        node.beacon()
+            .request(BeaconEndpoint::Ping(old_enr.clone()))
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(pong_info.enr_seq, new_seq);
+    assert!(pong_info.new_enr_detected);
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while table_enr_seq(node, peer) != Some(new_seq) {
+        assert!(
+            Instant::now() < deadline,
+            "Routing table entry wasn't updated to the new ENR"
+        );
+        sleep(Duration::from_millis(10)).await;
+    }
+
+    // The new ENR is known now, even though the old one is pinged.
+    let pong_info: PongInfo = serde_json::from_value(
+        node.beacon()
+            .request(BeaconEndpoint::Ping(old_enr.clone()))
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    assert!(!pong_info.new_enr_detected);
+
+    network.shutdown().await;
+}
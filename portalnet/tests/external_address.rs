@@ -0,0 +1,104 @@
+use std::{
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::{Duration, Instant},
+};
+
+use ethportal_api::types::network::Subnetwork;
+use tokio::time::sleep;
+use trin_test_utils::{TestNetwork, TestNode};
+
+/// How long the external address takes at most to be advertised, and to reach the peers.
+const ADDRESS_CHANGE_TIMEOUT: Duration = Duration::from_secs(10);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Waits until the condition holds, panicking after [ADDRESS_CHANGE_TIMEOUT].
+async fn wait_until(description: &str, condition: impl Fn() -> bool) {
+    let deadline = Instant::now() + ADDRESS_CHANGE_TIMEOUT;
+    while !condition() {
+        assert!(
+            Instant::now() < deadline,
+            "Timed out waiting until {description}"
+        );
+        sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Reports the external address as discv5's address voting does, and waits until the node
+/// advertises it.
+async fn report_socket_update(node: &TestNode, socket_addr: SocketAddr) {
+    node.discovery.report_socket_update(socket_addr);
+    wait_until("the new address is advertised", || {
+        node.discovery.advertised_address().socket_addr == Some(socket_addr)
+    })
+    .await;
+}
+
+#[test_log::test(tokio::test)]
+async fn external_address_change_updates_enr() {
+    let network = TestNetwork::start(2, &[Subnetwork::Beacon]).await.unwrap();
+    let node = network.node(0);
+    let peer = network.node(1);
+    let old_enr = node.enr();
+    let old_address = node.discovery.advertised_address();
+    assert_eq!(
+        old_address.socket_addr,
+        old_enr.udp4_socket().map(SocketAddr::V4)
+    );
+    assert_eq!(old_address.changed_at, None);
+
+    // The address voting of discv5 converges on a new address.
+    let new_socket_addr = SocketAddr::new(
+        Ipv4Addr::new(127, 0, 0, 2).into(),
+        old_enr.udp4().unwrap() + 1,
+    );
+    report_socket_update(node, new_socket_addr).await;
+
+    let new_enr = node.enr();
+    assert!(new_enr.seq() > old_enr.seq());
+    assert_eq!(
+        new_enr.udp4_socket().map(SocketAddr::V4),
+        Some(new_socket_addr)
+    );
+    let new_address = node.discovery.advertised_address();
+    assert!(new_address.changed_at.is_some());
+
+    // The overlay pings the routing table peers, which request the new ENR.
+    wait_until("the peer's overlay learns the new ENR", || {
+        peer.routing_table(Subnetwork::Beacon)
+            .iter()
+            .any(|enr| enr.node_id() == new_enr.node_id() && enr.seq() == new_enr.seq())
+    })
+    .await;
+
+    // The same address again isn't a change.
+    assert_eq!(
+        node.discovery.handle_socket_update(new_socket_addr).await,
+        0
+    );
+    assert_eq!(node.enr().seq(), new_enr.seq());
+    assert_eq!(node.discovery.advertised_address(), new_address);
+
+    network.shutdown().await;
+}
+
+#[test_log::test(tokio::test)]
+async fn external_ipv6_address_is_advertised() {
+    let network = TestNetwork::start(1, &[Subnetwork::Beacon]).await.unwrap();
+    let node = network.node(0);
+    let old_enr = node.enr();
+    assert_eq!(old_enr.udp6_socket(), None);
+
+    let new_socket_addr = SocketAddr::new(Ipv6Addr::LOCALHOST.into(), old_enr.udp4().unwrap());
+    report_socket_update(node, new_socket_addr).await;
+
+    let new_enr = node.enr();
+    assert!(new_enr.seq() > old_enr.seq());
+    assert_eq!(
+        new_enr.udp6_socket().map(SocketAddr::V6),
+        Some(new_socket_addr)
+    );
+    assert!(node.discovery.advertised_address().changed_at.is_some());
+
+    network.shutdown().await;
+}
@@ -0,0 +1,33 @@
+use ethportal_api::types::{
+    jsonrpc::endpoints::HistoryEndpoint, network::Subnetwork, portal::FindNodesInfo,
+};
+use trin_test_utils::TestNetwork;
+
+#[test_log::test(tokio::test)]
+async fn find_nodes_response_is_truncated_to_max_results() {
+    let network = TestNetwork::start(3, &[Subnetwork::History]).await.unwrap();
+    let (node, peer) = (network.node(0), network.node(1));
+    let find_nodes = |max_results| async move {
+        let response = node
+            .history()
+            .request(HistoryEndpoint::FindNodes(
+                peer.enr(),
+                (0..=256).collect(),
+                max_results,
+            ))
+            .await
+            .unwrap();
+        serde_json::from_value::<FindNodesInfo>(response).unwrap()
+    };
+
+    // The peer returns itself and the other nodes that it bonded with.
+    let all_enrs = find_nodes(None).await;
+    assert!(all_enrs.len() >= 2, "{all_enrs:?}");
+
+    let enrs = find_nodes(Some(1)).await;
+    assert_eq!(enrs, all_enrs[..1]);
+    let enrs = find_nodes(Some(all_enrs.len() as u64 + 1)).await;
+    assert_eq!(enrs, all_enrs);
+
+    network.shutdown().await;
+}
@@ -0,0 +1,311 @@
+use std::time::Duration;
+
+use alloy::primitives::b256;
+use ethportal_api::{
+    consensus::fork::ForkName,
+    light_client::optimistic_update::LightClientOptimisticUpdate,
+    types::{
+        content_key::beacon::LightClientOptimisticUpdateKey,
+        content_value::beacon::ForkVersionedLightClientOptimisticUpdate,
+        execution::header_with_proof::HeaderWithProof,
+        jsonrpc::endpoints::{BeaconEndpoint, HistoryEndpoint},
+        network::Subnetwork,
+    },
+    BeaconContentKey, BeaconContentValue, ContentValue, HistoryContentKey, HistoryContentValue,
+};
+use portalnet::gossip::GossipResult;
+use ssz::Decode;
+use trin_test_utils::{
+    assert_routing_contains, seed_content, wait_for_gossip, TestContentKey, TestNetwork, TestNode,
+    TestNodeConfig,
+};
+
+const GOSSIP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Pre-merge header with proof of block #15040641.
+fn history_header_fixture() -> (HistoryContentKey, HistoryContentValue) {
+    history_fixture(15040641, "header")
+}
+
+/// Pre-merge header with proof, block body or receipts of the block, read from the `header`,
+/// `body` or `receipts` asset.
+fn history_fixture(block_number: u64, asset: &str) -> (HistoryContentKey, HistoryContentValue) {
+    let read_asset = |name| {
+        std::fs::read(format!(
+            "../test_assets/mainnet/large_content/{block_number}/{name}.bin"
+        ))
+        .expect("cannot find test asset")
+    };
+    let header = HeaderWithProof::from_ssz_bytes(&read_asset("header")).unwrap();
+    let block_hash = header.header.hash();
+    let content_key = match asset {
+        "header" => {
+            return (
+                HistoryContentKey::new_block_header_by_hash(block_hash),
+                HistoryContentValue::BlockHeaderWithProof(header),
+            )
+        }
+        "body" => HistoryContentKey::new_block_body(block_hash),
+        "receipts" => HistoryContentKey::new_block_receipts(block_hash),
+        _ => panic!("Unknown history asset: {asset}"),
+    };
+    let content_value = HistoryContentValue::decode(&content_key, &read_asset(asset)).unwrap();
+    (content_key, content_value)
+}
+
+/// Starts a node that isn't connected to any other node, with 1 MB of storage so that it soon
+/// drops content.
+async fn start_small_node() -> TestNode {
+    TestNode::start(TestNodeConfig {
+        storage_mb: 1,
+        // node id: 0x27128939ed60d6f4caef0374da15361a2c1cd6baa1a5bccebac1acd18f485900
+        private_key: Some(b256!(
+            "9ca7889c09ef1162132251b6284bd48e64bd3e71d75ea33b959c37be0582a2fd"
+        )),
+        ..TestNodeConfig::new(&[Subnetwork::History])
+    })
+    .await
+    .unwrap()
+}
+
+/// Stores the content through the Store endpoint of the node.
+async fn store(
+    node: &TestNode,
+    (content_key, content_value): &(HistoryContentKey, HistoryContentValue),
+) {
+    let stored = node
+        .history()
+        .request(HistoryEndpoint::Store(
+            content_key.clone(),
+            content_value.clone(),
+            None,
+            None,
+        ))
+        .await
+        .unwrap();
+    assert!(stored.as_bool().unwrap());
+}
+
+fn has_content(node: &TestNode, content_key: &HistoryContentKey) -> bool {
+    TestContentKey::get(node, content_key).unwrap().is_some()
+}
+
+/// Waits until the node has stored the content.
+async fn assert_received(
+    node: &TestNode,
+    (content_key, content_value): &(HistoryContentKey, HistoryContentValue),
+) {
+    let received = wait_for_gossip(node, content_key, GOSSIP_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(received, content_value.encode());
+}
+
+fn beacon_optimistic_update_fixture() -> (BeaconContentKey, BeaconContentValue) {
+    let update = std::fs::read(
+        "../test_assets/beacon/deneb/LightClientOptimisticUpdate/ssz_random/case_0/serialized.ssz_snappy",
+    )
+    .expect("cannot find test asset");
+    let update = snap::raw::Decoder::new().decompress_vec(&update).unwrap();
+    let update = LightClientOptimisticUpdate::from_ssz_bytes(&update, ForkName::Deneb).unwrap();
+    let content_key =
+        BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey {
+            signature_slot: *update.signature_slot(),
+        });
+    let content_value =
+        BeaconContentValue::LightClientOptimisticUpdate(ForkVersionedLightClientOptimisticUpdate {
+            fork_name: ForkName::Deneb,
+            update,
+        });
+    (content_key, content_value)
+}
+
+#[test_log::test(tokio::test)]
+async fn history_gossip_with_trace() {
+    let network = TestNetwork::start(3, &[Subnetwork::History]).await.unwrap();
+    let (gossiper, receivers) = (network.node(0), &network.nodes[1..]);
+    for receiver in receivers {
+        assert_routing_contains(gossiper, receiver, Subnetwork::History);
+    }
+
+    let (content_key, content_value) = history_header_fixture();
+    let result = gossiper
+        .history()
+        .request(HistoryEndpoint::TraceGossip(
+            content_key.clone(),
+            content_value.clone(),
+        ))
+        .await
+        .unwrap();
+    let result: GossipResult = serde_json::from_value(result).unwrap();
+    assert_eq!(result.offered.len(), 2);
+    // The first receiver may gossip the content on to the second one before our offer arrives,
+    // so not every offer is necessarily accepted.
+    assert!(!result.accepted.is_empty());
+    assert_eq!(result.transferred, result.accepted);
+
+    for receiver in receivers {
+        let received = wait_for_gossip(receiver, &content_key, GOSSIP_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(received, content_value.encode());
+    }
+
+    // Gossiping again isn't accepted, as all nodes already have the content
+    let result = gossiper
+        .history()
+        .request(HistoryEndpoint::TraceGossip(content_key, content_value))
+        .await
+        .unwrap();
+    let result: GossipResult = serde_json::from_value(result).unwrap();
+    assert_eq!(result.offered.len(), 2);
+    assert!(result.accepted.is_empty());
+    assert!(result.transferred.is_empty());
+    assert_eq!(result.declined.len(), 2);
+    assert!(result.failed.is_empty());
+
+    network.shutdown().await;
+}
+
+#[test_log::test(tokio::test)]
+async fn history_gossip_skips_nodes_with_content() {
+    let network = TestNetwork::start(3, &[Subnetwork::History]).await.unwrap();
+    let (content_key, content_value) = history_header_fixture();
+    seed_content(network.node(1), content_key.clone(), &content_value).unwrap();
+
+    let result = network
+        .node(0)
+        .history()
+        .request(HistoryEndpoint::TraceGossip(
+            content_key.clone(),
+            content_value.clone(),
+        ))
+        .await
+        .unwrap();
+    let result: GossipResult = serde_json::from_value(result).unwrap();
+    assert_eq!(result.offered.len(), 2);
+    assert_eq!(result.accepted, vec![network.node(2).enr()]);
+    assert_eq!(result.declined, vec![network.node(1).enr()]);
+
+    let received = wait_for_gossip(network.node(2), &content_key, GOSSIP_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(received, content_value.encode());
+
+    network.shutdown().await;
+}
+
+#[test_log::test(tokio::test)]
+async fn beacon_gossip_optimistic_update() {
+    let network = TestNetwork::start(2, &[Subnetwork::Beacon]).await.unwrap();
+    assert_routing_contains(network.node(0), network.node(1), Subnetwork::Beacon);
+    assert_routing_contains(network.node(1), network.node(0), Subnetwork::Beacon);
+
+    let (content_key, content_value) = beacon_optimistic_update_fixture();
+    let result = network
+        .node(0)
+        .beacon()
+        .request(BeaconEndpoint::Gossip(
+            content_key.clone(),
+            content_value.clone(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result, 1);
+
+    let received = wait_for_gossip(network.node(1), &content_key, GOSSIP_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(received, content_value.encode());
+
+    network.shutdown().await;
+}
+
+#[test_log::test(tokio::test)]
+async fn history_content_dropped_after_offer_is_gossiped() {
+    let network = TestNetwork::start(3, &[Subnetwork::History]).await.unwrap();
+    let small_node = start_small_node().await;
+
+    let header_1 = history_fixture(15040641, "header");
+    let receipts_1 = history_fixture(15040641, "receipts");
+    store(&small_node, &header_1).await;
+    store(&small_node, &receipts_1).await;
+    assert!(has_content(&small_node, &header_1.0));
+    assert!(has_content(&small_node, &receipts_1.0));
+    for node in &network.nodes {
+        assert!(!has_content(node, &header_1.0));
+        assert!(!has_content(node, &receipts_1.0));
+    }
+
+    // Connect the small node to the network, and offer it block 2, which it doesn't store locally.
+    for node in &network.nodes {
+        small_node.bond(node).await.unwrap();
+    }
+    let offerer = network.node(2);
+    let header_2 = history_fixture(15040708, "header");
+    let body_2 = history_fixture(15040708, "body");
+    for content in [&header_2, &body_2] {
+        offerer
+            .history()
+            .request(HistoryEndpoint::Offer(
+                small_node.enr(),
+                vec![content.clone()],
+            ))
+            .await
+            .unwrap();
+    }
+
+    // Storing block 2 makes the small node drop the receipts of block 1, which it gossips to the
+    // other nodes, along with block 2.
+    assert_received(&small_node, &body_2).await;
+    for node in &network.nodes {
+        assert_received(node, &body_2).await;
+        assert_received(node, &receipts_1).await;
+    }
+    // Only checked once all the gossip has been received.
+    assert!(!has_content(&small_node, &receipts_1.0));
+
+    small_node.shutdown().await;
+    network.shutdown().await;
+}
+
+#[test_log::test(tokio::test)]
+async fn history_content_dropped_after_find_content_is_gossiped() {
+    let network = TestNetwork::start(3, &[Subnetwork::History]).await.unwrap();
+    let small_node = start_small_node().await;
+
+    let receipts_1 = history_fixture(15040641, "receipts");
+    store(&small_node, &receipts_1).await;
+    let provider = network.node(2);
+    let body_2 = history_fixture(15040708, "body");
+    for content in [
+        &history_fixture(15040641, "header"),
+        &history_fixture(15040708, "header"),
+        &body_2,
+    ] {
+        store(provider, content).await;
+    }
+
+    // Connect the small node to the network, and let it find block 2, which it then stores.
+    for node in &network.nodes {
+        small_node.bond(node).await.unwrap();
+    }
+    small_node
+        .history()
+        .request(HistoryEndpoint::GetContent(body_2.0.clone()))
+        .await
+        .unwrap();
+
+    // Storing block 2 makes the small node drop the receipts of block 1, which it gossips to the
+    // other nodes, along with block 2.
+    assert_received(&small_node, &body_2).await;
+    for node in &network.nodes {
+        assert_received(node, &body_2).await;
+        assert_received(node, &receipts_1).await;
+    }
+    // Only checked once all the gossip has been received.
+    assert!(!has_content(&small_node, &receipts_1.0));
+
+    small_node.shutdown().await;
+    network.shutdown().await;
+}
@@ -0,0 +1,48 @@
+use ethportal_api::{
+    types::{
+        execution::header_with_proof::HeaderWithProof, jsonrpc::endpoints::HistoryEndpoint,
+        network::Subnetwork, portal::AcceptInfo,
+    },
+    HistoryContentKey, HistoryContentValue,
+};
+use ssz::Decode;
+use trin_test_utils::TestNetwork;
+
+/// Pre-merge header with proof of block #15040641.
+fn history_header_fixture() -> (HistoryContentKey, HistoryContentValue) {
+    let header = std::fs::read("../test_assets/mainnet/large_content/15040641/header.bin")
+        .expect("cannot find test asset");
+    let header = HeaderWithProof::from_ssz_bytes(&header).unwrap();
+    let content_key = HistoryContentKey::new_block_header_by_hash(header.header.hash());
+    (
+        content_key,
+        HistoryContentValue::BlockHeaderWithProof(header),
+    )
+}
+
+#[test_log::test(tokio::test)]
+async fn offer_returns_accept_bitfield_as_received() {
+    let network = TestNetwork::start(2, &[Subnetwork::History]).await.unwrap();
+    let (node, peer) = (network.node(0), network.node(1));
+    let (content_key, content_value) = history_header_fixture();
+
+    let result = node
+        .history()
+        .request(HistoryEndpoint::Offer(
+            peer.enr(),
+            vec![(content_key, content_value)],
+        ))
+        .await
+        .unwrap();
+    let info: AcceptInfo = serde_json::from_value(result).unwrap();
+    assert_eq!(info.content_keys.iter().collect::<Vec<_>>(), vec![true]);
+    // The peers use the baseline protocol version, so the peer sent the SSZ encoded bitlist: a
+    // single set bit, followed by the length bit.
+    assert_eq!(
+        info.accept_bitfield
+            .map(|accept_bitfield| accept_bitfield.to_vec()),
+        Some(vec![0x03])
+    );
+
+    network.shutdown().await;
+}
@@ -3,14 +3,17 @@ use std::{
     sync::Arc,
 };
 
-use discv5::TalkRequest;
+use alloy::primitives::B256;
+use discv5::{enr::CombinedKey, TalkRequest};
 use ethportal_api::{
     types::{
         content_key::overlay::IdentityContentKey,
         distance::XorMetric,
         enr::{Enr, SszEnr},
-        network::Subnetwork,
-        portal_wire::{Content, Message, MAINNET},
+        network::{Network, Subnetwork},
+        portal::{EnrSource, TracedPeerStatus},
+        portal_wire::{Content, Message, ANGELFOOD, MAINNET},
+        query_trace::QueryTrace,
     },
     utils::bytes::hex_encode_upper,
     OverlayContentKey,
@@ -19,15 +22,17 @@ use parking_lot::RwLock;
 use portalnet::{
     config::PortalnetConfig,
     discovery::{Discovery, Discv5UdpSocket},
+    events::PortalnetEvents,
     overlay::{
         config::{FindContentConfig, OverlayConfig},
         protocol::OverlayProtocol,
     },
 };
 use tokio::{
-    sync::{mpsc, mpsc::unbounded_channel, RwLock as TokioRwLock},
+    sync::{broadcast, mpsc, mpsc::unbounded_channel, RwLock as TokioRwLock},
     time::{self, Duration},
 };
+use trin_metrics::portalnet::PORTALNET_METRICS;
 use trin_storage::{ContentStore, DistanceFunction, MemoryContentStore};
 use trin_validation::{oracle::HeaderOracle, validator::MockValidator};
 use utp_rs::socket::UtpSocket;
@@ -263,3 +268,188 @@ async fn overlay_event_stream() {
 
     overlay.event_stream().await.unwrap();
 }
+
+#[tokio::test]
+async fn overlay_get_enr_of_local_node() {
+    let portal_config = PortalnetConfig {
+        no_stun: true,
+        no_upnp: true,
+        ..Default::default()
+    };
+    let discovery = Arc::new(Discovery::new(portal_config, MAINNET.clone()).unwrap());
+    let overlay = init_overlay(Arc::clone(&discovery), Subnetwork::History).await;
+
+    let local_enr = discovery.local_enr();
+    let (enr, source) = overlay.get_enr_with_source(local_enr.node_id()).unwrap();
+    assert_eq!(enr, local_enr);
+    assert_eq!(source, EnrSource::LocalNode);
+    assert_eq!(
+        overlay.lookup_enr(local_enr.node_id()).await.unwrap(),
+        local_enr
+    );
+}
+
+#[tokio::test]
+async fn overlay_get_enr_known_only_to_discv5() {
+    let portal_config = PortalnetConfig {
+        no_stun: true,
+        no_upnp: true,
+        ..Default::default()
+    };
+    let discovery = Arc::new(Discovery::new(portal_config, MAINNET.clone()).unwrap());
+    let overlay = init_overlay(Arc::clone(&discovery), Subnetwork::History).await;
+
+    let peer_enr = Enr::builder()
+        .ip4(Ipv4Addr::new(127, 0, 0, 1))
+        .udp4(8004)
+        .build(&CombinedKey::generate_secp256k1())
+        .unwrap();
+    let node_id = peer_enr.node_id();
+    assert!(overlay.get_enr(node_id).is_err());
+
+    // Add the ENR to the discv5 routing table only, not to the overlay routing table.
+    discovery.add_enr(peer_enr.clone()).unwrap();
+    let (enr, source) = overlay.get_enr_with_source(node_id).unwrap();
+    assert_eq!(enr, peer_enr);
+    assert_eq!(source, EnrSource::Discv5);
+}
+
+#[tokio::test]
+async fn overlay_analyze_trace() {
+    let protocol = Subnetwork::History;
+    let ip_addr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+
+    let mut overlays = vec![];
+    for port in [8005, 8006, 8035] {
+        let portal_config = PortalnetConfig {
+            listen_port: port,
+            external_addr: Some(SocketAddr::new(ip_addr, port)),
+            ..PortalnetConfig::default()
+        };
+        let mut discovery = Discovery::new(portal_config, MAINNET.clone()).unwrap();
+        let talk_req_rx = discovery.start().await.unwrap();
+        let overlay = Arc::new(init_overlay(Arc::new(discovery), protocol).await);
+        spawn_overlay(talk_req_rx, Arc::clone(&overlay)).await;
+        overlays.push(overlay);
+    }
+    let (overlay_one, overlay_two, overlay_three) = (&overlays[0], &overlays[1], &overlays[2]);
+
+    // Node two is added to node one's routing table by the ping.
+    overlay_one
+        .send_ping(overlay_two.local_enr())
+        .await
+        .unwrap();
+
+    // Trace in which node two responded with a peer that doesn't exist.
+    let nonexistent_enr = Enr::builder()
+        .ip4(Ipv4Addr::new(127, 0, 0, 1))
+        .udp4(8007)
+        .build(&CombinedKey::generate_secp256k1())
+        .unwrap();
+    let mut trace = QueryTrace::new(&overlay_one.local_enr(), B256::random());
+    trace.node_responded_with(
+        &overlay_two.local_enr(),
+        vec![&nonexistent_enr, &overlay_three.local_enr()],
+    );
+
+    let analysis = overlay_one.analyze_trace(&trace).await;
+    assert_eq!(analysis.peers.len(), 3);
+    assert_eq!(
+        analysis.peers[&overlay_two.local_enr().node_id()],
+        TracedPeerStatus {
+            responded_in_trace: true,
+            failure_in_trace: None,
+            in_routing_table: true,
+            reachable: true,
+        }
+    );
+    assert_eq!(
+        analysis.peers[&nonexistent_enr.node_id()],
+        TracedPeerStatus {
+            responded_in_trace: false,
+            failure_in_trace: None,
+            in_routing_table: false,
+            reachable: false,
+        }
+    );
+
+    // Node three responds to the ping of the analysis, but isn't added to the routing table.
+    let node_three = overlay_three.local_enr().node_id();
+    assert_eq!(
+        analysis.peers[&node_three],
+        TracedPeerStatus {
+            responded_in_trace: false,
+            failure_in_trace: None,
+            in_routing_table: false,
+            reachable: true,
+        }
+    );
+    assert!(!overlay_one
+        .table_entries_enr()
+        .iter()
+        .any(|enr| enr.node_id() == node_three));
+}
+
+#[tokio::test]
+async fn cross_network_talk_request_is_dropped() {
+    let ip_addr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+
+    // Node one is on mainnet, and dispatches its talk requests with the portalnet events handler.
+    let portal_config_one = PortalnetConfig {
+        listen_port: 8008,
+        external_addr: Some(SocketAddr::new(ip_addr, 8008)),
+        ..PortalnetConfig::default()
+    };
+    let mut discovery_one = Discovery::new(portal_config_one, MAINNET.clone()).unwrap();
+    let talk_req_rx = discovery_one.start().await.unwrap();
+    let discovery_one = Arc::new(discovery_one);
+    let (history_tx, mut history_rx) = mpsc::unbounded_channel();
+    let (_history_event_tx, history_event_rx) = broadcast::channel(1);
+    let (utp_tx, _utp_rx) = mpsc::unbounded_channel();
+    let events = PortalnetEvents::new(
+        talk_req_rx,
+        (Some(history_tx), Some(history_event_rx)),
+        (None, None),
+        (None, None),
+        utp_tx,
+        MAINNET.clone(),
+    )
+    .await;
+    tokio::spawn(events.start());
+
+    // Node two is on angelfood.
+    let portal_config_two = PortalnetConfig {
+        listen_port: 8009,
+        external_addr: Some(SocketAddr::new(ip_addr, 8009)),
+        bootnodes: vec![],
+        ..PortalnetConfig::default()
+    };
+    let mut discovery_two = Discovery::new(portal_config_two, ANGELFOOD.clone()).unwrap();
+    let _ = discovery_two.start().await.unwrap();
+
+    let cross_network_talk_requests = || {
+        PORTALNET_METRICS
+            .overlay()
+            .cross_network_talk_request_total
+            .with_label_values(&[&Network::Angelfood.to_string()])
+            .get()
+    };
+    let dropped_before = cross_network_talk_requests();
+
+    // The talk request uses the angelfood history protocol id, so node one drops it without
+    // serving it.
+    let result = discovery_two
+        .send_talk_req(discovery_one.local_enr(), Subnetwork::History, vec![0])
+        .await;
+    if let Ok(response) = result {
+        assert!(response.is_empty());
+    }
+    assert!(history_rx.try_recv().is_err());
+    assert_eq!(cross_network_talk_requests(), dropped_before + 1);
+
+    // Node two's ENR advertises angelfood, so node one refuses to add it.
+    let overlay_one = init_overlay(Arc::clone(&discovery_one), Subnetwork::History).await;
+    assert!(overlay_one
+        .add_enr(discovery_two.local_enr(), false)
+        .is_err());
+}
@@ -0,0 +1,177 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use ethportal_api::{
+    types::{network::Subnetwork, portal_wire::MAINNET},
+    utils::bytes::hex_encode_upper,
+};
+use portalnet::{
+    config::PortalnetConfig,
+    discovery::{Discovery, Discv5UdpSocket, UtpEnr},
+};
+use tokio::{
+    net::UdpSocket,
+    sync::{mpsc, RwLock},
+};
+use trin_validation::oracle::HeaderOracle;
+use utp_rs::{cid::ConnectionId, conn::ConnectionConfig, socket::UtpSocket};
+
+/// Starts a node on the port. Its uTP packets are exchanged on their own port, if `utp_port` is
+/// set, and otherwise share the discv5 socket, wrapped in TALKREQ messages.
+async fn start_node(port: u16, utp_port: Option<u16>) -> (Arc<Discovery>, Arc<UtpSocket<UtpEnr>>) {
+    let ip_addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    let portal_config = PortalnetConfig {
+        listen_port: port,
+        external_addr: Some(SocketAddr::new(ip_addr, port)),
+        utp_port,
+        ..PortalnetConfig::default()
+    };
+    let mut discovery = Discovery::new(portal_config, MAINNET.clone()).unwrap();
+    let mut talk_req_rx = discovery.start().await.unwrap();
+    let discovery = Arc::new(discovery);
+
+    // Forward the uTP talk requests to the uTP socket.
+    let (utp_talk_req_tx, utp_talk_req_rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Some(request) = talk_req_rx.recv().await {
+            let subnetwork = MAINNET
+                .get_subnetwork_from_protocol_identifier(&hex_encode_upper(request.protocol()))
+                .unwrap();
+            if let Subnetwork::Utp = subnetwork {
+                utp_talk_req_tx.send(request).unwrap();
+            }
+        }
+    });
+
+    let header_oracle = Arc::new(RwLock::new(HeaderOracle::default()));
+    let discv5_utp =
+        Discv5UdpSocket::new(Arc::clone(&discovery), utp_talk_req_rx, header_oracle, 50);
+    let discv5_utp = match utp_port {
+        Some(utp_port) => {
+            let socket = UdpSocket::bind((ip_addr, utp_port)).await.unwrap();
+            discv5_utp.with_utp_socket(socket)
+        }
+        None => discv5_utp,
+    };
+    (discovery, Arc::new(UtpSocket::with_socket(discv5_utp)))
+}
+
+/// Transfers a payload that takes many packets from the sender to the receiver, and returns what
+/// the receiver read.
+async fn transfer(
+    (sender, sender_utp): (Arc<Discovery>, Arc<UtpSocket<UtpEnr>>),
+    (receiver, receiver_utp): (Arc<Discovery>, Arc<UtpSocket<UtpEnr>>),
+    payload: Vec<u8>,
+) -> Vec<u8> {
+    sender.add_enr(receiver.local_enr()).unwrap();
+    receiver.add_enr(sender.local_enr()).unwrap();
+
+    let utp_config = ConnectionConfig {
+        max_packet_size: 1024,
+        max_idle_timeout: Duration::from_secs(10),
+        ..Default::default()
+    };
+    let receiver_cid = receiver_utp.cid(UtpEnr(sender.local_enr()), false);
+    let sender_cid = ConnectionId {
+        recv: receiver_cid.send,
+        send: receiver_cid.send.wrapping_add(1),
+        peer: UtpEnr(receiver.local_enr()),
+    };
+
+    let read = tokio::spawn(async move {
+        let mut stream = receiver_utp
+            .accept_with_cid(receiver_cid, utp_config)
+            .await
+            .unwrap();
+        let mut data = vec![];
+        stream.read_to_eof(&mut data).await.unwrap();
+        data
+    });
+    let mut stream = sender_utp
+        .connect_with_cid(sender_cid, utp_config)
+        .await
+        .unwrap();
+    assert_eq!(stream.write(&payload).await.unwrap(), payload.len());
+    stream.close().await.unwrap();
+    read.await.unwrap()
+}
+
+#[test_log::test(tokio::test)]
+async fn transfer_over_shared_discv5_socket() {
+    let sender = start_node(8020, None).await;
+    let receiver = start_node(8021, None).await;
+
+    let payload = vec![0xab; 50_000];
+    assert_eq!(transfer(sender, receiver, payload.clone()).await, payload);
+}
+
+#[test_log::test(tokio::test)]
+async fn transfer_over_own_utp_ports() {
+    let sender = start_node(8022, Some(8023)).await;
+    let receiver = start_node(8024, Some(8025)).await;
+    assert_eq!(
+        UtpEnr(receiver.0.local_enr()).utp_socket(),
+        Some(SocketAddr::from(([127, 0, 0, 1], 8025)))
+    );
+
+    let payload = vec![0xcd; 50_000];
+    assert_eq!(transfer(sender, receiver, payload.clone()).await, payload);
+}
+
+#[test_log::test(tokio::test)]
+async fn transfer_between_own_utp_port_and_shared_socket() {
+    // Packets are only exchanged directly if both peers have their own uTP port, so they're wrapped
+    // in TALKREQ messages in both directions otherwise.
+    let sender = start_node(8026, Some(8027)).await;
+    let receiver = start_node(8028, None).await;
+    let payload = vec![0xef; 50_000];
+    assert_eq!(transfer(sender, receiver, payload.clone()).await, payload);
+
+    let sender = start_node(8029, None).await;
+    let receiver = start_node(8030, Some(8031)).await;
+    let payload = vec![0x12; 50_000];
+    assert_eq!(transfer(sender, receiver, payload.clone()).await, payload);
+}
+
+/// Returns a uTP SYN packet with an arbitrary connection id and sequence number.
+fn utp_syn_packet() -> Vec<u8> {
+    let mut packet = vec![(4 << 4) | 1, 0];
+    packet.extend_from_slice(&1234u16.to_be_bytes()); // connection id
+    packet.extend_from_slice(&[0; 8]); // timestamp and timestamp difference
+    packet.extend_from_slice(&1_048_576u32.to_be_bytes()); // window size
+    packet.extend_from_slice(&1u16.to_be_bytes()); // sequence number
+    packet.extend_from_slice(&0u16.to_be_bytes()); // ack number
+    packet
+}
+
+#[test_log::test(tokio::test)]
+async fn crafted_packets_of_the_other_protocol_are_dropped() {
+    let sender = start_node(8032, Some(8033)).await;
+    let receiver = start_node(8034, None).await;
+    assert!(portalnet::socket::is_utp_packet(&utp_syn_packet()));
+
+    // A raw uTP packet on the shared discv5 port isn't a discv5 packet, and a discv5-like packet on
+    // the uTP port isn't a uTP packet. Neither disrupts the transfers over either socket.
+    let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let discv5_like_packet = [0xff; 64];
+    assert!(!portalnet::socket::is_utp_packet(&discv5_like_packet));
+    socket
+        .send_to(&utp_syn_packet(), (Ipv4Addr::LOCALHOST, 8034))
+        .await
+        .unwrap();
+    socket
+        .send_to(&discv5_like_packet, (Ipv4Addr::LOCALHOST, 8033))
+        .await
+        .unwrap();
+    // A uTP packet from an address that no peer advertises is dropped as well.
+    socket
+        .send_to(&utp_syn_packet(), (Ipv4Addr::LOCALHOST, 8033))
+        .await
+        .unwrap();
+
+    let payload = vec![0x34; 50_000];
+    assert_eq!(transfer(sender, receiver, payload.clone()).await, payload);
+}
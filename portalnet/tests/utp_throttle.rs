@@ -0,0 +1,186 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use discv5::enr::CombinedKey;
+use ethportal_api::{
+    types::{enr::Enr, network::Subnetwork, portal_wire::MAINNET},
+    utils::bytes::hex_encode_upper,
+};
+use portalnet::{
+    config::PortalnetConfig,
+    discovery::{Discovery, Discv5UdpSocket, UtpEnr, ENR_UTP_PORT_KEY},
+};
+use tokio::{
+    net::UdpSocket,
+    sync::{mpsc, RwLock},
+};
+use trin_validation::oracle::HeaderOracle;
+use utp_rs::{cid::ConnectionId, conn::ConnectionConfig, socket::UtpSocket, udp::AsyncUdpSocket};
+
+/// Returns a port that is currently unused, as picked by the OS.
+fn unused_port() -> u16 {
+    std::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// Starts a node on an unused port, whose uTP packets are exchanged through discv5 TALKREQ
+/// messages.
+async fn start_node(utp_max_up_kbps: u64) -> (Arc<Discovery>, Arc<UtpSocket<UtpEnr>>) {
+    let ip_addr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let port = unused_port();
+    let portal_config = PortalnetConfig {
+        listen_port: port,
+        external_addr: Some(SocketAddr::new(ip_addr, port)),
+        utp_max_up_kbps,
+        ..PortalnetConfig::default()
+    };
+    let mut discovery = Discovery::new(portal_config, MAINNET.clone()).unwrap();
+    let mut talk_req_rx = discovery.start().await.unwrap();
+    let discovery = Arc::new(discovery);
+
+    // Forward the uTP talk requests to the uTP socket.
+    let (utp_talk_req_tx, utp_talk_req_rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Some(request) = talk_req_rx.recv().await {
+            let subnetwork = MAINNET
+                .get_subnetwork_from_protocol_identifier(&hex_encode_upper(request.protocol()))
+                .unwrap();
+            if let Subnetwork::Utp = subnetwork {
+                utp_talk_req_tx.send(request).unwrap();
+            }
+        }
+    });
+
+    let header_oracle = Arc::new(RwLock::new(HeaderOracle::default()));
+    let discv5_utp =
+        Discv5UdpSocket::new(Arc::clone(&discovery), utp_talk_req_rx, header_oracle, 50);
+    (discovery, Arc::new(UtpSocket::with_socket(discv5_utp)))
+}
+
+#[test_log::test(tokio::test)]
+async fn upload_rate_limit_paces_transfer() {
+    // 80 kbps is 10 KB/s.
+    let (sender, sender_utp) = start_node(80).await;
+    let (receiver, receiver_utp) = start_node(0).await;
+    sender.add_enr(receiver.local_enr()).unwrap();
+    receiver.add_enr(sender.local_enr()).unwrap();
+
+    let utp_config = ConnectionConfig {
+        max_packet_size: 1024,
+        max_idle_timeout: Duration::from_secs(30),
+        ..Default::default()
+    };
+    let receiver_cid = receiver_utp.cid(UtpEnr(sender.local_enr()), false);
+    let sender_cid = ConnectionId {
+        recv: receiver_cid.send,
+        send: receiver_cid.send.wrapping_add(1),
+        peer: UtpEnr(receiver.local_enr()),
+    };
+
+    let payload = vec![0xab; 100_000];
+    let start = Instant::now();
+    let read = tokio::spawn(async move {
+        let mut stream = receiver_utp
+            .accept_with_cid(receiver_cid, utp_config)
+            .await
+            .unwrap();
+        let mut data = vec![];
+        stream.read_to_eof(&mut data).await.unwrap();
+        data
+    });
+    let mut stream = sender_utp
+        .connect_with_cid(sender_cid, utp_config)
+        .await
+        .unwrap();
+    assert_eq!(stream.write(&payload).await.unwrap(), payload.len());
+    stream.close().await.unwrap();
+    let data = read.await.unwrap();
+    let elapsed = start.elapsed();
+
+    assert_eq!(data, payload);
+    // The packet headers and the initial burst roughly cancel out, so the transfer should take
+    // about 10 seconds.
+    assert!(
+        elapsed >= Duration::from_secs(8) && elapsed <= Duration::from_secs(15),
+        "Unexpected transfer duration: {elapsed:?}"
+    );
+
+    let stats = sender.utp_throttle().stats();
+    assert_eq!(stats.upload.max_kbps, Some(80));
+    assert!(stats.upload.bytes >= payload.len() as u64);
+    assert!(stats.upload.throttled_packets > 0);
+    assert_eq!(receiver.utp_throttle().stats().upload.throttled_packets, 0);
+}
+
+/// Returns a uTP packet of the packet type, with a payload of `payload_size` bytes.
+fn utp_packet(packet_type: u8, payload_size: usize) -> Vec<u8> {
+    let mut packet = vec![(packet_type << 4) | 1];
+    packet.resize(20 + payload_size, 0);
+    packet
+}
+
+#[test_log::test(tokio::test)]
+async fn control_packets_skip_throttled_data_packets() {
+    // 8 kbps is 1000 bytes per second, so the data packets after the initial burst of 4096 bytes
+    // wait for seconds.
+    let portal_config = PortalnetConfig {
+        listen_port: unused_port(),
+        utp_max_up_kbps: 8,
+        ..PortalnetConfig::default()
+    };
+    let discovery = Arc::new(Discovery::new(portal_config, MAINNET.clone()).unwrap());
+    let (_utp_talk_req_tx, utp_talk_req_rx) = mpsc::unbounded_channel();
+    let header_oracle = Arc::new(RwLock::new(HeaderOracle::default()));
+    let utp_socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let mut sender = Discv5UdpSocket::new(discovery, utp_talk_req_rx, header_oracle, 50)
+        .with_utp_socket(utp_socket);
+
+    // The peer receives the uTP packets on a plain UDP socket, and doesn't run discv5.
+    let receiver = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let receiver_port = receiver.local_addr().unwrap().port();
+    let peer = UtpEnr(
+        Enr::builder()
+            .ip4(Ipv4Addr::LOCALHOST)
+            .udp4(unused_port())
+            .add_value(ENR_UTP_PORT_KEY, &receiver_port)
+            .build(&CombinedKey::generate_secp256k1())
+            .unwrap(),
+    );
+
+    // Neither the throttled data packets nor the ACK after them hold up the sender.
+    let data_packet = utp_packet(0, 1000);
+    let ack = utp_packet(2, 0);
+    let start = Instant::now();
+    for _ in 0..8 {
+        sender.send_to(&data_packet, &peer).await.unwrap();
+    }
+    sender.send_to(&ack, &peer).await.unwrap();
+    assert!(start.elapsed() < Duration::from_millis(500));
+
+    // The ACK arrives right after the burst, before the data packets that wait for the limit.
+    let mut buf = [0; 2048];
+    let mut received = vec![];
+    for _ in 0..5 {
+        let (n, _) = tokio::time::timeout(Duration::from_millis(500), receiver.recv_from(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        received.push(n);
+    }
+    assert_eq!(received, [1020, 1020, 1020, 1020, 20]);
+
+    // The throttled data packets are still sent, in the order that they were sent.
+    for _ in 0..4 {
+        let (n, _) = tokio::time::timeout(Duration::from_secs(10), receiver.recv_from(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(n, 1020);
+    }
+}
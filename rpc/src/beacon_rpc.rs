@@ -1,24 +1,41 @@
-use alloy::primitives::B256;
+use std::{sync::Arc, time::Duration};
+
+use alloy::primitives::{Bytes, B256};
 use discv5::enr::NodeId;
 use ethportal_api::{
-    consensus::header::BeaconBlockHeader,
+    consensus::{
+        chain_config::ChainConfig, header::BeaconBlockHeader,
+        historical_summaries::HistoricalSummariesWithProof,
+    },
     light_client::store::LightClientStore,
     types::{
         consensus::light_client::{
             finality_update::LightClientFinalityUpdate,
             optimistic_update::LightClientOptimisticUpdate,
         },
-        enr::Enr,
+        enr::{enr_multiaddrs, Enr, RpcEnr},
         jsonrpc::{endpoints::BeaconEndpoint, request::BeaconJsonRpcRequest},
         portal::{
-            AcceptInfo, DataRadius, FindContentInfo, FindNodesInfo, GetContentInfo,
-            PaginateLocalContentInfo, PongInfo, TraceContentInfo, TraceGossipInfo,
-            MAX_CONTENT_KEYS_PER_OFFER,
+            AcceptInfo, AcceptSnapshotInfo, ActiveLookupInfo, BandwidthLimitInfo,
+            BeaconContentTypeCounts, ContentInspectionInfo, ContentOrClosestInfo,
+            ContentValidationInfo, FetchAndStoreInfo, FindContentInfo, FindNodesInfo,
+            GcOrphansInfo, GetContentInfo, GetEnrInfo, HeaderUpdateTimesInfo, LocalContentInfo,
+            LookupEnrInfo, NearestStoredKeyInfo, OfferAndVerifyInfo, OfferManyInfo,
+            OverlayNetworkInfo, PaginateLocalContentInfo, PingRawInfo, PinnedContentInfo, PongInfo,
+            PrefetchStatusInfo, PruneEstimateInfo, RangeCoverageInfo, RecentOfferersInfo,
+            SelfTestInfo, StorageBackendInfo, StorageInfo, StoreLatencyStats, TraceAnalysisInfo,
+            TraceContentInfo, TraceGossipInfo, UtpLimitInfo, ValidationMode, ValidationModeInfo,
+            ValidationReportInfo, VerifyPeersInfo, MAX_CONTENT_ITEMS_PER_VALIDATION_BATCH,
+            MAX_CONTENT_KEYS_PER_OFFER, MAX_NEAREST_STORED_KEYS,
         },
-        portal_wire::OfferTrace,
+        portal_wire::{
+            OfferTrace, MAX_DISCV5_TALK_REQ_PAYLOAD_SIZE, MAX_DISCV5_TALK_REQ_PROTOCOL_SIZE,
+        },
+        query_trace::QueryTrace,
     },
-    BeaconContentKey, BeaconContentValue, BeaconNetworkApiServer, ContentValue, RawContentValue,
-    RoutingTableInfo,
+    BeaconContentKey, BeaconContentValue, BeaconNetworkApiServer, BucketRefreshTimesInfo,
+    ContentValueFormat, ContentValueParam, KeyspaceDensityInfo, RawContentValue,
+    ReplacementCacheInfo, RoutingTableInfo,
 };
 use tokio::sync::mpsc;
 
@@ -26,16 +43,29 @@ use crate::{
     errors::RpcServeError,
     fetch::proxy_to_subnet,
     jsonrpsee::core::{async_trait, RpcResult},
+    offer::check_offer_content_sizes,
+    paginate::{check_max_results, check_paginate_limit},
 };
 
 pub struct BeaconNetworkApi {
     network: mpsc::UnboundedSender<BeaconJsonRpcRequest>,
+    /// The beacon chain that the content values passed to the endpoints are decoded for
+    chain_config: Arc<ChainConfig>,
+    max_paginate_limit: u64,
 }
 
 impl BeaconNetworkApi {
     #[allow(dead_code)]
-    pub fn new(network: mpsc::UnboundedSender<BeaconJsonRpcRequest>) -> Self {
-        Self { network }
+    pub fn new(
+        network: mpsc::UnboundedSender<BeaconJsonRpcRequest>,
+        chain_config: Arc<ChainConfig>,
+        max_paginate_limit: u64,
+    ) -> Self {
+        Self {
+            network,
+            chain_config,
+            max_paginate_limit,
+        }
     }
 }
 
@@ -47,15 +77,66 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
+    /// Returns the ENRs in the replacement cache of each overlay routing table bucket.
+    async fn replacement_cache(&self) -> RpcResult<ReplacementCacheInfo> {
+        let endpoint = BeaconEndpoint::ReplacementCache;
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Returns how densely the overlay routing table buckets cover the node id space.
+    async fn keyspace_density(&self) -> RpcResult<KeyspaceDensityInfo> {
+        let endpoint = BeaconEndpoint::KeyspaceDensity;
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Returns when the overlay routing table buckets were last refreshed.
+    async fn bucket_refresh_times(&self) -> RpcResult<BucketRefreshTimesInfo> {
+        let endpoint = BeaconEndpoint::BucketRefreshTimes;
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
     /// Write an Ethereum Node Record to the overlay routing table.
-    async fn add_enr(&self, enr: Enr) -> RpcResult<bool> {
-        let endpoint = BeaconEndpoint::AddEnr(enr);
+    async fn add_enr(&self, enr: RpcEnr, allow_unreachable: Option<bool>) -> RpcResult<bool> {
+        let endpoint = BeaconEndpoint::AddEnr(enr.into(), allow_unreachable.unwrap_or(false));
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
     /// Fetch the latest ENR associated with the given node ID.
-    async fn get_enr(&self, node_id: NodeId) -> RpcResult<Enr> {
+    async fn get_enr(
+        &self,
+        node_id: NodeId,
+        include_multiaddrs: Option<bool>,
+    ) -> RpcResult<LookupEnrInfo> {
         let endpoint = BeaconEndpoint::GetEnr(node_id);
+        let enr: Enr = proxy_to_subnet(&self.network, endpoint).await?;
+        if include_multiaddrs.unwrap_or(false) {
+            let multiaddrs = enr_multiaddrs(&enr);
+            return Ok(LookupEnrInfo::WithMultiaddrs { enr, multiaddrs });
+        }
+        Ok(LookupEnrInfo::Enr(enr))
+    }
+
+    /// Fetch the latest ENR associated with the given node ID, along with where it was found.
+    async fn get_enr_info(
+        &self,
+        node_id: NodeId,
+        include_multiaddrs: Option<bool>,
+    ) -> RpcResult<GetEnrInfo> {
+        let endpoint = BeaconEndpoint::GetEnrInfo(node_id);
+        let mut result: GetEnrInfo = proxy_to_subnet(&self.network, endpoint).await?;
+        if include_multiaddrs.unwrap_or(false) {
+            result.multiaddrs = Some(enr_multiaddrs(&result.enr));
+        }
+        Ok(result)
+    }
+
+    /// Fetch the decoded value of a field of the ENR associated with the given node ID.
+    async fn get_enr_field(
+        &self,
+        node_id: NodeId,
+        key: String,
+    ) -> RpcResult<Option<serde_json::Value>> {
+        let endpoint = BeaconEndpoint::GetEnrField(node_id, key);
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
@@ -71,22 +152,69 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
+    /// Returns how long ago the light client last updated its optimistic and finalized headers.
+    async fn header_update_times(&self) -> RpcResult<HeaderUpdateTimesInfo> {
+        let endpoint = BeaconEndpoint::HeaderUpdateTimes;
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
     /// Fetch the ENR representation associated with the given Node ID.
-    async fn lookup_enr(&self, node_id: NodeId) -> RpcResult<Enr> {
+    async fn lookup_enr(
+        &self,
+        node_id: NodeId,
+        include_multiaddrs: Option<bool>,
+    ) -> RpcResult<LookupEnrInfo> {
         let endpoint = BeaconEndpoint::LookupEnr(node_id);
-        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+        let enr: Enr = proxy_to_subnet(&self.network, endpoint).await?;
+        if include_multiaddrs.unwrap_or(false) {
+            let multiaddrs = enr_multiaddrs(&enr);
+            return Ok(LookupEnrInfo::WithMultiaddrs { enr, multiaddrs });
+        }
+        Ok(LookupEnrInfo::Enr(enr))
     }
 
     /// Send a PING message to the designated node and wait for a PONG response
-    async fn ping(&self, enr: Enr) -> RpcResult<PongInfo> {
-        let endpoint = BeaconEndpoint::Ping(enr);
+    async fn ping(&self, enr: RpcEnr) -> RpcResult<PongInfo> {
+        let endpoint = BeaconEndpoint::Ping(enr.into());
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Send a PING message to the designated node and return the raw request and response
+    async fn ping_raw(&self, enr: RpcEnr) -> RpcResult<PingRawInfo> {
+        let endpoint = BeaconEndpoint::PingRaw(enr.into());
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Send a raw discv5 TALKREQ message to the designated node and return the raw response
+    async fn talk_req(&self, enr: RpcEnr, protocol_id: Bytes, payload: Bytes) -> RpcResult<Bytes> {
+        if protocol_id.is_empty() || protocol_id.len() > MAX_DISCV5_TALK_REQ_PROTOCOL_SIZE {
+            return Err(RpcServeError::Message(format!(
+                "Protocol id must be between 1 and {MAX_DISCV5_TALK_REQ_PROTOCOL_SIZE} bytes, got {}",
+                protocol_id.len()
+            ))
+            .into());
+        }
+        if payload.len() > MAX_DISCV5_TALK_REQ_PAYLOAD_SIZE {
+            return Err(RpcServeError::Message(format!(
+                "Payload must be at most {MAX_DISCV5_TALK_REQ_PAYLOAD_SIZE} bytes, got {}",
+                payload.len()
+            ))
+            .into());
+        }
+        let endpoint = BeaconEndpoint::TalkReq(enr.into(), protocol_id, payload);
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
     /// Send a FINDNODES request for nodes that fall within the given set of distances, to the
     /// designated peer and wait for a response
-    async fn find_nodes(&self, enr: Enr, distances: Vec<u16>) -> RpcResult<FindNodesInfo> {
-        let endpoint = BeaconEndpoint::FindNodes(enr, distances);
+    async fn find_nodes(
+        &self,
+        enr: RpcEnr,
+        distances: Vec<u16>,
+        max_results: Option<u64>,
+    ) -> RpcResult<FindNodesInfo> {
+        check_max_results(max_results)?;
+        let endpoint = BeaconEndpoint::FindNodes(enr.into(), distances, max_results);
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
@@ -97,7 +225,7 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
     }
 
     /// Lookup a target node within in the network
-    async fn radius(&self) -> RpcResult<DataRadius> {
+    async fn radius(&self) -> RpcResult<B256> {
         let endpoint = BeaconEndpoint::DataRadius;
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
@@ -114,6 +242,12 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
+    /// Get the latest historical summaries with proof that were accepted by the node.
+    async fn historical_summaries(&self) -> RpcResult<HistoricalSummariesWithProof> {
+        let endpoint = BeaconEndpoint::HistoricalSummaries;
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
     /// Get the finalized beacon header.
     async fn finalized_header(&self) -> RpcResult<BeaconBlockHeader> {
         let endpoint = BeaconEndpoint::FinalizedHeader;
@@ -135,10 +269,23 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
     /// Send FINDCONTENT message to get the content with a content key.
     async fn find_content(
         &self,
-        enr: Enr,
+        enr: RpcEnr,
         content_key: BeaconContentKey,
     ) -> RpcResult<FindContentInfo> {
-        let endpoint = BeaconEndpoint::FindContent(enr, content_key);
+        let endpoint = BeaconEndpoint::FindContent(enr.into(), content_key);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Send FINDCONTENT message to get the content with a content key, and store the content
+    /// after validating it. Content outside our radius is stored only if `ignore_radius` is set.
+    async fn fetch_and_store(
+        &self,
+        enr: RpcEnr,
+        content_key: BeaconContentKey,
+        ignore_radius: Option<bool>,
+    ) -> RpcResult<FetchAndStoreInfo> {
+        let endpoint =
+            BeaconEndpoint::FetchAndStore(enr.into(), content_key, ignore_radius.unwrap_or(false));
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
@@ -159,12 +306,25 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
+    /// Same as `trace_get_content`, but if the content isn't found, returns the closest peers to
+    /// the content that were discovered during the lookup.
+    async fn get_content_or_closest(
+        &self,
+        content_key: BeaconContentKey,
+        timeout_ms: Option<u64>,
+    ) -> RpcResult<ContentOrClosestInfo> {
+        let endpoint =
+            BeaconEndpoint::GetContentOrClosest(content_key, timeout_ms.map(Duration::from_millis));
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
     /// Pagination of local content keys
     async fn paginate_local_content_keys(
         &self,
         offset: u64,
         limit: u64,
     ) -> RpcResult<PaginateLocalContentInfo<BeaconContentKey>> {
+        check_paginate_limit(limit, self.max_paginate_limit)?;
         let endpoint = BeaconEndpoint::PaginateLocalContentKeys(offset, limit);
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
@@ -176,8 +336,9 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
         content_key: BeaconContentKey,
         content_value: RawContentValue,
     ) -> RpcResult<u32> {
-        let content_value = BeaconContentValue::decode(&content_key, &content_value)
-            .map_err(RpcServeError::from)?;
+        let content_value =
+            BeaconContentValue::decode_for_chain(&content_key, &content_value, &self.chain_config)
+                .map_err(RpcServeError::from)?;
         let endpoint = BeaconEndpoint::Gossip(content_key, content_value);
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
@@ -189,8 +350,9 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
         content_key: BeaconContentKey,
         content_value: RawContentValue,
     ) -> RpcResult<TraceGossipInfo> {
-        let content_value = BeaconContentValue::decode(&content_key, &content_value)
-            .map_err(RpcServeError::from)?;
+        let content_value =
+            BeaconContentValue::decode_for_chain(&content_key, &content_value, &self.chain_config)
+                .map_err(RpcServeError::from)?;
         let endpoint = BeaconEndpoint::TraceGossip(content_key, content_value);
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
@@ -201,8 +363,10 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
     /// receive.
     async fn offer(
         &self,
-        enr: Enr,
-        content_items: Vec<(BeaconContentKey, RawContentValue)>,
+        enr: RpcEnr,
+        content_items: Vec<(BeaconContentKey, ContentValueParam)>,
+        format: Option<ContentValueFormat>,
+        include_accept_bitfield: Option<bool>,
     ) -> RpcResult<AcceptInfo> {
         if !(1..=MAX_CONTENT_KEYS_PER_OFFER).contains(&content_items.len()) {
             return Err(RpcServeError::Message(format!(
@@ -214,12 +378,48 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
         let content_items = content_items
             .into_iter()
             .map(|(key, value)| {
-                BeaconContentValue::decode(&key, &value)
+                decode_content_value(&key, value, format.unwrap_or_default(), &self.chain_config)
+                    .map(|value| (key, value))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        check_offer_content_sizes(
+            content_items
+                .iter()
+                .map(|(key, value)| (key, value.encode_for_chain(&self.chain_config).len())),
+            |key| key.max_content_value_size(),
+        )?;
+        let endpoint = BeaconEndpoint::Offer(enr.into(), content_items);
+        let mut accept_info: AcceptInfo = proxy_to_subnet(&self.network, endpoint).await?;
+        if !include_accept_bitfield.unwrap_or(false) {
+            accept_info.accept_bitfield = None;
+        }
+        Ok(accept_info)
+    }
+
+    /// Send an OFFER request with given ContentItems, to the designated peer and wait for a
+    /// response. Does not store content locally.
+    /// Returns which of the offered content keys the peer accepted and which it declined.
+    async fn offer_many(
+        &self,
+        enr: RpcEnr,
+        content_items: Vec<(BeaconContentKey, RawContentValue)>,
+    ) -> RpcResult<OfferManyInfo<BeaconContentKey>> {
+        if !(1..=MAX_CONTENT_KEYS_PER_OFFER).contains(&content_items.len()) {
+            return Err(RpcServeError::Message(format!(
+                "Invalid amount of content items: {}",
+                content_items.len()
+            ))
+            .into());
+        }
+        let content_items = content_items
+            .into_iter()
+            .map(|(key, value)| {
+                BeaconContentValue::decode_for_chain(&key, &value, &self.chain_config)
                     .map(|value| (key, value))
                     .map_err(RpcServeError::from)
             })
             .collect::<Result<Vec<_>, _>>()?;
-        let endpoint = BeaconEndpoint::Offer(enr, content_items);
+        let endpoint = BeaconEndpoint::OfferMany(enr.into(), content_items);
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
@@ -228,13 +428,30 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
     /// Returns trace info from the offer.
     async fn trace_offer(
         &self,
-        enr: Enr,
+        enr: RpcEnr,
         content_key: BeaconContentKey,
         content_value: RawContentValue,
     ) -> RpcResult<OfferTrace> {
-        let content_value = BeaconContentValue::decode(&content_key, &content_value)
-            .map_err(RpcServeError::from)?;
-        let endpoint = BeaconEndpoint::TraceOffer(enr, content_key, content_value);
+        let content_value =
+            BeaconContentValue::decode_for_chain(&content_key, &content_value, &self.chain_config)
+                .map_err(RpcServeError::from)?;
+        let endpoint = BeaconEndpoint::TraceOffer(enr.into(), content_key, content_value);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Send an OFFER request with the content to the designated peer, and verify that the peer
+    /// serves the content afterwards.
+    /// Does not store the content locally.
+    async fn offer_and_verify(
+        &self,
+        enr: RpcEnr,
+        content_key: BeaconContentKey,
+        content_value: RawContentValue,
+    ) -> RpcResult<OfferAndVerifyInfo> {
+        let content_value =
+            BeaconContentValue::decode_for_chain(&content_key, &content_value, &self.chain_config)
+                .map_err(RpcServeError::from)?;
+        let endpoint = BeaconEndpoint::OfferAndVerify(enr.into(), content_key, content_value);
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
@@ -242,23 +459,349 @@ impl BeaconNetworkApiServer for BeaconNetworkApi {
     async fn store(
         &self,
         content_key: BeaconContentKey,
-        content_value: RawContentValue,
+        content_value: ContentValueParam,
+        format: Option<ContentValueFormat>,
+        signature: Option<Bytes>,
     ) -> RpcResult<bool> {
-        let content_value = BeaconContentValue::decode(&content_key, &content_value)
-            .map_err(RpcServeError::from)?;
-        let endpoint = BeaconEndpoint::Store(content_key, content_value);
+        let content_value = decode_content_value(
+            &content_key,
+            content_value,
+            format.unwrap_or_default(),
+            &self.chain_config,
+        )?;
+        let endpoint = BeaconEndpoint::Store(content_key, content_value, signature);
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
     /// Get a content from the local database.
-    async fn local_content(&self, content_key: BeaconContentKey) -> RpcResult<RawContentValue> {
-        let endpoint = BeaconEndpoint::LocalContent(content_key);
+    async fn local_content(
+        &self,
+        content_key: BeaconContentKey,
+        include_meta: Option<bool>,
+    ) -> RpcResult<LocalContentInfo> {
+        let endpoint = BeaconEndpoint::LocalContent(content_key, include_meta.unwrap_or(false));
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Returns the size of the locally stored content, and how much is saved by content dedup.
+    async fn storage_info(&self) -> RpcResult<StorageInfo> {
+        let endpoint = BeaconEndpoint::StorageInfo;
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Returns the number of locally stored entries per content type.
+    async fn content_type_breakdown(&self) -> RpcResult<BeaconContentTypeCounts> {
+        let endpoint = BeaconEndpoint::ContentTypeBreakdown;
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Returns the type, path and metadata of the database the content is stored in.
+    async fn storage_backend_info(
+        &self,
+        redact_path: Option<bool>,
+    ) -> RpcResult<StorageBackendInfo> {
+        let endpoint = BeaconEndpoint::StorageBackendInfo(redact_path.unwrap_or_default());
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Estimate how soon the locally stored content will be pruned or replaced.
+    async fn prune_estimate(&self, content_key: BeaconContentKey) -> RpcResult<PruneEstimateInfo> {
+        let endpoint = BeaconEndpoint::PruneEstimate(content_key);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Return the latency percentiles of reading from and writing to the local store.
+    async fn store_latency_stats(&self) -> RpcResult<StoreLatencyStats> {
+        let endpoint = BeaconEndpoint::StoreLatencyStats;
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Recompute the node's data radius from the current storage usage.
+    async fn recompute_radius(&self) -> RpcResult<B256> {
+        let endpoint = BeaconEndpoint::RecomputeRadius;
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Ping up to `limit` routing table peers and return which of them responded.
+    async fn verify_peers(&self, limit: u64, evict: bool) -> RpcResult<VerifyPeersInfo> {
+        let endpoint = BeaconEndpoint::VerifyPeers(limit, evict);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Return up to `limit` peers that recently offered us content.
+    async fn recent_offerers(&self, limit: u64) -> RpcResult<RecentOfferersInfo> {
+        let endpoint = BeaconEndpoint::RecentOfferers(limit);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Report the current status of the peers in a previously recorded query trace.
+    async fn analyze_trace(&self, trace: QueryTrace) -> RpcResult<TraceAnalysisInfo> {
+        let endpoint = BeaconEndpoint::AnalyzeTrace(trace);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Set the uTP upload rate limit. Returns the current limit.
+    async fn set_bandwidth_limit(
+        &self,
+        bytes_per_sec: Option<u64>,
+    ) -> RpcResult<BandwidthLimitInfo> {
+        if bytes_per_sec == Some(0) {
+            return Err(RpcServeError::Message(
+                "Bandwidth limit must be greater than zero".to_string(),
+            )
+            .into());
+        }
+        let endpoint = BeaconEndpoint::SetBandwidthLimit(bytes_per_sec);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Return the max number of concurrent uTP transfers, and the number of running transfers.
+    async fn get_utp_limit(&self) -> RpcResult<UtpLimitInfo> {
+        let endpoint = BeaconEndpoint::GetUtpLimit;
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Set the max number of concurrent uTP transfers.
+    async fn set_utp_limit(&self, limit: u32) -> RpcResult<UtpLimitInfo> {
+        if limit == 0 {
+            return Err(RpcServeError::Message(
+                "uTP transfer limit must be greater than zero".to_string(),
+            )
+            .into());
+        }
+        let endpoint = BeaconEndpoint::SetUtpLimit(limit);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Force-close the uTP transfer with the peer over the connection with the given id.
+    async fn close_transfer(&self, node_id: NodeId, connection_id: u16) -> RpcResult<bool> {
+        let endpoint = BeaconEndpoint::CloseTransfer(node_id, connection_id);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Prefetch the content of the given keys in the background, and return the job id.
+    async fn prefetch(&self, content_keys: Vec<BeaconContentKey>) -> RpcResult<u64> {
+        let endpoint = BeaconEndpoint::Prefetch(content_keys);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Return the progress of the prefetch job.
+    async fn prefetch_status(&self, job_id: u64) -> RpcResult<PrefetchStatusInfo> {
+        let endpoint = BeaconEndpoint::PrefetchStatus(job_id);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Cancel the prefetch job and return its status.
+    async fn cancel_prefetch(&self, job_id: u64) -> RpcResult<PrefetchStatusInfo> {
+        let endpoint = BeaconEndpoint::CancelPrefetch(job_id);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Return the inputs of the accept decision, with their version.
+    async fn accept_snapshot(&self) -> RpcResult<AcceptSnapshotInfo> {
+        let endpoint = BeaconEndpoint::AcceptSnapshot;
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Run a self-test of the local store, content ids and radius.
+    async fn self_test(&self) -> RpcResult<SelfTestInfo> {
+        let endpoint = BeaconEndpoint::SelfTest;
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Set how strictly the content accepted from other nodes is validated.
+    async fn set_validation_mode(
+        &self,
+        mode: ValidationMode,
+    ) -> RpcResult<ValidationModeInfo<BeaconContentKey>> {
+        let endpoint = BeaconEndpoint::SetValidationMode(mode);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Return the locally stored content keys closest to the content id.
+    async fn nearest_stored_keys(
+        &self,
+        content_id: B256,
+        limit: u64,
+    ) -> RpcResult<Vec<NearestStoredKeyInfo<BeaconContentKey>>> {
+        if !(1..=MAX_NEAREST_STORED_KEYS).contains(&limit) {
+            return Err(RpcServeError::Message(format!(
+                "Invalid limit: {limit}, expected between 1 and {MAX_NEAREST_STORED_KEYS}"
+            ))
+            .into());
+        }
+        let endpoint = BeaconEndpoint::NearestStoredKeys(content_id, limit);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Return how much of the locally stored content is within the content id range.
+    async fn range_coverage(&self, start_id: B256, end_id: B256) -> RpcResult<RangeCoverageInfo> {
+        if start_id > end_id {
+            return Err(RpcServeError::Message(format!(
+                "Invalid range: start id {start_id} is greater than end id {end_id}"
+            ))
+            .into());
+        }
+        let endpoint = BeaconEndpoint::RangeCoverage(start_id, end_id);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Remove the stored content values that are no longer referenced by any content key.
+    async fn gc_orphans(&self) -> RpcResult<GcOrphansInfo> {
+        let endpoint = BeaconEndpoint::GcOrphans;
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Keep the content regardless of the storage capacity.
+    async fn pin_content(&self, content_key: BeaconContentKey) -> RpcResult<PinnedContentInfo> {
+        let endpoint = BeaconEndpoint::PinContent(content_key);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Stop keeping the content regardless of the storage capacity.
+    async fn unpin_content(&self, content_key: BeaconContentKey) -> RpcResult<PinnedContentInfo> {
+        let endpoint = BeaconEndpoint::UnpinContent(content_key);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Return the metrics of the beacon subnetwork in the Prometheus text exposition format.
+    async fn metrics_text(&self) -> RpcResult<String> {
+        let endpoint = BeaconEndpoint::MetricsText;
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Pause or resume gossiping content to other nodes.
+    async fn set_gossip_enabled(&self, enabled: bool) -> RpcResult<bool> {
+        let endpoint = BeaconEndpoint::SetGossipEnabled(enabled);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Forget the content recently found by lookups.
+    async fn clear_lookup_cache(&self) -> RpcResult<u64> {
+        let endpoint = BeaconEndpoint::ClearLookupCache;
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Return the content lookups that are currently running.
+    async fn active_lookups(&self) -> RpcResult<Vec<ActiveLookupInfo>> {
+        let endpoint = BeaconEndpoint::ActiveLookups;
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Validate the content items without storing them.
+    async fn validate_content_batch(
+        &self,
+        content_items: Vec<(BeaconContentKey, RawContentValue)>,
+    ) -> RpcResult<Vec<ContentValidationInfo>> {
+        if !(1..=MAX_CONTENT_ITEMS_PER_VALIDATION_BATCH).contains(&content_items.len()) {
+            return Err(RpcServeError::Message(format!(
+                "Invalid amount of content items: {}",
+                content_items.len()
+            ))
+            .into());
+        }
+        let endpoint = BeaconEndpoint::ValidateContentBatch(content_items);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Validate the stored content again, with the outcome of each check.
+    async fn revalidate_detailed(
+        &self,
+        content_key: BeaconContentKey,
+    ) -> RpcResult<ValidationReportInfo> {
+        let endpoint = BeaconEndpoint::RevalidateDetailed(content_key);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Decode the content value, along with its raw bytes and content id.
+    async fn inspect_content(
+        &self,
+        content_key: BeaconContentKey,
+        content_value: RawContentValue,
+    ) -> RpcResult<ContentInspectionInfo> {
+        let endpoint = BeaconEndpoint::InspectContent(content_key, content_value);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Return the network, subnetwork and protocol id of the overlay.
+    async fn network_info(&self) -> RpcResult<OverlayNetworkInfo> {
+        let endpoint = BeaconEndpoint::NetworkInfo;
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 }
 
+/// Decodes the content value of the chain passed to the JSON-RPC endpoint, which must be in the
+/// requested format.
+fn decode_content_value(
+    content_key: &BeaconContentKey,
+    content_value: ContentValueParam,
+    format: ContentValueFormat,
+    chain_config: &ChainConfig,
+) -> Result<BeaconContentValue, RpcServeError> {
+    let content_value = match (format, content_value) {
+        (ContentValueFormat::Ssz, ContentValueParam::Ssz(content_value)) => {
+            BeaconContentValue::decode_for_chain(content_key, &content_value, chain_config)
+        }
+        (ContentValueFormat::Json, ContentValueParam::Json(content_value)) => {
+            BeaconContentValue::from_json(content_key, serde_json::Value::Object(content_value))
+        }
+        (ContentValueFormat::Ssz, ContentValueParam::Json(_)) => {
+            return Err(RpcServeError::InvalidParams(
+                "Expected hex encoded content value, use the json format for JSON objects"
+                    .to_string(),
+            ))
+        }
+        (ContentValueFormat::Json, ContentValueParam::Ssz(_)) => {
+            return Err(RpcServeError::InvalidParams(
+                "Expected a JSON object content value".to_string(),
+            ))
+        }
+    };
+    content_value.map_err(RpcServeError::from)
+}
+
 impl std::fmt::Debug for BeaconNetworkApi {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("BeaconNetworkApi").finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ethportal_api::{consensus::chain_config::MAINNET_CHAIN_CONFIG, LightClientBootstrapKey};
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn content_value_must_be_in_requested_format() {
+        let content_key = BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey {
+            block_hash: [0; 32],
+        });
+        let decode = |content_value: serde_json::Value, format| {
+            decode_content_value(
+                &content_key,
+                serde_json::from_value(content_value).unwrap(),
+                format,
+                &MAINNET_CHAIN_CONFIG,
+            )
+        };
+
+        assert!(matches!(
+            decode(
+                json!({ "version": "deneb", "data": {} }),
+                ContentValueFormat::Ssz
+            ),
+            Err(RpcServeError::InvalidParams(_))
+        ));
+        assert!(matches!(
+            decode(json!("0x0102"), ContentValueFormat::Json),
+            Err(RpcServeError::InvalidParams(_))
+        ));
+        assert!(matches!(
+            decode(json!("0x0102"), ContentValueFormat::Ssz),
+            Err(RpcServeError::Message(_))
+        ));
+    }
+}
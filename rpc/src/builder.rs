@@ -1,13 +1,18 @@
 use std::{
     collections::{HashMap, HashSet},
     fmt,
+    path::PathBuf,
     sync::Arc,
 };
 
 use ethportal_api::{
-    types::jsonrpc::request::{BeaconJsonRpcRequest, HistoryJsonRpcRequest, StateJsonRpcRequest},
+    consensus::chain_config::{ChainConfig, MAINNET_CHAIN_CONFIG},
+    types::{
+        cli::DEFAULT_RPC_MAX_PAGINATE_LIMIT,
+        jsonrpc::request::{BeaconJsonRpcRequest, HistoryJsonRpcRequest, StateJsonRpcRequest},
+    },
     BeaconNetworkApiServer, Discv5ApiServer, EthApiServer, HistoryNetworkApiServer,
-    StateNetworkApiServer, Web3ApiServer,
+    PortalApiServer, StateNetworkApiServer, Web3ApiServer,
 };
 use portalnet::discovery::Discovery;
 use serde::Deserialize;
@@ -18,7 +23,7 @@ use crate::{
     errors::{RpcError, WsHttpSamePortError},
     jsonrpsee::{Methods, RpcModule},
     rpc_server::{RpcServerConfig, RpcServerHandle},
-    BeaconNetworkApi, Discv5Api, EthApi, HistoryNetworkApi, StateNetworkApi, Web3Api,
+    BeaconNetworkApi, Discv5Api, EthApi, HistoryNetworkApi, PortalApi, StateNetworkApi, Web3Api,
 };
 
 /// Represents RPC modules that are supported by Trin
@@ -36,6 +41,8 @@ pub enum PortalRpcModule {
     Eth,
     /// `portal_history` module
     History,
+    /// `portal_` module, for endpoints that aren't specific to a subnetwork
+    Portal,
     /// `state` module
     State,
     /// `web3_` module
@@ -289,6 +296,12 @@ pub struct RpcModuleBuilder {
     beacon_tx: Option<mpsc::UnboundedSender<BeaconJsonRpcRequest>>,
     /// State protocol
     state_tx: Option<mpsc::UnboundedSender<StateJsonRpcRequest>>,
+    /// Node data directory
+    node_data_dir: Option<PathBuf>,
+    /// The max number of content keys in a page of local content keys
+    max_paginate_limit: u64,
+    /// The beacon chain that beacon content values are decoded for
+    beacon_chain: Arc<ChainConfig>,
 }
 
 impl RpcModuleBuilder {
@@ -299,6 +312,9 @@ impl RpcModuleBuilder {
             history_tx: None,
             beacon_tx: None,
             state_tx: None,
+            node_data_dir: None,
+            max_paginate_limit: DEFAULT_RPC_MAX_PAGINATE_LIMIT,
+            beacon_chain: MAINNET_CHAIN_CONFIG.clone(),
         }
     }
 
@@ -344,6 +360,21 @@ impl RpcModuleBuilder {
         self
     }
 
+    pub fn with_node_data_dir(mut self, node_data_dir: PathBuf) -> Self {
+        self.node_data_dir = Some(node_data_dir);
+        self
+    }
+
+    pub fn with_max_paginate_limit(mut self, max_paginate_limit: u64) -> Self {
+        self.max_paginate_limit = max_paginate_limit;
+        self
+    }
+
+    pub fn with_beacon_chain(mut self, beacon_chain: Arc<ChainConfig>) -> Self {
+        self.beacon_chain = beacon_chain;
+        self
+    }
+
     /// Returns all installed methods
     pub fn methods(&self) -> Vec<Methods> {
         self.modules.values().cloned().collect()
@@ -422,23 +453,50 @@ impl RpcModuleBuilder {
                                 .history_tx
                                 .clone()
                                 .expect("History protocol not initialized");
-                            HistoryNetworkApi::new(history_tx).into_rpc().into()
+                            HistoryNetworkApi::new(history_tx, self.max_paginate_limit)
+                                .into_rpc()
+                                .into()
                         }
                         PortalRpcModule::Beacon => {
                             let beacon_tx = self
                                 .beacon_tx
                                 .clone()
                                 .expect("Beacon protocol not initialized");
-                            BeaconNetworkApi::new(beacon_tx).into_rpc().into()
+                            BeaconNetworkApi::new(
+                                beacon_tx,
+                                self.beacon_chain.clone(),
+                                self.max_paginate_limit,
+                            )
+                            .into_rpc()
+                            .into()
                         }
                         PortalRpcModule::State => {
                             let state_tx = self
                                 .state_tx
                                 .clone()
                                 .expect("State protocol not initialized");
-                            StateNetworkApi::new(state_tx).into_rpc().into()
+                            StateNetworkApi::new(state_tx, self.max_paginate_limit)
+                                .into_rpc()
+                                .into()
+                        }
+                        PortalRpcModule::Portal => {
+                            let node_data_dir = self
+                                .node_data_dir
+                                .clone()
+                                .expect("Node data dir not initialized");
+                            PortalApi::new(
+                                self.discv5.clone(),
+                                node_data_dir,
+                                self.history_tx.clone(),
+                                self.state_tx.clone(),
+                                self.beacon_tx.clone(),
+                            )
+                            .into_rpc()
+                            .into()
+                        }
+                        PortalRpcModule::Web3 => {
+                            Web3Api::new(self.discv5.network()).into_rpc().into()
                         }
-                        PortalRpcModule::Web3 => Web3Api.into_rpc().into(),
                     })
                     .clone()
             })
@@ -480,6 +538,7 @@ mod tests {
                 "beacon" =>  PortalRpcModule::Beacon,
                 "discv5" =>  PortalRpcModule::Discv5,
                 "history" =>  PortalRpcModule::History,
+                "portal" =>  PortalRpcModule::Portal,
                 "web3" =>  PortalRpcModule::Web3,
             );
     }
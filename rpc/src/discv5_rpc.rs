@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
 use discv5::enr::NodeId;
-use ethportal_api::{types::enr::Enr, Discv5ApiServer, NodeInfo, RoutingTableInfo};
+use ethportal_api::{
+    types::enr::{enr_multiaddrs, Enr, RpcEnr},
+    Discv5ApiServer, NodeInfo, RoutingTableInfo,
+};
 use portalnet::discovery::Discovery;
 
 use crate::{
@@ -21,12 +24,17 @@ impl Discv5Api {
 
 #[async_trait]
 impl Discv5ApiServer for Discv5Api {
-    /// Returns ENR and Node ID information of the local discv5 node.
-    async fn node_info(&self) -> RpcResult<NodeInfo> {
-        Ok(self
+    /// Returns ENR and Node ID information of the local discv5 node, optionally with its
+    /// multiaddrs.
+    async fn node_info(&self, include_multiaddrs: Option<bool>) -> RpcResult<NodeInfo> {
+        let mut node_info = self
             .discv5
             .node_info()
-            .map_err(|err| RpcServeError::Message(err.to_string()))?)
+            .map_err(|err| RpcServeError::Message(err.to_string()))?;
+        if include_multiaddrs.unwrap_or(false) {
+            node_info.multiaddrs = Some(enr_multiaddrs(&node_info.enr));
+        }
+        Ok(node_info)
     }
 
     /// Update the socket address of the local node record.
@@ -44,7 +52,7 @@ impl Discv5ApiServer for Discv5Api {
     }
 
     /// Write an Ethereum Node Record to the routing table.
-    async fn add_enr(&self, _enr: Enr) -> RpcResult<bool> {
+    async fn add_enr(&self, _enr: RpcEnr) -> RpcResult<bool> {
         Err(RpcServeError::MethodNotFound("add_enr".to_owned()))?
     }
 
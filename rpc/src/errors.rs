@@ -1,8 +1,10 @@
 use std::io;
 
 use ethportal_api::{types::query_trace::QueryTrace, ContentValueError};
+use portalnet::overlay::errors::{OverlayRequestErrorKind, OverlayRequestJsonError};
 use reth_ipc::server::IpcServerStartError;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 use crate::{
     jsonrpsee::{
@@ -41,27 +43,85 @@ pub enum RpcServeError {
     /// Method not available
     #[error("Method not available: {0}")]
     MethodNotFound(String),
+    /// The parameters of the call are invalid, e.g. a content value that doesn't match its key
+    #[error("Invalid params: {0}")]
+    InvalidParams(String),
     /// ContentNotFound
     #[error("Content not found: {message}")]
     ContentNotFound {
         message: String,
         trace: Option<Box<QueryTrace>>,
     },
+    /// A request to a peer failed
+    #[error("Overlay request failed: {message}")]
+    OverlayRequest {
+        kind: OverlayRequestErrorKind,
+        message: String,
+    },
+    /// An error of a request handled by a subnetwork, along with the id that the request is
+    /// logged with
+    #[error("{error}")]
+    WithRequestId {
+        error: Box<RpcServeError>,
+        request_id: String,
+    },
+}
+
+/// Error code for a generic server error, for errors that don't have a more specific code.
+///
+/// It's in the range reserved for implementation defined server errors, see
+/// https://www.jsonrpc.org/specification#error_object, without conflicting with the standard
+/// Ethereum error codes: https://docs.infura.io/networks/ethereum/json-rpc-methods#error-codes
+pub const SERVER_ERROR_CODE: i32 = -32099;
+/// Error code for a request to a peer whose ENR doesn't have a reachable address.
+pub const UNREACHABLE_ADDRESS_ERROR_CODE: i32 = -39002;
+/// Error code for a request that failed because of the discv5 session with the peer.
+pub const SESSION_FAILURE_ERROR_CODE: i32 = -39003;
+/// Error code for a request to which the peer didn't respond in time.
+pub const TIMEOUT_ERROR_CODE: i32 = -39004;
+/// Error code for a request whose response couldn't be decoded.
+pub const DECODE_FAILURE_ERROR_CODE: i32 = -39005;
+/// Error code for a request to which the peer responded with an error.
+pub const REMOTE_ERROR_CODE: i32 = -39006;
+
+/// Returns the error code matching the kind of the failed overlay request.
+fn overlay_request_error_code(kind: OverlayRequestErrorKind) -> i32 {
+    match kind {
+        OverlayRequestErrorKind::UnreachableAddress => UNREACHABLE_ADDRESS_ERROR_CODE,
+        OverlayRequestErrorKind::SessionFailure => SESSION_FAILURE_ERROR_CODE,
+        OverlayRequestErrorKind::Timeout => TIMEOUT_ERROR_CODE,
+        OverlayRequestErrorKind::DecodeFailure => DECODE_FAILURE_ERROR_CODE,
+        OverlayRequestErrorKind::RemoteError => REMOTE_ERROR_CODE,
+        OverlayRequestErrorKind::Other => SERVER_ERROR_CODE,
+    }
 }
 
 impl From<RpcServeError> for ErrorObjectOwned {
     fn from(e: RpcServeError) -> Self {
         match e {
-            // -32099 is a custom error code for a server error
-            // see: https://www.jsonrpc.org/specification#error_object
-            // It's a bit of a cop-out, until we implement more specific errors, being
-            // sure not to conflict with the standard Ethereum error codes:
-            // https://docs.infura.io/networks/ethereum/json-rpc-methods#error-codes
-            RpcServeError::Message(msg) => ErrorObject::owned(-32099, msg, None::<()>),
+            RpcServeError::Message(msg) => ErrorObject::owned(SERVER_ERROR_CODE, msg, None::<()>),
             RpcServeError::MethodNotFound(method) => ErrorObject::owned(-32601, method, None::<()>),
+            RpcServeError::InvalidParams(msg) => ErrorObject::owned(-32602, msg, None::<()>),
             RpcServeError::ContentNotFound { message, trace } => {
                 ErrorObject::owned(-39001, message, Some(trace))
             }
+            RpcServeError::OverlayRequest { kind, message } => {
+                ErrorObject::owned(overlay_request_error_code(kind), message, None::<()>)
+            }
+            RpcServeError::WithRequestId { error, request_id } => {
+                let error = ErrorObjectOwned::from(*error);
+                // The request id is added to the data of the error, which is an object if the error
+                // has any data.
+                let mut data = match error
+                    .data()
+                    .and_then(|data| serde_json::from_str::<Value>(data.get()).ok())
+                {
+                    Some(Value::Object(data)) => data,
+                    _ => Map::new(),
+                };
+                data.insert("requestId".to_string(), Value::String(request_id));
+                ErrorObject::owned(error.code(), error.message().to_string(), Some(data))
+            }
         }
     }
 }
@@ -71,20 +131,43 @@ impl From<RpcServeError> for ErrorObjectOwned {
 pub struct ContentNotFoundJsonError {
     pub message: String,
     pub trace: Option<QueryTrace>,
+    /// If the error is a cached result, when the original lookup started, in milliseconds since
+    /// the Unix epoch.
+    #[serde(rename = "cachedAtMs", default)]
+    pub cached_at_ms: Option<u64>,
 }
 
 impl From<ContentNotFoundJsonError> for RpcServeError {
     fn from(e: ContentNotFoundJsonError) -> Self {
+        let message = match e.cached_at_ms {
+            Some(cached_at_ms) => format!(
+                "{}: cached result of a lookup started at {cached_at_ms} ms since the Unix epoch",
+                e.message
+            ),
+            None => e.message,
+        };
         RpcServeError::ContentNotFound {
-            message: e.message,
+            message,
             trace: e.trace.map(Box::new),
         }
     }
 }
 
+impl From<OverlayRequestJsonError> for RpcServeError {
+    fn from(e: OverlayRequestJsonError) -> Self {
+        RpcServeError::OverlayRequest {
+            kind: e.kind,
+            message: e.message,
+        }
+    }
+}
+
 impl From<ContentValueError> for RpcServeError {
     fn from(err: ContentValueError) -> Self {
-        RpcServeError::Message(format!("Error decoding content value: {err}"))
+        match err {
+            ContentValueError::KeyMismatch { .. } => RpcServeError::InvalidParams(err.to_string()),
+            _ => RpcServeError::Message(format!("Error decoding content value: {err}")),
+        }
     }
 }
 
@@ -108,3 +191,65 @@ pub enum WsHttpSamePortError {
         ws_modules: Vec<PortalRpcModule>,
     },
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn with_request_id(error: RpcServeError) -> ErrorObjectOwned {
+        RpcServeError::WithRequestId {
+            error: Box::new(error),
+            request_id: "test-request".to_string(),
+        }
+        .into()
+    }
+
+    fn data(error: &ErrorObjectOwned) -> Value {
+        serde_json::from_str(error.data().unwrap().get()).unwrap()
+    }
+
+    #[test]
+    fn request_id_is_added_to_error_without_data() {
+        let error = with_request_id(RpcServeError::Message("failed".to_string()));
+        assert_eq!(error.code(), SERVER_ERROR_CODE);
+        assert_eq!(error.message(), "failed");
+        assert_eq!(data(&error), json!({ "requestId": "test-request" }));
+
+        let error = with_request_id(RpcServeError::ContentNotFound {
+            message: "Unable to locate content on the network".to_string(),
+            trace: None,
+        });
+        assert_eq!(error.code(), -39001);
+        assert_eq!(data(&error), json!({ "requestId": "test-request" }));
+    }
+
+    #[test]
+    fn content_key_mismatch_is_invalid_params() {
+        let error = ErrorObjectOwned::from(RpcServeError::from(ContentValueError::KeyMismatch {
+            content_key: "0x00".to_string(),
+        }));
+        assert_eq!(error.code(), -32602);
+        assert_eq!(
+            error.message(),
+            "content value doesn't match the type of content key 0x00"
+        );
+    }
+
+    #[test]
+    fn request_id_is_added_to_error_data() {
+        let trace = QueryTrace::new(
+            &ethportal_api::types::enr::generate_random_remote_enr().1,
+            [1; 32].into(),
+        );
+        let error = with_request_id(RpcServeError::ContentNotFound {
+            message: "Unable to locate content on the network".to_string(),
+            trace: Some(Box::new(trace.clone())),
+        });
+        let mut expected = serde_json::to_value(trace).unwrap();
+        expected["requestId"] = json!("test-request");
+        assert_eq!(data(&error), expected);
+    }
+}
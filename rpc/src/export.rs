@@ -0,0 +1,141 @@
+use std::time::Instant;
+
+use alloy::primitives::B256;
+use ethportal_api::{
+    types::{
+        jsonrpc::{endpoints::SubnetworkEndpoint, request::JsonRpcRequest},
+        portal::{
+            ContentRecord, ExportContentItem, ExportContentSummary, PaginateLocalContentInfo,
+            StreamLocalContentKeysItem, StreamLocalContentKeysSummary,
+        },
+    },
+    OverlayContentKey,
+};
+use tokio::sync::mpsc;
+
+use crate::{
+    errors::RpcServeError,
+    fetch::proxy_to_subnet,
+    jsonrpsee::{core::SubscriptionResult, PendingSubscriptionSink, SubscriptionMessage},
+};
+
+/// The number of content entries that are fetched from the database at once, if not specified.
+const DEFAULT_EXPORT_BATCH_SIZE: u64 = 100;
+
+/// The max number of content entries that can be fetched from the database at once.
+const MAX_EXPORT_BATCH_SIZE: u64 = 10_000;
+
+/// Streams all locally stored content of the subnetwork to the subscriber, ordered by content id,
+/// followed by the summary of the export.
+///
+/// Content is fetched from the subnetwork in batches, where `batch_endpoint` creates the request
+/// for the batch of the given size that follows the given content id. The store isn't locked
+/// between batches, so content that is written during the export may or may not be included.
+pub async fn export_content<TEndpoint, TContentKey>(
+    network: &mpsc::UnboundedSender<JsonRpcRequest<TEndpoint>>,
+    pending: PendingSubscriptionSink,
+    batch_size: Option<u64>,
+    batch_endpoint: impl Fn(Option<B256>, u64) -> TEndpoint,
+) -> SubscriptionResult
+where
+    TEndpoint: SubnetworkEndpoint + Clone,
+    TContentKey: OverlayContentKey,
+{
+    let batch_size = batch_size.unwrap_or(DEFAULT_EXPORT_BATCH_SIZE);
+    if !(1..=MAX_EXPORT_BATCH_SIZE).contains(&batch_size) {
+        pending
+            .reject(RpcServeError::Message(format!(
+                "Invalid batch size: {batch_size}, expected between 1 and {MAX_EXPORT_BATCH_SIZE}"
+            )))
+            .await;
+        return Ok(());
+    }
+    let sink = pending.accept().await?;
+
+    let start = Instant::now();
+    let mut count = 0;
+    let mut total_bytes = 0;
+    let mut after = None;
+    loop {
+        let records: Vec<ContentRecord<TContentKey>> =
+            proxy_to_subnet(network, batch_endpoint(after, batch_size)).await?;
+        let is_last_batch = (records.len() as u64) < batch_size;
+        if let Some(last_record) = records.last() {
+            after = Some(B256::from(last_record.content_key.content_id()));
+        }
+        for record in records {
+            count += 1;
+            total_bytes += record.content_value.len() as u64;
+            let item = ExportContentItem::Content(record);
+            sink.send(SubscriptionMessage::from_json(&item)?).await?;
+        }
+        if is_last_batch {
+            break;
+        }
+    }
+
+    let item = ExportContentItem::<TContentKey>::Summary(ExportContentSummary {
+        count,
+        total_bytes,
+        duration_ms: start.elapsed().as_millis() as u64,
+    });
+    sink.send(SubscriptionMessage::from_json(&item)?).await?;
+    Ok(())
+}
+
+/// Streams all locally stored content keys of the subnetwork to the subscriber, in batches
+/// ordered by content id, followed by the summary once all keys were streamed.
+///
+/// The keys are paginated from the subnetwork, where `page_endpoint` creates the request for the
+/// page that follows the given cursor and has the given limit, so only a single batch is kept in
+/// memory at once. Each page continues after the last content id of the previous one, so content
+/// that is written or evicted during the enumeration doesn't cause other keys to be skipped or
+/// repeated. The enumeration stops once the subscriber unsubscribes.
+pub async fn stream_local_content_keys<TEndpoint, TContentKey>(
+    network: &mpsc::UnboundedSender<JsonRpcRequest<TEndpoint>>,
+    pending: PendingSubscriptionSink,
+    batch_size: Option<u64>,
+    page_endpoint: impl Fn(Option<B256>, u64) -> TEndpoint,
+) -> SubscriptionResult
+where
+    TEndpoint: SubnetworkEndpoint + Clone,
+    TContentKey: OverlayContentKey,
+{
+    let batch_size = batch_size.unwrap_or(DEFAULT_EXPORT_BATCH_SIZE);
+    if !(1..=MAX_EXPORT_BATCH_SIZE).contains(&batch_size) {
+        pending
+            .reject(RpcServeError::Message(format!(
+                "Invalid batch size: {batch_size}, expected between 1 and {MAX_EXPORT_BATCH_SIZE}"
+            )))
+            .await;
+        return Ok(());
+    }
+    let sink = pending.accept().await?;
+
+    let start = Instant::now();
+    let mut count = 0;
+    let mut cursor = None;
+    loop {
+        let page: PaginateLocalContentInfo<TContentKey> =
+            proxy_to_subnet(network, page_endpoint(cursor, batch_size)).await?;
+        if !page.content_keys.is_empty() {
+            count += page.content_keys.len() as u64;
+            let item = StreamLocalContentKeysItem::ContentKeys {
+                content_keys: page.content_keys,
+            };
+            // Fails once the subscriber unsubscribed or disconnected, which ends the enumeration.
+            sink.send(SubscriptionMessage::from_json(&item)?).await?;
+        }
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    let item = StreamLocalContentKeysItem::<TContentKey>::Summary(StreamLocalContentKeysSummary {
+        count,
+        duration_ms: start.elapsed().as_millis() as u64,
+    });
+    sink.send(SubscriptionMessage::from_json(&item)?).await?;
+    Ok(())
+}
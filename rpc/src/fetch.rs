@@ -1,9 +1,14 @@
-use ethportal_api::types::jsonrpc::{endpoints::SubnetworkEndpoint, request::JsonRpcRequest};
+use ethportal_api::types::{
+    content_value::error::{ContentKeyMismatchJsonError, ContentValueError},
+    jsonrpc::{endpoints::SubnetworkEndpoint, request::JsonRpcRequest},
+};
+use portalnet::overlay::errors::OverlayRequestJsonError;
 use serde_json::Value;
 use tokio::sync::mpsc;
 
 use crate::{
     errors::{ContentNotFoundJsonError, RpcServeError},
+    logging::current_request_id,
     serde::from_value,
 };
 
@@ -17,19 +22,36 @@ where
     TOutput: serde::de::DeserializeOwned,
 {
     let (resp_tx, mut resp_rx) = mpsc::unbounded_channel::<Result<Value, String>>();
-    let message = JsonRpcRequest {
-        endpoint,
-        resp: resp_tx,
+    // The request keeps the id of the RPC call that it's made for, so that their logs match.
+    let message = match current_request_id() {
+        Some(request_id) => JsonRpcRequest {
+            endpoint,
+            resp: resp_tx,
+            request_id,
+        },
+        None => JsonRpcRequest::new(endpoint, resp_tx),
     };
+    let request_id = message.request_id.clone();
     let _ = network.send(message);
 
-    let Some(response) = resp_rx.recv().await else {
-        return Err(RpcServeError::Message(format!(
+    let result = match resp_rx.recv().await {
+        Some(response) => parse_response(response),
+        None => Err(RpcServeError::Message(format!(
             "Internal error: No response from {} subnetwork",
             TEndpoint::subnetwork()
-        )));
+        ))),
     };
+    result.map_err(|error| RpcServeError::WithRequestId {
+        error: Box::new(error),
+        request_id,
+    })
+}
 
+/// Parses the response of a subnetwork, recovering the errors that were sent in their JSON format.
+fn parse_response<TOutput>(response: Result<Value, String>) -> Result<TOutput, RpcServeError>
+where
+    TOutput: serde::de::DeserializeOwned,
+{
     match response {
         Ok(result) => from_value(result),
         Err(msg) => {
@@ -38,7 +60,37 @@ where
                     return Err(err.into());
                 }
             }
+            if let Ok(err) = serde_json::from_str::<OverlayRequestJsonError>(&msg) {
+                return Err(err.into());
+            }
+            if let Ok(err) = serde_json::from_str::<ContentKeyMismatchJsonError>(&msg) {
+                return Err(ContentValueError::from(err).into());
+            }
             Err(RpcServeError::Message(msg))
         }
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_key_mismatch_is_parsed_as_invalid_params() {
+        let err = ContentValueError::KeyMismatch {
+            content_key: "0x00".to_string(),
+        };
+        let Err(RpcServeError::InvalidParams(message)) =
+            parse_response::<Value>(Err(err.to_json_string()))
+        else {
+            panic!("Expected invalid params error");
+        };
+        assert_eq!(message, err.to_string());
+
+        // Other errors that mention the mismatch aren't invalid params.
+        let Err(RpcServeError::Message(_)) = parse_response::<Value>(Err(err.to_string())) else {
+            panic!("Expected message error");
+        };
+    }
+}
@@ -8,8 +8,14 @@ mod discv5_rpc;
 mod errors;
 mod eth_rpc;
 mod evm_state;
+mod export;
 mod fetch;
 mod history_rpc;
+mod limits;
+mod logging;
+mod offer;
+mod paginate;
+mod portal_rpc;
 mod rpc_server;
 mod serde;
 mod state_rpc;
@@ -17,13 +23,19 @@ mod web3_rpc;
 
 use std::{
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    path::PathBuf,
     sync::Arc,
+    time::Duration,
 };
 
 use beacon_rpc::BeaconNetworkApi;
 pub use builder::{PortalRpcModule, RpcModuleBuilder, TransportRpcModuleConfig};
 use discv5_rpc::Discv5Api;
 use errors::RpcError;
+pub use errors::{
+    DECODE_FAILURE_ERROR_CODE, REMOTE_ERROR_CODE, SERVER_ERROR_CODE, SESSION_FAILURE_ERROR_CODE,
+    TIMEOUT_ERROR_CODE, UNREACHABLE_ADDRESS_ERROR_CODE,
+};
 use eth_rpc::EthApi;
 use ethportal_api::{
     jsonrpsee,
@@ -34,6 +46,7 @@ use ethportal_api::{
     },
 };
 use history_rpc::HistoryNetworkApi;
+use portal_rpc::PortalApi;
 use portalnet::discovery::Discovery;
 use reth_ipc::server::Builder as IpcServerBuilder;
 use state_rpc::StateNetworkApi;
@@ -41,17 +54,24 @@ use tokio::sync::mpsc;
 use web3_rpc::Web3Api;
 
 pub use crate::rpc_server::RpcServerHandle;
-use crate::{jsonrpsee::server::ServerBuilder, rpc_server::RpcServerConfig};
+use crate::{
+    jsonrpsee::server::ServerBuilder, logging::RpcLoggingConfig, rpc_server::RpcServerConfig,
+};
 
 pub async fn launch_jsonrpc_server(
     trin_config: TrinConfig,
     discv5: Arc<Discovery>,
+    node_data_dir: PathBuf,
     history_handler: Option<mpsc::UnboundedSender<HistoryJsonRpcRequest>>,
     state_handler: Option<mpsc::UnboundedSender<StateJsonRpcRequest>>,
     beacon_handler: Option<mpsc::UnboundedSender<BeaconJsonRpcRequest>>,
 ) -> Result<RpcServerHandle, RpcError> {
-    // Discv5 and Web3 modules are enabled with every network
-    let mut modules = vec![PortalRpcModule::Discv5, PortalRpcModule::Web3];
+    // Discv5, Portal and Web3 modules are enabled with every network
+    let mut modules = vec![
+        PortalRpcModule::Discv5,
+        PortalRpcModule::Portal,
+        PortalRpcModule::Web3,
+    ];
 
     for network in trin_config.portal_subnetworks.iter() {
         match network {
@@ -65,10 +85,18 @@ pub async fn launch_jsonrpc_server(
         }
     }
 
+    let logging = RpcLoggingConfig {
+        slow_call_threshold: Duration::from_millis(trin_config.rpc_slow_call_threshold),
+        always_log_methods: trin_config.rpc_log_methods.iter().cloned().collect(),
+    };
+
     let handle: RpcServerHandle = match trin_config.web3_transport {
         Web3TransportType::IPC => {
             let transport = TransportRpcModuleConfig::default().with_ipc(modules);
             let transport_modules = RpcModuleBuilder::new(discv5)
+                .with_node_data_dir(node_data_dir)
+                .with_max_paginate_limit(trin_config.rpc_max_paginate_limit)
+                .with_beacon_chain(trin_config.chain_config())
                 .maybe_with_history(history_handler)
                 .maybe_with_beacon(beacon_handler)
                 .maybe_with_state(state_handler)
@@ -82,6 +110,9 @@ pub async fn launch_jsonrpc_server(
                         .expect("Path should be string"),
                 )
                 .with_ipc(IpcServerBuilder::default())
+                .with_logging(logging)
+                .with_max_batch_size(trin_config.rpc_max_batch_size)
+                .with_max_in_flight_calls(trin_config.rpc_max_in_flight_calls as usize)
                 .start(transport_modules)
                 .await?
         }
@@ -93,6 +124,9 @@ pub async fn launch_jsonrpc_server(
             let transport = transport.with_http(modules);
 
             let transport_modules = RpcModuleBuilder::new(discv5)
+                .with_node_data_dir(node_data_dir)
+                .with_max_paginate_limit(trin_config.rpc_max_paginate_limit)
+                .with_beacon_chain(trin_config.chain_config())
                 .maybe_with_history(history_handler)
                 .maybe_with_beacon(beacon_handler)
                 .maybe_with_state(state_handler)
@@ -105,7 +139,8 @@ pub async fn launch_jsonrpc_server(
                         .socket_addrs(|| None)
                         .expect("Invalid socket address")[0],
                 )
-                .with_http(ServerBuilder::default());
+                .with_http(ServerBuilder::default())
+                .with_logging(logging);
             let rpc_server_config = match trin_config.ws {
                 true => rpc_server_config
                     .with_ws_address(SocketAddr::V4(SocketAddrV4::new(
@@ -115,7 +150,11 @@ pub async fn launch_jsonrpc_server(
                     .with_ws(ServerBuilder::default()),
                 false => rpc_server_config,
             };
-            rpc_server_config.start(transport_modules).await?
+            rpc_server_config
+                .with_max_batch_size(trin_config.rpc_max_batch_size)
+                .with_max_in_flight_calls(trin_config.rpc_max_in_flight_calls as usize)
+                .start(transport_modules)
+                .await?
         }
     };
 
@@ -0,0 +1,197 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use serde::de::IgnoredAny;
+use serde_json::json;
+use tokio::sync::Semaphore;
+use tower::{layer::Layer, Service};
+
+use crate::jsonrpsee::{
+    server::middleware::rpc::RpcServiceT,
+    types::{error::reject_too_big_batch_request, Request},
+    MethodResponse,
+};
+
+/// A [Layer] that limits the number of RPC calls that are handled at once, across all the servers
+/// that share it.
+///
+/// The calls of a batch request are handled concurrently, each call being sent to the channel of
+/// its subnetwork, so the calls above the limit wait for the others to finish.
+#[derive(Clone, Debug)]
+pub struct InFlightLimitLayer {
+    semaphore: Arc<Semaphore>,
+}
+
+impl InFlightLimitLayer {
+    pub fn new(max_in_flight_calls: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_in_flight_calls)),
+        }
+    }
+}
+
+impl Default for InFlightLimitLayer {
+    fn default() -> Self {
+        Self::new(Semaphore::MAX_PERMITS)
+    }
+}
+
+impl<S> Layer<S> for InFlightLimitLayer {
+    type Service = InFlightLimit<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        InFlightLimit {
+            service,
+            semaphore: self.semaphore.clone(),
+        }
+    }
+}
+
+/// The RPC service that limits the number of calls of the inner service that are handled at once.
+/// See [InFlightLimitLayer].
+#[derive(Clone, Debug)]
+pub struct InFlightLimit<S> {
+    service: S,
+    semaphore: Arc<Semaphore>,
+}
+
+impl<'a, S> RpcServiceT<'a> for InFlightLimit<S>
+where
+    S: RpcServiceT<'a> + Clone + Send + Sync + 'a,
+    S::Future: 'a,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let service = self.service.clone();
+        let semaphore = self.semaphore.clone();
+        Box::pin(async move {
+            // The semaphore is never closed.
+            let _permit = semaphore.acquire().await;
+            service.call(request).await
+        })
+    }
+}
+
+/// A [Layer] of the ipc server that rejects batch requests with more calls than the limit, with a
+/// single error response, as the http and ws servers do.
+#[derive(Clone, Copy, Debug)]
+pub struct IpcBatchLimitLayer {
+    max_batch_size: usize,
+}
+
+impl IpcBatchLimitLayer {
+    pub fn new(max_batch_size: usize) -> Self {
+        Self { max_batch_size }
+    }
+}
+
+impl Default for IpcBatchLimitLayer {
+    fn default() -> Self {
+        Self::new(usize::MAX)
+    }
+}
+
+impl<S> Layer<S> for IpcBatchLimitLayer {
+    type Service = IpcBatchLimit<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        IpcBatchLimit {
+            service,
+            max_batch_size: self.max_batch_size,
+        }
+    }
+}
+
+/// The ipc service that rejects the batch requests with more calls than the limit. See
+/// [IpcBatchLimitLayer].
+#[derive(Clone, Debug)]
+pub struct IpcBatchLimit<S> {
+    service: S,
+    max_batch_size: usize,
+}
+
+impl<S> Service<String> for IpcBatchLimit<S>
+where
+    S: Service<String, Response = Option<String>>,
+    S::Error: Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Option<String>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: String) -> Self::Future {
+        match batch_len(&request) {
+            Some(len) if len > self.max_batch_size => {
+                let response = json!({
+                    "jsonrpc": "2.0",
+                    "error": reject_too_big_batch_request(self.max_batch_size),
+                    "id": null,
+                });
+                Box::pin(std::future::ready(Ok(Some(response.to_string()))))
+            }
+            _ => Box::pin(self.service.call(request)),
+        }
+    }
+}
+
+/// Returns the number of calls of the request if it is a batch request. Invalid requests are left
+/// to the server to reject.
+fn batch_len(request: &str) -> Option<usize> {
+    if !request.trim_start().starts_with('[') {
+        return None;
+    }
+    serde_json::from_str::<Vec<IgnoredAny>>(request)
+        .ok()
+        .map(|calls| calls.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use serde_json::Value;
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    async fn call_ipc(max_batch_size: usize, request: Value) -> Value {
+        let service = IpcBatchLimitLayer::new(max_batch_size).layer(service_fn(
+            |request: String| async move { Ok::<_, Infallible>(Some(request)) },
+        ));
+        let response = service.oneshot(request.to_string()).await.unwrap().unwrap();
+        serde_json::from_str(&response).unwrap()
+    }
+
+    #[tokio::test]
+    async fn ipc_batches_above_the_limit_are_rejected() {
+        let call =
+            |id: u64| json!({ "jsonrpc": "2.0", "id": id, "method": "portal_historyRadius" });
+
+        // Requests within the limit reach the server.
+        let batch = json!([call(1), call(2)]);
+        assert_eq!(call_ipc(2, batch.clone()).await, batch);
+        assert_eq!(call_ipc(2, call(1)).await, call(1));
+
+        let response = call_ipc(2, json!([call(1), call(2), call(3)])).await;
+        assert_eq!(response["error"]["code"], -32010, "{response}");
+        assert!(response["id"].is_null());
+    }
+
+    #[test]
+    fn batch_len_counts_the_calls_of_batches_only() {
+        assert_eq!(batch_len(" [{}, {}, 1]"), Some(3));
+        assert_eq!(batch_len("[]"), Some(0));
+        assert_eq!(batch_len(r#"{"method": "[]"}"#), None);
+        assert_eq!(batch_len("[{}"), None);
+    }
+}
@@ -0,0 +1,423 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use ethportal_api::{
+    types::cli::DEFAULT_RPC_SLOW_CALL_THRESHOLD_MS, BeaconContentKey, HistoryContentKey,
+    OverlayContentKey, StateContentKey,
+};
+use serde_json::Value;
+use tower::layer::Layer;
+use tracing::{debug, info, info_span, Instrument};
+use trin_metrics::rpc::RpcMetricsReporter;
+use uuid::Uuid;
+
+use crate::jsonrpsee::{
+    server::middleware::rpc::RpcServiceT,
+    types::{ErrorCode, Id, Request},
+    MethodResponse,
+};
+
+tokio::task_local! {
+    /// The id of the RPC call that is being handled, which its subnetwork requests are tagged with.
+    static CALL_REQUEST_ID: String;
+}
+
+/// Returns the request id of the RPC call that is being handled, if any.
+pub(crate) fn current_request_id() -> Option<String> {
+    CALL_REQUEST_ID.try_with(String::clone).ok()
+}
+
+/// Returns the request id of the call: the caller's id if it is a string, so that callers can
+/// correlate the call with the logs of the node, and a new random id otherwise. Numeric ids are
+/// only unique per connection, so they aren't reused.
+fn request_id(request: &Request) -> String {
+    match &request.id {
+        Id::Str(id) => id.to_string(),
+        Id::Number(_) | Id::Null => Uuid::new_v4().to_string(),
+    }
+}
+
+/// The max length of a hex string in the params of a logged call of a method that isn't part of a
+/// subnetwork. Longer hex strings are logged as their length in bytes instead.
+const MAX_LOGGED_HEX_LEN: usize = 2 + 2 * 64;
+
+/// The transport that RPC calls are received over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transport {
+    Http,
+    Ws,
+    /// Http and WS on the same port, which aren't told apart.
+    WsHttp,
+    Ipc,
+}
+
+impl fmt::Display for Transport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Transport::Http => write!(f, "http"),
+            Transport::Ws => write!(f, "ws"),
+            Transport::WsHttp => write!(f, "ws_http"),
+            Transport::Ipc => write!(f, "ipc"),
+        }
+    }
+}
+
+/// Configures the logging of RPC calls.
+#[derive(Clone, Debug)]
+pub struct RpcLoggingConfig {
+    /// Calls that take at least this long are logged at info level, and faster calls at debug
+    /// level.
+    pub slow_call_threshold: Duration,
+    /// The methods whose calls are always logged at info level, regardless of their duration.
+    pub always_log_methods: HashSet<String>,
+}
+
+impl Default for RpcLoggingConfig {
+    fn default() -> Self {
+        Self {
+            slow_call_threshold: Duration::from_millis(DEFAULT_RPC_SLOW_CALL_THRESHOLD_MS),
+            always_log_methods: HashSet::new(),
+        }
+    }
+}
+
+/// A [Layer] that logs the RPC calls of a server, and reports their duration to the metrics.
+#[derive(Clone, Debug)]
+pub struct RpcLoggerLayer {
+    config: Arc<RpcLoggingConfig>,
+    transport: Transport,
+    metrics: RpcMetricsReporter,
+}
+
+impl RpcLoggerLayer {
+    pub fn new(config: RpcLoggingConfig, transport: Transport) -> Self {
+        Self {
+            config: Arc::new(config),
+            transport,
+            metrics: RpcMetricsReporter::default(),
+        }
+    }
+}
+
+impl<S> Layer<S> for RpcLoggerLayer {
+    type Service = RpcLogger<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RpcLogger {
+            service,
+            config: self.config.clone(),
+            transport: self.transport,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+/// The RPC service that logs the calls of the inner service. See [RpcLoggerLayer].
+#[derive(Clone, Debug)]
+pub struct RpcLogger<S> {
+    service: S,
+    config: Arc<RpcLoggingConfig>,
+    transport: Transport,
+    metrics: RpcMetricsReporter,
+}
+
+impl<'a, S> RpcServiceT<'a> for RpcLogger<S>
+where
+    S: RpcServiceT<'a> + Send + Sync,
+    S::Future: 'a,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let method = request.method_name().to_string();
+        let params = request
+            .params
+            .as_ref()
+            .map(|params| params.get().to_string());
+        let config = self.config.clone();
+        let transport = self.transport;
+        let metrics = self.metrics.clone();
+        let start = Instant::now();
+        let request_id = request_id(&request);
+        let span = info_span!("rpc_call", request_id = %request_id);
+        let response = CALL_REQUEST_ID.scope(request_id, self.service.call(request));
+
+        Box::pin(
+            async move {
+                let response = response.await;
+                let record = CallRecord::new(
+                    method,
+                    params,
+                    transport,
+                    start.elapsed(),
+                    response.is_success(),
+                );
+                let is_method_registered =
+                    response.as_error_code() != Some(ErrorCode::MethodNotFound.code());
+                metrics.report_call(
+                    is_method_registered.then_some(record.method.as_str()),
+                    &transport.to_string(),
+                    record.success,
+                    record.duration,
+                );
+                log_call(&config, &record);
+                response
+            }
+            .instrument(span),
+        )
+    }
+}
+
+/// The logged record of an RPC call.
+#[derive(Debug)]
+struct CallRecord {
+    method: String,
+    /// The params as they were received. They are only redacted if the call is logged.
+    params: Option<String>,
+    transport: Transport,
+    duration: Duration,
+    success: bool,
+}
+
+impl CallRecord {
+    fn new(
+        method: String,
+        params: Option<String>,
+        transport: Transport,
+        duration: Duration,
+        success: bool,
+    ) -> Self {
+        Self {
+            method,
+            params,
+            transport,
+            duration,
+            success,
+        }
+    }
+
+    /// The size of the params, in bytes.
+    fn params_len(&self) -> usize {
+        self.params.as_ref().map_or(0, String::len)
+    }
+}
+
+/// Formats the params of a call of the method, redacted by [redact_params]. The params are only
+/// parsed once the log event is formatted, so calls that aren't logged don't pay for it.
+struct RedactedParams<'a> {
+    method: &'a str,
+    params: Option<&'a str>,
+}
+
+impl fmt::Display for RedactedParams<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.params {
+            Some(params) => write!(f, "{}", redact_params(self.method, params)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Logs the call at info level if it's slow or if its method is always logged, and at debug level
+/// otherwise.
+fn log_call(config: &RpcLoggingConfig, record: &CallRecord) {
+    let outcome = if record.success { "success" } else { "error" };
+    let duration_ms = record.duration.as_millis() as u64;
+    if record.duration >= config.slow_call_threshold
+        || config.always_log_methods.contains(&record.method)
+    {
+        info!(
+            method = %record.method,
+            transport = %record.transport,
+            params_len = record.params_len(),
+            params = %RedactedParams {
+                method: &record.method,
+                params: record.params.as_deref(),
+            },
+            duration_ms,
+            outcome,
+            "RPC call"
+        );
+    } else {
+        debug!(
+            method = %record.method,
+            transport = %record.transport,
+            params_len = record.params_len(),
+            params = %RedactedParams {
+                method: &record.method,
+                params: record.params.as_deref(),
+            },
+            duration_ms,
+            outcome,
+            "RPC call"
+        );
+    }
+}
+
+/// Returns the params of a call of the method with the hex strings that may be content values
+/// replaced by their length in bytes, so that content values aren't logged whatever their size.
+///
+/// The hex strings of the params of a subnetwork method are redacted unless they are content keys
+/// of the subnetwork. Other methods don't take content values, so only their hex strings longer
+/// than [MAX_LOGGED_HEX_LEN] are redacted.
+fn redact_params(method: &str, params: &str) -> String {
+    match serde_json::from_str::<Value>(params) {
+        Ok(mut params) => {
+            redact_value(&mut params, content_key_check(method));
+            params.to_string()
+        }
+        Err(_) => format!("<{} bytes>", params.len()),
+    }
+}
+
+/// Returns the check of whether a hex string is a content key of the subnetwork of the method, if
+/// it's a subnetwork method.
+fn content_key_check(method: &str) -> Option<fn(&str) -> bool> {
+    if method.starts_with("portal_history") {
+        Some(|hex| HistoryContentKey::try_from_hex(hex).is_ok())
+    } else if method.starts_with("portal_state") {
+        Some(|hex| StateContentKey::try_from_hex(hex).is_ok())
+    } else if method.starts_with("portal_beacon") {
+        Some(|hex| BeaconContentKey::try_from_hex(hex).is_ok())
+    } else {
+        None
+    }
+}
+
+fn redact_value(value: &mut Value, is_content_key: Option<fn(&str) -> bool>) {
+    match value {
+        Value::String(string) if string.starts_with("0x") => {
+            let redacted = match is_content_key {
+                Some(is_content_key) => !is_content_key(string),
+                None => string.len() > MAX_LOGGED_HEX_LEN,
+            };
+            if redacted {
+                *string = format!("<{} bytes>", (string.len() - 2) / 2);
+            }
+        }
+        Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| redact_value(value, is_content_key)),
+        Value::Object(values) => values
+            .values_mut()
+            .for_each(|value| redact_value(value, is_content_key)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[test]
+    #[traced_test]
+    fn store_call_payload_is_redacted() {
+        let content_key = format!("0x00{}", "cd".repeat(32));
+        let content_value = format!("0x{}", "ab".repeat(1000));
+        let params = json!([content_key, content_value, null]).to_string();
+        let record = CallRecord::new(
+            "portal_historyStore".to_string(),
+            Some(params.clone()),
+            Transport::Ipc,
+            Duration::from_millis(3),
+            true,
+        );
+        let config = RpcLoggingConfig {
+            always_log_methods: HashSet::from(["portal_historyStore".to_string()]),
+            ..Default::default()
+        };
+        log_call(&config, &record);
+
+        assert!(logs_contain("INFO"));
+        assert!(logs_contain("method=portal_historyStore"));
+        assert!(logs_contain("transport=ipc"));
+        assert!(logs_contain(&format!("params_len={}", params.len())));
+        // The content key is short enough to be logged, but the content value is not.
+        assert!(logs_contain(&content_key));
+        assert!(logs_contain("<1000 bytes>"));
+        assert!(!logs_contain(&"ab".repeat(64)));
+    }
+
+    #[test]
+    #[traced_test]
+    fn fast_calls_are_logged_at_debug_level() {
+        let config = RpcLoggingConfig {
+            slow_call_threshold: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let fast_call = CallRecord::new(
+            "portal_historyPing".to_string(),
+            Some("[]".to_string()),
+            Transport::Http,
+            Duration::from_millis(99),
+            true,
+        );
+        log_call(&config, &fast_call);
+        assert!(logs_contain("DEBUG"));
+        assert!(!logs_contain("INFO"));
+
+        let slow_call = CallRecord::new(
+            "portal_historyGetContent".to_string(),
+            Some("[]".to_string()),
+            Transport::Ws,
+            Duration::from_millis(100),
+            false,
+        );
+        log_call(&config, &slow_call);
+        assert!(logs_contain("INFO"));
+        assert!(logs_contain("outcome=\"error\""));
+    }
+
+    #[test]
+    fn params_are_redacted_when_formatted() {
+        let params = format!(r#"["0x{}"]"#, "ab".repeat(100));
+        let redacted = RedactedParams {
+            method: "eth_call",
+            params: Some(&params),
+        };
+        assert_eq!(redacted.to_string(), r#"["<100 bytes>"]"#);
+        let redacted = RedactedParams {
+            method: "eth_call",
+            params: None,
+        };
+        assert_eq!(redacted.to_string(), "");
+    }
+
+    #[test]
+    fn short_content_values_are_redacted() {
+        let content_key = format!("0x00{}", "cd".repeat(32));
+        let params = json!([content_key, "0xabcd"]).to_string();
+        assert_eq!(
+            redact_params("portal_historyStore", &params),
+            json!([content_key, "<2 bytes>"]).to_string()
+        );
+        // A history content key isn't a beacon content key.
+        assert_eq!(
+            redact_params("portal_beaconStore", &params),
+            json!(["<33 bytes>", "<2 bytes>"]).to_string()
+        );
+        let params = json!(["enr:-abc", [[content_key, "0x"]]]).to_string();
+        assert_eq!(
+            redact_params("portal_historyOffer", &params),
+            json!(["enr:-abc", [[content_key, "<0 bytes>"]]]).to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_params_are_logged_as_their_length() {
+        assert_eq!(redact_params("eth_call", "[0xab"), "<5 bytes>");
+        assert_eq!(
+            redact_params("eth_call", r#"{"key":"0x1234","nested":[1,"abc"]}"#),
+            r#"{"key":"0x1234","nested":[1,"abc"]}"#
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use ethportal_api::OverlayContentKey;
+
+use crate::errors::RpcServeError;
+
+/// Checks the sizes of the content values to offer, before the offer is sent.
+///
+/// Values larger than the `max_size` of their content key are rejected, instead of failing during
+/// the uTP transfer of the offered content.
+pub fn check_offer_content_sizes<'a, TContentKey: OverlayContentKey + 'a>(
+    content_items: impl IntoIterator<Item = (&'a TContentKey, usize)>,
+    max_size: impl Fn(&TContentKey) -> usize,
+) -> Result<(), RpcServeError> {
+    for (content_key, size) in content_items {
+        let limit = max_size(content_key);
+        if size > limit {
+            return Err(RpcServeError::Message(format!(
+                "Content value of {content_key} is {size} bytes, over the max of {limit} bytes"
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use ethportal_api::{
+        types::{
+            content_key::beacon::{
+                HistoricalSummariesWithProofKey, LightClientBootstrapKey,
+                LightClientFinalityUpdateKey, LightClientOptimisticUpdateKey,
+                LightClientUpdatesByRangeKey,
+            },
+            portal::MAX_OFFER_CONTENT_VALUE_SIZE,
+        },
+        BeaconContentKey, HistoryContentKey,
+    };
+
+    fn check_history_size(size: usize) -> Result<(), RpcServeError> {
+        let content_key = HistoryContentKey::random().unwrap();
+        check_offer_content_sizes([(&content_key, size)], |_| MAX_OFFER_CONTENT_VALUE_SIZE)
+    }
+
+    #[test]
+    fn max_content_value_size() {
+        assert!(check_history_size(MAX_OFFER_CONTENT_VALUE_SIZE).is_ok());
+        assert!(check_history_size(MAX_OFFER_CONTENT_VALUE_SIZE + 1).is_err());
+    }
+
+    #[test]
+    fn beacon_max_content_value_sizes() {
+        let content_keys = [
+            BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey {
+                block_hash: [1; 32],
+            }),
+            BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                start_period: 1,
+                count: 4,
+            }),
+            BeaconContentKey::LightClientFinalityUpdate(LightClientFinalityUpdateKey {
+                finalized_slot: 1,
+            }),
+            BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey {
+                signature_slot: 1,
+            }),
+            BeaconContentKey::HistoricalSummariesWithProof(HistoricalSummariesWithProofKey {
+                epoch: 1,
+            }),
+        ];
+        for content_key in content_keys {
+            let max_size = content_key.max_content_value_size();
+            let check = |size| {
+                check_offer_content_sizes([(&content_key, size)], |key: &BeaconContentKey| {
+                    key.max_content_value_size()
+                })
+            };
+            assert!(check(max_size).is_ok(), "{content_key}");
+            assert!(check(max_size + 1).is_err(), "{content_key}");
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::errors::RpcServeError;
+
+/// Checks that a page of local content keys isn't larger than the max, so that a single request
+/// can't build an arbitrarily large response.
+pub fn check_paginate_limit(limit: u64, max_limit: u64) -> Result<(), RpcServeError> {
+    if limit > max_limit {
+        return Err(RpcServeError::InvalidParams(format!(
+            "Invalid limit: {limit}, expected at most {max_limit}. Use the cursor-based pagination to list all content keys"
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that the max number of ENRs kept from a FINDNODES response, if given, keeps at least one.
+pub fn check_max_results(max_results: Option<u64>) -> Result<(), RpcServeError> {
+    if max_results == Some(0) {
+        return Err(RpcServeError::InvalidParams(
+            "Invalid max_results: 0, expected at least 1".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_is_capped() {
+        assert!(check_paginate_limit(0, 1000).is_ok());
+        assert!(check_paginate_limit(1000, 1000).is_ok());
+
+        let RpcServeError::InvalidParams(message) = check_paginate_limit(1001, 1000).unwrap_err()
+        else {
+            panic!("Expected invalid params error");
+        };
+        assert!(
+            message.starts_with("Invalid limit: 1001, expected at most 1000"),
+            "Unexpected error: {message}"
+        );
+    }
+
+    #[test]
+    fn max_results_keeps_at_least_one_enr() {
+        assert!(check_max_results(None).is_ok());
+        assert!(check_max_results(Some(1)).is_ok());
+
+        let RpcServeError::InvalidParams(message) = check_max_results(Some(0)).unwrap_err() else {
+            panic!("Expected invalid params error");
+        };
+        assert_eq!(message, "Invalid max_results: 0, expected at least 1");
+    }
+}
@@ -0,0 +1,174 @@
+use std::{path::PathBuf, str::FromStr, sync::Arc};
+
+use discv5::enr::NodeId;
+use ethportal_api::{
+    types::{
+        enr::Enr,
+        jsonrpc::{
+            endpoints::{BeaconEndpoint, HistoryEndpoint, StateEndpoint},
+            request::{BeaconJsonRpcRequest, HistoryJsonRpcRequest, StateJsonRpcRequest},
+        },
+        network::Subnetwork,
+        portal::{AddBootnodeInfo, BootnodeBond, NodeOverview, PongInfo, UtpStatsInfo},
+    },
+    version::get_client_version,
+    PortalApiServer,
+};
+use portalnet::{
+    discovery::{check_enr, check_enr_network, Discovery},
+    utils::db::{persist_bootnode, remove_persisted_bootnode},
+};
+use tokio::sync::mpsc;
+use tracing::info;
+
+use crate::{
+    errors::RpcServeError,
+    fetch::proxy_to_subnet,
+    jsonrpsee::core::{async_trait, RpcResult},
+};
+
+pub struct PortalApi {
+    discv5: Arc<Discovery>,
+    node_data_dir: PathBuf,
+    history_tx: Option<mpsc::UnboundedSender<HistoryJsonRpcRequest>>,
+    state_tx: Option<mpsc::UnboundedSender<StateJsonRpcRequest>>,
+    beacon_tx: Option<mpsc::UnboundedSender<BeaconJsonRpcRequest>>,
+}
+
+impl PortalApi {
+    pub fn new(
+        discv5: Arc<Discovery>,
+        node_data_dir: PathBuf,
+        history_tx: Option<mpsc::UnboundedSender<HistoryJsonRpcRequest>>,
+        state_tx: Option<mpsc::UnboundedSender<StateJsonRpcRequest>>,
+        beacon_tx: Option<mpsc::UnboundedSender<BeaconJsonRpcRequest>>,
+    ) -> Self {
+        Self {
+            discv5,
+            node_data_dir,
+            history_tx,
+            state_tx,
+            beacon_tx,
+        }
+    }
+}
+
+/// Converts the result of pinging a bootnode into its bond result.
+fn bootnode_bond(ping_result: Result<PongInfo, RpcServeError>) -> BootnodeBond {
+    match ping_result {
+        Ok(_) => BootnodeBond {
+            bonded: true,
+            error: None,
+        },
+        Err(err) => BootnodeBond {
+            bonded: false,
+            error: Some(err.to_string()),
+        },
+    }
+}
+
+#[async_trait]
+impl PortalApiServer for PortalApi {
+    /// Bond with the given bootnode on every enabled subnetwork, and persist it.
+    async fn add_bootnode(&self, enr: String, force: Option<bool>) -> RpcResult<AddBootnodeInfo> {
+        let enr = Enr::from_str(enr.trim())
+            .map_err(|err| RpcServeError::Message(format!("Invalid ENR: {err}")))?;
+        check_enr(&enr, self.discv5.local_enr().node_id(), None, false)
+            .map_err(RpcServeError::Message)?;
+        check_enr_network(&enr, self.discv5.network()).map_err(RpcServeError::Message)?;
+
+        // Bond the same way as with the bootnodes provided at startup: add the bootnode to the
+        // discv5 routing table, and ping it on every subnetwork. A successful ping inserts it into
+        // the overlay routing table.
+        if let Err(err) = self.discv5.add_enr(enr.clone()) {
+            return Err(
+                RpcServeError::Message(format!("Failed to add bootnode enr: {err}")).into(),
+            );
+        }
+        let history = async {
+            match &self.history_tx {
+                Some(tx) => Some(bootnode_bond(
+                    proxy_to_subnet(tx, HistoryEndpoint::Ping(enr.clone())).await,
+                )),
+                None => None,
+            }
+        };
+        let state = async {
+            match &self.state_tx {
+                Some(tx) => Some(bootnode_bond(
+                    proxy_to_subnet(tx, StateEndpoint::Ping(enr.clone())).await,
+                )),
+                None => None,
+            }
+        };
+        let beacon = async {
+            match &self.beacon_tx {
+                Some(tx) => Some(bootnode_bond(
+                    proxy_to_subnet(tx, BeaconEndpoint::Ping(enr.clone())).await,
+                )),
+                None => None,
+            }
+        };
+        let (history, state, beacon) = tokio::join!(history, state, beacon);
+
+        let bonded = [&history, &state, &beacon]
+            .into_iter()
+            .flatten()
+            .any(|bond| bond.bonded);
+        let persisted = bonded || force.unwrap_or(false);
+        if persisted {
+            persist_bootnode(&self.node_data_dir, &enr).map_err(|err| {
+                RpcServeError::Message(format!("Failed to persist bootnode: {err}"))
+            })?;
+            info!(enr = %enr, "Added bootnode");
+        }
+
+        Ok(AddBootnodeInfo {
+            history,
+            state,
+            beacon,
+            persisted,
+        })
+    }
+
+    /// Remove the persisted bootnode with the given node id.
+    async fn remove_bootnode(&self, node_id: NodeId) -> RpcResult<bool> {
+        Ok(remove_persisted_bootnode(&self.node_data_dir, &node_id)
+            .map_err(|err| RpcServeError::Message(format!("Failed to remove bootnode: {err}")))?)
+    }
+
+    /// Returns an overview of the node.
+    async fn node_overview(&self) -> RpcResult<NodeOverview> {
+        let enr = self.discv5.local_enr();
+        let subnetworks = [
+            (Subnetwork::History, self.history_tx.is_some()),
+            (Subnetwork::State, self.state_tx.is_some()),
+            (Subnetwork::Beacon, self.beacon_tx.is_some()),
+        ]
+        .into_iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(subnetwork, _)| subnetwork.to_cli_arg())
+        .collect();
+        let advertised_address = self.discv5.advertised_address();
+        Ok(NodeOverview {
+            client_version: get_client_version(self.discv5.network()),
+            network: self.discv5.network().to_string(),
+            node_id: enr.node_id(),
+            enr,
+            subnetworks,
+            advertised_address: advertised_address.socket_addr,
+            address_changed_at: advertised_address.changed_at,
+        })
+    }
+
+    /// Returns the uTP traffic and the use of the rate limits.
+    async fn utp_stats(&self) -> RpcResult<UtpStatsInfo> {
+        Ok(self.discv5.utp_throttle().stats())
+    }
+}
+
+impl std::fmt::Debug for PortalApi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PortalApi").finish_non_exhaustive()
+    }
+}
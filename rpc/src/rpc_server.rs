@@ -18,19 +18,39 @@ use crate::{
     errors::WsHttpSamePortError,
     jsonrpsee::{
         http_client::{HttpClient, HttpClientBuilder},
-        server::{Server, ServerBuilder, ServerHandle},
+        server::{
+            middleware::rpc::RpcServiceBuilder, BatchRequestConfig, Server, ServerBuilder,
+            ServerHandle,
+        },
         ws_client::{WsClient, WsClientBuilder},
         RpcModule,
     },
+    limits::{InFlightLimitLayer, IpcBatchLimitLayer},
+    logging::{RpcLoggerLayer, RpcLoggingConfig, Transport},
     RpcError, TransportRpcModuleConfig,
 };
 
+/// The RPC middleware of the servers, which logs the calls and limits the number of calls that are
+/// handled at once.
+type RpcMiddleware = Stack<InFlightLimitLayer, Stack<RpcLoggerLayer, Identity>>;
+
+/// Returns the RPC middleware of a server of the transport.
+fn rpc_middleware(
+    logging: &RpcLoggingConfig,
+    in_flight_limit: &InFlightLimitLayer,
+    transport: Transport,
+) -> RpcServiceBuilder<RpcMiddleware> {
+    RpcServiceBuilder::new()
+        .layer(RpcLoggerLayer::new(logging.clone(), transport))
+        .layer(in_flight_limit.clone())
+}
+
 /// Container type for each transport ie. http, ws, and ipc server
 pub struct RpcServer {
     /// Configured ws,http servers
     pub ws_http: WsHttpServer,
     /// ipc server
-    pub ipc: Option<IpcServer>,
+    pub ipc: Option<IpcServer<Stack<IpcBatchLimitLayer, Identity>, RpcMiddleware>>,
 }
 
 impl RpcServer {
@@ -205,6 +225,17 @@ pub enum ServerKind {
     WsHttp(SocketAddr),
 }
 
+impl ServerKind {
+    /// Returns the transport of the calls that the server receives.
+    fn transport(&self) -> Transport {
+        match self {
+            ServerKind::Http(_) => Transport::Http,
+            ServerKind::WS(_) => Transport::Ws,
+            ServerKind::WsHttp(_) => Transport::WsHttp,
+        }
+    }
+}
+
 impl fmt::Display for ServerKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -244,6 +275,12 @@ pub struct RpcServerConfig {
     ipc_server_config: Option<IpcServerBuilder<Identity, Identity>>,
     /// The endpoint where to launch the ipc server
     ipc_endpoint: Option<String>,
+    /// Configs for the logging of the calls of all transports
+    logging: RpcLoggingConfig,
+    /// The max number of calls in a batch request of all transports
+    max_batch_size: Option<u32>,
+    /// Limits the number of calls of all transports that are handled at once
+    in_flight_limit: InFlightLimitLayer,
 }
 
 impl RpcServerConfig {
@@ -334,6 +371,28 @@ impl RpcServerConfig {
         self
     }
 
+    /// Limits the number of calls in a batch request of all transports. Larger batches are
+    /// rejected with a single error response.
+    pub fn with_max_batch_size(mut self, max_batch_size: u32) -> Self {
+        self.max_batch_size = Some(max_batch_size);
+        self
+    }
+
+    /// Limits the number of calls of all transports that are handled at once. The calls of a batch
+    /// request are handled concurrently, up to this limit.
+    ///
+    /// Default is no limit
+    pub fn with_max_in_flight_calls(mut self, max_in_flight_calls: usize) -> Self {
+        self.in_flight_limit = InFlightLimitLayer::new(max_in_flight_calls);
+        self
+    }
+
+    /// Configures the logging of the calls of all transports
+    pub fn with_logging(mut self, logging: RpcLoggingConfig) -> Self {
+        self.logging = logging;
+        self
+    }
+
     /// Configures the endpoint of the ipc server
     ///
     /// Default is [DEFAULT_WEB3_IPC_PATH]
@@ -419,6 +478,8 @@ impl RpcServerConfig {
                 http_socket_addr,
                 cors,
                 ServerKind::WsHttp(http_socket_addr),
+                &self.logging,
+                &self.in_flight_limit,
             )
             .await?;
             return Ok(WsHttpServer {
@@ -447,6 +508,8 @@ impl RpcServerConfig {
                 ws_socket_addr,
                 self.ws_cors_domains.take(),
                 ServerKind::WS(ws_socket_addr),
+                &self.logging,
+                &self.in_flight_limit,
             )
             .await?;
             ws_local_addr = Some(server.local_addr());
@@ -460,6 +523,8 @@ impl RpcServerConfig {
                 http_socket_addr,
                 self.http_cors_domains.take(),
                 ServerKind::Http(http_socket_addr),
+                &self.logging,
+                &self.in_flight_limit,
             )
             .await?;
             http_local_addr = Some(server.local_addr());
@@ -483,13 +548,34 @@ impl RpcServerConfig {
     /// Note: The server is not started and does nothing unless polled, See also [RpcServer::start]
     pub async fn build(mut self) -> Result<RpcServer, RpcError> {
         let mut server = RpcServer::empty();
+        if let Some(max_batch_size) = self.max_batch_size {
+            let config = BatchRequestConfig::Limit(max_batch_size);
+            self.http_server_config = self
+                .http_server_config
+                .map(|http| http.set_batch_request_config(config));
+            self.ws_server_config = self
+                .ws_server_config
+                .map(|ws| ws.set_batch_request_config(config));
+        }
         server.ws_http = self.build_ws_http().await?;
 
         if let Some(builder) = self.ipc_server_config {
             let ipc_path = self
                 .ipc_endpoint
                 .unwrap_or_else(|| DEFAULT_WEB3_IPC_PATH.to_string());
-            let ipc = builder.build(ipc_path);
+            let batch_limit = self
+                .max_batch_size
+                .map_or_else(IpcBatchLimitLayer::default, |max_batch_size| {
+                    IpcBatchLimitLayer::new(max_batch_size as usize)
+                });
+            let ipc = builder
+                .set_ipc_connection_middleware(tower::ServiceBuilder::new().layer(batch_limit))
+                .set_rpc_middleware(rpc_middleware(
+                    &self.logging,
+                    &self.in_flight_limit,
+                    Transport::Ipc,
+                ))
+                .build(ipc_path);
             server.ipc = Some(ipc);
         }
 
@@ -571,9 +657,9 @@ impl Default for WsHttpServers {
 /// Http Servers Enum
 pub enum WsHttpServerKind {
     /// Http server
-    Plain(Server),
+    Plain(Server<Identity, RpcMiddleware>),
     /// Http server with cors
-    WithCors(Server<Stack<CorsLayer, Identity>>),
+    WithCors(Server<Stack<CorsLayer, Identity>, RpcMiddleware>),
 }
 
 impl WsHttpServerKind {
@@ -591,7 +677,14 @@ impl WsHttpServerKind {
         socket_addr: SocketAddr,
         cors_domains: Option<String>,
         server_kind: ServerKind,
+        logging: &RpcLoggingConfig,
+        in_flight_limit: &InFlightLimitLayer,
     ) -> Result<Self, RpcError> {
+        let builder = builder.set_rpc_middleware(rpc_middleware(
+            logging,
+            in_flight_limit,
+            server_kind.transport(),
+        ));
         if let Some(cors) = cors_domains.as_deref().map(cors::create_cors_layer) {
             let cors = cors.map_err(|err| RpcError::Custom(err.to_string()))?;
             let server = builder
@@ -621,10 +714,34 @@ impl WsHttpServerKind {
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
-    use std::{io, sync::Arc};
-
-    use ethportal_api::types::portal_wire::MAINNET;
+    use std::{
+        io,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use alloy::primitives::{B256, U256};
+    use ethportal_api::{
+        jsonrpsee::{
+            core::{
+                client::{ClientT, IdKind},
+                params::BatchRequestBuilder,
+                ClientError,
+            },
+            rpc_params,
+        },
+        types::{
+            jsonrpc::{endpoints::HistoryEndpoint, request::HistoryJsonRpcRequest},
+            portal::{PaginateLocalContentInfo, StreamLocalContentKeysItem},
+            portal_wire::MAINNET,
+        },
+        HistoryContentKey, HistoryNetworkApiClient, OverlayContentKey,
+    };
     use portalnet::discovery::Discovery;
+    use serde_json::{json, Value};
+    use trin_metrics::rpc::{RpcMetricsReporter, UNKNOWN_METHOD};
 
     use super::*;
     use crate::{builder::RpcModuleSelection, PortalRpcModule, RpcModuleBuilder};
@@ -649,6 +766,7 @@ mod tests {
         let (beacon_tx, _) = tokio::sync::mpsc::unbounded_channel();
         let discv5 = Arc::new(Discovery::new(Default::default(), MAINNET.clone()).unwrap());
         RpcModuleBuilder::new(discv5)
+            .with_node_data_dir(std::env::temp_dir())
             .with_history(history_tx)
             .with_beacon(beacon_tx)
     }
@@ -695,6 +813,226 @@ mod tests {
             .unwrap()
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_radius_is_returned_as_32_byte_hex() {
+        let (history_tx, mut history_rx) =
+            tokio::sync::mpsc::unbounded_channel::<HistoryJsonRpcRequest>();
+        let radius = B256::from(U256::from(0xabcdu64));
+        tokio::spawn(async move {
+            while let Some(request) = history_rx.recv().await {
+                assert!(matches!(request.endpoint, HistoryEndpoint::DataRadius));
+                let _ = request.resp.send(Ok(json!(radius)));
+            }
+        });
+        let server =
+            test_rpc_builder()
+                .with_history(history_tx)
+                .build(TransportRpcModuleConfig::set_http(vec![
+                    PortalRpcModule::History,
+                ]));
+        let handle = server
+            .start_server(
+                RpcServerConfig::http(Default::default()).with_http_address(test_address()),
+            )
+            .await
+            .unwrap();
+        let client = handle.http_client().unwrap();
+
+        let response: Value = client
+            .request("portal_historyRadius", rpc_params![])
+            .await
+            .unwrap();
+        assert_eq!(response, json!(format!("0x{}abcd", "0".repeat(60))));
+        assert_eq!(
+            HistoryNetworkApiClient::radius(&client).await.unwrap(),
+            radius
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_local_content_keys_are_streamed_page_after_page() {
+        let (history_tx, mut history_rx) =
+            tokio::sync::mpsc::unbounded_channel::<HistoryJsonRpcRequest>();
+        let mut content_keys = (0..5)
+            .map(|_| HistoryContentKey::new_block_header_by_hash(B256::random()))
+            .collect::<Vec<_>>();
+        content_keys.sort_by_key(|content_key| content_key.content_id());
+        let all_content_keys = content_keys.clone();
+        tokio::spawn(async move {
+            while let Some(request) = history_rx.recv().await {
+                let HistoryEndpoint::PaginateLocalContentKeysAfter(cursor, limit, None, None) =
+                    request.endpoint
+                else {
+                    panic!("Unexpected endpoint: {:?}", request.endpoint);
+                };
+                let page = all_content_keys
+                    .iter()
+                    .filter(|content_key| {
+                        cursor.map_or(true, |cursor| B256::from(content_key.content_id()) > cursor)
+                    })
+                    .take(limit as usize)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let next_cursor = match page.last() {
+                    Some(content_key) if page.len() as u64 == limit => {
+                        Some(B256::from(content_key.content_id()))
+                    }
+                    _ => None,
+                };
+                let _ = request.resp.send(Ok(json!(PaginateLocalContentInfo {
+                    content_keys: page,
+                    total_entries: Some(all_content_keys.len() as u64),
+                    next_cursor,
+                })));
+            }
+        });
+        let server =
+            test_rpc_builder()
+                .with_history(history_tx)
+                .build(TransportRpcModuleConfig::set_ws(vec![
+                    PortalRpcModule::History,
+                ]));
+        let handle = server
+            .start_server(RpcServerConfig::ws(Default::default()).with_ws_address(test_address()))
+            .await
+            .unwrap();
+        let client = handle.ws_client().await.unwrap();
+
+        let mut subscription = HistoryNetworkApiClient::stream_local_content_keys(&client, Some(2))
+            .await
+            .unwrap();
+        let mut streamed_content_keys = vec![];
+        let summary = loop {
+            match subscription.next().await.unwrap().unwrap() {
+                StreamLocalContentKeysItem::ContentKeys { content_keys: page } => {
+                    assert!(page.len() <= 2);
+                    streamed_content_keys.extend(page);
+                }
+                StreamLocalContentKeysItem::Summary(summary) => break summary,
+            }
+        };
+        assert_eq!(streamed_content_keys, content_keys);
+        assert_eq!(summary.count, 5);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_batch_calls_are_handled_concurrently_up_to_the_limit() {
+        const MAX_IN_FLIGHT_CALLS: usize = 2;
+        let (history_tx, mut history_rx) =
+            tokio::sync::mpsc::unbounded_channel::<HistoryJsonRpcRequest>();
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+            tokio::spawn(async move {
+                while let Some(request) = history_rx.recv().await {
+                    let in_flight = in_flight.clone();
+                    let max_in_flight = max_in_flight.clone();
+                    tokio::spawn(async move {
+                        let calls = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_in_flight.fetch_max(calls, Ordering::SeqCst);
+                        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                        let _ = request.resp.send(Ok(json!(B256::ZERO)));
+                    });
+                }
+            });
+        }
+        let server =
+            test_rpc_builder()
+                .with_history(history_tx)
+                .build(TransportRpcModuleConfig::set_http(vec![
+                    PortalRpcModule::History,
+                ]));
+        let handle = server
+            .start_server(
+                RpcServerConfig::http(Default::default())
+                    .with_http_address(test_address())
+                    .with_max_in_flight_calls(MAX_IN_FLIGHT_CALLS),
+            )
+            .await
+            .unwrap();
+        let client = handle.http_client().unwrap();
+
+        let mut batch = BatchRequestBuilder::new();
+        for _ in 0..5 {
+            batch.insert("portal_historyRadius", rpc_params![]).unwrap();
+        }
+        let responses = client.batch_request::<Value>(batch).await.unwrap();
+        assert_eq!(responses.num_successful_calls(), 5);
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), MAX_IN_FLIGHT_CALLS);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_caller_request_id_is_propagated() {
+        let (history_tx, mut history_rx) =
+            tokio::sync::mpsc::unbounded_channel::<HistoryJsonRpcRequest>();
+        let (request_id_tx, mut request_id_rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(request) = history_rx.recv().await {
+                let _ = request_id_tx.send(request.request_id);
+                let _ = request.resp.send(Err("failed".to_string()));
+            }
+        });
+        let server =
+            test_rpc_builder()
+                .with_history(history_tx)
+                .build(TransportRpcModuleConfig::set_http(vec![
+                    PortalRpcModule::History,
+                ]));
+        let handle = server
+            .start_server(
+                RpcServerConfig::http(Default::default()).with_http_address(test_address()),
+            )
+            .await
+            .unwrap();
+        let error_request_id = |result: Result<Value, ClientError>| match result {
+            Err(ClientError::Call(error)) => {
+                let data: Value = serde_json::from_str(error.data().unwrap().get()).unwrap();
+                data["requestId"].as_str().unwrap().to_string()
+            }
+            result => panic!("Unexpected result: {result:?}"),
+        };
+
+        // A caller that sends string ids gets them back as the request ids.
+        let client = HttpClientBuilder::default()
+            .id_format(IdKind::String)
+            .build(handle.http_url().unwrap())
+            .unwrap();
+        let result = client.request("portal_historyRadius", rpc_params![]).await;
+        let request_id = request_id_rx.recv().await.unwrap();
+        assert_eq!(error_request_id(result), request_id);
+        // The client numbers its requests, in strings.
+        assert!(request_id.parse::<u64>().is_ok(), "{request_id}");
+
+        // Numeric ids aren't unique across callers, so a new id is generated instead.
+        let client = handle.http_client().unwrap();
+        let result = client.request("portal_historyRadius", rpc_params![]).await;
+        let request_id = request_id_rx.recv().await.unwrap();
+        assert_eq!(error_request_id(result), request_id);
+        assert!(request_id.parse::<u64>().is_err(), "{request_id}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_unregistered_methods_are_reported_as_unknown() {
+        let handle = launch_http(vec![PortalRpcModule::History]).await;
+        let client = handle.http_client().unwrap();
+        let call_duration = RpcMetricsReporter::default().rpc_metrics.call_duration;
+        let unknown_calls = || {
+            call_duration
+                .with_label_values(&[UNKNOWN_METHOD, "http", "error"])
+                .get_sample_count()
+        };
+
+        let unknown_calls_before = unknown_calls();
+        let result: Result<Value, _> = client
+            .request("portal_historyNoSuchMethod", rpc_params![])
+            .await;
+        assert!(result.is_err());
+        assert_eq!(unknown_calls(), unknown_calls_before + 1);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_http_addr_in_use() {
         let handle = launch_http(vec![PortalRpcModule::History]).await;
@@ -1,12 +1,15 @@
+use alloy::primitives::{Bytes, B256};
 use discv5::enr::NodeId;
 use ethportal_api::{
     types::{
-        enr::Enr,
+        enr::{enr_multiaddrs, Enr, RpcEnr},
         jsonrpc::{endpoints::StateEndpoint, request::StateJsonRpcRequest},
         portal::{
-            AcceptInfo, DataRadius, FindContentInfo, FindNodesInfo, GetContentInfo,
-            PaginateLocalContentInfo, PongInfo, TraceContentInfo, TraceGossipInfo,
-            MAX_CONTENT_KEYS_PER_OFFER,
+            AcceptInfo, ContentProvenance, ContentProviderInfo, ContentRecord, FetchAndStoreInfo,
+            FindContentInfo, FindNodesInfo, GetContentInfo, GetEnrInfo, ImportContentInfo,
+            LocalContentInfo, LookupEnrInfo, OfferManyInfo, PaginateLocalContentInfo, PongInfo,
+            SimulatedOfferInfo, TraceContentInfo, TraceGossipInfo, MAX_CONTENT_KEYS_PER_OFFER,
+            MAX_OFFER_CONTENT_VALUE_SIZE, MAX_SIMULATED_OFFER_SAMPLE_SIZE,
         },
         portal_wire::OfferTrace,
     },
@@ -17,17 +20,30 @@ use tokio::sync::mpsc;
 
 use crate::{
     errors::RpcServeError,
+    export::{export_content, stream_local_content_keys},
     fetch::proxy_to_subnet,
-    jsonrpsee::core::{async_trait, RpcResult},
+    jsonrpsee::{
+        core::{async_trait, RpcResult, SubscriptionResult},
+        PendingSubscriptionSink,
+    },
+    offer::check_offer_content_sizes,
+    paginate::{check_max_results, check_paginate_limit},
 };
 
 pub struct StateNetworkApi {
     network: mpsc::UnboundedSender<StateJsonRpcRequest>,
+    max_paginate_limit: u64,
 }
 
 impl StateNetworkApi {
-    pub fn new(network: mpsc::UnboundedSender<StateJsonRpcRequest>) -> Self {
-        Self { network }
+    pub fn new(
+        network: mpsc::UnboundedSender<StateJsonRpcRequest>,
+        max_paginate_limit: u64,
+    ) -> Self {
+        Self {
+            network,
+            max_paginate_limit,
+        }
     }
 }
 
@@ -40,15 +56,38 @@ impl StateNetworkApiServer for StateNetworkApi {
     }
 
     /// Write an Ethereum Node Record to the overlay routing table.
-    async fn add_enr(&self, enr: Enr) -> RpcResult<bool> {
-        let endpoint = StateEndpoint::AddEnr(enr);
+    async fn add_enr(&self, enr: RpcEnr, allow_unreachable: Option<bool>) -> RpcResult<bool> {
+        let endpoint = StateEndpoint::AddEnr(enr.into(), allow_unreachable.unwrap_or(false));
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
     /// Fetch the latest ENR associated with the given node ID.
-    async fn get_enr(&self, node_id: NodeId) -> RpcResult<Enr> {
+    async fn get_enr(
+        &self,
+        node_id: NodeId,
+        include_multiaddrs: Option<bool>,
+    ) -> RpcResult<LookupEnrInfo> {
         let endpoint = StateEndpoint::GetEnr(node_id);
-        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+        let enr: Enr = proxy_to_subnet(&self.network, endpoint).await?;
+        if include_multiaddrs.unwrap_or(false) {
+            let multiaddrs = enr_multiaddrs(&enr);
+            return Ok(LookupEnrInfo::WithMultiaddrs { enr, multiaddrs });
+        }
+        Ok(LookupEnrInfo::Enr(enr))
+    }
+
+    /// Fetch the latest ENR associated with the given node ID, along with where it was found.
+    async fn get_enr_info(
+        &self,
+        node_id: NodeId,
+        include_multiaddrs: Option<bool>,
+    ) -> RpcResult<GetEnrInfo> {
+        let endpoint = StateEndpoint::GetEnrInfo(node_id);
+        let mut result: GetEnrInfo = proxy_to_subnet(&self.network, endpoint).await?;
+        if include_multiaddrs.unwrap_or(false) {
+            result.multiaddrs = Some(enr_multiaddrs(&result.enr));
+        }
+        Ok(result)
     }
 
     /// Delete Node ID from the overlay routing table.
@@ -58,21 +97,36 @@ impl StateNetworkApiServer for StateNetworkApi {
     }
 
     /// Fetch the ENR representation associated with the given Node ID.
-    async fn lookup_enr(&self, node_id: NodeId) -> RpcResult<Enr> {
+    async fn lookup_enr(
+        &self,
+        node_id: NodeId,
+        include_multiaddrs: Option<bool>,
+    ) -> RpcResult<LookupEnrInfo> {
         let endpoint = StateEndpoint::LookupEnr(node_id);
-        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+        let enr: Enr = proxy_to_subnet(&self.network, endpoint).await?;
+        if include_multiaddrs.unwrap_or(false) {
+            let multiaddrs = enr_multiaddrs(&enr);
+            return Ok(LookupEnrInfo::WithMultiaddrs { enr, multiaddrs });
+        }
+        Ok(LookupEnrInfo::Enr(enr))
     }
 
     /// Send a PING message to the designated node and wait for a PONG response
-    async fn ping(&self, enr: Enr) -> RpcResult<PongInfo> {
-        let endpoint = StateEndpoint::Ping(enr);
+    async fn ping(&self, enr: RpcEnr) -> RpcResult<PongInfo> {
+        let endpoint = StateEndpoint::Ping(enr.into());
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
     /// Send a FINDNODES request for nodes that fall within the given set of distances, to the
     /// designated peer and wait for a response
-    async fn find_nodes(&self, enr: Enr, distances: Vec<u16>) -> RpcResult<FindNodesInfo> {
-        let endpoint = StateEndpoint::FindNodes(enr, distances);
+    async fn find_nodes(
+        &self,
+        enr: RpcEnr,
+        distances: Vec<u16>,
+        max_results: Option<u64>,
+    ) -> RpcResult<FindNodesInfo> {
+        check_max_results(max_results)?;
+        let endpoint = StateEndpoint::FindNodes(enr.into(), distances, max_results);
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
@@ -83,7 +137,7 @@ impl StateNetworkApiServer for StateNetworkApi {
     }
 
     /// Lookup a target node within in the network
-    async fn radius(&self) -> RpcResult<DataRadius> {
+    async fn radius(&self) -> RpcResult<B256> {
         let endpoint = StateEndpoint::DataRadius;
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
@@ -91,10 +145,23 @@ impl StateNetworkApiServer for StateNetworkApi {
     /// Send FINDCONTENT message to get the content with a content key.
     async fn find_content(
         &self,
-        enr: Enr,
+        enr: RpcEnr,
         content_key: StateContentKey,
     ) -> RpcResult<FindContentInfo> {
-        let endpoint = StateEndpoint::FindContent(enr, content_key);
+        let endpoint = StateEndpoint::FindContent(enr.into(), content_key);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Send FINDCONTENT message to get the content with a content key, and store the content
+    /// after validating it. Content outside our radius is stored only if `ignore_radius` is set.
+    async fn fetch_and_store(
+        &self,
+        enr: RpcEnr,
+        content_key: StateContentKey,
+        ignore_radius: Option<bool>,
+    ) -> RpcResult<FetchAndStoreInfo> {
+        let endpoint =
+            StateEndpoint::FetchAndStore(enr.into(), content_key, ignore_radius.unwrap_or(false));
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
@@ -117,8 +184,74 @@ impl StateNetworkApiServer for StateNetworkApi {
         &self,
         offset: u64,
         limit: u64,
+        provenance: Option<ContentProvenance>,
+        source: Option<String>,
     ) -> RpcResult<PaginateLocalContentInfo<StateContentKey>> {
-        let endpoint = StateEndpoint::PaginateLocalContentKeys(offset, limit);
+        check_paginate_limit(limit, self.max_paginate_limit)?;
+        let endpoint = StateEndpoint::PaginateLocalContentKeys(offset, limit, provenance, source);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Cursor-based pagination of local content keys
+    async fn paginate_local_content_keys_after(
+        &self,
+        cursor: Option<B256>,
+        limit: u64,
+        provenance: Option<ContentProvenance>,
+        source: Option<String>,
+    ) -> RpcResult<PaginateLocalContentInfo<StateContentKey>> {
+        check_paginate_limit(limit, self.max_paginate_limit)?;
+        let endpoint =
+            StateEndpoint::PaginateLocalContentKeysAfter(cursor, limit, provenance, source);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Stream all locally stored content, ordered by content id, followed by the summary of the
+    /// export.
+    async fn export_content(
+        &self,
+        pending: PendingSubscriptionSink,
+        batch_size: Option<u64>,
+    ) -> SubscriptionResult {
+        export_content::<_, StateContentKey>(
+            &self.network,
+            pending,
+            batch_size,
+            StateEndpoint::ExportContentBatch,
+        )
+        .await
+    }
+
+    /// Stream all locally stored content keys in batches, followed by the summary once all keys
+    /// were streamed.
+    async fn stream_local_content_keys(
+        &self,
+        pending: PendingSubscriptionSink,
+        batch_size: Option<u64>,
+    ) -> SubscriptionResult {
+        stream_local_content_keys::<_, StateContentKey>(
+            &self.network,
+            pending,
+            batch_size,
+            |cursor, limit| StateEndpoint::PaginateLocalContentKeysAfter(cursor, limit, None, None),
+        )
+        .await
+    }
+
+    /// Store the content records (e.g. exported from another node) to the local database.
+    async fn import_content(
+        &self,
+        content_records: Vec<ContentRecord<StateContentKey>>,
+    ) -> RpcResult<ImportContentInfo<StateContentKey>> {
+        let content_items = content_records
+            .into_iter()
+            .map(|record| {
+                StateContentValue::decode(&record.content_key, &record.content_value)
+                    .map(|value| (record.content_key, value))
+                    .map_err(RpcServeError::from)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let endpoint = StateEndpoint::ImportContent(content_items);
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
@@ -154,9 +287,42 @@ impl StateNetworkApiServer for StateNetworkApi {
     /// receive.
     async fn offer(
         &self,
-        enr: Enr,
+        enr: RpcEnr,
         content_items: Vec<(StateContentKey, RawContentValue)>,
+        include_accept_bitfield: Option<bool>,
     ) -> RpcResult<AcceptInfo> {
+        if !(1..=MAX_CONTENT_KEYS_PER_OFFER).contains(&content_items.len()) {
+            return Err(RpcServeError::Message(format!(
+                "Invalid amount of content items: {}",
+                content_items.len()
+            ))
+            .into());
+        }
+        check_offer_content_sizes(
+            content_items.iter().map(|(key, value)| (key, value.len())),
+            |_| MAX_OFFER_CONTENT_VALUE_SIZE,
+        )?;
+        let content_items = content_items
+            .into_iter()
+            .map(|(key, value)| StateContentValue::decode(&key, &value).map(|value| (key, value)))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(RpcServeError::from)?;
+        let endpoint = StateEndpoint::Offer(enr.into(), content_items);
+        let mut accept_info: AcceptInfo = proxy_to_subnet(&self.network, endpoint).await?;
+        if !include_accept_bitfield.unwrap_or(false) {
+            accept_info.accept_bitfield = None;
+        }
+        Ok(accept_info)
+    }
+
+    /// Send an OFFER request with given ContentItems, to the designated peer and wait for a
+    /// response. Does not store content locally.
+    /// Returns which of the offered content keys the peer accepted and which it declined.
+    async fn offer_many(
+        &self,
+        enr: RpcEnr,
+        content_items: Vec<(StateContentKey, RawContentValue)>,
+    ) -> RpcResult<OfferManyInfo<StateContentKey>> {
         if !(1..=MAX_CONTENT_KEYS_PER_OFFER).contains(&content_items.len()) {
             return Err(RpcServeError::Message(format!(
                 "Invalid amount of content items: {}",
@@ -169,7 +335,7 @@ impl StateNetworkApiServer for StateNetworkApi {
             .map(|(key, value)| StateContentValue::decode(&key, &value).map(|value| (key, value)))
             .collect::<Result<Vec<_>, _>>()
             .map_err(RpcServeError::from)?;
-        let endpoint = StateEndpoint::Offer(enr, content_items);
+        let endpoint = StateEndpoint::OfferMany(enr.into(), content_items);
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
@@ -178,13 +344,13 @@ impl StateNetworkApiServer for StateNetworkApi {
     /// Returns trace info from the offer.
     async fn trace_offer(
         &self,
-        enr: Enr,
+        enr: RpcEnr,
         content_key: StateContentKey,
         content_value: RawContentValue,
     ) -> RpcResult<OfferTrace> {
         let content_value =
             StateContentValue::decode(&content_key, &content_value).map_err(RpcServeError::from)?;
-        let endpoint = StateEndpoint::TraceOffer(enr, content_key, content_value);
+        let endpoint = StateEndpoint::TraceOffer(enr.into(), content_key, content_value);
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
@@ -193,16 +359,60 @@ impl StateNetworkApiServer for StateNetworkApi {
         &self,
         content_key: StateContentKey,
         content_value: RawContentValue,
+        source: Option<String>,
+        signature: Option<Bytes>,
     ) -> RpcResult<bool> {
         let content_value =
             StateContentValue::decode(&content_key, &content_value).map_err(RpcServeError::from)?;
-        let endpoint = StateEndpoint::Store(content_key, content_value);
+        let endpoint = StateEndpoint::Store(content_key, content_value, source, signature);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Delete all the content stored with the source from the local database.
+    async fn delete_content_by_source(&self, source: String) -> RpcResult<u64> {
+        let endpoint = StateEndpoint::DeleteContentBySource(source);
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 
     /// Get a content from the local database.
-    async fn local_content(&self, content_key: StateContentKey) -> RpcResult<RawContentValue> {
-        let endpoint = StateEndpoint::LocalContent(content_key);
+    async fn local_content(
+        &self,
+        content_key: StateContentKey,
+        include_meta: Option<bool>,
+        include_rank: Option<bool>,
+    ) -> RpcResult<LocalContentInfo> {
+        let endpoint = StateEndpoint::LocalContent(
+            content_key,
+            include_meta.unwrap_or(false),
+            include_rank.unwrap_or(false),
+        );
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Return the peers that provided the locally stored content.
+    async fn content_providers(
+        &self,
+        content_key: StateContentKey,
+    ) -> RpcResult<Vec<ContentProviderInfo>> {
+        let endpoint = StateEndpoint::ContentProviders(content_key);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Return how many synthetic content ids would be accepted if they were offered.
+    async fn simulate_incoming_offer(&self, sample_size: u64) -> RpcResult<SimulatedOfferInfo> {
+        if !(1..=MAX_SIMULATED_OFFER_SAMPLE_SIZE).contains(&sample_size) {
+            return Err(RpcServeError::Message(format!(
+                "Invalid sample size: {sample_size}, expected between 1 and {MAX_SIMULATED_OFFER_SAMPLE_SIZE}"
+            ))
+            .into());
+        }
+        let endpoint = StateEndpoint::SimulateIncomingOffer(sample_size);
+        Ok(proxy_to_subnet(&self.network, endpoint).await?)
+    }
+
+    /// Forget the content recently found by lookups.
+    async fn clear_lookup_cache(&self) -> RpcResult<u64> {
+        let endpoint = StateEndpoint::ClearLookupCache;
         Ok(proxy_to_subnet(&self.network, endpoint).await?)
     }
 }
@@ -1,26 +1,21 @@
-use ethportal_api::{version::get_trin_version, Web3ApiServer};
+use ethportal_api::{types::network::Network, version::get_client_version, Web3ApiServer};
 
 use crate::jsonrpsee::core::{async_trait, RpcResult};
 
-pub struct Web3Api;
-
-impl Web3Api {
-    pub fn new() -> Self {
-        Self
-    }
+pub struct Web3Api {
+    network: Network,
 }
 
-impl Default for Web3Api {
-    fn default() -> Self {
-        Self::new()
+impl Web3Api {
+    pub fn new(network: Network) -> Self {
+        Self { network }
     }
 }
 
 #[async_trait]
 impl Web3ApiServer for Web3Api {
     async fn client_version(&self) -> RpcResult<String> {
-        let trin_version = get_trin_version();
-        Ok(format!("trin v{trin_version}"))
+        Ok(get_client_version(self.network))
     }
 }
 
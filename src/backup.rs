@@ -0,0 +1,259 @@
+//! Backs up the state of a node into a single archive, and restores it, e.g. to migrate the node
+//! to another host.
+//!
+//! The archive is a zstd compressed tar archive of:
+//! - the [BackupManifest], as `manifest.json`
+//! - the private key of the node
+//! - the database with the content of all the subnetworks
+//! - the bootnodes added at runtime, if any
+//! - the routing tables of the subnetworks, as last persisted by the node, if any
+//!
+//! The database is copied with SQLite's online backup API, and the routing tables are persisted
+//! by replacing their file at once, so the node doesn't need to be stopped while it is backed up.
+//! The restored node uses the peers of the routing tables as bootnodes.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use alloy::primitives::B256;
+use anyhow::{anyhow, bail, ensure};
+use discv5::enr::{CombinedKey, Enr, NodeId};
+use ethportal_api::{
+    types::cli::TrinConfig,
+    utils::bytes::{hex_decode, hex_encode},
+    version::get_trin_version,
+};
+use portalnet::utils::db::{
+    configure_node_data_dir, configure_trin_data_dir, BOOTNODES_FILE_NAME,
+    ROUTING_TABLES_FILE_NAME, UNSAFE_PRIVATE_KEY_FILE_NAME,
+};
+use serde::{Deserialize, Serialize};
+use tempfile::TempDir;
+use trin_storage::{
+    utils::{backup_database, check_database},
+    PortalStorageConfigFactory, DATABASE_NAME,
+};
+
+/// The version of the archive format. Archives of other versions are only restored when forced.
+pub const BACKUP_SCHEMA_VERSION: u32 = 1;
+
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// The compression level of the archive.
+const ZSTD_COMPRESSION_LEVEL: i32 = 3;
+
+/// Describes the node state in a backup archive.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    /// The version of the archive format, see [BACKUP_SCHEMA_VERSION]
+    pub schema_version: u32,
+    /// The trin version that made the backup
+    pub trin_version: String,
+    pub network: String,
+    pub node_id: B256,
+    /// When the backup was made, in seconds since the Unix epoch
+    pub created_at: u64,
+    /// The versions of the content stores in the database, by content type
+    pub store_versions: BTreeMap<String, String>,
+    /// The storage capacity of the subnetworks, in bytes, as configured for the backup
+    pub storage_capacity_bytes: BTreeMap<String, u64>,
+    /// Whether the archive has the bootnodes added at runtime
+    pub has_bootnodes: bool,
+    /// Whether the archive has the persisted routing tables
+    #[serde(default)]
+    pub has_routing_tables: bool,
+}
+
+/// The outcome of restoring a backup.
+#[derive(Debug)]
+pub struct RestoredNode {
+    pub manifest: BackupManifest,
+    pub node_data_dir: PathBuf,
+}
+
+/// Backs up the node of the configured data dir, network and private key into the archive.
+pub fn backup(trin_config: &TrinConfig, path: &Path) -> anyhow::Result<BackupManifest> {
+    let trin_data_dir = configure_trin_data_dir(trin_config.data_dir.clone(), false)?;
+    let (node_data_dir, private_key) = configure_node_data_dir(
+        &trin_data_dir,
+        trin_config.private_key,
+        trin_config.network.network(),
+    )?;
+    let node_id = node_id_from_private_key(private_key)?;
+
+    let staging_dir = TempDir::new()?;
+    let database_path = staging_dir.path().join(DATABASE_NAME);
+    backup_database(&node_data_dir, &database_path)?;
+    let store_versions = check_database(&database_path)?;
+    fs::write(
+        staging_dir.path().join(UNSAFE_PRIVATE_KEY_FILE_NAME),
+        hex_encode(private_key),
+    )?;
+    let has_bootnodes = copy_if_exists(&node_data_dir, staging_dir.path(), BOOTNODES_FILE_NAME)?;
+    let has_routing_tables =
+        copy_if_exists(&node_data_dir, staging_dir.path(), ROUTING_TABLES_FILE_NAME)?;
+
+    // The factory is created on the copy of the database, so that the node's database isn't
+    // touched.
+    let storage_config_factory = PortalStorageConfigFactory::new(
+        trin_config.storage_capacity_config(),
+        NodeId::new(&node_id.0),
+        staging_dir.path().to_path_buf(),
+    )?;
+    let storage_capacity_bytes = trin_config
+        .portal_subnetworks
+        .iter()
+        .map(|subnetwork| {
+            let config = storage_config_factory.create(subnetwork)?;
+            Ok((subnetwork.to_string(), config.storage_capacity_bytes))
+        })
+        .collect::<anyhow::Result<_>>()?;
+    drop(storage_config_factory);
+
+    let manifest = BackupManifest {
+        schema_version: BACKUP_SCHEMA_VERSION,
+        trin_version: get_trin_version().to_string(),
+        network: trin_config.network.network().to_string(),
+        node_id,
+        created_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        store_versions,
+        storage_capacity_bytes,
+        has_bootnodes,
+        has_routing_tables,
+    };
+    fs::write(
+        staging_dir.path().join(MANIFEST_FILE_NAME),
+        serde_json::to_vec_pretty(&manifest)?,
+    )?;
+
+    let encoder = zstd::Encoder::new(File::create(path)?, ZSTD_COMPRESSION_LEVEL)?;
+    let mut archive = tar::Builder::new(encoder);
+    for file_name in [
+        MANIFEST_FILE_NAME,
+        UNSAFE_PRIVATE_KEY_FILE_NAME,
+        DATABASE_NAME,
+    ] {
+        archive.append_path_with_name(staging_dir.path().join(file_name), file_name)?;
+    }
+    for (file_name, included) in [
+        (BOOTNODES_FILE_NAME, has_bootnodes),
+        (ROUTING_TABLES_FILE_NAME, has_routing_tables),
+    ] {
+        if included {
+            archive.append_path_with_name(staging_dir.path().join(file_name), file_name)?;
+        }
+    }
+    archive.into_inner()?.finish()?;
+    Ok(manifest)
+}
+
+/// Restores the node of the archive into the configured data dir, after validating the archive.
+///
+/// Unless forced, archives of other schema versions aren't restored, and neither is an archive
+/// that would replace the node key or the content database in the data dir.
+pub fn restore(trin_config: &TrinConfig, path: &Path, force: bool) -> anyhow::Result<RestoredNode> {
+    let staging_dir = TempDir::new()?;
+    let decoder = zstd::Decoder::new(BufReader::new(File::open(path)?))?;
+    tar::Archive::new(decoder).unpack(staging_dir.path())?;
+
+    let manifest: BackupManifest = serde_json::from_slice(
+        &fs::read(staging_dir.path().join(MANIFEST_FILE_NAME))
+            .map_err(|err| anyhow!("Backup archive has no manifest: {err}"))?,
+    )?;
+    ensure!(
+        force || manifest.schema_version == BACKUP_SCHEMA_VERSION,
+        "Backup schema version {} is incompatible with version {BACKUP_SCHEMA_VERSION}, use \
+         --force to restore it anyway",
+        manifest.schema_version
+    );
+    let network = trin_config.network.network();
+    ensure!(
+        manifest.network == network.to_string(),
+        "Backup is of a {} node, but the {network} network is configured",
+        manifest.network
+    );
+
+    let private_key = fs::read_to_string(staging_dir.path().join(UNSAFE_PRIVATE_KEY_FILE_NAME))?;
+    let private_key = B256::try_from(hex_decode(private_key.trim())?.as_slice())?;
+    ensure!(
+        node_id_from_private_key(private_key)? == manifest.node_id,
+        "Backup private key doesn't match node id {}",
+        manifest.node_id
+    );
+    let database_path = staging_dir.path().join(DATABASE_NAME);
+    let store_versions = check_database(&database_path)?;
+    ensure!(
+        store_versions == manifest.store_versions,
+        "Backup database store versions {store_versions:?} don't match the manifest: {:?}",
+        manifest.store_versions
+    );
+
+    let trin_data_dir = configure_trin_data_dir(trin_config.data_dir.clone(), false)?;
+    let private_key_path = trin_data_dir.join(UNSAFE_PRIVATE_KEY_FILE_NAME);
+    if private_key_path.exists() && !force {
+        let existing_key = hex_decode(fs::read_to_string(&private_key_path)?.trim())?;
+        if existing_key != private_key.as_slice() {
+            bail!(
+                "Data dir {} has the key of another node, use --force to replace it",
+                trin_data_dir.display()
+            );
+        }
+    }
+    let (node_data_dir, _) = configure_node_data_dir(&trin_data_dir, Some(private_key), network)?;
+    let node_database_path = node_data_dir.join(DATABASE_NAME);
+    if node_database_path.exists() {
+        if !force {
+            bail!(
+                "Node data dir {} already has a content database, use --force to replace it",
+                node_data_dir.display()
+            );
+        }
+        // The journal files of the replaced database must not be applied to the restored one.
+        for suffix in ["-wal", "-shm", "-journal"] {
+            let journal_path = node_data_dir.join(format!("{DATABASE_NAME}{suffix}"));
+            if journal_path.exists() {
+                fs::remove_file(journal_path)?;
+            }
+        }
+    }
+
+    fs::write(private_key_path, hex_encode(private_key))?;
+    fs::copy(database_path, node_database_path)?;
+    for (file_name, included) in [
+        (BOOTNODES_FILE_NAME, manifest.has_bootnodes),
+        (ROUTING_TABLES_FILE_NAME, manifest.has_routing_tables),
+    ] {
+        let path = node_data_dir.join(file_name);
+        if included {
+            fs::copy(staging_dir.path().join(file_name), path)?;
+        } else if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(RestoredNode {
+        manifest,
+        node_data_dir,
+    })
+}
+
+/// Copies the file of the directory into the other directory, if it exists. Returns whether it was
+/// copied.
+fn copy_if_exists(from_dir: &Path, to_dir: &Path, file_name: &str) -> anyhow::Result<bool> {
+    let path = from_dir.join(file_name);
+    if !path.exists() {
+        return Ok(false);
+    }
+    fs::copy(path, to_dir.join(file_name))?;
+    Ok(true)
+}
+
+fn node_id_from_private_key(private_key: B256) -> anyhow::Result<B256> {
+    let key = CombinedKey::secp256k1_from_bytes(private_key.0.clone().as_mut_slice())
+        .map_err(|err| anyhow!("Invalid private key: {err:?}"))?;
+    Ok(B256::from(Enr::empty(&key)?.node_id().raw()))
+}
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use ethportal_api::{
+    test_vectors::ContentTestVector, types::network::Subnetwork, BeaconContentValue, ContentValue,
+    HistoryContentValue, OverlayContentKey, RawContentKey, RawContentValue, StateContentValue,
+};
+
+/// Generates a content test vector, in the format of the portal-spec-tests fixtures, by decoding
+/// the given content key and value with our types and encoding them again.
+pub fn main() -> Result<()> {
+    let config = GenerateConfig::parse();
+
+    let test_vector = match config.subnetwork {
+        Subnetwork::Beacon => generate::<BeaconContentValue>(&config)?,
+        Subnetwork::History => generate::<HistoryContentValue>(&config)?,
+        Subnetwork::State => generate::<StateContentValue>(&config)?,
+        subnetwork => return Err(anyhow!("Unsupported subnetwork: {subnetwork}")),
+    };
+
+    let yaml = test_vector.to_yaml()?;
+    match config.output {
+        Some(path) => std::fs::write(path, yaml)?,
+        None => print!("{yaml}"),
+    }
+    Ok(())
+}
+
+fn generate<V: ContentValue>(config: &GenerateConfig) -> Result<ContentTestVector> {
+    let content_key = V::TContentKey::try_from_bytes(&config.content_key)?;
+    let content_value = config
+        .content_value
+        .as_ref()
+        .map(|content_value| V::decode(&content_key, content_value))
+        .transpose()?;
+    let test_vector = ContentTestVector::new(&content_key, content_value.as_ref());
+    test_vector.check_round_trip::<V>()?;
+    Ok(test_vector)
+}
+
+// CLI Parameter Handling
+#[derive(Parser, Debug, PartialEq)]
+#[command(
+    name = "Trin Test Vector Generator",
+    about = "Generate portal-spec-tests style test vectors for content keys and values"
+)]
+pub struct GenerateConfig {
+    #[arg(
+        long,
+        help = "Subnetwork of the content: beacon, history or state",
+        value_parser = Subnetwork::from_cli_arg
+    )]
+    pub subnetwork: Subnetwork,
+
+    #[arg(long, help = "Hex encoded content key (with 0x prefix)")]
+    pub content_key: RawContentKey,
+
+    #[arg(long, help = "Hex encoded content value (with 0x prefix)")]
+    pub content_value: Option<RawContentValue>,
+
+    #[arg(long, help = "File to write the test vector to, instead of stdout")]
+    pub output: Option<PathBuf>,
+}
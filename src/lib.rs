@@ -1,12 +1,19 @@
 #![warn(clippy::unwrap_used)]
 #![warn(clippy::uninlined_format_args)]
 
-use std::sync::Arc;
+pub mod backup;
+pub mod replay;
+
+use std::{collections::BTreeMap, net::Ipv4Addr, path::PathBuf, sync::Arc, time::Duration};
 
 #[cfg(windows)]
 use ethportal_api::types::cli::Web3TransportType;
 use ethportal_api::{
-    types::{cli::TrinConfig, network::Subnetwork},
+    types::{
+        cli::TrinConfig,
+        jsonrpc::request::{BeaconJsonRpcRequest, HistoryJsonRpcRequest, StateJsonRpcRequest},
+        network::Subnetwork,
+    },
     utils::bytes::hex_encode,
     version::get_trin_version,
 };
@@ -14,22 +21,58 @@ use portalnet::{
     config::PortalnetConfig,
     discovery::{Discovery, Discv5UdpSocket},
     events::PortalnetEvents,
-    utils::db::{configure_node_data_dir, configure_trin_data_dir},
+    utils::db::{
+        configure_node_data_dir, configure_trin_data_dir, persist_routing_tables,
+        read_persisted_bootnodes, read_persisted_routing_tables,
+    },
+    wire_recorder,
 };
 use rpc::{launch_jsonrpc_server, RpcServerHandle};
-use tokio::sync::{mpsc, RwLock};
-use tracing::info;
+use tokio::{
+    net::UdpSocket,
+    sync::{mpsc, RwLock},
+    task::JoinHandle,
+};
+use tracing::{info, warn};
 use tree_hash::TreeHash;
-use trin_beacon::initialize_beacon_network;
-use trin_history::initialize_history_network;
-use trin_state::initialize_state_network;
-use trin_storage::PortalStorageConfigFactory;
+use trin_beacon::{initialize_beacon_network, network::BeaconNetwork};
+use trin_history::{initialize_history_network, network::HistoryNetwork};
+use trin_state::{initialize_state_network, network::StateNetwork};
+use trin_storage::{PortalStorageConfig, PortalStorageConfigFactory};
 use trin_validation::oracle::HeaderOracle;
 use utp_rs::socket::UtpSocket;
 
+/// How often the routing tables of the subnetworks are persisted to the node data dir.
+const ROUTING_TABLES_PERSIST_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// A running trin node, as started by [start_trin].
+pub struct TrinNode {
+    pub discovery: Arc<Discovery>,
+    pub rpc_handle: RpcServerHandle,
+    pub history: Option<TrinSubnetwork<HistoryNetwork, HistoryJsonRpcRequest>>,
+    pub state: Option<TrinSubnetwork<StateNetwork, StateJsonRpcRequest>>,
+    pub beacon: Option<TrinSubnetwork<BeaconNetwork, BeaconJsonRpcRequest>>,
+    /// The tasks of the node. They keep running if their handles are dropped, until they're
+    /// aborted. The overlay service of each subnetwork isn't among them, as it's owned by the
+    /// overlay protocol of the subnetwork and aborted once that is dropped.
+    pub tasks: Vec<JoinHandle<()>>,
+}
+
+/// An enabled subnetwork of a [TrinNode].
+pub struct TrinSubnetwork<TNetwork, TRequest> {
+    pub network: Arc<TNetwork>,
+    /// The channel of the jsonrpc handler of the subnetwork.
+    pub jsonrpc_tx: mpsc::UnboundedSender<TRequest>,
+}
+
 pub async fn run_trin(
     trin_config: TrinConfig,
 ) -> Result<RpcServerHandle, Box<dyn std::error::Error>> {
+    Ok(start_trin(trin_config).await?.rpc_handle)
+}
+
+/// Starts a trin node, returning its components.
+pub async fn start_trin(trin_config: TrinConfig) -> Result<TrinNode, Box<dyn std::error::Error>> {
     // Panic early on a windows build that is trying to use IPC, which is unsupported for now
     // Make sure not to panic on non-windows configurations.
     #[cfg(windows)]
@@ -41,6 +84,11 @@ pub async fn run_trin(
     info!("Launching Trin: v{trin_version}");
     info!(config = %trin_config, "With:");
 
+    if let Some(path) = &trin_config.record_wire {
+        wire_recorder::start(path, trin_config.record_wire_max_mb * 1_000_000)?;
+        info!(path = %path.display(), "Recording wire messages");
+    }
+
     // Setup temp trin data directory if we're in ephemeral mode
     let trin_data_dir =
         configure_trin_data_dir(trin_config.data_dir.clone(), trin_config.ephemeral)?;
@@ -52,12 +100,44 @@ pub async fn run_trin(
         trin_config.network.network(),
     )?;
 
-    let portalnet_config = PortalnetConfig::new(&trin_config, private_key);
+    let mut portalnet_config = PortalnetConfig::new(&trin_config, private_key);
+
+    // Include the bootnodes that were added at runtime, in previous runs
+    for bootnode in read_persisted_bootnodes(&node_data_dir)? {
+        if !portalnet_config
+            .bootnodes
+            .iter()
+            .any(|enr| enr.node_id() == bootnode.node_id())
+        {
+            portalnet_config.bootnodes.push(bootnode);
+        }
+    }
+
+    // The peers of the routing tables of previous runs are the subnetworks' extra bootnodes
+    let persisted_routing_tables = read_persisted_routing_tables(&node_data_dir)?;
+    let subnetwork_portalnet_config = |subnetwork: Subnetwork| {
+        let mut config = portalnet_config.clone();
+        for enr in persisted_routing_tables
+            .get(&subnetwork.to_string())
+            .into_iter()
+            .flatten()
+        {
+            if !config
+                .bootnodes
+                .iter()
+                .any(|bootnode| bootnode.node_id() == enr.node_id())
+            {
+                config.bootnodes.push(enr.clone());
+            }
+        }
+        config
+    };
 
     // Initialize base discovery protocol
     let mut discovery = Discovery::new(portalnet_config.clone(), trin_config.network.clone())?;
     let talk_req_rx = discovery.start().await?;
     let discovery = Arc::new(discovery);
+    discovery.spawn_address_monitor();
 
     // Initialize prometheus metrics
     if let Some(addr) = trin_config.enable_metrics_with_url {
@@ -87,34 +167,56 @@ pub async fn run_trin(
         header_oracle.clone(),
         enr_cache_capacity,
     );
+    // Exchange uTP packets on their own port, instead of wrapping them in discv5 TALKREQ messages,
+    // if the port is configured.
+    let discv5_utp_socket = match portalnet_config.utp_port {
+        Some(utp_port) => {
+            let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, utp_port)).await?;
+            info!(utp_port, "Listening for uTP packets on their own port");
+            discv5_utp_socket.with_utp_socket(socket)
+        }
+        None => discv5_utp_socket,
+    };
     let utp_socket = UtpSocket::with_socket(discv5_utp_socket);
     let utp_socket = Arc::new(utp_socket);
 
-    let storage_config_factory = PortalStorageConfigFactory::new(
+    let mut storage_config_factory = PortalStorageConfigFactory::new(
         trin_config.storage_capacity_config(),
         discovery.local_enr().node_id(),
-        node_data_dir,
-    )?;
+        node_data_dir.clone(),
+    )?
+    .with_beacon_pinned_capacity(trin_config.storage_beacon_pinned)
+    .with_recent_writes_capacity(trin_config.storage_recent_writes as usize);
+    if let (Some(archive_path), Some(archive_mb)) = (
+        &trin_config.storage_archive_path,
+        trin_config.storage_archive_mb,
+    ) {
+        storage_config_factory = storage_config_factory.with_archive(archive_path, archive_mb)?;
+    }
 
     // Initialize state sub-network service and event handlers, if selected
-    let (state_handler, state_network_task, state_event_tx, state_jsonrpc_tx, state_event_stream) =
+    let (state_handler, state_network_tasks, state_event_tx, state_jsonrpc_tx, state_event_stream) =
         if trin_config.portal_subnetworks.contains(&Subnetwork::State) {
             initialize_state_network(
                 &discovery,
                 utp_socket.clone(),
-                portalnet_config.clone(),
-                storage_config_factory.create(&Subnetwork::State)?,
+                subnetwork_portalnet_config(Subnetwork::State),
+                PortalStorageConfig {
+                    verify_on_read: trin_config.storage_verify_on_read,
+                    eviction_policy: trin_config.storage_eviction_policy,
+                    ..storage_config_factory.create(&Subnetwork::State)?
+                },
                 header_oracle.clone(),
             )
             .await?
         } else {
-            (None, None, None, None, None)
+            (None, vec![], None, None, None)
         };
 
     // Initialize trin-beacon sub-network service and event handlers, if selected
     let (
         beacon_handler,
-        beacon_network_task,
+        beacon_network_tasks,
         beacon_event_tx,
         beacon_jsonrpc_tx,
         beacon_event_stream,
@@ -122,19 +224,23 @@ pub async fn run_trin(
         initialize_beacon_network(
             &discovery,
             utp_socket.clone(),
-            portalnet_config.clone(),
-            storage_config_factory.create(&Subnetwork::Beacon)?,
+            subnetwork_portalnet_config(Subnetwork::Beacon),
+            PortalStorageConfig {
+                content_dedup: trin_config.storage_dedup,
+                verify_on_read: trin_config.storage_verify_on_read,
+                ..storage_config_factory.create(&Subnetwork::Beacon)?
+            },
             header_oracle.clone(),
         )
         .await?
     } else {
-        (None, None, None, None, None)
+        (None, vec![], None, None, None)
     };
 
     // Initialize chain history sub-network service and event handlers, if selected
     let (
         history_handler,
-        history_network_task,
+        history_network_tasks,
         history_event_tx,
         history_jsonrpc_tx,
         history_event_stream,
@@ -145,39 +251,74 @@ pub async fn run_trin(
         initialize_history_network(
             &discovery,
             utp_socket.clone(),
-            portalnet_config.clone(),
-            storage_config_factory.create(&Subnetwork::History)?,
+            subnetwork_portalnet_config(Subnetwork::History),
+            PortalStorageConfig {
+                verify_on_read: trin_config.storage_verify_on_read,
+                eviction_policy: trin_config.storage_eviction_policy,
+                ..storage_config_factory.create(&Subnetwork::History)?
+            },
             header_oracle.clone(),
         )
         .await?
     } else {
-        (None, None, None, None, None)
+        (None, vec![], None, None, None)
     };
 
+    let history = history_handler
+        .as_ref()
+        .zip(history_jsonrpc_tx.clone())
+        .map(|(handler, jsonrpc_tx)| TrinSubnetwork {
+            network: handler.network.clone(),
+            jsonrpc_tx,
+        });
+    let state =
+        state_handler
+            .as_ref()
+            .zip(state_jsonrpc_tx.clone())
+            .map(|(handler, jsonrpc_tx)| TrinSubnetwork {
+                network: handler.network.clone(),
+                jsonrpc_tx,
+            });
+    let beacon =
+        beacon_handler
+            .as_ref()
+            .zip(beacon_jsonrpc_tx.clone())
+            .map(|(handler, jsonrpc_tx)| TrinSubnetwork {
+                network: handler.network.clone(),
+                jsonrpc_tx,
+            });
+
     // Launch JSON-RPC server
     let jsonrpc_trin_config = trin_config.clone();
     let jsonrpc_discovery = Arc::clone(&discovery);
     let rpc_handle: RpcServerHandle = launch_jsonrpc_server(
         jsonrpc_trin_config,
         jsonrpc_discovery,
+        node_data_dir.clone(),
         history_jsonrpc_tx,
         state_jsonrpc_tx,
         beacon_jsonrpc_tx,
     )
     .await?;
 
+    let mut tasks = vec![spawn_routing_tables_persister(
+        node_data_dir,
+        history.as_ref().map(|history| history.network.clone()),
+        state.as_ref().map(|state| state.network.clone()),
+        beacon.as_ref().map(|beacon| beacon.network.clone()),
+    )];
     if let Some(handler) = state_handler {
-        tokio::spawn(async move { handler.handle_client_queries().await });
+        tasks.push(tokio::spawn(handler.handle_client_queries()));
     }
     if let Some(handler) = history_handler {
-        tokio::spawn(async move { handler.handle_client_queries().await });
+        tasks.push(tokio::spawn(handler.handle_client_queries()));
     }
     if let Some(handler) = beacon_handler {
-        tokio::spawn(async move { handler.handle_client_queries().await });
+        tasks.push(tokio::spawn(handler.handle_client_queries()));
     }
 
     // Spawn main portal events handler
-    tokio::spawn(async move {
+    tasks.push(tokio::spawn(async move {
         let events = PortalnetEvents::new(
             talk_req_rx,
             (history_event_tx, history_event_stream),
@@ -188,17 +329,58 @@ pub async fn run_trin(
         )
         .await;
         events.start().await;
-    });
+    }));
 
-    if let Some(network) = history_network_task {
-        tokio::spawn(network);
-    }
-    if let Some(network) = state_network_task {
-        tokio::spawn(network);
-    }
-    if let Some(network) = beacon_network_task {
-        tokio::spawn(network);
-    }
+    tasks.extend(history_network_tasks);
+    tasks.extend(state_network_tasks);
+    tasks.extend(beacon_network_tasks);
 
-    Ok(rpc_handle)
+    Ok(TrinNode {
+        discovery,
+        rpc_handle,
+        history,
+        state,
+        beacon,
+        tasks,
+    })
+}
+
+/// Spawns the task that periodically persists the routing tables of the subnetworks to the node
+/// data dir, so that they're backed up, and used as bootnodes by the next run.
+fn spawn_routing_tables_persister(
+    node_data_dir: PathBuf,
+    history: Option<Arc<HistoryNetwork>>,
+    state: Option<Arc<StateNetwork>>,
+    beacon: Option<Arc<BeaconNetwork>>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ROUTING_TABLES_PERSIST_INTERVAL);
+        // The first tick is immediate, while the routing tables are still being populated.
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let mut routing_tables = BTreeMap::new();
+            if let Some(history) = &history {
+                routing_tables.insert(
+                    Subnetwork::History.to_string(),
+                    history.overlay.table_entries_enr(),
+                );
+            }
+            if let Some(state) = &state {
+                routing_tables.insert(
+                    Subnetwork::State.to_string(),
+                    state.overlay.table_entries_enr(),
+                );
+            }
+            if let Some(beacon) = &beacon {
+                routing_tables.insert(
+                    Subnetwork::Beacon.to_string(),
+                    beacon.overlay.table_entries_enr(),
+                );
+            }
+            if let Err(err) = persist_routing_tables(&node_data_dir, &routing_tables) {
+                warn!(error = %err, "Failed to persist the routing tables");
+            }
+        }
+    })
 }
@@ -1,14 +1,45 @@
 #![warn(clippy::unwrap_used)]
 
-use ethportal_api::types::cli::TrinConfig;
+use ethportal_api::types::cli::{TrinConfig, TrinConfigCommands};
 use tracing::error;
-use trin::run_trin;
+use trin::{
+    backup::{backup, restore},
+    replay::replay,
+    run_trin,
+};
 use trin_utils::log::init_tracing_logger;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     init_tracing_logger();
     let trin_config = TrinConfig::from_cli();
+    match &trin_config.command {
+        Some(TrinConfigCommands::Replay(replay_config)) => {
+            let report = replay(&replay_config.path).await?;
+            println!("{report}");
+            return Ok(());
+        }
+        Some(TrinConfigCommands::Backup(backup_config)) => {
+            let manifest = backup(&trin_config, &backup_config.path)?;
+            println!(
+                "Backed up node {} to {}",
+                manifest.node_id,
+                backup_config.path.display()
+            );
+            return Ok(());
+        }
+        Some(TrinConfigCommands::Restore(restore_config)) => {
+            let restored = restore(&trin_config, &restore_config.path, restore_config.force)?;
+            println!(
+                "Restored node {} to {}, backed up with storage capacities (bytes): {:?}",
+                restored.manifest.node_id,
+                restored.node_data_dir.display(),
+                restored.manifest.storage_capacity_bytes
+            );
+            return Ok(());
+        }
+        _ => {}
+    }
     let rpc_handle = run_trin(trin_config).await?;
 
     tokio::signal::ctrl_c()
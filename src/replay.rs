@@ -0,0 +1,352 @@
+//! Replays the inbound requests of a wire recording (see [portalnet::wire_recorder]) against a
+//! fresh node, and reports the responses that differ from the recorded ones.
+//!
+//! Only PING, FINDNODES and FINDCONTENT requests are replayed, as OFFER requests would need the
+//! uTP transfers. The fresh node has its own ENR, an empty routing table and empty storage, so
+//! some divergences are expected: they show how the recorded responses depended on the state of
+//! the recording node.
+
+use std::{
+    collections::HashSet,
+    fmt,
+    net::{Ipv4Addr, UdpSocket},
+    path::Path,
+    sync::Arc,
+};
+
+use alloy::primitives::B256;
+use anyhow::anyhow;
+use discv5::enr::NodeId;
+use ethportal_api::types::{
+    cli::StorageCapacityConfig,
+    network::Subnetwork,
+    portal_wire::{Message, Request, Response, MAINNET},
+};
+use portalnet::{
+    config::PortalnetConfig,
+    discovery::{Discovery, Discv5UdpSocket},
+    overlay::errors::OverlayRequestError,
+    wire_recorder::{read_records, WireDirection, WireRecord},
+};
+use ssz::Encode;
+use tempfile::TempDir;
+use tokio::sync::{mpsc, RwLock};
+use trin_beacon::{initialize_beacon_network, network::BeaconNetwork};
+use trin_history::{initialize_history_network, network::HistoryNetwork};
+use trin_state::{initialize_state_network, network::StateNetwork};
+use trin_storage::PortalStorageConfigFactory;
+use trin_validation::oracle::HeaderOracle;
+use utp_rs::socket::UtpSocket;
+
+/// The storage capacity of the fresh node.
+const REPLAY_STORAGE_CAPACITY_MB: u32 = 100;
+
+/// The outcome of replaying a wire recording.
+#[derive(Debug, Default)]
+pub struct ReplayReport {
+    /// The number of replayed requests
+    pub replayed: usize,
+    /// The number of records that weren't replayed, e.g. outbound requests and offers
+    pub skipped: usize,
+    pub divergences: Vec<Divergence>,
+}
+
+/// A replayed request whose response differs from the recorded one.
+#[derive(Debug)]
+pub struct Divergence {
+    /// The index of the record in the recording
+    pub index: usize,
+    pub subnetwork: Subnetwork,
+    pub peer: NodeId,
+    pub request: Request,
+    pub recorded: Result<Message, String>,
+    pub replayed: Result<Message, String>,
+}
+
+impl fmt::Display for ReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Replayed {} requests, skipped {} records, {} responses diverged",
+            self.replayed,
+            self.skipped,
+            self.divergences.len()
+        )?;
+        for divergence in &self.divergences {
+            writeln!(
+                f,
+                "#{} {} request from {}: {:?}\n  recorded: {:?}\n  replayed: {:?}",
+                divergence.index,
+                divergence.subnetwork,
+                divergence.peer,
+                divergence.request,
+                divergence.recorded,
+                divergence.replayed,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Replays the inbound requests of the wire recording at `path` against a fresh node.
+pub async fn replay(path: &Path) -> anyhow::Result<ReplayReport> {
+    let mut requests = vec![];
+    let mut report = ReplayReport::default();
+    for (index, record) in read_records(path)?.into_iter().enumerate() {
+        match replayable_request(&record)? {
+            Some((subnetwork, request)) => requests.push((index, subnetwork, record, request)),
+            None => report.skipped += 1,
+        }
+    }
+
+    let subnetworks = requests
+        .iter()
+        .map(|(_, subnetwork, _, _)| *subnetwork)
+        .collect::<HashSet<_>>();
+    let node = ReplayNode::start(&subnetworks).await?;
+
+    for (index, subnetwork, record, request) in requests {
+        let replayed = node
+            .process_request(subnetwork, request.clone(), record.peer)
+            .await
+            .map(|response| Message::from(response).as_ssz_bytes())
+            .map_err(|err| err.to_string());
+        report.replayed += 1;
+
+        let recorded = record.response.map(|response| response.to_vec());
+        let diverged = match (&recorded, &replayed) {
+            (Ok(recorded), Ok(replayed)) => recorded != replayed,
+            (Err(_), Err(_)) => false,
+            _ => true,
+        };
+        if diverged {
+            report.divergences.push(Divergence {
+                index,
+                subnetwork,
+                peer: record.peer,
+                request,
+                recorded: decode_response(recorded),
+                replayed: decode_response(replayed),
+            });
+        }
+    }
+    Ok(report)
+}
+
+/// Returns the subnetwork and the request of the record, if it should be replayed.
+fn replayable_request(record: &WireRecord) -> anyhow::Result<Option<(Subnetwork, Request)>> {
+    if record.direction != WireDirection::Inbound {
+        return Ok(None);
+    }
+    let subnetwork = Subnetwork::from_cli_arg(&record.subnetwork).map_err(|err| anyhow!(err))?;
+    if !matches!(
+        subnetwork,
+        Subnetwork::History | Subnetwork::Beacon | Subnetwork::State
+    ) {
+        return Ok(None);
+    }
+    let Ok(request) = record.request_message().map(Request::try_from) else {
+        return Ok(None);
+    };
+    match request {
+        Ok(request @ (Request::Ping(_) | Request::FindNodes(_) | Request::FindContent(_))) => {
+            Ok(Some((subnetwork, request)))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn decode_response(response: Result<Vec<u8>, String>) -> Result<Message, String> {
+    response.and_then(|response| {
+        Message::try_from(response).map_err(|err| format!("Invalid response: {err:?}"))
+    })
+}
+
+/// A node without bootnodes that only answers replayed requests.
+struct ReplayNode {
+    history: Option<Arc<HistoryNetwork>>,
+    beacon: Option<Arc<BeaconNetwork>>,
+    state: Option<Arc<StateNetwork>>,
+    // Kept until the node is dropped, as it holds the node's storage.
+    _data_dir: TempDir,
+}
+
+impl ReplayNode {
+    async fn start(subnetworks: &HashSet<Subnetwork>) -> anyhow::Result<Self> {
+        let portalnet_config = PortalnetConfig {
+            private_key: B256::random(),
+            listen_port: UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))?
+                .local_addr()?
+                .port(),
+            bootnodes: vec![],
+            no_stun: true,
+            no_upnp: true,
+            ..PortalnetConfig::default()
+        };
+        let mut discovery = Discovery::new(portalnet_config.clone(), MAINNET.clone())
+            .map_err(|err| anyhow!(err))?;
+        discovery.start().await.map_err(|err| anyhow!(err))?;
+        let discovery = Arc::new(discovery);
+
+        let header_oracle = Arc::new(RwLock::new(HeaderOracle::default()));
+        let (_utp_talk_reqs_tx, utp_talk_reqs_rx) = mpsc::unbounded_channel();
+        let discv5_utp_socket = Discv5UdpSocket::new(
+            Arc::clone(&discovery),
+            utp_talk_reqs_rx,
+            header_oracle.clone(),
+            portalnet_config.utp_transfer_limit * 2 * subnetworks.len(),
+        );
+        let utp_socket = Arc::new(UtpSocket::with_socket(discv5_utp_socket));
+
+        let data_dir = TempDir::new()?;
+        let storage_config_factory = PortalStorageConfigFactory::new(
+            StorageCapacityConfig::Combined {
+                total_mb: REPLAY_STORAGE_CAPACITY_MB,
+                subnetworks: subnetworks.iter().copied().collect(),
+            },
+            discovery.local_enr().node_id(),
+            data_dir.path().to_path_buf(),
+        )?;
+
+        let history = match subnetworks.contains(&Subnetwork::History) {
+            true => initialize_history_network(
+                &discovery,
+                utp_socket.clone(),
+                portalnet_config.clone(),
+                storage_config_factory.create(&Subnetwork::History)?,
+                header_oracle.clone(),
+            )
+            .await?
+            .0
+            .map(|handler| handler.network),
+            false => None,
+        };
+        let beacon = match subnetworks.contains(&Subnetwork::Beacon) {
+            true => initialize_beacon_network(
+                &discovery,
+                utp_socket.clone(),
+                portalnet_config.clone(),
+                storage_config_factory.create(&Subnetwork::Beacon)?,
+                header_oracle.clone(),
+            )
+            .await?
+            .0
+            .map(|handler| handler.network),
+            false => None,
+        };
+        let state = match subnetworks.contains(&Subnetwork::State) {
+            true => initialize_state_network(
+                &discovery,
+                utp_socket.clone(),
+                portalnet_config.clone(),
+                storage_config_factory.create(&Subnetwork::State)?,
+                header_oracle.clone(),
+            )
+            .await?
+            .0
+            .map(|handler| handler.network),
+            false => None,
+        };
+
+        Ok(Self {
+            history,
+            beacon,
+            state,
+            _data_dir: data_dir,
+        })
+    }
+
+    async fn process_request(
+        &self,
+        subnetwork: Subnetwork,
+        request: Request,
+        source: NodeId,
+    ) -> Result<Response, OverlayRequestError> {
+        let unavailable =
+            || OverlayRequestError::Failure(format!("{subnetwork} subnetwork isn't replayed"));
+        match subnetwork {
+            Subnetwork::History => {
+                let network = self.history.as_ref().ok_or_else(unavailable)?;
+                network
+                    .overlay
+                    .process_replayed_request(request, source)
+                    .await
+            }
+            Subnetwork::Beacon => {
+                let network = self.beacon.as_ref().ok_or_else(unavailable)?;
+                network
+                    .overlay
+                    .process_replayed_request(request, source)
+                    .await
+            }
+            Subnetwork::State => {
+                let network = self.state.as_ref().ok_or_else(unavailable)?;
+                network
+                    .overlay
+                    .process_replayed_request(request, source)
+                    .await
+            }
+            _ => Err(unavailable()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ethportal_api::types::portal_wire::{CustomPayload, FindNodes, Nodes, Ping, Pong};
+    use portalnet::wire_recorder::write_record;
+
+    use super::*;
+
+    fn inbound_record(request: Message, response: Message) -> WireRecord {
+        WireRecord {
+            timestamp_ms: 0,
+            direction: WireDirection::Inbound,
+            subnetwork: Subnetwork::History.to_cli_arg(),
+            peer: NodeId::random(),
+            request: request.as_ssz_bytes().into(),
+            response: Ok(response.as_ssz_bytes().into()),
+        }
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn replay_reports_divergences() {
+        let find_nodes = inbound_record(
+            Message::FindNodes(FindNodes {
+                distances: vec![256],
+            }),
+            Message::Nodes(Nodes {
+                total: 1,
+                enrs: vec![],
+            }),
+        );
+        // The fresh node has a different ENR sequence number
+        let ping = inbound_record(
+            Message::Ping(Ping {
+                enr_seq: 1,
+                custom_payload: CustomPayload::from(vec![]),
+            }),
+            Message::Pong(Pong {
+                enr_seq: u64::MAX,
+                custom_payload: CustomPayload::from(vec![]),
+            }),
+        );
+        let mut outbound = ping.clone();
+        outbound.direction = WireDirection::Outbound;
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        for record in [&find_nodes, &ping, &outbound] {
+            write_record(&mut file.as_file(), record).unwrap();
+        }
+
+        let report = replay(file.path()).await.unwrap();
+        assert_eq!(report.replayed, 2);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(report.divergences.len(), 1);
+        let divergence = &report.divergences[0];
+        assert_eq!(divergence.index, 1);
+        assert_eq!(divergence.peer, ping.peer);
+        assert!(matches!(divergence.replayed, Ok(Message::Pong(_))));
+    }
+}
@@ -0,0 +1,136 @@
+#![cfg(unix)]
+use std::{
+    collections::BTreeMap,
+    fs,
+    net::{IpAddr, Ipv4Addr},
+    path::Path,
+};
+
+use discv5::enr::{CombinedKey, Enr};
+
+use ethportal_api::{
+    types::{
+        cli::TrinConfig, execution::header_with_proof::HeaderWithProof, portal::LocalContentInfo,
+    },
+    ContentValue, Discv5ApiClient, HistoryContentKey, HistoryContentValue, HistoryNetworkApiClient,
+};
+use jsonrpsee::async_client::Client;
+use portalnet::utils::db::{
+    configure_node_data_dir, persist_routing_tables, read_persisted_routing_tables,
+};
+use rpc::RpcServerHandle;
+use serial_test::serial;
+use ssz::Decode;
+use tempfile::TempDir;
+use trin::backup::{backup, restore, BACKUP_SCHEMA_VERSION};
+
+mod utils;
+use utils::init_tracing;
+
+async fn start_node(data_dir: &Path, discovery_port: u16) -> (RpcServerHandle, Client) {
+    let test_ip_addr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let external_addr = format!("{test_ip_addr}:{discovery_port}");
+    let ipc_path = data_dir.join("trin.ipc");
+    let trin_config = TrinConfig::new_from([
+        "trin",
+        "--data-dir",
+        data_dir.to_str().unwrap(),
+        "--web3-ipc-path",
+        ipc_path.to_str().unwrap(),
+        "--external-address",
+        external_addr.as_str(),
+        "--discovery-port",
+        &discovery_port.to_string(),
+        "--bootnodes",
+        "none",
+    ])
+    .unwrap();
+    let handle = trin::run_trin(trin_config).await.unwrap();
+    let client = reth_ipc::client::IpcClientBuilder::default()
+        .build(ipc_path.to_str().unwrap())
+        .await
+        .unwrap();
+    (handle, client)
+}
+
+fn data_dir_config(data_dir: &Path) -> TrinConfig {
+    TrinConfig::new_from(["trin", "--data-dir", data_dir.to_str().unwrap()]).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn backup_and_restore_node() {
+    init_tracing();
+    let source_dir = TempDir::new().unwrap();
+    let target_dir = TempDir::new().unwrap();
+    let archive_dir = TempDir::new().unwrap();
+    let archive_path = archive_dir.path().join("backup.tar.zst");
+
+    let header = fs::read("test_assets/mainnet/large_content/15040641/header.bin").unwrap();
+    let header = HeaderWithProof::from_ssz_bytes(&header).unwrap();
+    let content_key = HistoryContentKey::new_block_header_by_hash(header.header.hash());
+    let content_value = HistoryContentValue::BlockHeaderWithProof(header).encode();
+
+    let (source_handle, source_client) = start_node(source_dir.path(), 8993).await;
+    assert!(HistoryNetworkApiClient::store(
+        &source_client,
+        content_key.clone(),
+        content_value.clone(),
+        None,
+        None
+    )
+    .await
+    .unwrap());
+    let source_node_id = Discv5ApiClient::node_info(&source_client, None)
+        .await
+        .unwrap()
+        .node_id;
+
+    // The node persists its routing tables periodically.
+    let source_config = data_dir_config(source_dir.path());
+    let (source_node_data_dir, _) =
+        configure_node_data_dir(source_dir.path(), None, source_config.network.network()).unwrap();
+    let peer = Enr::builder()
+        .udp4(9000)
+        .build(&CombinedKey::generate_secp256k1())
+        .unwrap();
+    let routing_tables = BTreeMap::from([("History".to_string(), vec![peer])]);
+    persist_routing_tables(&source_node_data_dir, &routing_tables).unwrap();
+
+    // The source node is still running while it is backed up.
+    let manifest = backup(&data_dir_config(source_dir.path()), &archive_path).unwrap();
+    source_handle.stop().unwrap();
+    assert_eq!(manifest.schema_version, BACKUP_SCHEMA_VERSION);
+    assert_eq!(manifest.node_id.0, source_node_id.raw());
+    assert!(!manifest.store_versions.is_empty());
+    assert!(manifest.has_routing_tables);
+
+    let target_config = data_dir_config(target_dir.path());
+    let restored = restore(&target_config, &archive_path, false).unwrap();
+    assert_eq!(restored.manifest, manifest);
+    assert!(restored.node_data_dir.starts_with(target_dir.path()));
+
+    // Restoring again would replace the restored database.
+    let err = restore(&target_config, &archive_path, false).unwrap_err();
+    assert!(
+        err.to_string().contains("already has a content database"),
+        "Unexpected error: {err}"
+    );
+    restore(&target_config, &archive_path, true).unwrap();
+    assert_eq!(
+        read_persisted_routing_tables(&restored.node_data_dir).unwrap(),
+        routing_tables
+    );
+
+    let (target_handle, target_client) = start_node(target_dir.path(), 8994).await;
+    let target_node_id = Discv5ApiClient::node_info(&target_client, None)
+        .await
+        .unwrap()
+        .node_id;
+    assert_eq!(target_node_id, source_node_id);
+    let content = HistoryNetworkApiClient::local_content(&target_client, content_key, None, None)
+        .await
+        .unwrap();
+    assert_eq!(content, LocalContentInfo::Content(content_value));
+    target_handle.stop().unwrap();
+}
@@ -0,0 +1,219 @@
+use ethportal_api::{
+    consensus::{
+        fork::ForkName,
+        historical_summaries::{HistoricalSummaries, HistoricalSummariesWithProof},
+    },
+    types::{
+        content_key::beacon::HistoricalSummariesWithProofKey,
+        content_value::beacon::ForkVersionedHistoricalSummariesWithProof,
+        execution::header_with_proof::HeaderWithProof,
+        jsonrpc::endpoints::{BeaconEndpoint, HistoryEndpoint},
+        network::Subnetwork,
+        portal::LocalContentInfo,
+    },
+    utils::content_signature::{sign_content, PublicKey, SecretKey},
+    BeaconContentKey, BeaconContentValue, ContentValue, HistoryContentKey, HistoryContentValue,
+    OverlayContentKey,
+};
+use ssz::Decode;
+use trin_test_utils::{TestNode, TestNodeConfig};
+
+/// Returns a secret key and its public key.
+fn keypair(seed: u8) -> (SecretKey, PublicKey) {
+    let secret_key = SecretKey::from_slice(&[seed; 32]).unwrap();
+    (secret_key, PublicKey::from_secret_key_global(&secret_key))
+}
+
+/// Starts a node that trusts the signatures of the public key.
+async fn start_node(subnetworks: &[Subnetwork], trusted_signer: PublicKey) -> TestNode {
+    TestNode::start(TestNodeConfig {
+        trusted_content_signers: vec![trusted_signer],
+        ..TestNodeConfig::new(subnetworks)
+    })
+    .await
+    .unwrap()
+}
+
+/// Pre-merge header with proof of block #15040641.
+fn history_header_fixture() -> (HistoryContentKey, HistoryContentValue) {
+    let header = std::fs::read("test_assets/mainnet/large_content/15040641/header.bin")
+        .expect("cannot find test asset");
+    let header = HeaderWithProof::from_ssz_bytes(&header).unwrap();
+    let content_key = HistoryContentKey::new_block_header_by_hash(header.header.hash());
+    (
+        content_key,
+        HistoryContentValue::BlockHeaderWithProof(header),
+    )
+}
+
+fn beacon_historical_summaries_fixture() -> (BeaconContentKey, BeaconContentValue) {
+    let epoch = 300_000;
+    let content_key =
+        BeaconContentKey::HistoricalSummariesWithProof(HistoricalSummariesWithProofKey { epoch });
+    let content_value = BeaconContentValue::HistoricalSummariesWithProof(
+        ForkVersionedHistoricalSummariesWithProof {
+            fork_name: ForkName::Deneb,
+            historical_summaries_with_proof: HistoricalSummariesWithProof {
+                epoch,
+                historical_summaries: HistoricalSummaries::default(),
+                proof: Default::default(),
+            },
+        },
+    );
+    (content_key, content_value)
+}
+
+/// Returns whether the content is trusted, as reported by the LocalContent endpoint, or `None` if
+/// it isn't stored.
+async fn history_trusted(node: &TestNode, content_key: &HistoryContentKey) -> Option<bool> {
+    let result = node
+        .history()
+        .request(HistoryEndpoint::LocalContent(
+            content_key.clone(),
+            true,
+            false,
+        ))
+        .await
+        .ok()?;
+    match serde_json::from_value::<LocalContentInfo>(result).unwrap() {
+        LocalContentInfo::WithMeta { meta, .. } => Some(meta.trusted),
+        info => panic!("Expected content with metadata, got {info:?}"),
+    }
+}
+
+/// Returns whether the beacon content is trusted, as reported by the LocalContent endpoint, or
+/// `None` if it isn't stored.
+async fn beacon_trusted(node: &TestNode, content_key: &BeaconContentKey) -> Option<bool> {
+    let result = node
+        .beacon()
+        .request(BeaconEndpoint::LocalContent(content_key.clone(), true))
+        .await
+        .ok()?;
+    match serde_json::from_value::<LocalContentInfo>(result).unwrap() {
+        LocalContentInfo::WithMeta { meta, .. } => Some(meta.trusted),
+        info => panic!("Expected content with metadata, got {info:?}"),
+    }
+}
+
+#[test_log::test(tokio::test)]
+async fn history_store_checks_signature() {
+    let (trusted_key, trusted_signer) = keypair(1);
+    let (untrusted_key, _) = keypair(2);
+    let node = start_node(&[Subnetwork::History], trusted_signer).await;
+    let (content_key, content_value) = history_header_fixture();
+    let key_bytes = content_key.to_bytes();
+    let value_bytes = content_value.encode();
+
+    // Content signed by an untrusted signer isn't stored.
+    let signature = sign_content(&untrusted_key, &key_bytes, &value_bytes);
+    let err = node
+        .history()
+        .request(HistoryEndpoint::Store(
+            content_key.clone(),
+            content_value.clone(),
+            None,
+            Some(signature),
+        ))
+        .await
+        .unwrap_err();
+    assert!(err.contains("untrusted signer"), "{err}");
+    assert_eq!(history_trusted(&node, &content_key).await, None);
+
+    // Neither is content whose signature is over other content.
+    let signature = sign_content(&trusted_key, &key_bytes, &[0]);
+    let err = node
+        .history()
+        .request(HistoryEndpoint::Store(
+            content_key.clone(),
+            content_value.clone(),
+            None,
+            Some(signature),
+        ))
+        .await
+        .unwrap_err();
+    assert!(err.contains("untrusted signer"), "{err}");
+    assert_eq!(history_trusted(&node, &content_key).await, None);
+
+    // Unsigned content is stored, but isn't trusted...
+    let result = node
+        .history()
+        .request(HistoryEndpoint::Store(
+            content_key.clone(),
+            content_value.clone(),
+            None,
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result, true);
+    assert_eq!(history_trusted(&node, &content_key).await, Some(false));
+
+    // ...until it's stored again with the signature of the trusted signer.
+    let signature = sign_content(&trusted_key, &key_bytes, &value_bytes);
+    let result = node
+        .history()
+        .request(HistoryEndpoint::Store(
+            content_key.clone(),
+            content_value,
+            None,
+            Some(signature),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result, true);
+    assert_eq!(history_trusted(&node, &content_key).await, Some(true));
+
+    node.shutdown().await;
+}
+
+#[test_log::test(tokio::test)]
+async fn beacon_store_checks_signature() {
+    let (trusted_key, trusted_signer) = keypair(1);
+    let (untrusted_key, _) = keypair(2);
+    let node = start_node(&[Subnetwork::Beacon], trusted_signer).await;
+    let (content_key, content_value) = beacon_historical_summaries_fixture();
+    let key_bytes = content_key.to_bytes();
+    let value_bytes = content_value.encode();
+
+    let signature = sign_content(&untrusted_key, &key_bytes, &value_bytes);
+    let err = node
+        .beacon()
+        .request(BeaconEndpoint::Store(
+            content_key.clone(),
+            content_value.clone(),
+            Some(signature),
+        ))
+        .await
+        .unwrap_err();
+    assert!(err.contains("untrusted signer"), "{err}");
+    assert_eq!(beacon_trusted(&node, &content_key).await, None);
+
+    // Unsigned content is stored, but isn't trusted...
+    let result = node
+        .beacon()
+        .request(BeaconEndpoint::Store(
+            content_key.clone(),
+            content_value.clone(),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result, true);
+    assert_eq!(beacon_trusted(&node, &content_key).await, Some(false));
+
+    // ...until it's stored again with the signature of the trusted signer.
+    let signature = sign_content(&trusted_key, &key_bytes, &value_bytes);
+    let result = node
+        .beacon()
+        .request(BeaconEndpoint::Store(
+            content_key.clone(),
+            content_value,
+            Some(signature),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result, true);
+    assert_eq!(beacon_trusted(&node, &content_key).await, Some(true));
+
+    node.shutdown().await;
+}
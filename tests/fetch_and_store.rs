@@ -0,0 +1,171 @@
+use alloy::primitives::B256;
+use ethportal_api::{
+    consensus::fork::ForkName,
+    light_client::optimistic_update::LightClientOptimisticUpdate,
+    types::{
+        content_key::beacon::LightClientOptimisticUpdateKey,
+        content_value::beacon::ForkVersionedLightClientOptimisticUpdate,
+        execution::header_with_proof::HeaderWithProof,
+        jsonrpc::endpoints::{BeaconEndpoint, HistoryEndpoint},
+        network::Subnetwork,
+        portal::{ContentProvenance, FetchAndStoreInfo, LocalContentInfo},
+    },
+    BeaconContentKey, BeaconContentValue, ContentValue, HistoryContentKey, HistoryContentValue,
+};
+use ssz::Decode;
+use trin_test_utils::{seed_content, TestNetwork};
+
+/// Pre-merge header with proof of block #15040641.
+fn history_header_fixture() -> (HistoryContentKey, HistoryContentValue) {
+    let header = std::fs::read("test_assets/mainnet/large_content/15040641/header.bin")
+        .expect("cannot find test asset");
+    let header = HeaderWithProof::from_ssz_bytes(&header).unwrap();
+    let content_key = HistoryContentKey::new_block_header_by_hash(header.header.hash());
+    (
+        content_key,
+        HistoryContentValue::BlockHeaderWithProof(header),
+    )
+}
+
+fn beacon_optimistic_update_fixture() -> (BeaconContentKey, BeaconContentValue) {
+    let update = std::fs::read(
+        "test_assets/beacon/deneb/LightClientOptimisticUpdate/ssz_random/case_0/serialized.ssz_snappy",
+    )
+    .expect("cannot find test asset");
+    let update = snap::raw::Decoder::new().decompress_vec(&update).unwrap();
+    let update = LightClientOptimisticUpdate::from_ssz_bytes(&update, ForkName::Deneb).unwrap();
+    let content_key =
+        BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey {
+            signature_slot: *update.signature_slot(),
+        });
+    let content_value =
+        BeaconContentValue::LightClientOptimisticUpdate(ForkVersionedLightClientOptimisticUpdate {
+            fork_name: ForkName::Deneb,
+            update,
+        });
+    (content_key, content_value)
+}
+
+#[test_log::test(tokio::test)]
+async fn fetch_and_store_stores_content_of_peer() {
+    let network = TestNetwork::start(2, &[Subnetwork::History]).await.unwrap();
+    let (content_key, content_value) = history_header_fixture();
+    seed_content(network.node(0), content_key.clone(), &content_value).unwrap();
+    let peer = network.node(0).enr();
+    let node = network.node(1);
+
+    let result = node
+        .history()
+        .request(HistoryEndpoint::FetchAndStore(
+            peer.clone(),
+            content_key.clone(),
+            false,
+        ))
+        .await
+        .unwrap();
+    let info: FetchAndStoreInfo = serde_json::from_value(result).unwrap();
+    assert!(info.stored, "Content wasn't stored: {:?}", info.reason);
+    assert_eq!(info.content_length, content_value.encode().len() as u64);
+
+    let result = node
+        .history()
+        .request(HistoryEndpoint::LocalContent(
+            content_key.clone(),
+            true,
+            false,
+        ))
+        .await
+        .unwrap();
+    match serde_json::from_value::<LocalContentInfo>(result).unwrap() {
+        LocalContentInfo::WithMeta { content, meta, .. } => {
+            assert_eq!(content, content_value.encode());
+            assert_eq!(meta.provenance, ContentProvenance::Fetch);
+        }
+        info => panic!("Expected content with metadata, got {info:?}"),
+    }
+
+    // Fetching the content again doesn't store it twice.
+    let result = node
+        .history()
+        .request(HistoryEndpoint::FetchAndStore(peer, content_key, false))
+        .await
+        .unwrap();
+    let info: FetchAndStoreInfo = serde_json::from_value(result).unwrap();
+    assert!(!info.stored);
+    assert_eq!(info.reason.as_deref(), Some("Content is already stored"));
+
+    network.shutdown().await;
+}
+
+#[test_log::test(tokio::test)]
+async fn fetch_and_store_rejects_invalid_content() {
+    let network = TestNetwork::start(2, &[Subnetwork::History]).await.unwrap();
+    let (_, content_value) = history_header_fixture();
+    // The peer serves the header for a key of another block hash.
+    let content_key = HistoryContentKey::new_block_header_by_hash(B256::random());
+    seed_content(network.node(0), content_key.clone(), &content_value).unwrap();
+    let node = network.node(1);
+
+    let err = node
+        .history()
+        .request(HistoryEndpoint::FetchAndStore(
+            network.node(0).enr(),
+            content_key.clone(),
+            true,
+        ))
+        .await
+        .unwrap_err();
+    assert!(err.contains("validation failed"), "Unexpected error: {err}");
+
+    let err = node
+        .history()
+        .request(HistoryEndpoint::LocalContent(content_key, false, false))
+        .await
+        .unwrap_err();
+    assert!(err.contains("not found"), "Unexpected error: {err}");
+
+    network.shutdown().await;
+}
+
+#[test_log::test(tokio::test)]
+async fn fetch_and_store_stores_beacon_content_of_peer() {
+    let network = TestNetwork::start(2, &[Subnetwork::Beacon]).await.unwrap();
+    let (content_key, content_value) = beacon_optimistic_update_fixture();
+    seed_content(network.node(0), content_key.clone(), &content_value).unwrap();
+    let peer = network.node(0).enr();
+    let node = network.node(1);
+
+    let result = node
+        .beacon()
+        .request(BeaconEndpoint::FetchAndStore(
+            peer.clone(),
+            content_key.clone(),
+            false,
+        ))
+        .await
+        .unwrap();
+    let info: FetchAndStoreInfo = serde_json::from_value(result).unwrap();
+    assert!(info.stored, "Content wasn't stored: {:?}", info.reason);
+    assert_eq!(info.content_length, content_value.encode().len() as u64);
+
+    let result = node
+        .beacon()
+        .request(BeaconEndpoint::LocalContent(content_key.clone(), false))
+        .await
+        .unwrap();
+    assert_eq!(
+        serde_json::from_value::<LocalContentInfo>(result).unwrap(),
+        LocalContentInfo::Content(content_value.encode())
+    );
+
+    let result = node
+        .beacon()
+        .request(BeaconEndpoint::FetchAndStore(peer, content_key, false))
+        .await
+        .unwrap();
+    let info: FetchAndStoreInfo = serde_json::from_value(result).unwrap();
+    assert!(!info.stored);
+    assert_eq!(info.reason.as_deref(), Some("Content is already stored"));
+
+    network.shutdown().await;
+}
@@ -0,0 +1,128 @@
+use std::{
+    io,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use alloy::primitives::B256;
+use ethportal_api::{
+    types::{
+        execution::header_with_proof::HeaderWithProof, jsonrpc::endpoints::HistoryEndpoint,
+        network::Subnetwork,
+    },
+    HistoryContentKey, HistoryContentValue,
+};
+use ssz::Decode;
+use tracing::{subscriber::DefaultGuard, Level};
+use trin_test_utils::{assert_routing_contains, wait_for_gossip, TestNetwork};
+
+/// Collects the formatted log lines of the test.
+#[derive(Clone, Default)]
+struct LogLines(Arc<Mutex<Vec<u8>>>);
+
+impl LogLines {
+    /// Returns the log lines that mention the request id and were emitted by the target.
+    fn matching(&self, request_id: &str, target: &str) -> Vec<String> {
+        let logs = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        String::from_utf8_lossy(&logs)
+            .lines()
+            .filter(|line| line.contains(request_id) && line.contains(target))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl io::Write for LogLines {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Collects the logs of the current thread. The tests run on a single-threaded runtime, so every
+/// task of the nodes logs to the subscriber of this thread.
+fn capture_logs() -> (LogLines, DefaultGuard) {
+    let logs = LogLines::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(Level::DEBUG)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    (logs, tracing::subscriber::set_default(subscriber))
+}
+
+#[tokio::test]
+async fn request_id_is_attached_to_rpc_and_overlay_logs() {
+    let (logs, _guard) = capture_logs();
+
+    let network = TestNetwork::start(2, &[Subnetwork::History]).await.unwrap();
+    let request_id = "test-request-id";
+    let content_key = HistoryContentKey::new_block_header_by_hash(B256::random());
+    let result = network
+        .node(0)
+        .history()
+        .request_with_id(HistoryEndpoint::GetContent(content_key), request_id)
+        .await;
+    assert!(result.is_err(), "Absent content shouldn't be found");
+
+    assert!(
+        !logs
+            .matching(request_id, "trin_history::jsonrpc")
+            .is_empty(),
+        "The request id should be attached to the RPC logs"
+    );
+    assert!(
+        !logs
+            .matching(request_id, "portalnet::overlay::service")
+            .is_empty(),
+        "The request id should be attached to the overlay logs"
+    );
+
+    network.shutdown().await;
+}
+
+#[tokio::test]
+async fn request_id_is_attached_to_gossip_logs() {
+    let (logs, _guard) = capture_logs();
+
+    let network = TestNetwork::start(2, &[Subnetwork::History]).await.unwrap();
+    let (gossiper, receiver) = (network.node(0), network.node(1));
+    assert_routing_contains(gossiper, receiver, Subnetwork::History);
+
+    let header = std::fs::read("test_assets/mainnet/large_content/15040641/header.bin")
+        .expect("cannot find test asset");
+    let header = HeaderWithProof::from_ssz_bytes(&header).unwrap();
+    let content_key = HistoryContentKey::new_block_header_by_hash(header.header.hash());
+    let content_value = HistoryContentValue::BlockHeaderWithProof(header);
+    let request_id = "test-gossip-request-id";
+    gossiper
+        .history()
+        .request_with_id(
+            HistoryEndpoint::Gossip(content_key.clone(), content_value),
+            request_id,
+        )
+        .await
+        .unwrap();
+    wait_for_gossip(receiver, &content_key, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    // The offer is answered in the overlay service, after the RPC request has been responded to.
+    let answers = logs
+        .matching(request_id, "portalnet::overlay::service")
+        .into_iter()
+        .filter(|line| line.contains("Gossip offer answered"))
+        .collect::<Vec<_>>();
+    assert_eq!(answers.len(), 1, "{answers:?}");
+    assert!(answers[0].contains("accepted=true"), "{answers:?}");
+
+    network.shutdown().await;
+}
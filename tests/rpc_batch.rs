@@ -0,0 +1,100 @@
+#![cfg(unix)]
+/// Test that JSON-RPC batch requests are routed call by call, and that too large batches are
+/// rejected.
+use std::net::{IpAddr, Ipv4Addr};
+
+use alloy::primitives::B256;
+use ethportal_api::{types::cli::TrinConfig, HistoryContentKey};
+use serde_json::{json, Value};
+use serial_test::serial;
+
+mod utils;
+use utils::init_tracing;
+
+const MAX_BATCH_SIZE: usize = 3;
+
+async fn post(url: &str, body: Value) -> Value {
+    let url = url.to_string();
+    tokio::task::spawn_blocking(move || {
+        ureq::post(&url)
+            .send_json(body)
+            .unwrap()
+            .into_json()
+            .unwrap()
+    })
+    .await
+    .unwrap()
+}
+
+fn call(id: u64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn batch_request_is_routed_per_call() {
+    init_tracing();
+    let test_ip_addr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    // Use an uncommon port for the test to avoid clashes.
+    let test_discovery_port = 8995;
+    let external_addr = format!("{test_ip_addr}:{test_discovery_port}");
+    let http_address = format!("http://{test_ip_addr}:8595");
+    let trin_config = TrinConfig::new_from([
+        "trin",
+        "--portal-subnetworks",
+        "history,beacon",
+        "--web3-transport",
+        "http",
+        "--web3-http-address",
+        http_address.as_str(),
+        "--rpc-max-batch-size",
+        &MAX_BATCH_SIZE.to_string(),
+        "--external-address",
+        external_addr.as_str(),
+        "--ephemeral",
+        "--discovery-port",
+        &test_discovery_port.to_string(),
+        "--bootnodes",
+        "none",
+    ])
+    .unwrap();
+    let web3_server = trin::run_trin(trin_config).await.unwrap();
+
+    let content_key = HistoryContentKey::new_block_header_by_hash(B256::ZERO);
+    let batch = vec![
+        call(1, "portal_beaconRadius", json!([])),
+        call(2, "portal_invalidMethod", json!([])),
+        call(3, "portal_historyLocalContent", json!([content_key])),
+    ];
+    let responses = post(&http_address, json!(batch)).await;
+    let responses = responses
+        .as_array()
+        .expect("Batch response should be an array");
+    assert_eq!(responses.len(), 3);
+    let response = |id: u64| {
+        responses
+            .iter()
+            .find(|response| response["id"] == id)
+            .unwrap_or_else(|| panic!("Missing response for id {id}"))
+    };
+    assert!(response(1)["result"].is_string(), "{}", response(1));
+    assert_eq!(response(2)["error"]["code"], -32601);
+    assert!(
+        response(3)["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("Content not found in local storage"),
+        "{}",
+        response(3)
+    );
+
+    // A batch with more calls than allowed is rejected as a whole.
+    let batch = (0..=MAX_BATCH_SIZE as u64)
+        .map(|id| call(id, "portal_beaconRadius", json!([])))
+        .collect::<Vec<_>>();
+    let response = post(&http_address, json!(batch)).await;
+    assert_eq!(response["error"]["code"], -32010, "{response}");
+    assert!(response["id"].is_null());
+
+    web3_server.stop().unwrap();
+}
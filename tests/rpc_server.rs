@@ -87,6 +87,8 @@ async fn test_eth_get_block_by_number() {
         .store(
             HistoryContentKey::new_block_header_by_number(block_number),
             HistoryContentValue::BlockHeaderWithProof(hwp.clone()).encode(),
+            None,
+            None,
         )
         .await
         .unwrap());
@@ -96,6 +98,8 @@ async fn test_eth_get_block_by_number() {
         .store(
             HistoryContentKey::new_block_body(hwp.header.hash()),
             HistoryContentValue::BlockBody(body.clone()).encode(),
+            None,
+            None,
         )
         .await
         .unwrap());
@@ -153,6 +157,8 @@ async fn test_eth_get_block_by_number_hydrated() {
         .store(
             HistoryContentKey::new_block_header_by_number(block_number),
             HistoryContentValue::BlockHeaderWithProof(hwp.clone()).encode(),
+            None,
+            None,
         )
         .await
         .unwrap());
@@ -207,6 +213,8 @@ async fn test_eth_get_block_by_hash() {
         .store(
             HistoryContentKey::new_block_header_by_hash(block_hash),
             HistoryContentValue::BlockHeaderWithProof(hwp.clone()).encode(),
+            None,
+            None,
         )
         .await
         .unwrap());
@@ -216,6 +224,8 @@ async fn test_eth_get_block_by_hash() {
         .store(
             HistoryContentKey::new_block_body(block_hash),
             HistoryContentValue::BlockBody(body.clone()).encode(),
+            None,
+            None,
         )
         .await
         .unwrap());
@@ -273,6 +283,8 @@ async fn test_eth_get_block_by_hash_hydrated() {
         .store(
             HistoryContentKey::new_block_header_by_hash(block_hash),
             HistoryContentValue::BlockHeaderWithProof(hwp.clone()).encode(),
+            None,
+            None,
         )
         .await
         .unwrap());
@@ -34,6 +34,7 @@ async fn peertest_stateless() {
 
     peertest::scenarios::paginate::test_paginate_local_storage(&peertest).await;
     peertest::scenarios::basic::test_web3_client_version(&target).await;
+    peertest::scenarios::basic::test_portal_node_overview(&target).await;
     peertest::scenarios::basic::test_discv5_node_info(&peertest).await;
     peertest::scenarios::basic::test_discv5_routing_table_info(&target).await;
     peertest::scenarios::eth_rpc::test_eth_chain_id(&peertest).await;
@@ -46,6 +47,7 @@ async fn peertest_stateless() {
         peertest::scenarios::basic::test_delete_enr(subnetwork, &target, &peertest).await;
         peertest::scenarios::basic::test_lookup_enr(subnetwork, &peertest).await;
         peertest::scenarios::basic::test_ping(subnetwork, &target, &peertest).await;
+        peertest::scenarios::basic::test_ping_errors(subnetwork, &target).await;
         peertest::scenarios::basic::test_find_nodes(subnetwork, &target, &peertest).await;
         peertest::scenarios::basic::test_find_nodes_zero_distance(subnetwork, &target, &peertest)
             .await;
@@ -54,6 +56,9 @@ async fn peertest_stateless() {
         peertest::scenarios::find::test_recursive_find_nodes_random(subnetwork, &peertest).await;
     }
 
+    peertest::scenarios::basic::test_beacon_replacement_cache(&target).await;
+    peertest::scenarios::basic::test_beacon_verify_peers(&target, &peertest).await;
+    peertest::scenarios::basic::test_add_bootnode(&target, &peertest).await;
     peertest::scenarios::basic::test_history_store(&target).await;
     peertest::scenarios::basic::test_history_local_content_absent(&target).await;
     peertest.exit_all_nodes();
@@ -70,6 +75,16 @@ async fn peertest_offer() {
     handle.stop().unwrap();
 }
 
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn peertest_offer_many() {
+    let (peertest, target, handle) =
+        setup_peertest(&Network::Mainnet, &[Subnetwork::History]).await;
+    peertest::scenarios::offer_accept::test_offer_many(&peertest, &target).await;
+    peertest.exit_all_nodes();
+    handle.stop().unwrap();
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[serial]
 async fn peertest_offer_with_trace() {
@@ -90,16 +105,6 @@ async fn peertest_offer_concurrent_utp_transfer_limit() {
     handle.stop().unwrap();
 }
 
-#[tokio::test(flavor = "multi_thread")]
-#[serial]
-async fn peertest_gossip_with_trace() {
-    let (peertest, target, handle) =
-        setup_peertest(&Network::Mainnet, &[Subnetwork::History]).await;
-    peertest::scenarios::gossip::test_gossip_with_trace(&peertest, &target).await;
-    peertest.exit_all_nodes();
-    handle.stop().unwrap();
-}
-
 #[tokio::test(flavor = "multi_thread")]
 #[serial]
 async fn peertest_find_content_return_enr() {
@@ -292,26 +297,6 @@ async fn peertest_history_offer_propagates_gossip_multiple_large_content_values(
     handle.stop().unwrap();
 }
 
-#[tokio::test(flavor = "multi_thread")]
-#[serial]
-async fn peertest_history_gossip_dropped_with_offer() {
-    let (peertest, target, handle) =
-        setup_peertest(&Network::Mainnet, &[Subnetwork::History]).await;
-    peertest::scenarios::gossip::test_gossip_dropped_with_offer(&peertest, &target).await;
-    peertest.exit_all_nodes();
-    handle.stop().unwrap();
-}
-
-#[tokio::test(flavor = "multi_thread")]
-#[serial]
-async fn peertest_history_gossip_dropped_with_find_content() {
-    let (peertest, target, handle) =
-        setup_peertest(&Network::Mainnet, &[Subnetwork::History]).await;
-    peertest::scenarios::gossip::test_gossip_dropped_with_find_content(&peertest, &target).await;
-    peertest.exit_all_nodes();
-    handle.stop().unwrap();
-}
-
 #[tokio::test(flavor = "multi_thread")]
 #[serial]
 async fn peertest_ping_cross_discv5_protocol_id() {
@@ -1,6 +1,5 @@
 use std::sync::Arc;
 
-use ethportal_api::types::portal_wire::Message;
 use portalnet::events::OverlayRequest;
 use tokio::sync::mpsc::UnboundedReceiver;
 use tracing::{error, warn, Instrument};
@@ -50,7 +49,9 @@ impl BeaconEvents {
             .instrument(tracing::info_span!("beacon_network", req = %talk_request_id))
             .await
         {
-            Ok(response) => Message::from(response).into(),
+            Ok(response) => network
+                .overlay
+                .encode_response(*request.node_id(), response),
             Err(error) => {
                 error!(
                     error = %error,
@@ -1,25 +1,50 @@
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+use alloy::primitives::{Bytes, B256};
 use discv5::enr::NodeId;
 use ethportal_api::{
+    consensus::chain_config::ChainConfig,
     types::{
         content_value::ContentValue,
-        distance::Distance,
         jsonrpc::{endpoints::BeaconEndpoint, request::BeaconJsonRpcRequest},
-        portal::{AcceptInfo, FindNodesInfo, GetContentInfo, PongInfo, TraceContentInfo},
-        portal_wire::Content,
+        network::Subnetwork,
+        portal::{
+            AcceptInfo, BandwidthLimitInfo, ContentInspectionInfo, ContentOrClosestInfo,
+            FindNodesInfo, GetContentInfo, GetEnrInfo, HeaderUpdateTimesInfo, LocalContentInfo,
+            LocalContentMeta, OfferAndVerifyInfo, OfferManyInfo, OverlayNetworkInfo,
+            TraceContentInfo, UtpLimitInfo,
+        },
+        portal_wire::{Content, NetworkSpec},
         query_trace::QueryTrace,
     },
-    utils::bytes::hex_encode,
+    utils::{bytes::hex_encode, content_signature::verify_content_signature},
     BeaconContentKey, BeaconContentValue, OverlayContentKey, RawContentValue,
 };
-use portalnet::overlay::{config::FindContentConfig, errors::OverlayRequestError};
+use portalnet::{
+    discovery::decode_enr_field,
+    overlay::{
+        config::FindContentConfig,
+        errors::{OverlayRequestError, OverlayRequestJsonError},
+    },
+};
 use serde_json::{json, Value};
 use tokio::sync::mpsc;
-use tracing::error;
+use tracing::{debug, error, info_span, Instrument};
+use trin_metrics::portalnet::subnetwork_metrics_text;
 use trin_storage::ContentStore;
 
-use crate::network::BeaconNetwork;
+use crate::{network::BeaconNetwork, prefetch::PrefetchOutcome};
+
+/// How long the peer gets to store the content of an OfferAndVerify request, before it's asked for
+/// the content.
+const OFFER_VERIFY_DELAY: Duration = Duration::from_millis(500);
+
+/// The max duration of the FindContent request that verifies the content of an OfferAndVerify
+/// request.
+const OFFER_VERIFY_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Handles Beacon network JSON-RPC requests
 pub struct BeaconRequestHandler {
@@ -32,7 +57,8 @@ impl BeaconRequestHandler {
     pub async fn handle_client_queries(mut self) {
         while let Some(request) = self.rpc_rx.recv().await {
             let network = self.network.clone();
-            tokio::spawn(async move { complete_request(network, request).await });
+            let span = info_span!("rpc_request", request_id = %request.request_id);
+            tokio::spawn(complete_request(network, request).instrument(span));
         }
     }
 }
@@ -40,28 +66,43 @@ impl BeaconRequestHandler {
 /// Generates a response for a given request and sends it to the receiver.
 async fn complete_request(network: Arc<BeaconNetwork>, request: BeaconJsonRpcRequest) {
     let response: Result<Value, String> = match request.endpoint {
-        BeaconEndpoint::LocalContent(content_key) => local_content(network, content_key).await,
+        BeaconEndpoint::LocalContent(content_key, include_meta) => {
+            local_content(network, content_key, include_meta).await
+        }
         BeaconEndpoint::PaginateLocalContentKeys(offset, limit) => {
             paginate_local_content_keys(network, offset, limit).await
         }
-        BeaconEndpoint::Store(content_key, content_value) => {
-            store(network, content_key, content_value).await
+        BeaconEndpoint::Store(content_key, content_value, signature) => {
+            store(network, content_key, content_value, signature).await
         }
         BeaconEndpoint::GetContent(content_key) => get_content(network, content_key, false).await,
         BeaconEndpoint::TraceGetContent(content_key) => {
             get_content(network, content_key, true).await
         }
-        BeaconEndpoint::AddEnr(enr) => add_enr(network, enr).await,
+        BeaconEndpoint::GetContentOrClosest(content_key, timeout) => {
+            get_content_or_closest(network, content_key, timeout).await
+        }
+        BeaconEndpoint::AddEnr(enr, allow_unreachable) => {
+            add_enr(network, enr, allow_unreachable).await
+        }
         BeaconEndpoint::DataRadius => {
             let radius = network.overlay.data_radius();
-            Ok(json!(*radius))
+            // Serialized as a 32-byte hex string, like the data radius of a pong.
+            Ok(json!(B256::from(*radius)))
         }
         BeaconEndpoint::DeleteEnr(node_id) => delete_enr(network, node_id).await,
         BeaconEndpoint::FindContent(enr, content_key) => {
             find_content(network, enr, content_key).await
         }
-        BeaconEndpoint::FindNodes(enr, distances) => find_nodes(network, enr, distances).await,
+        BeaconEndpoint::FetchAndStore(enr, content_key, ignore_radius) => {
+            fetch_and_store(network, enr, content_key, ignore_radius).await
+        }
+        BeaconEndpoint::FindNodes(enr, distances, max_results) => {
+            find_nodes(network, enr, distances, max_results).await
+        }
         BeaconEndpoint::GetEnr(node_id) => get_enr(network, node_id).await,
+        BeaconEndpoint::GetEnrInfo(node_id) => get_enr_info(network, node_id).await,
+        BeaconEndpoint::GetEnrField(node_id, key) => get_enr_field(network, node_id, key).await,
         BeaconEndpoint::Gossip(content_key, content_value) => {
             gossip(network, content_key, content_value, false).await
         }
@@ -69,17 +110,100 @@ async fn complete_request(network: Arc<BeaconNetwork>, request: BeaconJsonRpcReq
             gossip(network, content_key, content_value, true).await
         }
         BeaconEndpoint::LightClientStore => light_client_store(&network).await,
+        BeaconEndpoint::HeaderUpdateTimes => header_update_times(&network).await,
         BeaconEndpoint::LookupEnr(node_id) => lookup_enr(network, node_id).await,
         BeaconEndpoint::Offer(enr, content_items) => offer(network, enr, content_items).await,
+        BeaconEndpoint::OfferMany(enr, content_items) => {
+            offer_many(network, enr, content_items).await
+        }
         BeaconEndpoint::TraceOffer(enr, content_key, content_value) => {
             trace_offer(network, enr, content_key, content_value).await
         }
+        BeaconEndpoint::OfferAndVerify(enr, content_key, content_value) => {
+            offer_and_verify(network, enr, content_key, content_value).await
+        }
         BeaconEndpoint::Ping(enr) => ping(network, enr).await,
+        BeaconEndpoint::PingRaw(enr) => ping_raw(network, enr).await,
+        BeaconEndpoint::TalkReq(enr, protocol_id, payload) => {
+            talk_req(network, enr, protocol_id, payload).await
+        }
         BeaconEndpoint::RoutingTableInfo => {
             serde_json::to_value(network.overlay.routing_table_info())
                 .map_err(|err| err.to_string())
         }
+        BeaconEndpoint::ReplacementCache => {
+            serde_json::to_value(network.overlay.replacement_cache_info())
+                .map_err(|err| err.to_string())
+        }
+        BeaconEndpoint::KeyspaceDensity => Ok(json!(network.overlay.keyspace_density())),
+        BeaconEndpoint::BucketRefreshTimes => Ok(json!(network.overlay.bucket_refresh_times())),
         BeaconEndpoint::RecursiveFindNodes(node_id) => recursive_find_nodes(network, node_id).await,
+        BeaconEndpoint::VerifyPeers(limit, evict) => Ok(json!(
+            network.overlay.verify_peers(limit as usize, evict).await
+        )),
+        BeaconEndpoint::RecentOfferers(limit) => {
+            Ok(json!(network.overlay.recent_offerers(limit as usize)))
+        }
+        BeaconEndpoint::SetBandwidthLimit(bytes_per_sec) => {
+            set_bandwidth_limit(network, bytes_per_sec).await
+        }
+        BeaconEndpoint::GetUtpLimit => Ok(json!(utp_limit_info(&network))),
+        BeaconEndpoint::SetUtpLimit(limit) => {
+            network
+                .overlay
+                .set_utp_concurrent_transfer_limit(limit as usize);
+            Ok(json!(utp_limit_info(&network)))
+        }
+        BeaconEndpoint::CloseTransfer(node_id, connection_id) => {
+            close_transfer(network, node_id, connection_id).await
+        }
+        BeaconEndpoint::StorageInfo => storage_info(network).await,
+        BeaconEndpoint::ContentTypeBreakdown => content_type_breakdown(network).await,
+        BeaconEndpoint::StorageBackendInfo(redact_path) => {
+            storage_backend_info(network, redact_path).await
+        }
+        BeaconEndpoint::PruneEstimate(content_key) => prune_estimate(network, content_key).await,
+        BeaconEndpoint::StoreLatencyStats => {
+            Ok(json!(network.overlay.store.read().latency_stats()))
+        }
+        BeaconEndpoint::RecomputeRadius => recompute_radius(network).await,
+        BeaconEndpoint::AnalyzeTrace(trace) => {
+            Ok(json!(network.overlay.analyze_trace(&trace).await))
+        }
+        BeaconEndpoint::Prefetch(content_keys) => prefetch(network, content_keys).await,
+        BeaconEndpoint::PrefetchStatus(job_id) => prefetch_status(network, job_id).await,
+        BeaconEndpoint::CancelPrefetch(job_id) => cancel_prefetch(network, job_id).await,
+        BeaconEndpoint::AcceptSnapshot => Ok(json!(network.overlay.accept_snapshot())),
+        BeaconEndpoint::SelfTest => Ok(json!(network.overlay.store.write().self_test())),
+        BeaconEndpoint::SetValidationMode(mode) => Ok(json!(network.set_validation_mode(mode))),
+        BeaconEndpoint::NearestStoredKeys(content_id, limit) => {
+            nearest_stored_keys(network, content_id, limit).await
+        }
+        BeaconEndpoint::RangeCoverage(start_id, end_id) => {
+            range_coverage(network, start_id, end_id).await
+        }
+        BeaconEndpoint::GcOrphans => gc_orphans(network).await,
+        BeaconEndpoint::PinContent(content_key) => pin_content(network, content_key).await,
+        BeaconEndpoint::UnpinContent(content_key) => unpin_content(network, content_key).await,
+        BeaconEndpoint::MetricsText => metrics_text(),
+        BeaconEndpoint::ClearLookupCache => Ok(json!(network.overlay.clear_lookup_cache())),
+        BeaconEndpoint::ActiveLookups => active_lookups(network).await,
+        BeaconEndpoint::ValidateContentBatch(content_items) => {
+            Ok(json!(network.validate_content_batch(content_items).await))
+        }
+        BeaconEndpoint::RevalidateDetailed(content_key) => {
+            revalidate_detailed(network, content_key).await
+        }
+        BeaconEndpoint::InspectContent(content_key, content_value) => Ok(json!(inspect_content(
+            &content_key,
+            content_value,
+            &network.chain_config
+        ))),
+        BeaconEndpoint::NetworkInfo => network_info(network),
+        BeaconEndpoint::SetGossipEnabled(enabled) => {
+            network.overlay.set_gossip_enabled(enabled);
+            Ok(json!(network.overlay.is_gossip_enabled()))
+        }
         BeaconEndpoint::OptimisticStateRoot => {
             let beacon_client = network.beacon_client.lock().await;
             match beacon_client.as_ref() {
@@ -106,6 +230,7 @@ async fn complete_request(network: Arc<BeaconNetwork>, request: BeaconJsonRpcReq
                 None => Err("Beacon client not initialized".to_string()),
             }
         }
+        BeaconEndpoint::HistoricalSummaries => historical_summaries(network).await,
         BeaconEndpoint::FinalizedHeader => {
             let beacon_client = network.beacon_client.lock().await;
             match beacon_client.as_ref() {
@@ -146,6 +271,9 @@ async fn complete_request(network: Arc<BeaconNetwork>, request: BeaconJsonRpcReq
             }
         }
     };
+    if let Err(err) = &response {
+        debug!(error = %err, "RPC request failed");
+    }
     let _ = request.resp.send(response);
 }
 
@@ -199,10 +327,13 @@ async fn get_content(
                     message,
                     utp,
                     trace,
+                    cached_at_ms,
+                    ..
                 } => {
                     let err = json!({
                         "message": format!("{message}: utp: {utp}"),
-                        "trace": trace
+                        "trace": trace,
+                        "cachedAtMs": cached_at_ms,
                     });
                     return Err(err.to_string());
                 }
@@ -239,14 +370,105 @@ async fn get_content(
     }
 }
 
+/// Constructs a JSON call for the GetContentOrClosest method.
+///
+/// Unlike GetContent, failing to find the content isn't an error. Instead, the closest peers to
+/// the content that were discovered before the lookup was exhausted or timed out are returned.
+async fn get_content_or_closest(
+    network: Arc<BeaconNetwork>,
+    content_key: BeaconContentKey,
+    timeout: Option<Duration>,
+) -> Result<Value, String> {
+    let local_enr = network.overlay.local_enr();
+    let content_id = content_key.content_id();
+    match network.overlay.store.read().get(&content_key) {
+        Ok(Some(content)) => {
+            let mut trace = QueryTrace::new(&local_enr, content_id.into());
+            trace.node_responded_with_content(&local_enr);
+            trace.content_validated(local_enr.into());
+            return Ok(json!(ContentOrClosestInfo {
+                content: Some(content),
+                utp_transfer: false,
+                closest_enrs: vec![],
+                trace,
+            }));
+        }
+        Ok(None) => {}
+        Err(err) => {
+            error!(
+                error = %err,
+                content.key = %content_key,
+                "Error checking data store for content",
+            );
+        }
+    }
+
+    let result = network
+        .overlay
+        .lookup_content(
+            content_key.clone(),
+            FindContentConfig {
+                is_trace: true,
+                timeout,
+            },
+        )
+        .await
+        .map_err(|err| err.to_string())?;
+    let info = match result {
+        Ok((content, utp_transfer, trace)) => ContentOrClosestInfo {
+            content: Some(content),
+            utp_transfer,
+            closest_enrs: vec![],
+            trace: trace.ok_or("Content query trace requested but none provided.")?,
+        },
+        Err(OverlayRequestError::ContentNotFound {
+            utp,
+            trace,
+            closest_enrs,
+            ..
+        }) => ContentOrClosestInfo {
+            content: None,
+            utp_transfer: utp,
+            closest_enrs,
+            // The query doesn't start if there are no peers in the routing table, in which case
+            // there is no trace either.
+            trace: trace.unwrap_or_else(|| QueryTrace::new(&local_enr, content_id.into())),
+        },
+        Err(err) => {
+            error!(
+                error = %err,
+                content.key = %content_key,
+                "Error looking up content",
+            );
+            return Err(err.to_string());
+        }
+    };
+    Ok(json!(info))
+}
+
 /// Constructs a JSON call for the LocalContent method.
 async fn local_content(
     network: Arc<BeaconNetwork>,
     content_key: BeaconContentKey,
+    include_meta: bool,
 ) -> Result<Value, String> {
-    let response = match network.overlay.store.read().get(&content_key)
+    let store = network.overlay.store.read();
+    let response = match store.get(&content_key)
         {
             Ok(val) => match val {
+                Some(val) if include_meta => {
+                    let trusted = store.is_trusted(&content_key).map_err(|err| format!(
+                        "Database error while looking for metadata of content key in local storage: {content_key:?}, with error: {err}",
+                    ))?;
+                    Ok(json!(LocalContentInfo::WithMeta {
+                        content: val,
+                        meta: LocalContentMeta {
+                            trusted,
+                            ..LocalContentMeta::unknown()
+                        },
+                        distance_rank: None,
+                    }))
+                }
                 Some(val) => {
                     Ok(Value::String(hex_encode(val)))
                 }
@@ -259,6 +481,58 @@ async fn local_content(
     response
 }
 
+/// Constructs a JSON call for the NetworkInfo method.
+fn network_info(network: Arc<BeaconNetwork>) -> Result<Value, String> {
+    let subnetwork = network.overlay.protocol();
+    let portal_network = network.overlay.discovery.network();
+    let protocol_id = NetworkSpec::from_network(portal_network)
+        .get_protocol_identifier_from_subnetwork(subnetwork)
+        .map_err(|err| err.to_string())?;
+    Ok(json!(OverlayNetworkInfo {
+        network: portal_network.to_string(),
+        subnetwork: subnetwork.to_cli_arg(),
+        protocol_id,
+    }))
+}
+
+/// Decodes the content value, without validating or storing it.
+fn inspect_content(
+    content_key: &BeaconContentKey,
+    content_value: RawContentValue,
+    chain_config: &ChainConfig,
+) -> ContentInspectionInfo {
+    let decoded = BeaconContentValue::decode_for_chain(content_key, &content_value, chain_config);
+    let (decoded, error) = match decoded {
+        Ok(decoded) => (Some(decoded.to_json()), None),
+        Err(err) => (None, Some(format!("Unable to decode content value: {err}"))),
+    };
+    ContentInspectionInfo {
+        raw: content_value,
+        decoded,
+        content_id: B256::from(content_key.content_id()),
+        error,
+    }
+}
+
+/// Constructs a JSON call for the RevalidateDetailed method.
+async fn revalidate_detailed(
+    network: Arc<BeaconNetwork>,
+    content_key: BeaconContentKey,
+) -> Result<Value, String> {
+    let content = match network.overlay.store.read().get(&content_key) {
+        Ok(Some(content)) => content,
+        Ok(None) => return Err("Content not found in local storage".to_string()),
+        Err(err) => {
+            return Err(format!(
+                "Database error while looking for content key in local storage: {content_key:?}, with error: {err}",
+            ))
+        }
+    };
+    Ok(json!(
+        network.validation_report(&content_key, &content).await
+    ))
+}
+
 /// Constructs a JSON call for the PaginateLocalContentKeys method.
 async fn paginate_local_content_keys(
     network: Arc<BeaconNetwork>,
@@ -275,20 +549,178 @@ async fn paginate_local_content_keys(
     response
 }
 
-/// Constructs a JSON call for the Store method.
-async fn store(
+/// Constructs a JSON call for the StorageInfo method.
+async fn storage_info(network: Arc<BeaconNetwork>) -> Result<Value, String> {
+    match network.overlay.store.read().storage_info() {
+        Ok(info) => Ok(json!(info)),
+        Err(err) => Err(format!("Database error while reading storage info: {err}")),
+    }
+}
+
+/// Constructs a JSON call for the ContentTypeBreakdown method.
+async fn content_type_breakdown(network: Arc<BeaconNetwork>) -> Result<Value, String> {
+    match network.overlay.store.read().content_type_counts() {
+        Ok(counts) => Ok(json!(counts)),
+        Err(err) => Err(format!(
+            "Database error while counting content types: {err}"
+        )),
+    }
+}
+
+/// Constructs a JSON call for the HistoricalSummaries method.
+async fn historical_summaries(network: Arc<BeaconNetwork>) -> Result<Value, String> {
+    match network.overlay.store.read().latest_historical_summaries() {
+        Ok(Some(historical_summaries)) => Ok(json!(historical_summaries)),
+        Ok(None) => Err("No historical summaries stored".to_string()),
+        Err(err) => Err(format!(
+            "Database error while looking up historical summaries: {err}"
+        )),
+    }
+}
+
+/// Constructs a JSON call for the StorageBackendInfo method.
+async fn storage_backend_info(
+    network: Arc<BeaconNetwork>,
+    redact_path: bool,
+) -> Result<Value, String> {
+    match network
+        .overlay
+        .store
+        .read()
+        .storage_backend_info(redact_path)
+    {
+        Ok(info) => Ok(json!(info)),
+        Err(err) => Err(format!(
+            "Database error while reading storage backend info: {err}"
+        )),
+    }
+}
+
+/// Constructs a JSON call for the PruneEstimate method.
+async fn prune_estimate(
     network: Arc<BeaconNetwork>,
     content_key: BeaconContentKey,
-    content_value: BeaconContentValue,
 ) -> Result<Value, String> {
-    let data = content_value.encode().to_vec();
-    let response = match network
+    match network.overlay.store.read().prune_estimate(&content_key) {
+        Ok(info) => Ok(json!(info)),
+        Err(err) => Err(format!(
+            "Database error while estimating prune time for content key {content_key}: {err}"
+        )),
+    }
+}
+
+/// Constructs a JSON call for the NearestStoredKeys method.
+async fn nearest_stored_keys(
+    network: Arc<BeaconNetwork>,
+    content_id: B256,
+    limit: u64,
+) -> Result<Value, String> {
+    match network
         .overlay
         .store
-        .write()
-        .put::<Vec<u8>>(content_key, data)
+        .read()
+        .nearest_stored_keys(&content_id.0, limit as usize)
+    {
+        Ok(keys) => Ok(json!(keys)),
+        Err(err) => Err(format!(
+            "Database error while finding the stored keys nearest to {content_id}: {err}"
+        )),
+    }
+}
+
+/// Constructs a JSON call for the RangeCoverage method.
+async fn range_coverage(
+    network: Arc<BeaconNetwork>,
+    start_id: B256,
+    end_id: B256,
+) -> Result<Value, String> {
+    match network
+        .overlay
+        .store
+        .read()
+        .range_coverage(&start_id.0, &end_id.0)
     {
-        Ok(_) => Ok(Value::Bool(true)),
+        Ok(coverage) => Ok(json!(coverage)),
+        Err(err) => Err(format!(
+            "Database error while computing the coverage of range {start_id}..={end_id}: {err}"
+        )),
+    }
+}
+
+/// Constructs a JSON call for the GcOrphans method.
+async fn gc_orphans(network: Arc<BeaconNetwork>) -> Result<Value, String> {
+    // The write lock keeps content from being stored while the orphans are removed.
+    match network.overlay.store.write().gc_orphans() {
+        Ok(info) => Ok(json!(info)),
+        Err(err) => Err(format!(
+            "Database error while removing orphaned content blobs: {err}"
+        )),
+    }
+}
+
+/// Constructs a JSON call for the PinContent method.
+async fn pin_content(
+    network: Arc<BeaconNetwork>,
+    content_key: BeaconContentKey,
+) -> Result<Value, String> {
+    match network.overlay.store.write().pin(content_key.clone()) {
+        Ok(info) => Ok(json!(info)),
+        Err(err) => Err(format!("Failed to pin content {content_key}: {err}")),
+    }
+}
+
+/// Constructs a JSON call for the UnpinContent method.
+async fn unpin_content(
+    network: Arc<BeaconNetwork>,
+    content_key: BeaconContentKey,
+) -> Result<Value, String> {
+    match network.overlay.store.write().unpin(&content_key) {
+        Ok(info) => Ok(json!(info)),
+        Err(err) => Err(format!("Failed to unpin content {content_key}: {err}")),
+    }
+}
+
+/// Constructs a JSON call for the RecomputeRadius method.
+async fn recompute_radius(network: Arc<BeaconNetwork>) -> Result<Value, String> {
+    match network.overlay.store.write().recompute_radius() {
+        Ok(radius) => Ok(json!(B256::from(*radius))),
+        Err(err) => Err(format!("Database error while recomputing radius: {err}")),
+    }
+}
+
+/// Constructs a JSON call for the Store method.
+async fn store(
+    network: Arc<BeaconNetwork>,
+    content_key: BeaconContentKey,
+    content_value: BeaconContentValue,
+    signature: Option<Bytes>,
+) -> Result<Value, String> {
+    content_value
+        .ensure_valid_for_key(&content_key)
+        .map_err(|err| err.to_json_string())?;
+    let data = content_value
+        .encode_for_chain(&network.chain_config)
+        .to_vec();
+    // Content with a signature that doesn't verify isn't stored at all.
+    if let Some(signature) = &signature {
+        verify_content_signature(
+            &network.trusted_content_signers,
+            &content_key.to_bytes(),
+            &data,
+            signature,
+        )?;
+    }
+    let mut store = network.overlay.store.write();
+    let response = match store.put(content_key.clone(), &data) {
+        Ok(_) => {
+            if signature.is_some() {
+                store.mark_trusted(&content_key, &data).map_err(|err| {
+                    format!("Failed to mark content {content_key} as trusted: {err}")
+                })?;
+            }
+            network.overlay.invalidate_failed_lookup(&content_key);
+            Ok(Value::Bool(true))
+        }
         Err(msg) => Ok(Value::String(msg.to_string())),
     };
     response
@@ -298,8 +730,9 @@ async fn store(
 async fn add_enr(
     network: Arc<BeaconNetwork>,
     enr: discv5::enr::Enr<discv5::enr::CombinedKey>,
+    allow_unreachable: bool,
 ) -> Result<Value, String> {
-    match network.overlay.add_enr(enr) {
+    match network.overlay.add_enr(enr, allow_unreachable) {
         Ok(_) => Ok(json!(true)),
         Err(err) => Err(format!("AddEnr failed: {err:?}")),
     }
@@ -313,6 +746,30 @@ async fn get_enr(network: Arc<BeaconNetwork>, node_id: NodeId) -> Result<Value,
     }
 }
 
+/// Constructs a JSON call for the GetEnrInfo method.
+async fn get_enr_info(network: Arc<BeaconNetwork>, node_id: NodeId) -> Result<Value, String> {
+    match network.overlay.get_enr_with_source(node_id) {
+        Ok((enr, source)) => Ok(json!(GetEnrInfo {
+            enr,
+            source,
+            multiaddrs: None,
+        })),
+        Err(err) => Err(format!("GetEnrInfo failed: {err:?}")),
+    }
+}
+
+/// Constructs a JSON call for the GetEnrField method.
+async fn get_enr_field(
+    network: Arc<BeaconNetwork>,
+    node_id: NodeId,
+    key: String,
+) -> Result<Value, String> {
+    match network.overlay.get_enr_with_source(node_id) {
+        Ok((enr, _)) => Ok(json!(decode_enr_field(&enr, &key))),
+        Err(err) => Err(format!("GetEnrField failed: {err:?}")),
+    }
+}
+
 /// Constructs a JSON call for the deleteEnr method.
 async fn delete_enr(network: Arc<BeaconNetwork>, node_id: NodeId) -> Result<Value, String> {
     let is_deleted = network.overlay.delete_enr(node_id);
@@ -346,7 +803,24 @@ async fn find_content(
                 "enrs": enrs,
             })),
         },
-        Err(msg) => Err(format!("FindContent request timeout: {msg:?}")),
+        Err(err) => Err(json!(OverlayRequestJsonError::new("FindContent", &err)).to_string()),
+    }
+}
+
+/// Constructs a JSON call for the FetchAndStore method.
+async fn fetch_and_store(
+    network: Arc<BeaconNetwork>,
+    enr: discv5::enr::Enr<discv5::enr::CombinedKey>,
+    content_key: BeaconContentKey,
+    ignore_radius: bool,
+) -> Result<Value, String> {
+    match network
+        .overlay
+        .fetch_and_store(enr, content_key, ignore_radius)
+        .await
+    {
+        Ok(info) => Ok(json!(info)),
+        Err(err) => Err(json!(OverlayRequestJsonError::new("FetchAndStore", &err)).to_string()),
     }
 }
 
@@ -355,14 +829,16 @@ async fn find_nodes(
     network: Arc<BeaconNetwork>,
     enr: discv5::enr::Enr<discv5::enr::CombinedKey>,
     distances: Vec<u16>,
+    max_results: Option<u64>,
 ) -> Result<Value, String> {
     match network.overlay.send_find_nodes(enr, distances).await {
         Ok(nodes) => Ok(json!(nodes
             .enrs
             .into_iter()
+            .take(max_results.map_or(usize::MAX, |max_results| max_results as usize))
             .map(|enr| enr.into())
             .collect::<FindNodesInfo>())),
-        Err(msg) => Err(format!("FindNodes request timeout: {msg:?}")),
+        Err(err) => Err(json!(OverlayRequestJsonError::new("FindNodes", &err)).to_string()),
     }
 }
 
@@ -373,7 +849,13 @@ async fn gossip(
     content_value: BeaconContentValue,
     is_trace: bool,
 ) -> Result<Value, String> {
-    let data = content_value.encode();
+    content_value
+        .ensure_valid_for_key(&content_key)
+        .map_err(|err| err.to_json_string())?;
+    if !network.overlay.is_gossip_enabled() {
+        return Err("Gossip disabled".to_string());
+    }
+    let data = content_value.encode_for_chain(&network.chain_config);
     match is_trace {
         true => Ok(json!(
             network
@@ -384,6 +866,7 @@ async fn gossip(
         false => Ok(network
             .overlay
             .propagate_gossip(vec![(content_key, data)])
+            .offered
             .into()),
     }
 }
@@ -394,15 +877,57 @@ async fn offer(
     enr: discv5::enr::Enr<discv5::enr::CombinedKey>,
     content_items: Vec<(BeaconContentKey, BeaconContentValue)>,
 ) -> Result<Value, String> {
+    for (content_key, content_value) in &content_items {
+        content_value
+            .ensure_valid_for_key(content_key)
+            .map_err(|err| err.to_json_string())?;
+    }
     let content_items = content_items
         .into_iter()
-        .map(|(key, value)| (key.to_bytes(), value.encode()))
+        .map(|(key, value)| {
+            let value = value.encode_for_chain(&network.chain_config);
+            (key.to_bytes(), value)
+        })
         .collect();
+    match network
+        .overlay
+        .send_offer_with_accept_bytes(enr, content_items)
+        .await
+    {
+        Ok((accept, accept_bytes)) => Ok(json!(
+            AcceptInfo::new(accept.content_keys).with_accept_bitfield(accept_bytes)
+        )),
+        Err(err) => Err(json!(OverlayRequestJsonError::new("Offer", &err)).to_string()),
+    }
+}
+
+/// Constructs a JSON call for the OfferMany method.
+async fn offer_many(
+    network: Arc<BeaconNetwork>,
+    enr: discv5::enr::Enr<discv5::enr::CombinedKey>,
+    content_items: Vec<(BeaconContentKey, BeaconContentValue)>,
+) -> Result<Value, String> {
+    for (content_key, content_value) in &content_items {
+        content_value
+            .ensure_valid_for_key(content_key)
+            .map_err(|err| err.to_json_string())?;
+    }
+    let (content_keys, content_items): (Vec<_>, Vec<_>) = content_items
+        .into_iter()
+        .map(|(key, value)| {
+            let content_item = (
+                key.to_bytes(),
+                value.encode_for_chain(&network.chain_config),
+            );
+            (key, content_item)
+        })
+        .unzip();
     match network.overlay.send_offer(enr, content_items).await {
-        Ok(accept) => Ok(json!(AcceptInfo {
-            content_keys: accept.content_keys,
-        })),
-        Err(msg) => Err(format!("Offer request timeout: {msg:?}")),
+        Ok(accept) => Ok(json!(OfferManyInfo::new(
+            content_keys,
+            &accept.content_keys
+        ))),
+        Err(err) => Err(json!(OverlayRequestJsonError::new("OfferMany", &err)).to_string()),
     }
 }
 
@@ -413,27 +938,121 @@ async fn trace_offer(
     content_key: BeaconContentKey,
     content_value: BeaconContentValue,
 ) -> Result<Value, String> {
+    content_value
+        .ensure_valid_for_key(&content_key)
+        .map_err(|err| err.to_json_string())?;
     match network
         .overlay
-        .send_offer_trace(enr, content_key.to_bytes(), content_value.encode())
+        .send_offer_trace(
+            enr,
+            content_key.to_bytes(),
+            content_value.encode_for_chain(&network.chain_config),
+        )
         .await
     {
         Ok(accept) => Ok(json!(accept)),
-        Err(msg) => Err(format!("Offer request timeout: {msg:?}")),
+        Err(err) => Err(json!(OverlayRequestJsonError::new("Offer", &err)).to_string()),
     }
 }
 
+/// Constructs a JSON call for the OfferAndVerify method.
+async fn offer_and_verify(
+    network: Arc<BeaconNetwork>,
+    enr: discv5::enr::Enr<discv5::enr::CombinedKey>,
+    content_key: BeaconContentKey,
+    content_value: BeaconContentValue,
+) -> Result<Value, String> {
+    content_value
+        .ensure_valid_for_key(&content_key)
+        .map_err(|err| err.to_json_string())?;
+    let content_key = content_key.to_bytes();
+    let content_value = content_value.encode_for_chain(&network.chain_config);
+    let accepted = match network
+        .overlay
+        .send_offer(
+            enr.clone(),
+            vec![(content_key.clone(), content_value.clone())],
+        )
+        .await
+    {
+        Ok(accept) => accept.content_keys.get(0).unwrap_or(false),
+        Err(err) => {
+            return Err(json!(OverlayRequestJsonError::new("OfferAndVerify", &err)).to_string())
+        }
+    };
+
+    // The content is verified even if the peer declined it, as the peer may have it already. The
+    // peer gets some time to receive, validate and store the accepted content first.
+    tokio::time::sleep(OFFER_VERIFY_DELAY).await;
+    // Whether the peer serves the same bytes is reported separately from whether they pass local
+    // validation, so that content that the local node can't validate can still be verified.
+    let (verified, valid) = match tokio::time::timeout(
+        OFFER_VERIFY_TIMEOUT,
+        network
+            .overlay
+            .send_find_content_with_validity(enr, content_key),
+    )
+    .await
+    {
+        Ok(Ok((Content::Content(content), _, valid))) => (content == content_value, valid),
+        Ok(Ok(_)) => (false, None),
+        Ok(Err(err)) => {
+            debug!(%err, "Failed to verify the offered content");
+            (false, None)
+        }
+        Err(_) => {
+            debug!("Timed out verifying the offered content");
+            (false, None)
+        }
+    };
+    Ok(json!(OfferAndVerifyInfo {
+        accepted,
+        verified,
+        valid,
+    }))
+}
+
 /// Constructs a JSON call for the Ping method.
 async fn ping(
     network: Arc<BeaconNetwork>,
     enr: discv5::enr::Enr<discv5::enr::CombinedKey>,
 ) -> Result<Value, String> {
-    match network.overlay.send_ping(enr).await {
-        Ok(pong) => Ok(json!(PongInfo {
-            enr_seq: pong.enr_seq,
-            data_radius: *Distance::from(pong.custom_payload),
-        })),
-        Err(msg) => Err(format!("Ping request timeout: {msg:?}")),
+    match network.overlay.send_ping_info(enr).await {
+        Ok(pong_info) => Ok(json!(pong_info)),
+        Err(err) => Err(json!(OverlayRequestJsonError::new("Ping", &err)).to_string()),
+    }
+}
+
+/// Constructs a JSON call for the PingRaw method.
+async fn ping_raw(
+    network: Arc<BeaconNetwork>,
+    enr: discv5::enr::Enr<discv5::enr::CombinedKey>,
+) -> Result<Value, String> {
+    match network.overlay.send_ping_raw(enr).await {
+        Ok(ping_raw_info) => Ok(json!(ping_raw_info)),
+        Err(err) => Err(json!(OverlayRequestJsonError::new("PingRaw", &err)).to_string()),
+    }
+}
+
+/// Constructs a JSON call for the TalkReq method.
+async fn talk_req(
+    network: Arc<BeaconNetwork>,
+    enr: discv5::enr::Enr<discv5::enr::CombinedKey>,
+    protocol_id: Bytes,
+    payload: Bytes,
+) -> Result<Value, String> {
+    match network
+        .overlay
+        .discovery
+        .send_raw_talk_req(enr, protocol_id.to_vec(), payload.to_vec())
+        .await
+    {
+        Ok(response) => Ok(json!(hex_encode(response))),
+        Err(err) => Err(json!(OverlayRequestJsonError::new(
+            "TalkReq",
+            &OverlayRequestError::from(err)
+        ))
+        .to_string()),
     }
 }
 
@@ -446,6 +1065,140 @@ async fn recursive_find_nodes(
     Ok(json!(nodes))
 }
 
+/// Constructs a JSON call for the SetBandwidthLimit method.
+async fn set_bandwidth_limit(
+    network: Arc<BeaconNetwork>,
+    bytes_per_sec: Option<u64>,
+) -> Result<Value, String> {
+    network.overlay.set_utp_bandwidth_limit(bytes_per_sec);
+    Ok(json!(BandwidthLimitInfo {
+        bytes_per_sec: network.overlay.utp_bandwidth_limit(),
+    }))
+}
+
+/// Returns the uTP transfer limit and the number of running transfers.
+fn utp_limit_info(network: &BeaconNetwork) -> UtpLimitInfo {
+    UtpLimitInfo {
+        limit: network.overlay.utp_concurrent_transfer_limit() as u32,
+        active: network.overlay.active_utp_transfers() as u32,
+    }
+}
+
+/// Constructs a JSON call for the CloseTransfer method.
+async fn close_transfer(
+    network: Arc<BeaconNetwork>,
+    node_id: NodeId,
+    connection_id: u16,
+) -> Result<Value, String> {
+    if !network.overlay.close_utp_transfer(node_id, connection_id) {
+        return Err(format!(
+            "No uTP transfer in progress with peer {node_id} over connection {connection_id}"
+        ));
+    }
+    Ok(json!(true))
+}
+
+/// Constructs a JSON call for the Prefetch method.
+async fn prefetch(
+    network: Arc<BeaconNetwork>,
+    content_keys: Vec<BeaconContentKey>,
+) -> Result<Value, String> {
+    let prefetch_network = Arc::clone(&network);
+    let job_id = network
+        .prefetch_jobs
+        .start(content_keys, move |content_key| {
+            prefetch_content(Arc::clone(&prefetch_network), content_key)
+        });
+    match job_id {
+        Some(job_id) => Ok(json!(job_id)),
+        None => Err("Too many prefetch jobs are running, try again later".to_string()),
+    }
+}
+
+/// Looks up the content on the network, unless it is already stored, and stores it locally.
+async fn prefetch_content(
+    network: Arc<BeaconNetwork>,
+    content_key: BeaconContentKey,
+) -> PrefetchOutcome {
+    let local_content = network.overlay.store.read().get(&content_key);
+    if let Ok(Some(_)) = local_content {
+        return PrefetchOutcome::Found;
+    }
+    let lookup = network
+        .overlay
+        .lookup_content(content_key.clone(), FindContentConfig::default())
+        .await;
+    let content = match lookup {
+        Ok(Ok((content, _, _))) => content,
+        Ok(Err(OverlayRequestError::ContentNotFound { .. })) => return PrefetchOutcome::NotFound,
+        Ok(Err(err)) | Err(err) => {
+            debug!(
+                error = %err,
+                content.key = %content_key,
+                "Error prefetching content",
+            );
+            return PrefetchOutcome::Failed;
+        }
+    };
+    // The lookup already stores the content if it is within our radius.
+    let local_content = network.overlay.store.read().get(&content_key);
+    if let Ok(Some(_)) = local_content {
+        return PrefetchOutcome::Found;
+    }
+    let stored = network
+        .overlay
+        .store
+        .write()
+        .put::<RawContentValue>(content_key.clone(), content);
+    match stored {
+        Ok(_) => {
+            network.overlay.invalidate_failed_lookup(&content_key);
+            PrefetchOutcome::Found
+        }
+        Err(err) => {
+            error!(
+                error = %err,
+                content.key = %content_key,
+                "Error storing prefetched content",
+            );
+            PrefetchOutcome::Failed
+        }
+    }
+}
+
+/// Constructs a JSON call for the PrefetchStatus method.
+async fn prefetch_status(network: Arc<BeaconNetwork>, job_id: u64) -> Result<Value, String> {
+    match network.prefetch_jobs.status(job_id) {
+        Some(status) => Ok(json!(status)),
+        None => Err(format!("Unknown prefetch job: {job_id}")),
+    }
+}
+
+/// Constructs a JSON call for the CancelPrefetch method.
+async fn cancel_prefetch(network: Arc<BeaconNetwork>, job_id: u64) -> Result<Value, String> {
+    match network.prefetch_jobs.cancel(job_id) {
+        Some(status) => Ok(json!(status)),
+        None => Err(format!("Unknown prefetch job: {job_id}")),
+    }
+}
+
+/// Constructs a JSON call for the MetricsText method.
+fn metrics_text() -> Result<Value, String> {
+    subnetwork_metrics_text(Subnetwork::Beacon)
+        .map(|text| json!(text))
+        .map_err(|err| format!("Failed to encode the beacon metrics: {err}"))
+}
+
+/// Constructs a JSON call for the ActiveLookups method.
+async fn active_lookups(network: Arc<BeaconNetwork>) -> Result<Value, String> {
+    network
+        .overlay
+        .active_lookups()
+        .await
+        .map(|lookups| json!(lookups))
+        .map_err(|err| format!("Failed to get the active lookups: {err}"))
+}
+
 /// Constructs a JSON call for the LightClientStore method.
 async fn light_client_store(network: &Arc<BeaconNetwork>) -> Result<Value, String> {
     let beacon_client = network.beacon_client.lock().await;
@@ -460,3 +1213,21 @@ async fn light_client_store(network: &Arc<BeaconNetwork>) -> Result<Value, Strin
         None => Err("Beacon client not initialized".to_string()),
     }
 }
+
+/// Constructs a JSON call for the HeaderUpdateTimes method.
+async fn header_update_times(network: &Arc<BeaconNetwork>) -> Result<Value, String> {
+    let beacon_client = network.beacon_client.lock().await;
+    match beacon_client.as_ref() {
+        Some(client) => {
+            let update_times = client.get_header_update_times().await;
+            let ms_ago = |updated_at: Option<Instant>| {
+                updated_at.map(|updated_at| updated_at.elapsed().as_millis() as u64)
+            };
+            Ok(json!(HeaderUpdateTimesInfo {
+                optimistic_updated_ms_ago: ms_ago(update_times.optimistic),
+                finalized_updated_ms_ago: ms_ago(update_times.finalized),
+            }))
+        }
+        None => Err("Beacon client not initialized".to_string()),
+    }
+}
@@ -4,6 +4,8 @@
 pub mod events;
 mod jsonrpc;
 pub mod network;
+mod prefetch;
+mod seed;
 mod storage;
 mod sync;
 #[cfg(test)]
@@ -31,7 +33,7 @@ use utp_rs::socket::UtpSocket;
 use crate::{events::BeaconEvents, jsonrpc::BeaconRequestHandler, network::BeaconNetwork};
 
 type BeaconHandler = Option<BeaconRequestHandler>;
-type BeaconNetworkTask = Option<JoinHandle<()>>;
+type BeaconNetworkTasks = Vec<JoinHandle<()>>;
 type BeaconMessageTx = Option<mpsc::UnboundedSender<OverlayRequest>>;
 type BeaconJsonRpcTx = Option<mpsc::UnboundedSender<BeaconJsonRpcRequest>>;
 type BeaconEventStream = Option<broadcast::Receiver<EventEnvelope>>;
@@ -44,7 +46,7 @@ pub async fn initialize_beacon_network(
     header_oracle: Arc<RwLock<HeaderOracle>>,
 ) -> anyhow::Result<(
     BeaconHandler,
-    BeaconNetworkTask,
+    BeaconNetworkTasks,
     BeaconMessageTx,
     BeaconJsonRpcTx,
     BeaconEventStream,
@@ -68,10 +70,10 @@ pub async fn initialize_beacon_network(
     };
     let beacon_network_task =
         spawn_beacon_network(beacon_network.clone(), portalnet_config, beacon_message_rx);
-    spawn_beacon_heartbeat(beacon_network);
+    let beacon_heartbeat_task = spawn_beacon_heartbeat(beacon_network);
     Ok((
         Some(beacon_handler),
-        Some(beacon_network_task),
+        vec![beacon_network_task, beacon_heartbeat_task],
         Some(beacon_message_tx),
         Some(beacon_jsonrpc_tx),
         Some(beacon_event_stream),
@@ -94,19 +96,15 @@ pub fn spawn_beacon_network(
             message_rx: beacon_message_rx,
         };
 
-        // Spawn beacon event handler
-        tokio::spawn(beacon_events.start());
-
-        // hacky test: make sure we establish a session with the boot node
-        network.overlay.ping_bootnodes().await;
-
-        tokio::signal::ctrl_c()
-            .await
-            .expect("failed to pause until ctrl-c");
+        // Run the beacon event handler in this task, so that it's stopped along with it.
+        tokio::join!(beacon_events.start(), async {
+            // hacky test: make sure we establish a session with the boot node
+            network.overlay.ping_bootnodes().await;
+        });
     })
 }
 
-pub fn spawn_beacon_heartbeat(network: Arc<BeaconNetwork>) {
+pub fn spawn_beacon_heartbeat(network: Arc<BeaconNetwork>) -> JoinHandle<()> {
     tokio::spawn(async move {
         let mut heart_interval = interval(Duration::from_millis(30000));
 
@@ -121,5 +119,5 @@ pub fn spawn_beacon_heartbeat(network: Arc<BeaconNetwork>) {
             info!("reports~ data: {storage_log}; msgs: {message_log}");
             info!("reports~ utp: {utp_log}");
         }
-    });
+    })
 }
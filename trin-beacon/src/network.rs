@@ -1,10 +1,20 @@
 use std::sync::Arc;
 
 use alloy::primitives::B256;
+use anyhow::anyhow;
 use ethportal_api::{
-    types::{distance::XorMetric, network::Subnetwork},
-    BeaconContentKey,
+    consensus::{chain_config::ChainConfig, light_client::bootstrap::LightClientBootstrap},
+    light_client::bootstrap::LightClientBootstrapDeneb,
+    types::{
+        content_value::beacon::ForkVersionedLightClientBootstrap,
+        distance::XorMetric,
+        network::Subnetwork,
+        portal::{ContentValidationInfo, ValidationMode, ValidationModeInfo, ValidationReportInfo},
+    },
+    utils::content_signature::PublicKey,
+    BeaconContentKey, BeaconContentValue, LightClientBootstrapKey, RawContentValue,
 };
+use futures::{stream, StreamExt};
 use light_client::{consensus::rpc::portal_rpc::PortalRpc, database::FileDB, Client};
 use parking_lot::RwLock as PLRwLock;
 use portalnet::{
@@ -13,12 +23,16 @@ use portalnet::{
     overlay::{config::OverlayConfig, protocol::OverlayProtocol},
 };
 use tokio::sync::{Mutex, RwLock};
-use tracing::{error, info};
-use trin_storage::PortalStorageConfig;
+use tracing::{error, info, warn};
+use trin_storage::{ContentStore, PortalStorageConfig};
 use trin_validation::oracle::HeaderOracle;
+use url::Url;
 use utp_rs::socket::UtpSocket;
 
-use crate::{storage::BeaconStorage, sync::BeaconSync, validation::BeaconValidator};
+use crate::{
+    prefetch::PrefetchJobs, seed::SeedProvider, storage::BeaconStorage, sync::BeaconSync,
+    validation::BeaconValidator,
+};
 
 /// Beacon network layer on top of the overlay protocol. Encapsulates beacon network specific data
 /// and logic.
@@ -26,12 +40,21 @@ use crate::{storage::BeaconStorage, sync::BeaconSync, validation::BeaconValidato
 pub struct BeaconNetwork {
     pub overlay: Arc<OverlayProtocol<BeaconContentKey, XorMetric, BeaconValidator, BeaconStorage>>,
     pub beacon_client: Arc<Mutex<Option<Client<FileDB, PortalRpc>>>>,
+    pub prefetch_jobs: Arc<PrefetchJobs>,
+    /// The beacon chain that the network serves content of
+    pub chain_config: Arc<ChainConfig>,
+    /// The public keys whose signatures are accepted for content stored over JSON-RPC.
+    pub trusted_content_signers: Vec<PublicKey>,
+    validator: Arc<BeaconValidator>,
 }
 
 /// Gossiping content as it gets dropped from local storage is disabled for the beacon network,
 /// since beacon nodes already store all the content they're interested in.
 const GOSSIP_DROPPED: bool = false;
 
+/// The max number of content items that are validated at once by a validation batch.
+const VALIDATION_BATCH_CONCURRENCY: usize = 16;
+
 impl BeaconNetwork {
     pub async fn new(
         discovery: Arc<Discovery>,
@@ -43,19 +66,29 @@ impl BeaconNetwork {
         let config = OverlayConfig {
             bootnode_enrs: portal_config.bootnodes,
             utp_transfer_limit: portal_config.utp_transfer_limit,
+            failed_lookup_cache: portal_config.failed_lookup_cache,
+            lookup_cache: portal_config.lookup_cache,
+            rebroadcast: portal_config.rebroadcast,
+            gossip: portal_config.beacon_gossip,
             gossip_dropped: GOSSIP_DROPPED,
             ..Default::default()
         };
-        let storage = Arc::new(PLRwLock::new(BeaconStorage::new(storage_config)?));
+        let storage = Arc::new(PLRwLock::new(BeaconStorage::new_for_chain(
+            storage_config,
+            portal_config.beacon_chain.clone(),
+        )?));
         let storage_clone = Arc::clone(&storage);
-        let validator = Arc::new(BeaconValidator::new(header_oracle));
+        let validator = Arc::new(BeaconValidator::new(
+            header_oracle,
+            portal_config.beacon_chain.clone(),
+        ));
         let overlay = OverlayProtocol::new(
             config,
             discovery,
             utp_socket,
             storage,
             Subnetwork::Beacon,
-            validator,
+            Arc::clone(&validator),
         )
         .await;
 
@@ -63,8 +96,26 @@ impl BeaconNetwork {
         let beacon_client = Arc::new(Mutex::new(None));
         let beacon_client_clone = Arc::clone(&beacon_client);
 
+        let mut trusted_block_root = portal_config.trusted_block_root;
+        if let Some(url) = portal_config.beacon_seed_from {
+            let seeded = seed_content(
+                url,
+                trusted_block_root,
+                &validator,
+                &storage_clone,
+                &portal_config.beacon_chain,
+            )
+            .await;
+            match seeded {
+                Ok(block_root) => trusted_block_root = Some(block_root),
+                Err(err) => {
+                    warn!(error = %err, "Failed to seed beacon content from the Beacon API provider.")
+                }
+            }
+        }
+
         // Spawn the beacon sync task.
-        let trusted_block_root: Option<B256> = match portal_config.trusted_block_root {
+        let trusted_block_root: Option<B256> = match trusted_block_root {
             Some(trusted_block_root) => Some(trusted_block_root),
             None => {
                 // If no trusted block root is provided, we check for the latest block root in the
@@ -78,9 +129,20 @@ impl BeaconNetwork {
         };
 
         if let Some(trusted_block_root) = trusted_block_root {
+            // Keep the bootstrap of the trusted block root even once the storage is full.
+            let trusted_bootstrap_key =
+                BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey {
+                    block_hash: trusted_block_root.0,
+                });
+            if let Err(err) = storage_clone.write().pin(trusted_bootstrap_key) {
+                warn!(error = %err, "Failed to pin the bootstrap of the trusted block root.");
+            }
+            let chain_config = portal_config.beacon_chain.clone();
+            let local_bootstrap =
+                lookup_local_bootstrap(&storage_clone, trusted_block_root, &chain_config)?;
             tokio::spawn(async move {
-                let beacon_sync = BeaconSync::new(overlay_tx);
-                let beacon_sync = beacon_sync.start(trusted_block_root).await;
+                let beacon_sync = BeaconSync::new(overlay_tx, chain_config);
+                let beacon_sync = beacon_sync.start(trusted_block_root, local_bootstrap).await;
                 match beacon_sync {
                     Ok(client) => {
                         let mut beacon_client = beacon_client_clone.lock().await;
@@ -96,6 +158,105 @@ impl BeaconNetwork {
         Ok(Self {
             overlay: Arc::new(overlay),
             beacon_client,
+            prefetch_jobs: Arc::new(PrefetchJobs::default()),
+            chain_config: portal_config.beacon_chain,
+            trusted_content_signers: portal_config.trusted_content_signers,
+            validator,
         })
     }
+
+    /// Sets how strictly the content accepted from other nodes is validated, and returns the
+    /// content recently accepted despite failing non-critical checks.
+    pub fn set_validation_mode(
+        &self,
+        mode: ValidationMode,
+    ) -> ValidationModeInfo<BeaconContentKey> {
+        self.validator.set_mode(mode);
+        ValidationModeInfo {
+            mode: self.validator.mode(),
+            tagged: self.validator.tagged(),
+        }
+    }
+
+    /// Validates the content items concurrently, without storing them, and returns the result of
+    /// each item in the order of the items.
+    pub async fn validate_content_batch(
+        &self,
+        content_items: Vec<(BeaconContentKey, RawContentValue)>,
+    ) -> Vec<ContentValidationInfo> {
+        stream::iter(content_items)
+            .map(|(content_key, content_value)| async move {
+                match self.validator.verify(&content_key, &content_value).await {
+                    Ok(()) => ContentValidationInfo {
+                        valid: true,
+                        error: None,
+                    },
+                    Err(err) => ContentValidationInfo {
+                        valid: false,
+                        error: Some(format!("{err:#}")),
+                    },
+                }
+            })
+            .buffered(VALIDATION_BATCH_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Validates the content strictly, without storing it, and reports the outcome of each check.
+    pub async fn validation_report(
+        &self,
+        content_key: &BeaconContentKey,
+        content: &[u8],
+    ) -> ValidationReportInfo {
+        self.validator.report(content_key, content).await
+    }
+}
+
+/// Seeds the content fetched from the Beacon API provider into the storage, and returns the block
+/// root of the seeded bootstrap. Nothing is stored unless all the content is valid.
+async fn seed_content(
+    url: Url,
+    block_root: Option<B256>,
+    validator: &BeaconValidator,
+    storage: &PLRwLock<BeaconStorage>,
+    chain_config: &ChainConfig,
+) -> anyhow::Result<B256> {
+    info!(url = %url, "Seeding beacon content from the Beacon API provider ...");
+    let seed_content = SeedProvider::new(url)?.fetch(block_root).await?;
+    let mut content_items = vec![];
+    for (content_key, content_value) in seed_content.content {
+        let content_value = content_value.encode_for_chain(chain_config);
+        validator
+            .verify(&content_key, &content_value)
+            .await
+            .map_err(|err| anyhow!("Seeded content {content_key} is invalid: {err}"))?;
+        content_items.push((content_key, content_value));
+    }
+    let mut storage = storage.write();
+    for (content_key, content_value) in content_items {
+        storage.put(content_key, content_value)?;
+    }
+    info!(block_root = %seed_content.block_root, "Seeded beacon content from the Beacon API provider.");
+    Ok(seed_content.block_root)
+}
+
+/// Returns the bootstrap for the block root from the storage, if it is stored.
+fn lookup_local_bootstrap(
+    storage: &PLRwLock<BeaconStorage>,
+    block_root: B256,
+    chain_config: &ChainConfig,
+) -> anyhow::Result<Option<LightClientBootstrapDeneb>> {
+    let content_key = BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey {
+        block_hash: block_root.0,
+    });
+    let Some(content_value) = storage.read().get(&content_key)? else {
+        return Ok(None);
+    };
+    match BeaconContentValue::decode_for_chain(&content_key, &content_value, chain_config)? {
+        BeaconContentValue::LightClientBootstrap(ForkVersionedLightClientBootstrap {
+            bootstrap: LightClientBootstrap::Deneb(bootstrap),
+            ..
+        }) => Ok(Some(bootstrap)),
+        _ => Ok(None),
+    }
 }
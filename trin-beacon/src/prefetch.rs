@@ -0,0 +1,291 @@
+use std::{collections::BTreeMap, future::Future, sync::Arc};
+
+use ethportal_api::types::portal::{PrefetchJobState, PrefetchStatusInfo};
+use futures::{stream, StreamExt};
+use parking_lot::Mutex;
+use tokio::{sync::Semaphore, task::AbortHandle};
+
+/// The max number of lookups that a prefetch job runs at once.
+const PREFETCH_CONCURRENCY: usize = 4;
+
+/// The max number of lookups that all prefetch jobs run at once. The lookups of the jobs above it
+/// wait for their turn.
+const MAX_PREFETCH_LOOKUPS: usize = 16;
+
+/// The max number of prefetch jobs whose status is kept. Once reached, the status of the oldest
+/// finished job is dropped to make room for a new job. New jobs are rejected while all of the
+/// kept jobs are running.
+const MAX_PREFETCH_JOBS: usize = 64;
+
+/// The outcome of prefetching a single content key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrefetchOutcome {
+    /// The content was found and stored locally.
+    Found,
+    /// The content wasn't found on the network.
+    NotFound,
+    /// The lookup or the storage of the content failed.
+    Failed,
+}
+
+struct PrefetchJob {
+    status: Arc<Mutex<PrefetchStatusInfo>>,
+    abort_handle: AbortHandle,
+}
+
+#[derive(Default)]
+struct Jobs {
+    next_id: u64,
+    by_id: BTreeMap<u64, PrefetchJob>,
+}
+
+impl Jobs {
+    /// Drops the status of the oldest finished jobs, so that there is room for a new job.
+    fn make_room(&mut self) {
+        while self.by_id.len() >= MAX_PREFETCH_JOBS {
+            let oldest_finished = self
+                .by_id
+                .iter()
+                .find(|(_, job)| job.status.lock().state != PrefetchJobState::Running)
+                .map(|(job_id, _)| *job_id);
+            match oldest_finished {
+                Some(job_id) => {
+                    self.by_id.remove(&job_id);
+                }
+                None => return,
+            }
+        }
+    }
+}
+
+/// Background jobs that look up content ahead of demand and store it locally.
+pub struct PrefetchJobs {
+    jobs: Mutex<Jobs>,
+    /// Bounds the lookups of all jobs, see [MAX_PREFETCH_LOOKUPS].
+    lookups: Arc<Semaphore>,
+}
+
+impl Default for PrefetchJobs {
+    fn default() -> Self {
+        Self {
+            jobs: Mutex::default(),
+            lookups: Arc::new(Semaphore::new(MAX_PREFETCH_LOOKUPS)),
+        }
+    }
+}
+
+impl PrefetchJobs {
+    /// Starts prefetching the content keys in the background, with `prefetch`, and returns the id
+    /// of the job.
+    ///
+    /// Returns `None`, without starting the job, if [MAX_PREFETCH_JOBS] jobs are already running.
+    pub fn start<K, F, Fut>(&self, content_keys: Vec<K>, prefetch: F) -> Option<u64>
+    where
+        K: Send + 'static,
+        F: Fn(K) -> Fut + Send + 'static,
+        Fut: Future<Output = PrefetchOutcome> + Send + 'static,
+    {
+        let mut jobs = self.jobs.lock();
+        jobs.make_room();
+        if jobs.by_id.len() >= MAX_PREFETCH_JOBS {
+            return None;
+        }
+        let job_id = jobs.next_id;
+        jobs.next_id += 1;
+
+        let status = Arc::new(Mutex::new(PrefetchStatusInfo {
+            job_id,
+            state: PrefetchJobState::Running,
+            total: content_keys.len() as u64,
+            found: 0,
+            not_found: 0,
+            failed: 0,
+        }));
+        let job_status = Arc::clone(&status);
+        let lookups = Arc::clone(&self.lookups);
+        let handle = tokio::spawn(async move {
+            stream::iter(content_keys)
+                .for_each_concurrent(PREFETCH_CONCURRENCY, |content_key| {
+                    let outcome = prefetch(content_key);
+                    let status = Arc::clone(&job_status);
+                    let lookups = Arc::clone(&lookups);
+                    async move {
+                        let _permit = lookups
+                            .acquire()
+                            .await
+                            .expect("the prefetch semaphore is never closed");
+                        let outcome = outcome.await;
+                        let mut status = status.lock();
+                        match outcome {
+                            PrefetchOutcome::Found => status.found += 1,
+                            PrefetchOutcome::NotFound => status.not_found += 1,
+                            PrefetchOutcome::Failed => status.failed += 1,
+                        }
+                    }
+                })
+                .await;
+            let mut status = job_status.lock();
+            if status.state == PrefetchJobState::Running {
+                status.state = PrefetchJobState::Completed;
+            }
+        });
+        jobs.by_id.insert(
+            job_id,
+            PrefetchJob {
+                status,
+                abort_handle: handle.abort_handle(),
+            },
+        );
+        Some(job_id)
+    }
+
+    /// Returns the status of the job, if it is known.
+    pub fn status(&self, job_id: u64) -> Option<PrefetchStatusInfo> {
+        self.jobs
+            .lock()
+            .by_id
+            .get(&job_id)
+            .map(|job| job.status.lock().clone())
+    }
+
+    /// Cancels the job, if it is still running, and returns its status.
+    pub fn cancel(&self, job_id: u64) -> Option<PrefetchStatusInfo> {
+        let jobs = self.jobs.lock();
+        let job = jobs.by_id.get(&job_id)?;
+        let mut status = job.status.lock();
+        if status.state == PrefetchJobState::Running {
+            job.abort_handle.abort();
+            status.state = PrefetchJobState::Cancelled;
+        }
+        Some(status.clone())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::future::BoxFuture;
+
+    use super::*;
+
+    /// Waits until the job isn't running anymore, and returns its status.
+    async fn wait_for_job(jobs: &PrefetchJobs, job_id: u64) -> PrefetchStatusInfo {
+        loop {
+            let status = jobs.status(job_id).unwrap();
+            if status.state != PrefetchJobState::Running {
+                return status;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    /// Returns a lookup that counts the lookups in flight, and the max number of them, in
+    /// `in_flight`.
+    fn counting_lookup(
+        in_flight: Arc<Mutex<(usize, usize)>>,
+    ) -> impl Fn(u32) -> BoxFuture<'static, PrefetchOutcome> + Send + 'static {
+        move |_| {
+            let in_flight = Arc::clone(&in_flight);
+            Box::pin(async move {
+                {
+                    let mut in_flight = in_flight.lock();
+                    in_flight.0 += 1;
+                    in_flight.1 = in_flight.1.max(in_flight.0);
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                in_flight.lock().0 -= 1;
+                PrefetchOutcome::Found
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_progress_per_outcome() {
+        let jobs = PrefetchJobs::default();
+        let job_id = jobs
+            .start(vec![0, 1, 2, 3, 4, 5], |content_key| async move {
+                match content_key % 3 {
+                    0 => PrefetchOutcome::Found,
+                    1 => PrefetchOutcome::NotFound,
+                    _ => PrefetchOutcome::Failed,
+                }
+            })
+            .unwrap();
+
+        let status = wait_for_job(&jobs, job_id).await;
+        assert_eq!(status.state, PrefetchJobState::Completed);
+        assert_eq!(status.total, 6);
+        assert_eq!(status.found, 2);
+        assert_eq!(status.not_found, 2);
+        assert_eq!(status.failed, 2);
+        assert_eq!(status.pending(), 0);
+        assert!(jobs.status(job_id + 1).is_none());
+    }
+
+    #[tokio::test]
+    async fn bounds_concurrent_lookups() {
+        let jobs = PrefetchJobs::default();
+        let in_flight = Arc::new(Mutex::new((0, 0)));
+        let job_id = jobs
+            .start((0..20).collect(), counting_lookup(Arc::clone(&in_flight)))
+            .unwrap();
+
+        let status = wait_for_job(&jobs, job_id).await;
+        assert_eq!(status.found, 20);
+        assert_eq!(in_flight.lock().1, PREFETCH_CONCURRENCY);
+    }
+
+    #[tokio::test]
+    async fn bounds_concurrent_lookups_of_all_jobs() {
+        let jobs = PrefetchJobs::default();
+        let in_flight = Arc::new(Mutex::new((0, 0)));
+        let job_count = 2 * MAX_PREFETCH_LOOKUPS / PREFETCH_CONCURRENCY;
+        let job_ids = (0..job_count)
+            .map(|_| {
+                jobs.start((0..20).collect(), counting_lookup(Arc::clone(&in_flight)))
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        for job_id in job_ids {
+            assert_eq!(wait_for_job(&jobs, job_id).await.found, 20);
+        }
+        assert_eq!(in_flight.lock().1, MAX_PREFETCH_LOOKUPS);
+    }
+
+    #[tokio::test]
+    async fn rejects_jobs_while_all_kept_jobs_are_running() {
+        let jobs = PrefetchJobs::default();
+        let job_ids = (0..MAX_PREFETCH_JOBS)
+            .map(|_| jobs.start(vec![0], |_| futures::future::pending()).unwrap())
+            .collect::<Vec<_>>();
+        assert!(jobs
+            .start(vec![0], |_| futures::future::pending())
+            .is_none());
+
+        // The cancelled job makes room for a new one.
+        jobs.cancel(job_ids[0]).unwrap();
+        let job_id = jobs.start(vec![0], |_| futures::future::pending()).unwrap();
+        assert!(jobs.status(job_ids[0]).is_none());
+        assert!(jobs.status(job_id).is_some());
+    }
+
+    #[tokio::test]
+    async fn cancel_stops_job() {
+        let jobs = PrefetchJobs::default();
+        let job_id = jobs
+            .start(vec![0, 1], |_| futures::future::pending())
+            .unwrap();
+
+        let status = jobs.cancel(job_id).unwrap();
+        assert_eq!(status.state, PrefetchJobState::Cancelled);
+        assert_eq!(status.pending(), 2);
+        assert_eq!(
+            jobs.status(job_id).unwrap().state,
+            PrefetchJobState::Cancelled
+        );
+        assert!(jobs.cancel(job_id + 1).is_none());
+    }
+}
@@ -0,0 +1,342 @@
+use std::time::Duration;
+
+use alloy::primitives::B256;
+use anyhow::{anyhow, bail, ensure};
+use ethportal_api::{
+    types::content_key::beacon::{LightClientFinalityUpdateKey, LightClientOptimisticUpdateKey},
+    BeaconContentKey, BeaconContentValue, LightClientBootstrapKey, LightClientUpdatesByRangeKey,
+};
+use serde_json::Value;
+use tree_hash::TreeHash;
+use url::Url;
+
+/// The number of sync committee periods, up to the current one, whose updates are seeded.
+const SEED_UPDATE_PERIODS: u64 = 2;
+
+/// The number of slots in a sync committee period.
+const SLOTS_PER_PERIOD: u64 = 32 * 256;
+
+/// The max time to wait for a response of the Beacon API provider.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The beacon content fetched from a Beacon API provider, converted into portal content.
+#[derive(Debug)]
+pub struct SeedContent {
+    /// The block root of the fetched bootstrap.
+    pub block_root: B256,
+    pub content: Vec<(BeaconContentKey, BeaconContentValue)>,
+}
+
+/// Fetches the beacon content from a trusted Beacon API provider, so that the node can serve it
+/// and start its light client without waiting for the portal network.
+pub struct SeedProvider {
+    client: reqwest::Client,
+    url: Url,
+}
+
+impl SeedProvider {
+    pub fn new(url: Url) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()?;
+        Ok(Self { client, url })
+    }
+
+    /// Fetches the bootstrap for the block root, the latest finality and optimistic updates, and
+    /// the updates of the last sync committee periods. If no block root is given, the bootstrap is
+    /// fetched for the provider's finalized block root.
+    pub async fn fetch(&self, block_root: Option<B256>) -> anyhow::Result<SeedContent> {
+        let block_root = match block_root {
+            Some(block_root) => block_root,
+            None => block_root_from_json(
+                self.get_json("/eth/v1/beacon/blocks/finalized/root")
+                    .await?,
+            )?,
+        };
+        let bootstrap = bootstrap_from_json(
+            block_root,
+            self.get_json(&format!(
+                "/eth/v1/beacon/light_client/bootstrap/{block_root}"
+            ))
+            .await?,
+        )?;
+        let finality_update = finality_update_from_json(
+            self.get_json("/eth/v1/beacon/light_client/finality_update")
+                .await?,
+        )?;
+        let optimistic_update = optimistic_update_from_json(
+            self.get_json("/eth/v1/beacon/light_client/optimistic_update")
+                .await?,
+        )?;
+
+        let BeaconContentKey::LightClientFinalityUpdate(finality_update_key) = &finality_update.0
+        else {
+            unreachable!("finality_update_from_json returns a finality update key");
+        };
+        let current_period = finality_update_key.finalized_slot / SLOTS_PER_PERIOD;
+        let start_period = current_period.saturating_sub(SEED_UPDATE_PERIODS - 1);
+        let count = current_period - start_period + 1;
+        let updates = updates_by_range_from_json(
+            start_period,
+            self.get_json(&format!(
+                "/eth/v1/beacon/light_client/updates?start_period={start_period}&count={count}"
+            ))
+            .await?,
+        )?;
+
+        Ok(SeedContent {
+            block_root,
+            content: vec![bootstrap, updates, finality_update, optimistic_update],
+        })
+    }
+
+    async fn get_json(&self, path: &str) -> anyhow::Result<Value> {
+        let url = self.url.join(path)?;
+        let response = self
+            .client
+            .get(url.clone())
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(|err| anyhow!("Beacon API provider request to {url} failed: {err}"))?;
+        Ok(response.json().await?)
+    }
+}
+
+/// Converts the response of the `/eth/v1/beacon/blocks/{block_id}/root` endpoint into the block
+/// root.
+pub fn block_root_from_json(response: Value) -> anyhow::Result<B256> {
+    Ok(serde_json::from_value(response["data"]["root"].clone())?)
+}
+
+/// Converts the response of the `/eth/v1/beacon/light_client/bootstrap/{block_root}` endpoint
+/// into the bootstrap content, after checking that the bootstrap is for the block root.
+pub fn bootstrap_from_json(
+    block_root: B256,
+    response: Value,
+) -> anyhow::Result<(BeaconContentKey, BeaconContentValue)> {
+    let content_key = BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey {
+        block_hash: block_root.0,
+    });
+    let content_value = BeaconContentValue::from_json(&content_key, response)?;
+    let BeaconContentValue::LightClientBootstrap(bootstrap) = &content_value else {
+        unreachable!("The content value of a bootstrap key is a bootstrap");
+    };
+    let header_root = bootstrap
+        .bootstrap
+        .clone()
+        .get_beacon_block_header()
+        .tree_hash_root();
+    ensure!(
+        header_root == block_root,
+        "Light client bootstrap is for block root {header_root}, expected {block_root}"
+    );
+    Ok((content_key, content_value))
+}
+
+/// Converts the response of the `/eth/v1/beacon/light_client/updates` endpoint, for the periods
+/// starting at the start period, into the updates by range content.
+pub fn updates_by_range_from_json(
+    start_period: u64,
+    response: Value,
+) -> anyhow::Result<(BeaconContentKey, BeaconContentValue)> {
+    let Value::Array(updates) = &response else {
+        bail!("Light client updates response is not a list");
+    };
+    ensure!(
+        !updates.is_empty(),
+        "Light client updates response is empty"
+    );
+    let content_key = BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+        start_period,
+        count: updates.len() as u64,
+    });
+    let content_value = BeaconContentValue::from_json(&content_key, response)?;
+    Ok((content_key, content_value))
+}
+
+/// Converts the response of the `/eth/v1/beacon/light_client/finality_update` endpoint into the
+/// finality update content, keyed by its finalized slot.
+pub fn finality_update_from_json(
+    response: Value,
+) -> anyhow::Result<(BeaconContentKey, BeaconContentValue)> {
+    let content_value = BeaconContentValue::from_json(
+        &BeaconContentKey::LightClientFinalityUpdate(LightClientFinalityUpdateKey::new(0)),
+        response,
+    )?;
+    let BeaconContentValue::LightClientFinalityUpdate(finality_update) = &content_value else {
+        unreachable!("The content value of a finality update key is a finality update");
+    };
+    let content_key = BeaconContentKey::LightClientFinalityUpdate(
+        LightClientFinalityUpdateKey::new(finality_update.get_finalized_slot()),
+    );
+    Ok((content_key, content_value))
+}
+
+/// Converts the response of the `/eth/v1/beacon/light_client/optimistic_update` endpoint into the
+/// optimistic update content, keyed by its signature slot.
+pub fn optimistic_update_from_json(
+    response: Value,
+) -> anyhow::Result<(BeaconContentKey, BeaconContentValue)> {
+    let content_value = BeaconContentValue::from_json(
+        &BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey::new(0)),
+        response,
+    )?;
+    let BeaconContentValue::LightClientOptimisticUpdate(optimistic_update) = &content_value else {
+        unreachable!("The content value of an optimistic update key is an optimistic update");
+    };
+    let content_key = BeaconContentKey::LightClientOptimisticUpdate(
+        LightClientOptimisticUpdateKey::new(*optimistic_update.update.signature_slot()),
+    );
+    Ok((content_key, content_value))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::fs;
+
+    use ethportal_api::{
+        consensus::{fork::ForkName, header::BeaconBlockHeader},
+        ContentValue,
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    fn read_fixture(name: &str, slot: u64) -> Value {
+        let path = format!("../test_assets/beacon/deneb/{name}/json/{slot}.json");
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    fn bootstrap_fixture_root() -> B256 {
+        let response = read_fixture("LightClientBootstrap", 7358656);
+        let header: BeaconBlockHeader =
+            serde_json::from_value(response["data"]["header"]["beacon"].clone()).unwrap();
+        header.tree_hash_root()
+    }
+
+    #[test]
+    fn converts_bootstrap() {
+        let block_root = bootstrap_fixture_root();
+        let (content_key, content_value) =
+            bootstrap_from_json(block_root, read_fixture("LightClientBootstrap", 7358656)).unwrap();
+
+        assert_eq!(
+            content_key,
+            BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey {
+                block_hash: block_root.0
+            })
+        );
+        let BeaconContentValue::LightClientBootstrap(bootstrap) = &content_value else {
+            panic!("Expected a bootstrap, got {content_value:?}");
+        };
+        assert_eq!(bootstrap.fork_name, ForkName::Deneb);
+        assert_eq!(bootstrap.get_slot(), 7358656);
+        assert_eq!(
+            BeaconContentValue::decode(&content_key, &content_value.encode()).unwrap(),
+            content_value
+        );
+    }
+
+    #[test]
+    fn rejects_bootstrap_for_other_block_root() {
+        let err = bootstrap_from_json(B256::ZERO, read_fixture("LightClientBootstrap", 7358656))
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("expected 0x0000"),
+            "Unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn converts_updates_by_range() {
+        let start_period = 7357099 / SLOTS_PER_PERIOD;
+        let (content_key, content_value) =
+            updates_by_range_from_json(start_period, read_fixture("LightClientUpdate", 7357099))
+                .unwrap();
+
+        assert_eq!(
+            content_key,
+            BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                start_period,
+                count: 1,
+            })
+        );
+        let BeaconContentValue::LightClientUpdatesByRange(updates) = &content_value else {
+            panic!("Expected updates by range, got {content_value:?}");
+        };
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].fork_name, ForkName::Deneb);
+        assert_eq!(
+            BeaconContentValue::decode(&content_key, &content_value.encode()).unwrap(),
+            content_value
+        );
+
+        assert!(updates_by_range_from_json(start_period, json!([])).is_err());
+        assert!(updates_by_range_from_json(start_period, json!({})).is_err());
+    }
+
+    #[test]
+    fn converts_finality_update() {
+        let response = read_fixture("LightClientFinalityUpdate", 7358726);
+        let finalized_slot: u64 = response["data"]["finalized_header"]["beacon"]["slot"]
+            .as_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let (content_key, content_value) = finality_update_from_json(response).unwrap();
+
+        assert_eq!(
+            content_key,
+            BeaconContentKey::LightClientFinalityUpdate(LightClientFinalityUpdateKey::new(
+                finalized_slot
+            ))
+        );
+        assert_eq!(
+            BeaconContentValue::decode(&content_key, &content_value.encode()).unwrap(),
+            content_value
+        );
+    }
+
+    #[test]
+    fn converts_optimistic_update() {
+        let response = read_fixture("LightClientOptimisticUpdate", 7358726);
+        let signature_slot: u64 = response["data"]["signature_slot"]
+            .as_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let (content_key, content_value) = optimistic_update_from_json(response).unwrap();
+
+        assert_eq!(
+            content_key,
+            BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey::new(
+                signature_slot
+            ))
+        );
+        assert_eq!(
+            BeaconContentValue::decode(&content_key, &content_value.encode()).unwrap(),
+            content_value
+        );
+    }
+
+    #[test]
+    fn converts_block_root() {
+        let block_root = B256::random();
+        let response = json!({
+            "execution_optimistic": false,
+            "finalized": true,
+            "data": { "root": block_root },
+        });
+        assert_eq!(block_root_from_json(response).unwrap(), block_root);
+        assert!(block_root_from_json(json!({ "data": {} })).is_err());
+    }
+
+    #[test]
+    fn rejects_values_of_other_content() {
+        let response = read_fixture("LightClientOptimisticUpdate", 7358726);
+        assert!(finality_update_from_json(response).is_err());
+    }
+}
@@ -1,41 +1,84 @@
-use std::path::PathBuf;
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, VecDeque},
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use alloy::primitives::B256;
+use alloy::primitives::{b256, keccak256, B256, U256};
+use discv5::enr::NodeId;
 use ethportal_api::{
-    consensus::fork::ForkName,
+    consensus::{
+        chain_config::{ChainConfig, MAINNET_CHAIN_CONFIG},
+        historical_summaries::HistoricalSummariesWithProof,
+    },
     types::{
+        content_key::beacon::{
+            HistoricalSummariesWithProofKey, LightClientFinalityUpdateKey,
+            LightClientOptimisticUpdateKey,
+        },
         content_value::beacon::{
-            ForkVersionedLightClientBootstrap, ForkVersionedLightClientFinalityUpdate,
-            ForkVersionedLightClientOptimisticUpdate, ForkVersionedLightClientUpdate,
+            ForkVersionedHistoricalSummariesWithProof, ForkVersionedLightClientBootstrap,
+            ForkVersionedLightClientFinalityUpdate, ForkVersionedLightClientOptimisticUpdate,
             LightClientUpdatesByRange,
         },
-        distance::Distance,
+        distance::{Distance, Metric, XorMetric},
         network::Subnetwork,
-        portal::PaginateLocalContentInfo,
+        portal::{
+            BeaconContentTypeCounts, GcOrphansInfo, LatencyPercentiles, NearestStoredKeyInfo,
+            PaginateLocalContentInfo, PinnedContentInfo, PruneEstimate, PruneEstimateInfo,
+            RangeCoverageInfo, SelfTestCheck, SelfTestInfo, SelfTestSubsystem, StorageBackendInfo,
+            StorageInfo, StoreLatencyStats,
+        },
     },
-    BeaconContentKey, OverlayContentKey, RawContentValue,
+    BeaconContentKey, LightClientBootstrapKey, LightClientUpdatesByRangeKey, OverlayContentKey,
+    RawContentValue,
 };
+use parking_lot::Mutex;
 use r2d2::Pool;
 use r2d2_sqlite::{rusqlite, SqliteConnectionManager};
-use rusqlite::params;
-use ssz::{Decode, Encode};
+use rusqlite::{params, OptionalExtension, Transaction};
+use ssz::Encode;
 use ssz_types::{typenum::U128, VariableList};
-use tracing::debug;
+use tracing::{debug, error, warn};
 use tree_hash::TreeHash;
 use trin_metrics::storage::StorageMetricsReporter;
 use trin_storage::{
     error::ContentStoreError,
     sql::{
+        BEACON_CONTENT_DEDUP_SIZE_QUERY, BEACON_CONTENT_TYPE_COUNT_QUERY,
+        BEACON_PINNED_CONTENT_KEYS_QUERY, BEACON_TRUSTED_CONTENT_LOOKUP_QUERY,
+        DELETE_BEACON_PINNED_CONTENT_QUERY, DELETE_LC_BOOTSTRAP_QUERY, DELETE_LC_UPDATE_QUERY,
+        DELETE_UNREFERENCED_BEACON_CONTENT_BLOBS_QUERY, HISTORICAL_SUMMARIES_EPOCHS_QUERY,
         HISTORICAL_SUMMARIES_EPOCH_LOOKUP_QUERY, HISTORICAL_SUMMARIES_LOOKUP_QUERY,
-        INSERT_BOOTSTRAP_QUERY, INSERT_LC_UPDATE_QUERY,
-        INSERT_OR_REPLACE_HISTORICAL_SUMMARIES_QUERY, LC_BOOTSTRAP_LATEST_BLOCK_ROOT_QUERY,
-        LC_BOOTSTRAP_LOOKUP_QUERY, LC_BOOTSTRAP_ROOT_LOOKUP_QUERY, LC_UPDATE_LOOKUP_QUERY,
-        LC_UPDATE_PERIOD_LOOKUP_QUERY, TOTAL_DATA_SIZE_QUERY_BEACON,
+        INSERT_BEACON_CONTENT_BLOB_QUERY, INSERT_BOOTSTRAP_QUERY, INSERT_LC_UPDATE_QUERY,
+        INSERT_OR_IGNORE_BEACON_PINNED_CONTENT_QUERY,
+        INSERT_OR_REPLACE_BEACON_TRUSTED_CONTENT_QUERY,
+        INSERT_OR_REPLACE_HISTORICAL_SUMMARIES_QUERY, LC_BOOTSTRAP_BLOCK_ROOTS_QUERY,
+        LC_BOOTSTRAP_LATEST_BLOCK_ROOT_QUERY, LC_BOOTSTRAP_LOOKUP_QUERY,
+        LC_BOOTSTRAP_OLDEST_FIRST_QUERY, LC_BOOTSTRAP_ROOT_LOOKUP_QUERY,
+        LC_BOOTSTRAP_SIZE_LOOKUP_QUERY, LC_UPDATE_LOOKUP_QUERY, LC_UPDATE_OLDEST_FIRST_QUERY,
+        LC_UPDATE_PERIODS_QUERY, LC_UPDATE_PERIOD_LOOKUP_QUERY, LC_UPDATE_RANGE_SIZE_QUERY,
+        TOTAL_DATA_SIZE_QUERY_BEACON, UNREFERENCED_BEACON_CONTENT_BLOBS_SIZE_QUERY,
     },
-    utils::get_total_size_of_directory_in_bytes,
+    utils::{get_total_size_of_directory_in_bytes, storage_backend_info},
     ContentStore, DataSize, PortalStorageConfig, ShouldWeStoreContent,
 };
 
+/// The period of the synthetic light client update stored by the self-test. It is the largest
+/// period that SQLite can store, far beyond any real sync committee period.
+const SELF_TEST_PERIOD: u64 = i64::MAX as u64;
+
+/// The prefix of the synthetic value stored by the self-test.
+const SELF_TEST_VALUE_PREFIX: &[u8] = b"trin self-test";
+
+/// The encoding of the content key of the synthetic update, and its content id (the sha256 hash of
+/// the encoding), computed independently of the content key implementation.
+const SELF_TEST_CONTENT_KEY: &str = "0x11ffffffffffffff7f0100000000000000";
+const SELF_TEST_CONTENT_ID: B256 =
+    b256!("45e06ad7d6a16b60c27d1c7da7b78ab692d019e28a8861246794ac80d9300960");
+
 /// Store ephemeral light client data in memory
 #[derive(Debug)]
 pub struct BeaconStorageCache {
@@ -79,14 +122,7 @@ impl BeaconStorageCache {
         if let Some(finality_update) = &self.finality_update {
             // Returns the current finality update if it's finality slot is bigger or equal to the
             // requested slot.
-            if finality_update
-                .update
-                .finalized_header_deneb()
-                .ok()?
-                .beacon
-                .slot
-                >= finalized_slot
-            {
+            if finality_update.update.finalized_slot() >= finalized_slot {
                 return Some(finality_update.clone());
             }
         }
@@ -108,6 +144,88 @@ impl BeaconStorageCache {
     }
 }
 
+/// The number of recent inserts used to estimate the ingress rate of a content type.
+const INGRESS_RATE_SAMPLES: usize = 8;
+
+/// Tracks when content of a single type was recently stored.
+#[derive(Debug, Default)]
+struct IngressRate {
+    inserts: VecDeque<Instant>,
+}
+
+impl IngressRate {
+    fn record(&mut self) {
+        if self.inserts.len() == INGRESS_RATE_SAMPLES {
+            self.inserts.pop_front();
+        }
+        self.inserts.push_back(Instant::now());
+    }
+
+    /// Returns the mean interval between recent inserts, if there were at least two.
+    fn mean_interval(&self) -> Option<Duration> {
+        if self.inserts.len() < 2 {
+            return None;
+        }
+        let (first, last) = (self.inserts.front()?, self.inserts.back()?);
+        Some(last.duration_since(*first) / (self.inserts.len() - 1) as u32)
+    }
+
+    fn last_insert(&self) -> Option<Instant> {
+        self.inserts.back().copied()
+    }
+}
+
+/// Ingress rates of the content types that are replaced when newer content is stored.
+#[derive(Debug, Default)]
+struct IngressRates {
+    optimistic_update: IngressRate,
+    finality_update: IngressRate,
+    historical_summaries: IngressRate,
+}
+
+/// The number of recent operations over which the store latency percentiles are computed.
+const LATENCY_SAMPLES: usize = 1024;
+
+/// The latencies of the most recent operations of a single kind.
+#[derive(Debug, Default)]
+struct LatencyWindow {
+    samples: VecDeque<Duration>,
+}
+
+impl LatencyWindow {
+    fn record(&mut self, latency: Duration) {
+        if self.samples.len() == LATENCY_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(latency);
+    }
+
+    /// Returns the nearest-rank percentiles of the recorded latencies.
+    fn percentiles(&self) -> LatencyPercentiles {
+        let mut samples: Vec<Duration> = self.samples.iter().copied().collect();
+        samples.sort_unstable();
+        let percentile = |percent: usize| {
+            let rank = (percent * samples.len()).div_ceil(100);
+            samples
+                .get(rank.saturating_sub(1))
+                .map(|latency| latency.as_micros() as u64)
+        };
+        LatencyPercentiles {
+            samples: samples.len() as u64,
+            p50_us: percentile(50),
+            p95_us: percentile(95),
+            p99_us: percentile(99),
+        }
+    }
+}
+
+/// Latencies of the reads and writes of the store.
+#[derive(Debug, Default)]
+struct StoreLatencies {
+    get: LatencyWindow,
+    put: LatencyWindow,
+}
+
 /// Storage layer for the state network. Encapsulates beacon network specific data and logic.
 #[derive(Debug)]
 pub struct BeaconStorage {
@@ -115,82 +233,42 @@ pub struct BeaconStorage {
     sql_connection_pool: Pool<SqliteConnectionManager>,
     metrics: StorageMetricsReporter,
     cache: BeaconStorageCache,
+    /// If enabled, content values are stored once in the content blob table, and the beacon
+    /// tables only reference them by hash.
+    content_dedup: bool,
+    /// Whether the content values read from the database are decoded and checked against their
+    /// content key, to detect corruption.
+    verify_on_read: bool,
+    ingress_rates: IngressRates,
+    latencies: Mutex<StoreLatencies>,
+    /// The chain whose fork digests prefix the content values.
+    chain_config: Arc<ChainConfig>,
+    /// The capacity of the content that isn't pinned. Nothing is pruned if it's zero, which it is
+    /// unless the capacity of the beacon storage is specified explicitly.
+    storage_capacity_bytes: u64,
+    /// The keys of the content that is kept regardless of the storage capacity, which are also
+    /// persisted in the database. The content doesn't have to be stored yet.
+    pinned_keys: Vec<BeaconContentKey>,
+    /// The size of the stored pinned content. It's only recomputed when the pinned keys or their
+    /// stored content change, rather than on every store.
+    pinned_bytes: u64,
+    /// The budget of the stored pinned content, separate from the storage capacity.
+    pinned_capacity_bytes: u64,
 }
 
 impl ContentStore for BeaconStorage {
     type Key = BeaconContentKey;
 
     fn get(&self, key: &BeaconContentKey) -> Result<Option<RawContentValue>, ContentStoreError> {
-        match key {
-            BeaconContentKey::LightClientBootstrap(content_key) => self
-                .lookup_lc_bootstrap_value(&content_key.block_hash)
-                .map(|value| value.map(RawContentValue::from))
-                .map_err(|err| {
-                    ContentStoreError::Database(format!(
-                        "Error looking up LightClientBootstrap content value: {err:?}"
-                    ))
-                }),
-            BeaconContentKey::LightClientUpdatesByRange(content_key) => {
-                let periods =
-                    content_key.start_period..(content_key.start_period + content_key.count);
-
-                let mut content: Vec<ForkVersionedLightClientUpdate> = Vec::new();
-
-                for period in periods {
-                    let result = self.lookup_lc_update_value(period).map_err(|err| {
-                        ContentStoreError::Database(format!(
-                            "Error looking up LightClientUpdate content value: {err:?}"
-                        ))
-                    })?;
-
-                    match result {
-                        Some(result) => content.push(
-                            ForkVersionedLightClientUpdate::from_ssz_bytes(result.as_slice())
-                                .map_err(|err| {
-                                    ContentStoreError::Database(format!(
-                                        "Error ssz decode ForkVersionedLightClientUpdate value: {err:?}"
-                                    ))
-                                })?,
-                        ),
-                        None => return Ok(None),
-                    }
-                }
-
-                let result = VariableList::<ForkVersionedLightClientUpdate, U128>::new(content)
-                    .map_err(|err| ContentStoreError::Database(
-                        format!(
-                            "Error building VariableList from ForkVersionedLightClientUpdate data: {err:?}"
-                        ),
-                    ))?;
-
-                Ok(Some(result.as_ssz_bytes().into()))
-            }
-            BeaconContentKey::LightClientFinalityUpdate(content_key) => {
-                match self.cache.get_finality_update(content_key.finalized_slot) {
-                    Some(finality_update) => Ok(Some(finality_update.as_ssz_bytes().into())),
-                    None => Ok(None),
-                }
-            }
-            BeaconContentKey::LightClientOptimisticUpdate(content_key) => {
-                match self.cache.get_optimistic_update(content_key.signature_slot) {
-                    Some(optimistic_update) => Ok(Some(optimistic_update.as_ssz_bytes().into())),
-                    None => Ok(None),
-                }
-            }
-            BeaconContentKey::HistoricalSummariesWithProof(content_key) => {
-                let epoch = content_key.epoch;
-                match self
-                    .lookup_historical_summaries_value(epoch)
-                    .map_err(|err| {
-                        ContentStoreError::Database(format!(
-                            "Error looking up HistoricalSummariesWithProof content value: {err:?}"
-                        ))
-                    })? {
-                    Some(result) => Ok(Some(result.into())),
-                    None => Ok(None),
-                }
+        let started_at = Instant::now();
+        let result = match self.lookup_content_value(key) {
+            Ok(Some(value)) if self.verify_on_read => {
+                self.verify_content_value(key, &value).map(|_| Some(value))
             }
-        }
+            result => result,
+        };
+        self.latencies.lock().get.record(started_at.elapsed());
+        result
     }
 
     fn put<V: AsRef<[u8]>>(
@@ -198,8 +276,11 @@ impl ContentStore for BeaconStorage {
         key: BeaconContentKey,
         value: V,
     ) -> Result<Vec<(BeaconContentKey, RawContentValue)>, ContentStoreError> {
+        let started_at = Instant::now();
         // in the beacon network we don't return any dropped content for propagation
-        self.store(&key, &value.as_ref().to_vec()).and(Ok(vec![]))
+        let result = self.store(&key, &value.as_ref().to_vec()).and(Ok(vec![]));
+        self.latencies.lock().put.record(started_at.elapsed());
+        result
     }
 
     /// The "radius" concept is not applicable for Beacon network
@@ -285,16 +366,62 @@ impl ContentStore for BeaconStorage {
     fn radius(&self) -> Distance {
         Distance::MAX
     }
+
+    /// Returns the stored content whose content id is within the radius of the node, ordered by
+    /// content id. The light client updates are returned one period at a time.
+    fn entries_within_radius(
+        &self,
+        node_id: &NodeId,
+        radius: Distance,
+        limit: usize,
+    ) -> Result<Vec<(BeaconContentKey, RawContentValue)>, ContentStoreError> {
+        let mut keys = self
+            .stored_keys()?
+            .into_iter()
+            .filter(|key| XorMetric::distance(&key.content_id(), &node_id.raw()) <= radius)
+            .collect::<Vec<_>>();
+        keys.sort_by_key(|key| key.content_id());
+
+        let mut entries = vec![];
+        for key in keys {
+            if entries.len() >= limit {
+                break;
+            }
+            if let Some(value) = self.lookup_content_value(&key)? {
+                entries.push((key, value));
+            }
+        }
+        Ok(entries)
+    }
 }
 
 impl BeaconStorage {
     pub fn new(config: PortalStorageConfig) -> Result<Self, ContentStoreError> {
-        let storage = Self {
+        Self::new_for_chain(config, MAINNET_CHAIN_CONFIG.clone())
+    }
+
+    /// Creates the storage for the content values of the chain, like [Self::new] does for mainnet.
+    pub fn new_for_chain(
+        config: PortalStorageConfig,
+        chain_config: Arc<ChainConfig>,
+    ) -> Result<Self, ContentStoreError> {
+        let mut storage = Self {
             node_data_dir: config.node_data_dir,
             sql_connection_pool: config.sql_connection_pool,
             metrics: StorageMetricsReporter::new(Subnetwork::Beacon),
             cache: BeaconStorageCache::new(),
+            content_dedup: config.content_dedup,
+            verify_on_read: config.verify_on_read,
+            ingress_rates: IngressRates::default(),
+            latencies: Mutex::new(StoreLatencies::default()),
+            chain_config,
+            storage_capacity_bytes: config.storage_capacity_bytes,
+            pinned_keys: vec![],
+            pinned_bytes: 0,
+            pinned_capacity_bytes: config.pinned_capacity_bytes,
         };
+        storage.pinned_keys = storage.lookup_pinned_keys()?;
+        storage.pinned_bytes = storage.compute_pinned_bytes()?;
 
         // Report current total storage usage.
         let total_storage_usage = storage.get_total_storage_usage_in_bytes_on_disk()?;
@@ -312,50 +439,249 @@ impl BeaconStorage {
         Ok(storage)
     }
 
+    /// Returns the bytes to store in the `value` column of the beacon tables: the hash of the value
+    /// if content dedup is enabled, otherwise the value itself.
+    fn column_value(&self, value: &[u8]) -> Vec<u8> {
+        if self.content_dedup {
+            keccak256(value).to_vec()
+        } else {
+            value.to_vec()
+        }
+    }
+
+    /// Stores the value in the content blob table, if content dedup is enabled. Identical values
+    /// are only stored once.
+    fn db_insert_content_blob(
+        &self,
+        tx: &Transaction,
+        value: &[u8],
+    ) -> Result<(), ContentStoreError> {
+        if self.content_dedup {
+            tx.execute(
+                INSERT_BEACON_CONTENT_BLOB_QUERY,
+                params![keccak256(value).as_slice(), value],
+            )?;
+        }
+        Ok(())
+    }
+
     fn db_insert_lc_bootstrap(
         &self,
         block_root: &[u8; 32],
-        value: &Vec<u8>,
+        value: &[u8],
         slot: u64,
     ) -> Result<usize, ContentStoreError> {
-        let conn = self.sql_connection_pool.get()?;
-        Ok(conn.execute(
+        let mut conn = self.sql_connection_pool.get()?;
+        let tx = conn.transaction()?;
+        let inserted = tx.execute(
             INSERT_BOOTSTRAP_QUERY,
-            params![block_root, value, slot, 32 + value.len() + 8],
-        )?)
+            params![
+                block_root,
+                self.column_value(value),
+                slot,
+                32 + value.len() + 8
+            ],
+        )?;
+        if inserted > 0 {
+            self.db_insert_content_blob(&tx, value)?;
+        }
+        tx.commit()?;
+        Ok(inserted)
     }
 
-    fn db_insert_lc_update(&self, period: &u64, value: &Vec<u8>) -> Result<(), ContentStoreError> {
-        let conn = self.sql_connection_pool.get()?;
+    fn db_insert_lc_update(&self, period: &u64, value: &[u8]) -> Result<(), ContentStoreError> {
+        let mut conn = self.sql_connection_pool.get()?;
+        let tx = conn.transaction()?;
         let value_size = value.len();
 
-        match conn.execute(
+        let inserted = tx.execute(
             INSERT_LC_UPDATE_QUERY,
-            params![period, value, 0, value_size],
-        ) {
-            Ok(_) => Ok(()),
-            Err(err) => Err(err.into()),
+            params![period, self.column_value(value), 0, value_size],
+        )?;
+        if inserted > 0 {
+            self.db_insert_content_blob(&tx, value)?;
         }
+        Ok(tx.commit()?)
+    }
+
+    /// Deletes the bootstrap, along with its content blob if no other content references it.
+    fn db_delete_lc_bootstrap(&self, block_root: &[u8]) -> Result<(), ContentStoreError> {
+        let mut conn = self.sql_connection_pool.get()?;
+        let tx = conn.transaction()?;
+        tx.execute(DELETE_LC_BOOTSTRAP_QUERY, [block_root])?;
+        tx.execute(DELETE_UNREFERENCED_BEACON_CONTENT_BLOBS_QUERY, [])?;
+        Ok(tx.commit()?)
+    }
+
+    /// Deletes the light client update of the period, along with its content blob if no other
+    /// content references it.
+    fn db_delete_lc_update(&self, period: u64) -> Result<(), ContentStoreError> {
+        let mut conn = self.sql_connection_pool.get()?;
+        let tx = conn.transaction()?;
+        tx.execute(DELETE_LC_UPDATE_QUERY, [period])?;
+        tx.execute(DELETE_UNREFERENCED_BEACON_CONTENT_BLOBS_QUERY, [])?;
+        Ok(tx.commit()?)
     }
 
     /// Insert or replace historical summaries with proof into the database
     fn db_insert_or_replace_historical_summaries_with_proof(
         &self,
         epoch: &u64,
-        value: &Vec<u8>,
+        value: &[u8],
     ) -> Result<(), ContentStoreError> {
-        let conn = self.sql_connection_pool.get()?;
+        let mut conn = self.sql_connection_pool.get()?;
+        let tx = conn.transaction()?;
         let value_size = value.len();
 
-        match conn.execute(
+        tx.execute(
             INSERT_OR_REPLACE_HISTORICAL_SUMMARIES_QUERY,
-            params![1, epoch, value, value_size],
-        ) {
-            Ok(_) => Ok(()),
-            Err(err) => Err(err.into()),
+            params![1, epoch, self.column_value(value), value_size],
+        )?;
+        self.db_insert_content_blob(&tx, value)?;
+        // The replaced historical summaries may have been the last reference to its blob.
+        tx.execute(DELETE_UNREFERENCED_BEACON_CONTENT_BLOBS_QUERY, [])?;
+        Ok(tx.commit()?)
+    }
+
+    /// Looks up the content value from the database, or from the cache of the latest updates.
+    fn lookup_content_value(
+        &self,
+        key: &BeaconContentKey,
+    ) -> Result<Option<RawContentValue>, ContentStoreError> {
+        match key {
+            BeaconContentKey::LightClientBootstrap(content_key) => self
+                .lookup_lc_bootstrap_value(&content_key.block_hash)
+                .map(|value| value.map(RawContentValue::from))
+                .map_err(|err| {
+                    ContentStoreError::Database(format!(
+                        "Error looking up LightClientBootstrap content value: {err:?}"
+                    ))
+                }),
+            BeaconContentKey::LightClientUpdatesByRange(content_key) => {
+                let periods =
+                    content_key.start_period..(content_key.start_period + content_key.count);
+
+                // The stored updates are served as they are, so that each keeps the fork digest
+                // that it was stored with.
+                let mut content: Vec<Vec<u8>> = Vec::new();
+
+                for period in periods {
+                    let result = self.lookup_lc_update_value(period).map_err(|err| {
+                        ContentStoreError::Database(format!(
+                            "Error looking up LightClientUpdate content value: {err:?}"
+                        ))
+                    })?;
+
+                    match result {
+                        Some(result) => content.push(result),
+                        None => return Ok(None),
+                    }
+                }
+
+                let result = VariableList::<Vec<u8>, U128>::new(content)
+                    .map_err(|err| ContentStoreError::Database(
+                        format!(
+                            "Error building VariableList from ForkVersionedLightClientUpdate data: {err:?}"
+                        ),
+                    ))?;
+
+                Ok(Some(result.as_ssz_bytes().into()))
+            }
+            BeaconContentKey::LightClientFinalityUpdate(content_key) => {
+                match self.cache.get_finality_update(content_key.finalized_slot) {
+                    Some(finality_update) => Ok(Some(
+                        finality_update.encode_for_chain(&self.chain_config).into(),
+                    )),
+                    None => Ok(None),
+                }
+            }
+            BeaconContentKey::LightClientOptimisticUpdate(content_key) => {
+                match self.cache.get_optimistic_update(content_key.signature_slot) {
+                    Some(optimistic_update) => Ok(Some(
+                        optimistic_update
+                            .encode_for_chain(&self.chain_config)
+                            .into(),
+                    )),
+                    None => Ok(None),
+                }
+            }
+            BeaconContentKey::HistoricalSummariesWithProof(content_key) => {
+                let epoch = content_key.epoch;
+                match self
+                    .lookup_historical_summaries_value(epoch)
+                    .map_err(|err| {
+                        ContentStoreError::Database(format!(
+                            "Error looking up HistoricalSummariesWithProof content value: {err:?}"
+                        ))
+                    })? {
+                    Some(result) => Ok(Some(result.into())),
+                    None => Ok(None),
+                }
+            }
         }
     }
 
+    /// Checks that the content value read from the database decodes, and that it's the content of
+    /// the key. It returns `Corrupted` error otherwise.
+    fn verify_content_value(
+        &self,
+        key: &BeaconContentKey,
+        value: &[u8],
+    ) -> Result<(), ContentStoreError> {
+        let message = match key {
+            BeaconContentKey::LightClientBootstrap(content_key) => {
+                match ForkVersionedLightClientBootstrap::decode_for_chain(value, &self.chain_config)
+                {
+                    Ok(bootstrap) => {
+                        let block_root = bootstrap
+                            .bootstrap
+                            .get_beacon_block_header()
+                            .tree_hash_root();
+                        (block_root != B256::from(content_key.block_hash))
+                            .then(|| format!("bootstrap has block root {block_root}"))
+                    }
+                    Err(err) => Some(format!("invalid bootstrap: {err:?}")),
+                }
+            }
+            BeaconContentKey::LightClientUpdatesByRange(content_key) => {
+                match LightClientUpdatesByRange::decode_for_chain(value, &self.chain_config) {
+                    Ok(updates) => (updates.as_ref().len() as u64 != content_key.count)
+                        .then(|| format!("{} updates are stored", updates.as_ref().len())),
+                    Err(err) => Some(format!("invalid updates: {err:?}")),
+                }
+            }
+            BeaconContentKey::HistoricalSummariesWithProof(content_key) => {
+                match ForkVersionedHistoricalSummariesWithProof::decode_for_chain(
+                    value,
+                    &self.chain_config,
+                ) {
+                    Ok(summaries) => {
+                        let epoch = summaries.historical_summaries_with_proof.epoch;
+                        (epoch < content_key.epoch)
+                            .then(|| format!("historical summaries are of epoch {epoch}"))
+                    }
+                    Err(err) => Some(format!("invalid historical summaries: {err:?}")),
+                }
+            }
+            // The latest updates are only kept in memory.
+            BeaconContentKey::LightClientFinalityUpdate(_)
+            | BeaconContentKey::LightClientOptimisticUpdate(_) => None,
+        };
+        let Some(message) = message else {
+            return Ok(());
+        };
+        let content_id = B256::from(key.content_id());
+        error!(
+            content.id = %content_id,
+            content.key = %key,
+            "Corrupted content in beacon store: {message}",
+        );
+        Err(ContentStoreError::Corrupted {
+            content_id: content_id.to_string(),
+            message,
+        })
+    }
+
     pub fn store(
         &mut self,
         key: &BeaconContentKey,
@@ -365,57 +691,18 @@ impl BeaconStorage {
 
         match key {
             BeaconContentKey::LightClientBootstrap(_) => {
-                let bootstrap = ForkVersionedLightClientBootstrap::from_ssz_bytes(value.as_slice())
-                    .map_err(|err| ContentStoreError::InvalidData {
-                        message: format!(
-                            "Error deserializing ForkVersionedLightClientBootstrap value: {err:?}"
-                        ),
-                    })?;
-
-                let (slot, block_root) = match bootstrap.fork_name {
-                    ForkName::Bellatrix => {
-                        let bootstrap_header = bootstrap.bootstrap.header_bellatrix().map_err(|err| {
-                            ContentStoreError::InvalidData {
-                                message: format!(
-                                    "Error getting header from bellatrix ForkVersionedLightClientBootstrap value: {err:?}"
-                                ),
-                            }
-                        })?;
-
-                        (
-                            bootstrap_header.beacon.slot,
-                            bootstrap_header.beacon.tree_hash_root(),
-                        )
-                    }
-                    ForkName::Capella => {
-                        let bootstrap_header = bootstrap.bootstrap.header_capella().map_err(|err| {
-                            ContentStoreError::InvalidData {
-                                message: format!(
-                                    "Error getting header from capella ForkVersionedLightClientBootstrap value: {err:?}"
-                                ),
-                            }
-                        })?;
-
-                        (
-                            bootstrap_header.beacon.slot,
-                            bootstrap_header.beacon.tree_hash_root(),
-                        )
-                    }
-                    ForkName::Deneb => {
-                        let bootstrap_header = bootstrap.bootstrap.header_deneb().map_err(|err| {
-                            ContentStoreError::InvalidData {
-                                message: format!(
-                                    "Error getting header from deneb ForkVersionedLightClientBootstrap value: {err:?}"
-                                ),
-                            }
-                        })?;
-
-                        (
-                            bootstrap_header.beacon.slot,
-                            bootstrap_header.beacon.tree_hash_root(),
-                        )
-                    }
-                };
+                let bootstrap = ForkVersionedLightClientBootstrap::decode_for_chain(
+                    value.as_slice(),
+                    &self.chain_config,
+                )
+                .map_err(|err| ContentStoreError::InvalidData {
+                    message: format!(
+                        "Error deserializing ForkVersionedLightClientBootstrap value: {err:?}"
+                    ),
+                })?;
+
+                let header = bootstrap.bootstrap.get_beacon_block_header();
+                let (slot, block_root) = (header.slot, header.tree_hash_root());
 
                 if let Err(err) = self.db_insert_lc_bootstrap(&block_root, value, slot) {
                     debug!(block_root = %block_root, "Error writing light client bootstrap to lc_bootstrap db table: {err:?}");
@@ -428,15 +715,20 @@ impl BeaconStorage {
                 // Build a range of values starting with update.start_period and len
                 // update.count
                 let periods = update.start_period..(update.start_period + update.count);
-                let update_values = LightClientUpdatesByRange::from_ssz_bytes(value.as_slice())
-                    .map_err(|err| ContentStoreError::InvalidData {
-                        message: format!(
-                            "Error deserializing LightClientUpdatesByRange value: {err:?}"
-                        ),
-                    })?;
+                let update_values = LightClientUpdatesByRange::decode_for_chain(
+                    value.as_slice(),
+                    &self.chain_config,
+                )
+                .map_err(|err| ContentStoreError::InvalidData {
+                    message: format!(
+                        "Error deserializing LightClientUpdatesByRange value: {err:?}"
+                    ),
+                })?;
 
                 for (period, value) in periods.zip(update_values.as_ref()) {
-                    if let Err(err) = self.db_insert_lc_update(&period, &value.encode()) {
+                    if let Err(err) = self
+                        .db_insert_lc_update(&period, &value.encode_for_chain(&self.chain_config))
+                    {
                         debug!("Error writing light client update by range content ID {content_id:?} to beacon network db: {err:?}");
                     } else {
                         self.metrics.increase_entry_count();
@@ -444,25 +736,30 @@ impl BeaconStorage {
                 }
             }
             BeaconContentKey::LightClientFinalityUpdate(_) => {
-                self.cache.set_finality_update(
-                        ForkVersionedLightClientFinalityUpdate::from_ssz_bytes(value.as_slice())
-                            .map_err(|err| ContentStoreError::InvalidData {
-                                message: format!(
-                                    "Error deserializing ForkVersionedLightClientFinalityUpdate value: {err:?}"
-                                ),
-                            })?,
-                    );
+                let finality_update = ForkVersionedLightClientFinalityUpdate::decode_for_chain(
+                    value.as_slice(),
+                    &self.chain_config,
+                )
+                .map_err(|err| ContentStoreError::InvalidData {
+                    message: format!(
+                        "Error deserializing ForkVersionedLightClientFinalityUpdate value: {err:?}"
+                    ),
+                })?;
+                self.cache.set_finality_update(finality_update);
+                self.ingress_rates.finality_update.record();
             }
             BeaconContentKey::LightClientOptimisticUpdate(_) => {
-                self.cache.set_optimistic_update(
-                        ForkVersionedLightClientOptimisticUpdate::from_ssz_bytes(value.as_slice()).map_err(
-                            |err| ContentStoreError::InvalidData {
-                                message: format!(
-                                    "Error deserializing ForkVersionedLightClientOptimisticUpdate value: {err:?}"
-                                ),
-                            },
-                        )?,
-                    );
+                let optimistic_update = ForkVersionedLightClientOptimisticUpdate::decode_for_chain(
+                    value.as_slice(),
+                    &self.chain_config,
+                )
+                .map_err(|err| ContentStoreError::InvalidData {
+                    message: format!(
+                        "Error deserializing ForkVersionedLightClientOptimisticUpdate value: {err:?}"
+                    ),
+                })?;
+                self.cache.set_optimistic_update(optimistic_update);
+                self.ingress_rates.optimistic_update.record();
             }
             BeaconContentKey::HistoricalSummariesWithProof(historical_summaries_key) => {
                 if let Err(err) = self.db_insert_or_replace_historical_summaries_with_proof(
@@ -473,9 +770,28 @@ impl BeaconStorage {
                     return Err(err);
                 } else {
                     self.metrics.increase_entry_count();
+                    self.ingress_rates.historical_summaries.record();
+                }
+            }
+        }
+
+        let covering_pinned_keys = self.pinned_keys_covering(key);
+        if !covering_pinned_keys.is_empty() {
+            self.pinned_bytes = self.compute_pinned_bytes()?;
+            if self.pinned_bytes > self.pinned_capacity_bytes {
+                for pinned_key in &covering_pinned_keys {
+                    warn!(
+                        content.key = %pinned_key,
+                        pinned_bytes = self.pinned_bytes,
+                        pinned_capacity_bytes = self.pinned_capacity_bytes,
+                        "Stored pinned content exceeds the pinned budget, unpinning it"
+                    );
+                    self.remove_pinned_key(pinned_key)?;
                 }
+                self.pinned_bytes = self.compute_pinned_bytes()?;
             }
         }
+        self.prune()?;
 
         let total_bytes_on_disk = self.get_total_storage_usage_in_bytes_on_disk()?;
         self.metrics
@@ -484,34 +800,304 @@ impl BeaconStorage {
         Ok(())
     }
 
-    pub fn paginate(
+    /// Marks the stored content as trusted, as it was stored with a signature of a trusted signer.
+    /// The content is only trusted as long as the stored value is the signed one, so it isn't once
+    /// it's replaced by another value.
+    pub fn mark_trusted(
         &self,
-        _offset: &u64,
-        _limit: &u64,
-    ) -> Result<PaginateLocalContentInfo<BeaconContentKey>, ContentStoreError> {
-        Err(ContentStoreError::Database(
-            "Paginate not implemented for Beacon storage".to_string(),
-        ))
-    }
-
-    /// Public method for determining how much actual disk space is being used to store this node's
-    /// Portal Network data. Intended for analysis purposes. PortalStorage's capacity
-    /// decision-making is not based off of this method.
-    pub fn get_total_storage_usage_in_bytes_on_disk(&self) -> Result<u64, ContentStoreError> {
-        let storage_usage = get_total_size_of_directory_in_bytes(&self.node_data_dir)?;
-        Ok(storage_usage)
+        key: &BeaconContentKey,
+        value: &[u8],
+    ) -> Result<(), ContentStoreError> {
+        let conn = self.sql_connection_pool.get()?;
+        conn.execute(
+            INSERT_OR_REPLACE_BEACON_TRUSTED_CONTENT_QUERY,
+            params![key.to_bytes().as_ref(), keccak256(value).as_slice()],
+        )?;
+        Ok(())
     }
 
-    /// Internal method for measuring the total amount of requestable data that the node is storing.
-    fn get_total_storage_usage_in_bytes_from_network(&self) -> Result<u64, ContentStoreError> {
+    /// Returns whether the stored content value is the one that was stored with a signature of a
+    /// trusted signer.
+    pub fn is_trusted(&self, key: &BeaconContentKey) -> Result<bool, ContentStoreError> {
+        let Some(value) = self.lookup_content_value(key)? else {
+            return Ok(false);
+        };
         let conn = self.sql_connection_pool.get()?;
+        let value_hash: Option<Vec<u8>> = conn
+            .query_row(
+                BEACON_TRUSTED_CONTENT_LOOKUP_QUERY,
+                [key.to_bytes().as_ref()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value_hash.is_some_and(|value_hash| value_hash == keccak256(&value).as_slice()))
+    }
 
-        let mut content_data_stmt = conn.prepare(TOTAL_DATA_SIZE_QUERY_BEACON)?;
-        let content_data_result = content_data_stmt.query_map([], |row| {
-            Ok(DataSize {
-                num_bytes: row.get(0)?,
-            })
-        });
+    /// Keeps the content regardless of the storage capacity, as long as the stored pinned content
+    /// fits the pinned budget. The content doesn't have to be stored yet.
+    ///
+    /// Only bootstraps and light client updates can be pinned. The latest finality and optimistic
+    /// updates are kept in memory and the historical summaries are only replaced by newer ones,
+    /// so they are never pruned.
+    pub fn pin(&mut self, key: BeaconContentKey) -> Result<PinnedContentInfo, ContentStoreError> {
+        if !matches!(
+            key,
+            BeaconContentKey::LightClientBootstrap(_)
+                | BeaconContentKey::LightClientUpdatesByRange(_)
+        ) {
+            return Err(ContentStoreError::InvalidData {
+                message: "Only bootstraps and light client updates can be pinned".to_string(),
+            });
+        }
+        if !self.pinned_keys.contains(&key) {
+            self.pinned_keys.push(key.clone());
+            let result = self.compute_pinned_bytes().and_then(|bytes| {
+                if bytes > self.pinned_capacity_bytes {
+                    return Err(ContentStoreError::InvalidData {
+                        message: format!(
+                            "Pinned content would use {bytes} bytes, more than the pinned budget \
+                             of {} bytes",
+                            self.pinned_capacity_bytes
+                        ),
+                    });
+                }
+                let conn = self.sql_connection_pool.get()?;
+                conn.execute(
+                    INSERT_OR_IGNORE_BEACON_PINNED_CONTENT_QUERY,
+                    [key.to_bytes().as_ref()],
+                )?;
+                Ok(bytes)
+            });
+            match result {
+                Ok(bytes) => self.pinned_bytes = bytes,
+                Err(err) => {
+                    self.pinned_keys.pop();
+                    return Err(err);
+                }
+            }
+        }
+        self.pinned_content_info()
+    }
+
+    /// Stops keeping the content regardless of the storage capacity. The content is pruned right
+    /// away if it doesn't fit the storage capacity.
+    pub fn unpin(
+        &mut self,
+        key: &BeaconContentKey,
+    ) -> Result<PinnedContentInfo, ContentStoreError> {
+        self.remove_pinned_key(key)?;
+        self.pinned_bytes = self.compute_pinned_bytes()?;
+        self.prune()?;
+        self.pinned_content_info()
+    }
+
+    /// Removes the pinned key from memory and from the database, without updating the size of the
+    /// pinned content.
+    fn remove_pinned_key(&mut self, key: &BeaconContentKey) -> Result<(), ContentStoreError> {
+        let conn = self.sql_connection_pool.get()?;
+        conn.execute(
+            DELETE_BEACON_PINNED_CONTENT_QUERY,
+            [key.to_bytes().as_ref()],
+        )?;
+        self.pinned_keys.retain(|pinned_key| pinned_key != key);
+        Ok(())
+    }
+
+    /// Returns the pinned keys persisted in the database, in the order they were pinned.
+    fn lookup_pinned_keys(&self) -> Result<Vec<BeaconContentKey>, ContentStoreError> {
+        let conn = self.sql_connection_pool.get()?;
+        let mut query = conn.prepare(BEACON_PINNED_CONTENT_KEYS_QUERY)?;
+        let keys: Vec<Vec<u8>> = query
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, rusqlite::Error>>()?;
+        keys.into_iter()
+            .map(|key| BeaconContentKey::try_from_bytes(key).map_err(ContentStoreError::from))
+            .collect()
+    }
+
+    /// Returns the pinned keys whose content is (partly) stored under the key.
+    fn pinned_keys_covering(&self, key: &BeaconContentKey) -> Vec<BeaconContentKey> {
+        self.pinned_keys
+            .iter()
+            .filter(|pinned_key| match (key, pinned_key) {
+                (
+                    BeaconContentKey::LightClientBootstrap(content_key),
+                    BeaconContentKey::LightClientBootstrap(pinned_content_key),
+                ) => content_key.block_hash == pinned_content_key.block_hash,
+                (
+                    BeaconContentKey::LightClientUpdatesByRange(content_key),
+                    BeaconContentKey::LightClientUpdatesByRange(pinned_content_key),
+                ) => {
+                    content_key.start_period
+                        < pinned_content_key
+                            .start_period
+                            .saturating_add(pinned_content_key.count)
+                        && pinned_content_key.start_period
+                            < content_key.start_period.saturating_add(content_key.count)
+                }
+                _ => false,
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Returns how many keys are pinned and how much of the pinned budget their content uses.
+    pub fn pinned_content_info(&self) -> Result<PinnedContentInfo, ContentStoreError> {
+        Ok(PinnedContentInfo {
+            pinned_keys: self.pinned_keys.len() as u64,
+            pinned_bytes: self.pinned_bytes,
+            pinned_capacity_bytes: self.pinned_capacity_bytes,
+        })
+    }
+
+    /// Returns the size of the stored pinned content. Light client updates pinned by overlapping
+    /// ranges are only counted once.
+    fn compute_pinned_bytes(&self) -> Result<u64, ContentStoreError> {
+        let conn = self.sql_connection_pool.get()?;
+        let mut pinned_bytes = 0.0;
+        let mut ranges = vec![];
+        for key in &self.pinned_keys {
+            match key {
+                BeaconContentKey::LightClientBootstrap(content_key) => {
+                    pinned_bytes += conn.query_row(
+                        LC_BOOTSTRAP_SIZE_LOOKUP_QUERY,
+                        [content_key.block_hash],
+                        |row| row.get::<_, f64>(0),
+                    )?;
+                }
+                BeaconContentKey::LightClientUpdatesByRange(content_key) => ranges.push((
+                    content_key.start_period,
+                    content_key.start_period.saturating_add(content_key.count),
+                )),
+                _ => {}
+            }
+        }
+        ranges.sort_unstable();
+        let mut merged_ranges: Vec<(u64, u64)> = vec![];
+        for (start, end) in ranges {
+            match merged_ranges.last_mut() {
+                Some((_, merged_end)) if start <= *merged_end => {
+                    *merged_end = (*merged_end).max(end)
+                }
+                _ => merged_ranges.push((start, end)),
+            }
+        }
+        for (start, end) in merged_ranges {
+            pinned_bytes += conn.query_row(LC_UPDATE_RANGE_SIZE_QUERY, [start, end], |row| {
+                row.get::<_, f64>(0)
+            })?;
+        }
+        Ok(pinned_bytes as u64)
+    }
+
+    fn is_pinned_bootstrap(&self, block_root: &[u8; 32]) -> bool {
+        self.pinned_keys.iter().any(|key| {
+            matches!(
+                key,
+                BeaconContentKey::LightClientBootstrap(content_key)
+                    if content_key.block_hash == *block_root
+            )
+        })
+    }
+
+    fn is_pinned_period(&self, period: u64) -> bool {
+        self.pinned_keys.iter().any(|key| {
+            matches!(
+                key,
+                BeaconContentKey::LightClientUpdatesByRange(content_key)
+                    if (content_key.start_period
+                        ..content_key.start_period.saturating_add(content_key.count))
+                        .contains(&period)
+            )
+        })
+    }
+
+    /// Deletes the oldest bootstraps that aren't pinned, and then the light client updates of the
+    /// oldest periods, until the content that isn't pinned fits the storage capacity. The pinned
+    /// content counts against the pinned budget instead.
+    fn prune(&mut self) -> Result<(), ContentStoreError> {
+        if self.storage_capacity_bytes == 0 {
+            return Ok(());
+        }
+        let mut unpinned_bytes = self
+            .get_total_storage_usage_in_bytes_from_network()?
+            .saturating_sub(self.pinned_bytes);
+        if unpinned_bytes <= self.storage_capacity_bytes {
+            return Ok(());
+        }
+
+        let bootstraps: Vec<(Vec<u8>, u64)> = {
+            let conn = self.sql_connection_pool.get()?;
+            let mut query = conn.prepare(LC_BOOTSTRAP_OLDEST_FIRST_QUERY)?;
+            let rows = query.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_, rusqlite::Error>>()?
+        };
+        for (block_root, size) in bootstraps {
+            if unpinned_bytes <= self.storage_capacity_bytes {
+                break;
+            }
+            let Ok(block_root) = <[u8; 32]>::try_from(block_root.as_slice()) else {
+                continue;
+            };
+            if self.is_pinned_bootstrap(&block_root) {
+                continue;
+            }
+            self.db_delete_lc_bootstrap(&block_root)?;
+            self.metrics.decrease_entry_count();
+            unpinned_bytes = unpinned_bytes.saturating_sub(size);
+        }
+
+        let updates: Vec<(u64, u64)> = {
+            let conn = self.sql_connection_pool.get()?;
+            let mut query = conn.prepare(LC_UPDATE_OLDEST_FIRST_QUERY)?;
+            let rows = query.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_, rusqlite::Error>>()?
+        };
+        for (period, size) in updates {
+            if unpinned_bytes <= self.storage_capacity_bytes {
+                break;
+            }
+            if self.is_pinned_period(period) {
+                continue;
+            }
+            self.db_delete_lc_update(period)?;
+            self.metrics.decrease_entry_count();
+            unpinned_bytes = unpinned_bytes.saturating_sub(size);
+        }
+
+        // Report the content size after pruning.
+        self.get_total_storage_usage_in_bytes_from_network()?;
+        Ok(())
+    }
+
+    pub fn paginate(
+        &self,
+        _offset: &u64,
+        _limit: &u64,
+    ) -> Result<PaginateLocalContentInfo<BeaconContentKey>, ContentStoreError> {
+        Err(ContentStoreError::Database(
+            "Paginate not implemented for Beacon storage".to_string(),
+        ))
+    }
+
+    /// Public method for determining how much actual disk space is being used to store this node's
+    /// Portal Network data. Intended for analysis purposes. PortalStorage's capacity
+    /// decision-making is not based off of this method.
+    pub fn get_total_storage_usage_in_bytes_on_disk(&self) -> Result<u64, ContentStoreError> {
+        let storage_usage = get_total_size_of_directory_in_bytes(&self.node_data_dir)?;
+        Ok(storage_usage)
+    }
+
+    /// Internal method for measuring the total amount of requestable data that the node is storing.
+    fn get_total_storage_usage_in_bytes_from_network(&self) -> Result<u64, ContentStoreError> {
+        let conn = self.sql_connection_pool.get()?;
+
+        let mut content_data_stmt = conn.prepare(TOTAL_DATA_SIZE_QUERY_BEACON)?;
+        let content_data_result = content_data_stmt.query_map([], |row| {
+            Ok(DataSize {
+                num_bytes: row.get(0)?,
+            })
+        });
         let content_data_sum = match content_data_result?.next() {
             Some(total) => total,
             None => {
@@ -655,21 +1241,426 @@ impl BeaconStorage {
             None => Ok(None),
         }
     }
+
+    /// Returns the stored historical summaries with proof, which are the latest that were accepted.
+    pub fn latest_historical_summaries(
+        &self,
+    ) -> anyhow::Result<Option<HistoricalSummariesWithProof>> {
+        let Some(value) = self.lookup_historical_summaries_value(0)? else {
+            return Ok(None);
+        };
+        let value =
+            ForkVersionedHistoricalSummariesWithProof::decode_for_chain(&value, &self.chain_config)
+                .map_err(|err| {
+                    anyhow::anyhow!("Stored historical summaries have invalid SSZ bytes: {err:?}")
+                })?;
+        Ok(Some(value.historical_summaries_with_proof))
+    }
+
+    /// Returns the size of the stored content and the savings from content dedup.
+    pub fn storage_info(&self) -> Result<StorageInfo, ContentStoreError> {
+        let content_bytes = self.get_total_storage_usage_in_bytes_from_network()?;
+
+        let conn = self.sql_connection_pool.get()?;
+        let (referenced_size, stored_size): (f64, f64) =
+            conn.query_row(BEACON_CONTENT_DEDUP_SIZE_QUERY, [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
+
+        Ok(StorageInfo {
+            content_dedup: self.content_dedup,
+            content_bytes,
+            dedup_savings_bytes: (referenced_size as u64).saturating_sub(stored_size as u64),
+        })
+    }
+
+    /// Removes the content blobs that no content key references anymore, e.g. after a store was
+    /// interrupted, and returns how many were removed and the bytes reclaimed.
+    ///
+    /// The blobs are counted and removed in the same transaction, so the result matches what was
+    /// removed, even if content is stored concurrently.
+    pub fn gc_orphans(&self) -> Result<GcOrphansInfo, ContentStoreError> {
+        let mut conn = self.sql_connection_pool.get()?;
+        let tx = conn.transaction()?;
+        let (orphan_count, orphan_size): (u64, f64) =
+            tx.query_row(UNREFERENCED_BEACON_CONTENT_BLOBS_SIZE_QUERY, [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
+        let removed_blobs = tx.execute(DELETE_UNREFERENCED_BEACON_CONTENT_BLOBS_QUERY, [])?;
+        tx.commit()?;
+        debug!(
+            removed_blobs,
+            orphan_count, "Removed orphaned content blobs"
+        );
+
+        Ok(GcOrphansInfo {
+            removed_blobs: removed_blobs as u64,
+            reclaimed_bytes: orphan_size as u64,
+        })
+    }
+
+    /// Returns the number of stored entries per content type. Every content type is stored in its
+    /// own table, or in memory, so no content key has to be classified.
+    pub fn content_type_counts(&self) -> Result<BeaconContentTypeCounts, ContentStoreError> {
+        let conn = self.sql_connection_pool.get()?;
+        let (light_client_bootstrap, light_client_update, historical_summaries_with_proof) =
+            conn.query_row(BEACON_CONTENT_TYPE_COUNT_QUERY, [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?;
+
+        Ok(BeaconContentTypeCounts {
+            light_client_bootstrap,
+            light_client_update,
+            light_client_optimistic_update: self.cache.optimistic_update.is_some().into(),
+            light_client_finality_update: self.cache.finality_update.is_some().into(),
+            historical_summaries_with_proof,
+        })
+    }
+
+    /// Returns the type, path and metadata of the database the content is stored in.
+    pub fn storage_backend_info(
+        &self,
+        redact_path: bool,
+    ) -> Result<StorageBackendInfo, ContentStoreError> {
+        storage_backend_info(&self.sql_connection_pool, redact_path)
+    }
+
+    /// Returns the latency percentiles of the most recent reads and writes.
+    pub fn latency_stats(&self) -> StoreLatencyStats {
+        let latencies = self.latencies.lock();
+        StoreLatencyStats {
+            get: latencies.get.percentiles(),
+            put: latencies.put.percentiles(),
+        }
+    }
+
+    /// Checks the core storage paths by writing a synthetic light client update and reading it
+    /// back, along with the content id derivation and the radius computation. The synthetic update
+    /// is removed afterwards.
+    ///
+    /// The synthetic update is stored under a period that real updates never reach, and the
+    /// self-test fails without writing anything if that period is somehow already stored.
+    pub fn self_test(&mut self) -> SelfTestInfo {
+        let value = [SELF_TEST_VALUE_PREFIX, B256::random().as_slice()].concat();
+        let mut inserted = false;
+        let mut checks = vec![run_self_test_check(SelfTestSubsystem::StorePut, || {
+            if self
+                .lookup_lc_update_period(SELF_TEST_PERIOD)
+                .map_err(|err| err.to_string())?
+                .is_some()
+            {
+                return Err(format!(
+                    "Synthetic update period {SELF_TEST_PERIOD} is already stored"
+                ));
+            }
+            self.db_insert_lc_update(&SELF_TEST_PERIOD, &value)
+                .map_err(|err| err.to_string())?;
+            inserted = true;
+            Ok(())
+        })];
+        checks.push(run_self_test_check(
+            SelfTestSubsystem::StoreGet,
+            || match self
+                .lookup_lc_update_value(SELF_TEST_PERIOD)
+                .map_err(|err| err.to_string())?
+            {
+                Some(stored) if stored == value => Ok(()),
+                Some(_) => Err("Read a different value than was written".to_string()),
+                None => Err("The written value wasn't found".to_string()),
+            },
+        ));
+        checks.push(run_self_test_check(SelfTestSubsystem::ContentId, || {
+            let key = BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                start_period: SELF_TEST_PERIOD,
+                count: 1,
+            });
+            if key.to_hex() != SELF_TEST_CONTENT_KEY {
+                return Err(format!("The content key was encoded as {}", key.to_hex()));
+            }
+            if key.content_id() != SELF_TEST_CONTENT_ID {
+                return Err(format!(
+                    "The content id was derived as {}",
+                    B256::from(key.content_id())
+                ));
+            }
+            let decoded = BeaconContentKey::try_from_hex(SELF_TEST_CONTENT_KEY)
+                .map_err(|err| err.to_string())?;
+            if decoded != key {
+                return Err(format!("The content key was decoded as {decoded}"));
+            }
+            Ok(())
+        }));
+        checks.push(run_self_test_check(SelfTestSubsystem::Radius, || {
+            let radius = self.recompute_radius().map_err(|err| err.to_string())?;
+            if radius != self.radius() {
+                return Err(format!(
+                    "Recomputed radius {radius} differs from the current radius {}",
+                    self.radius()
+                ));
+            }
+            if radius != Distance::MAX {
+                return Err(format!(
+                    "Unexpected radius {radius}, beacon storage accepts all content"
+                ));
+            }
+            Ok(())
+        }));
+        if inserted {
+            checks.push(run_self_test_check(SelfTestSubsystem::Cleanup, || {
+                self.db_delete_lc_update(SELF_TEST_PERIOD)
+                    .map_err(|err| err.to_string())
+            }));
+        }
+        SelfTestInfo {
+            passed: checks.iter().all(|check| check.passed),
+            checks,
+        }
+    }
+
+    /// Returns the keys of all the stored content. Light client updates are stored per period, so
+    /// they are returned as single period ranges.
+    fn stored_keys(&self) -> Result<Vec<BeaconContentKey>, ContentStoreError> {
+        let mut keys = vec![];
+        let conn = self.sql_connection_pool.get()?;
+        let mut query = conn.prepare(LC_BOOTSTRAP_BLOCK_ROOTS_QUERY)?;
+        for block_root in query.query_map([], |row| row.get::<_, Vec<u8>>(0))? {
+            let block_root = B256::try_from(block_root?.as_slice()).map_err(|err| {
+                ContentStoreError::Database(format!("Invalid bootstrap block root: {err}"))
+            })?;
+            keys.push(BeaconContentKey::LightClientBootstrap(
+                LightClientBootstrapKey {
+                    block_hash: block_root.0,
+                },
+            ));
+        }
+        let mut query = conn.prepare(LC_UPDATE_PERIODS_QUERY)?;
+        for period in query.query_map([], |row| row.get::<_, u64>(0))? {
+            keys.push(BeaconContentKey::LightClientUpdatesByRange(
+                LightClientUpdatesByRangeKey {
+                    start_period: period?,
+                    count: 1,
+                },
+            ));
+        }
+        let mut query = conn.prepare(HISTORICAL_SUMMARIES_EPOCHS_QUERY)?;
+        for epoch in query.query_map([], |row| row.get::<_, u64>(0))? {
+            keys.push(BeaconContentKey::HistoricalSummariesWithProof(
+                HistoricalSummariesWithProofKey { epoch: epoch? },
+            ));
+        }
+        if let Some(optimistic_update) = &self.cache.optimistic_update {
+            keys.push(BeaconContentKey::LightClientOptimisticUpdate(
+                LightClientOptimisticUpdateKey::new(*optimistic_update.update.signature_slot()),
+            ));
+        }
+        if let Some(finality_update) = &self.cache.finality_update {
+            keys.push(BeaconContentKey::LightClientFinalityUpdate(
+                LightClientFinalityUpdateKey::new(finality_update.get_finalized_slot()),
+            ));
+        }
+        Ok(keys)
+    }
+
+    /// Returns how many of the stored content ids are within the range `[start_id, end_id]`, the
+    /// fraction of the stored content that they make up, and the fraction of the content id space
+    /// that the range spans.
+    pub fn range_coverage(
+        &self,
+        start_id: &[u8; 32],
+        end_id: &[u8; 32],
+    ) -> Result<RangeCoverageInfo, ContentStoreError> {
+        let stored_keys = self.stored_keys()?;
+        let content_count = stored_keys
+            .iter()
+            .filter(|key| (start_id..=end_id).contains(&&key.content_id()))
+            .count();
+        let stored_fraction = if stored_keys.is_empty() {
+            0.0
+        } else {
+            content_count as f64 / stored_keys.len() as f64
+        };
+        // The width of the range, as a fraction of the content id space. The most significant limb
+        // of the width is precise enough for an f64.
+        let width = U256::from_be_bytes(*end_id) - U256::from_be_bytes(*start_id);
+        let range_fraction = width.as_limbs()[3] as f64 / 2f64.powi(64);
+        Ok(RangeCoverageInfo {
+            content_count: content_count as u64,
+            stored_fraction,
+            range_fraction,
+        })
+    }
+
+    /// Returns up to `limit` of the stored content keys whose content ids are the closest to the
+    /// target content id, ordered by increasing XOR distance.
+    ///
+    /// Beacon storage isn't indexed by content id, so all the stored content keys are scanned,
+    /// keeping only the closest ones in a bounded heap. Light client updates are returned as single
+    /// period ranges.
+    pub fn nearest_stored_keys(
+        &self,
+        content_id: &[u8; 32],
+        limit: usize,
+    ) -> Result<Vec<NearestStoredKeyInfo<BeaconContentKey>>, ContentStoreError> {
+        let mut nearest = BinaryHeap::with_capacity(limit + 1);
+        let mut add_key = |key: BeaconContentKey| {
+            let key_content_id = key.content_id();
+            nearest.push(NearKey {
+                distance: XorMetric::distance(&key_content_id, content_id),
+                content_id: key_content_id,
+                key,
+            });
+            if nearest.len() > limit {
+                nearest.pop();
+            }
+        };
+
+        for key in self.stored_keys()? {
+            add_key(key);
+        }
+
+        Ok(nearest
+            .into_sorted_vec()
+            .into_iter()
+            .map(|near_key| NearestStoredKeyInfo {
+                content_key: near_key.key,
+                content_id: B256::from(near_key.content_id),
+                distance: B256::from(near_key.distance.big_endian()),
+            })
+            .collect())
+    }
+
+    /// Returns a heuristic estimate of how soon the content will be pruned or replaced.
+    ///
+    /// Beacon storage doesn't prune by distance, so bootstraps and light client updates are kept
+    /// indefinitely if they are pinned or if the storage has no capacity limit. The other content
+    /// types are replaced by newer content, so their estimate is based on how often that content
+    /// was stored recently.
+    pub fn prune_estimate(
+        &self,
+        key: &BeaconContentKey,
+    ) -> Result<PruneEstimateInfo, ContentStoreError> {
+        if self.get(key)?.is_none() {
+            return Ok(PruneEstimateInfo {
+                estimate: PruneEstimate::NotStored,
+                reason: "Content is not stored locally".to_string(),
+            });
+        }
+
+        let (content_type, ingress_rate) = match key {
+            BeaconContentKey::LightClientBootstrap(_)
+            | BeaconContentKey::LightClientUpdatesByRange(_) => {
+                let (estimate, reason) = if self.pinned_keys.contains(key) {
+                    (
+                        PruneEstimate::Unlikely,
+                        "Content is pinned, so it's kept regardless of the storage capacity",
+                    )
+                } else if self.storage_capacity_bytes == 0 {
+                    (
+                        PruneEstimate::Unlikely,
+                        "Beacon storage has max radius and no capacity limit, and bootstraps and \
+                         light client updates are never replaced",
+                    )
+                } else {
+                    (
+                        PruneEstimate::Unknown,
+                        "Beacon storage prunes the oldest bootstraps and light client updates \
+                         that aren't pinned once it's full",
+                    )
+                };
+                return Ok(PruneEstimateInfo {
+                    estimate,
+                    reason: reason.to_string(),
+                });
+            }
+            BeaconContentKey::LightClientOptimisticUpdate(_) => {
+                ("optimistic update", &self.ingress_rates.optimistic_update)
+            }
+            BeaconContentKey::LightClientFinalityUpdate(_) => {
+                ("finality update", &self.ingress_rates.finality_update)
+            }
+            BeaconContentKey::HistoricalSummariesWithProof(_) => (
+                "historical summaries",
+                &self.ingress_rates.historical_summaries,
+            ),
+        };
+
+        let (Some(mean_interval), Some(last_insert)) =
+            (ingress_rate.mean_interval(), ingress_rate.last_insert())
+        else {
+            return Ok(PruneEstimateInfo {
+                estimate: PruneEstimate::Unknown,
+                reason: format!(
+                    "Content is replaced by the next {content_type}, but too few were stored \
+                     recently to estimate when"
+                ),
+            });
+        };
+        let remaining = mean_interval.saturating_sub(last_insert.elapsed());
+        Ok(PruneEstimateInfo {
+            estimate: PruneEstimate::WithinSeconds(remaining.as_secs()),
+            reason: format!(
+                "Content is replaced by the next {content_type}, which recently arrived every \
+                 {}s on average",
+                mean_interval.as_secs()
+            ),
+        })
+    }
+
     /// Get a summary of the current state of storage
     pub fn get_summary_info(&self) -> String {
         self.metrics.get_summary()
     }
 }
 
+/// A stored content key in the bounded heap of the keys nearest to a target content id. The keys
+/// are ordered by distance, so that the farthest key is at the top of the heap.
+struct NearKey {
+    distance: Distance,
+    content_id: [u8; 32],
+    key: BeaconContentKey,
+}
+
+impl PartialEq for NearKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.distance == other.distance
+    }
+}
+
+impl Eq for NearKey {}
+
+impl PartialOrd for NearKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NearKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance.cmp(&other.distance)
+    }
+}
+
+/// Runs a single check of the self-test, and times it.
+fn run_self_test_check(
+    subsystem: SelfTestSubsystem,
+    check: impl FnOnce() -> Result<(), String>,
+) -> SelfTestCheck {
+    let start = Instant::now();
+    let result = check();
+    SelfTestCheck {
+        subsystem,
+        passed: result.is_ok(),
+        duration_us: start.elapsed().as_micros() as u64,
+        error: result.err(),
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test {
     use ethportal_api::{
-        types::content_key::beacon::{
-            HistoricalSummariesWithProofKey, LightClientFinalityUpdateKey,
-            LightClientOptimisticUpdateKey,
-        },
-        LightClientBootstrapKey, LightClientUpdatesByRangeKey,
+        consensus::{chain_config::SEPOLIA_CHAIN_CONFIG, fork::ForkName},
+        types::{content_value::beacon::ForkVersionedLightClientUpdate, portal::StorageBackend},
     };
     use tree_hash::TreeHash;
     use trin_storage::test_utils::create_test_portal_storage_config_with_capacity;
@@ -696,6 +1687,299 @@ mod test {
         assert_eq!(result, value.as_ssz_bytes());
     }
 
+    #[test]
+    fn test_beacon_storage_verifies_content_on_read() {
+        let (_temp_dir, mut config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        config.verify_on_read = true;
+        let mut storage = BeaconStorage::new(config.clone()).unwrap();
+        let (key, value) = bootstrap_at_slot(1);
+        storage.put(key.clone(), value.as_ssz_bytes()).unwrap();
+        assert_eq!(storage.get(&key).unwrap().unwrap(), value.as_ssz_bytes());
+
+        // Replace the stored value with the bootstrap of another block.
+        let (_, other_value) = bootstrap_at_slot(2);
+        config
+            .sql_connection_pool
+            .get()
+            .unwrap()
+            .execute(
+                "UPDATE lc_bootstrap SET value = ?1",
+                params![other_value.as_ssz_bytes()],
+            )
+            .unwrap();
+        assert!(matches!(
+            storage.get(&key),
+            Err(ContentStoreError::Corrupted { .. })
+        ));
+
+        // Values that can't be decoded are corrupted as well.
+        config
+            .sql_connection_pool
+            .get()
+            .unwrap()
+            .execute("UPDATE lc_bootstrap SET value = ?1", params![vec![0u8; 8]])
+            .unwrap();
+        assert!(matches!(
+            storage.get(&key),
+            Err(ContentStoreError::Corrupted { .. })
+        ));
+
+        // Without the check, the corrupted content is returned as is.
+        config.verify_on_read = false;
+        let storage = BeaconStorage::new(config).unwrap();
+        assert_eq!(storage.get(&key).unwrap().unwrap(), vec![0u8; 8]);
+    }
+
+    #[test]
+    fn test_beacon_storage_entries_within_radius() {
+        let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        let mut storage = BeaconStorage::new(config).unwrap();
+        let mut keys = vec![];
+        for slot in [1, 2, 3] {
+            let (key, value) = bootstrap_at_slot(slot);
+            storage.put(key.clone(), value.as_ssz_bytes()).unwrap();
+            keys.push(key);
+        }
+        keys.sort_by_key(|key| key.content_id());
+        let node_id = NodeId::random();
+
+        let entries = storage
+            .entries_within_radius(&node_id, Distance::MAX, 10)
+            .unwrap();
+        assert_eq!(
+            entries.iter().map(|(key, _)| key).collect::<Vec<_>>(),
+            keys.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(entries[0].1, storage.get(&keys[0]).unwrap().unwrap());
+
+        let entries = storage
+            .entries_within_radius(&node_id, Distance::MAX, 2)
+            .unwrap();
+        assert_eq!(entries.len(), 2);
+
+        // The content of the node itself is within the zero radius.
+        let node_id = NodeId::new(&keys[1].content_id());
+        let entries = storage
+            .entries_within_radius(&node_id, Distance::ZERO, 10)
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, keys[1]);
+    }
+
+    #[test]
+    fn test_beacon_storage_trusted_content() {
+        let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        let mut storage = BeaconStorage::new(config).unwrap();
+        let (key, value) = bootstrap_at_slot(1);
+        assert!(!storage.is_trusted(&key).unwrap());
+
+        // Content that isn't stored isn't trusted.
+        storage.mark_trusted(&key, &value.as_ssz_bytes()).unwrap();
+        assert!(!storage.is_trusted(&key).unwrap());
+
+        storage.put(key.clone(), value.as_ssz_bytes()).unwrap();
+        assert!(storage.is_trusted(&key).unwrap());
+
+        // A signature over another value doesn't make the stored value trusted.
+        storage.mark_trusted(&key, &[0]).unwrap();
+        assert!(!storage.is_trusted(&key).unwrap());
+    }
+
+    fn bootstrap_at_slot(slot: u64) -> (BeaconContentKey, ForkVersionedLightClientBootstrap) {
+        let mut value = test_utils::get_light_client_bootstrap(0);
+        value.bootstrap.header_deneb_mut().unwrap().beacon.slot = slot;
+        let block_root = value
+            .bootstrap
+            .header_deneb()
+            .unwrap()
+            .beacon
+            .tree_hash_root();
+        let key = BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey {
+            block_hash: *block_root,
+        });
+        (key, value)
+    }
+
+    fn unpinned_bytes(storage: &BeaconStorage) -> u64 {
+        storage
+            .get_total_storage_usage_in_bytes_from_network()
+            .unwrap()
+            - storage.pinned_bytes
+    }
+
+    #[test]
+    fn test_beacon_storage_keeps_pinned_content_over_capacity() {
+        let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(1).unwrap();
+        let mut storage = BeaconStorage::new(config).unwrap();
+        let (key, value) = bootstrap_at_slot(0);
+        assert!(matches!(
+            storage.is_key_within_radius_and_unavailable(&key).unwrap(),
+            ShouldWeStoreContent::Store
+        ));
+        storage.put(key.clone(), value.as_ssz_bytes()).unwrap();
+        storage.pin(key.clone()).unwrap();
+        // Content can be pinned before it's stored.
+        let pinned_update_key =
+            BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                start_period: 0,
+                count: 1,
+            });
+        storage.pin(pinned_update_key.clone()).unwrap();
+
+        // Store light client updates beyond the capacity of 1 MB.
+        let lc_update = test_utils::get_light_client_update(0);
+        let update_value =
+            VariableList::<ForkVersionedLightClientUpdate, U128>::new(vec![lc_update]).unwrap();
+        for period in 0..64 {
+            let update_key =
+                BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                    start_period: period,
+                    count: 1,
+                });
+            storage
+                .put(update_key, update_value.as_ssz_bytes())
+                .unwrap();
+        }
+        assert!(
+            storage
+                .get_total_storage_usage_in_bytes_from_network()
+                .unwrap()
+                > storage.storage_capacity_bytes
+        );
+        assert!(unpinned_bytes(&storage) <= storage.storage_capacity_bytes);
+
+        // The pinned content is kept, while the oldest content that isn't pinned is pruned.
+        assert_eq!(storage.get(&key).unwrap().unwrap(), value.as_ssz_bytes());
+        assert!(storage.lookup_lc_update_period(0).unwrap().is_some());
+        assert!(storage.lookup_lc_update_period(1).unwrap().is_none());
+        assert!(storage.lookup_lc_update_period(63).unwrap().is_some());
+        assert_eq!(storage.radius(), Distance::MAX);
+
+        let info = storage.pinned_content_info().unwrap();
+        assert_eq!(info.pinned_keys, 2);
+        let bootstrap_size = 32 + value.as_ssz_bytes().len() + 8;
+        let update_size = update_value[0]
+            .encode_for_chain(&storage.chain_config)
+            .len();
+        assert_eq!(info.pinned_bytes, (bootstrap_size + update_size) as u64);
+    }
+
+    #[test]
+    fn test_beacon_storage_prunes_oldest_unpinned_bootstraps() {
+        let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(1).unwrap();
+        let mut storage = BeaconStorage::new(config).unwrap();
+        let bootstraps: Vec<_> = (0..64).map(bootstrap_at_slot).collect();
+        storage.pin(bootstraps[0].0.clone()).unwrap();
+        for (key, value) in &bootstraps {
+            storage.put(key.clone(), value.as_ssz_bytes()).unwrap();
+        }
+        assert!(unpinned_bytes(&storage) <= storage.storage_capacity_bytes);
+        assert!(storage.get(&bootstraps[0].0).unwrap().is_some());
+        assert!(storage.get(&bootstraps[1].0).unwrap().is_none());
+        assert!(storage.get(&bootstraps[63].0).unwrap().is_some());
+
+        // The unpinned bootstrap is the oldest one, so it's pruned once it counts against the
+        // capacity of the full storage.
+        let info = storage.unpin(&bootstraps[0].0).unwrap();
+        assert_eq!(info.pinned_keys, 0);
+        assert_eq!(info.pinned_bytes, 0);
+        assert!(storage.get(&bootstraps[0].0).unwrap().is_none());
+        assert!(unpinned_bytes(&storage) <= storage.storage_capacity_bytes);
+    }
+
+    #[test]
+    fn test_beacon_storage_unbounded_without_capacity() {
+        let (_temp_dir, mut config) = create_test_portal_storage_config_with_capacity(1).unwrap();
+        config.storage_capacity_bytes = 0;
+        let mut storage = BeaconStorage::new(config).unwrap();
+        let bootstraps: Vec<_> = (0..64).map(bootstrap_at_slot).collect();
+        for (key, value) in &bootstraps {
+            storage.put(key.clone(), value.as_ssz_bytes()).unwrap();
+        }
+        for (key, _) in &bootstraps {
+            assert!(storage.get(key).unwrap().is_some());
+        }
+    }
+
+    #[test]
+    fn test_beacon_storage_pinned_budget() {
+        let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        let mut storage = BeaconStorage::new(config).unwrap();
+        let (first_key, first_value) = bootstrap_at_slot(0);
+        let (second_key, second_value) = bootstrap_at_slot(1);
+        storage
+            .put(first_key.clone(), first_value.as_ssz_bytes())
+            .unwrap();
+        storage
+            .put(second_key.clone(), second_value.as_ssz_bytes())
+            .unwrap();
+        let info = storage.pin(first_key.clone()).unwrap();
+        assert!(info.pinned_bytes > 0);
+        storage.pinned_capacity_bytes = info.pinned_bytes;
+
+        // Pinning the same content twice doesn't count it twice.
+        assert_eq!(storage.pin(first_key.clone()).unwrap(), info);
+        let err = storage.pin(second_key.clone()).unwrap_err();
+        assert!(err.to_string().contains("pinned budget"), "{err}");
+        assert_eq!(storage.pinned_content_info().unwrap(), info);
+
+        storage.unpin(&first_key).unwrap();
+        assert_eq!(storage.pin(second_key).unwrap(), info);
+
+        // Only the latest finality update is stored, so it can't be pinned.
+        let finality_update_key =
+            BeaconContentKey::LightClientFinalityUpdate(LightClientFinalityUpdateKey {
+                finalized_slot: 0,
+            });
+        assert!(storage.pin(finality_update_key).is_err());
+    }
+
+    #[test]
+    fn test_beacon_storage_unpins_content_beyond_pinned_budget() {
+        let (_temp_dir, mut config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        config.pinned_capacity_bytes = 1;
+        let mut storage = BeaconStorage::new(config.clone()).unwrap();
+        let (key, value) = bootstrap_at_slot(0);
+        // The content isn't stored yet, so it fits the budget.
+        storage.pin(key.clone()).unwrap();
+        storage.put(key.clone(), value.as_ssz_bytes()).unwrap();
+        assert!(storage.get(&key).unwrap().is_some());
+        assert_eq!(storage.pinned_content_info().unwrap().pinned_keys, 0);
+
+        // The content stays unpinned after a restart.
+        drop(storage);
+        let storage = BeaconStorage::new(config).unwrap();
+        assert_eq!(storage.pinned_content_info().unwrap().pinned_keys, 0);
+    }
+
+    #[test]
+    fn test_beacon_storage_pins_persist_across_restarts() {
+        let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        let mut storage = BeaconStorage::new(config.clone()).unwrap();
+        let (bootstrap_key, value) = bootstrap_at_slot(0);
+        let update_key =
+            BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                start_period: 0,
+                count: 4,
+            });
+        storage
+            .put(bootstrap_key.clone(), value.as_ssz_bytes())
+            .unwrap();
+        storage.pin(bootstrap_key.clone()).unwrap();
+        let info = storage.pin(update_key.clone()).unwrap();
+        drop(storage);
+
+        let mut storage = BeaconStorage::new(config.clone()).unwrap();
+        assert_eq!(storage.pinned_keys, vec![bootstrap_key.clone(), update_key]);
+        assert_eq!(storage.pinned_content_info().unwrap(), info);
+
+        storage.unpin(&bootstrap_key).unwrap();
+        drop(storage);
+        let storage = BeaconStorage::new(config).unwrap();
+        assert_eq!(storage.pinned_content_info().unwrap().pinned_keys, 1);
+        assert_eq!(storage.pinned_content_info().unwrap().pinned_bytes, 0);
+    }
+
     #[test]
     fn test_beacon_storage_get_put_updates_by_range() {
         let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
@@ -738,6 +2022,171 @@ mod test {
         assert_eq!(result_1, expected_value_1.as_ssz_bytes());
     }
 
+    #[test]
+    fn test_beacon_storage_content_type_counts() {
+        let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        let mut storage = BeaconStorage::new(config).unwrap();
+        assert_eq!(
+            storage.content_type_counts().unwrap(),
+            BeaconContentTypeCounts::default()
+        );
+
+        let key = BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+            start_period: 1,
+            count: 2,
+        });
+        let value = VariableList::<ForkVersionedLightClientUpdate, U128>::new(vec![
+            test_utils::get_light_client_update(0),
+            test_utils::get_light_client_update(1),
+        ])
+        .unwrap();
+        storage.put(key, value.as_ssz_bytes()).unwrap();
+        let value = test_utils::get_light_client_optimistic_update(0);
+        let key = BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey {
+            signature_slot: *value.update.signature_slot(),
+        });
+        storage.put(key, value.as_ssz_bytes()).unwrap();
+
+        assert_eq!(
+            storage.content_type_counts().unwrap(),
+            BeaconContentTypeCounts {
+                light_client_update: 2,
+                light_client_optimistic_update: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_beacon_storage_content_dedup() {
+        let (_temp_dir, mut config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        config.content_dedup = true;
+        let mut storage = BeaconStorage::new(config).unwrap();
+        let lc_update = test_utils::get_light_client_update(0);
+        let value =
+            VariableList::<ForkVersionedLightClientUpdate, U128>::new(vec![lc_update.clone()])
+                .unwrap();
+
+        // Store the same update under two different periods
+        for start_period in [1, 2] {
+            let key = BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                start_period,
+                count: 1,
+            });
+            storage.put(key.clone(), value.as_ssz_bytes()).unwrap();
+            let result = storage.get(&key).unwrap().unwrap();
+            assert_eq!(result, value.as_ssz_bytes());
+        }
+
+        let lc_update_size = lc_update.as_ssz_bytes().len() as u64;
+        let info = storage.storage_info().unwrap();
+        assert!(info.content_dedup);
+        assert_eq!(info.content_bytes, 2 * lc_update_size);
+        // One copy of the update is saved, at the cost of storing its hash three times.
+        assert_eq!(info.dedup_savings_bytes, lc_update_size - 3 * 32);
+    }
+
+    #[test]
+    fn test_beacon_storage_content_dedup_reads_content_stored_without_dedup() {
+        let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        let value = test_utils::get_light_client_bootstrap(0);
+        let block_root = value
+            .bootstrap
+            .header_deneb()
+            .unwrap()
+            .beacon
+            .tree_hash_root();
+        let key = BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey {
+            block_hash: *block_root,
+        });
+        let mut storage = BeaconStorage::new(config.clone()).unwrap();
+        storage.put(key.clone(), value.as_ssz_bytes()).unwrap();
+
+        let storage = BeaconStorage::new(PortalStorageConfig {
+            content_dedup: true,
+            ..config
+        })
+        .unwrap();
+        let result = storage.get(&key).unwrap().unwrap();
+        assert_eq!(result, value.as_ssz_bytes());
+        assert_eq!(storage.storage_info().unwrap().dedup_savings_bytes, 0);
+    }
+
+    #[test]
+    fn test_beacon_storage_content_dedup_replace_historical_summaries() {
+        let (_temp_dir, mut config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        config.content_dedup = true;
+        let pool = config.sql_connection_pool.clone();
+        let mut storage = BeaconStorage::new(config).unwrap();
+        let (value, _) = test_utils::get_history_summaries_with_proof();
+        let epoch = value.historical_summaries_with_proof.epoch;
+        let key = BeaconContentKey::HistoricalSummariesWithProof(HistoricalSummariesWithProofKey {
+            epoch,
+        });
+        storage.put(key.clone(), value.as_ssz_bytes()).unwrap();
+
+        let mut newer_value = value.clone();
+        newer_value.historical_summaries_with_proof.epoch = epoch + 1;
+        let newer_key =
+            BeaconContentKey::HistoricalSummariesWithProof(HistoricalSummariesWithProofKey {
+                epoch: epoch + 1,
+            });
+        storage
+            .put(newer_key.clone(), newer_value.as_ssz_bytes())
+            .unwrap();
+        let result = storage.get(&newer_key).unwrap().unwrap();
+        assert_eq!(result, newer_value.as_ssz_bytes());
+
+        // The blob of the replaced historical summaries is removed
+        let blob_count: u64 = pool
+            .get()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM beacon_content_blob", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(blob_count, 1);
+    }
+
+    #[test]
+    fn test_beacon_storage_gc_orphans() {
+        let (_temp_dir, mut config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        config.content_dedup = true;
+        let pool = config.sql_connection_pool.clone();
+        let mut storage = BeaconStorage::new(config).unwrap();
+        let value = VariableList::<ForkVersionedLightClientUpdate, U128>::new(vec![
+            test_utils::get_light_client_update(0),
+        ])
+        .unwrap();
+        let key = BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+            start_period: 1,
+            count: 1,
+        });
+        storage.put(key.clone(), value.as_ssz_bytes()).unwrap();
+
+        // A blob that no content key references, as left by an interrupted store.
+        let orphan = vec![0xab; 100];
+        pool.get()
+            .unwrap()
+            .execute(
+                INSERT_BEACON_CONTENT_BLOB_QUERY,
+                params![keccak256(&orphan).as_slice(), orphan],
+            )
+            .unwrap();
+
+        let info = storage.gc_orphans().unwrap();
+        assert_eq!(
+            info,
+            GcOrphansInfo {
+                removed_blobs: 1,
+                reclaimed_bytes: 100 + 32,
+            }
+        );
+        // The referenced content is kept, and there is nothing left to remove.
+        assert_eq!(storage.get(&key).unwrap().unwrap(), value.as_ssz_bytes());
+        assert_eq!(storage.gc_orphans().unwrap(), GcOrphansInfo::default());
+    }
+
     #[test]
     fn test_beacon_storage_get_put_finality_update() {
         let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
@@ -777,6 +2226,51 @@ mod test {
         assert_eq!(result, value.as_ssz_bytes());
     }
 
+    #[test]
+    fn test_beacon_storage_serves_content_of_past_forks() {
+        let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        let chain_config = SEPOLIA_CHAIN_CONFIG.clone();
+        let mut storage = BeaconStorage::new_for_chain(config, chain_config.clone()).unwrap();
+        let prefixed = |fork_name: ForkName, type_name: &str| {
+            let bytes = std::fs::read(format!(
+                "../test_assets/beacon/{fork_name}/{type_name}/ssz_random/case_0/serialized.ssz_snappy"
+            ))
+            .unwrap();
+            let bytes = snap::raw::Decoder::new().decompress_vec(&bytes).unwrap();
+            [
+                chain_config.fork_digest(fork_name).as_slice(),
+                bytes.as_slice(),
+            ]
+            .concat()
+        };
+
+        // The updates on both sides of the Deneb fork keep the fork digests of the chain.
+        let key = BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+            start_period: 0,
+            count: 2,
+        });
+        let value = vec![
+            prefixed(ForkName::Capella, "LightClientUpdate"),
+            prefixed(ForkName::Deneb, "LightClientUpdate"),
+        ]
+        .as_ssz_bytes();
+        storage.put(key.clone(), &value).unwrap();
+        assert_eq!(storage.get(&key).unwrap().unwrap(), value);
+
+        // The finality update of a past fork is served as well.
+        let value = prefixed(ForkName::Capella, "LightClientFinalityUpdate");
+        let finalized_slot =
+            ForkVersionedLightClientFinalityUpdate::decode_for_chain(&value, &chain_config)
+                .unwrap()
+                .update
+                .finalized_slot();
+        let key = BeaconContentKey::LightClientFinalityUpdate(LightClientFinalityUpdateKey {
+            finalized_slot,
+        });
+        storage.put(key.clone(), &value).unwrap();
+        assert_eq!(storage.get(&key).unwrap().unwrap(), value);
+    }
+
     #[test]
     fn test_beacon_storage_get_put_optimistic_update() {
         let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
@@ -854,6 +2348,12 @@ mod test {
         let result = storage.get(&key).unwrap();
         assert_eq!(result, None);
 
+        // The stored historical summaries are the latest ones
+        assert_eq!(
+            storage.latest_historical_summaries().unwrap(),
+            Some(value.historical_summaries_with_proof)
+        );
+
         // Test getting the latest historical summaries with proof
         let key = BeaconContentKey::HistoricalSummariesWithProof(HistoricalSummariesWithProofKey {
             epoch: 0,
@@ -861,4 +2361,268 @@ mod test {
         let result = storage.get(&key).unwrap().unwrap();
         assert_eq!(result, value.as_ssz_bytes());
     }
+
+    #[test]
+    fn test_beacon_storage_nearest_stored_keys() {
+        let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        let mut storage = BeaconStorage::new(config).unwrap();
+        let updates = VariableList::<ForkVersionedLightClientUpdate, U128>::new(vec![
+            test_utils::get_light_client_update(0),
+            test_utils::get_light_client_update(1),
+        ])
+        .unwrap();
+        let updates_key =
+            BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                start_period: 1,
+                count: 2,
+            });
+        storage.put(updates_key, updates.as_ssz_bytes()).unwrap();
+        let optimistic_update = test_utils::get_light_client_optimistic_update(0);
+        let optimistic_key =
+            BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey {
+                signature_slot: *optimistic_update.update.signature_slot(),
+            });
+        storage
+            .put(optimistic_key.clone(), optimistic_update.as_ssz_bytes())
+            .unwrap();
+
+        let target = optimistic_key.content_id();
+        let nearest = storage.nearest_stored_keys(&target, 10).unwrap();
+        assert_eq!(nearest.len(), 3);
+        assert_eq!(nearest[0].content_key, optimistic_key);
+        assert_eq!(nearest[0].distance, B256::ZERO);
+        assert!(nearest
+            .windows(2)
+            .all(|keys| keys[0].distance < keys[1].distance));
+        for key in &nearest {
+            assert_eq!(key.content_id, B256::from(key.content_key.content_id()));
+        }
+        assert!(nearest[1..].iter().all(|key| matches!(
+            key.content_key,
+            BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                count: 1,
+                ..
+            })
+        )));
+
+        let limited = storage.nearest_stored_keys(&target, 2).unwrap();
+        assert_eq!(limited, nearest[..2]);
+    }
+
+    #[test]
+    fn test_beacon_storage_range_coverage() {
+        let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        let mut storage = BeaconStorage::new(config).unwrap();
+        let full_range = storage.range_coverage(&[0; 32], &[0xff; 32]).unwrap();
+        assert_eq!(full_range.content_count, 0);
+        assert_eq!(full_range.stored_fraction, 0.0);
+        assert_eq!(full_range.range_fraction, 1.0);
+
+        let optimistic_update = test_utils::get_light_client_optimistic_update(0);
+        let optimistic_key =
+            BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey {
+                signature_slot: *optimistic_update.update.signature_slot(),
+            });
+        storage
+            .put(optimistic_key.clone(), optimistic_update.as_ssz_bytes())
+            .unwrap();
+        let updates = VariableList::<ForkVersionedLightClientUpdate, U128>::new(vec![
+            test_utils::get_light_client_update(0),
+        ])
+        .unwrap();
+        let updates_key =
+            BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                start_period: 1,
+                count: 1,
+            });
+        storage
+            .put(updates_key.clone(), updates.as_ssz_bytes())
+            .unwrap();
+
+        let full_range = storage.range_coverage(&[0; 32], &[0xff; 32]).unwrap();
+        assert_eq!(full_range.content_count, 2);
+        assert_eq!(full_range.stored_fraction, 1.0);
+
+        let content_id = optimistic_key.content_id();
+        let single_id = storage.range_coverage(&content_id, &content_id).unwrap();
+        assert_eq!(single_id.content_count, 1);
+        assert_eq!(single_id.stored_fraction, 0.5);
+        assert_eq!(single_id.range_fraction, 0.0);
+
+        let mut lower_half_end = [0xff; 32];
+        lower_half_end[0] = 0x7f;
+        let lower_half = storage.range_coverage(&[0; 32], &lower_half_end).unwrap();
+        assert_eq!(lower_half.range_fraction, 0.5);
+        let expected_count = [content_id, updates_key.content_id()]
+            .iter()
+            .filter(|id| id[0] < 0x80)
+            .count() as u64;
+        assert_eq!(lower_half.content_count, expected_count);
+    }
+
+    #[test]
+    fn test_beacon_storage_prune_estimate() {
+        let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        let mut storage = BeaconStorage::new(config).unwrap();
+
+        let bootstrap = test_utils::get_light_client_bootstrap(0);
+        let bootstrap_key = BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey {
+            block_hash: *bootstrap
+                .bootstrap
+                .header_deneb()
+                .unwrap()
+                .beacon
+                .tree_hash_root(),
+        });
+        let estimate = storage.prune_estimate(&bootstrap_key).unwrap().estimate;
+        assert_eq!(estimate, PruneEstimate::NotStored);
+        storage
+            .put(bootstrap_key.clone(), bootstrap.as_ssz_bytes())
+            .unwrap();
+        let estimate = storage.prune_estimate(&bootstrap_key).unwrap().estimate;
+        assert_eq!(estimate, PruneEstimate::Unknown);
+        storage.pin(bootstrap_key.clone()).unwrap();
+        let estimate = storage.prune_estimate(&bootstrap_key).unwrap().estimate;
+        assert_eq!(estimate, PruneEstimate::Unlikely);
+
+        // A single optimistic update isn't enough to estimate the ingress rate
+        let update = test_utils::get_light_client_optimistic_update(0);
+        let update_key =
+            BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey {
+                signature_slot: *update.update.signature_slot(),
+            });
+        storage
+            .put(update_key.clone(), update.as_ssz_bytes())
+            .unwrap();
+        let estimate = storage.prune_estimate(&update_key).unwrap().estimate;
+        assert_eq!(estimate, PruneEstimate::Unknown);
+
+        storage
+            .put(update_key.clone(), update.as_ssz_bytes())
+            .unwrap();
+        let estimate = storage.prune_estimate(&update_key).unwrap().estimate;
+        assert_eq!(estimate, PruneEstimate::WithinSeconds(0));
+    }
+
+    #[test]
+    fn test_ingress_rate_keeps_recent_samples() {
+        let mut rate = IngressRate::default();
+        assert_eq!(rate.mean_interval(), None);
+        rate.record();
+        assert_eq!(rate.mean_interval(), None);
+        for _ in 0..INGRESS_RATE_SAMPLES * 2 {
+            rate.record();
+        }
+        assert_eq!(rate.inserts.len(), INGRESS_RATE_SAMPLES);
+        assert!(rate.mean_interval().is_some());
+    }
+
+    #[test]
+    fn test_latency_window_percentiles() {
+        let mut window = LatencyWindow::default();
+        assert_eq!(window.percentiles(), LatencyPercentiles::default());
+
+        // Only the most recent samples are kept, so the first (slowest) ones are dropped.
+        for micros in (1..=LATENCY_SAMPLES as u64).map(|micros| micros + 10_000) {
+            window.record(Duration::from_micros(micros));
+        }
+        for micros in 1..=100 {
+            window.record(Duration::from_micros(micros));
+        }
+
+        let percentiles = window.percentiles();
+        assert_eq!(percentiles.samples, LATENCY_SAMPLES as u64);
+        assert_eq!(percentiles.p50_us, Some(10_512));
+        assert_eq!(percentiles.p99_us, Some(11_014));
+    }
+
+    #[test]
+    fn test_latency_stats_records_get_and_put() {
+        let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        let mut storage = BeaconStorage::new(config).unwrap();
+        let value = test_utils::get_light_client_finality_update(0);
+        let finalized_slot = value.update.finalized_header_deneb().unwrap().beacon.slot;
+        let key = BeaconContentKey::LightClientFinalityUpdate(LightClientFinalityUpdateKey {
+            finalized_slot,
+        });
+        storage.put(key.clone(), value.as_ssz_bytes()).unwrap();
+        storage.get(&key).unwrap();
+        storage.get(&key).unwrap();
+
+        let stats = storage.latency_stats();
+        assert_eq!(stats.get.samples, 2);
+        assert_eq!(stats.put.samples, 1);
+        assert!(stats.put.p99_us.is_some());
+    }
+
+    #[test]
+    fn test_beacon_storage_backend_info() {
+        let (temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        let storage = BeaconStorage::new(config).unwrap();
+
+        let info = storage.storage_backend_info(false).unwrap();
+        assert_eq!(info.backend, StorageBackend::Sqlite);
+        let path = PathBuf::from(info.path.unwrap());
+        assert!(path.starts_with(temp_dir.path()));
+        assert!(info.page_size > 0);
+
+        let info = storage.storage_backend_info(true).unwrap();
+        assert_eq!(
+            info.path,
+            Some(path.file_name().unwrap().to_string_lossy().to_string())
+        );
+    }
+
+    #[test]
+    fn test_beacon_storage_self_test() {
+        let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        let mut storage = BeaconStorage::new(config).unwrap();
+
+        let info = storage.self_test();
+        assert!(info.passed, "{info:?}");
+        let subsystems: Vec<SelfTestSubsystem> =
+            info.checks.iter().map(|check| check.subsystem).collect();
+        assert_eq!(
+            subsystems,
+            vec![
+                SelfTestSubsystem::StorePut,
+                SelfTestSubsystem::StoreGet,
+                SelfTestSubsystem::ContentId,
+                SelfTestSubsystem::Radius,
+                SelfTestSubsystem::Cleanup,
+            ]
+        );
+        assert!(storage
+            .lookup_lc_update_period(SELF_TEST_PERIOD)
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            storage
+                .get_total_storage_usage_in_bytes_from_network()
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_beacon_storage_self_test_keeps_colliding_update() {
+        let (_temp_dir, config) = create_test_portal_storage_config_with_capacity(10).unwrap();
+        let mut storage = BeaconStorage::new(config).unwrap();
+        storage
+            .db_insert_lc_update(&SELF_TEST_PERIOD, &[1, 2, 3])
+            .unwrap();
+
+        let info = storage.self_test();
+        assert!(!info.passed);
+        assert_eq!(info.checks[0].subsystem, SelfTestSubsystem::StorePut);
+        assert!(!info.checks[0].passed);
+        assert!(info
+            .checks
+            .iter()
+            .all(|check| check.subsystem != SelfTestSubsystem::Cleanup));
+        assert_eq!(
+            storage.lookup_lc_update_value(SELF_TEST_PERIOD).unwrap(),
+            Some(vec![1, 2, 3])
+        );
+    }
 }
@@ -1,10 +1,15 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
 
 use alloy::primitives::B256;
-use ethportal_api::BeaconContentKey;
+use ethportal_api::{
+    consensus::chain_config::ChainConfig, light_client::bootstrap::LightClientBootstrapDeneb,
+    BeaconContentKey,
+};
 use light_client::{
-    config::networks, consensus::rpc::portal_rpc::PortalRpc, database::FileDB, Client,
-    ClientBuilder,
+    config::{client_config::Config, networks},
+    consensus::rpc::portal_rpc::PortalRpc,
+    database::FileDB,
+    Client, ClientBuilder,
 };
 use portalnet::overlay::command::OverlayCommand;
 use tokio::sync::mpsc::UnboundedSender;
@@ -13,22 +18,38 @@ use tracing::{error, info};
 #[derive(Clone, Debug)]
 pub struct BeaconSync {
     pub overlay_tx: UnboundedSender<OverlayCommand<BeaconContentKey>>,
+    pub chain_config: Arc<ChainConfig>,
 }
 
 impl BeaconSync {
-    pub fn new(overlay_tx: UnboundedSender<OverlayCommand<BeaconContentKey>>) -> Self {
-        Self { overlay_tx }
+    pub fn new(
+        overlay_tx: UnboundedSender<OverlayCommand<BeaconContentKey>>,
+        chain_config: Arc<ChainConfig>,
+    ) -> Self {
+        Self {
+            overlay_tx,
+            chain_config,
+        }
     }
 
+    /// Starts syncing the light client from the trusted block root. The bootstrap for the trusted
+    /// block root is looked up on the network, unless it is available locally.
     pub async fn start(
         &self,
         trusted_block_root: B256,
+        local_bootstrap: Option<LightClientBootstrapDeneb>,
     ) -> anyhow::Result<Client<FileDB, PortalRpc>> {
         // Create a new Light Client Builder
         let mut builder = ClientBuilder::new();
 
-        // Set the network to mainnet
-        builder = builder.network(networks::Network::Mainnet);
+        // Set the chain that the light client follows
+        let base_config = networks::from_chain_config(&self.chain_config);
+        builder = builder.config(Config {
+            chain: base_config.chain,
+            forks: base_config.forks,
+            max_checkpoint_age: base_config.max_checkpoint_age,
+            ..Default::default()
+        });
 
         // Set the checkpoint to the last known checkpoint
         builder = builder.checkpoint(&trusted_block_root.to_string());
@@ -37,7 +58,11 @@ impl BeaconSync {
         builder = builder.data_dir(PathBuf::from("/tmp/portal-light-client"));
 
         // Build Portal rpc
-        let portal_rpc = PortalRpc::with_portal(self.overlay_tx.clone());
+        let mut portal_rpc =
+            PortalRpc::with_portal(self.overlay_tx.clone(), self.chain_config.clone());
+        if let Some(bootstrap) = local_bootstrap {
+            portal_rpc = portal_rpc.with_bootstrap(bootstrap);
+        }
 
         // Build the client
         let mut client: Client<FileDB, PortalRpc> = builder
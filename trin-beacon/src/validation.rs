@@ -1,10 +1,13 @@
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Arc,
+};
 
 use alloy::primitives::B256;
 use anyhow::anyhow;
 use chrono::Duration;
 use ethportal_api::{
-    consensus::fork::ForkName,
+    consensus::{chain_config::ChainConfig, fork::ForkName},
     light_client::{
         finality_update::LightClientFinalityUpdate, optimistic_update::LightClientOptimisticUpdate,
         update::LightClientUpdate,
@@ -12,22 +15,21 @@ use ethportal_api::{
     types::{
         content_key::beacon::HistoricalSummariesWithProofKey,
         content_value::beacon::{
-            ForkVersionedHistoricalSummariesWithProof, ForkVersionedLightClientBootstrap,
-            ForkVersionedLightClientFinalityUpdate, ForkVersionedLightClientOptimisticUpdate,
-            LightClientUpdatesByRange,
+            BeaconContentValue, ForkVersionedHistoricalSummariesWithProof,
+            ForkVersionedLightClientBootstrap, ForkVersionedLightClientFinalityUpdate,
+            ForkVersionedLightClientOptimisticUpdate, LightClientUpdatesByRange,
+        },
+        portal::{
+            TaggedContentInfo, ValidationMode, ValidationReportInfo, ValidationStepInfo,
+            ValidationStepStatus,
         },
     },
     BeaconContentKey,
 };
-use light_client::{
-    config::Network,
-    consensus::{
-        rpc::portal_rpc::expected_current_slot, types::GenericUpdate, verify_generic_update,
-    },
-};
-use ssz::Decode;
+use light_client::consensus::{types::GenericUpdate, verify_generic_update};
+use parking_lot::{Mutex, RwLock as PLRwLock};
 use tokio::sync::RwLock;
-use tracing::debug;
+use tracing::{debug, warn};
 use tree_hash::TreeHash;
 use trin_validation::{
     merkle::proof::verify_merkle_proof,
@@ -35,229 +37,281 @@ use trin_validation::{
     validator::{ValidationResult, Validator},
 };
 
+/// The max number of tagged content items that are remembered.
+const MAX_TAGGED_CONTENT: usize = 64;
+
 pub struct BeaconValidator {
     // TODO: HeaderOracle is not network agnostic name
     pub header_oracle: Arc<RwLock<HeaderOracle>>,
-    pub genesis_root: Vec<u8>,
-    pub fork_version: Vec<u8>,
+    /// The chain that the content belongs to
+    pub chain_config: Arc<ChainConfig>,
+    mode: PLRwLock<ValidationMode>,
+    /// The content accepted in lenient mode despite failing non-critical checks, the most recent
+    /// first.
+    tagged: Mutex<VecDeque<TaggedContentInfo<BeaconContentKey>>>,
 }
 
 impl BeaconValidator {
-    pub fn new(header_oracle: Arc<RwLock<HeaderOracle>>) -> Self {
-        let light_client_network = Network::Mainnet;
-        let light_client_config = light_client_network.to_base_config();
-        let genesis_root = light_client_config.chain.genesis_root;
-        let fork_version = light_client_config.forks.deneb.fork_version;
+    pub fn new(header_oracle: Arc<RwLock<HeaderOracle>>, chain_config: Arc<ChainConfig>) -> Self {
         Self {
             header_oracle,
-            genesis_root,
-            fork_version,
+            chain_config,
+            mode: PLRwLock::new(ValidationMode::default()),
+            tagged: Mutex::new(VecDeque::with_capacity(MAX_TAGGED_CONTENT)),
         }
     }
-}
 
-impl Validator<BeaconContentKey> for BeaconValidator {
-    async fn validate_content(
+    pub fn mode(&self) -> ValidationMode {
+        *self.mode.read()
+    }
+
+    pub fn set_mode(&self, mode: ValidationMode) {
+        *self.mode.write() = mode;
+    }
+
+    /// Returns the content recently accepted despite failing non-critical checks, the most recent
+    /// first.
+    pub fn tagged(&self) -> Vec<TaggedContentInfo<BeaconContentKey>> {
+        self.tagged.lock().iter().cloned().collect()
+    }
+
+    /// Records content that was accepted despite failing a non-critical check.
+    fn tag(&self, content_key: &BeaconContentKey, reason: &anyhow::Error) {
+        warn!(
+            content.key = %content_key,
+            reason = %reason,
+            "Accepting content that failed non-critical validation in lenient mode"
+        );
+        let mut tagged = self.tagged.lock();
+        if tagged.len() >= MAX_TAGGED_CONTENT {
+            tagged.pop_back();
+        }
+        tagged.push_front(TaggedContentInfo {
+            content_key: content_key.clone(),
+            reason: reason.to_string(),
+        });
+    }
+
+    /// Checks the content strictly, regardless of the validation mode, and without tagging it.
+    pub async fn verify(
         &self,
         content_key: &BeaconContentKey,
         content: &[u8],
-    ) -> anyhow::Result<ValidationResult<BeaconContentKey>> {
+    ) -> anyhow::Result<()> {
+        self.check_content(content_key, content, &mut ValidationSteps::default())
+            .await?
+    }
+
+    /// Checks the content strictly, like [Self::verify], and reports the outcome of each check,
+    /// together with the roots computed for them.
+    pub async fn report(
+        &self,
+        content_key: &BeaconContentKey,
+        content: &[u8],
+    ) -> ValidationReportInfo {
+        let mut steps = ValidationSteps::default();
+        // The outcome is recorded in the steps.
+        let _ = self.check_content(content_key, content, &mut steps).await;
+        steps.into_report()
+    }
+
+    /// Checks the content, returning an error if a critical check fails, i.e. if the content can't
+    /// be decoded or doesn't match its content key. The result of the non-critical checks, which
+    /// verify the content against the light client and the finalized state, is returned
+    /// separately.
+    async fn check_content(
+        &self,
+        content_key: &BeaconContentKey,
+        content: &[u8],
+        steps: &mut ValidationSteps,
+    ) -> anyhow::Result<anyhow::Result<()>> {
         match content_key {
             BeaconContentKey::LightClientBootstrap(_) => {
-                let bootstrap = ForkVersionedLightClientBootstrap::from_ssz_bytes(content)
+                let bootstrap = steps.check(
+                    "decode",
+                    true,
+                    ForkVersionedLightClientBootstrap::decode_for_chain(
+                        content,
+                        &self.chain_config,
+                    )
                     .map_err(|err| {
                         anyhow!(
                             "Fork versioned light client bootstrap has invalid SSZ bytes: {:?}",
                             err
                         )
-                    })?;
-
-                // Check if the light client bootstrap slot is ole than 4 months
-                let four_months = Duration::days(30 * 4);
-                let four_months_in_senonds = four_months.num_seconds();
-                let four_months_in_slots: u64 = (four_months_in_senonds / 12) as u64;
-                let four_months_ago_slot = expected_current_slot() - four_months_in_slots;
-                let bootstrap_slot = bootstrap.get_slot();
-
-                if bootstrap_slot < four_months_ago_slot {
-                    return Err(anyhow!(
-                        "Light client bootstrap slot is too old: {}",
-                        bootstrap_slot
-                    ));
-                }
+                    }),
+                )?;
 
-                let finalized_header = self.header_oracle.read().await.get_finalized_header().await;
-                let bootstrap_block_header = bootstrap.bootstrap.get_beacon_block_header();
-
-                if let Ok(finalized_header) = finalized_header {
-                    if finalized_header != bootstrap_block_header {
-                        return Err(anyhow!(
-                            "Light client bootstrap header does not match the finalized header: {finalized_header:?} != {bootstrap_block_header:?}",
-                        ));
-                    }
-                }
+                Ok(self.verify_bootstrap(&bootstrap, steps).await)
             }
             BeaconContentKey::LightClientUpdatesByRange(key) => {
-                let lc_updates =
-                    LightClientUpdatesByRange::from_ssz_bytes(content).map_err(|err| {
-                        anyhow!(
-                            "Light client updates by range has invalid SSZ bytes: {:?}",
-                            err
-                        )
-                    })?;
+                let lc_updates = steps.check(
+                    "decode",
+                    true,
+                    LightClientUpdatesByRange::decode_for_chain(content, &self.chain_config)
+                        .map_err(|err| {
+                            anyhow!(
+                                "Light client updates by range has invalid SSZ bytes: {:?}",
+                                err
+                            )
+                        }),
+                )?;
 
                 // Check if lc updates count match the content key count
-                if lc_updates.0.len() as u64 != key.count {
-                    return Err(anyhow!(
-                        "Light client updates count does not match the content key count: {} != {}",
-                        lc_updates.0.len(),
-                        key.count
-                    ));
-                }
-
-                if let Ok(light_client_store) = self
-                    .header_oracle
-                    .read()
-                    .await
-                    .get_light_client_store()
-                    .await
-                {
-                    let expected_slot = expected_current_slot();
-                    for update in lc_updates.0.iter() {
-                        match &update.update {
-                            LightClientUpdate::Deneb(update) => {
-                                let generic_update: GenericUpdate = update.into();
-                                verify_generic_update(
-                                    &light_client_store,
-                                    &generic_update,
-                                    expected_slot,
-                                    &self.genesis_root,
-                                    &self.fork_version,
-                                )?;
-                            }
-                            _ => {
-                                return Err(anyhow!("Unsupported light client update fork version"))
-                            }
-                        }
-                    }
-                }
+                steps.check(
+                    "update_count",
+                    true,
+                    if lc_updates.0.len() as u64 != key.count {
+                        Err(anyhow!(
+                            "Light client updates count does not match the content key count: {} != {}",
+                            lc_updates.0.len(),
+                            key.count
+                        ))
+                    } else {
+                        Ok(())
+                    },
+                )?;
+
+                let generic_updates = steps.check(
+                    "supported_fork",
+                    true,
+                    lc_updates
+                        .0
+                        .iter()
+                        .map(|update| match &update.update {
+                            LightClientUpdate::Deneb(update) => Ok(GenericUpdate::from(update)),
+                            _ => Err(anyhow!("Unsupported light client update fork version")),
+                        })
+                        .collect::<anyhow::Result<Vec<_>>>(),
+                )?;
+
+                Ok(self.verify_updates(&generic_updates, steps).await)
             }
             BeaconContentKey::LightClientFinalityUpdate(key) => {
-                let lc_finality_update = ForkVersionedLightClientFinalityUpdate::from_ssz_bytes(
-                    content,
-                )
-                .map_err(|err| {
-                    anyhow!(
+                let lc_finality_update = steps.check(
+                    "decode",
+                    true,
+                    ForkVersionedLightClientFinalityUpdate::decode_for_chain(
+                        content,
+                        &self.chain_config,
+                    )
+                    .map_err(|err| {
+                        anyhow!(
                         "Fork versioned light client finality update has invalid SSZ bytes: {:?}",
                         err
                     )
-                })?;
+                    }),
+                )?;
 
                 // Check if the light client finality update is from the recent fork
-                if lc_finality_update.fork_name != ForkName::Deneb {
-                    return Err(anyhow!(
-                        "Light client finality update is not from the recent fork. Expected deneb, got {}",
-                        lc_finality_update.fork_name
-                    ));
-                }
+                steps.check(
+                    "recent_fork",
+                    true,
+                    if lc_finality_update.fork_name != ForkName::Deneb {
+                        Err(anyhow!(
+                            "Light client finality update is not from the recent fork. Expected deneb, got {}",
+                            lc_finality_update.fork_name
+                        ))
+                    } else {
+                        Ok(())
+                    },
+                )?;
 
                 // Check if key finalized slot matches the light client finality update finalized
                 // slot
                 let finalized_slot = lc_finality_update.get_finalized_slot();
 
-                if key.finalized_slot > finalized_slot {
-                    return Err(anyhow!(
-                        "Light client finality update finalized slot should be equal or greater than content key finalized slot: {} < {}",
-                        finalized_slot,
-                        key.finalized_slot
-                    ));
-                }
-
-                match &lc_finality_update.update {
-                    LightClientFinalityUpdate::Deneb(update) => {
-                        if let Ok(light_client_store) = self
-                            .header_oracle
-                            .read()
-                            .await
-                            .get_light_client_store()
-                            .await
-                        {
-                            let generic_update: GenericUpdate = update.into();
-                            verify_generic_update(
-                                &light_client_store,
-                                &generic_update,
-                                expected_current_slot(),
-                                &self.genesis_root,
-                                &self.fork_version,
-                            )?;
-                        }
-                    }
-                    _ => {
-                        return Err(anyhow!(
-                            "Unsupported light client finality update fork version"
+                steps.check(
+                    "finalized_slot",
+                    true,
+                    if key.finalized_slot > finalized_slot {
+                        Err(anyhow!(
+                            "Light client finality update finalized slot should be equal or greater than content key finalized slot: {} < {}",
+                            finalized_slot,
+                            key.finalized_slot
                         ))
-                    }
-                }
+                    } else {
+                        Ok(())
+                    },
+                )?;
+
+                let update = steps.check(
+                    "supported_fork",
+                    true,
+                    match &lc_finality_update.update {
+                        LightClientFinalityUpdate::Deneb(update) => Ok(GenericUpdate::from(update)),
+                        _ => Err(anyhow!(
+                            "Unsupported light client finality update fork version"
+                        )),
+                    },
+                )?;
+
+                Ok(self.verify_updates(&[update], steps).await)
             }
             BeaconContentKey::LightClientOptimisticUpdate(key) => {
-                let lc_optimistic_update =
-                    ForkVersionedLightClientOptimisticUpdate::from_ssz_bytes(content).map_err(
-                        |err| {
-                            anyhow!(
+                let lc_optimistic_update = steps.check(
+                    "decode",
+                    true,
+                    ForkVersionedLightClientOptimisticUpdate::decode_for_chain(
+                        content,
+                        &self.chain_config,
+                    )
+                    .map_err(|err| {
+                        anyhow!(
                         "Fork versioned light client optimistic update has invalid SSZ bytes: {:?}",
                         err
                     )
-                        },
-                    )?;
+                    }),
+                )?;
 
                 // Check if the light client optimistic update is from the recent fork
-                if lc_optimistic_update.fork_name != ForkName::Deneb {
-                    return Err(anyhow!(
-                        "Light client optimistic update is not from the recent fork. Expected deneb, got {}",
-                        lc_optimistic_update.fork_name
-                    ));
-                }
+                steps.check(
+                    "recent_fork",
+                    true,
+                    if lc_optimistic_update.fork_name != ForkName::Deneb {
+                        Err(anyhow!(
+                            "Light client optimistic update is not from the recent fork. Expected deneb, got {}",
+                            lc_optimistic_update.fork_name
+                        ))
+                    } else {
+                        Ok(())
+                    },
+                )?;
 
                 // Check if key signature slot matches the light client optimistic update signature
                 // slot
-                if &key.signature_slot != lc_optimistic_update.update.signature_slot() {
-                    return Err(anyhow!(
-                        "Light client optimistic update signature slot does not match the content key signature slot: {} != {}",
-                        lc_optimistic_update.update.signature_slot(),
-                        key.signature_slot
-                    ));
-                }
-
-                match &lc_optimistic_update.update {
-                    LightClientOptimisticUpdate::Deneb(update) => {
-                        if let Ok(light_client_store) = self
-                            .header_oracle
-                            .read()
-                            .await
-                            .get_light_client_store()
-                            .await
-                        {
-                            let generic_update: GenericUpdate = update.into();
-
-                            verify_generic_update(
-                                &light_client_store,
-                                &generic_update,
-                                expected_current_slot(),
-                                &self.genesis_root,
-                                &self.fork_version,
-                            )?;
+                steps.check(
+                    "signature_slot",
+                    true,
+                    if &key.signature_slot != lc_optimistic_update.update.signature_slot() {
+                        Err(anyhow!(
+                            "Light client optimistic update signature slot does not match the content key signature slot: {} != {}",
+                            lc_optimistic_update.update.signature_slot(),
+                            key.signature_slot
+                        ))
+                    } else {
+                        Ok(())
+                    },
+                )?;
+
+                let update = steps.check(
+                    "supported_fork",
+                    true,
+                    match &lc_optimistic_update.update {
+                        LightClientOptimisticUpdate::Deneb(update) => {
+                            Ok(GenericUpdate::from(update))
                         }
-                    }
-                    _ => {
-                        return Err(anyhow!(
+                        _ => Err(anyhow!(
                             "Unsupported light client optimistic update fork version"
-                        ))
-                    }
-                }
+                        )),
+                    },
+                )?;
+
+                Ok(self.verify_updates(&[update], steps).await)
             }
             BeaconContentKey::HistoricalSummariesWithProof(key) => {
                 let fork_versioned_historical_summaries =
-                    Self::general_summaries_validation(content, key)?;
+                    self.general_summaries_validation(content, key, steps)?;
 
                 let latest_finalized_root = self
                     .header_oracle
@@ -267,17 +321,161 @@ impl Validator<BeaconContentKey> for BeaconValidator {
                     .await;
 
                 if let Ok(latest_finalized_root) = latest_finalized_root {
-                    Self::state_summaries_validation(
-                        fork_versioned_historical_summaries,
-                        latest_finalized_root,
-                    )
-                    .await?;
+                    steps.root("finalized_state_root", latest_finalized_root);
+                    Ok(steps.check(
+                        "historical_summaries_proof",
+                        false,
+                        Self::state_summaries_validation(
+                            fork_versioned_historical_summaries,
+                            latest_finalized_root,
+                        )
+                        .await,
+                    ))
                 } else {
                     debug!("Failed to get latest finalized state root. Bypassing historical summaries with proof validation");
+                    steps.skip(
+                        "historical_summaries_proof",
+                        false,
+                        "The finalized state root is unknown",
+                    );
+                    Ok(Ok(()))
                 }
             }
         }
+    }
+
+    /// Checks that the bootstrap is recent and matches the finalized header, if it is known.
+    async fn verify_bootstrap(
+        &self,
+        bootstrap: &ForkVersionedLightClientBootstrap,
+        steps: &mut ValidationSteps,
+    ) -> anyhow::Result<()> {
+        let bootstrap_block_header = bootstrap.bootstrap.get_beacon_block_header();
+        steps.root("header_root", bootstrap_block_header.tree_hash_root());
+
+        // Check if the light client bootstrap slot is ole than 4 months
+        let four_months = Duration::days(30 * 4);
+        let four_months_in_senonds = four_months.num_seconds();
+        let four_months_in_slots =
+            four_months_in_senonds as u64 / self.chain_config.seconds_per_slot;
+        let four_months_ago_slot = self
+            .chain_config
+            .current_slot()
+            .saturating_sub(four_months_in_slots);
+        let bootstrap_slot = bootstrap.get_slot();
+
+        steps.check(
+            "recent_slot",
+            false,
+            if bootstrap_slot < four_months_ago_slot {
+                Err(anyhow!(
+                    "Light client bootstrap slot is too old: {}",
+                    bootstrap_slot
+                ))
+            } else {
+                Ok(())
+            },
+        )?;
+
+        let finalized_header = self.header_oracle.read().await.get_finalized_header().await;
+
+        if let Ok(finalized_header) = finalized_header {
+            steps.root("finalized_header_root", finalized_header.tree_hash_root());
+            steps.check(
+                "finalized_header",
+                false,
+                if finalized_header != bootstrap_block_header {
+                    Err(anyhow!(
+                        "Light client bootstrap header does not match the finalized header: {finalized_header:?} != {bootstrap_block_header:?}",
+                    ))
+                } else {
+                    Ok(())
+                },
+            )
+        } else {
+            steps.skip("finalized_header", false, "The finalized header is unknown");
+            Ok(())
+        }
+    }
+
+    /// Verifies the updates against the light client store, if it is available.
+    async fn verify_updates(
+        &self,
+        updates: &[GenericUpdate],
+        steps: &mut ValidationSteps,
+    ) -> anyhow::Result<()> {
+        for (index, update) in updates.iter().enumerate() {
+            steps.root(
+                indexed_name("attested_header_root", index, updates.len()),
+                update.attested_header.tree_hash_root(),
+            );
+            if let Some(finalized_header) = &update.finalized_header {
+                steps.root(
+                    indexed_name("finalized_header_root", index, updates.len()),
+                    finalized_header.tree_hash_root(),
+                );
+            }
+        }
+
+        if let Ok(light_client_store) = self
+            .header_oracle
+            .read()
+            .await
+            .get_light_client_store()
+            .await
+        {
+            let expected_slot = self.chain_config.current_slot();
+            for (index, update) in updates.iter().enumerate() {
+                // The sync committee signs with the fork version of the slot before the signature
+                // slot, so an update is verified with the fork it was signed in, even across a
+                // fork boundary.
+                let fork_name = self
+                    .chain_config
+                    .fork_at_slot(update.signature_slot.saturating_sub(1));
+                steps.check(
+                    indexed_name("light_client_update", index, updates.len()),
+                    false,
+                    verify_generic_update(
+                        &light_client_store,
+                        update,
+                        expected_slot,
+                        self.chain_config.genesis_validators_root.as_slice(),
+                        self.chain_config.fork(fork_name).version.as_slice(),
+                    ),
+                )?;
+            }
+        } else {
+            steps.skip(
+                "light_client_update",
+                false,
+                "The light client store is unavailable",
+            );
+        }
+        Ok(())
+    }
+}
 
+impl Validator<BeaconContentKey> for BeaconValidator {
+    async fn validate_content(
+        &self,
+        content_key: &BeaconContentKey,
+        content: &[u8],
+    ) -> anyhow::Result<ValidationResult<BeaconContentKey>> {
+        let mode = self.mode();
+        if mode == ValidationMode::Off {
+            // Content that can't be decoded can't be served, so it is never stored.
+            BeaconContentValue::decode_for_chain(content_key, content, &self.chain_config)?;
+            return Ok(ValidationResult::new(true));
+        }
+        if let Err(err) = self
+            .check_content(content_key, content, &mut ValidationSteps::default())
+            .await?
+        {
+            if mode == ValidationMode::Strict {
+                return Err(err);
+            }
+            self.tag(content_key, &err);
+        }
         Ok(ValidationResult::new(true))
     }
 }
@@ -285,26 +483,48 @@ impl Validator<BeaconContentKey> for BeaconValidator {
 impl BeaconValidator {
     /// General validation for the historical summaries with proof content
     fn general_summaries_validation(
+        &self,
         content: &[u8],
         key: &HistoricalSummariesWithProofKey,
+        steps: &mut ValidationSteps,
     ) -> anyhow::Result<ForkVersionedHistoricalSummariesWithProof> {
-        let fork_versioned_historical_summaries =
-            ForkVersionedHistoricalSummariesWithProof::from_ssz_bytes(content).map_err(|err| {
+        let fork_versioned_historical_summaries = steps.check(
+            "decode",
+            true,
+            ForkVersionedHistoricalSummariesWithProof::decode_for_chain(
+                content,
+                &self.chain_config,
+            )
+            .map_err(|err| {
                 anyhow!("Historical summaries with proof has invalid SSZ bytes: {err:?}")
-            })?;
+            }),
+        )?;
+        steps.root(
+            "historical_summaries_root",
+            fork_versioned_historical_summaries
+                .historical_summaries_with_proof
+                .historical_summaries
+                .tree_hash_root(),
+        );
 
         // Check if the historical summaries with proof epoch matches the content key epoch
-        if fork_versioned_historical_summaries
-            .historical_summaries_with_proof
-            .epoch
-            != key.epoch
-        {
-            return Err(anyhow!(
+        steps.check(
+            "epoch",
+            true,
+            if fork_versioned_historical_summaries
+                .historical_summaries_with_proof
+                .epoch
+                != key.epoch
+            {
+                Err(anyhow!(
                         "Historical summaries with proof epoch does not match the content key epoch: {} != {}",
                         fork_versioned_historical_summaries.historical_summaries_with_proof.epoch,
                         key.epoch
-                    ));
-        }
+                    ))
+            } else {
+                Ok(())
+            },
+        )?;
         Ok(fork_versioned_historical_summaries)
     }
 
@@ -340,10 +560,76 @@ impl BeaconValidator {
         Ok(())
     }
 }
+
+/// Records the outcome of the checks of [BeaconValidator::check_content], and the roots computed
+/// for them, for [BeaconValidator::report].
+#[derive(Default)]
+struct ValidationSteps {
+    steps: Vec<ValidationStepInfo>,
+    roots: BTreeMap<String, B256>,
+}
+
+impl ValidationSteps {
+    /// Records the outcome of the check, and returns it.
+    fn check<T>(
+        &mut self,
+        name: impl Into<String>,
+        critical: bool,
+        result: anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let (status, detail) = match &result {
+            Ok(_) => (ValidationStepStatus::Passed, None),
+            Err(err) => (ValidationStepStatus::Failed, Some(err.to_string())),
+        };
+        self.steps.push(ValidationStepInfo {
+            name: name.into(),
+            status,
+            critical,
+            detail,
+        });
+        result
+    }
+
+    /// Records a check that didn't run, as its input isn't available.
+    fn skip(&mut self, name: impl Into<String>, critical: bool, reason: &str) {
+        self.steps.push(ValidationStepInfo {
+            name: name.into(),
+            status: ValidationStepStatus::Skipped,
+            critical,
+            detail: Some(reason.to_string()),
+        });
+    }
+
+    fn root(&mut self, name: impl Into<String>, root: B256) {
+        self.roots.insert(name.into(), root);
+    }
+
+    fn into_report(self) -> ValidationReportInfo {
+        ValidationReportInfo {
+            valid: self
+                .steps
+                .iter()
+                .all(|step| step.status != ValidationStepStatus::Failed),
+            steps: self.steps,
+            roots: self.roots,
+        }
+    }
+}
+
+/// Returns the name of a check or root of one of the updates, indexed if there is more than one.
+fn indexed_name(name: &str, index: usize, count: usize) -> String {
+    if count == 1 {
+        name.to_string()
+    } else {
+        format!("{name}[{index}]")
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
     use ethportal_api::{
+        consensus::chain_config::MAINNET_CHAIN_CONFIG,
         types::{
             content_key::beacon::{
                 HistoricalSummariesWithProofKey, LightClientFinalityUpdateKey,
@@ -361,7 +647,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_validate_light_client_bootstrap() {
-        let validator = BeaconValidator::new(Arc::new(RwLock::new(HeaderOracle::default())));
+        let validator = BeaconValidator::new(
+            Arc::new(RwLock::new(HeaderOracle::default())),
+            MAINNET_CHAIN_CONFIG.clone(),
+        );
         let mut bootstrap = test_utils::get_light_client_bootstrap(0);
         let content = bootstrap.as_ssz_bytes();
         let content_key = BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey {
@@ -388,9 +677,163 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_report_light_client_bootstrap() {
+        let validator = BeaconValidator::new(
+            Arc::new(RwLock::new(HeaderOracle::default())),
+            MAINNET_CHAIN_CONFIG.clone(),
+        );
+        let mut bootstrap = test_utils::get_light_client_bootstrap(0);
+        let content_key = BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey {
+            block_hash: [0; 32],
+        });
+        let report = validator
+            .report(&content_key, &bootstrap.as_ssz_bytes())
+            .await;
+
+        assert!(report.valid);
+        let statuses = report
+            .steps
+            .iter()
+            .map(|step| (step.name.as_str(), step.status, step.critical))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                ("decode", ValidationStepStatus::Passed, true),
+                ("recent_slot", ValidationStepStatus::Passed, false),
+                // The finalized header isn't known yet.
+                ("finalized_header", ValidationStepStatus::Skipped, false),
+            ]
+        );
+        assert_eq!(
+            report.roots.get("header_root"),
+            Some(
+                &bootstrap
+                    .bootstrap
+                    .get_beacon_block_header()
+                    .tree_hash_root()
+            )
+        );
+
+        // The checks after a failed check don't run.
+        bootstrap.bootstrap.header_deneb_mut().unwrap().beacon.slot = 0;
+        let report = validator
+            .report(&content_key, &bootstrap.as_ssz_bytes())
+            .await;
+
+        assert!(!report.valid);
+        assert_eq!(report.steps.len(), 2);
+        assert_eq!(report.steps[1].status, ValidationStepStatus::Failed);
+        assert_eq!(
+            report.steps[1].detail.as_deref(),
+            Some("Light client bootstrap slot is too old: 0")
+        );
+
+        let report = validator.report(&content_key, &[1, 2, 3]).await;
+        assert!(!report.valid);
+        assert_eq!(report.steps.len(), 1);
+        assert!(report.roots.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_lenient_mode_accepts_and_tags_non_critical_failures() {
+        let validator = BeaconValidator::new(
+            Arc::new(RwLock::new(HeaderOracle::default())),
+            MAINNET_CHAIN_CONFIG.clone(),
+        );
+        validator.set_mode(ValidationMode::Lenient);
+        let mut bootstrap = test_utils::get_light_client_bootstrap(0);
+        bootstrap.bootstrap.header_deneb_mut().unwrap().beacon.slot = 0;
+        let content_key = BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey {
+            block_hash: [0; 32],
+        });
+        let result = validator
+            .validate_content(&content_key, &bootstrap.as_ssz_bytes())
+            .await
+            .unwrap();
+
+        assert!(result.valid_for_storing);
+        assert_eq!(
+            validator.tagged(),
+            vec![TaggedContentInfo {
+                content_key: content_key.clone(),
+                reason: "Light client bootstrap slot is too old: 0".to_string(),
+            }]
+        );
+
+        // Content that can't be decoded is still rejected.
+        let result = validator.validate_content(&content_key, &[1, 2, 3]).await;
+        assert!(result.is_err());
+        assert_eq!(validator.tagged().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_lenient_mode_rejects_content_key_mismatch() {
+        let validator = BeaconValidator::new(
+            Arc::new(RwLock::new(HeaderOracle::default())),
+            MAINNET_CHAIN_CONFIG.clone(),
+        );
+        validator.set_mode(ValidationMode::Lenient);
+        let optimistic_update = test_utils::get_light_client_optimistic_update(0);
+        let content_key =
+            BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey {
+                signature_slot: 0,
+            });
+        let result = validator
+            .validate_content(&content_key, &optimistic_update.as_ssz_bytes())
+            .await;
+
+        assert!(result.is_err());
+        assert!(validator.tagged().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_off_mode_skips_validation() {
+        let validator = BeaconValidator::new(
+            Arc::new(RwLock::new(HeaderOracle::default())),
+            MAINNET_CHAIN_CONFIG.clone(),
+        );
+        validator.set_mode(ValidationMode::Off);
+        let lc_update_0 = test_utils::get_light_client_update(0);
+        let updates = LightClientUpdatesByRange(VariableList::from(vec![lc_update_0]));
+        // The count doesn't match the content key, which isn't checked.
+        let content_key =
+            BeaconContentKey::LightClientUpdatesByRange(LightClientUpdatesByRangeKey {
+                start_period: 0,
+                count: 2,
+            });
+        let result = validator
+            .validate_content(&content_key, &updates.as_ssz_bytes())
+            .await
+            .unwrap();
+
+        assert!(result.valid_for_storing);
+        assert!(validator.tagged().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_off_mode_rejects_undecodable_content() {
+        let validator = BeaconValidator::new(
+            Arc::new(RwLock::new(HeaderOracle::default())),
+            MAINNET_CHAIN_CONFIG.clone(),
+        );
+        validator.set_mode(ValidationMode::Off);
+        let content_key = BeaconContentKey::LightClientBootstrap(LightClientBootstrapKey {
+            block_hash: [0; 32],
+        });
+        let result = validator.validate_content(&content_key, &[1, 2, 3]).await;
+
+        assert!(result.is_err());
+        assert!(validator.tagged().is_empty());
+    }
+
     #[tokio::test]
     async fn test_validate_light_client_updates_by_range() {
-        let validator = BeaconValidator::new(Arc::new(RwLock::new(HeaderOracle::default())));
+        let validator = BeaconValidator::new(
+            Arc::new(RwLock::new(HeaderOracle::default())),
+            MAINNET_CHAIN_CONFIG.clone(),
+        );
         let lc_update_0 = test_utils::get_light_client_update(0);
         let updates = LightClientUpdatesByRange(VariableList::from(vec![lc_update_0.clone()]));
         let content = updates.as_ssz_bytes();
@@ -423,7 +866,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_validate_light_client_finality_update() {
-        let validator = BeaconValidator::new(Arc::new(RwLock::new(HeaderOracle::default())));
+        let validator = BeaconValidator::new(
+            Arc::new(RwLock::new(HeaderOracle::default())),
+            MAINNET_CHAIN_CONFIG.clone(),
+        );
         let finality_update = test_utils::get_light_client_finality_update(0);
         let content = finality_update.as_ssz_bytes();
         let content_key =
@@ -457,7 +903,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_validate_light_client_optimistic_update() {
-        let validator = BeaconValidator::new(Arc::new(RwLock::new(HeaderOracle::default())));
+        let validator = BeaconValidator::new(
+            Arc::new(RwLock::new(HeaderOracle::default())),
+            MAINNET_CHAIN_CONFIG.clone(),
+        );
         let optimistic_update = test_utils::get_light_client_optimistic_update(0);
         let content = optimistic_update.as_ssz_bytes();
         let content_key =
@@ -494,12 +943,25 @@ mod tests {
         let content_key = HistoricalSummariesWithProofKey {
             epoch: 450508969718611630,
         };
-        let result = BeaconValidator::general_summaries_validation(&content, &content_key);
+        let validator = BeaconValidator::new(
+            Arc::new(RwLock::new(HeaderOracle::default())),
+            MAINNET_CHAIN_CONFIG.clone(),
+        );
+        let result = validator.general_summaries_validation(
+            &content,
+            &content_key,
+            &mut ValidationSteps::default(),
+        );
         assert!(result.is_ok());
 
         // Expect error because the epoch does not match the content key epoch
         let invalid_content_key = HistoricalSummariesWithProofKey { epoch: 0 };
-        let result = BeaconValidator::general_summaries_validation(&content, &invalid_content_key)
+        let result = validator
+            .general_summaries_validation(
+                &content,
+                &invalid_content_key,
+                &mut ValidationSteps::default(),
+            )
             .unwrap_err();
         assert_eq!(
             result.to_string(),
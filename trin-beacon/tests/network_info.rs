@@ -0,0 +1,32 @@
+use ethportal_api::types::{
+    jsonrpc::endpoints::BeaconEndpoint, network::Subnetwork, portal::OverlayNetworkInfo,
+    portal_wire::MAINNET,
+};
+use trin_test_utils::TestNetwork;
+
+#[test_log::test(tokio::test)]
+async fn beacon_network_info_matches_overlay_config() {
+    let network = TestNetwork::start(1, &[Subnetwork::Beacon]).await.unwrap();
+
+    let network_info: OverlayNetworkInfo = serde_json::from_value(
+        network
+            .node(0)
+            .beacon()
+            .request(BeaconEndpoint::NetworkInfo)
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        network_info,
+        OverlayNetworkInfo {
+            network: "mainnet".to_string(),
+            subnetwork: "beacon".to_string(),
+            protocol_id: MAINNET
+                .get_protocol_identifier_from_subnetwork(&Subnetwork::Beacon)
+                .unwrap(),
+        }
+    );
+
+    network.shutdown().await;
+}
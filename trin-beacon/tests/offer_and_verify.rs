@@ -0,0 +1,80 @@
+use ethportal_api::{
+    consensus::fork::ForkName,
+    light_client::optimistic_update::LightClientOptimisticUpdate,
+    types::{
+        content_key::beacon::LightClientOptimisticUpdateKey,
+        content_value::beacon::ForkVersionedLightClientOptimisticUpdate,
+        jsonrpc::endpoints::BeaconEndpoint, network::Subnetwork, portal::OfferAndVerifyInfo,
+    },
+    BeaconContentKey, BeaconContentValue,
+};
+use ssz::Decode;
+use trin_test_utils::{TestNetwork, TestNode};
+
+fn beacon_optimistic_update_fixture() -> (BeaconContentKey, BeaconContentValue) {
+    let update = std::fs::read(
+        "../test_assets/beacon/deneb/LightClientOptimisticUpdate/ssz_random/case_0/serialized.ssz_snappy",
+    )
+    .expect("cannot find test asset");
+    let update = snap::raw::Decoder::new().decompress_vec(&update).unwrap();
+    let update = LightClientOptimisticUpdate::from_ssz_bytes(&update, ForkName::Deneb).unwrap();
+    let content_key =
+        BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey {
+            signature_slot: *update.signature_slot(),
+        });
+    let content_value =
+        BeaconContentValue::LightClientOptimisticUpdate(ForkVersionedLightClientOptimisticUpdate {
+            fork_name: ForkName::Deneb,
+            update,
+        });
+    (content_key, content_value)
+}
+
+async fn offer_and_verify(
+    node: &TestNode,
+    peer: &TestNode,
+    content_key: &BeaconContentKey,
+    content_value: &BeaconContentValue,
+) -> OfferAndVerifyInfo {
+    serde_json::from_value(
+        node.beacon()
+            .request(BeaconEndpoint::OfferAndVerify(
+                peer.enr(),
+                content_key.clone(),
+                content_value.clone(),
+            ))
+            .await
+            .unwrap(),
+    )
+    .unwrap()
+}
+
+#[test_log::test(tokio::test)]
+async fn offered_content_is_verified() {
+    let network = TestNetwork::start(2, &[Subnetwork::Beacon]).await.unwrap();
+    let (node, peer) = (network.node(0), network.node(1));
+    let (content_key, content_value) = beacon_optimistic_update_fixture();
+
+    let info = offer_and_verify(node, peer, &content_key, &content_value).await;
+    assert_eq!(
+        info,
+        OfferAndVerifyInfo {
+            accepted: true,
+            verified: true,
+            valid: Some(true),
+        }
+    );
+
+    // The peer declines the content that it already has, but still serves it.
+    let info = offer_and_verify(node, peer, &content_key, &content_value).await;
+    assert_eq!(
+        info,
+        OfferAndVerifyInfo {
+            accepted: false,
+            verified: true,
+            valid: Some(true),
+        }
+    );
+
+    network.shutdown().await;
+}
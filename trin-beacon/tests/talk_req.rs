@@ -0,0 +1,72 @@
+use alloy::primitives::Bytes;
+use ethportal_api::{
+    types::{
+        distance::Distance,
+        jsonrpc::endpoints::BeaconEndpoint,
+        network::Subnetwork,
+        portal::PingRawInfo,
+        portal_wire::{CustomPayload, Message, Ping, MAINNET},
+    },
+    utils::bytes::hex_decode,
+};
+use ssz::Encode;
+use trin_test_utils::TestNetwork;
+
+#[test_log::test(tokio::test)]
+async fn beacon_talk_req_returns_raw_response() {
+    let network = TestNetwork::start(2, &[Subnetwork::Beacon]).await.unwrap();
+    let protocol_id = MAINNET
+        .get_protocol_identifier_from_subnetwork(&Subnetwork::Beacon)
+        .unwrap();
+    let ping: Vec<u8> = Message::Ping(Ping {
+        enr_seq: network.node(0).enr().seq(),
+        custom_payload: CustomPayload::from(Distance::MAX.as_ssz_bytes()),
+    })
+    .into();
+
+    let response = network
+        .node(0)
+        .beacon()
+        .request(BeaconEndpoint::TalkReq(
+            network.node(1).enr(),
+            hex_decode(&protocol_id).unwrap().into(),
+            ping.into(),
+        ))
+        .await
+        .unwrap();
+    let response: Bytes = serde_json::from_value(response).unwrap();
+    let response = Message::try_from(response.to_vec()).unwrap();
+    match response {
+        Message::Pong(pong) => assert_eq!(pong.enr_seq, network.node(1).enr().seq()),
+        message => panic!("Expected a pong response, got {message:?}"),
+    }
+
+    network.shutdown().await;
+}
+
+#[test_log::test(tokio::test)]
+async fn beacon_ping_raw_returns_wire_bytes_and_pong() {
+    let network = TestNetwork::start(2, &[Subnetwork::Beacon]).await.unwrap();
+
+    let response = network
+        .node(0)
+        .beacon()
+        .request(BeaconEndpoint::PingRaw(network.node(1).enr()))
+        .await
+        .unwrap();
+    let info: PingRawInfo = serde_json::from_value(response).unwrap();
+    match Message::try_from(info.request.to_vec()).unwrap() {
+        Message::Ping(ping) => assert_eq!(ping.enr_seq, network.node(0).enr().seq()),
+        message => panic!("Expected a ping request, got {message:?}"),
+    }
+    let pong = match Message::try_from(info.response.to_vec()).unwrap() {
+        Message::Pong(pong) => pong,
+        message => panic!("Expected a pong response, got {message:?}"),
+    };
+    let pong_info = info.pong.unwrap();
+    assert_eq!(pong_info.enr_seq, pong.enr_seq);
+    assert_eq!(pong_info.enr_seq, network.node(1).enr().seq());
+    assert_eq!(info.error, None);
+
+    network.shutdown().await;
+}
@@ -0,0 +1,144 @@
+use alloy::primitives::B256;
+use ethportal_api::{
+    consensus::fork::ForkName,
+    light_client::optimistic_update::LightClientOptimisticUpdate,
+    types::{
+        content_key::beacon::LightClientOptimisticUpdateKey,
+        content_value::beacon::ForkVersionedLightClientOptimisticUpdate,
+        jsonrpc::endpoints::BeaconEndpoint,
+        network::Subnetwork,
+        portal::{ContentInspectionInfo, ContentValidationInfo},
+    },
+    BeaconContentKey, BeaconContentValue, ContentValue, OverlayContentKey, RawContentValue,
+};
+use ssz::Decode;
+use trin_test_utils::TestNetwork;
+
+fn beacon_optimistic_update_fixture() -> (BeaconContentKey, RawContentValue) {
+    let update = std::fs::read(
+        "../test_assets/beacon/deneb/LightClientOptimisticUpdate/ssz_random/case_0/serialized.ssz_snappy",
+    )
+    .expect("cannot find test asset");
+    let update = snap::raw::Decoder::new().decompress_vec(&update).unwrap();
+    let update = LightClientOptimisticUpdate::from_ssz_bytes(&update, ForkName::Deneb).unwrap();
+    let content_key =
+        BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey {
+            signature_slot: *update.signature_slot(),
+        });
+    let content_value =
+        BeaconContentValue::LightClientOptimisticUpdate(ForkVersionedLightClientOptimisticUpdate {
+            fork_name: ForkName::Deneb,
+            update,
+        });
+    (content_key, content_value.encode())
+}
+
+#[test_log::test(tokio::test)]
+async fn validate_content_batch_preserves_order_and_stores_nothing() {
+    let network = TestNetwork::start(1, &[Subnetwork::Beacon]).await.unwrap();
+    let node = network.node(0);
+
+    let (content_key, content_value) = beacon_optimistic_update_fixture();
+    let BeaconContentKey::LightClientOptimisticUpdate(key) = &content_key else {
+        unreachable!("The fixture is an optimistic update");
+    };
+    let other_key = BeaconContentKey::LightClientOptimisticUpdate(LightClientOptimisticUpdateKey {
+        signature_slot: key.signature_slot + 1,
+    });
+    let content_items = vec![
+        (content_key.clone(), content_value.clone()),
+        (content_key.clone(), RawContentValue::from(vec![0xab; 8])),
+        (other_key, content_value.clone()),
+        (content_key.clone(), content_value),
+    ];
+    let result = node
+        .beacon()
+        .request(BeaconEndpoint::ValidateContentBatch(content_items))
+        .await
+        .unwrap();
+    let result: Vec<ContentValidationInfo> = serde_json::from_value(result).unwrap();
+
+    assert_eq!(
+        result.iter().map(|info| info.valid).collect::<Vec<_>>(),
+        vec![true, false, false, true]
+    );
+    assert!(result[0].error.is_none());
+    assert!(
+        result[1]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("invalid SSZ bytes"),
+        "Unexpected error: {:?}",
+        result[1].error
+    );
+    assert!(
+        result[2].error.as_ref().unwrap().contains("signature slot"),
+        "Unexpected error: {:?}",
+        result[2].error
+    );
+
+    // The valid content isn't stored.
+    let err = node
+        .beacon()
+        .request(BeaconEndpoint::LocalContent(content_key, false))
+        .await
+        .unwrap_err();
+    assert!(err.contains("not found"), "Unexpected error: {err}");
+
+    network.shutdown().await;
+}
+
+#[test_log::test(tokio::test)]
+async fn inspect_content_returns_raw_and_decoded_value() {
+    let network = TestNetwork::start(1, &[Subnetwork::Beacon]).await.unwrap();
+    let node = network.node(0);
+
+    let (content_key, content_value) = beacon_optimistic_update_fixture();
+    let result = node
+        .beacon()
+        .request(BeaconEndpoint::InspectContent(
+            content_key.clone(),
+            content_value.clone(),
+        ))
+        .await
+        .unwrap();
+    let info: ContentInspectionInfo = serde_json::from_value(result).unwrap();
+    let expected = BeaconContentValue::decode(&content_key, &content_value).unwrap();
+    assert_eq!(info.raw, content_value);
+    assert_eq!(info.decoded, Some(expected.to_json()));
+    assert_eq!(info.content_id, B256::from(content_key.content_id()));
+    assert!(info.error.is_none());
+
+    // The raw bytes are returned even if they can't be decoded.
+    let invalid_value = RawContentValue::from(vec![0xab; 8]);
+    let result = node
+        .beacon()
+        .request(BeaconEndpoint::InspectContent(
+            content_key.clone(),
+            invalid_value.clone(),
+        ))
+        .await
+        .unwrap();
+    let info: ContentInspectionInfo = serde_json::from_value(result).unwrap();
+    assert_eq!(info.raw, invalid_value);
+    assert!(info.decoded.is_none());
+    assert!(
+        info.error
+            .as_ref()
+            .unwrap()
+            .starts_with("Unable to decode content value"),
+        "Unexpected error: {:?}",
+        info.error
+    );
+
+    // The content isn't stored.
+    let err = node
+        .beacon()
+        .request(BeaconEndpoint::LocalContent(content_key, false))
+        .await
+        .unwrap_err();
+    assert!(err.contains("not found"), "Unexpected error: {err}");
+
+    network.shutdown().await;
+}
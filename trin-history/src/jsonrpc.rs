@@ -1,23 +1,31 @@
 use std::sync::Arc;
 
-use alloy::primitives::Bytes;
+use alloy::primitives::{Bytes, B256};
 use discv5::enr::NodeId;
 use ethportal_api::{
     types::{
-        distance::Distance,
+        execution::header_with_proof::HeaderWithProof,
         jsonrpc::{endpoints::HistoryEndpoint, request::HistoryJsonRpcRequest},
-        portal::{AcceptInfo, FindNodesInfo, GetContentInfo, PongInfo, TraceContentInfo},
+        portal::{
+            AcceptInfo, ContentProvenance, FindNodesInfo, GetContentInfo, GetEnrInfo,
+            HeaderVerificationInfo, ImportContentInfo, LocalContentInfo, LocalContentMeta,
+            OfferManyInfo, TraceContentInfo,
+        },
         portal_wire::Content,
         query_trace::QueryTrace,
     },
-    utils::bytes::hex_encode,
-    ContentValue, HistoryContentKey, HistoryContentValue, OverlayContentKey,
+    utils::{bytes::hex_encode, content_signature::verify_content_signature},
+    ContentValue, HistoryContentKey, HistoryContentValue, OverlayContentKey, RawContentValue,
+};
+use portalnet::overlay::{
+    config::FindContentConfig,
+    errors::{OverlayRequestError, OverlayRequestJsonError},
 };
-use portalnet::overlay::{config::FindContentConfig, errors::OverlayRequestError};
 use serde_json::{json, Value};
+use ssz::Decode;
 use tokio::sync::mpsc;
-use tracing::error;
-use trin_storage::ContentStore;
+use tracing::{debug, error, info_span, Instrument};
+use trin_storage::{ContentLabels, ContentStore, ShouldWeStoreContent};
 
 use crate::network::HistoryNetwork;
 
@@ -32,7 +40,8 @@ impl HistoryRequestHandler {
     pub async fn handle_client_queries(mut self) {
         while let Some(request) = self.history_rx.recv().await {
             let network = self.network.clone();
-            tokio::spawn(async move { complete_request(network, request).await });
+            let span = info_span!("rpc_request", request_id = %request.request_id);
+            tokio::spawn(complete_request(network, request).instrument(span));
         }
     }
 }
@@ -40,28 +49,56 @@ impl HistoryRequestHandler {
 /// Generates a response for a given request and sends it to the receiver.
 async fn complete_request(network: Arc<HistoryNetwork>, request: HistoryJsonRpcRequest) {
     let response: Result<Value, String> = match request.endpoint {
-        HistoryEndpoint::LocalContent(content_key) => local_content(network, content_key).await,
-        HistoryEndpoint::PaginateLocalContentKeys(offset, limit) => {
-            paginate_local_content_keys(network, offset, limit).await
+        HistoryEndpoint::LocalContent(content_key, include_meta, include_rank) => {
+            local_content(network, content_key, include_meta, include_rank).await
+        }
+        HistoryEndpoint::ContentProviders(content_key) => {
+            content_providers(network, content_key).await
+        }
+        HistoryEndpoint::SimulateIncomingOffer(sample_size) => {
+            simulate_incoming_offer(network, sample_size).await
+        }
+        HistoryEndpoint::ContentSummary => content_summary(network).await,
+        HistoryEndpoint::ClearLookupCache => Ok(json!(network.overlay.clear_lookup_cache())),
+        HistoryEndpoint::VerifyHeader(header_with_proof) => {
+            verify_header(network, header_with_proof).await
+        }
+        HistoryEndpoint::PaginateLocalContentKeys(offset, limit, provenance, source) => {
+            paginate_local_content_keys(network, offset, limit, provenance, source).await
+        }
+        HistoryEndpoint::PaginateLocalContentKeysAfter(after, limit, provenance, source) => {
+            paginate_local_content_keys_after(network, after, limit, provenance, source).await
+        }
+        HistoryEndpoint::Store(content_key, content_value, source, signature) => {
+            store(network, content_key, content_value, source, signature).await
         }
-        HistoryEndpoint::Store(content_key, content_value) => {
-            store(network, content_key, content_value).await
+        HistoryEndpoint::DeleteContentBySource(source) => {
+            delete_content_by_source(network, source).await
         }
         HistoryEndpoint::GetContent(content_key) => get_content(network, content_key, false).await,
         HistoryEndpoint::TraceGetContent(content_key) => {
             get_content(network, content_key, true).await
         }
-        HistoryEndpoint::AddEnr(enr) => add_enr(network, enr).await,
+        HistoryEndpoint::AddEnr(enr, allow_unreachable) => {
+            add_enr(network, enr, allow_unreachable).await
+        }
         HistoryEndpoint::DataRadius => {
             let radius = network.overlay.data_radius();
-            Ok(json!(*radius))
+            // Serialized as a 32-byte hex string, like the data radius of a pong.
+            Ok(json!(B256::from(*radius)))
         }
         HistoryEndpoint::DeleteEnr(node_id) => delete_enr(network, node_id).await,
         HistoryEndpoint::FindContent(enr, content_key) => {
             find_content(network, enr, content_key).await
         }
-        HistoryEndpoint::FindNodes(enr, distances) => find_nodes(network, enr, distances).await,
+        HistoryEndpoint::FetchAndStore(enr, content_key, ignore_radius) => {
+            fetch_and_store(network, enr, content_key, ignore_radius).await
+        }
+        HistoryEndpoint::FindNodes(enr, distances, max_results) => {
+            find_nodes(network, enr, distances, max_results).await
+        }
         HistoryEndpoint::GetEnr(node_id) => get_enr(network, node_id).await,
+        HistoryEndpoint::GetEnrInfo(node_id) => get_enr_info(network, node_id).await,
         HistoryEndpoint::Gossip(content_key, content_value) => {
             gossip(network, content_key, content_value).await
         }
@@ -70,6 +107,9 @@ async fn complete_request(network: Arc<HistoryNetwork>, request: HistoryJsonRpcR
         }
         HistoryEndpoint::LookupEnr(node_id) => lookup_enr(network, node_id).await,
         HistoryEndpoint::Offer(enr, content_items) => offer(network, enr, content_items).await,
+        HistoryEndpoint::OfferMany(enr, content_items) => {
+            offer_many(network, enr, content_items).await
+        }
         HistoryEndpoint::TraceOffer(enr, content_key, content_value) => {
             trace_offer(network, enr, content_key, content_value).await
         }
@@ -81,7 +121,16 @@ async fn complete_request(network: Arc<HistoryNetwork>, request: HistoryJsonRpcR
         HistoryEndpoint::RecursiveFindNodes(node_id) => {
             recursive_find_nodes(network, node_id).await
         }
+        HistoryEndpoint::ExportContentBatch(after, limit) => {
+            export_content_batch(network, after, limit).await
+        }
+        HistoryEndpoint::ImportContent(content_items) => {
+            import_content(network, content_items).await
+        }
     };
+    if let Err(err) = &response {
+        debug!(error = %err, "RPC request failed");
+    }
     let _ = request.resp.send(response);
 }
 
@@ -134,10 +183,13 @@ async fn get_content(
                     message,
                     utp,
                     trace,
+                    cached_at_ms,
+                    ..
                 } => {
                     let err = json!({
                         "message": format!("{message}: utp: {utp}"),
-                        "trace": trace
+                        "trace": trace,
+                        "cachedAtMs": cached_at_ms,
                     });
                     return Err(err.to_string());
                 }
@@ -178,10 +230,30 @@ async fn get_content(
 async fn local_content(
     network: Arc<HistoryNetwork>,
     content_key: HistoryContentKey,
+    include_meta: bool,
+    include_rank: bool,
 ) -> Result<Value, String> {
-    let response = match network.overlay.store.read().get(&content_key)
+    let store = network.overlay.store.read();
+    let response = match store.get(&content_key)
         {
             Ok(val) => match val {
+                Some(val) if include_meta || include_rank => {
+                    let meta = store.meta(&content_key).map_err(|err| format!(
+                        "Database error while looking for metadata of content key in local storage: {content_key:?}, with error: {err}",
+                    ))?;
+                    let distance_rank = if include_rank {
+                        store.distance_rank(&content_key).map_err(|err| format!(
+                            "Database error while looking for distance rank of content key in local storage: {content_key:?}, with error: {err}",
+                        ))?
+                    } else {
+                        None
+                    };
+                    Ok(json!(LocalContentInfo::WithMeta {
+                        content: val,
+                        meta: meta.unwrap_or_else(LocalContentMeta::unknown),
+                        distance_rank,
+                    }))
+                }
                 Some(val) => {
                     Ok(Value::String(hex_encode(val)))
                 }
@@ -199,13 +271,91 @@ async fn local_content(
     response
 }
 
+/// Constructs a JSON call for the ContentProviders method.
+async fn content_providers(
+    network: Arc<HistoryNetwork>,
+    content_key: HistoryContentKey,
+) -> Result<Value, String> {
+    let meta = network.overlay.store.read().meta(&content_key).map_err(|err| {
+        format!(
+            "Database error while looking for metadata of content key in local storage: {content_key:?}, with error: {err}",
+        )
+    })?;
+    match meta {
+        Some(meta) => Ok(json!(meta.providers())),
+        None => {
+            let err = json!({
+                "message": "Content not found in local storage",
+            });
+            Err(err.to_string())
+        }
+    }
+}
+
+/// Constructs a JSON call for the SimulateIncomingOffer method.
+async fn simulate_incoming_offer(
+    network: Arc<HistoryNetwork>,
+    sample_size: u64,
+) -> Result<Value, String> {
+    // The lock of the storage is only held to take what the offers are simulated against.
+    let offer_simulation = network.overlay.store.read().offer_simulation();
+    match offer_simulation.run(sample_size) {
+        Ok(info) => Ok(json!(info)),
+        Err(err) => Err(format!(
+            "Database error while simulating offers of {sample_size} content ids. Error message: {err}"
+        )),
+    }
+}
+
+/// Constructs a JSON call for the ContentSummary method.
+async fn content_summary(network: Arc<HistoryNetwork>) -> Result<Value, String> {
+    match network.overlay.store.read().content_summary() {
+        Ok(summary) => Ok(json!(summary)),
+        Err(err) => Err(format!(
+            "Database error while summarizing local content. Error message: {err}"
+        )),
+    }
+}
+
+/// Constructs a JSON call for the VerifyHeader method.
+async fn verify_header(
+    network: Arc<HistoryNetwork>,
+    header_with_proof: RawContentValue,
+) -> Result<Value, String> {
+    let header_with_proof = HeaderWithProof::from_ssz_bytes(&header_with_proof)
+        .map_err(|err| format!("Invalid header with proof encoding: {err:?}"))?;
+    let header_oracle = network.header_oracle.read().await;
+    let header_validator = &header_oracle.header_validator;
+    let info = match header_oracle
+        .historical_summaries_for_proof(&header_with_proof.proof)
+        .await
+    {
+        Ok(historical_summaries) => header_validator
+            .verify_header_with_proof(&header_with_proof, historical_summaries.as_deref()),
+        // Post-Capella headers are rejected when gossiped if their proof can't be verified.
+        Err(err) => HeaderVerificationInfo {
+            accepted: false,
+            proof_verified: false,
+            error: Some(err.to_string()),
+            ..header_validator.verify_header_with_proof(&header_with_proof, None)
+        },
+    };
+    Ok(json!(info))
+}
+
 /// Constructs a JSON call for the PaginateLocalContentKeys method.
 async fn paginate_local_content_keys(
     network: Arc<HistoryNetwork>,
     offset: u64,
     limit: u64,
+    provenance: Option<ContentProvenance>,
+    source: Option<String>,
 ) -> Result<Value, String> {
-    let response = match network.overlay.store.read().paginate(offset, limit)
+    let response = match network
+        .overlay
+        .store
+        .read()
+        .paginate(offset, limit, provenance, source.as_deref())
         {
             Ok(val) => Ok(json!(val)),
             Err(err) => Err(format!(
@@ -215,31 +365,140 @@ async fn paginate_local_content_keys(
     response
 }
 
+/// Constructs a JSON call for the PaginateLocalContentKeysAfter method.
+async fn paginate_local_content_keys_after(
+    network: Arc<HistoryNetwork>,
+    after: Option<B256>,
+    limit: u64,
+    provenance: Option<ContentProvenance>,
+    source: Option<String>,
+) -> Result<Value, String> {
+    match network
+        .overlay
+        .store
+        .read()
+        .paginate_after(after, limit, provenance, source.as_deref())
+    {
+        Ok(val) => Ok(json!(val)),
+        Err(err) => Err(format!(
+            "Database error while paginating local content keys after: {after:?}, limit: {limit:?}. Error message: {err}"
+        )),
+    }
+}
+
 /// Constructs a JSON call for the Store method.
 async fn store(
     network: Arc<HistoryNetwork>,
     content_key: HistoryContentKey,
     content_value: ethportal_api::HistoryContentValue,
+    source: Option<String>,
+    signature: Option<Bytes>,
 ) -> Result<Value, String> {
+    content_value
+        .ensure_valid_for_key(&content_key)
+        .map_err(|err| err.to_json_string())?;
     let data = content_value.encode().to_vec();
-    let response = match network
-        .overlay
-        .store
-        .write()
-        .put::<Vec<u8>>(content_key, data)
-    {
-        Ok(_) => Ok(Value::Bool(true)),
+    // Content with a signature that doesn't verify isn't stored at all.
+    let trusted = match &signature {
+        Some(signature) => {
+            verify_content_signature(
+                &network.trusted_content_signers,
+                &content_key.to_bytes(),
+                &data,
+                signature,
+            )?;
+            true
+        }
+        None => false,
+    };
+    let result = network.overlay.store.write().put_with_labels(
+        content_key.clone(),
+        data,
+        ContentProvenance::RpcStore,
+        /* offered_by= */ None,
+        &ContentLabels { source, trusted },
+    );
+    let response = match result {
+        Ok(_) => {
+            network.overlay.invalidate_failed_lookup(&content_key);
+            Ok(Value::Bool(true))
+        }
         Err(err) => Ok(Value::String(err.to_string())),
     };
     response
 }
 
+/// Constructs a JSON call for the DeleteContentBySource method.
+async fn delete_content_by_source(
+    network: Arc<HistoryNetwork>,
+    source: String,
+) -> Result<Value, String> {
+    match network.overlay.store.write().delete_by_source(&source) {
+        Ok(deleted_keys) => Ok(json!(deleted_keys.len())),
+        Err(err) => Err(format!(
+            "Database error while deleting content with source: {source:?}. Error message: {err}"
+        )),
+    }
+}
+
+/// Constructs a JSON call for the ExportContentBatch method.
+async fn export_content_batch(
+    network: Arc<HistoryNetwork>,
+    after: Option<B256>,
+    limit: u64,
+) -> Result<Value, String> {
+    // The read lock is held only for a single batch, so the export doesn't block writes.
+    match network.overlay.store.read().export_batch(after, limit) {
+        Ok(records) => Ok(json!(records)),
+        Err(err) => Err(format!(
+            "Database error while exporting local content after: {after:?}, limit: {limit:?}. Error message: {err}"
+        )),
+    }
+}
+
+/// Constructs a JSON call for the ImportContent method.
+async fn import_content(
+    network: Arc<HistoryNetwork>,
+    content_items: Vec<(HistoryContentKey, HistoryContentValue)>,
+) -> Result<Value, String> {
+    let mut info = ImportContentInfo {
+        stored: 0,
+        failed: vec![],
+    };
+    for (content_key, content_value) in content_items {
+        if let Err(err) = content_value.ensure_valid_for_key(&content_key) {
+            debug!(content.key = %content_key, "Failed to import content: {err}");
+            info.failed.push(content_key);
+            continue;
+        }
+        // The write lock is re-acquired for every item, so that other requests can progress.
+        let result = network.overlay.store.write().put_with_meta(
+            content_key.clone(),
+            content_value.encode(),
+            ContentProvenance::Import,
+            /* offered_by= */ None,
+        );
+        match result {
+            Ok(_) => {
+                network.overlay.invalidate_failed_lookup(&content_key);
+                info.stored += 1;
+            }
+            Err(err) => {
+                debug!(content.key = %content_key, "Failed to import content: {err}");
+                info.failed.push(content_key);
+            }
+        }
+    }
+    Ok(json!(info))
+}
+
 /// Constructs a JSON call for the AddEnr method.
 async fn add_enr(
     network: Arc<HistoryNetwork>,
     enr: discv5::enr::Enr<discv5::enr::CombinedKey>,
+    allow_unreachable: bool,
 ) -> Result<Value, String> {
-    match network.overlay.add_enr(enr) {
+    match network.overlay.add_enr(enr, allow_unreachable) {
         Ok(_) => Ok(json!(true)),
         Err(err) => Err(format!("AddEnr failed: {err:?}")),
     }
@@ -253,6 +512,18 @@ async fn get_enr(network: Arc<HistoryNetwork>, node_id: NodeId) -> Result<Value,
     }
 }
 
+/// Constructs a JSON call for the GetEnrInfo method.
+async fn get_enr_info(network: Arc<HistoryNetwork>, node_id: NodeId) -> Result<Value, String> {
+    match network.overlay.get_enr_with_source(node_id) {
+        Ok((enr, source)) => Ok(json!(GetEnrInfo {
+            enr,
+            source,
+            multiaddrs: None,
+        })),
+        Err(err) => Err(format!("GetEnrInfo failed: {err:?}")),
+    }
+}
+
 /// Constructs a JSON call for the deleteEnr method.
 async fn delete_enr(network: Arc<HistoryNetwork>, node_id: NodeId) -> Result<Value, String> {
     let is_deleted = network.overlay.delete_enr(node_id);
@@ -286,7 +557,24 @@ async fn find_content(
                 "enrs": enrs,
             })),
         },
-        Err(msg) => Err(format!("FindContent request timeout: {msg:?}")),
+        Err(err) => Err(json!(OverlayRequestJsonError::new("FindContent", &err)).to_string()),
+    }
+}
+
+/// Constructs a JSON call for the FetchAndStore method.
+async fn fetch_and_store(
+    network: Arc<HistoryNetwork>,
+    enr: discv5::enr::Enr<discv5::enr::CombinedKey>,
+    content_key: HistoryContentKey,
+    ignore_radius: bool,
+) -> Result<Value, String> {
+    match network
+        .overlay
+        .fetch_and_store(enr, content_key, ignore_radius)
+        .await
+    {
+        Ok(info) => Ok(json!(info)),
+        Err(err) => Err(json!(OverlayRequestJsonError::new("FetchAndStore", &err)).to_string()),
     }
 }
 
@@ -295,14 +583,16 @@ async fn find_nodes(
     network: Arc<HistoryNetwork>,
     enr: discv5::enr::Enr<discv5::enr::CombinedKey>,
     distances: Vec<u16>,
+    max_results: Option<u64>,
 ) -> Result<Value, String> {
     match network.overlay.send_find_nodes(enr, distances).await {
         Ok(nodes) => Ok(json!(nodes
             .enrs
             .into_iter()
+            .take(max_results.map_or(usize::MAX, |max_results| max_results as usize))
             .map(|enr| enr.into())
             .collect::<FindNodesInfo>())),
-        Err(msg) => Err(format!("FindNodes request timeout: {msg:?}")),
+        Err(err) => Err(json!(OverlayRequestJsonError::new("FindNodes", &err)).to_string()),
     }
 }
 
@@ -312,10 +602,15 @@ async fn gossip(
     content_key: HistoryContentKey,
     content_value: ethportal_api::HistoryContentValue,
 ) -> Result<Value, String> {
+    content_value
+        .ensure_valid_for_key(&content_key)
+        .map_err(|err| err.to_json_string())?;
     let data = content_value.encode();
+    store_gossiped_content(&network, &content_key, &data);
     Ok(network
         .overlay
         .propagate_gossip(vec![(content_key, data)])
+        .offered
         .into())
 }
 
@@ -325,7 +620,11 @@ async fn trace_gossip(
     content_key: HistoryContentKey,
     content_value: ethportal_api::HistoryContentValue,
 ) -> Result<Value, String> {
+    content_value
+        .ensure_valid_for_key(&content_key)
+        .map_err(|err| err.to_json_string())?;
     let data = content_value.encode();
+    store_gossiped_content(&network, &content_key, &data);
     Ok(json!(
         network
             .overlay
@@ -334,21 +633,87 @@ async fn trace_gossip(
     ))
 }
 
+/// Stores gossiped content locally if it's within the radius and not already stored, the same
+/// way as content accepted from an offer. Failing to store it doesn't prevent the gossip.
+fn store_gossiped_content(
+    network: &HistoryNetwork,
+    content_key: &HistoryContentKey,
+    data: &RawContentValue,
+) {
+    let should_store = network
+        .overlay
+        .store
+        .read()
+        .is_key_within_radius_and_unavailable(content_key);
+    match should_store {
+        Ok(ShouldWeStoreContent::Store) => {
+            if let Err(err) = network.overlay.store.write().put_with_meta(
+                content_key.clone(),
+                data,
+                ContentProvenance::Gossip,
+                /* offered_by= */ None,
+            ) {
+                error!(content.key = %content_key, "Unable to store gossiped content: {err}");
+                return;
+            }
+            network.overlay.invalidate_failed_lookup(content_key);
+        }
+        Ok(_) => {}
+        Err(err) => error!("Unable to read store: {err}"),
+    }
+}
+
 /// Constructs a JSON call for the Offer method.
 async fn offer(
     network: Arc<HistoryNetwork>,
     enr: discv5::enr::Enr<discv5::enr::CombinedKey>,
     content_items: Vec<(HistoryContentKey, HistoryContentValue)>,
 ) -> Result<Value, String> {
+    for (content_key, content_value) in &content_items {
+        content_value
+            .ensure_valid_for_key(content_key)
+            .map_err(|err| err.to_json_string())?;
+    }
     let content_items = content_items
         .into_iter()
         .map(|(key, value)| (key.to_bytes(), value.encode()))
         .collect();
+    match network
+        .overlay
+        .send_offer_with_accept_bytes(enr, content_items)
+        .await
+    {
+        Ok((accept, accept_bytes)) => Ok(json!(
+            AcceptInfo::new(accept.content_keys).with_accept_bitfield(accept_bytes)
+        )),
+        Err(err) => Err(json!(OverlayRequestJsonError::new("Offer", &err)).to_string()),
+    }
+}
+
+/// Constructs a JSON call for the OfferMany method.
+async fn offer_many(
+    network: Arc<HistoryNetwork>,
+    enr: discv5::enr::Enr<discv5::enr::CombinedKey>,
+    content_items: Vec<(HistoryContentKey, HistoryContentValue)>,
+) -> Result<Value, String> {
+    for (content_key, content_value) in &content_items {
+        content_value
+            .ensure_valid_for_key(content_key)
+            .map_err(|err| err.to_json_string())?;
+    }
+    let (content_keys, content_items): (Vec<_>, Vec<_>) = content_items
+        .into_iter()
+        .map(|(key, value)| {
+            let content_item = (key.to_bytes(), value.encode());
+            (key, content_item)
+        })
+        .unzip();
     match network.overlay.send_offer(enr, content_items).await {
-        Ok(accept) => Ok(json!(AcceptInfo {
-            content_keys: accept.content_keys,
-        })),
-        Err(msg) => Err(format!("Offer request timeout: {msg:?}")),
+        Ok(accept) => Ok(json!(OfferManyInfo::new(
+            content_keys,
+            &accept.content_keys
+        ))),
+        Err(err) => Err(json!(OverlayRequestJsonError::new("OfferMany", &err)).to_string()),
     }
 }
 
@@ -359,13 +724,16 @@ async fn trace_offer(
     content_key: HistoryContentKey,
     content_value: HistoryContentValue,
 ) -> Result<Value, String> {
+    content_value
+        .ensure_valid_for_key(&content_key)
+        .map_err(|err| err.to_json_string())?;
     match network
         .overlay
         .send_offer_trace(enr, content_key.to_bytes(), content_value.encode())
         .await
     {
         Ok(accept) => Ok(json!(accept)),
-        Err(msg) => Err(format!("Offer request timeout: {msg:?}")),
+        Err(err) => Err(json!(OverlayRequestJsonError::new("Offer", &err)).to_string()),
     }
 }
 
@@ -374,12 +742,9 @@ async fn ping(
     network: Arc<HistoryNetwork>,
     enr: discv5::enr::Enr<discv5::enr::CombinedKey>,
 ) -> Result<Value, String> {
-    match network.overlay.send_ping(enr).await {
-        Ok(pong) => Ok(json!(PongInfo {
-            enr_seq: pong.enr_seq,
-            data_radius: *Distance::from(pong.custom_payload),
-        })),
-        Err(msg) => Err(format!("Ping request timeout: {msg:?}")),
+    match network.overlay.send_ping_info(enr).await {
+        Ok(pong_info) => Ok(json!(pong_info)),
+        Err(err) => Err(json!(OverlayRequestJsonError::new("Ping", &err)).to_string()),
     }
 }
 
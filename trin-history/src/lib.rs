@@ -29,7 +29,7 @@ use utp_rs::socket::UtpSocket;
 use crate::{events::HistoryEvents, jsonrpc::HistoryRequestHandler};
 
 type HistoryHandler = Option<HistoryRequestHandler>;
-type HistoryNetworkTask = Option<JoinHandle<()>>;
+type HistoryNetworkTasks = Vec<JoinHandle<()>>;
 type HistoryMessageTx = Option<mpsc::UnboundedSender<OverlayRequest>>;
 type HistoryJsonRpcTx = Option<mpsc::UnboundedSender<HistoryJsonRpcRequest>>;
 type HistoryEventStream = Option<broadcast::Receiver<EventEnvelope>>;
@@ -42,7 +42,7 @@ pub async fn initialize_history_network(
     header_oracle: Arc<RwLock<HeaderOracle>>,
 ) -> anyhow::Result<(
     HistoryHandler,
-    HistoryNetworkTask,
+    HistoryNetworkTasks,
     HistoryMessageTx,
     HistoryJsonRpcTx,
     HistoryEventStream,
@@ -67,10 +67,10 @@ pub async fn initialize_history_network(
     };
     let history_network_task =
         spawn_history_network(history_network.clone(), portalnet_config, history_event_rx);
-    spawn_history_heartbeat(history_network);
+    let history_heartbeat_task = spawn_history_heartbeat(history_network);
     Ok((
         Some(history_handler),
-        Some(history_network_task),
+        vec![history_network_task, history_heartbeat_task],
         Some(history_event_tx),
         Some(history_jsonrpc_tx),
         Some(event_stream),
@@ -93,19 +93,15 @@ pub fn spawn_history_network(
             message_rx: history_message_rx,
         };
 
-        // Spawn history event handler
-        tokio::spawn(history_events.start());
-
-        // hacky test: make sure we establish a session with the boot node
-        network.overlay.ping_bootnodes().await;
-
-        tokio::signal::ctrl_c()
-            .await
-            .expect("failed to pause until ctrl-c");
+        // Run the history event handler in this task, so that it's stopped along with it.
+        tokio::join!(history_events.start(), async {
+            // hacky test: make sure we establish a session with the boot node
+            network.overlay.ping_bootnodes().await;
+        });
     })
 }
 
-pub fn spawn_history_heartbeat(network: Arc<HistoryNetwork>) {
+pub fn spawn_history_heartbeat(network: Arc<HistoryNetwork>) -> JoinHandle<()> {
     tokio::spawn(async move {
         let mut heart_interval = interval(Duration::from_millis(30000));
 
@@ -120,5 +116,5 @@ pub fn spawn_history_heartbeat(network: Arc<HistoryNetwork>) {
             info!("reports~ data: {storage_log}; msgs: {message_log}");
             info!("reports~ utp: {utp_log}");
         }
-    });
+    })
 }
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use ethportal_api::{
     types::{distance::XorMetric, network::Subnetwork},
+    utils::content_signature::PublicKey,
     HistoryContentKey,
 };
 use parking_lot::RwLock as PLRwLock;
@@ -27,6 +28,9 @@ const GOSSIP_DROPPED: bool = true;
 pub struct HistoryNetwork {
     pub overlay:
         Arc<OverlayProtocol<HistoryContentKey, XorMetric, ChainHistoryValidator, HistoryStorage>>,
+    pub header_oracle: Arc<RwLock<HeaderOracle>>,
+    /// The public keys whose signatures mark content stored over JSON-RPC as trusted.
+    pub trusted_content_signers: Vec<PublicKey>,
 }
 
 impl HistoryNetwork {
@@ -37,15 +41,22 @@ impl HistoryNetwork {
         portal_config: PortalnetConfig,
         header_oracle: Arc<RwLock<HeaderOracle>>,
     ) -> anyhow::Result<Self> {
+        let trusted_content_signers = portal_config.trusted_content_signers;
         let config = OverlayConfig {
             bootnode_enrs: portal_config.bootnodes,
             disable_poke: portal_config.disable_poke,
             gossip_dropped: GOSSIP_DROPPED,
             utp_transfer_limit: portal_config.utp_transfer_limit,
+            failed_lookup_cache: portal_config.failed_lookup_cache,
+            lookup_cache: portal_config.lookup_cache,
+            rebroadcast: portal_config.rebroadcast,
+            gossip: portal_config.history_gossip,
             ..Default::default()
         };
         let storage = Arc::new(PLRwLock::new(HistoryStorage::new(storage_config)?));
-        let validator = Arc::new(ChainHistoryValidator { header_oracle });
+        let validator = Arc::new(ChainHistoryValidator {
+            header_oracle: header_oracle.clone(),
+        });
         let overlay = OverlayProtocol::new(
             config,
             discovery,
@@ -58,6 +69,8 @@ impl HistoryNetwork {
 
         Ok(Self {
             overlay: Arc::new(overlay),
+            header_oracle,
+            trusted_content_signers,
         })
     }
 }
@@ -1,24 +1,77 @@
+use alloy::primitives::B256;
+use discv5::enr::NodeId;
 use ethportal_api::{
-    types::{distance::Distance, network::Subnetwork, portal::PaginateLocalContentInfo},
+    types::{
+        content_key::history::{
+            HISTORY_BLOCK_BODY_KEY_PREFIX, HISTORY_BLOCK_HEADER_BY_HASH_KEY_PREFIX,
+            HISTORY_BLOCK_HEADER_BY_NUMBER_KEY_PREFIX, HISTORY_BLOCK_RECEIPTS_KEY_PREFIX,
+        },
+        distance::Distance,
+        execution::header_with_proof::HeaderWithProof,
+        network::Subnetwork,
+        portal::{
+            BlockNumberGap, ContentDistanceRank, ContentProvenance, ContentRecord,
+            HistoryContentSummaryInfo, HistoryContentTypeCounts, LocalContentMeta,
+            PaginateLocalContentInfo,
+        },
+    },
     HistoryContentKey, OverlayContentKey, RawContentValue,
 };
+use parking_lot::Mutex;
+use r2d2::Pool;
+use r2d2_sqlite::{rusqlite, SqliteConnectionManager};
+use rusqlite::{params, OptionalExtension};
+use ssz::Decode;
+use tracing::info;
 use trin_storage::{
     error::ContentStoreError,
-    versioned::{create_store, ContentType, IdIndexedV1Store, IdIndexedV1StoreConfig},
-    ContentId, ContentStore, PortalStorageConfig, ShouldWeStoreContent,
+    sql::{
+        DELETE_HISTORY_BLOCK_NUMBER_BACKFILL_QUERY, DELETE_HISTORY_BLOCK_NUMBER_QUERY,
+        HISTORY_BLOCK_NUMBER_BACKFILL_CREATE_TABLE, HISTORY_BLOCK_NUMBER_BACKFILL_LOOKUP_QUERY,
+        HISTORY_BLOCK_NUMBER_CREATE_TABLE, HISTORY_BLOCK_NUMBER_GAPS_QUERY,
+        HISTORY_BLOCK_NUMBER_LOOKUP_QUERY, HISTORY_BLOCK_NUMBER_RANGE_QUERY,
+        HISTORY_BLOCK_NUMBER_TABLE_EXISTS_QUERY, INSERT_HISTORY_BLOCK_NUMBER_QUERY,
+        INSERT_OR_REPLACE_HISTORY_BLOCK_NUMBER_BACKFILL_QUERY,
+    },
+    versioned::{
+        create_store, ContentType, IdIndexedV1Store, IdIndexedV1StoreConfig, OfferSimulation,
+    },
+    ContentId, ContentLabels, ContentStore, DataRadiusHistory, PortalStorageConfig,
+    ShouldWeStoreContent,
 };
 
+/// The number of the largest gaps in header coverage that are reported in the content summary.
+const SUMMARY_GAP_COUNT: u64 = 10;
+
+/// The number of entries that are read at once, while indexing the block numbers of the headers
+/// stored before the block number index was introduced.
+const BLOCK_NUMBER_BACKFILL_BATCH_SIZE: u64 = 1000;
+
 /// Storage layer for the history network. Encapsulates history network specific data and logic.
 #[derive(Debug)]
 pub struct HistoryStorage {
     store: IdIndexedV1Store<HistoryContentKey>,
+    sql_connection_pool: Pool<SqliteConnectionManager>,
+    /// The content summary, computed lazily and cleared whenever content is stored.
+    summary: Mutex<Option<HistoryContentSummaryInfo>>,
 }
 
 impl ContentStore for HistoryStorage {
     type Key = HistoryContentKey;
 
     fn get(&self, key: &HistoryContentKey) -> Result<Option<RawContentValue>, ContentStoreError> {
-        self.store.lookup_content_value(&key.content_id().into())
+        let value = self.store.lookup_content_value(&key.content_id().into())?;
+        if value.is_some() {
+            return Ok(value);
+        }
+        // The header may be stored by hash, which has the same content value.
+        let HistoryContentKey::BlockHeaderByNumber(key) = key else {
+            return Ok(None);
+        };
+        match self.lookup_header_content_id(key.block_number)? {
+            Some(content_id) => self.store.lookup_content_value(&content_id),
+            None => Ok(None),
+        }
     }
 
     fn put<V: AsRef<[u8]>>(
@@ -26,58 +79,377 @@ impl ContentStore for HistoryStorage {
         key: HistoryContentKey,
         value: V,
     ) -> Result<Vec<(HistoryContentKey, RawContentValue)>, ContentStoreError> {
-        self.store
-            .insert(&key, RawContentValue::copy_from_slice(value.as_ref()))
+        self.put_with_meta(
+            key,
+            value,
+            ContentProvenance::Unknown,
+            /* offered_by= */ None,
+        )
+    }
+
+    fn put_with_meta<V: AsRef<[u8]>>(
+        &mut self,
+        key: HistoryContentKey,
+        value: V,
+        provenance: ContentProvenance,
+        offered_by: Option<NodeId>,
+    ) -> Result<Vec<(HistoryContentKey, RawContentValue)>, ContentStoreError> {
+        self.put_with_labels(
+            key,
+            value,
+            provenance,
+            offered_by,
+            &ContentLabels::default(),
+        )
     }
 
     fn is_key_within_radius_and_unavailable(
         &self,
         key: &HistoryContentKey,
     ) -> Result<ShouldWeStoreContent, ContentStoreError> {
-        let content_id = ContentId::from(key.content_id());
-        if self.store.distance_to_content_id(&content_id) > self.store.radius() {
-            Ok(ShouldWeStoreContent::NotWithinRadius)
-        } else if self.store.has_content(&content_id)? {
-            Ok(ShouldWeStoreContent::AlreadyStored)
-        } else {
-            Ok(ShouldWeStoreContent::Store)
-        }
+        self.store.should_store(&ContentId::from(key.content_id()))
     }
 
     fn radius(&self) -> Distance {
         self.store.radius()
     }
+
+    fn recompute_radius(&mut self) -> Result<Distance, ContentStoreError> {
+        self.store.recompute_radius()?;
+        Ok(self.store.radius())
+    }
+
+    fn radius_history(&self) -> Option<&DataRadiusHistory> {
+        Some(self.store.radius_history())
+    }
+
+    fn entries_within_radius(
+        &self,
+        node_id: &NodeId,
+        radius: Distance,
+        limit: usize,
+    ) -> Result<Vec<(HistoryContentKey, RawContentValue)>, ContentStoreError> {
+        self.store
+            .entries_within_radius(node_id, radius, limit as u64)
+    }
 }
 
 impl HistoryStorage {
     pub fn new(config: PortalStorageConfig) -> Result<Self, ContentStoreError> {
         let sql_connection_pool = config.sql_connection_pool.clone();
         let config = IdIndexedV1StoreConfig::new(ContentType::History, Subnetwork::History, config);
-        Ok(Self {
-            store: create_store(ContentType::History, config, sql_connection_pool)?,
+        let mut storage = Self {
+            store: create_store(ContentType::History, config, sql_connection_pool.clone())?,
+            sql_connection_pool,
+            summary: Mutex::new(None),
+        };
+
+        let mut conn = storage.sql_connection_pool.get()?;
+        let tx = conn.transaction()?;
+        let has_block_number_index =
+            tx.query_row(HISTORY_BLOCK_NUMBER_TABLE_EXISTS_QUERY, [], |row| {
+                row.get::<_, u64>(0)
+            })? > 0;
+        tx.execute_batch(HISTORY_BLOCK_NUMBER_CREATE_TABLE)?;
+        tx.execute_batch(HISTORY_BLOCK_NUMBER_BACKFILL_CREATE_TABLE)?;
+        if !has_block_number_index {
+            tx.execute(
+                INSERT_OR_REPLACE_HISTORY_BLOCK_NUMBER_BACKFILL_QUERY,
+                params![None::<Vec<u8>>],
+            )?;
+        }
+        tx.commit()?;
+        storage.backfill_block_numbers()?;
+
+        // The content that was dropped while pruning on startup is still in the index.
+        let pruned_keys = storage.store.take_pruned_on_init();
+        storage.forget_deleted_content(&pruned_keys)?;
+        Ok(storage)
+    }
+
+    /// Removes the deleted content from the block number index, and clears the cached summary.
+    fn forget_deleted_content<'a>(
+        &mut self,
+        deleted_keys: impl IntoIterator<Item = &'a HistoryContentKey>,
+    ) -> Result<(), ContentStoreError> {
+        *self.summary.get_mut() = None;
+        let conn = self.sql_connection_pool.get()?;
+        for deleted_key in deleted_keys {
+            conn.execute(
+                DELETE_HISTORY_BLOCK_NUMBER_QUERY,
+                params![deleted_key.content_id().to_vec()],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Indexes the block numbers of the headers that were stored before the block number index
+    /// was introduced, by decoding them.
+    ///
+    /// The headers are indexed in batches, and the progress is recorded with every batch, so an
+    /// interrupted backfill resumes where it stopped. Does nothing if there is no backfill to do.
+    fn backfill_block_numbers(&self) -> Result<(), ContentStoreError> {
+        let mut conn = self.sql_connection_pool.get()?;
+        let Some(mut after) = conn
+            .query_row(HISTORY_BLOCK_NUMBER_BACKFILL_LOOKUP_QUERY, [], |row| {
+                row.get::<_, Option<ContentId>>(0)
+            })
+            .optional()?
+        else {
+            return Ok(());
+        };
+
+        let mut indexed = 0;
+        loop {
+            let batch = self
+                .store
+                .export_batch(after.as_ref(), BLOCK_NUMBER_BACKFILL_BATCH_SIZE)?;
+            let Some((last_key, _)) = batch.last() else {
+                break;
+            };
+            let last_content_id = ContentId::from(last_key.content_id());
+
+            let tx = conn.transaction()?;
+            for (key, value) in &batch {
+                if let Some(block_number) = block_number(key, value) {
+                    tx.execute(
+                        INSERT_HISTORY_BLOCK_NUMBER_QUERY,
+                        params![key.content_id().to_vec(), block_number],
+                    )?;
+                    indexed += 1;
+                }
+            }
+            tx.execute(
+                INSERT_OR_REPLACE_HISTORY_BLOCK_NUMBER_BACKFILL_QUERY,
+                params![last_content_id.to_vec()],
+            )?;
+            tx.commit()?;
+            after = Some(last_content_id);
+        }
+        conn.execute(DELETE_HISTORY_BLOCK_NUMBER_BACKFILL_QUERY, [])?;
+        if indexed > 0 {
+            info!("Indexed the block numbers of {indexed} stored block headers");
+        }
+        Ok(())
+    }
+
+    /// Returns the content id of a stored header of the block number, using the block number
+    /// index, so that headers stored by hash can be found by number.
+    fn lookup_header_content_id(
+        &self,
+        block_number: u64,
+    ) -> Result<Option<ContentId>, ContentStoreError> {
+        Ok(self
+            .sql_connection_pool
+            .get()?
+            .query_row(
+                HISTORY_BLOCK_NUMBER_LOOKUP_QUERY,
+                params![block_number],
+                |row| row.get::<_, ContentId>(0),
+            )
+            .optional()?)
+    }
+
+    /// Returns the summary of the locally stored content. It is computed on the first call after
+    /// content was stored, and cached until then.
+    pub fn content_summary(&self) -> Result<HistoryContentSummaryInfo, ContentStoreError> {
+        let mut summary = self.summary.lock();
+        if let Some(summary) = summary.as_ref() {
+            return Ok(summary.clone());
+        }
+        let computed = self.compute_content_summary()?;
+        *summary = Some(computed.clone());
+        Ok(computed)
+    }
+
+    fn compute_content_summary(&self) -> Result<HistoryContentSummaryInfo, ContentStoreError> {
+        let mut content_type_counts = HistoryContentTypeCounts::default();
+        for (selector, count) in self.store.entry_count_by_key_selector()? {
+            match selector {
+                HISTORY_BLOCK_HEADER_BY_HASH_KEY_PREFIX => {
+                    content_type_counts.block_header_by_hash = count
+                }
+                HISTORY_BLOCK_HEADER_BY_NUMBER_KEY_PREFIX => {
+                    content_type_counts.block_header_by_number = count
+                }
+                HISTORY_BLOCK_BODY_KEY_PREFIX => content_type_counts.block_body = count,
+                HISTORY_BLOCK_RECEIPTS_KEY_PREFIX => content_type_counts.block_receipts = count,
+                _ => {}
+            }
+        }
+
+        let conn = self.sql_connection_pool.get()?;
+        let (lowest_block_number, highest_block_number, header_block_count) =
+            conn.query_row(HISTORY_BLOCK_NUMBER_RANGE_QUERY, [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?;
+        let largest_gaps = conn
+            .prepare(HISTORY_BLOCK_NUMBER_GAPS_QUERY)?
+            .query_map([SUMMARY_GAP_COUNT], |row| {
+                let start = row.get::<_, u64>("gap_start")?;
+                let end = row.get::<_, u64>("gap_end")?;
+                Ok(BlockNumberGap {
+                    start,
+                    end,
+                    length: end - start + 1,
+                })
+            })?
+            .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+        Ok(HistoryContentSummaryInfo {
+            lowest_block_number,
+            highest_block_number,
+            header_block_count,
+            content_type_counts,
+            largest_gaps,
         })
     }
 
+    /// Returns up to `limit` locally stored content entries whose content id is greater than
+    /// `after`, ordered by content id. See [IdIndexedV1Store::export_batch] for details.
+    pub fn export_batch(
+        &self,
+        after: Option<B256>,
+        limit: u64,
+    ) -> Result<Vec<ContentRecord<HistoryContentKey>>, ContentStoreError> {
+        let after = after.map(ContentId::from);
+        Ok(self
+            .store
+            .export_batch(after.as_ref(), limit)?
+            .into_iter()
+            .map(|(content_key, content_value)| ContentRecord {
+                content_key,
+                content_value,
+            })
+            .collect())
+    }
+
+    /// Returns how the content got into the store, the peer that offered it and when it was
+    /// stored, if it is stored.
+    pub fn meta(
+        &self,
+        key: &HistoryContentKey,
+    ) -> Result<Option<LocalContentMeta>, ContentStoreError> {
+        self.store.lookup_meta(&key.content_id().into())
+    }
+
+    /// Same as [ContentStore::put_with_meta], but also labels the content, even if it's already
+    /// stored. The content and its labels are written in a single transaction.
+    pub fn put_with_labels<V: AsRef<[u8]>>(
+        &mut self,
+        key: HistoryContentKey,
+        value: V,
+        provenance: ContentProvenance,
+        offered_by: Option<NodeId>,
+        labels: &ContentLabels,
+    ) -> Result<Vec<(HistoryContentKey, RawContentValue)>, ContentStoreError> {
+        let value = RawContentValue::copy_from_slice(value.as_ref());
+        let dropped_content =
+            self.store
+                .insert_with_labels(&key, value.clone(), provenance, offered_by, labels)?;
+        *self.summary.get_mut() = None;
+
+        if let Some(block_number) = block_number(&key, &value) {
+            self.sql_connection_pool.get()?.execute(
+                INSERT_HISTORY_BLOCK_NUMBER_QUERY,
+                params![key.content_id().to_vec(), block_number],
+            )?;
+        }
+        self.forget_deleted_content(dropped_content.iter().map(|(dropped_key, _)| dropped_key))?;
+        Ok(dropped_content)
+    }
+
+    /// Deletes all the content labeled with the source, and returns the content keys of the
+    /// deleted content.
+    pub fn delete_by_source(
+        &mut self,
+        source: &str,
+    ) -> Result<Vec<HistoryContentKey>, ContentStoreError> {
+        let deleted_keys = self.store.delete_by_source(source)?;
+        self.forget_deleted_content(&deleted_keys)?;
+        Ok(deleted_keys)
+    }
+
+    /// Returns the rank of the content among all stored content by distance to the local node.
+    pub fn distance_rank(
+        &self,
+        key: &HistoryContentKey,
+    ) -> Result<Option<ContentDistanceRank>, ContentStoreError> {
+        self.store.lookup_distance_rank(&key.content_id().into())
+    }
+
+    /// Returns what offers of synthetic content ids, spread evenly across the keyspace, are
+    /// simulated against, so that the simulation doesn't hold the lock of the storage.
+    pub fn offer_simulation(&self) -> OfferSimulation {
+        self.store.offer_simulation()
+    }
+
     /// Get a summary of the current state of storage
     pub fn get_summary_info(&self) -> String {
         self.store.get_summary_info()
     }
 
     /// Returns a paginated list of all available content keys from local storage (from any
-    /// subnetwork) according to the provided offset and limit.
+    /// subnetwork) according to the provided offset and limit, optionally only the content with
+    /// the provenance and the source.
     pub fn paginate(
         &self,
         offset: u64,
         limit: u64,
+        provenance: Option<ContentProvenance>,
+        source: Option<&str>,
+    ) -> Result<PaginateLocalContentInfo<HistoryContentKey>, ContentStoreError> {
+        let paginate_result = self.store.paginate(offset, limit, provenance, source)?;
+        Ok(PaginateLocalContentInfo {
+            content_keys: paginate_result.content_keys,
+            total_entries: paginate_result.entry_count,
+            next_cursor: None,
+        })
+    }
+
+    /// Returns up to `limit` locally available content keys whose content id is greater than
+    /// `after`, ordered by content id, optionally only the content with the provenance and the
+    /// source. See [IdIndexedV1Store::paginate_after] for details.
+    ///
+    /// The content id of the last key is returned as the cursor of the next page, unless the page
+    /// isn't full.
+    pub fn paginate_after(
+        &self,
+        after: Option<B256>,
+        limit: u64,
+        provenance: Option<ContentProvenance>,
+        source: Option<&str>,
     ) -> Result<PaginateLocalContentInfo<HistoryContentKey>, ContentStoreError> {
-        let paginate_result = self.store.paginate(offset, limit)?;
+        let after = after.map(ContentId::from);
+        let paginate_result =
+            self.store
+                .paginate_after(after.as_ref(), limit, provenance, source)?;
+        let next_cursor = match paginate_result.content_keys.last() {
+            Some(content_key) if paginate_result.content_keys.len() as u64 == limit => {
+                Some(B256::from(content_key.content_id()))
+            }
+            _ => None,
+        };
         Ok(PaginateLocalContentInfo {
             content_keys: paginate_result.content_keys,
             total_entries: paginate_result.entry_count,
+            next_cursor,
         })
     }
 }
 
+/// Returns the block number of the block header, from the content key if it has it, otherwise by
+/// decoding the header with proof. Returns `None` for the other content types.
+fn block_number(key: &HistoryContentKey, value: &RawContentValue) -> Option<u64> {
+    match key {
+        HistoryContentKey::BlockHeaderByNumber(key) => Some(key.block_number),
+        HistoryContentKey::BlockHeaderByHash(_) => HeaderWithProof::from_ssz_bytes(value)
+            .ok()
+            .map(|header| header.header.number),
+        HistoryContentKey::BlockBody(_) | HistoryContentKey::BlockReceipts(_) => None,
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 pub mod test {
@@ -131,4 +503,248 @@ pub mod test {
         temp_dir.close()?;
         Ok(())
     }
+
+    /// Stores the headers of the blocks by number, the header with proof of block #15040641 by
+    /// hash, and a block body.
+    fn store_summary_fixture(storage: &mut HistoryStorage, block_numbers: &[u64]) {
+        for block_number in block_numbers {
+            let content_key = HistoryContentKey::new_block_header_by_number(*block_number);
+            storage.put(content_key, [0u8; 32]).unwrap();
+        }
+        let header = std::fs::read("../test_assets/mainnet/large_content/15040641/header.bin")
+            .expect("cannot find test asset");
+        let block_hash = HeaderWithProof::from_ssz_bytes(&header)
+            .unwrap()
+            .header
+            .hash();
+        storage
+            .put(
+                HistoryContentKey::new_block_header_by_hash(block_hash),
+                header,
+            )
+            .unwrap();
+        storage
+            .put(HistoryContentKey::new_block_body(block_hash), [0u8; 32])
+            .unwrap();
+    }
+
+    fn gap(start: u64, end: u64) -> BlockNumberGap {
+        BlockNumberGap {
+            start,
+            end,
+            length: end - start + 1,
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_content_summary() -> Result<(), ContentStoreError> {
+        let (temp_dir, storage_config) =
+            create_test_portal_storage_config_with_capacity(CAPACITY_MB).unwrap();
+        let mut storage = HistoryStorage::new(storage_config)?;
+        let summary = storage.content_summary()?;
+        assert_eq!(summary.lowest_block_number, None);
+        assert_eq!(summary.header_block_count, 0);
+        assert!(summary.largest_gaps.is_empty());
+
+        store_summary_fixture(&mut storage, &[1, 2, 3, 7, 8, 20]);
+        let summary = storage.content_summary()?;
+        assert_eq!(summary.lowest_block_number, Some(1));
+        assert_eq!(summary.highest_block_number, Some(15040641));
+        assert_eq!(summary.header_block_count, 7);
+        assert_eq!(
+            summary.content_type_counts,
+            HistoryContentTypeCounts {
+                block_header_by_hash: 1,
+                block_header_by_number: 6,
+                block_body: 1,
+                block_receipts: 0,
+            }
+        );
+        assert_eq!(
+            summary.largest_gaps,
+            vec![gap(21, 15040640), gap(9, 19), gap(4, 6)]
+        );
+
+        // Storing content invalidates the cached summary.
+        storage.put(HistoryContentKey::new_block_header_by_number(5), [0u8; 32])?;
+        assert_eq!(
+            storage.content_summary()?.largest_gaps,
+            vec![gap(21, 15040640), gap(9, 19), gap(4, 4), gap(6, 6)]
+        );
+
+        drop(storage);
+        temp_dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_content_summary_backfills_block_numbers() -> Result<(), ContentStoreError> {
+        let (temp_dir, storage_config) =
+            create_test_portal_storage_config_with_capacity(CAPACITY_MB).unwrap();
+        let mut storage = HistoryStorage::new(storage_config.clone())?;
+        store_summary_fixture(&mut storage, &[10]);
+        drop(storage);
+
+        // Databases created before the block number index was introduced don't have it.
+        storage_config
+            .sql_connection_pool
+            .get()
+            .unwrap()
+            .execute_batch("DROP TABLE history_block_number")?;
+
+        let storage = HistoryStorage::new(storage_config)?;
+        let summary = storage.content_summary()?;
+        assert_eq!(summary.lowest_block_number, Some(10));
+        assert_eq!(summary.highest_block_number, Some(15040641));
+        assert_eq!(summary.largest_gaps, vec![gap(11, 15040640)]);
+
+        drop(storage);
+        temp_dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_block_number_backfill_resumes() -> Result<(), ContentStoreError> {
+        let (temp_dir, storage_config) =
+            create_test_portal_storage_config_with_capacity(CAPACITY_MB).unwrap();
+        let mut storage = HistoryStorage::new(storage_config.clone())?;
+        let block_numbers = [10, 11];
+        for block_number in block_numbers {
+            let content_key = HistoryContentKey::new_block_header_by_number(block_number);
+            storage.put(content_key, [0u8; 32])?;
+        }
+        drop(storage);
+
+        // The backfill was interrupted after indexing the header with the lower content id.
+        let [first, second] = block_numbers.map(|block_number| {
+            let content_id =
+                HistoryContentKey::new_block_header_by_number(block_number).content_id();
+            (content_id, block_number)
+        });
+        let (indexed, not_indexed) = if first.0 < second.0 {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let conn = storage_config.sql_connection_pool.get().unwrap();
+        conn.execute_batch("DELETE FROM history_block_number")?;
+        conn.execute(
+            INSERT_OR_REPLACE_HISTORY_BLOCK_NUMBER_BACKFILL_QUERY,
+            params![indexed.0.to_vec()],
+        )?;
+        drop(conn);
+
+        let storage = HistoryStorage::new(storage_config)?;
+        let summary = storage.content_summary()?;
+        assert_eq!(summary.header_block_count, 1);
+        assert_eq!(summary.lowest_block_number, Some(not_indexed.1));
+
+        drop(storage);
+        temp_dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_get_header_by_number_uses_block_number_index() -> Result<(), ContentStoreError> {
+        let (temp_dir, storage_config) =
+            create_test_portal_storage_config_with_capacity(CAPACITY_MB).unwrap();
+        let mut storage = HistoryStorage::new(storage_config)?;
+        let header = std::fs::read("../test_assets/mainnet/large_content/15040641/header.bin")
+            .expect("cannot find test asset");
+        let block_hash = HeaderWithProof::from_ssz_bytes(&header)
+            .unwrap()
+            .header
+            .hash();
+        storage.put(
+            HistoryContentKey::new_block_header_by_hash(block_hash),
+            &header,
+        )?;
+
+        // Only the header by hash is stored, and it is found by number through the index.
+        let result = storage.get(&HistoryContentKey::new_block_header_by_number(15040641))?;
+        assert_eq!(result, Some(RawContentValue::from(header)));
+        assert_eq!(
+            storage.get(&HistoryContentKey::new_block_header_by_number(15040642))?,
+            None
+        );
+
+        drop(storage);
+        temp_dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_eviction_removes_block_numbers() -> Result<(), ContentStoreError> {
+        let (temp_dir, storage_config) =
+            create_test_portal_storage_config_with_capacity(1).unwrap();
+        let mut storage = HistoryStorage::new(storage_config)?;
+        let block_numbers = 0..40;
+        for block_number in block_numbers.clone() {
+            // The radius shrinks once content is evicted.
+            let content_key = HistoryContentKey::new_block_header_by_number(block_number);
+            if storage.is_key_within_radius_and_unavailable(&content_key)?
+                == ShouldWeStoreContent::Store
+            {
+                storage.put(content_key, vec![0u8; 100_000])?;
+            }
+        }
+
+        let stored = block_numbers
+            .filter(|block_number| {
+                let content_key = HistoryContentKey::new_block_header_by_number(*block_number);
+                storage
+                    .store
+                    .has_content(&content_key.content_id().into())
+                    .unwrap()
+            })
+            .count() as u64;
+        assert!(stored < 40, "Some headers should have been evicted");
+        assert_eq!(storage.content_summary()?.header_block_count, stored);
+
+        drop(storage);
+        temp_dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_pruning_on_startup_removes_block_numbers() -> Result<(), ContentStoreError> {
+        let (temp_dir, storage_config) =
+            create_test_portal_storage_config_with_capacity(CAPACITY_MB).unwrap();
+        let mut storage = HistoryStorage::new(storage_config.clone())?;
+        let block_numbers = 0..10;
+        for block_number in block_numbers.clone() {
+            let content_key = HistoryContentKey::new_block_header_by_number(block_number);
+            storage.put(content_key, vec![0u8; 100_000])?;
+        }
+        assert_eq!(storage.content_summary()?.header_block_count, 10);
+        drop(storage);
+
+        // The content no longer fits once the capacity is reduced, so it is pruned on startup.
+        let storage_config = PortalStorageConfig {
+            storage_capacity_bytes: 500_000,
+            ..storage_config
+        };
+        let storage = HistoryStorage::new(storage_config)?;
+        let stored = block_numbers
+            .filter(|block_number| {
+                let content_key = HistoryContentKey::new_block_header_by_number(*block_number);
+                storage
+                    .store
+                    .has_content(&content_key.content_id().into())
+                    .unwrap()
+            })
+            .count() as u64;
+        assert!(stored < 10, "Some headers should have been pruned");
+        assert_eq!(storage.content_summary()?.header_block_count, stored);
+
+        drop(storage);
+        temp_dir.close()?;
+        Ok(())
+    }
 }
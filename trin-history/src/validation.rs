@@ -21,6 +21,28 @@ pub struct ChainHistoryValidator {
     pub header_oracle: Arc<RwLock<HeaderOracle>>,
 }
 
+impl ChainHistoryValidator {
+    /// Validates the header with its proof. Post-Capella proofs are verified against the latest
+    /// historical summaries of the beacon network, so they are rejected if the beacon network is
+    /// disabled, hasn't got the historical summaries, or its historical summaries don't cover the
+    /// header yet.
+    async fn validate_header_with_proof(
+        &self,
+        header_with_proof: &HeaderWithProof,
+    ) -> anyhow::Result<()> {
+        let header_oracle = self.header_oracle.read().await;
+        let historical_summaries = header_oracle
+            .historical_summaries_for_proof(&header_with_proof.proof)
+            .await?;
+        header_oracle
+            .header_validator
+            .validate_header_with_proof_and_summaries(
+                header_with_proof,
+                historical_summaries.as_deref(),
+            )
+    }
+}
+
 impl Validator<HistoryContentKey> for ChainHistoryValidator {
     async fn validate_content(
         &self,
@@ -39,11 +61,7 @@ impl Validator<HistoryContentKey> for ChainHistoryValidator {
                     "Content validation failed: Invalid header hash. Found: {header_hash:?} - Expected: {:?}",
                     hex_encode(header_hash)
                 );
-                self.header_oracle
-                    .read()
-                    .await
-                    .header_validator
-                    .validate_header_with_proof(&header_with_proof)?;
+                self.validate_header_with_proof(&header_with_proof).await?;
 
                 Ok(ValidationResult::new(true))
             }
@@ -58,11 +76,7 @@ impl Validator<HistoryContentKey> for ChainHistoryValidator {
                     "Content validation failed: Invalid header number. Found: {header_number} - Expected: {}",
                     key.block_number
                 );
-                self.header_oracle
-                    .read()
-                    .await
-                    .header_validator
-                    .validate_header_with_proof(&header_with_proof)?;
+                self.validate_header_with_proof(&header_with_proof).await?;
 
                 Ok(ValidationResult::new(true))
             }
@@ -76,22 +90,9 @@ impl Validator<HistoryContentKey> for ChainHistoryValidator {
                     .recursive_find_header_by_hash_with_proof(B256::from(key.block_hash))
                     .await?
                     .header;
-                let actual_uncles_root = block_body.uncles_root();
-                if actual_uncles_root != trusted_header.uncles_hash {
-                    return Err(anyhow!(
-                        "Content validation failed: Invalid uncles root. Found: {:?} - Expected: {:?}",
-                        actual_uncles_root,
-                        trusted_header.uncles_hash
-                    ));
-                }
-                let actual_txs_root = block_body.transactions_root()?;
-                if actual_txs_root != trusted_header.transactions_root {
-                    return Err(anyhow!(
-                        "Content validation failed: Invalid transactions root. Found: {:?} - Expected: {:?}",
-                        actual_txs_root,
-                        trusted_header.transactions_root
-                    ));
-                }
+                block_body
+                    .validate_against_header(&trusted_header)
+                    .map_err(|err| anyhow!("Content validation failed: {err}"))?;
                 Ok(ValidationResult::new(true))
             }
             HistoryContentKey::BlockReceipts(key) => {
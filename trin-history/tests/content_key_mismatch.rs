@@ -0,0 +1,82 @@
+use ethportal_api::{
+    types::{
+        content_value::error::{ContentKeyMismatchJsonError, ContentValueError},
+        execution::header_with_proof::HeaderWithProof,
+        jsonrpc::endpoints::HistoryEndpoint,
+        network::Subnetwork,
+    },
+    HistoryContentKey, HistoryContentValue, OverlayContentKey,
+};
+use ssz::Decode;
+use trin_test_utils::TestNetwork;
+
+/// Pre-merge header with proof of block #15040641.
+fn history_header() -> HistoryContentValue {
+    let header = std::fs::read("../test_assets/mainnet/large_content/15040641/header.bin")
+        .expect("cannot find test asset");
+    HistoryContentValue::BlockHeaderWithProof(HeaderWithProof::from_ssz_bytes(&header).unwrap())
+}
+
+fn assert_key_mismatch(result: Result<serde_json::Value, String>, content_key: &HistoryContentKey) {
+    let err = result.unwrap_err();
+    let err = serde_json::from_str::<ContentKeyMismatchJsonError>(&err)
+        .unwrap_or_else(|_| panic!("Unexpected error: {err}"));
+    assert_eq!(
+        ContentValueError::from(err),
+        ContentValueError::KeyMismatch {
+            content_key: content_key.to_hex()
+        }
+    );
+}
+
+#[test_log::test(tokio::test)]
+async fn mismatched_content_is_rejected() {
+    let network = TestNetwork::start(2, &[Subnetwork::History]).await.unwrap();
+    let (node, peer) = (network.node(0), network.node(1));
+    // A header isn't the receipts that the key refers to.
+    let content_key = HistoryContentKey::new_block_receipts([1; 32]);
+    let content_value = history_header();
+
+    assert_key_mismatch(
+        node.history()
+            .request(HistoryEndpoint::Store(
+                content_key.clone(),
+                content_value.clone(),
+                None,
+                None,
+            ))
+            .await,
+        &content_key,
+    );
+    // The content wasn't stored.
+    assert!(node
+        .history()
+        .request(HistoryEndpoint::LocalContent(
+            content_key.clone(),
+            false,
+            false
+        ))
+        .await
+        .is_err());
+
+    assert_key_mismatch(
+        node.history()
+            .request(HistoryEndpoint::Gossip(
+                content_key.clone(),
+                content_value.clone(),
+            ))
+            .await,
+        &content_key,
+    );
+    assert_key_mismatch(
+        node.history()
+            .request(HistoryEndpoint::Offer(
+                peer.enr(),
+                vec![(content_key.clone(), content_value)],
+            ))
+            .await,
+        &content_key,
+    );
+
+    network.shutdown().await;
+}
@@ -0,0 +1,189 @@
+use std::str::FromStr;
+
+use alloy::primitives::B256;
+use ethportal_api::{
+    consensus::{
+        fork::ForkName,
+        historical_summaries::{
+            HistoricalSummaries, HistoricalSummariesStateProof, HistoricalSummariesWithProof,
+        },
+    },
+    types::{
+        content_key::beacon::HistoricalSummariesWithProofKey,
+        content_value::beacon::ForkVersionedHistoricalSummariesWithProof,
+        execution::header_with_proof::{BlockHeaderProof, HistoricalSummariesBlockProof},
+        jsonrpc::endpoints::BeaconEndpoint,
+        network::Subnetwork,
+        portal::HeaderVerificationInfo,
+    },
+    BeaconContentKey, BeaconContentValue,
+};
+use ssz::Decode;
+use trin_test_utils::{TestNetwork, TestNode};
+use trin_validation::constants::SLOTS_PER_EPOCH;
+
+/// The directory of the post-Capella header proofs of the portal spec tests.
+const CAPELLA_PROOFS_DIR: &str =
+    "../portal-spec-tests/tests/mainnet/history/headers_with_proof/block_proofs_capella";
+
+/// The slot of the historical summaries that the post-Capella header proof is valid against.
+const HISTORICAL_SUMMARIES_SLOT: u64 = 8953856;
+
+/// The post-Capella header proof of block #17034870, along with the historical summaries that it
+/// is valid against.
+struct PostCapellaFixture {
+    block_number: u64,
+    header_hash: B256,
+    proof: BlockHeaderProof,
+    historical_summaries: HistoricalSummaries,
+}
+
+fn post_capella_fixture() -> PostCapellaFixture {
+    let block_number = 17034870;
+    let file = std::fs::read_to_string(format!(
+        "{CAPELLA_PROOFS_DIR}/beacon_block_proof-{block_number}.yaml"
+    ))
+    .expect("cannot find test asset");
+    let value: serde_yaml::Value = serde_yaml::from_str(&file).unwrap();
+    let header_hash = B256::from_str(value["execution_block_header"].as_str().unwrap()).unwrap();
+    let proof: HistoricalSummariesBlockProof = serde_yaml::from_value(value).unwrap();
+    let historical_summaries = std::fs::read(format!(
+        "{CAPELLA_PROOFS_DIR}/historical_summaries_at_slot_{HISTORICAL_SUMMARIES_SLOT}.ssz"
+    ))
+    .expect("cannot find test asset");
+    PostCapellaFixture {
+        block_number,
+        header_hash,
+        proof: BlockHeaderProof::HistoricalSummariesBlockProof(proof),
+        historical_summaries: HistoricalSummaries::from_ssz_bytes(&historical_summaries).unwrap(),
+    }
+}
+
+/// Stores the historical summaries in the beacon network of the node.
+async fn store_historical_summaries(node: &TestNode, historical_summaries: HistoricalSummaries) {
+    let epoch = HISTORICAL_SUMMARIES_SLOT / SLOTS_PER_EPOCH;
+    let content_key =
+        BeaconContentKey::HistoricalSummariesWithProof(HistoricalSummariesWithProofKey { epoch });
+    let content_value = BeaconContentValue::HistoricalSummariesWithProof(
+        ForkVersionedHistoricalSummariesWithProof {
+            fork_name: ForkName::Deneb,
+            historical_summaries_with_proof: HistoricalSummariesWithProof {
+                epoch,
+                historical_summaries,
+                proof: HistoricalSummariesStateProof::default(),
+            },
+        },
+    );
+    let result = node
+        .beacon()
+        .request(BeaconEndpoint::Store(content_key, content_value, None))
+        .await
+        .unwrap();
+    assert_eq!(result, true);
+}
+
+/// Verifies the header proof against the historical summaries of the beacon network of the node,
+/// as the history network of the node does with offered headers.
+async fn verify(
+    node: &TestNode,
+    block_number: u64,
+    header_hash: B256,
+    proof: &BlockHeaderProof,
+) -> anyhow::Result<HeaderVerificationInfo> {
+    let header_oracle = node.history().network.header_oracle.read().await;
+    let historical_summaries = header_oracle.historical_summaries_for_proof(proof).await?;
+    Ok(header_oracle.header_validator.verify_header_proof(
+        block_number,
+        header_hash,
+        proof,
+        historical_summaries.as_deref(),
+    ))
+}
+
+#[test_log::test(tokio::test)]
+async fn post_capella_header_is_validated_against_beacon_historical_summaries() {
+    let network = TestNetwork::start(1, &[Subnetwork::History, Subnetwork::Beacon])
+        .await
+        .unwrap();
+    let node = network.node(0);
+    let fixture = post_capella_fixture();
+    store_historical_summaries(node, fixture.historical_summaries).await;
+
+    let info = verify(
+        node,
+        fixture.block_number,
+        fixture.header_hash,
+        &fixture.proof,
+    )
+    .await
+    .unwrap();
+    assert!(info.accepted, "Unexpected error: {:?}", info.error);
+    assert!(info.proof_verified);
+
+    // A proof that doesn't lead to the historical summary is rejected.
+    let mut tampered_proof = fixture.proof;
+    if let BlockHeaderProof::HistoricalSummariesBlockProof(proof) = &mut tampered_proof {
+        proof.historical_summaries_proof[0] = B256::random();
+    }
+    let info = verify(
+        node,
+        fixture.block_number,
+        fixture.header_hash,
+        &tampered_proof,
+    )
+    .await
+    .unwrap();
+    assert!(!info.accepted);
+    let err = info.error.unwrap();
+    assert!(
+        err.contains("HistoricalSummariesProof"),
+        "Unexpected error: {err}"
+    );
+
+    network.shutdown().await;
+}
+
+#[test_log::test(tokio::test)]
+async fn post_capella_header_is_rejected_without_beacon_network() {
+    let network = TestNetwork::start(1, &[Subnetwork::History]).await.unwrap();
+    let fixture = post_capella_fixture();
+
+    let err = verify(
+        network.node(0),
+        fixture.block_number,
+        fixture.header_hash,
+        &fixture.proof,
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("Beacon network required"),
+        "Unexpected error: {err}"
+    );
+
+    network.shutdown().await;
+}
+
+#[test_log::test(tokio::test)]
+async fn post_capella_header_is_rejected_without_historical_summaries() {
+    let network = TestNetwork::start(1, &[Subnetwork::History, Subnetwork::Beacon])
+        .await
+        .unwrap();
+    let fixture = post_capella_fixture();
+
+    let err = verify(
+        network.node(0),
+        fixture.block_number,
+        fixture.header_hash,
+        &fixture.proof,
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Historical summaries required to validate post-Capella header"),
+        "Unexpected error: {err}"
+    );
+
+    network.shutdown().await;
+}
@@ -0,0 +1,285 @@
+use std::time::Duration;
+
+use ethportal_api::{
+    types::{
+        execution::header_with_proof::HeaderWithProof,
+        jsonrpc::endpoints::HistoryEndpoint,
+        network::Subnetwork,
+        portal::{
+            ContentProvenance, ContentProviderInfo, LocalContentInfo, LocalContentMeta,
+            PaginateLocalContentInfo,
+        },
+    },
+    HistoryContentKey, HistoryContentValue,
+};
+use ssz::Decode;
+use trin_test_utils::{assert_routing_contains, wait_for_gossip, TestNetwork, TestNode};
+
+const TRANSFER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Pre-merge header with proof of block #15040641.
+fn history_header_fixture() -> (HistoryContentKey, HistoryContentValue) {
+    let header = std::fs::read("../test_assets/mainnet/large_content/15040641/header.bin")
+        .expect("cannot find test asset");
+    let header = HeaderWithProof::from_ssz_bytes(&header).unwrap();
+    let content_key = HistoryContentKey::new_block_header_by_hash(header.header.hash());
+    (
+        content_key,
+        HistoryContentValue::BlockHeaderWithProof(header),
+    )
+}
+
+/// Returns the metadata of the content, as reported by the LocalContent endpoint.
+async fn meta(node: &TestNode, content_key: &HistoryContentKey) -> LocalContentMeta {
+    let result = node
+        .history()
+        .request(HistoryEndpoint::LocalContent(
+            content_key.clone(),
+            true,
+            false,
+        ))
+        .await
+        .unwrap();
+    match serde_json::from_value::<LocalContentInfo>(result).unwrap() {
+        LocalContentInfo::WithMeta { meta, .. } => meta,
+        info => panic!("Expected content with metadata, got {info:?}"),
+    }
+}
+
+/// Returns the peers that provided the content, as reported by the ContentProviders endpoint.
+async fn providers(node: &TestNode, content_key: &HistoryContentKey) -> Vec<ContentProviderInfo> {
+    let result = node
+        .history()
+        .request(HistoryEndpoint::ContentProviders(content_key.clone()))
+        .await
+        .unwrap();
+    serde_json::from_value(result).unwrap()
+}
+
+/// Returns the locally stored content keys with the provenance.
+async fn paginate(
+    node: &TestNode,
+    provenance: ContentProvenance,
+) -> PaginateLocalContentInfo<HistoryContentKey> {
+    let result = node
+        .history()
+        .request(HistoryEndpoint::PaginateLocalContentKeys(
+            0,
+            10,
+            Some(provenance),
+            None,
+        ))
+        .await
+        .unwrap();
+    serde_json::from_value(result).unwrap()
+}
+
+async fn paginate_by_source(
+    node: &TestNode,
+    source: &str,
+) -> PaginateLocalContentInfo<HistoryContentKey> {
+    let result = node
+        .history()
+        .request(HistoryEndpoint::PaginateLocalContentKeys(
+            0,
+            10,
+            None,
+            Some(source.to_string()),
+        ))
+        .await
+        .unwrap();
+    serde_json::from_value(result).unwrap()
+}
+
+#[test_log::test(tokio::test)]
+async fn provenance_is_recorded_per_write_path() {
+    let network = TestNetwork::start(4, &[Subnetwork::History]).await.unwrap();
+    let (content_key, content_value) = history_header_fixture();
+
+    let result = network
+        .node(0)
+        .history()
+        .request(HistoryEndpoint::Store(
+            content_key.clone(),
+            content_value.clone(),
+            None,
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result, true);
+    assert_eq!(
+        meta(network.node(0), &content_key).await.provenance,
+        ContentProvenance::RpcStore
+    );
+
+    network
+        .node(1)
+        .history()
+        .request(HistoryEndpoint::ImportContent(vec![(
+            content_key.clone(),
+            content_value.clone(),
+        )]))
+        .await
+        .unwrap();
+    assert_eq!(
+        meta(network.node(1), &content_key).await.provenance,
+        ContentProvenance::Import
+    );
+
+    network
+        .node(2)
+        .history()
+        .request(HistoryEndpoint::GetContent(content_key.clone()))
+        .await
+        .unwrap();
+    wait_for_gossip(network.node(2), &content_key, TRANSFER_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(
+        meta(network.node(2), &content_key).await.provenance,
+        ContentProvenance::FoundViaLookup
+    );
+
+    // The last node may already have been offered the content, as the found content is gossiped
+    // after the lookup. Either way, it got the content through an offer.
+    network
+        .node(0)
+        .history()
+        .request(HistoryEndpoint::Offer(
+            network.node(3).enr(),
+            vec![(content_key.clone(), content_value.clone())],
+        ))
+        .await
+        .unwrap();
+    wait_for_gossip(network.node(3), &content_key, TRANSFER_TIMEOUT)
+        .await
+        .unwrap();
+    let offered_meta = meta(network.node(3), &content_key).await;
+    assert_eq!(offered_meta.provenance, ContentProvenance::Offer);
+    let offered_by = offered_meta.offered_by.unwrap();
+    assert!(
+        [
+            network.node(0).enr().node_id(),
+            network.node(2).enr().node_id()
+        ]
+        .contains(&offered_by),
+        "Unexpected offering peer: {offered_by}"
+    );
+    assert!(offered_meta.stored_at.is_some());
+
+    // Only the content that was offered has a provider.
+    assert_eq!(
+        providers(network.node(3), &content_key).await,
+        vec![ContentProviderInfo {
+            node_id: offered_by,
+            provenance: ContentProvenance::Offer,
+            stored_at: offered_meta.stored_at,
+        }]
+    );
+    assert!(providers(network.node(0), &content_key).await.is_empty());
+    assert_eq!(meta(network.node(0), &content_key).await.offered_by, None);
+
+    // Only the content with the provenance is listed.
+    let offered = paginate(network.node(3), ContentProvenance::Offer).await;
+    assert_eq!(offered.content_keys, vec![content_key.clone()]);
+    assert_eq!(offered.total_entries, Some(1));
+    let stored = paginate(network.node(3), ContentProvenance::RpcStore).await;
+    assert!(stored.content_keys.is_empty());
+    assert_eq!(stored.total_entries, Some(0));
+
+    // Without the metadata, only the content value is returned.
+    let result = network
+        .node(3)
+        .history()
+        .request(HistoryEndpoint::LocalContent(content_key, false, false))
+        .await
+        .unwrap();
+    assert!(matches!(
+        serde_json::from_value::<LocalContentInfo>(result).unwrap(),
+        LocalContentInfo::Content(_)
+    ));
+
+    network.shutdown().await;
+}
+
+#[test_log::test(tokio::test)]
+async fn content_is_deleted_by_source() {
+    let network = TestNetwork::start(1, &[Subnetwork::History]).await.unwrap();
+    let node = network.node(0);
+    let (content_key, content_value) = history_header_fixture();
+
+    let result = node
+        .history()
+        .request(HistoryEndpoint::Store(
+            content_key.clone(),
+            content_value,
+            Some("bad-import".to_string()),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result, true);
+    assert_eq!(
+        meta(node, &content_key).await.source,
+        Some("bad-import".to_string())
+    );
+
+    // Only the content with the source is listed.
+    assert_eq!(
+        paginate_by_source(node, "bad-import").await.content_keys,
+        vec![content_key.clone()]
+    );
+    assert_eq!(
+        paginate_by_source(node, "other-import").await.total_entries,
+        Some(0)
+    );
+
+    let result = node
+        .history()
+        .request(HistoryEndpoint::DeleteContentBySource(
+            "bad-import".to_string(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result, 1);
+    let err = node
+        .history()
+        .request(HistoryEndpoint::LocalContent(content_key, false, false))
+        .await
+        .unwrap_err();
+    assert!(err.contains("not found"), "Unexpected error: {err}");
+
+    network.shutdown().await;
+}
+
+#[test_log::test(tokio::test)]
+async fn gossiped_content_is_stored_locally() {
+    let network = TestNetwork::start(2, &[Subnetwork::History]).await.unwrap();
+    assert_routing_contains(network.node(0), network.node(1), Subnetwork::History);
+    let (content_key, content_value) = history_header_fixture();
+
+    network
+        .node(0)
+        .history()
+        .request(HistoryEndpoint::Gossip(
+            content_key.clone(),
+            content_value.clone(),
+        ))
+        .await
+        .unwrap();
+    let gossiped_meta = meta(network.node(0), &content_key).await;
+    assert_eq!(gossiped_meta.provenance, ContentProvenance::Gossip);
+    assert_eq!(gossiped_meta.offered_by, None);
+
+    // The peer that the content was gossiped to accepted it from an offer.
+    wait_for_gossip(network.node(1), &content_key, TRANSFER_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(
+        meta(network.node(1), &content_key).await.provenance,
+        ContentProvenance::Offer
+    );
+
+    network.shutdown().await;
+}
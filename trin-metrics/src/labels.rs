@@ -46,6 +46,57 @@ impl From<UtpOutcomeLabel> for MetricLabel {
     }
 }
 
+impl From<PokeTriggerLabel> for MetricLabel {
+    fn from(label: PokeTriggerLabel) -> Self {
+        match label {
+            PokeTriggerLabel::Lookup => "lookup",
+            PokeTriggerLabel::NewPeer => "new peer",
+        }
+    }
+}
+
+impl From<PokeOutcomeLabel> for MetricLabel {
+    fn from(label: PokeOutcomeLabel) -> Self {
+        match label {
+            PokeOutcomeLabel::Offered => "offered",
+            PokeOutcomeLabel::AlreadyOffered => "already offered",
+            PokeOutcomeLabel::RateLimited => "rate limited",
+        }
+    }
+}
+
+impl From<GossipOutcomeLabel> for MetricLabel {
+    fn from(label: GossipOutcomeLabel) -> Self {
+        match label {
+            GossipOutcomeLabel::Skipped => "skipped",
+            GossipOutcomeLabel::Declined => "declined",
+            GossipOutcomeLabel::Accepted => "accepted",
+            GossipOutcomeLabel::Transferred => "transferred",
+            GossipOutcomeLabel::Failed => "failed",
+            GossipOutcomeLabel::Dropped => "dropped",
+        }
+    }
+}
+
+impl From<LivenessPingLabel> for MetricLabel {
+    fn from(label: LivenessPingLabel) -> Self {
+        match label {
+            LivenessPingLabel::Sent => "sent",
+            LivenessPingLabel::Skipped => "skipped",
+            LivenessPingLabel::Failed => "failed",
+        }
+    }
+}
+
+impl From<PeerStateLabel> for MetricLabel {
+    fn from(label: PeerStateLabel) -> Self {
+        match label {
+            PeerStateLabel::Connected => "connected",
+            PeerStateLabel::Disconnected => "disconnected",
+        }
+    }
+}
+
 impl From<&Request> for MessageLabel {
     fn from(request: &Request) -> Self {
         match request {
@@ -113,3 +164,62 @@ pub enum UtpOutcomeLabel {
     FailedDataTx,
     FailedShutdown,
 }
+
+/// Poke Trigger Labels
+/// - These label values identify why content was offered to a peer without the peer asking for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PokeTriggerLabel {
+    /// Content found by a lookup, offered to the peers that were closer but didn't have it
+    Lookup,
+    /// Local content offered to a newly discovered peer
+    NewPeer,
+}
+
+/// Poke Outcome Labels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PokeOutcomeLabel {
+    /// Content that was offered to the peer
+    Offered,
+    /// Content that wasn't offered, because it was already offered to the peer
+    AlreadyOffered,
+    /// Content that wasn't offered, because too much content was offered recently
+    RateLimited,
+}
+
+/// Gossip Outcome Labels
+/// - These label values identify why gossiped content wasn't transferred to a peer, or that it was
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GossipOutcomeLabel {
+    /// A peer that content wasn't offered to, because its radius excludes it
+    Skipped,
+    /// An offer that the peer didn't accept any of the content of
+    Declined,
+    /// An offer that the peer accepted some of the content of
+    Accepted,
+    /// Large content, offered on its own, that was transferred to the peer that accepted it
+    Transferred,
+    /// Large content, offered on its own, that the peer didn't respond to the offer of
+    Failed,
+    /// Large content that wasn't offered, because too much large content was being offered
+    Dropped,
+}
+
+/// Liveness Ping Labels
+/// - These label values identify the outcome of the liveness checks of routing table peers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LivenessPingLabel {
+    /// A liveness ping that was sent to a peer
+    Sent,
+    /// A liveness ping that wasn't sent, because the peer was active recently
+    Skipped,
+    /// A liveness ping that the peer didn't respond to
+    Failed,
+}
+
+/// Peer State Labels
+/// - These label values identify the connection state that a routing table peer moved to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerStateLabel {
+    Connected,
+    Disconnected,
+}
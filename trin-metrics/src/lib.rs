@@ -5,5 +5,6 @@ pub mod bridge;
 pub mod labels;
 pub mod overlay;
 pub mod portalnet;
+pub mod rpc;
 pub mod storage;
 pub mod timer;
@@ -2,12 +2,15 @@ use ethportal_api::types::portal_wire::{Request, Response};
 use prometheus_exporter::{
     self,
     prometheus::{
-        opts, register_int_counter_vec_with_registry, register_int_gauge_vec_with_registry,
-        IntCounterVec, IntGaugeVec, Registry,
+        opts, register_int_counter_vec_with_registry, register_int_counter_with_registry,
+        register_int_gauge_vec_with_registry, IntCounter, IntCounterVec, IntGaugeVec, Registry,
     },
 };
 
-use crate::labels::{MessageDirectionLabel, MessageLabel, UtpDirectionLabel, UtpOutcomeLabel};
+use crate::labels::{
+    GossipOutcomeLabel, LivenessPingLabel, MessageDirectionLabel, MessageLabel, PeerStateLabel,
+    PokeOutcomeLabel, PokeTriggerLabel, UtpDirectionLabel, UtpOutcomeLabel,
+};
 
 /// Contains metrics reporters for use in the overlay network
 /// (eg. `portalnet/src/overlay.rs` & `portalnet/src/overlay_service.rs`).
@@ -19,6 +22,12 @@ pub struct OverlayMetrics {
     pub utp_outcome_total: IntCounterVec,
     pub utp_active_gauge: IntGaugeVec,
     pub validation_total: IntCounterVec,
+    pub cross_network_talk_request_total: IntCounterVec,
+    pub poke_total: IntCounterVec,
+    pub gossip_outcome_total: IntCounterVec,
+    pub liveness_ping_total: IntCounterVec,
+    pub peer_state_transition_total: IntCounterVec,
+    pub external_address_change_total: IntCounter,
 }
 
 impl OverlayMetrics {
@@ -55,11 +64,64 @@ impl OverlayMetrics {
             &["protocol", "success"],
             registry
         )?;
+        let cross_network_talk_request_total = register_int_counter_vec_with_registry!(
+            opts!(
+                "trin_cross_network_talk_request_total",
+                "count all dropped talk requests with a protocol id of a different network"
+            ),
+            &["network"],
+            registry
+        )?;
+        let poke_total = register_int_counter_vec_with_registry!(
+            opts!(
+                "trin_poke_total",
+                "count all content offered to peers without them asking for it, by trigger and outcome"
+            ),
+            &["protocol", "trigger", "outcome"],
+            registry
+        )?;
+        let gossip_outcome_total = register_int_counter_vec_with_registry!(
+            opts!(
+                "trin_gossip_outcome_total",
+                "count all peers skipped by gossip because their radius excludes the content, all offers that peers declined or accepted, and the outcomes of large content offered on its own"
+            ),
+            &["protocol", "outcome"],
+            registry
+        )?;
+        let liveness_ping_total = register_int_counter_vec_with_registry!(
+            opts!(
+                "trin_liveness_ping_total",
+                "count all liveness checks of routing table peers, by outcome"
+            ),
+            &["protocol", "outcome"],
+            registry
+        )?;
+        let peer_state_transition_total = register_int_counter_vec_with_registry!(
+            opts!(
+                "trin_peer_state_transition_total",
+                "count all routing table peers that became connected or disconnected"
+            ),
+            &["protocol", "state"],
+            registry
+        )?;
+        let external_address_change_total = register_int_counter_with_registry!(
+            opts!(
+                "trin_external_address_change_total",
+                "count all changes of the external address that the local ENR advertises"
+            ),
+            registry
+        )?;
         Ok(Self {
             message_total,
             utp_outcome_total,
             utp_active_gauge,
             validation_total,
+            cross_network_talk_request_total,
+            poke_total,
+            gossip_outcome_total,
+            liveness_ping_total,
+            peer_state_transition_total,
+            external_address_change_total,
         })
     }
 }
@@ -179,6 +241,47 @@ impl OverlayMetricsReporter {
             .inc();
     }
 
+    //
+    // Pokes
+    //
+    pub fn report_poke(&self, trigger: PokeTriggerLabel, outcome: PokeOutcomeLabel) {
+        let labels: [&str; 3] = [&self.protocol, trigger.into(), outcome.into()];
+        self.overlay_metrics
+            .poke_total
+            .with_label_values(&labels)
+            .inc();
+    }
+
+    //
+    // Gossip
+    //
+    pub fn report_gossip_outcome(&self, outcome: GossipOutcomeLabel, count: u64) {
+        let labels: [&str; 2] = [&self.protocol, outcome.into()];
+        self.overlay_metrics
+            .gossip_outcome_total
+            .with_label_values(&labels)
+            .inc_by(count);
+    }
+
+    //
+    // Liveness checks
+    //
+    pub fn report_liveness_ping(&self, outcome: LivenessPingLabel, count: u64) {
+        let labels: [&str; 2] = [&self.protocol, outcome.into()];
+        self.overlay_metrics
+            .liveness_ping_total
+            .with_label_values(&labels)
+            .inc_by(count);
+    }
+
+    pub fn report_peer_state_transition(&self, state: PeerStateLabel) {
+        let labels: [&str; 2] = [&self.protocol, state.into()];
+        self.overlay_metrics
+            .peer_state_transition_total
+            .with_label_values(&labels)
+            .inc();
+    }
+
     pub fn get_utp_summary(&self) -> String {
         let inbound_success =
             self.utp_outcome_total(UtpDirectionLabel::Inbound, UtpOutcomeLabel::Success);
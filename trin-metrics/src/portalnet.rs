@@ -1,7 +1,10 @@
+use ethportal_api::types::network::Subnetwork;
 use lazy_static::lazy_static;
-use prometheus_exporter::prometheus::default_registry;
+use prometheus_exporter::prometheus::{default_registry, Encoder, TextEncoder};
 
-use crate::{bridge::BridgeMetrics, overlay::OverlayMetrics, storage::StorageMetrics};
+use crate::{
+    bridge::BridgeMetrics, overlay::OverlayMetrics, rpc::RpcMetrics, storage::StorageMetrics,
+};
 
 // We use lazy_static to ensure that the metrics registry is initialized only once, for each
 // runtime. This is important because the registry is a global singleton, and if it is
@@ -18,6 +21,7 @@ fn initialize_metrics_registry() -> PortalnetMetrics {
 pub struct PortalnetMetrics {
     bridge: BridgeMetrics,
     overlay: OverlayMetrics,
+    rpc: RpcMetrics,
     storage: StorageMetrics,
 }
 
@@ -27,10 +31,12 @@ impl PortalnetMetrics {
         let overlay = OverlayMetrics::new(registry)?;
         let storage = StorageMetrics::new(registry)?;
         let bridge = BridgeMetrics::new(registry)?;
+        let rpc = RpcMetrics::new(registry)?;
         Ok(Self {
             overlay,
             storage,
             bridge,
+            rpc,
         })
     }
 
@@ -45,4 +51,64 @@ impl PortalnetMetrics {
     pub fn bridge(&self) -> BridgeMetrics {
         self.bridge.clone()
     }
+
+    pub fn rpc(&self) -> RpcMetrics {
+        self.rpc.clone()
+    }
+}
+
+/// Returns the metrics reported for the subnetwork, i.e. with its `protocol` label, in the
+/// Prometheus text exposition format.
+pub fn subnetwork_metrics_text(subnetwork: Subnetwork) -> anyhow::Result<String> {
+    lazy_static::initialize(&PORTALNET_METRICS);
+    let protocol = subnetwork.to_string();
+    let metric_families: Vec<_> = default_registry()
+        .gather()
+        .into_iter()
+        .filter_map(|mut metric_family| {
+            let metrics: Vec<_> = metric_family
+                .take_metric()
+                .into_vec()
+                .into_iter()
+                .filter(|metric| {
+                    metric.get_label().iter().any(|label| {
+                        label.get_name() == "protocol" && label.get_value() == protocol
+                    })
+                })
+                .collect();
+            if metrics.is_empty() {
+                return None;
+            }
+            metric_family.set_metric(metrics.into());
+            Some(metric_family)
+        })
+        .collect();
+
+    let mut buffer = vec![];
+    TextEncoder::new().encode(&metric_families, &mut buffer)?;
+    Ok(String::from_utf8(buffer)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subnetwork_metrics_text_only_includes_subnetwork() {
+        let storage = PORTALNET_METRICS.storage();
+        storage
+            .entry_count
+            .with_label_values(&[&Subnetwork::Beacon.to_string()])
+            .set(3);
+        storage
+            .entry_count
+            .with_label_values(&[&Subnetwork::History.to_string()])
+            .set(5);
+
+        let text = subnetwork_metrics_text(Subnetwork::Beacon).unwrap();
+        assert!(text.contains("# HELP trin_entry_count "));
+        assert!(text.contains("# TYPE trin_entry_count gauge"));
+        assert!(text.contains("trin_entry_count{protocol=\"Beacon\"} 3"));
+        assert!(!text.contains("History"));
+    }
 }
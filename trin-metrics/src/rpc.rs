@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use prometheus_exporter::{
+    self,
+    prometheus::{histogram_opts, register_histogram_vec_with_registry, HistogramVec, Registry},
+};
+
+use crate::portalnet::PORTALNET_METRICS;
+
+/// The method label of the calls of methods that the server doesn't have, so that arbitrary
+/// method names don't add labels.
+pub const UNKNOWN_METHOD: &str = "unknown";
+
+/// Contains metrics reporters for the JSON-RPC server.
+#[derive(Clone, Debug)]
+pub struct RpcMetrics {
+    pub call_duration: HistogramVec,
+}
+
+impl RpcMetrics {
+    pub fn new(registry: &Registry) -> anyhow::Result<Self> {
+        let call_duration = register_histogram_vec_with_registry!(
+            histogram_opts!(
+                "trin_rpc_call_duration_seconds",
+                "the duration of JSON-RPC calls, by method"
+            ),
+            &["method", "transport", "outcome"],
+            registry
+        )?;
+        Ok(Self { call_duration })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RpcMetricsReporter {
+    pub rpc_metrics: RpcMetrics,
+}
+
+impl Default for RpcMetricsReporter {
+    fn default() -> Self {
+        Self {
+            rpc_metrics: PORTALNET_METRICS.rpc(),
+        }
+    }
+}
+
+impl RpcMetricsReporter {
+    /// Records the duration of a call of the method over the transport. The method is `None` if
+    /// the server doesn't have it, and is reported as [UNKNOWN_METHOD].
+    pub fn report_call(
+        &self,
+        method: Option<&str>,
+        transport: &str,
+        success: bool,
+        duration: Duration,
+    ) {
+        let outcome = if success { "success" } else { "error" };
+        self.rpc_metrics
+            .call_duration
+            .with_label_values(&[method.unwrap_or(UNKNOWN_METHOD), transport, outcome])
+            .observe(duration.as_secs_f64());
+    }
+}
@@ -1,23 +1,29 @@
 use std::{fmt::Debug, sync::Arc};
 
+use alloy::primitives::{Bytes, B256};
 use discv5::{enr::NodeId, Enr};
 use ethportal_api::{
     jsonrpsee::core::Serialize,
     types::{
-        distance::Distance,
         jsonrpc::{endpoints::StateEndpoint, request::StateJsonRpcRequest},
-        portal::{AcceptInfo, FindNodesInfo, GetContentInfo, PongInfo, TraceContentInfo},
+        portal::{
+            AcceptInfo, ContentProvenance, FindNodesInfo, GetContentInfo, GetEnrInfo,
+            ImportContentInfo, LocalContentInfo, LocalContentMeta, OfferManyInfo, TraceContentInfo,
+        },
         portal_wire::Content,
         query_trace::QueryTrace,
     },
-    utils::bytes::hex_encode,
+    utils::{bytes::hex_encode, content_signature::verify_content_signature},
     ContentValue, OverlayContentKey, RawContentValue, StateContentKey, StateContentValue,
 };
-use portalnet::overlay::{config::FindContentConfig, errors::OverlayRequestError};
+use portalnet::overlay::{
+    config::FindContentConfig,
+    errors::{OverlayRequestError, OverlayRequestJsonError},
+};
 use serde_json::{json, Value};
 use tokio::sync::mpsc;
-use tracing::error;
-use trin_storage::ContentStore;
+use tracing::{debug, error, info_span, Instrument};
+use trin_storage::{ContentLabels, ContentStore, ShouldWeStoreContent};
 
 use crate::network::StateNetwork;
 
@@ -31,7 +37,8 @@ impl StateRequestHandler {
     pub async fn handle_client_queries(mut self) {
         while let Some(request) = self.state_rx.recv().await {
             let network = Arc::clone(&self.network);
-            tokio::spawn(async move { Self::handle_request(network, request).await });
+            let span = info_span!("rpc_request", request_id = %request.request_id);
+            tokio::spawn(Self::handle_request(network, request).instrument(span));
         }
     }
 
@@ -39,29 +46,51 @@ impl StateRequestHandler {
         let response: Result<Value, String> = match request.endpoint {
             StateEndpoint::RoutingTableInfo => routing_table_info(network),
             StateEndpoint::Ping(enr) => ping(network, enr).await,
-            StateEndpoint::AddEnr(enr) => add_enr(network, enr),
+            StateEndpoint::AddEnr(enr, allow_unreachable) => {
+                add_enr(network, enr, allow_unreachable)
+            }
             StateEndpoint::DeleteEnr(node_id) => delete_enr(network, node_id),
             StateEndpoint::GetEnr(node_id) => get_enr(network, node_id),
+            StateEndpoint::GetEnrInfo(node_id) => get_enr_info(network, node_id),
             StateEndpoint::LookupEnr(node_id) => lookup_enr(network, node_id).await,
-            StateEndpoint::FindNodes(enr, distances) => find_nodes(network, enr, distances).await,
+            StateEndpoint::FindNodes(enr, distances, max_results) => {
+                find_nodes(network, enr, distances, max_results).await
+            }
             StateEndpoint::RecursiveFindNodes(node_id) => {
                 recursive_find_nodes(network, node_id).await
             }
             StateEndpoint::DataRadius => radius(network),
-            StateEndpoint::LocalContent(content_key) => local_content(network, content_key).await,
+            StateEndpoint::LocalContent(content_key, include_meta, include_rank) => {
+                local_content(network, content_key, include_meta, include_rank).await
+            }
+            StateEndpoint::ContentProviders(content_key) => {
+                content_providers(network, content_key).await
+            }
+            StateEndpoint::SimulateIncomingOffer(sample_size) => {
+                simulate_incoming_offer(network, sample_size)
+            }
             StateEndpoint::FindContent(enr, content_key) => {
                 find_content(network, enr, content_key).await
             }
+            StateEndpoint::FetchAndStore(enr, content_key, ignore_radius) => {
+                fetch_and_store(network, enr, content_key, ignore_radius).await
+            }
             StateEndpoint::GetContent(content_key) => {
                 get_content(network, content_key, /* is_trace= */ false).await
             }
             StateEndpoint::TraceGetContent(content_key) => {
                 get_content(network, content_key, /* is_trace= */ true).await
             }
-            StateEndpoint::Store(content_key, content_value) => {
-                store(network, content_key, content_value).await
+            StateEndpoint::Store(content_key, content_value, source, signature) => {
+                store(network, content_key, content_value, source, signature).await
+            }
+            StateEndpoint::DeleteContentBySource(source) => {
+                delete_content_by_source(network, source)
             }
             StateEndpoint::Offer(enr, content_items) => offer(network, enr, content_items).await,
+            StateEndpoint::OfferMany(enr, content_items) => {
+                offer_many(network, enr, content_items).await
+            }
             StateEndpoint::TraceOffer(enr, content_key, content_value) => {
                 trace_offer(network, enr, content_key, content_value).await
             }
@@ -83,11 +112,22 @@ impl StateRequestHandler {
                 )
                 .await
             }
-            StateEndpoint::PaginateLocalContentKeys(offset, limit) => {
-                paginate(network, offset, limit)
+            StateEndpoint::PaginateLocalContentKeys(offset, limit, provenance, source) => {
+                paginate(network, offset, limit, provenance, source)
             }
+            StateEndpoint::PaginateLocalContentKeysAfter(after, limit, provenance, source) => {
+                paginate_after(network, after, limit, provenance, source)
+            }
+            StateEndpoint::ExportContentBatch(after, limit) => {
+                export_content_batch(network, after, limit)
+            }
+            StateEndpoint::ImportContent(content_items) => import_content(network, content_items),
+            StateEndpoint::ClearLookupCache => Ok(json!(network.overlay.clear_lookup_cache())),
         };
 
+        if let Err(err) = &response {
+            debug!(error = %err, "RPC request failed");
+        }
         let _ = request.resp.send(response);
     }
 }
@@ -97,17 +137,17 @@ fn routing_table_info(network: Arc<StateNetwork>) -> Result<Value, String> {
 }
 
 async fn ping(network: Arc<StateNetwork>, enr: Enr) -> Result<Value, String> {
-    to_json_result(
-        "Ping",
-        network.overlay.send_ping(enr).await.map(|pong| PongInfo {
-            enr_seq: pong.enr_seq,
-            data_radius: *Distance::from(pong.custom_payload),
-        }),
-    )
+    to_overlay_request_json_result("Ping", network.overlay.send_ping_info(enr).await)
 }
 
-fn add_enr(network: Arc<StateNetwork>, enr: Enr) -> Result<Value, String> {
-    to_json_result("AddEnr", network.overlay.add_enr(enr).map(|_| true))
+fn add_enr(network: Arc<StateNetwork>, enr: Enr, allow_unreachable: bool) -> Result<Value, String> {
+    to_json_result(
+        "AddEnr",
+        network
+            .overlay
+            .add_enr(enr, allow_unreachable)
+            .map(|_| true),
+    )
 }
 
 fn delete_enr(network: Arc<StateNetwork>, node_id: NodeId) -> Result<Value, String> {
@@ -119,21 +159,37 @@ fn get_enr(network: Arc<StateNetwork>, node_id: NodeId) -> Result<Value, String>
     to_json_result("GetEnr", network.overlay.get_enr(node_id))
 }
 
+fn get_enr_info(network: Arc<StateNetwork>, node_id: NodeId) -> Result<Value, String> {
+    to_json_result(
+        "GetEnrInfo",
+        network
+            .overlay
+            .get_enr_with_source(node_id)
+            .map(|(enr, source)| GetEnrInfo {
+                enr,
+                source,
+                multiaddrs: None,
+            }),
+    )
+}
+
 async fn lookup_enr(network: Arc<StateNetwork>, node_id: NodeId) -> Result<Value, String> {
     to_json_result("LookupEnr", network.overlay.lookup_enr(node_id).await)
 }
 
 fn radius(network: Arc<StateNetwork>) -> Result<Value, String> {
     let radius = network.overlay.data_radius();
-    Ok(json!(*radius))
+    // Serialized as a 32-byte hex string, like the data radius of a pong.
+    Ok(json!(B256::from(*radius)))
 }
 
 async fn find_nodes(
     network: Arc<StateNetwork>,
     enr: Enr,
     distances: Vec<u16>,
+    max_results: Option<u64>,
 ) -> Result<Value, String> {
-    to_json_result(
+    to_overlay_request_json_result(
         "FindNodes",
         network
             .overlay
@@ -143,6 +199,7 @@ async fn find_nodes(
                 nodes
                     .enrs
                     .into_iter()
+                    .take(max_results.map_or(usize::MAX, |max_results| max_results as usize))
                     .map(Enr::from)
                     .collect::<FindNodesInfo>()
             }),
@@ -172,8 +229,31 @@ fn local_storage_lookup(
 async fn local_content(
     network: Arc<StateNetwork>,
     content_key: StateContentKey,
+    include_meta: bool,
+    include_rank: bool,
 ) -> Result<Value, String> {
     match local_storage_lookup(&network, &content_key) {
+        Ok(Some(content)) if include_meta || include_rank => {
+            let store = network.overlay.store.read();
+            let meta = store.meta(&content_key).map_err(|err| {
+                format!(
+                    "LocalContent failed: error while looking for metadata in local storage: {err:?}",
+                )
+            })?;
+            let distance_rank = match include_rank {
+                true => store.distance_rank(&content_key).map_err(|err| {
+                    format!(
+                        "LocalContent failed: error while looking for distance rank in local storage: {err:?}",
+                    )
+                })?,
+                false => None,
+            };
+            Ok(json!(LocalContentInfo::WithMeta {
+                content,
+                meta: meta.unwrap_or_else(LocalContentMeta::unknown),
+                distance_rank,
+            }))
+        }
         Ok(Some(content)) => Ok(Value::String(hex_encode(content))),
         Ok(None) => {
             let err = json!({
@@ -187,6 +267,34 @@ async fn local_content(
     }
 }
 
+async fn content_providers(
+    network: Arc<StateNetwork>,
+    content_key: StateContentKey,
+) -> Result<Value, String> {
+    let meta = network.overlay.store.read().meta(&content_key);
+    let meta = meta.map_err(|err| {
+        format!(
+            "ContentProviders failed: error while looking for metadata in local storage: {err:?}",
+        )
+    })?;
+    match meta {
+        Some(meta) => Ok(json!(meta.providers())),
+        None => {
+            let err = json!({
+                "message": "Content not found in local storage",
+            });
+            Err(err.to_string())
+        }
+    }
+}
+
+fn simulate_incoming_offer(network: Arc<StateNetwork>, sample_size: u64) -> Result<Value, String> {
+    // The lock of the storage is only held to take what the offers are simulated against.
+    let offer_simulation = network.overlay.store.read().offer_simulation();
+    let result = offer_simulation.run(sample_size);
+    to_json_result("SimulateIncomingOffer", result)
+}
+
 async fn find_content(
     network: Arc<StateNetwork>,
     enr: Enr,
@@ -208,7 +316,20 @@ async fn find_content(
             "enrs": enrs,
         })),
     });
-    to_json_result("FindContent", result)
+    to_overlay_request_json_result("FindContent", result)
+}
+
+async fn fetch_and_store(
+    network: Arc<StateNetwork>,
+    enr: Enr,
+    content_key: StateContentKey,
+    ignore_radius: bool,
+) -> Result<Value, String> {
+    let result = network
+        .overlay
+        .fetch_and_store(enr, content_key, ignore_radius)
+        .await;
+    to_overlay_request_json_result("FetchAndStore", result)
 }
 
 async fn get_content(
@@ -256,10 +377,13 @@ async fn get_content(
                     message,
                     utp,
                     trace,
+                    cached_at_ms,
+                    ..
                 } => {
                     let err = json!({
                         "message": format!("{message}: utp: {utp}"),
-                        "trace": trace
+                        "trace": trace,
+                        "cachedAtMs": cached_at_ms,
                     });
                     err.to_string()
                 }
@@ -292,16 +416,37 @@ async fn store(
     network: Arc<StateNetwork>,
     content_key: StateContentKey,
     content_value: StateContentValue,
+    source: Option<String>,
+    signature: Option<Bytes>,
 ) -> Result<Value, String> {
-    to_json_result(
-        "Store",
-        network
-            .overlay
-            .store
-            .write()
-            .put(content_key, content_value.encode())
-            .map(|_| true),
-    )
+    content_value
+        .ensure_valid_for_key(&content_key)
+        .map_err(|err| err.to_json_string())?;
+    let data = content_value.encode();
+    // Content with a signature that doesn't verify isn't stored at all.
+    let trusted = match &signature {
+        Some(signature) => {
+            verify_content_signature(
+                &network.trusted_content_signers,
+                &content_key.to_bytes(),
+                &data,
+                signature,
+            )?;
+            true
+        }
+        None => false,
+    };
+    let result = network.overlay.store.write().put_with_labels(
+        content_key.clone(),
+        data,
+        ContentProvenance::RpcStore,
+        /* offered_by= */ None,
+        &ContentLabels { source, trusted },
+    );
+    if result.is_ok() {
+        network.overlay.invalidate_failed_lookup(&content_key);
+    }
+    to_json_result("Store", result.map(|_| true))
 }
 
 async fn offer(
@@ -309,30 +454,66 @@ async fn offer(
     enr: Enr,
     content_items: Vec<(StateContentKey, StateContentValue)>,
 ) -> Result<Value, String> {
+    for (content_key, content_value) in &content_items {
+        content_value
+            .ensure_valid_for_key(content_key)
+            .map_err(|err| err.to_json_string())?;
+    }
     let content_items = content_items
         .into_iter()
         .map(|(key, value)| (key.to_bytes(), value.encode()))
         .collect();
 
-    to_json_result(
+    to_overlay_request_json_result(
         "Offer",
         network
             .overlay
-            .send_offer(enr, content_items)
+            .send_offer_with_accept_bytes(enr, content_items)
             .await
-            .map(|accept| AcceptInfo {
-                content_keys: accept.content_keys,
+            .map(|(accept, accept_bytes)| {
+                AcceptInfo::new(accept.content_keys).with_accept_bitfield(accept_bytes)
             }),
     )
 }
 
+async fn offer_many(
+    network: Arc<StateNetwork>,
+    enr: Enr,
+    content_items: Vec<(StateContentKey, StateContentValue)>,
+) -> Result<Value, String> {
+    for (content_key, content_value) in &content_items {
+        content_value
+            .ensure_valid_for_key(content_key)
+            .map_err(|err| err.to_json_string())?;
+    }
+    let (content_keys, content_items): (Vec<_>, Vec<_>) = content_items
+        .into_iter()
+        .map(|(key, value)| {
+            let content_item = (key.to_bytes(), value.encode());
+            (key, content_item)
+        })
+        .unzip();
+
+    to_overlay_request_json_result(
+        "OfferMany",
+        network
+            .overlay
+            .send_offer(enr, content_items)
+            .await
+            .map(|accept| OfferManyInfo::new(content_keys, &accept.content_keys)),
+    )
+}
+
 async fn trace_offer(
     network: Arc<StateNetwork>,
     enr: Enr,
     content_key: StateContentKey,
     content_value: StateContentValue,
 ) -> Result<Value, String> {
-    to_json_result(
+    content_value
+        .ensure_valid_for_key(&content_key)
+        .map_err(|err| err.to_json_string())?;
+    to_overlay_request_json_result(
         "TraceOffer",
         network
             .overlay
@@ -347,28 +528,144 @@ async fn gossip(
     content_value: StateContentValue,
     is_trace: bool,
 ) -> Result<Value, String> {
+    content_value
+        .ensure_valid_for_key(&content_key)
+        .map_err(|err| err.to_json_string())?;
+    let data = content_value.encode();
+    store_gossiped_content(&network, &content_key, &data);
     if is_trace {
         Ok(json!(
             network
                 .overlay
-                .propagate_gossip_trace(content_key, content_value.encode())
+                .propagate_gossip_trace(content_key, data)
                 .await
         ))
     } else {
         Ok(network
             .overlay
-            .propagate_gossip(vec![(content_key, content_value.encode())])
+            .propagate_gossip(vec![(content_key, data)])
+            .offered
             .into())
     }
 }
 
-fn paginate(network: Arc<StateNetwork>, offset: u64, limit: u64) -> Result<Value, String> {
+/// Stores gossiped content locally if it's within the radius and not already stored, the same
+/// way as content accepted from an offer. Failing to store it doesn't prevent the gossip.
+fn store_gossiped_content(
+    network: &StateNetwork,
+    content_key: &StateContentKey,
+    data: &RawContentValue,
+) {
+    let should_store = network
+        .overlay
+        .store
+        .read()
+        .is_key_within_radius_and_unavailable(content_key);
+    match should_store {
+        Ok(ShouldWeStoreContent::Store) => {
+            if let Err(err) = network.overlay.store.write().put_with_meta(
+                content_key.clone(),
+                data,
+                ContentProvenance::Gossip,
+                /* offered_by= */ None,
+            ) {
+                error!(content.key = %content_key, "Unable to store gossiped content: {err}");
+                return;
+            }
+            network.overlay.invalidate_failed_lookup(content_key);
+        }
+        Ok(_) => {}
+        Err(err) => error!("Unable to read store: {err}"),
+    }
+}
+
+fn paginate(
+    network: Arc<StateNetwork>,
+    offset: u64,
+    limit: u64,
+    provenance: Option<ContentProvenance>,
+    source: Option<String>,
+) -> Result<Value, String> {
     to_json_result(
         "PaginateLocalContentKeys",
-        network.overlay.store.read().paginate(offset, limit),
+        network
+            .overlay
+            .store
+            .read()
+            .paginate(offset, limit, provenance, source.as_deref()),
+    )
+}
+
+fn paginate_after(
+    network: Arc<StateNetwork>,
+    after: Option<B256>,
+    limit: u64,
+    provenance: Option<ContentProvenance>,
+    source: Option<String>,
+) -> Result<Value, String> {
+    to_json_result(
+        "PaginateLocalContentKeysAfter",
+        network
+            .overlay
+            .store
+            .read()
+            .paginate_after(after, limit, provenance, source.as_deref()),
+    )
+}
+
+fn delete_content_by_source(network: Arc<StateNetwork>, source: String) -> Result<Value, String> {
+    to_json_result(
+        "DeleteContentBySource",
+        network
+            .overlay
+            .store
+            .write()
+            .delete_by_source(&source)
+            .map(|deleted_keys| deleted_keys.len()),
     )
 }
 
+fn export_content_batch(
+    network: Arc<StateNetwork>,
+    after: Option<B256>,
+    limit: u64,
+) -> Result<Value, String> {
+    // The read lock is held only for a single batch, so the export doesn't block writes.
+    to_json_result(
+        "ExportContentBatch",
+        network.overlay.store.read().export_batch(after, limit),
+    )
+}
+
+fn import_content(
+    network: Arc<StateNetwork>,
+    content_items: Vec<(StateContentKey, StateContentValue)>,
+) -> Result<Value, String> {
+    let mut info = ImportContentInfo {
+        stored: 0,
+        failed: vec![],
+    };
+    for (content_key, content_value) in content_items {
+        // The write lock is re-acquired for every item, so that other requests can progress.
+        let result = network
+            .overlay
+            .store
+            .write()
+            .import(content_key.clone(), content_value);
+        match result {
+            Ok(()) => {
+                network.overlay.invalidate_failed_lookup(&content_key);
+                info.stored += 1;
+            }
+            Err(err) => {
+                debug!(content.key = %content_key, "Failed to import content: {err}");
+                info.failed.push(content_key);
+            }
+        }
+    }
+    Ok(json!(info))
+}
+
 fn to_json_result(
     request: &str,
     result: Result<impl Serialize, impl Debug>,
@@ -377,3 +674,13 @@ fn to_json_result(
         .map(|value| json!(value))
         .map_err(|err| format!("{request} failed: {err:?}"))
 }
+
+/// Same as [to_json_result], but the error includes the kind of the failed overlay request.
+fn to_overlay_request_json_result(
+    request: &str,
+    result: Result<impl Serialize, OverlayRequestError>,
+) -> Result<Value, String> {
+    result
+        .map(|value| json!(value))
+        .map_err(|err| json!(OverlayRequestJsonError::new(request, &err)).to_string())
+}
@@ -33,7 +33,7 @@ mod storage;
 pub mod validation;
 
 type StateHandler = Option<StateRequestHandler>;
-type StateNetworkTask = Option<JoinHandle<()>>;
+type StateNetworkTasks = Vec<JoinHandle<()>>;
 type StateEventTx = Option<mpsc::UnboundedSender<OverlayRequest>>;
 type StateJsonRpcTx = Option<mpsc::UnboundedSender<StateJsonRpcRequest>>;
 type StateEventStream = Option<broadcast::Receiver<EventEnvelope>>;
@@ -46,7 +46,7 @@ pub async fn initialize_state_network(
     header_oracle: Arc<RwLock<HeaderOracle>>,
 ) -> anyhow::Result<(
     StateHandler,
-    StateNetworkTask,
+    StateNetworkTasks,
     StateEventTx,
     StateJsonRpcTx,
     StateEventStream,
@@ -73,11 +73,11 @@ pub async fn initialize_state_network(
         spawn_state_network(Arc::clone(&state_network), portalnet_config, state_event_rx);
     let state_event_stream = state_network.overlay.event_stream().await?;
 
-    spawn_state_heartbeat(state_network);
+    let state_heartbeat_task = spawn_state_heartbeat(state_network);
 
     Ok((
         Some(state_handler),
-        Some(state_network_task),
+        vec![state_network_task, state_heartbeat_task],
         Some(state_event_tx),
         Some(state_jsonrpc_tx),
         Some(state_event_stream),
@@ -100,19 +100,15 @@ pub fn spawn_state_network(
             message_rx: state_message_rx,
         };
 
-        // Spawn state event handler
-        tokio::spawn(state_events.start());
-
-        // hacky test: make sure we establish a session with the boot node
-        network.overlay.ping_bootnodes().await;
-
-        tokio::signal::ctrl_c()
-            .await
-            .expect("failed to pause until ctrl-c");
+        // Run the state event handler in this task, so that it's stopped along with it.
+        tokio::join!(state_events.start(), async {
+            // hacky test: make sure we establish a session with the boot node
+            network.overlay.ping_bootnodes().await;
+        });
     })
 }
 
-pub fn spawn_state_heartbeat(network: Arc<StateNetwork>) {
+pub fn spawn_state_heartbeat(network: Arc<StateNetwork>) -> JoinHandle<()> {
     tokio::spawn(async move {
         let mut heart_interval = interval(Duration::from_millis(30000));
 
@@ -132,5 +128,5 @@ pub fn spawn_state_heartbeat(network: Arc<StateNetwork>) {
             info!("reports~ data: {storage_log}; msgs: {message_log}; cpu={cpu_percent:.1}%");
             info!("reports~ utp: {utp_log}");
         }
-    });
+    })
 }
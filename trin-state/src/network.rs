@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use ethportal_api::{
     types::{distance::XorMetric, network::Subnetwork},
+    utils::content_signature::PublicKey,
     StateContentKey,
 };
 use parking_lot::RwLock as PLRwLock;
@@ -23,6 +24,8 @@ use crate::{storage::StateStorage, validation::StateValidator};
 #[derive(Clone)]
 pub struct StateNetwork {
     pub overlay: Arc<OverlayProtocol<StateContentKey, XorMetric, StateValidator, StateStorage>>,
+    /// The public keys whose signatures mark content stored over JSON-RPC as trusted.
+    pub trusted_content_signers: Vec<PublicKey>,
 }
 
 /// Poke is disabled for state network because Offer/Accept and Find/Found Content are different,
@@ -43,11 +46,16 @@ impl StateNetwork {
         if !portal_config.disable_poke {
             debug!("Poke is not supported by the State Network")
         }
+        let trusted_content_signers = portal_config.trusted_content_signers;
         let config = OverlayConfig {
             bootnode_enrs: portal_config.bootnodes,
             disable_poke: DISABLE_POKE,
             gossip_dropped: GOSSIP_DROPPED,
             utp_transfer_limit: portal_config.utp_transfer_limit,
+            failed_lookup_cache: portal_config.failed_lookup_cache,
+            lookup_cache: portal_config.lookup_cache,
+            rebroadcast: portal_config.rebroadcast,
+            gossip: portal_config.state_gossip,
             ..Default::default()
         };
         let storage = Arc::new(PLRwLock::new(StateStorage::new(storage_config)?));
@@ -64,6 +72,7 @@ impl StateNetwork {
 
         Ok(Self {
             overlay: Arc::new(overlay),
+            trusted_content_signers,
         })
     }
 }
@@ -1,18 +1,25 @@
-use alloy::primitives::keccak256;
+use alloy::primitives::{keccak256, B256};
+use discv5::enr::NodeId;
 use ethportal_api::{
     types::{
         content_key::state::{AccountTrieNodeKey, ContractBytecodeKey, ContractStorageTrieNodeKey},
         content_value::state::{ContractBytecode, TrieNode},
         distance::Distance,
         network::Subnetwork,
-        portal::PaginateLocalContentInfo,
+        portal::{
+            ContentDistanceRank, ContentProvenance, ContentRecord, LocalContentMeta,
+            PaginateLocalContentInfo,
+        },
     },
     ContentValue, OverlayContentKey, RawContentValue, StateContentKey, StateContentValue,
 };
 use trin_storage::{
     error::ContentStoreError,
-    versioned::{create_store, ContentType, IdIndexedV1Store, IdIndexedV1StoreConfig},
-    ContentId, ContentStore, PortalStorageConfig, ShouldWeStoreContent,
+    versioned::{
+        create_store, ContentType, IdIndexedV1Store, IdIndexedV1StoreConfig, OfferSimulation,
+    },
+    ContentId, ContentLabels, ContentStore, DataRadiusHistory, PortalStorageConfig,
+    ShouldWeStoreContent,
 };
 
 /// Storage layer for the state network. Encapsulates state network specific data and logic.
@@ -33,39 +40,59 @@ impl ContentStore for StateStorage {
         key: StateContentKey,
         value: V,
     ) -> Result<Vec<(StateContentKey, RawContentValue)>, ContentStoreError> {
-        let value = StateContentValue::decode(&key, value.as_ref())?;
+        self.put_with_meta(
+            key,
+            value,
+            ContentProvenance::Unknown,
+            /* offered_by= */ None,
+        )
+    }
 
-        match &key {
-            StateContentKey::AccountTrieNode(account_trie_node_key) => self
-                .put_account_trie_node(&key, account_trie_node_key, value)
-                // ignore any pruned content in state network
-                .and(Ok(vec![])),
-            StateContentKey::ContractStorageTrieNode(contract_storage_trie_key) => self
-                .put_contract_storage_trie_node(&key, contract_storage_trie_key, value)
-                .and(Ok(vec![])),
-            StateContentKey::ContractBytecode(contract_bytecode_key) => self
-                .put_contract_bytecode(&key, contract_bytecode_key, value)
-                .and(Ok(vec![])),
-        }
+    fn put_with_meta<V: AsRef<[u8]>>(
+        &mut self,
+        key: StateContentKey,
+        value: V,
+        provenance: ContentProvenance,
+        offered_by: Option<NodeId>,
+    ) -> Result<Vec<(StateContentKey, RawContentValue)>, ContentStoreError> {
+        self.put_with_labels(
+            key,
+            value,
+            provenance,
+            offered_by,
+            &ContentLabels::default(),
+        )
     }
 
     fn is_key_within_radius_and_unavailable(
         &self,
         key: &StateContentKey,
     ) -> Result<ShouldWeStoreContent, ContentStoreError> {
-        let content_id = ContentId::from(key.content_id());
-        if self.store.distance_to_content_id(&content_id) > self.store.radius() {
-            Ok(ShouldWeStoreContent::NotWithinRadius)
-        } else if self.store.has_content(&content_id)? {
-            Ok(ShouldWeStoreContent::AlreadyStored)
-        } else {
-            Ok(ShouldWeStoreContent::Store)
-        }
+        self.store.should_store(&ContentId::from(key.content_id()))
     }
 
     fn radius(&self) -> Distance {
         IdIndexedV1Store::radius(&self.store)
     }
+
+    fn recompute_radius(&mut self) -> Result<Distance, ContentStoreError> {
+        self.store.recompute_radius()?;
+        Ok(IdIndexedV1Store::radius(&self.store))
+    }
+
+    fn radius_history(&self) -> Option<&DataRadiusHistory> {
+        Some(self.store.radius_history())
+    }
+
+    fn entries_within_radius(
+        &self,
+        node_id: &NodeId,
+        radius: Distance,
+        limit: usize,
+    ) -> Result<Vec<(StateContentKey, RawContentValue)>, ContentStoreError> {
+        self.store
+            .entries_within_radius(node_id, radius, limit as u64)
+    }
 }
 
 impl StateStorage {
@@ -78,19 +105,191 @@ impl StateStorage {
     }
 
     /// Returns a paginated list of all locally available content keys, according to the provided
-    /// offset and limit.
+    /// offset and limit, optionally only the content with the provenance and the source.
     pub fn paginate(
         &self,
         offset: u64,
         limit: u64,
+        provenance: Option<ContentProvenance>,
+        source: Option<&str>,
     ) -> Result<PaginateLocalContentInfo<StateContentKey>, ContentStoreError> {
-        let paginate_result = self.store.paginate(offset, limit)?;
+        let paginate_result = self.store.paginate(offset, limit, provenance, source)?;
         Ok(PaginateLocalContentInfo {
             content_keys: paginate_result.content_keys,
             total_entries: paginate_result.entry_count,
+            next_cursor: None,
         })
     }
 
+    /// Returns up to `limit` locally available content keys whose content id is greater than
+    /// `after`, ordered by content id, optionally only the content with the provenance and the
+    /// source. See [IdIndexedV1Store::paginate_after] for details.
+    ///
+    /// The content id of the last key is returned as the cursor of the next page, unless the page
+    /// isn't full.
+    pub fn paginate_after(
+        &self,
+        after: Option<B256>,
+        limit: u64,
+        provenance: Option<ContentProvenance>,
+        source: Option<&str>,
+    ) -> Result<PaginateLocalContentInfo<StateContentKey>, ContentStoreError> {
+        let after = after.map(ContentId::from);
+        let paginate_result =
+            self.store
+                .paginate_after(after.as_ref(), limit, provenance, source)?;
+        let next_cursor = match paginate_result.content_keys.last() {
+            Some(content_key) if paginate_result.content_keys.len() as u64 == limit => {
+                Some(B256::from(content_key.content_id()))
+            }
+            _ => None,
+        };
+        Ok(PaginateLocalContentInfo {
+            content_keys: paginate_result.content_keys,
+            total_entries: paginate_result.entry_count,
+            next_cursor,
+        })
+    }
+
+    /// Returns up to `limit` locally stored content entries whose content id is greater than
+    /// `after`, ordered by content id. See [IdIndexedV1Store::export_batch] for details.
+    pub fn export_batch(
+        &self,
+        after: Option<B256>,
+        limit: u64,
+    ) -> Result<Vec<ContentRecord<StateContentKey>>, ContentStoreError> {
+        let after = after.map(ContentId::from);
+        Ok(self
+            .store
+            .export_batch(after.as_ref(), limit)?
+            .into_iter()
+            .map(|(content_key, content_value)| ContentRecord {
+                content_key,
+                content_value,
+            })
+            .collect())
+    }
+
+    /// Stores content that was exported from another node.
+    ///
+    /// Exported content is in the form that is returned on lookup (without proofs), which `put`
+    /// doesn't accept. Such content is only validated against the hash from the content key, and
+    /// stored as is. Content with proofs is stored as if it was passed to `put`.
+    pub fn import(
+        &mut self,
+        key: StateContentKey,
+        value: StateContentValue,
+    ) -> Result<(), ContentStoreError> {
+        let (value_hash, expected_hash) = match (&key, &value) {
+            (StateContentKey::AccountTrieNode(key), StateContentValue::TrieNode(trie_node)) => {
+                (keccak256(&trie_node.node[..]), key.node_hash)
+            }
+            (
+                StateContentKey::ContractStorageTrieNode(key),
+                StateContentValue::TrieNode(trie_node),
+            ) => (keccak256(&trie_node.node[..]), key.node_hash),
+            (
+                StateContentKey::ContractBytecode(key),
+                StateContentValue::ContractBytecode(value),
+            ) => (keccak256(&value.code[..]), key.code_hash),
+            _ => {
+                return self
+                    .put_with_meta(key, value.encode(), ContentProvenance::Import, None)
+                    .and(Ok(()))
+            }
+        };
+
+        if value_hash != expected_hash {
+            return Err(ContentStoreError::InvalidData {
+                message: format!(
+                    "Hash of the content ({value_hash}) doesn't match the hash from the content key ({expected_hash})"
+                ),
+            });
+        }
+        self.store
+            .insert_with_meta(&key, value.encode(), ContentProvenance::Import, None)
+            .and(Ok(()))
+    }
+
+    /// Returns how the content got into the store, the peer that offered it and when it was
+    /// stored, if it is stored.
+    pub fn meta(
+        &self,
+        key: &StateContentKey,
+    ) -> Result<Option<LocalContentMeta>, ContentStoreError> {
+        self.store.lookup_meta(&key.content_id().into())
+    }
+
+    /// Same as [ContentStore::put_with_meta], but also labels the content, even if it's already
+    /// stored. The content and its labels are written in a single transaction.
+    pub fn put_with_labels<V: AsRef<[u8]>>(
+        &mut self,
+        key: StateContentKey,
+        value: V,
+        provenance: ContentProvenance,
+        offered_by: Option<NodeId>,
+        labels: &ContentLabels,
+    ) -> Result<Vec<(StateContentKey, RawContentValue)>, ContentStoreError> {
+        let value = StateContentValue::decode(&key, value.as_ref())?;
+
+        match &key {
+            StateContentKey::AccountTrieNode(account_trie_node_key) => self
+                .put_account_trie_node(
+                    &key,
+                    account_trie_node_key,
+                    value,
+                    provenance,
+                    offered_by,
+                    labels,
+                )
+                // ignore any pruned content in state network
+                .and(Ok(vec![])),
+            StateContentKey::ContractStorageTrieNode(contract_storage_trie_key) => self
+                .put_contract_storage_trie_node(
+                    &key,
+                    contract_storage_trie_key,
+                    value,
+                    provenance,
+                    offered_by,
+                    labels,
+                )
+                .and(Ok(vec![])),
+            StateContentKey::ContractBytecode(contract_bytecode_key) => self
+                .put_contract_bytecode(
+                    &key,
+                    contract_bytecode_key,
+                    value,
+                    provenance,
+                    offered_by,
+                    labels,
+                )
+                .and(Ok(vec![])),
+        }
+    }
+
+    /// Deletes all the content labeled with the source, and returns the content keys of the
+    /// deleted content.
+    pub fn delete_by_source(
+        &mut self,
+        source: &str,
+    ) -> Result<Vec<StateContentKey>, ContentStoreError> {
+        self.store.delete_by_source(source)
+    }
+
+    /// Returns the rank of the content among all stored content by distance to the local node.
+    pub fn distance_rank(
+        &self,
+        key: &StateContentKey,
+    ) -> Result<Option<ContentDistanceRank>, ContentStoreError> {
+        self.store.lookup_distance_rank(&key.content_id().into())
+    }
+
+    /// Returns what offers of synthetic content ids, spread evenly across the keyspace, are
+    /// simulated against, so that the simulation doesn't hold the lock of the storage.
+    pub fn offer_simulation(&self) -> OfferSimulation {
+        self.store.offer_simulation()
+    }
+
     /// Get a summary of the current state of storage
     pub fn get_summary_info(&self) -> String {
         self.store.get_summary_info()
@@ -101,6 +300,9 @@ impl StateStorage {
         content_key: &StateContentKey,
         key: &AccountTrieNodeKey,
         value: StateContentValue,
+        provenance: ContentProvenance,
+        offered_by: Option<NodeId>,
+        labels: &ContentLabels,
     ) -> Result<Vec<(StateContentKey, RawContentValue)>, ContentStoreError> {
         let StateContentValue::AccountTrieNodeWithProof(value) = value else {
             return Err(ContentStoreError::InvalidData {
@@ -128,8 +330,13 @@ impl StateStorage {
         let trie_node = TrieNode {
             node: last_trie_node.clone(),
         };
-        self.store
-            .insert(content_key, StateContentValue::TrieNode(trie_node).encode())
+        self.store.insert_with_labels(
+            content_key,
+            StateContentValue::TrieNode(trie_node).encode(),
+            provenance,
+            offered_by,
+            labels,
+        )
     }
 
     fn put_contract_storage_trie_node(
@@ -137,6 +344,9 @@ impl StateStorage {
         content_key: &StateContentKey,
         key: &ContractStorageTrieNodeKey,
         value: StateContentValue,
+        provenance: ContentProvenance,
+        offered_by: Option<NodeId>,
+        labels: &ContentLabels,
     ) -> Result<Vec<(StateContentKey, RawContentValue)>, ContentStoreError> {
         let StateContentValue::ContractStorageTrieNodeWithProof(value) = value else {
             return Err(ContentStoreError::InvalidData {
@@ -164,8 +374,13 @@ impl StateStorage {
         let trie_node = TrieNode {
             node: last_trie_node.clone(),
         };
-        self.store
-            .insert(content_key, StateContentValue::TrieNode(trie_node).encode())
+        self.store.insert_with_labels(
+            content_key,
+            StateContentValue::TrieNode(trie_node).encode(),
+            provenance,
+            offered_by,
+            labels,
+        )
     }
 
     fn put_contract_bytecode(
@@ -173,6 +388,9 @@ impl StateStorage {
         content_key: &StateContentKey,
         key: &ContractBytecodeKey,
         value: StateContentValue,
+        provenance: ContentProvenance,
+        offered_by: Option<NodeId>,
+        labels: &ContentLabels,
     ) -> Result<Vec<(StateContentKey, RawContentValue)>, ContentStoreError> {
         let StateContentValue::ContractBytecodeWithProof(value) = value else {
             return Err(ContentStoreError::InvalidData {
@@ -194,9 +412,12 @@ impl StateStorage {
 
         let contract_code = ContractBytecode { code: value.code };
 
-        self.store.insert(
+        self.store.insert_with_labels(
             content_key,
             StateContentValue::ContractBytecode(contract_code).encode(),
+            provenance,
+            offered_by,
+            labels,
         )
     }
 }
@@ -266,6 +487,40 @@ pub mod test {
         Ok(())
     }
 
+    #[rstest]
+    #[case::account_trie_node("account_trie_node.yaml")]
+    #[case::contract_storage_trie_node("contract_storage_trie_node.yaml")]
+    #[case::contract_bytecode("contract_bytecode.yaml")]
+    fn export_import(#[case] file_name: &str) -> Result<()> {
+        let (_temp_dir, config) =
+            create_test_portal_storage_config_with_capacity(STORAGE_CAPACITY_MB)?;
+        let mut storage = StateStorage::new(config)?;
+        let (_other_temp_dir, other_config) =
+            create_test_portal_storage_config_with_capacity(STORAGE_CAPACITY_MB)?;
+        let mut other_storage = StateStorage::new(other_config)?;
+
+        let test_cases = read_test_cases(file_name)?;
+        for test_case in &test_cases {
+            storage.put(test_case.key.clone(), &test_case.store_value)?;
+        }
+
+        let records = storage.export_batch(/* after= */ None, /* limit= */ 100)?;
+        assert_eq!(records.len(), test_cases.len());
+        for record in records {
+            let value = StateContentValue::decode(&record.content_key, &record.content_value)?;
+            other_storage.import(record.content_key, value)?;
+        }
+
+        for test_case in test_cases {
+            assert_eq!(
+                other_storage.get(&test_case.key)?,
+                Some(test_case.lookup_value)
+            );
+        }
+
+        Ok(())
+    }
+
     fn read_test_cases(filename: &str) -> Result<Vec<ContentData>> {
         let file = read_portal_spec_tests_file(
             PathBuf::from("tests/mainnet/state/validation").join(filename),
@@ -1,7 +1,13 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use discv5::enr::NodeId;
-use ethportal_api::types::{cli::StorageCapacityConfig, network::Subnetwork};
+use ethportal_api::types::{
+    cli::{
+        StorageCapacityConfig, StorageEvictionPolicy, DEFAULT_STORAGE_BEACON_PINNED_MB,
+        DEFAULT_STORAGE_RECENT_WRITES,
+    },
+    network::Subnetwork,
+};
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 
@@ -15,6 +21,12 @@ pub struct PortalStorageConfigFactory {
     node_data_dir: PathBuf,
     capacity_config: StorageCapacityConfig,
     sql_connection_pool: Pool<SqliteConnectionManager>,
+    /// The connection pool of the archive database and its capacity, in megabytes.
+    archive: Option<(Pool<SqliteConnectionManager>, u32)>,
+    /// The budget of the pinned beacon content, in megabytes.
+    beacon_pinned_mb: u32,
+    /// The number of recently stored content items that are kept in memory.
+    recent_writes_capacity: usize,
 }
 
 impl PortalStorageConfigFactory {
@@ -34,9 +46,45 @@ impl PortalStorageConfigFactory {
             node_id,
             capacity_config,
             sql_connection_pool,
+            archive: None,
+            beacon_pinned_mb: DEFAULT_STORAGE_BEACON_PINNED_MB,
+            recent_writes_capacity: DEFAULT_STORAGE_RECENT_WRITES,
         })
     }
 
+    /// Adds a secondary tier to the storage of the history and state subnetworks, which is backed
+    /// by the database in the archive dir. Its capacity is split between the enabled subnetworks
+    /// like the capacity of the primary storage.
+    pub fn with_archive(
+        mut self,
+        archive_dir: &Path,
+        archive_mb: u32,
+    ) -> Result<Self, ContentStoreError> {
+        if archive_dir == self.node_data_dir {
+            return Err(ContentStoreError::Database(format!(
+                "Can't use the node data dir {} as the archive dir",
+                archive_dir.display()
+            )));
+        }
+        std::fs::create_dir_all(archive_dir)?;
+        self.archive = Some((setup_sql(archive_dir)?, archive_mb));
+        Ok(self)
+    }
+
+    /// Sets the budget of the pinned beacon content, which is separate from the storage capacity
+    /// of the beacon subnetwork.
+    pub fn with_beacon_pinned_capacity(mut self, beacon_pinned_mb: u32) -> Self {
+        self.beacon_pinned_mb = beacon_pinned_mb;
+        self
+    }
+
+    /// Sets the number of most recently stored history and state content items, which are served
+    /// from memory.
+    pub fn with_recent_writes_capacity(mut self, recent_writes_capacity: usize) -> Self {
+        self.recent_writes_capacity = recent_writes_capacity;
+        self
+    }
+
     pub fn create(
         &self,
         subnetwork: &Subnetwork,
@@ -84,15 +132,63 @@ impl PortalStorageConfigFactory {
             }
         };
 
+        let archive = self
+            .archive
+            .as_ref()
+            .and_then(|(sql_connection_pool, archive_mb)| {
+                let capacity_weight = Self::get_capacity_weight(subnetwork);
+                let total_capacity_weight = self
+                    .enabled_subnetworks()
+                    .iter()
+                    .map(Self::get_capacity_weight)
+                    .sum::<u64>();
+                if capacity_weight == 0 || total_capacity_weight == 0 {
+                    return None;
+                }
+                Some(ArchiveStorageConfig {
+                    storage_capacity_bytes: BYTES_IN_MB_U64
+                        * (*archive_mb as u64)
+                        * capacity_weight
+                        / total_capacity_weight,
+                    sql_connection_pool: sql_connection_pool.clone(),
+                })
+            });
+
         Ok(PortalStorageConfig {
             storage_capacity_bytes: capacity_bytes,
             node_id: self.node_id,
             node_data_dir: self.node_data_dir.clone(),
             distance_fn: DistanceFunction::Xor,
             sql_connection_pool: self.sql_connection_pool.clone(),
+            content_dedup: false,
+            verify_on_read: false,
+            eviction_policy: StorageEvictionPolicy::Distance,
+            archive,
+            recent_writes_capacity: self.recent_writes_capacity,
+            pinned_capacity_bytes: self.beacon_pinned_mb as u64 * BYTES_IN_MB_U64,
         })
     }
 
+    /// Returns the subnetworks that the capacity is configured for.
+    fn enabled_subnetworks(&self) -> Vec<Subnetwork> {
+        match &self.capacity_config {
+            StorageCapacityConfig::Combined { subnetworks, .. } => subnetworks.clone(),
+            StorageCapacityConfig::Specific {
+                beacon_mb,
+                history_mb,
+                state_mb,
+            } => [
+                (Subnetwork::Beacon, beacon_mb),
+                (Subnetwork::History, history_mb),
+                (Subnetwork::State, state_mb),
+            ]
+            .into_iter()
+            .filter(|(_, capacity_mb)| capacity_mb.is_some())
+            .map(|(subnetwork, _)| subnetwork)
+            .collect(),
+        }
+    }
+
     fn get_capacity_weight(subnetwork: &Subnetwork) -> u64 {
         match subnetwork {
             Subnetwork::History => Self::HISTORY_CAPACITY_WEIGHT,
@@ -110,6 +206,31 @@ pub struct PortalStorageConfig {
     pub node_data_dir: PathBuf,
     pub distance_fn: DistanceFunction,
     pub sql_connection_pool: Pool<SqliteConnectionManager>,
+    /// Whether identical content values are stored once and shared between content keys.
+    /// Currently only used by the beacon subnetwork.
+    pub content_dedup: bool,
+    /// Whether the content is checked against the content key it is stored under when it is read,
+    /// to detect corruption.
+    pub verify_on_read: bool,
+    /// The policy for choosing which content to evict once the storage is full. Currently only
+    /// used by the history and state subnetworks.
+    pub eviction_policy: StorageEvictionPolicy,
+    /// The secondary tier of the storage, which keeps the content that is evicted from the
+    /// primary one. Currently only used by the history and state subnetworks.
+    pub archive: Option<ArchiveStorageConfig>,
+    /// The number of most recently stored content items that are kept in memory, to serve them
+    /// without reading the database. Currently only used by the history and state subnetworks.
+    pub recent_writes_capacity: usize,
+    /// The budget of the pinned content, which is kept regardless of the storage capacity.
+    /// Currently only used by the beacon subnetwork.
+    pub pinned_capacity_bytes: u64,
+}
+
+/// The config of the secondary tier of the storage, see [PortalStorageConfigFactory::with_archive].
+#[derive(Clone, Debug)]
+pub struct ArchiveStorageConfig {
+    pub storage_capacity_bytes: u64,
+    pub sql_connection_pool: Pool<SqliteConnectionManager>,
 }
 
 #[cfg(test)]
@@ -262,4 +383,49 @@ mod tests {
         assert!(factory.create(&Subnetwork::State).is_err());
         temp_dir.close().unwrap();
     }
+
+    #[test]
+    fn archive_capacity_is_split() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_dir = TempDir::new().unwrap();
+        let factory = PortalStorageConfigFactory::new(
+            StorageCapacityConfig::Combined {
+                total_mb: 100,
+                subnetworks: vec![Subnetwork::Beacon, Subnetwork::History, Subnetwork::State],
+            },
+            NodeId::random(),
+            temp_dir.path().to_path_buf(),
+        )
+        .unwrap()
+        .with_archive(archive_dir.path(), 1000)
+        .unwrap();
+        assert!(factory
+            .create(&Subnetwork::Beacon)
+            .unwrap()
+            .archive
+            .is_none());
+        for subnetwork in [Subnetwork::History, Subnetwork::State] {
+            let archive = factory.create(&subnetwork).unwrap().archive.unwrap();
+            assert_eq!(archive.storage_capacity_bytes, 500_000_000);
+        }
+        temp_dir.close().unwrap();
+        archive_dir.close().unwrap();
+    }
+
+    #[test]
+    fn archive_dir_is_not_node_data_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let result = PortalStorageConfigFactory::new(
+            StorageCapacityConfig::Combined {
+                total_mb: 100,
+                subnetworks: vec![Subnetwork::History],
+            },
+            NodeId::random(),
+            temp_dir.path().to_path_buf(),
+        )
+        .unwrap()
+        .with_archive(temp_dir.path(), 1000);
+        assert!(result.is_err(), "Archive in node data dir should fail");
+        temp_dir.close().unwrap();
+    }
 }
@@ -27,6 +27,11 @@ pub enum ContentStoreError {
     #[error("data invalid {message}")]
     InvalidData { message: String },
 
+    /// The stored data doesn't match the content id it is stored under, e.g. because of disk
+    /// corruption.
+    #[error("stored content with id {content_id} is corrupted: {message}")]
+    Corrupted { content_id: String, message: String },
+
     #[error("rusqlite error {0}")]
     Rusqlite(#[from] rusqlite::Error),
 
@@ -53,4 +58,13 @@ pub enum ContentStoreError {
         old_version: StoreVersion,
         new_version: StoreVersion,
     },
+
+    /// The database was written by a newer trin, with a schema that this one doesn't understand.
+    #[error(
+        "Database schema version {version} is newer than the supported version {supported_version}"
+    )]
+    UnsupportedSchemaVersion {
+        version: u32,
+        supported_version: u32,
+    },
 }
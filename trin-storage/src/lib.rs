@@ -1,5 +1,7 @@
 pub mod config;
 pub mod error;
+pub mod radius_history;
+pub mod schema;
 pub mod sql;
 pub mod test_utils;
 pub mod utils;
@@ -8,16 +10,18 @@ pub mod versioned;
 use std::{ops::Deref, str::FromStr};
 
 use alloy::primitives::{Bytes, B256};
-pub use config::{PortalStorageConfig, PortalStorageConfigFactory};
+pub use config::{ArchiveStorageConfig, PortalStorageConfig, PortalStorageConfigFactory};
 use discv5::enr::NodeId;
 use error::ContentStoreError;
 use ethportal_api::{
     types::{
         content_key::overlay::{IdentityContentKey, OverlayContentKey},
         distance::{Distance, Metric, XorMetric},
+        portal::ContentProvenance,
     },
     RawContentValue,
 };
+pub use radius_history::{DataRadiusHistory, RadiusChange};
 use rusqlite::types::{FromSql, FromSqlError, ValueRef};
 
 pub const DATABASE_NAME: &str = "trin.sqlite";
@@ -46,6 +50,16 @@ pub enum ShouldWeStoreContent {
     AlreadyStored,
 }
 
+/// The labels that content stored over JSON-RPC is stored with, in addition to its provenance.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContentLabels {
+    /// The label of the source that the content is stored from, replacing the source of the
+    /// content if it's already stored.
+    pub source: Option<String>,
+    /// Whether the content is stored with a signature of a trusted signer.
+    pub trusted: bool,
+}
+
 /// A data store for Portal Network content (data).
 pub trait ContentStore {
     type Key;
@@ -64,6 +78,21 @@ pub trait ContentStore {
         value: V,
     ) -> Result<Vec<(Self::Key, RawContentValue)>, ContentStoreError>;
 
+    /// Puts a piece of content into the store, recording how it got there and the peer that
+    /// offered it, if any. See [Self::put].
+    ///
+    /// Stores that don't record the metadata of content store it as `put` does.
+    #[allow(clippy::type_complexity)]
+    fn put_with_meta<V: AsRef<[u8]>>(
+        &mut self,
+        key: Self::Key,
+        value: V,
+        _provenance: ContentProvenance,
+        _offered_by: Option<NodeId>,
+    ) -> Result<Vec<(Self::Key, RawContentValue)>, ContentStoreError> {
+        self.put(key, value)
+    }
+
     /// Returns whether the content denoted by `key` is within the radius of the data store and not
     /// already stored within the data store.
     fn is_key_within_radius_and_unavailable(
@@ -73,6 +102,35 @@ pub trait ContentStore {
 
     /// Returns the radius of the data store.
     fn radius(&self) -> Distance;
+
+    /// Recomputes the radius of the data store from its current usage, and returns it. A change
+    /// of the radius is recorded in the radius history.
+    ///
+    /// Stores whose radius doesn't depend on usage keep their radius.
+    fn recompute_radius(&mut self) -> Result<Distance, ContentStoreError> {
+        Ok(self.radius())
+    }
+
+    /// Returns the recent changes of the radius of the data store.
+    ///
+    /// Stores whose radius doesn't change have no history.
+    fn radius_history(&self) -> Option<&DataRadiusHistory> {
+        None
+    }
+
+    /// Returns up to `limit` stored entries whose content id is within `radius` of `node_id`, i.e.
+    /// the stored content that a peer with that radius is interested in.
+    ///
+    /// Stores that can't look up content by its distance to other nodes return no entries.
+    #[allow(clippy::type_complexity)]
+    fn entries_within_radius(
+        &self,
+        _node_id: &NodeId,
+        _radius: Distance,
+        _limit: usize,
+    ) -> Result<Vec<(Self::Key, RawContentValue)>, ContentStoreError> {
+        Ok(vec![])
+    }
 }
 
 /// An in-memory `ContentStore`.
@@ -85,6 +143,8 @@ pub struct MemoryContentStore {
     distance_fn: DistanceFunction,
     /// The radius of the store.
     radius: Distance,
+    /// The changes of the radius of the store.
+    radius_history: DataRadiusHistory,
 }
 
 impl MemoryContentStore {
@@ -95,11 +155,15 @@ impl MemoryContentStore {
             node_id,
             distance_fn,
             radius: Distance::MAX,
+            radius_history: DataRadiusHistory::default(),
         }
     }
 
     /// Sets the radius of the store to `radius`.
     pub fn set_radius(&mut self, radius: Distance) {
+        if radius != self.radius {
+            self.radius_history.record(self.radius, radius);
+        }
         self.radius = radius;
     }
 
@@ -154,6 +218,30 @@ impl ContentStore for MemoryContentStore {
     fn radius(&self) -> Distance {
         self.radius
     }
+
+    fn radius_history(&self) -> Option<&DataRadiusHistory> {
+        Some(&self.radius_history)
+    }
+
+    fn entries_within_radius(
+        &self,
+        node_id: &NodeId,
+        radius: Distance,
+        limit: usize,
+    ) -> Result<Vec<(Self::Key, RawContentValue)>, ContentStoreError> {
+        let mut entries: Vec<_> = self
+            .store
+            .iter()
+            .filter_map(|(content_id, value)| {
+                let content_id: [u8; 32] = content_id.as_slice().try_into().ok()?;
+                (self.distance_fn.distance(node_id, &content_id) <= radius)
+                    .then(|| (IdentityContentKey::new(content_id), value.clone()))
+            })
+            .collect();
+        entries.sort_by_key(|(key, _)| key.content_id());
+        entries.truncate(limit);
+        Ok(entries)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
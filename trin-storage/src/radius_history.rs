@@ -0,0 +1,100 @@
+use std::{
+    collections::VecDeque,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ethportal_api::types::distance::Distance;
+
+/// The number of radius changes that are kept in the history.
+const DATA_RADIUS_HISTORY_CAPACITY: usize = 64;
+
+/// A change of the radius of a data store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RadiusChange {
+    /// The number of the change, counting from 1 since the store was opened.
+    pub seq: u64,
+    /// When the radius changed, in seconds since the unix epoch.
+    pub changed_at: u64,
+    pub old_radius: Distance,
+    pub new_radius: Distance,
+}
+
+/// Keeps the most recent changes of the radius of a data store, so that they can be reported
+/// after the store recomputed its radius, e.g. while content was being written.
+#[derive(Debug, Default)]
+pub struct DataRadiusHistory {
+    /// The changes, the most recent last.
+    changes: VecDeque<RadiusChange>,
+    /// The number of the most recent change, or 0 if the radius never changed.
+    last_seq: u64,
+}
+
+impl DataRadiusHistory {
+    /// Records that the radius changed, which drops the oldest change once the history is full.
+    pub fn record(&mut self, old_radius: Distance, new_radius: Distance) {
+        if self.changes.len() == DATA_RADIUS_HISTORY_CAPACITY {
+            self.changes.pop_front();
+        }
+        self.last_seq += 1;
+        self.changes.push_back(RadiusChange {
+            seq: self.last_seq,
+            changed_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs()),
+            old_radius,
+            new_radius,
+        });
+    }
+
+    /// Returns the number of the most recent change, or 0 if the radius never changed.
+    pub fn last_seq(&self) -> u64 {
+        self.last_seq
+    }
+
+    /// Returns the changes that are still kept, the most recent last.
+    pub fn changes(&self) -> impl Iterator<Item = &RadiusChange> {
+        self.changes.iter()
+    }
+
+    /// Returns the changes that were recorded after the change numbered `seq`, the most recent
+    /// last. The changes that were already dropped from the history are skipped.
+    pub fn changes_after(&self, seq: u64) -> impl Iterator<Item = &RadiusChange> {
+        self.changes.iter().filter(move |change| change.seq > seq)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use alloy::primitives::U256;
+
+    use super::*;
+
+    #[test]
+    fn changes_after() {
+        let mut history = DataRadiusHistory::default();
+        assert_eq!(history.last_seq(), 0);
+        assert_eq!(history.changes().count(), 0);
+
+        history.record(Distance::MAX, Distance::from(U256::from(100)));
+        history.record(Distance::from(U256::from(100)), Distance::MAX);
+        assert_eq!(history.last_seq(), 2);
+        let changes: Vec<_> = history.changes_after(1).collect();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].seq, 2);
+        assert_eq!(changes[0].old_radius, Distance::from(U256::from(100)));
+        assert_eq!(changes[0].new_radius, Distance::MAX);
+        assert_eq!(history.changes_after(2).count(), 0);
+    }
+
+    #[test]
+    fn oldest_change_is_dropped() {
+        let mut history = DataRadiusHistory::default();
+        for radius in 0..=DATA_RADIUS_HISTORY_CAPACITY as u64 {
+            history.record(Distance::MAX, Distance::from(U256::from(radius)));
+        }
+        assert_eq!(history.changes().count(), DATA_RADIUS_HISTORY_CAPACITY);
+        assert_eq!(history.changes().next().unwrap().seq, 2);
+        assert_eq!(history.last_seq(), DATA_RADIUS_HISTORY_CAPACITY as u64 + 1);
+    }
+}
@@ -0,0 +1,361 @@
+//! The schema version of the database and its forward migrations.
+//!
+//! The migrations are applied in order when the database is opened, each in its own transaction,
+//! together with recording its version in the schema version table. Databases with a newer schema
+//! version than [SCHEMA_VERSION] were written by a newer trin and are refused, as this binary
+//! would fail on their schema at runtime.
+//!
+//! Databases that predate the schema version table have version 0. As the first migration only
+//! creates the tables that don't exist yet, it also applies to them.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::{named_params, Connection, Transaction};
+use tracing::info;
+
+use crate::{
+    error::ContentStoreError,
+    sql::{
+        BEACON_CONTENT_BLOB_CREATE_TABLE, BEACON_PINNED_CONTENT_CREATE_TABLE,
+        BEACON_TRUSTED_CONTENT_CREATE_TABLE, DROP_USAGE_STATS_DB,
+        HISTORICAL_SUMMARIES_CREATE_TABLE, LC_BOOTSTRAP_CREATE_TABLE, LC_UPDATE_CREATE_TABLE,
+        SCHEMA_VERSION_CREATE_TABLE, SCHEMA_VERSION_INSERT, SCHEMA_VERSION_LOOKUP,
+    },
+    versioned::{
+        id_indexed_v1::sql::{self as id_indexed_v1_sql, ADDED_COLUMNS},
+        sql::{STORE_INFO_CREATE_TABLE, TABLE_EXISTS},
+        ContentType,
+    },
+};
+
+/// The schema version that this binary understands, i.e. the version of the last migration.
+pub const SCHEMA_VERSION: u32 = 6;
+
+/// A forward migration of the schema, from the previous version to `version`.
+struct Migration {
+    version: u32,
+    description: &'static str,
+    apply: fn(&Transaction) -> Result<(), ContentStoreError>,
+}
+
+/// The migrations, ordered by version.
+const MIGRATIONS: [Migration; 6] = [
+    Migration {
+        version: 1,
+        description: "Create the beacon and store info tables",
+        apply: create_tables,
+    },
+    Migration {
+        version: 2,
+        description: "Add the content metadata columns to the id-indexed tables",
+        apply: add_content_meta_columns,
+    },
+    Migration {
+        version: 3,
+        description: "Add the trusted column to the id-indexed tables",
+        apply: add_trusted_column,
+    },
+    Migration {
+        version: 4,
+        description: "Create the beacon trusted content table",
+        apply: create_beacon_trusted_content_table,
+    },
+    Migration {
+        version: 5,
+        description: "Create the beacon pinned content table",
+        apply: create_beacon_pinned_content_table,
+    },
+    Migration {
+        version: 6,
+        description: "Add the value hash column to the id-indexed tables",
+        apply: add_value_hash_column,
+    },
+];
+
+/// Applies the migrations that the database is missing, and returns its schema version.
+///
+/// Fails if the database has a newer schema version than [SCHEMA_VERSION].
+pub fn migrate(conn: &mut Connection) -> Result<u32, ContentStoreError> {
+    conn.execute_batch(SCHEMA_VERSION_CREATE_TABLE)?;
+    let version = schema_version(conn)?;
+    if version > SCHEMA_VERSION {
+        return Err(ContentStoreError::UnsupportedSchemaVersion {
+            version,
+            supported_version: SCHEMA_VERSION,
+        });
+    }
+
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > version)
+    {
+        apply_migration(conn, migration)?;
+    }
+    Ok(SCHEMA_VERSION)
+}
+
+/// Applies the migration and records its version, in a single transaction.
+fn apply_migration(conn: &mut Connection, migration: &Migration) -> Result<(), ContentStoreError> {
+    let tx = conn.transaction()?;
+    (migration.apply)(&tx)?;
+    tx.execute(
+        SCHEMA_VERSION_INSERT,
+        named_params! {
+            ":version": migration.version,
+            ":description": migration.description,
+            ":applied_at": SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        },
+    )?;
+    tx.commit()?;
+    info!(
+        version = migration.version,
+        description = migration.description,
+        "Applied database migration"
+    );
+    Ok(())
+}
+
+/// Returns the schema version of the database, or 0 if no migration was applied.
+fn schema_version(conn: &Connection) -> Result<u32, ContentStoreError> {
+    let version: Option<u32> = conn.query_row(SCHEMA_VERSION_LOOKUP, [], |row| row.get(0))?;
+    Ok(version.unwrap_or_default())
+}
+
+/// Migration 1. The id-indexed tables aren't created here, as they are created by their store,
+/// possibly from the legacy history table.
+fn create_tables(tx: &Transaction) -> Result<(), ContentStoreError> {
+    tx.execute_batch(LC_BOOTSTRAP_CREATE_TABLE)?;
+    tx.execute_batch(LC_UPDATE_CREATE_TABLE)?;
+    tx.execute_batch(HISTORICAL_SUMMARIES_CREATE_TABLE)?;
+    tx.execute_batch(BEACON_CONTENT_BLOB_CREATE_TABLE)?;
+    tx.execute_batch(STORE_INFO_CREATE_TABLE)?;
+    tx.execute_batch(DROP_USAGE_STATS_DB)?;
+    Ok(())
+}
+
+/// Migration 2.
+fn add_content_meta_columns(tx: &Transaction) -> Result<(), ContentStoreError> {
+    add_columns(tx, 2)
+}
+
+/// Migration 3.
+fn add_trusted_column(tx: &Transaction) -> Result<(), ContentStoreError> {
+    add_columns(tx, 3)
+}
+
+/// Migration 6.
+fn add_value_hash_column(tx: &Transaction) -> Result<(), ContentStoreError> {
+    add_columns(tx, 6)
+}
+
+/// Adds the columns that the migration to `schema_version` introduced to the existing id-indexed
+/// tables. The id-indexed tables that are created afterwards already have them.
+///
+/// Databases that predate the schema version table may already have some of the content metadata
+/// columns, as they used to be added when the store was created, so those are skipped.
+fn add_columns(tx: &Transaction, schema_version: u32) -> Result<(), ContentStoreError> {
+    for content_type in [ContentType::History, ContentType::State] {
+        let table_name = id_indexed_v1_sql::table_name(&content_type);
+        if !tx
+            .prepare(TABLE_EXISTS)?
+            .exists(named_params! { ":table_name": table_name })?
+        {
+            continue;
+        }
+        for column in ADDED_COLUMNS
+            .iter()
+            .filter(|column| column.schema_version == schema_version)
+        {
+            let has_column = tx.query_row(
+                &id_indexed_v1_sql::has_column(&content_type, column.name),
+                [],
+                |row| row.get::<_, u64>(0),
+            )? > 0;
+            if !has_column {
+                tx.execute_batch(&id_indexed_v1_sql::add_column(
+                    &content_type,
+                    column.name,
+                    column.definition,
+                ))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Migration 4.
+fn create_beacon_trusted_content_table(tx: &Transaction) -> Result<(), ContentStoreError> {
+    tx.execute_batch(BEACON_TRUSTED_CONTENT_CREATE_TABLE)?;
+    Ok(())
+}
+
+/// Migration 5.
+fn create_beacon_pinned_content_table(tx: &Transaction) -> Result<(), ContentStoreError> {
+    tx.execute_batch(BEACON_PINNED_CONTENT_CREATE_TABLE)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::DATABASE_NAME;
+
+    fn open_database(temp_dir: &TempDir) -> Result<Connection> {
+        Ok(Connection::open(temp_dir.path().join(DATABASE_NAME))?)
+    }
+
+    fn table_exists(conn: &Connection, table_name: &str) -> Result<bool> {
+        Ok(conn
+            .prepare(TABLE_EXISTS)?
+            .exists(named_params! { ":table_name": table_name })?)
+    }
+
+    fn columns(conn: &Connection, table_name: &str) -> Result<Vec<String>> {
+        let mut query = conn.prepare(&format!(
+            "SELECT name FROM pragma_table_info('{table_name}')"
+        ))?;
+        let columns = query
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        Ok(columns)
+    }
+
+    #[test]
+    fn migrations_are_ordered() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, index as u32 + 1);
+        }
+        assert_eq!(MIGRATIONS.last().unwrap().version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn fresh_database() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut conn = open_database(&temp_dir)?;
+
+        assert_eq!(migrate(&mut conn)?, SCHEMA_VERSION);
+        assert_eq!(schema_version(&conn)?, SCHEMA_VERSION);
+        for table_name in [
+            "lc_bootstrap",
+            "lc_update",
+            "historical_summaries",
+            "store_info",
+            "beacon_trusted_content",
+            "beacon_pinned_content",
+        ] {
+            assert!(table_exists(&conn, table_name)?, "{table_name} is missing");
+        }
+
+        // Opening the database again applies no migration.
+        assert_eq!(migrate(&mut conn)?, SCHEMA_VERSION);
+        let applied: u32 =
+            conn.query_row("SELECT COUNT(*) FROM schema_version", [], |row| row.get(0))?;
+        assert_eq!(applied, SCHEMA_VERSION);
+        Ok(())
+    }
+
+    #[test]
+    fn sequential_upgrade() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut conn = open_database(&temp_dir)?;
+
+        // A database that predates the schema version table, with a state table created before
+        // the content metadata was recorded.
+        let table_name = id_indexed_v1_sql::table_name(&ContentType::State);
+        conn.execute_batch(&format!(
+            "CREATE TABLE {table_name} (
+                content_id BLOB PRIMARY KEY,
+                content_key BLOB NOT NULL,
+                content_value BLOB NOT NULL,
+                distance_short INTEGER NOT NULL,
+                content_size INTEGER NOT NULL
+            );
+            INSERT INTO {table_name} VALUES (x'01', x'02', x'03', 4, 5);"
+        ))?;
+
+        // Apply only the baseline migration, as an older binary would.
+        conn.execute_batch(SCHEMA_VERSION_CREATE_TABLE)?;
+        apply_migration(&mut conn, &MIGRATIONS[0])?;
+        assert_eq!(schema_version(&conn)?, 1);
+        let baseline_columns = columns(&conn, &table_name)?;
+        assert_eq!(
+            baseline_columns,
+            [
+                "content_id",
+                "content_key",
+                "content_value",
+                "distance_short",
+                "content_size"
+            ]
+        );
+
+        // Each migration adds only its own columns.
+        for migration in &MIGRATIONS[1..] {
+            apply_migration(&mut conn, migration)?;
+            assert_eq!(schema_version(&conn)?, migration.version);
+            let expected_columns = baseline_columns
+                .iter()
+                .map(String::as_str)
+                .chain(
+                    ADDED_COLUMNS
+                        .iter()
+                        .filter(|column| column.schema_version <= migration.version)
+                        .map(|column| column.name),
+                )
+                .collect::<Vec<_>>();
+            assert_eq!(
+                columns(&conn, &table_name)?,
+                expected_columns,
+                "Unexpected columns after migration {}",
+                migration.version
+            );
+        }
+        assert_eq!(migrate(&mut conn)?, SCHEMA_VERSION);
+
+        let provenance: String =
+            conn.query_row(&format!("SELECT provenance FROM {table_name}"), [], |row| {
+                row.get(0)
+            })?;
+        assert_eq!(provenance, "unknown");
+        let trusted: bool =
+            conn.query_row(&format!("SELECT trusted FROM {table_name}"), [], |row| {
+                row.get(0)
+            })?;
+        assert!(!trusted);
+        Ok(())
+    }
+
+    #[test]
+    fn too_new_database_is_refused() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut conn = open_database(&temp_dir)?;
+        migrate(&mut conn)?;
+        conn.execute(
+            SCHEMA_VERSION_INSERT,
+            named_params! {
+                ":version": SCHEMA_VERSION + 1,
+                ":description": "From the future",
+                ":applied_at": 0,
+            },
+        )?;
+
+        let err = migrate(&mut conn).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ContentStoreError::UnsupportedSchemaVersion {
+                    version,
+                    supported_version: SCHEMA_VERSION,
+                } if version == SCHEMA_VERSION + 1
+            ),
+            "Unexpected error: {err}"
+        );
+        Ok(())
+    }
+}
@@ -19,13 +19,30 @@ pub const INSERT_BOOTSTRAP_QUERY: &str =
 pub const LC_BOOTSTRAP_ROOT_LOOKUP_QUERY: &str =
     "SELECT block_root FROM lc_bootstrap WHERE block_root = (?1) LIMIT 1";
 
-pub const LC_BOOTSTRAP_LOOKUP_QUERY: &str =
-    "SELECT value FROM lc_bootstrap WHERE block_root = (?1) LIMIT 1";
+/// Query to get the bootstrap value by block root, resolving it through the content blob table if
+/// it was stored with content dedup.
+pub const LC_BOOTSTRAP_LOOKUP_QUERY: &str = "SELECT COALESCE(blob.value, lc_bootstrap.value)
+    FROM lc_bootstrap
+    LEFT JOIN beacon_content_blob AS blob ON blob.content_hash = lc_bootstrap.value
+    WHERE block_root = (?1) LIMIT 1";
 
 /// Query to get the block root of the latest bootstrap record.
 pub const LC_BOOTSTRAP_LATEST_BLOCK_ROOT_QUERY: &str =
     "SELECT block_root FROM lc_bootstrap ORDER BY slot DESC LIMIT 1";
 
+/// Query to get the block roots of all the bootstrap records.
+pub const LC_BOOTSTRAP_BLOCK_ROOTS_QUERY: &str = "SELECT block_root FROM lc_bootstrap";
+
+/// Query to get the block roots and sizes of the bootstrap records, oldest first.
+pub const LC_BOOTSTRAP_OLDEST_FIRST_QUERY: &str =
+    "SELECT block_root, content_size FROM lc_bootstrap ORDER BY slot ASC";
+
+/// Query to get the size of the bootstrap, which is zero if it isn't stored.
+pub const LC_BOOTSTRAP_SIZE_LOOKUP_QUERY: &str =
+    "SELECT TOTAL(content_size) FROM lc_bootstrap WHERE block_root = (?1)";
+
+pub const DELETE_LC_BOOTSTRAP_QUERY: &str = "DELETE FROM lc_bootstrap WHERE block_root = (?1)";
+
 /// Total beacon data size is the combination of lc_bootstrap, lc_update and historical_summaries
 /// tables
 pub const TOTAL_DATA_SIZE_QUERY_BEACON: &str = "SELECT
@@ -46,11 +63,29 @@ pub const INSERT_LC_UPDATE_QUERY: &str =
     "INSERT OR IGNORE INTO lc_update (period, value, score, update_size)
                       VALUES (?1, ?2, ?3, ?4)";
 
-pub const LC_UPDATE_LOOKUP_QUERY: &str = "SELECT value FROM lc_update WHERE period = (?1) LIMIT 1";
+/// Query to get the update value by period, resolving it through the content blob table if it was
+/// stored with content dedup.
+pub const LC_UPDATE_LOOKUP_QUERY: &str = "SELECT COALESCE(blob.value, lc_update.value)
+    FROM lc_update
+    LEFT JOIN beacon_content_blob AS blob ON blob.content_hash = lc_update.value
+    WHERE period = (?1) LIMIT 1";
 
 pub const LC_UPDATE_PERIOD_LOOKUP_QUERY: &str =
     "SELECT period FROM lc_update WHERE period = (?1) LIMIT 1";
 
+pub const DELETE_LC_UPDATE_QUERY: &str = "DELETE FROM lc_update WHERE period = (?1)";
+
+/// Query to get the periods of all the light client update records.
+pub const LC_UPDATE_PERIODS_QUERY: &str = "SELECT period FROM lc_update";
+
+/// Query to get the periods and sizes of the light client update records, oldest first.
+pub const LC_UPDATE_OLDEST_FIRST_QUERY: &str =
+    "SELECT period, COALESCE(update_size, 0) FROM lc_update ORDER BY period ASC";
+
+/// Query to get the total size of the light client updates of the periods in the range.
+pub const LC_UPDATE_RANGE_SIZE_QUERY: &str =
+    "SELECT TOTAL(update_size) FROM lc_update WHERE period >= (?1) AND period < (?2)";
+
 /// Create the historical summaries table. Add CHECK constraint to ensure that only one row is
 /// inserted.
 pub const HISTORICAL_SUMMARIES_CREATE_TABLE: &str =
@@ -66,14 +101,192 @@ pub const INSERT_OR_REPLACE_HISTORICAL_SUMMARIES_QUERY: &str =
     "INSERT OR REPLACE INTO historical_summaries (id, epoch, value, update_size)
                       VALUES (?1, ?2, ?3, ?4)";
 
-/// Query to get the historical summary that is greater than or equal to the given epoch.
+/// Query to get the historical summary that is greater than or equal to the given epoch, resolving
+/// it through the content blob table if it was stored with content dedup.
 pub const HISTORICAL_SUMMARIES_LOOKUP_QUERY: &str =
-    "SELECT value FROM historical_summaries WHERE epoch >= (?1) LIMIT 1";
+    "SELECT COALESCE(blob.value, historical_summaries.value)
+    FROM historical_summaries
+    LEFT JOIN beacon_content_blob AS blob ON blob.content_hash = historical_summaries.value
+    WHERE epoch >= (?1) LIMIT 1";
 
 /// Query to get the epoch of the first historical summary that is greater than or equal to the
 /// given epoch.
 pub const HISTORICAL_SUMMARIES_EPOCH_LOOKUP_QUERY: &str =
     "SELECT epoch FROM historical_summaries WHERE epoch >= (?1) LIMIT 1";
 
+/// Query to get the epochs of all the historical summaries records.
+pub const HISTORICAL_SUMMARIES_EPOCHS_QUERY: &str = "SELECT epoch FROM historical_summaries";
+
+/// Create the content blob table, used by content dedup. With content dedup, the `value` column of
+/// the beacon tables holds the hash of the content value, and the value itself is stored once in
+/// this table.
+pub const BEACON_CONTENT_BLOB_CREATE_TABLE: &str =
+    "CREATE TABLE IF NOT EXISTS beacon_content_blob (
+        content_hash BLOB PRIMARY KEY,
+        value BLOB NOT NULL
+    );";
+
+pub const INSERT_BEACON_CONTENT_BLOB_QUERY: &str =
+    "INSERT OR IGNORE INTO beacon_content_blob (content_hash, value) VALUES (?1, ?2)";
+
+/// Query to delete the content blobs that are no longer referenced by any beacon table.
+pub const DELETE_UNREFERENCED_BEACON_CONTENT_BLOBS_QUERY: &str =
+    "DELETE FROM beacon_content_blob WHERE content_hash NOT IN (
+        SELECT value FROM lc_bootstrap
+        UNION SELECT value FROM lc_update
+        UNION SELECT value FROM historical_summaries
+    )";
+
+/// Query to get the number of content blobs that are no longer referenced by any beacon table, and
+/// the size used to store them.
+pub const UNREFERENCED_BEACON_CONTENT_BLOBS_SIZE_QUERY: &str =
+    "SELECT COUNT(*), TOTAL(LENGTH(value) + LENGTH(content_hash)) FROM beacon_content_blob
+    WHERE content_hash NOT IN (
+        SELECT value FROM lc_bootstrap
+        UNION SELECT value FROM lc_update
+        UNION SELECT value FROM historical_summaries
+    )";
+
+/// Query to get the total size of the content values that are referenced through the content blob
+/// table, and the size actually used to store them (the blobs, plus the hash of every reference).
+pub const BEACON_CONTENT_DEDUP_SIZE_QUERY: &str = "SELECT
+    (SELECT TOTAL(LENGTH(blob.value)) FROM (
+        SELECT value FROM lc_bootstrap
+        UNION ALL SELECT value FROM lc_update
+        UNION ALL SELECT value FROM historical_summaries
+    ) AS refs JOIN beacon_content_blob AS blob ON blob.content_hash = refs.value) AS referenced_size,
+    (SELECT TOTAL(LENGTH(value) + LENGTH(content_hash)) FROM beacon_content_blob) +
+    (SELECT TOTAL(LENGTH(content_hash)) FROM (
+        SELECT value FROM lc_bootstrap
+        UNION ALL SELECT value FROM lc_update
+        UNION ALL SELECT value FROM historical_summaries
+    ) AS refs JOIN beacon_content_blob ON content_hash = refs.value) AS stored_size;";
+
+/// Query to get the number of stored bootstraps, light client updates and historical summaries.
+pub const BEACON_CONTENT_TYPE_COUNT_QUERY: &str = "SELECT
+    (SELECT COUNT(*) FROM lc_bootstrap),
+    (SELECT COUNT(*) FROM lc_update),
+    (SELECT COUNT(*) FROM historical_summaries);";
+
+/// Create the table of the beacon content stored with a signature of a trusted signer, along with
+/// the hash of the signed content value. The content is only trusted as long as the stored value
+/// has that hash.
+pub const BEACON_TRUSTED_CONTENT_CREATE_TABLE: &str =
+    "CREATE TABLE IF NOT EXISTS beacon_trusted_content (
+        content_key BLOB PRIMARY KEY,
+        value_hash BLOB NOT NULL
+    );";
+
+pub const INSERT_OR_REPLACE_BEACON_TRUSTED_CONTENT_QUERY: &str =
+    "INSERT OR REPLACE INTO beacon_trusted_content (content_key, value_hash) VALUES (?1, ?2)";
+
+pub const BEACON_TRUSTED_CONTENT_LOOKUP_QUERY: &str =
+    "SELECT value_hash FROM beacon_trusted_content WHERE content_key = (?1)";
+
+/// Create the table of the keys of the pinned beacon content, which is kept regardless of the
+/// storage capacity. The content doesn't have to be stored.
+pub const BEACON_PINNED_CONTENT_CREATE_TABLE: &str =
+    "CREATE TABLE IF NOT EXISTS beacon_pinned_content (
+        content_key BLOB PRIMARY KEY
+    );";
+
+pub const INSERT_OR_IGNORE_BEACON_PINNED_CONTENT_QUERY: &str =
+    "INSERT OR IGNORE INTO beacon_pinned_content (content_key) VALUES (?1)";
+
+pub const DELETE_BEACON_PINNED_CONTENT_QUERY: &str =
+    "DELETE FROM beacon_pinned_content WHERE content_key = (?1)";
+
+pub const BEACON_PINNED_CONTENT_KEYS_QUERY: &str =
+    "SELECT content_key FROM beacon_pinned_content ORDER BY rowid";
+
+/// Query to get the page size of the database, in bytes.
+pub const PAGE_SIZE_QUERY: &str = "PRAGMA page_size;";
+
+// History Specific SQL
+
+/// The block numbers of the stored block headers, by content id of their content key, so that the
+/// stored block range can be summarized without decoding the headers.
+pub const HISTORY_BLOCK_NUMBER_CREATE_TABLE: &str =
+    "CREATE TABLE IF NOT EXISTS history_block_number (
+        content_id BLOB PRIMARY KEY,
+        block_number INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS history_block_number_idx ON history_block_number(block_number);";
+
+/// Query to check whether the block number table exists, which it doesn't for databases created
+/// before it was introduced.
+pub const HISTORY_BLOCK_NUMBER_TABLE_EXISTS_QUERY: &str =
+    "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'history_block_number'";
+
+/// Create the table with the progress of indexing the block numbers of the headers that were stored
+/// before the block number table was introduced. Add CHECK constraint to ensure that only one row
+/// is inserted, which is deleted once the backfill is done.
+pub const HISTORY_BLOCK_NUMBER_BACKFILL_CREATE_TABLE: &str =
+    "CREATE TABLE IF NOT EXISTS history_block_number_backfill (
+        ID INTEGER PRIMARY KEY CHECK (ID = 1),
+        last_content_id BLOB
+    );";
+
+/// Query to set the content id up to which the headers were indexed, NULL if none was.
+pub const INSERT_OR_REPLACE_HISTORY_BLOCK_NUMBER_BACKFILL_QUERY: &str =
+    "INSERT OR REPLACE INTO history_block_number_backfill (ID, last_content_id) VALUES (1, ?1)";
+
+pub const HISTORY_BLOCK_NUMBER_BACKFILL_LOOKUP_QUERY: &str =
+    "SELECT last_content_id FROM history_block_number_backfill WHERE ID = 1";
+
+pub const DELETE_HISTORY_BLOCK_NUMBER_BACKFILL_QUERY: &str =
+    "DELETE FROM history_block_number_backfill";
+
+pub const INSERT_HISTORY_BLOCK_NUMBER_QUERY: &str =
+    "INSERT OR IGNORE INTO history_block_number (content_id, block_number) VALUES (?1, ?2)";
+
+pub const DELETE_HISTORY_BLOCK_NUMBER_QUERY: &str =
+    "DELETE FROM history_block_number WHERE content_id = (?1)";
+
+/// Query to get the content id of a stored header of the block number.
+pub const HISTORY_BLOCK_NUMBER_LOOKUP_QUERY: &str =
+    "SELECT content_id FROM history_block_number WHERE block_number = (?1) LIMIT 1";
+
+/// Query to get the lowest and highest block numbers, and the number of distinct block numbers.
+pub const HISTORY_BLOCK_NUMBER_RANGE_QUERY: &str =
+    "SELECT MIN(block_number), MAX(block_number), COUNT(DISTINCT block_number)
+    FROM history_block_number";
+
+/// Query to get the largest ranges of missing block numbers between the lowest and the highest
+/// block numbers, as their first and last block numbers.
+pub const HISTORY_BLOCK_NUMBER_GAPS_QUERY: &str = "SELECT
+        block_number + 1 AS gap_start,
+        next_block_number - 1 AS gap_end
+    FROM (
+        SELECT
+            block_number,
+            LEAD(block_number) OVER (ORDER BY block_number) AS next_block_number
+        FROM (SELECT DISTINCT block_number FROM history_block_number)
+    )
+    WHERE next_block_number - block_number > 1
+    ORDER BY next_block_number - block_number DESC, block_number
+    LIMIT (?1)";
+
+/// Query to get the journal mode of the database, e.g. "delete" or "wal".
+pub const JOURNAL_MODE_QUERY: &str = "PRAGMA journal_mode;";
+
+pub const INTEGRITY_CHECK_QUERY: &str = "PRAGMA integrity_check;";
+
 // todo: remove this in the future
 pub const DROP_USAGE_STATS_DB: &str = "DROP TABLE IF EXISTS usage_stats;";
+
+// Schema version SQL
+
+/// Create the schema version table, with a row for every applied migration.
+pub const SCHEMA_VERSION_CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS schema_version (
+    version INTEGER PRIMARY KEY,
+    description TEXT NOT NULL,
+    applied_at INTEGER NOT NULL
+);";
+
+pub const SCHEMA_VERSION_INSERT: &str =
+    "INSERT INTO schema_version (version, description, applied_at)
+        VALUES (:version, :description, :applied_at)";
+
+/// Query to get the version of the last applied migration, which is NULL if there is none.
+pub const SCHEMA_VERSION_LOOKUP: &str = "SELECT MAX(version) FROM schema_version";
@@ -1,35 +1,104 @@
-use std::{fs, path::Path};
+use std::{collections::BTreeMap, fs, path::Path};
 
+use ethportal_api::types::portal::{StorageBackend, StorageBackendInfo};
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{named_params, Connection, DatabaseName, OpenFlags};
 use tracing::info;
 
 use crate::{
     error::ContentStoreError,
-    sql::{
-        DROP_USAGE_STATS_DB, HISTORICAL_SUMMARIES_CREATE_TABLE, LC_BOOTSTRAP_CREATE_TABLE,
-        LC_UPDATE_CREATE_TABLE,
-    },
-    versioned::sql::STORE_INFO_CREATE_TABLE,
+    schema::migrate,
+    sql::{INTEGRITY_CHECK_QUERY, JOURNAL_MODE_QUERY, PAGE_SIZE_QUERY},
+    versioned::sql::{STORE_INFO_LOOKUP_ALL, TABLE_EXISTS},
     DATABASE_NAME,
 };
 
-/// Helper function for opening a SQLite connection.
+/// Helper function for opening a SQLite connection. The missing schema migrations are applied to
+/// the database, see [crate::schema].
 pub fn setup_sql(node_data_dir: &Path) -> Result<Pool<SqliteConnectionManager>, ContentStoreError> {
     let sql_path = node_data_dir.join(DATABASE_NAME);
     info!(path = %sql_path.display(), "Setting up SqliteDB");
 
     let manager = SqliteConnectionManager::file(sql_path);
     let pool = Pool::new(manager)?;
-    let conn = pool.get()?;
-    conn.execute_batch(LC_BOOTSTRAP_CREATE_TABLE)?;
-    conn.execute_batch(LC_UPDATE_CREATE_TABLE)?;
-    conn.execute_batch(HISTORICAL_SUMMARIES_CREATE_TABLE)?;
-    conn.execute_batch(STORE_INFO_CREATE_TABLE)?;
-    conn.execute_batch(DROP_USAGE_STATS_DB)?;
+    let schema_version = migrate(&mut pool.get()?)?;
+    info!(schema_version, "SqliteDB is up to date");
     Ok(pool)
 }
 
+/// Returns the type and metadata of the database behind the connection pool. If `redact_path` is
+/// set, only the file name of the database is returned.
+pub fn storage_backend_info(
+    pool: &Pool<SqliteConnectionManager>,
+    redact_path: bool,
+) -> Result<StorageBackendInfo, ContentStoreError> {
+    let conn = pool.get()?;
+    // In-memory and temporary databases have no path.
+    let path = conn.path().filter(|path| !path.is_empty()).map(|path| {
+        if redact_path {
+            Path::new(path)
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().to_string())
+                .unwrap_or_default()
+        } else {
+            path.to_string()
+        }
+    });
+    let page_size: u64 = conn.query_row(PAGE_SIZE_QUERY, [], |row| row.get(0))?;
+    let journal_mode: String = conn.query_row(JOURNAL_MODE_QUERY, [], |row| row.get(0))?;
+
+    Ok(StorageBackendInfo {
+        backend: match path {
+            Some(_) => StorageBackend::Sqlite,
+            None => StorageBackend::Memory,
+        },
+        path,
+        page_size,
+        wal_enabled: journal_mode.eq_ignore_ascii_case("wal"),
+    })
+}
+
+/// Copies the database of the node data dir to the destination file, using SQLite's online backup
+/// API, so that the copy is consistent even if the node is writing to the database.
+pub fn backup_database(node_data_dir: &Path, destination: &Path) -> Result<(), ContentStoreError> {
+    let sql_path = node_data_dir.join(DATABASE_NAME);
+    if !sql_path.exists() {
+        return Err(ContentStoreError::Database(format!(
+            "Database {} doesn't exist",
+            sql_path.display()
+        )));
+    }
+    let conn = Connection::open_with_flags(sql_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    conn.backup(DatabaseName::Main, destination, None)?;
+    Ok(())
+}
+
+/// Checks the integrity of the database file, and returns the versions of its content stores, by
+/// content type.
+pub fn check_database(path: &Path) -> Result<BTreeMap<String, String>, ContentStoreError> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let integrity: String = conn.query_row(INTEGRITY_CHECK_QUERY, [], |row| row.get(0))?;
+    if integrity != "ok" {
+        return Err(ContentStoreError::Database(format!(
+            "Database {} failed the integrity check: {integrity}",
+            path.display()
+        )));
+    }
+    // Databases that were never opened by a node have no store info.
+    if !conn
+        .prepare(TABLE_EXISTS)?
+        .exists(named_params! { ":table_name": "store_info" })?
+    {
+        return Ok(BTreeMap::new());
+    }
+    let mut query = conn.prepare(STORE_INFO_LOOKUP_ALL)?;
+    let store_versions = query
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+    Ok(store_versions)
+}
+
 /// Internal method used to measure on-disk storage usage.
 pub fn get_total_size_of_directory_in_bytes(
     path: impl AsRef<Path>,
@@ -0,0 +1,110 @@
+use std::{
+    collections::HashMap,
+    mem,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use alloy::primitives::B256;
+
+/// The number of pending access times after which they are written to the database.
+const FLUSH_THRESHOLD: usize = 1000;
+
+/// The max time for which access times are kept in memory before they are written to the database.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The access times that aren't written to the database yet.
+#[derive(Debug)]
+struct PendingAccessTimes {
+    /// The time at which each content was last read, in seconds since the Unix epoch.
+    times: HashMap<B256, u64>,
+    last_flush: Instant,
+}
+
+/// Keeps the times at which content was last read in memory, so that reading content doesn't
+/// write to the database. The access times are meant to be written in batches, once enough of them
+/// are pending or once they were pending long enough, and when the store is dropped.
+#[derive(Debug)]
+pub struct AccessTimes {
+    pending: Mutex<PendingAccessTimes>,
+}
+
+impl Default for AccessTimes {
+    fn default() -> Self {
+        Self {
+            pending: Mutex::new(PendingAccessTimes {
+                times: HashMap::new(),
+                last_flush: Instant::now(),
+            }),
+        }
+    }
+}
+
+impl AccessTimes {
+    /// Records that the content was read at the given time, and returns whether the pending
+    /// access times should be written.
+    pub fn record(&self, content_id: B256, accessed_at: u64) -> bool {
+        self.record_at(content_id, accessed_at, Instant::now())
+    }
+
+    fn record_at(&self, content_id: B256, accessed_at: u64, now: Instant) -> bool {
+        let mut pending = self.lock();
+        pending.times.insert(content_id, accessed_at);
+        pending.times.len() >= FLUSH_THRESHOLD || now - pending.last_flush >= FLUSH_INTERVAL
+    }
+
+    /// Returns the pending access times, which are no longer pending afterwards.
+    pub fn take(&self) -> HashMap<B256, u64> {
+        self.take_at(Instant::now())
+    }
+
+    fn take_at(&self, now: Instant) -> HashMap<B256, u64> {
+        let mut pending = self.lock();
+        pending.last_flush = now;
+        mem::take(&mut pending.times)
+    }
+
+    fn lock(&self) -> MutexGuard<PendingAccessTimes> {
+        // The pending access times remain consistent even if a thread panicked while holding it.
+        self.pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::U256;
+
+    use super::*;
+
+    #[test]
+    fn flush_is_due_after_threshold() {
+        let access_times = AccessTimes::default();
+        let now = Instant::now();
+        for i in 0..FLUSH_THRESHOLD - 1 {
+            assert!(!access_times.record_at(B256::from(U256::from(i)), 1, now));
+        }
+        // Reading the same content again only updates its access time.
+        assert!(!access_times.record_at(B256::ZERO, 2, now));
+        assert!(access_times.record_at(B256::random(), 1, now));
+
+        let times = access_times.take_at(now);
+        assert_eq!(times.len(), FLUSH_THRESHOLD);
+        assert_eq!(times[&B256::ZERO], 2);
+        assert!(access_times.take_at(now).is_empty());
+    }
+
+    #[test]
+    fn flush_is_due_after_interval() {
+        let access_times = AccessTimes::default();
+        let start = Instant::now();
+        access_times.take_at(start);
+
+        assert!(!access_times.record_at(B256::random(), 1, start + FLUSH_INTERVAL / 2));
+        assert!(access_times.record_at(B256::random(), 1, start + FLUSH_INTERVAL));
+
+        access_times.take_at(start + FLUSH_INTERVAL);
+        assert!(!access_times.record_at(B256::random(), 1, start + FLUSH_INTERVAL));
+    }
+}
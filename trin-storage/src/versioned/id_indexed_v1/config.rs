@@ -1,12 +1,12 @@
 use std::path::PathBuf;
 
 use discv5::enr::NodeId;
-use ethportal_api::types::network::Subnetwork;
+use ethportal_api::types::{cli::StorageEvictionPolicy, network::Subnetwork};
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 
 use super::pruning_strategy::PruningConfig;
-use crate::{versioned::ContentType, DistanceFunction, PortalStorageConfig};
+use crate::{versioned::ContentType, ArchiveStorageConfig, DistanceFunction, PortalStorageConfig};
 
 /// The config for the IdIndexedV1Store
 #[derive(Clone, Debug)]
@@ -19,6 +19,15 @@ pub struct IdIndexedV1StoreConfig {
     pub sql_connection_pool: Pool<SqliteConnectionManager>,
     pub distance_fn: DistanceFunction,
     pub pruning_config: PruningConfig,
+    /// Whether the content key stored along with the content value is checked to still produce
+    /// the content id, when the content value is read.
+    pub verify_on_read: bool,
+    /// The policy for choosing which content to evict once the storage is full.
+    pub eviction_policy: StorageEvictionPolicy,
+    /// The secondary tier, which keeps the content that is evicted from this store.
+    pub archive: Option<ArchiveStorageConfig>,
+    /// The number of most recently stored content items that are served from memory.
+    pub recent_writes_capacity: usize,
 }
 
 impl IdIndexedV1StoreConfig {
@@ -37,6 +46,10 @@ impl IdIndexedV1StoreConfig {
             distance_fn: config.distance_fn,
             // consider making this a parameter if we start using non-default value
             pruning_config: PruningConfig::default(),
+            verify_on_read: config.verify_on_read,
+            eviction_policy: config.eviction_policy,
+            archive: config.archive,
+            recent_writes_capacity: config.recent_writes_capacity,
         }
     }
 }
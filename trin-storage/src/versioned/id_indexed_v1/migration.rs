@@ -40,6 +40,20 @@ pub fn migrate_legacy_history_store(
         new_table_name
     ))?;
 
+    // Add the columns that the legacy table doesn't have. The schema migrations that add them to
+    // the existing tables were already applied, as they run when the database is opened.
+    debug!(content_type = %content_type, "Adding content meta columns");
+    for column in sql::ADDED_COLUMNS {
+        config
+            .sql_connection_pool
+            .get()?
+            .execute_batch(&sql::add_column(
+                content_type,
+                column.name,
+                column.definition,
+            ))?;
+    }
+
     info!(content_type = %content_type, "Migration finished");
     Ok(())
 }
@@ -1,8 +1,10 @@
+mod access_times;
 mod config;
 mod migration;
 mod pruning_strategy;
-pub(super) mod sql;
+mod recent_writes;
+pub(crate) mod sql;
 mod store;
 
 pub use config::IdIndexedV1StoreConfig;
-pub use store::IdIndexedV1Store;
+pub use store::{IdIndexedV1Store, OfferSimulation};
@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+
+use alloy::primitives::B256;
+use ethportal_api::RawContentValue;
+
+/// Keeps the most recently stored content values in memory, so that they can be served without
+/// reading the database. Content is often requested right after it was stored, e.g. when the node
+/// gossips the content it received.
+///
+/// Unlike a read cache, it's only populated when content is stored. As it holds few values, they
+/// are looked up by scanning them. The content that is deleted from the database has to be removed
+/// from it as well.
+#[derive(Debug)]
+pub struct RecentWrites {
+    /// The maximum number of content values.
+    capacity: usize,
+    /// The content ids and values, the most recently stored last.
+    entries: VecDeque<(B256, RawContentValue)>,
+}
+
+impl RecentWrites {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the content value, if it was stored recently.
+    pub fn get(&self, content_id: &B256) -> Option<RawContentValue> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(id, _)| id == content_id)
+            .map(|(_, value)| value.clone())
+    }
+
+    /// Records that the content was stored, which drops the least recently stored content once
+    /// the capacity is reached.
+    pub fn insert(&mut self, content_id: B256, content_value: RawContentValue) {
+        self.remove(&content_id);
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((content_id, content_value));
+    }
+
+    /// Removes the content, if it was stored recently.
+    pub fn remove(&mut self, content_id: &B256) {
+        if let Some(index) = self.entries.iter().position(|(id, _)| id == content_id) {
+            self.entries.remove(index);
+        }
+    }
+
+    /// Removes all content.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::U256;
+
+    use super::*;
+
+    fn id(i: u64) -> B256 {
+        B256::from(U256::from(i))
+    }
+
+    fn value(i: u8) -> RawContentValue {
+        RawContentValue::from(vec![i; 10])
+    }
+
+    #[test]
+    fn least_recently_stored_is_dropped() {
+        let mut recent_writes = RecentWrites::new(2);
+        recent_writes.insert(id(1), value(1));
+        recent_writes.insert(id(2), value(2));
+        recent_writes.insert(id(3), value(3));
+
+        assert_eq!(recent_writes.get(&id(1)), None);
+        assert_eq!(recent_writes.get(&id(2)), Some(value(2)));
+        assert_eq!(recent_writes.get(&id(3)), Some(value(3)));
+
+        // Storing the content again makes it the most recently stored.
+        recent_writes.insert(id(2), value(4));
+        recent_writes.insert(id(5), value(5));
+        assert_eq!(recent_writes.get(&id(2)), Some(value(4)));
+        assert_eq!(recent_writes.get(&id(3)), None);
+
+        // Removing content makes room for other content.
+        recent_writes.remove(&id(2));
+        recent_writes.insert(id(6), value(6));
+        assert_eq!(recent_writes.get(&id(5)), Some(value(5)));
+        assert_eq!(recent_writes.get(&id(6)), Some(value(6)));
+    }
+
+    #[test]
+    fn removed_content_is_not_returned() {
+        let mut recent_writes = RecentWrites::new(2);
+        recent_writes.insert(id(1), value(1));
+        recent_writes.insert(id(2), value(2));
+
+        recent_writes.remove(&id(1));
+        assert_eq!(recent_writes.get(&id(1)), None);
+        assert_eq!(recent_writes.get(&id(2)), Some(value(2)));
+
+        recent_writes.clear();
+        assert_eq!(recent_writes.get(&id(2)), None);
+    }
+}
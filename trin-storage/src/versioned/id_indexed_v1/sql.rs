@@ -13,7 +13,14 @@ pub fn create_table(content_type: &ContentType) -> String {
             content_key BLOB NOT NULL,
             content_value BLOB NOT NULL,
             distance_short INTEGER NOT NULL,
-            content_size INTEGER NOT NULL
+            content_size INTEGER NOT NULL,
+            provenance TEXT NOT NULL DEFAULT 'unknown',
+            offered_by BLOB,
+            stored_at INTEGER,
+            last_accessed INTEGER,
+            source TEXT,
+            trusted INTEGER NOT NULL DEFAULT 0,
+            value_hash BLOB
         );
         CREATE INDEX IF NOT EXISTS {0}_distance_short_idx ON {0} (distance_short);
         CREATE INDEX IF NOT EXISTS {0}_content_size_idx ON {0} (content_size);
@@ -22,6 +29,79 @@ pub fn create_table(content_type: &ContentType) -> String {
     )
 }
 
+/// A column that was added to the table after it was introduced.
+pub struct AddedColumn {
+    pub name: &'static str,
+    pub definition: &'static str,
+    /// The schema version whose migration adds the column to the existing tables, see
+    /// [crate::schema].
+    pub schema_version: u32,
+}
+
+/// The columns that were added to the table after it was introduced, in the order they were added.
+///
+/// The provenance of the content stored before it was recorded is unknown, and so are the peer
+/// that offered it, when it was stored and when it was last read. Such content has no source, and
+/// isn't trusted. Its value has no hash, so it can only be checked against its content key on read.
+pub const ADDED_COLUMNS: [AddedColumn; 7] = [
+    AddedColumn {
+        name: "provenance",
+        definition: "TEXT NOT NULL DEFAULT 'unknown'",
+        schema_version: 2,
+    },
+    AddedColumn {
+        name: "offered_by",
+        definition: "BLOB",
+        schema_version: 2,
+    },
+    AddedColumn {
+        name: "stored_at",
+        definition: "INTEGER",
+        schema_version: 2,
+    },
+    AddedColumn {
+        name: "last_accessed",
+        definition: "INTEGER",
+        schema_version: 2,
+    },
+    AddedColumn {
+        name: "source",
+        definition: "TEXT",
+        schema_version: 2,
+    },
+    AddedColumn {
+        name: "trusted",
+        definition: "INTEGER NOT NULL DEFAULT 0",
+        schema_version: 3,
+    },
+    AddedColumn {
+        name: "value_hash",
+        definition: "BLOB",
+        schema_version: 6,
+    },
+];
+
+/// All the columns of the table.
+const ROW_COLUMNS: &str = "content_id, content_key, content_value, distance_short, content_size, \
+    provenance, offered_by, stored_at, last_accessed, source, trusted, value_hash";
+
+/// Returns whether the table has the column, which tables created before it was introduced
+/// don't have.
+pub fn has_column(content_type: &ContentType, column: &str) -> String {
+    format!(
+        "SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name = '{column}'",
+        table_name(content_type)
+    )
+}
+
+/// Adds the column to a table that was created before it was introduced.
+pub fn add_column(content_type: &ContentType, column: &str, definition: &str) -> String {
+    format!(
+        "ALTER TABLE {} ADD COLUMN {column} {definition}",
+        table_name(content_type)
+    )
+}
+
 pub fn insert(content_type: &ContentType) -> String {
     format!(
         "
@@ -30,14 +110,45 @@ pub fn insert(content_type: &ContentType) -> String {
             content_key,
             content_value,
             distance_short,
-            content_size
+            content_size,
+            provenance,
+            offered_by,
+            stored_at,
+            value_hash
         )
         VALUES (
             :content_id,
             :content_key,
             :content_value,
             :distance_short,
-            :content_size
+            :content_size,
+            :provenance,
+            :offered_by,
+            :stored_at,
+            :value_hash
+        )",
+        table_name(content_type)
+    )
+}
+
+/// Inserts a row with all its columns, e.g. when it's moved from another tier of the store.
+pub fn insert_row(content_type: &ContentType) -> String {
+    format!(
+        "
+        INSERT OR IGNORE INTO {} ({ROW_COLUMNS})
+        VALUES (
+            :content_id,
+            :content_key,
+            :content_value,
+            :distance_short,
+            :content_size,
+            :provenance,
+            :offered_by,
+            :stored_at,
+            :last_accessed,
+            :source,
+            :trusted,
+            :value_hash
         )",
         table_name(content_type)
     )
@@ -52,6 +163,16 @@ pub fn delete(content_type: &ContentType) -> String {
     )
 }
 
+/// Deletes the content, and returns its row with all its columns.
+pub fn delete_row(content_type: &ContentType) -> String {
+    format!(
+        "DELETE FROM {}
+        WHERE content_id = :content_id
+        RETURNING {ROW_COLUMNS}",
+        table_name(content_type)
+    )
+}
+
 pub fn lookup_key(content_type: &ContentType) -> String {
     format!(
         "SELECT content_key FROM {} WHERE content_id = :content_id LIMIT 1",
@@ -66,6 +187,35 @@ pub fn lookup_value(content_type: &ContentType) -> String {
     )
 }
 
+pub fn lookup_meta(content_type: &ContentType) -> String {
+    format!(
+        "SELECT provenance, offered_by, stored_at, source, trusted FROM {}
+        WHERE content_id = :content_id
+        LIMIT 1",
+        table_name(content_type)
+    )
+}
+
+/// Counts the entries that are closer to the local node than the content, if it is stored.
+pub fn distance_rank(content_type: &ContentType) -> String {
+    format!(
+        "SELECT (SELECT COUNT(*) FROM {0} WHERE distance_short < target.distance_short)
+        FROM {0} AS target
+        WHERE target.content_id = :content_id
+        LIMIT 1",
+        table_name(content_type)
+    )
+}
+
+pub fn lookup_key_value(content_type: &ContentType) -> String {
+    format!(
+        "SELECT content_key, content_value, value_hash FROM {}
+        WHERE content_id = :content_id
+        LIMIT 1",
+        table_name(content_type)
+    )
+}
+
 pub fn delete_farthest(content_type: &ContentType) -> String {
     format!(
         "DELETE FROM {0}
@@ -75,7 +225,34 @@ pub fn delete_farthest(content_type: &ContentType) -> String {
             ORDER BY distance_short DESC
             LIMIT :limit
         )
-        RETURNING content_key, content_value, content_size",
+        RETURNING {ROW_COLUMNS}",
+        table_name(content_type)
+    )
+}
+
+/// Deletes the least recently read content that is at least `:band_start` away. The content that
+/// was never read counts as read when it was stored, and is deleted first among the content that
+/// was last read at the same time.
+pub fn delete_least_recently_accessed_in_band(content_type: &ContentType) -> String {
+    format!(
+        "DELETE FROM {0}
+        WHERE rowid IN (
+            SELECT rowid
+            FROM {0}
+            WHERE distance_short >= :band_start
+            ORDER BY COALESCE(last_accessed, stored_at, 0) ASC,
+                last_accessed IS NOT NULL ASC,
+                distance_short DESC
+            LIMIT :limit
+        )
+        RETURNING {ROW_COLUMNS}",
+        table_name(content_type)
+    )
+}
+
+pub fn update_last_accessed(content_type: &ContentType) -> String {
+    format!(
+        "UPDATE {} SET last_accessed = :last_accessed WHERE content_id = :content_id",
         table_name(content_type)
     )
 }
@@ -99,6 +276,98 @@ pub fn paginate(content_type: &ContentType) -> String {
     )
 }
 
+/// Lists the content keys with the provenance and the source. A NULL filter matches any content.
+pub fn paginate_filtered(content_type: &ContentType) -> String {
+    format!(
+        "SELECT content_key FROM {}
+        WHERE (:provenance IS NULL OR provenance = :provenance)
+            AND (:source IS NULL OR source = :source)
+        ORDER BY content_key
+        LIMIT :limit
+        OFFSET :offset",
+        table_name(content_type)
+    )
+}
+
+/// Lists the content keys after the content id, ordered by content id, with the provenance and the
+/// source. A NULL filter matches any content.
+pub fn paginate_after(content_type: &ContentType) -> String {
+    format!(
+        "SELECT content_key FROM {}
+        WHERE content_id > :after
+            AND (:provenance IS NULL OR provenance = :provenance)
+            AND (:source IS NULL OR source = :source)
+        ORDER BY content_id
+        LIMIT :limit",
+        table_name(content_type)
+    )
+}
+
+/// Counts the entries with the provenance and the source. A NULL filter matches any content.
+pub fn entry_count_filtered(content_type: &ContentType) -> String {
+    format!(
+        "SELECT COUNT(*) FROM {}
+        WHERE (:provenance IS NULL OR provenance = :provenance)
+            AND (:source IS NULL OR source = :source)",
+        table_name(content_type)
+    )
+}
+
+pub fn update_source(content_type: &ContentType) -> String {
+    format!(
+        "UPDATE {} SET source = :source WHERE content_id = :content_id",
+        table_name(content_type)
+    )
+}
+
+/// Marks the content as trusted, only if the stored content value is the one that was signed.
+pub fn update_trusted(content_type: &ContentType) -> String {
+    format!(
+        "UPDATE {} SET trusted = 1
+        WHERE content_id = :content_id AND content_value = :content_value",
+        table_name(content_type)
+    )
+}
+
+pub fn delete_by_source(content_type: &ContentType) -> String {
+    format!(
+        "DELETE FROM {}
+        WHERE source = :source
+        RETURNING content_key, content_size",
+        table_name(content_type)
+    )
+}
+
+/// Counts the entries by the first byte of the content key, which is the content type selector
+/// of most content keys.
+pub fn entry_count_by_key_selector(content_type: &ContentType) -> String {
+    format!(
+        "SELECT substr(content_key, 1, 1) AS selector, COUNT(*) AS count FROM {}
+        GROUP BY selector",
+        table_name(content_type)
+    )
+}
+
+pub fn export_batch(content_type: &ContentType) -> String {
+    format!(
+        "SELECT content_key, content_value FROM {}
+        WHERE content_id > :after
+        ORDER BY content_id
+        LIMIT :limit",
+        table_name(content_type)
+    )
+}
+
+pub fn lookup_id_range(content_type: &ContentType) -> String {
+    format!(
+        "SELECT content_id, content_key, content_value FROM {}
+        WHERE content_id >= :first AND content_id <= :last
+        ORDER BY content_id
+        LIMIT :limit",
+        table_name(content_type)
+    )
+}
+
 pub fn entry_count_and_size(content_type: &ContentType) -> String {
     format!(
         "SELECT COUNT(*) as count, TOTAL(content_size) as used_capacity FROM {}",
@@ -1,43 +1,307 @@
-use std::marker::PhantomData;
+use std::{
+    collections::HashSet,
+    marker::PhantomData,
+    mem,
+    sync::{Mutex, MutexGuard},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-use ethportal_api::{types::distance::Distance, OverlayContentKey, RawContentValue};
+use alloy::primitives::{keccak256, B256, U256};
+use discv5::enr::NodeId;
+use ethportal_api::{
+    types::{
+        cli::StorageEvictionPolicy,
+        distance::Distance,
+        portal::{ContentDistanceRank, ContentProvenance, LocalContentMeta, SimulatedOfferInfo},
+    },
+    utils::bytes::hex_encode,
+    OverlayContentKey, RawContentValue,
+};
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::{named_params, types::Type, OptionalExtension};
-use tracing::{debug, error, warn};
+use rusqlite::{named_params, types::Type, Connection, OptionalExtension};
+use tracing::{debug, error, info, warn};
 use trin_metrics::storage::StorageMetricsReporter;
 
 use super::{
-    migration::migrate_legacy_history_store, pruning_strategy::PruningStrategy, sql,
-    IdIndexedV1StoreConfig,
+    access_times::AccessTimes, migration::migrate_legacy_history_store,
+    pruning_strategy::PruningStrategy, recent_writes::RecentWrites, sql, IdIndexedV1StoreConfig,
 };
 use crate::{
     error::ContentStoreError,
     utils::get_total_size_of_directory_in_bytes,
     versioned::{usage_stats::UsageStats, ContentType, StoreVersion, VersionedContentStore},
-    ContentId,
+    ArchiveStorageConfig, ContentId, ContentLabels, DataRadiusHistory, ShouldWeStoreContent,
 };
 
+/// The change in storage usage, as a percentage of the storage capacity, after which the radius is
+/// recomputed.
+const RADIUS_RECOMPUTE_USAGE_CHANGE_PERCENT: u64 = 5;
+
+/// The number of bands the radius is split into by the `DistanceLru` eviction policy. The least
+/// recently read content of the farthest band is evicted first.
+const DISTANCE_LRU_BAND_COUNT: u32 = 16;
+
+const BYTES_IN_MB_F64: f64 = 1000.0 * 1000.0;
+
 /// The result of looking for the farthest content.
 struct FarthestQueryResult {
     content_id: ContentId,
     distance_u32: u32,
 }
 
+/// A row of the table with all its columns, which is moved between the tiers of the store as is.
+struct TableRow {
+    content_id: Vec<u8>,
+    content_key: Vec<u8>,
+    content_value: Vec<u8>,
+    distance_short: u32,
+    content_size: u64,
+    provenance: String,
+    offered_by: Option<Vec<u8>>,
+    stored_at: Option<u64>,
+    last_accessed: Option<u64>,
+    source: Option<String>,
+    trusted: bool,
+    value_hash: Option<Vec<u8>>,
+}
+
+impl TableRow {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            content_id: row.get("content_id")?,
+            content_key: row.get("content_key")?,
+            content_value: row.get("content_value")?,
+            distance_short: row.get("distance_short")?,
+            content_size: row.get("content_size")?,
+            provenance: row.get("provenance")?,
+            offered_by: row.get("offered_by")?,
+            stored_at: row.get("stored_at")?,
+            last_accessed: row.get("last_accessed")?,
+            source: row.get("source")?,
+            trusted: row.get("trusted")?,
+            value_hash: row.get("value_hash")?,
+        })
+    }
+
+    /// Inserts the row into the table, unless content with the same id is already there. Returns
+    /// whether it was inserted.
+    fn insert(&self, conn: &Connection, content_type: &ContentType) -> rusqlite::Result<bool> {
+        let inserted = conn.execute(
+            &sql::insert_row(content_type),
+            named_params! {
+                ":content_id": self.content_id,
+                ":content_key": self.content_key,
+                ":content_value": self.content_value,
+                ":distance_short": self.distance_short,
+                ":content_size": self.content_size,
+                ":provenance": self.provenance,
+                ":offered_by": self.offered_by,
+                ":stored_at": self.stored_at,
+                ":last_accessed": self.last_accessed,
+                ":source": self.source,
+                ":trusted": self.trusted,
+                ":value_hash": self.value_hash,
+            },
+        )?;
+        Ok(inserted > 0)
+    }
+}
+
+/// The radius and the tiers of a store, which offers are simulated against without borrowing the
+/// store. See [IdIndexedV1Store::simulate_offers].
+#[derive(Debug)]
+pub struct OfferSimulation {
+    radius: Distance,
+    tier_configs: Vec<IdIndexedV1StoreConfig>,
+}
+
+impl OfferSimulation {
+    /// Checks whether `sample_size` synthetic content ids, spread evenly across the keyspace,
+    /// would be stored if they were offered.
+    ///
+    /// The ids within the radius are looked up with a single statement per tier, in a read
+    /// transaction, so that the lookups see the same content.
+    pub fn run(&self, sample_size: u64) -> Result<SimulatedOfferInfo, ContentStoreError> {
+        let Some(primary_config) = self.tier_configs.first() else {
+            return Err(ContentStoreError::Database(
+                "Store has no tiers to simulate offers against".to_string(),
+            ));
+        };
+        let step = U256::MAX / U256::from(sample_size.max(1));
+        let mut within_radius = (0..sample_size)
+            .map(|i| ContentId::from(B256::from(step * U256::from(i))))
+            .filter(|content_id| {
+                primary_config
+                    .distance_fn
+                    .distance(&primary_config.node_id, &content_id.0)
+                    <= self.radius
+            })
+            .collect::<Vec<_>>();
+        let within_radius_count = within_radius.len() as u64;
+
+        for config in &self.tier_configs {
+            if within_radius.is_empty() {
+                break;
+            }
+            let mut conn = config.sql_connection_pool.get()?;
+            let transaction = conn.transaction()?;
+            {
+                let mut lookup_query =
+                    transaction.prepare(&sql::lookup_key(&config.content_type))?;
+                let mut not_stored = Vec::with_capacity(within_radius.len());
+                for content_id in within_radius {
+                    if !lookup_query.exists(named_params! { ":content_id": content_id.to_vec() })? {
+                        not_stored.push(content_id);
+                    }
+                }
+                within_radius = not_stored;
+            }
+            transaction.commit()?;
+        }
+
+        let accepted = within_radius.len() as u64;
+        Ok(SimulatedOfferInfo {
+            sampled: sample_size,
+            accepted,
+            already_stored: within_radius_count - accepted,
+            acceptance_rate: if sample_size == 0 {
+                0.0
+            } else {
+                accepted as f64 / sample_size as f64
+            },
+            radius: *self.radius,
+        })
+    }
+}
+
+/// The secondary tier of the store, which keeps the content that is evicted from the primary
+/// tier. Once it's full, its farthest content is deleted, and only then content is dropped from
+/// the store.
+#[derive(Debug)]
+struct ArchiveTier {
+    /// The config of the store, with the database and the capacity of the archive.
+    config: IdIndexedV1StoreConfig,
+    /// The strategy for deciding when and how much to prune.
+    pruning_strategy: PruningStrategy,
+    /// The usage stats tracked manually.
+    usage_stats: UsageStats,
+    /// The ids of the content that was read from the archive, which is moved back to the primary
+    /// tier on the next insert, as reads don't modify the store.
+    promotions: Mutex<HashSet<B256>>,
+}
+
+impl ArchiveTier {
+    fn new(
+        config: &IdIndexedV1StoreConfig,
+        archive: ArchiveStorageConfig,
+    ) -> Result<Self, ContentStoreError> {
+        let config = IdIndexedV1StoreConfig {
+            storage_capacity_bytes: archive.storage_capacity_bytes,
+            sql_connection_pool: archive.sql_connection_pool,
+            archive: None,
+            ..config.clone()
+        };
+        maybe_create_table_and_indexes(&config.content_type, &config.sql_connection_pool)?;
+        let usage_stats = config.sql_connection_pool.get()?.query_row(
+            &sql::entry_count_and_size(&config.content_type),
+            [],
+            |row| {
+                let entry_count = row.get("count")?;
+                let used_capacity: f64 = row.get("used_capacity")?;
+                Ok(UsageStats::new(entry_count, used_capacity.round() as u64))
+            },
+        )?;
+        Ok(Self {
+            pruning_strategy: PruningStrategy::new(config.clone()),
+            config,
+            usage_stats,
+            promotions: Mutex::default(),
+        })
+    }
+
+    /// Inserts the rows evicted from the primary tier.
+    fn insert_rows(
+        &mut self,
+        rows: impl IntoIterator<Item = TableRow>,
+    ) -> Result<(), ContentStoreError> {
+        let mut conn = self.config.sql_connection_pool.get()?;
+        let transaction = conn.transaction()?;
+        for row in rows {
+            if row.insert(&transaction, &self.config.content_type)? {
+                self.usage_stats.entry_count += 1;
+                self.usage_stats.total_entry_size_bytes += row.content_size;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Deletes the content, and returns its row, if it's stored.
+    fn take_row(&mut self, content_id: &ContentId) -> Result<Option<TableRow>, ContentStoreError> {
+        let row = self
+            .config
+            .sql_connection_pool
+            .get()?
+            .query_row(
+                &sql::delete_row(&self.config.content_type),
+                named_params! { ":content_id": content_id.to_vec() },
+                TableRow::from_row,
+            )
+            .optional()?;
+        if let Some(row) = &row {
+            self.usage_stats.entry_count -= 1;
+            self.usage_stats.total_entry_size_bytes -= row.content_size;
+        }
+        Ok(row)
+    }
+
+    fn queue_promotion(&self, content_id: &ContentId) {
+        self.lock_promotions().insert(**content_id);
+    }
+
+    fn take_promotions(&self) -> HashSet<B256> {
+        mem::take(&mut *self.lock_promotions())
+    }
+
+    fn lock_promotions(&self) -> MutexGuard<HashSet<B256>> {
+        // The pending promotions remain consistent even if a thread panicked while holding it.
+        self.promotions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Reads a row deleted by pruning, with its content key.
+fn read_deleted_row<TContentKey: OverlayContentKey>(
+    row: &rusqlite::Row,
+) -> rusqlite::Result<(TContentKey, TableRow)> {
+    let row = TableRow::from_row(row)?;
+    TContentKey::try_from_bytes(&row.content_key)
+        .map(|key| (key, row))
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, Type::Blob, e.into()))
+}
+
 /// The result of the pagination lookup.
 #[derive(Debug, PartialEq, Eq)]
 pub struct PaginateResult<TContentKey> {
     /// The content keys of the queried page
     pub content_keys: Vec<TContentKey>,
-    /// The total count of entries in the database
-    pub entry_count: u64,
+    /// The total count of the matching entries in the database, if they were counted
+    pub entry_count: Option<u64>,
 }
 
 /// The store for storing content key/value pairs.
 ///
 /// Different SQL table is created for each `ContentType`, with content-id as a primary key.
 /// It has a configurable capacity and it will prune data that is farthest from the `NodeId` once
-/// it uses more than storage capacity.
+/// it uses more than storage capacity. With the `DistanceLru` eviction policy, the least recently
+/// read data is pruned first among the data farthest from the `NodeId`.
+///
+/// If the config has an archive, the pruned data is moved to the archive database instead of being
+/// deleted. Content is looked up and deleted in both tiers, and content read from the archive is
+/// moved back on the next insert. The radius then only shrinks once the archive is full. Listing,
+/// exporting and the usage stats only cover the primary tier.
 #[derive(Debug)]
 pub struct IdIndexedV1Store<TContentKey: OverlayContentKey> {
     /// The configuration.
@@ -47,14 +311,36 @@ pub struct IdIndexedV1Store<TContentKey: OverlayContentKey> {
     radius: Distance,
     /// The strategy for deciding when and how much to prune.
     pruning_strategy: PruningStrategy,
+    /// The storage usage (in bytes) at the time the radius was last computed.
+    radius_usage_bytes: u64,
+    /// The recent changes of the radius.
+    radius_history: DataRadiusHistory,
     /// The usage stats tracked manually.
     usage_stats: UsageStats,
+    /// The times at which content was read, that aren't written to the database yet. Only tracked
+    /// with the `DistanceLru` eviction policy.
+    access_times: AccessTimes,
+    /// The most recently stored content values, which are looked up before the database.
+    recent_writes: RecentWrites,
+    /// The secondary tier, if the config has an archive.
+    archive: Option<ArchiveTier>,
+    /// The keys of the content that was dropped while pruning on startup, until they're taken.
+    pruned_on_init: Vec<TContentKey>,
     /// The Metrics for tracking performance.
     metrics: StorageMetricsReporter,
     /// Phantom Content Key
     _phantom_content_key: PhantomData<TContentKey>,
 }
 
+impl<TContentKey: OverlayContentKey> Drop for IdIndexedV1Store<TContentKey> {
+    /// Writes the pending access times, so that they aren't lost when the node stops.
+    fn drop(&mut self) {
+        if let Err(err) = self.flush_access_times() {
+            warn!(Db = %self.config.content_type, "Failed to write access times: {err}");
+        }
+    }
+}
+
 impl<TContentKey: OverlayContentKey> VersionedContentStore for IdIndexedV1Store<TContentKey> {
     type Config = IdIndexedV1StoreConfig;
 
@@ -82,12 +368,24 @@ impl<TContentKey: OverlayContentKey> VersionedContentStore for IdIndexedV1Store<
         let subnetwork = config.subnetwork;
 
         let pruning_strategy = PruningStrategy::new(config.clone());
+        let archive = config
+            .archive
+            .clone()
+            .map(|archive| ArchiveTier::new(&config, archive))
+            .transpose()?;
 
+        let recent_writes = RecentWrites::new(config.recent_writes_capacity);
         let mut store = Self {
             config,
             radius: Distance::MAX,
             pruning_strategy,
+            radius_usage_bytes: 0,
+            radius_history: DataRadiusHistory::default(),
             usage_stats: UsageStats::default(),
+            access_times: AccessTimes::default(),
+            recent_writes,
+            archive,
+            pruned_on_init: vec![],
             metrics: StorageMetricsReporter::new(subnetwork),
             _phantom_content_key: PhantomData,
         };
@@ -104,18 +402,57 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
 
         self.init_usage_stats()?;
 
-        if self.pruning_strategy.should_prune(&self.usage_stats) {
+        if self.should_prune() {
             debug!(
                 Db = %self.config.content_type,
                 "High storage usage ({}) -> Pruning",
                 self.usage_stats.total_entry_size_bytes,
             );
-            // ignore dropped content...
-            self.prune()?;
-        } else if self
-            .pruning_strategy
-            .is_usage_above_target_capacity(&self.usage_stats)
-        {
+            self.pruned_on_init = self
+                .prune()?
+                .into_iter()
+                .map(|(content_key, _)| content_key)
+                .collect();
+        } else {
+            self.recompute_radius()?;
+        }
+
+        // Check that distance to the farthest content is what is stored. This is a simple check
+        // that the NodeId didn't change.
+        let farthest = self.lookup_farthest()?;
+        if let Some(farthest) = farthest {
+            let distance = self.distance_to_content_id(&farthest.content_id);
+            if farthest.distance_u32 != distance.big_endian_u32() {
+                return Err(ContentStoreError::Database(format!(
+                    "Distance to the farthest (short: 0x{:08X}) didn't match expected distance ({distance})!",
+                    farthest.distance_u32
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    // PUBLIC FUNCTIONS
+
+    /// Returns the keys of the content that was dropped while pruning on startup, so that the
+    /// data kept alongside the store can be updated. Returns them only once.
+    pub fn take_pruned_on_init(&mut self) -> Vec<TContentKey> {
+        std::mem::take(&mut self.pruned_on_init)
+    }
+
+    /// Returns radius that it will accept to store.
+    pub fn radius(&self) -> Distance {
+        self.radius
+    }
+
+    /// Recomputes the radius from the current storage usage, the same way as on startup.
+    ///
+    /// Pruning only ever shrinks the radius, so after content is deleted the radius could stay
+    /// smaller than the storage usage justifies. Returns whether the radius changed.
+    pub fn recompute_radius(&mut self) -> Result<bool, ContentStoreError> {
+        let old_radius = self.radius;
+        if self.is_usage_above_target_capacity() {
             debug!(
                 Db = %self.config.content_type,
                 "Used capacity ({}) is above target capacity ({}) -> Using distance to farthest for radius",
@@ -123,7 +460,7 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
                 self.pruning_strategy.target_capacity_bytes()
             );
             self.set_radius_to_farthest()?;
-        } else if self.config.storage_capacity_bytes == 0 {
+        } else if self.total_capacity_bytes() == 0 {
             debug!(
                 Db = %self.config.content_type,
                 "Storage capacity is 0 -> Using ZERO radius",
@@ -140,28 +477,14 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
             self.radius = Distance::MAX;
             self.metrics.report_radius(self.radius);
         }
+        self.radius_usage_bytes = self.usage_stats.total_entry_size_bytes;
 
-        // Check that distance to the farthest content is what is stored. This is a simple check
-        // that the NodeId didn't change.
-        let farthest = self.lookup_farthest()?;
-        if let Some(farthest) = farthest {
-            let distance = self.distance_to_content_id(&farthest.content_id);
-            if farthest.distance_u32 != distance.big_endian_u32() {
-                return Err(ContentStoreError::Database(format!(
-                    "Distance to the farthest (short: 0x{:08X}) didn't match expected distance ({distance})!",
-                    farthest.distance_u32
-                )));
-            }
-        }
-
-        Ok(())
+        Ok(self.record_radius_change(old_radius))
     }
 
-    // PUBLIC FUNCTIONS
-
-    /// Returns radius that it will accept to store.
-    pub fn radius(&self) -> Distance {
-        self.radius
+    /// Returns the recent changes of the radius.
+    pub fn radius_history(&self) -> &DataRadiusHistory {
+        &self.radius_history
     }
 
     /// Returns distance to the content id.
@@ -172,15 +495,54 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
     }
 
     /// Returns whether data associated with the content id is already stored.
+    /// Returns whether content with the content id should be stored, i.e. whether it is within
+    /// the radius and not already stored.
+    pub fn should_store(
+        &self,
+        content_id: &ContentId,
+    ) -> Result<ShouldWeStoreContent, ContentStoreError> {
+        if self.distance_to_content_id(content_id) > self.radius {
+            Ok(ShouldWeStoreContent::NotWithinRadius)
+        } else if self.has_content(content_id)? {
+            Ok(ShouldWeStoreContent::AlreadyStored)
+        } else {
+            Ok(ShouldWeStoreContent::Store)
+        }
+    }
+
+    /// Checks whether `sample_size` synthetic content ids, spread evenly across the keyspace,
+    /// would be stored if they were offered, which estimates the share of the keyspace that
+    /// offers are accepted for. See [OfferSimulation].
+    pub fn simulate_offers(
+        &self,
+        sample_size: u64,
+    ) -> Result<SimulatedOfferInfo, ContentStoreError> {
+        self.offer_simulation().run(sample_size)
+    }
+
+    /// Returns what offers are simulated against, which can be used once the store is no longer
+    /// borrowed, e.g. after releasing the lock of the store.
+    pub fn offer_simulation(&self) -> OfferSimulation {
+        OfferSimulation {
+            radius: self.radius,
+            tier_configs: self.tier_configs().cloned().collect(),
+        }
+    }
+
     pub fn has_content(&self, content_id: &ContentId) -> Result<bool, ContentStoreError> {
         let timer = self.metrics.start_process_timer("has_content");
 
-        let has_content = self
-            .config
-            .sql_connection_pool
-            .get()?
-            .prepare(&sql::lookup_key(&self.config.content_type))?
-            .exists(named_params! { ":content_id": content_id.to_vec() })?;
+        let mut has_content = false;
+        for config in self.tier_configs() {
+            has_content = config
+                .sql_connection_pool
+                .get()?
+                .prepare(&sql::lookup_key(&config.content_type))?
+                .exists(named_params! { ":content_id": content_id.to_vec() })?;
+            if has_content {
+                break;
+            }
+        }
 
         self.metrics.stop_process_timer(timer);
         Ok(has_content)
@@ -193,39 +555,77 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
     ) -> Result<Option<TContentKey>, ContentStoreError> {
         let timer = self.metrics.start_process_timer("lookup_content_key");
 
-        let key = self
-            .config
-            .sql_connection_pool
-            .get()?
-            .query_row(
-                &sql::lookup_key(&self.config.content_type),
-                named_params! { ":content_id": content_id.to_vec() },
-                |row| {
-                    let bytes: Vec<u8> = row.get("content_key")?;
-                    TContentKey::try_from_bytes(bytes).map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(0, Type::Blob, e.into())
-                    })
-                },
-            )
-            .optional()?;
+        let mut key = None;
+        for config in self.tier_configs() {
+            key = config
+                .sql_connection_pool
+                .get()?
+                .query_row(
+                    &sql::lookup_key(&config.content_type),
+                    named_params! { ":content_id": content_id.to_vec() },
+                    |row| {
+                        let bytes: Vec<u8> = row.get("content_key")?;
+                        TContentKey::try_from_bytes(bytes).map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(0, Type::Blob, e.into())
+                        })
+                    },
+                )
+                .optional()?;
+            if key.is_some() {
+                break;
+            }
+        }
 
         self.metrics.stop_process_timer(timer);
         Ok(key)
     }
 
     /// Returns content value data is stored.
+    ///
+    /// The recently stored content is returned from memory. If `verify_on_read` is enabled, it
+    /// returns `Corrupted` error if the content key stored along with the value in the database
+    /// doesn't produce the content id anymore, or if the value doesn't have the hash it was stored
+    /// with.
     pub fn lookup_content_value(
         &self,
         content_id: &ContentId,
     ) -> Result<Option<RawContentValue>, ContentStoreError> {
+        if let Some(value) = self.recent_writes.get(content_id) {
+            self.record_access(content_id);
+            return Ok(Some(value));
+        }
+        let mut value = self.lookup_content_value_in(&self.config, content_id)?;
+        if value.is_none() {
+            if let Some(archive) = &self.archive {
+                value = self.lookup_content_value_in(&archive.config, content_id)?;
+                if value.is_some() {
+                    archive.queue_promotion(content_id);
+                }
+            }
+        }
+        if value.is_some() {
+            self.record_access(content_id);
+        }
+        Ok(value)
+    }
+
+    /// Returns content value data is stored in the tier with the config.
+    fn lookup_content_value_in(
+        &self,
+        config: &IdIndexedV1StoreConfig,
+        content_id: &ContentId,
+    ) -> Result<Option<RawContentValue>, ContentStoreError> {
+        if self.config.verify_on_read {
+            return self.lookup_verified_content_value(config, content_id);
+        }
+
         let timer = self.metrics.start_process_timer("lookup_content_value");
 
-        let value = self
-            .config
+        let value = config
             .sql_connection_pool
             .get()?
             .query_row(
-                &sql::lookup_value(&self.config.content_type),
+                &sql::lookup_value(&config.content_type),
                 named_params! { ":content_id": content_id.to_vec() },
                 |row| row.get::<&str, Vec<u8>>("content_value"),
             )
@@ -235,6 +635,106 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
         Ok(value.map(RawContentValue::from))
     }
 
+    /// Records that the content was read, if the eviction policy depends on it. The access times
+    /// are written to the database in batches.
+    fn record_access(&self, content_id: &ContentId) {
+        if self.config.eviction_policy != StorageEvictionPolicy::DistanceLru {
+            return;
+        }
+        if self.access_times.record(**content_id, unix_timestamp()) {
+            if let Err(err) = self.flush_access_times() {
+                warn!(Db = %self.config.content_type, "Failed to write access times: {err}");
+            }
+        }
+    }
+
+    /// Writes the pending access times to the database.
+    fn flush_access_times(&self) -> Result<(), ContentStoreError> {
+        let access_times = self.access_times.take();
+        if access_times.is_empty() {
+            return Ok(());
+        }
+        let timer = self.metrics.start_process_timer("flush_access_times");
+        let mut conn = self.config.sql_connection_pool.get()?;
+        let transaction = conn.transaction()?;
+        {
+            let mut update_query =
+                transaction.prepare(&sql::update_last_accessed(&self.config.content_type))?;
+            for (content_id, last_accessed) in access_times {
+                update_query.execute(named_params! {
+                    ":content_id": content_id.to_vec(),
+                    ":last_accessed": last_accessed,
+                })?;
+            }
+        }
+        transaction.commit()?;
+        self.metrics.stop_process_timer(timer);
+        Ok(())
+    }
+
+    /// Returns content value data is stored, after checking that the content key stored along
+    /// with it still produces the content id, and that the value still has the hash it was stored
+    /// with. The content stored before the hash was recorded only has its key checked.
+    fn lookup_verified_content_value(
+        &self,
+        config: &IdIndexedV1StoreConfig,
+        content_id: &ContentId,
+    ) -> Result<Option<RawContentValue>, ContentStoreError> {
+        let timer = self
+            .metrics
+            .start_process_timer("lookup_verified_content_value");
+
+        let key_value = config
+            .sql_connection_pool
+            .get()?
+            .query_row(
+                &sql::lookup_key_value(&config.content_type),
+                named_params! { ":content_id": content_id.to_vec() },
+                |row| {
+                    Ok((
+                        row.get::<&str, Vec<u8>>("content_key")?,
+                        row.get::<&str, Vec<u8>>("content_value")?,
+                        row.get::<&str, Option<Vec<u8>>>("value_hash")?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        self.metrics.stop_process_timer(timer);
+        let Some((content_key, content_value, value_hash)) = key_value else {
+            return Ok(None);
+        };
+        let content_id = **content_id;
+        let derived_content_id = TContentKey::try_from_bytes(&content_key)
+            .map(|content_key| B256::from(content_key.content_id()))
+            .map_err(|err| format!("invalid content key: {err}"));
+        let message = match derived_content_id {
+            Ok(derived_content_id) if derived_content_id != content_id => {
+                format!("content key produces content id {derived_content_id}")
+            }
+            Ok(_) => match value_hash {
+                Some(value_hash) if value_hash != keccak256(&content_value).as_slice() => {
+                    format!(
+                        "content value doesn't have the hash {} it was stored with",
+                        hex_encode(&value_hash)
+                    )
+                }
+                _ => return Ok(Some(RawContentValue::from(content_value))),
+            },
+            Err(message) => message,
+        };
+        error!(
+            content.id = %content_id,
+            content.key = %hex_encode(&content_key),
+            "Corrupted content in {} store: {message}",
+            self.config.content_type,
+        );
+        Err(ContentStoreError::Corrupted {
+            content_id: content_id.to_string(),
+            message,
+        })
+    }
+
     /// Inserts content key/value pair into storage and prunes the db if necessary.
     /// Returns any content items that were pruned.
     /// It returns `InsufficientRadius` error if content is outside radius.
@@ -242,9 +742,47 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
         &mut self,
         content_key: &TContentKey,
         content_value: RawContentValue,
+    ) -> Result<Vec<(TContentKey, RawContentValue)>, ContentStoreError> {
+        self.insert_with_meta(
+            content_key,
+            content_value,
+            ContentProvenance::Unknown,
+            /* offered_by= */ None,
+        )
+    }
+
+    /// Same as [Self::insert], but also records how the content got into the store, the peer that
+    /// offered it, if any, and when it was stored.
+    pub fn insert_with_meta(
+        &mut self,
+        content_key: &TContentKey,
+        content_value: RawContentValue,
+        provenance: ContentProvenance,
+        offered_by: Option<NodeId>,
+    ) -> Result<Vec<(TContentKey, RawContentValue)>, ContentStoreError> {
+        self.insert_with_labels(
+            content_key,
+            content_value,
+            provenance,
+            offered_by,
+            &ContentLabels::default(),
+        )
+    }
+
+    /// Same as [Self::insert_with_meta], but also labels the content, even if it's already stored.
+    /// The content and its labels are written in a single transaction.
+    pub fn insert_with_labels(
+        &mut self,
+        content_key: &TContentKey,
+        content_value: RawContentValue,
+        provenance: ContentProvenance,
+        offered_by: Option<NodeId>,
+        labels: &ContentLabels,
     ) -> Result<Vec<(TContentKey, RawContentValue)>, ContentStoreError> {
         let insert_with_pruning_timer = self.metrics.start_process_timer("insert_with_pruning");
 
+        self.promote_archived_content()?;
+
         let content_id = content_key.content_id();
 
         let distance = self.distance_to_content_id(&content_id.into());
@@ -258,9 +796,12 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
         let content_id = content_id.to_vec();
         let content_key = content_key.to_bytes().to_vec();
         let content_size = content_id.len() + content_key.len() + content_value.len();
+        let stored_at = unix_timestamp();
 
         let insert_timer = self.metrics.start_process_timer("insert");
-        self.config.sql_connection_pool.get()?.execute(
+        let mut conn = self.config.sql_connection_pool.get()?;
+        let transaction = conn.transaction()?;
+        let inserted = transaction.execute(
             &sql::insert(&self.config.content_type),
             named_params! {
                 ":content_id": content_id,
@@ -268,15 +809,48 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
                 ":content_value": content_value.as_ref(),
                 ":distance_short": distance.big_endian_u32(),
                 ":content_size": content_size,
+                ":provenance": provenance.as_str(),
+                ":offered_by": offered_by.map(|node_id| node_id.raw().to_vec()),
+                ":stored_at": stored_at,
+                ":value_hash": keccak256(&content_value).to_vec(),
             },
         )?;
+        if let Some(source) = &labels.source {
+            transaction.execute(
+                &sql::update_source(&self.config.content_type),
+                named_params! {
+                    ":content_id": content_id,
+                    ":source": source,
+                },
+            )?;
+        }
+        if labels.trusted {
+            transaction.execute(
+                &sql::update_trusted(&self.config.content_type),
+                named_params! {
+                    ":content_id": content_id,
+                    ":content_value": content_value.as_ref(),
+                },
+            )?;
+        }
+        transaction.commit()?;
+        drop(conn);
         self.metrics.stop_process_timer(insert_timer);
 
         self.usage_stats.entry_count += 1;
         self.usage_stats.total_entry_size_bytes += content_size as u64;
         self.usage_stats.report_metrics(&self.metrics);
 
-        let dropped_content = if self.pruning_strategy.should_prune(&self.usage_stats) {
+        let content_id = ContentId::from(B256::from_slice(&content_id));
+        // The content is only kept in the primary tier.
+        if let Some(archive) = &mut self.archive {
+            archive.take_row(&content_id)?;
+        }
+        if inserted > 0 {
+            self.recent_writes.insert(*content_id, content_value);
+        }
+
+        let dropped_content = if self.should_prune() {
             self.prune()?
         } else {
             vec![]
@@ -301,39 +875,279 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
             )
             .optional()?;
 
-        match content_size {
-            Some(content_size) => {
-                self.usage_stats.entry_count -= 1;
-                self.usage_stats.total_entry_size_bytes -= content_size;
-                self.usage_stats.report_metrics(&self.metrics);
-            }
-            None => {
-                debug!(Db = %self.config.content_type,
-                    "Didn't delete content with id: {content_id:?}",
-                );
-            }
+        self.recent_writes.remove(content_id);
+        if let Some(content_size) = content_size {
+            self.usage_stats.entry_count -= 1;
+            self.usage_stats.total_entry_size_bytes -= content_size;
+            self.usage_stats.report_metrics(&self.metrics);
         }
-
+        let archived_row = match &mut self.archive {
+            Some(archive) => archive.take_row(content_id)?,
+            None => None,
+        };
+        if content_size.is_some() || archived_row.is_some() {
+            self.maybe_recompute_radius()?;
+        } else {
+            debug!(Db = %self.config.content_type,
+                "Didn't delete content with id: {content_id:?}",
+            );
+        }
+
         self.metrics.stop_process_timer(timer);
         Ok(())
     }
 
+    /// Returns how the content got into the store, the peer that offered it and when it was
+    /// stored, if it is stored.
+    pub fn lookup_meta(
+        &self,
+        content_id: &ContentId,
+    ) -> Result<Option<LocalContentMeta>, ContentStoreError> {
+        let mut meta = None;
+        for config in self.tier_configs() {
+            meta = config
+                .sql_connection_pool
+                .get()?
+                .query_row(
+                    &sql::lookup_meta(&config.content_type),
+                    named_params! { ":content_id": content_id.to_vec() },
+                    |row| {
+                        Ok((
+                            row.get::<&str, String>("provenance")?,
+                            row.get::<&str, Option<[u8; 32]>>("offered_by")?,
+                            row.get::<&str, Option<u64>>("stored_at")?,
+                            row.get::<&str, Option<String>>("source")?,
+                            row.get::<&str, bool>("trusted")?,
+                        ))
+                    },
+                )
+                .optional()?;
+            if meta.is_some() {
+                break;
+            }
+        }
+        meta.map(|(provenance, offered_by, stored_at, source, trusted)| {
+            Ok(LocalContentMeta {
+                provenance: provenance
+                    .parse()
+                    .map_err(|message| ContentStoreError::InvalidData { message })?,
+                offered_by: offered_by.map(|raw| NodeId::new(&raw)),
+                stored_at,
+                source,
+                trusted,
+            })
+        })
+        .transpose()
+    }
+
+    /// Labels the stored content with the source it was stored from, replacing its previous
+    /// source, if any. Returns whether the content is stored.
+    pub fn set_source(
+        &self,
+        content_id: &ContentId,
+        source: &str,
+    ) -> Result<bool, ContentStoreError> {
+        let mut updated = 0;
+        for config in self.tier_configs() {
+            updated += config.sql_connection_pool.get()?.execute(
+                &sql::update_source(&config.content_type),
+                named_params! {
+                    ":content_id": content_id.to_vec(),
+                    ":source": source,
+                },
+            )?;
+        }
+        Ok(updated > 0)
+    }
+
+    /// Deletes all the content labeled with the source, and returns the content keys of the
+    /// deleted content.
+    pub fn delete_by_source(
+        &mut self,
+        source: &str,
+    ) -> Result<Vec<TContentKey>, ContentStoreError> {
+        let timer = self.metrics.start_process_timer("delete_by_source");
+
+        let delete_from = |config: &IdIndexedV1StoreConfig| -> Result<_, ContentStoreError> {
+            Ok(config
+                .sql_connection_pool
+                .get()?
+                .prepare(&sql::delete_by_source(&config.content_type))?
+                .query_map(named_params! { ":source": source }, |row| {
+                    Ok((
+                        row.get::<&str, Vec<u8>>("content_key")?,
+                        row.get::<&str, u64>("content_size")?,
+                    ))
+                })?
+                .collect::<Result<Vec<_>, rusqlite::Error>>()?)
+        };
+        let mut deleted = delete_from(&self.config)?;
+        // Only the content keys of the deleted content are known, so the recently stored content
+        // is dropped altogether.
+        self.recent_writes.clear();
+
+        // The usage stats are updated before the content keys are decoded, as the content is
+        // deleted regardless.
+        for (_, content_size) in &deleted {
+            self.usage_stats.entry_count -= 1;
+            self.usage_stats.total_entry_size_bytes -= content_size;
+        }
+        if let Some(archive) = &mut self.archive {
+            let archived = delete_from(&archive.config)?;
+            for (_, content_size) in &archived {
+                archive.usage_stats.entry_count -= 1;
+                archive.usage_stats.total_entry_size_bytes -= content_size;
+            }
+            deleted.extend(archived);
+        }
+        if !deleted.is_empty() {
+            self.usage_stats.report_metrics(&self.metrics);
+            self.maybe_recompute_radius()?;
+        }
+        let content_keys = deleted
+            .into_iter()
+            .map(|(content_key, _)| {
+                TContentKey::try_from_bytes(content_key).map_err(|err| {
+                    ContentStoreError::InvalidData {
+                        message: format!("Error deserializing deleted content key: {err}"),
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>, ContentStoreError>>();
+
+        self.metrics.stop_process_timer(timer);
+        content_keys
+    }
+
+    /// Returns the rank of the content among all stored content by distance to the local node, if
+    /// it is stored. The closest content has rank 0, and the farthest content is pruned first.
+    pub fn lookup_distance_rank(
+        &self,
+        content_id: &ContentId,
+    ) -> Result<Option<ContentDistanceRank>, ContentStoreError> {
+        let timer = self.metrics.start_process_timer("lookup_distance_rank");
+        let rank = self
+            .config
+            .sql_connection_pool
+            .get()?
+            .query_row(
+                &sql::distance_rank(&self.config.content_type),
+                named_params! { ":content_id": content_id.to_vec() },
+                |row| row.get::<_, u64>(0),
+            )
+            .optional()?;
+        self.metrics.stop_process_timer(timer);
+        Ok(rank.map(|rank| ContentDistanceRank {
+            rank,
+            total: self.usage_stats.entry_count,
+        }))
+    }
+
     /// Returns a paginated list of all locally available content keys, according to the provided
     /// offset and limit.
+    ///
+    /// This is the slow path for large stores: the entries before the offset are read to skip
+    /// them, so listing all content page by page takes quadratic time. It's kept for compatibility,
+    /// [Self::paginate_after] should be preferred.
+    ///
+    /// If `provenance` is set, only the content that got into the store that way is listed, and
+    /// if `source` is set, only the content labeled with that source is listed. Only the listed
+    /// content is counted in the entry count.
     pub fn paginate(
         &self,
         offset: u64,
         limit: u64,
+        provenance: Option<ContentProvenance>,
+        source: Option<&str>,
     ) -> Result<PaginateResult<TContentKey>, ContentStoreError> {
         let timer = self.metrics.start_process_timer("paginate");
 
+        let conn = self.config.sql_connection_pool.get()?;
+        let to_content_key = |row: &rusqlite::Row| {
+            let bytes = row.get::<&str, Vec<u8>>("content_key")?;
+            TContentKey::try_from_bytes(bytes)
+                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, Type::Blob, e.into()))
+        };
+        let (content_keys, entry_count) = match (provenance, source) {
+            (None, None) => {
+                let content_keys = conn
+                    .prepare(&sql::paginate(&self.config.content_type))?
+                    .query_map(
+                        named_params! {
+                            ":limit": limit,
+                            ":offset": offset,
+                        },
+                        to_content_key,
+                    )?
+                    .collect::<Result<Vec<TContentKey>, rusqlite::Error>>()?;
+                (content_keys, self.usage_stats.entry_count)
+            }
+            (provenance, source) => {
+                let provenance = provenance.map(|provenance| provenance.as_str());
+                let content_keys = conn
+                    .prepare(&sql::paginate_filtered(&self.config.content_type))?
+                    .query_map(
+                        named_params! {
+                            ":provenance": provenance,
+                            ":source": source,
+                            ":limit": limit,
+                            ":offset": offset,
+                        },
+                        to_content_key,
+                    )?
+                    .collect::<Result<Vec<TContentKey>, rusqlite::Error>>()?;
+                let entry_count = conn.query_row(
+                    &sql::entry_count_filtered(&self.config.content_type),
+                    named_params! {
+                        ":provenance": provenance,
+                        ":source": source,
+                    },
+                    |row| row.get::<_, u64>(0),
+                )?;
+                (content_keys, entry_count)
+            }
+        };
+
+        self.metrics.stop_process_timer(timer);
+        Ok(PaginateResult {
+            content_keys,
+            entry_count: Some(entry_count),
+        })
+    }
+
+    /// Returns up to `limit` locally available content keys whose content id is strictly greater
+    /// than `after` (or from the start, if `None`), ordered by content id.
+    ///
+    /// This is meant to be used as a cursor: passing the content id of the last returned key
+    /// fetches the next page. As the content id is looked up in the primary key index, every page
+    /// is as fast to fetch as the first one. The content is filtered like in [Self::paginate].
+    ///
+    /// Counting the filtered content takes a scan of the table, so it's only counted for the first
+    /// page (without `after`), rather than for every page of the enumeration.
+    pub fn paginate_after(
+        &self,
+        after: Option<&ContentId>,
+        limit: u64,
+        provenance: Option<ContentProvenance>,
+        source: Option<&str>,
+    ) -> Result<PaginateResult<TContentKey>, ContentStoreError> {
+        let timer = self.metrics.start_process_timer("paginate_after");
+
+        let is_first_page = after.is_none();
+        // Every content id is greater than the empty blob.
+        let after = after
+            .map(|content_id| content_id.to_vec())
+            .unwrap_or_default();
+        let provenance = provenance.map(|provenance| provenance.as_str());
         let conn = self.config.sql_connection_pool.get()?;
         let content_keys = conn
-            .prepare(&sql::paginate(&self.config.content_type))?
+            .prepare(&sql::paginate_after(&self.config.content_type))?
             .query_map(
                 named_params! {
+                    ":after": after,
+                    ":provenance": provenance,
+                    ":source": source,
                     ":limit": limit,
-                    ":offset": offset,
                 },
                 |row| {
                     let bytes = row.get::<&str, Vec<u8>>("content_key")?;
@@ -343,18 +1157,168 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
                 },
             )?
             .collect::<Result<Vec<TContentKey>, rusqlite::Error>>()?;
+        let entry_count = match (provenance, source) {
+            (None, None) => Some(self.usage_stats.entry_count),
+            _ if !is_first_page => None,
+            (provenance, source) => Some(conn.query_row(
+                &sql::entry_count_filtered(&self.config.content_type),
+                named_params! {
+                    ":provenance": provenance,
+                    ":source": source,
+                },
+                |row| row.get::<_, u64>(0),
+            )?),
+        };
 
         self.metrics.stop_process_timer(timer);
         Ok(PaginateResult {
             content_keys,
-            entry_count: self.usage_stats.entry_count,
+            entry_count,
         })
     }
 
+    /// Returns the number of entries per first byte of the content key, i.e. the content type
+    /// selector, ordered by selector.
+    pub fn entry_count_by_key_selector(&self) -> Result<Vec<(u8, u64)>, ContentStoreError> {
+        let timer = self
+            .metrics
+            .start_process_timer("entry_count_by_key_selector");
+
+        let counts = self
+            .config
+            .sql_connection_pool
+            .get()?
+            .prepare(&sql::entry_count_by_key_selector(&self.config.content_type))?
+            .query_map([], |row| {
+                let selector = row.get::<&str, Vec<u8>>("selector")?;
+                let count = row.get::<&str, u64>("count")?;
+                Ok((selector.first().copied().unwrap_or_default(), count))
+            })?
+            .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+        self.metrics.stop_process_timer(timer);
+        Ok(counts)
+    }
+
+    /// Returns up to `limit` entries whose content id is strictly greater than `after` (or from
+    /// the start, if `None`), ordered by content id.
+    ///
+    /// This is meant to be used as a cursor: passing the content id of the last returned entry
+    /// fetches the next batch. Each batch is a separate query, so the caller doesn't have to hold
+    /// on to the store between batches. Content written between batches is handled on a best-effort
+    /// basis: entries inserted after the cursor are included in the later batches, while entries
+    /// inserted before the cursor (or deleted after the cursor) are silently skipped.
+    pub fn export_batch(
+        &self,
+        after: Option<&ContentId>,
+        limit: u64,
+    ) -> Result<Vec<(TContentKey, RawContentValue)>, ContentStoreError> {
+        let timer = self.metrics.start_process_timer("export_batch");
+
+        // Every content id is greater than the empty blob.
+        let after = after
+            .map(|content_id| content_id.to_vec())
+            .unwrap_or_default();
+        let conn = self.config.sql_connection_pool.get()?;
+        let entries = conn
+            .prepare(&sql::export_batch(&self.config.content_type))?
+            .query_map(
+                named_params! {
+                    ":after": after,
+                    ":limit": limit,
+                },
+                |row| {
+                    let bytes = row.get::<&str, Vec<u8>>("content_key")?;
+                    let content_key = TContentKey::try_from_bytes(bytes).map_err(|e| {
+                        rusqlite::Error::FromSqlConversionFailure(0, Type::Blob, e.into())
+                    })?;
+                    let content_value = row.get::<&str, Vec<u8>>("content_value")?;
+                    Ok((content_key, RawContentValue::from(content_value)))
+                },
+            )?
+            .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+        self.metrics.stop_process_timer(timer);
+        Ok(entries)
+    }
+
+    /// Returns up to `limit` entries whose content id is within `radius` of `node_id`, ordered by
+    /// content id.
+    pub fn entries_within_radius(
+        &self,
+        node_id: &NodeId,
+        radius: Distance,
+        limit: u64,
+    ) -> Result<Vec<(TContentKey, RawContentValue)>, ContentStoreError> {
+        let timer = self.metrics.start_process_timer("entries_within_radius");
+
+        // The content ids within the radius share the bits of the node id that are above the
+        // highest bit of the radius, so they all fall into this range. Not all the ids in the
+        // range are within the radius, so the entries are filtered by their actual distance.
+        let mask = radius
+            .log2()
+            .map_or(U256::ZERO, |bits| U256::MAX >> (256 - bits));
+        let node_id_value = U256::from_be_bytes(node_id.raw());
+        let mut first = node_id_value & !mask;
+        let last = node_id_value | mask;
+
+        let conn = self.config.sql_connection_pool.get()?;
+        let mut statement = conn.prepare(&sql::lookup_id_range(&self.config.content_type))?;
+        let mut entries = vec![];
+        while (entries.len() as u64) < limit {
+            let batch = statement
+                .query_map(
+                    named_params! {
+                        ":first": first.to_be_bytes::<32>().to_vec(),
+                        ":last": last.to_be_bytes::<32>().to_vec(),
+                        ":limit": limit,
+                    },
+                    |row| {
+                        let content_id = row.get::<&str, ContentId>("content_id")?;
+                        let bytes = row.get::<&str, Vec<u8>>("content_key")?;
+                        let content_key = TContentKey::try_from_bytes(bytes).map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(0, Type::Blob, e.into())
+                        })?;
+                        let content_value = row.get::<&str, Vec<u8>>("content_value")?;
+                        Ok((
+                            content_id,
+                            content_key,
+                            RawContentValue::from(content_value),
+                        ))
+                    },
+                )?
+                .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+            let Some((last_content_id, _, _)) = batch.last() else {
+                break;
+            };
+            let next = U256::from_be_bytes(last_content_id.0);
+            for (content_id, content_key, content_value) in batch {
+                if self.config.distance_fn.distance(node_id, &content_id.0) <= radius {
+                    entries.push((content_key, content_value));
+                }
+            }
+            if next >= last {
+                break;
+            }
+            first = next + U256::from(1);
+        }
+        entries.truncate(limit as usize);
+
+        self.metrics.stop_process_timer(timer);
+        Ok(entries)
+    }
+
     pub fn usage_stats(&self) -> UsageStats {
         self.usage_stats.clone()
     }
 
+    /// Returns the usage stats of the archive tier, if the store has one.
+    pub fn archive_usage_stats(&self) -> Option<UsageStats> {
+        self.archive
+            .as_ref()
+            .map(|archive| archive.usage_stats.clone())
+    }
+
     /// Returns metrics summary.
     pub fn get_summary_info(&self) -> String {
         let timer = self.metrics.start_process_timer("get_summary_info");
@@ -368,7 +1332,16 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
         }
 
         self.metrics.stop_process_timer(timer);
-        self.metrics.get_summary()
+        match &self.archive {
+            Some(archive) => format!(
+                "{} archive={:.1}/{}mb #={}",
+                self.metrics.get_summary(),
+                archive.usage_stats.total_entry_size_bytes as f64 / BYTES_IN_MB_F64,
+                archive.config.storage_capacity_bytes as f64 / BYTES_IN_MB_F64,
+                archive.usage_stats.entry_count,
+            ),
+            None => self.metrics.get_summary(),
+        }
     }
 
     // INTERNAL FUNCTIONS
@@ -395,15 +1368,119 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
         Ok(())
     }
 
+    /// Returns the configs of the tiers of the store, the primary tier first.
+    fn tier_configs(&self) -> impl Iterator<Item = &IdIndexedV1StoreConfig> {
+        std::iter::once(&self.config).chain(self.archive.iter().map(|archive| &archive.config))
+    }
+
+    /// Returns the storage capacity of all the tiers.
+    fn total_capacity_bytes(&self) -> u64 {
+        self.tier_configs()
+            .map(|config| config.storage_capacity_bytes)
+            .sum()
+    }
+
+    /// Returns whether any of the tiers is above its storage capacity.
+    fn should_prune(&self) -> bool {
+        self.pruning_strategy.should_prune(&self.usage_stats)
+            || self
+                .archive
+                .as_ref()
+                .is_some_and(|archive| archive.pruning_strategy.should_prune(&archive.usage_stats))
+    }
+
+    /// Returns whether the storage is used above its target capacity. The content that is evicted
+    /// from the primary tier is kept in the archive tier, so only the usage of the archive counts,
+    /// unless it has no capacity.
+    fn is_usage_above_target_capacity(&self) -> bool {
+        match &self.archive {
+            Some(archive) if archive.config.storage_capacity_bytes > 0 => archive
+                .pruning_strategy
+                .is_usage_above_target_capacity(&archive.usage_stats),
+            _ => self
+                .pruning_strategy
+                .is_usage_above_target_capacity(&self.usage_stats),
+        }
+    }
+
+    /// Moves the content that was read from the archive tier back to the primary tier.
+    fn promote_archived_content(&mut self) -> Result<(), ContentStoreError> {
+        let Some(archive) = &mut self.archive else {
+            return Ok(());
+        };
+        let content_ids = archive.take_promotions();
+        if content_ids.is_empty() {
+            return Ok(());
+        }
+
+        let timer = self.metrics.start_process_timer("promote_archived_content");
+        let conn = self.config.sql_connection_pool.get()?;
+        for content_id in content_ids {
+            let Some(mut row) = archive.take_row(&content_id.into())? else {
+                continue;
+            };
+            // The content was read, which is why it's promoted.
+            row.last_accessed = Some(unix_timestamp());
+            if row.insert(&conn, &self.config.content_type)? {
+                self.usage_stats.entry_count += 1;
+                self.usage_stats.total_entry_size_bytes += row.content_size;
+            }
+        }
+        self.usage_stats.report_metrics(&self.metrics);
+        self.metrics.stop_process_timer(timer);
+        Ok(())
+    }
+
+    /// Deletes the farthest content from the archive tier while it's above its capacity. Returns
+    /// the deleted content, which is dropped from the store.
+    fn prune_archive(&mut self) -> Result<Vec<(TContentKey, RawContentValue)>, ContentStoreError> {
+        let mut deleted_content = Vec::new();
+        let Some(archive) = &mut self.archive else {
+            return Ok(deleted_content);
+        };
+
+        let conn = archive.config.sql_connection_pool.get()?;
+        let mut delete_query = conn.prepare(&sql::delete_farthest(&archive.config.content_type))?;
+        while archive.pruning_strategy.should_prune(&archive.usage_stats) {
+            let to_delete = archive
+                .pruning_strategy
+                .get_pruning_count(&archive.usage_stats);
+            let deleted_rows = delete_query
+                .query_map(
+                    named_params! { ":limit": to_delete },
+                    read_deleted_row::<TContentKey>,
+                )?
+                .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+            if deleted_rows.is_empty() {
+                error!(Db = %archive.config.content_type,
+                    "Attempted to delete {to_delete} from the archive but deleted none");
+                break;
+            }
+            for (content_key, row) in deleted_rows {
+                archive.usage_stats.entry_count -= 1;
+                archive.usage_stats.total_entry_size_bytes -= row.content_size;
+                deleted_content.push((content_key, RawContentValue::from(row.content_value)));
+            }
+        }
+        Ok(deleted_content)
+    }
+
     /// Returns the farthest content in the table.
     fn lookup_farthest(&self) -> Result<Option<FarthestQueryResult>, ContentStoreError> {
+        self.lookup_farthest_in(&self.config)
+    }
+
+    /// Returns the farthest content in the table of the tier with the config.
+    fn lookup_farthest_in(
+        &self,
+        config: &IdIndexedV1StoreConfig,
+    ) -> Result<Option<FarthestQueryResult>, ContentStoreError> {
         let timer = self.metrics.start_process_timer("lookup_farthest");
-        let farthest = self
-            .config
+        let farthest = config
             .sql_connection_pool
             .get()?
             .query_row(
-                &sql::lookup_farthest(&self.config.content_type),
+                &sql::lookup_farthest(&config.content_type),
                 named_params! { ":limit": 1 },
                 |row| {
                     Ok(FarthestQueryResult {
@@ -418,13 +1495,50 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
         Ok(farthest)
     }
 
-    /// Sets `self.radius` to the distance to the farthest stored content.
+    /// Recomputes the radius if storage usage changed significantly since it was last computed.
+    ///
+    /// Inserts don't need this, as pruning already updates the radius when usage grows.
+    fn maybe_recompute_radius(&mut self) -> Result<(), ContentStoreError> {
+        let usage_change = self
+            .radius_usage_bytes
+            .abs_diff(self.usage_stats.total_entry_size_bytes);
+        let threshold =
+            self.config.storage_capacity_bytes / 100 * RADIUS_RECOMPUTE_USAGE_CHANGE_PERCENT;
+        if usage_change >= threshold {
+            self.recompute_radius()?;
+        }
+        Ok(())
+    }
+
+    /// Records the change of the radius from `old_radius`, if it changed. Returns whether it
+    /// changed.
+    fn record_radius_change(&mut self, old_radius: Distance) -> bool {
+        if self.radius == old_radius {
+            return false;
+        }
+        info!(
+            Db = %self.config.content_type,
+            "Radius changed from {old_radius} to {}",
+            self.radius,
+        );
+        self.radius_history.record(old_radius, self.radius);
+        true
+    }
+
+    /// Sets `self.radius` to the distance to the farthest content stored in any of the tiers.
     ///
     /// If no content is found, it sets radius to `Distance::MAX`.
     fn set_radius_to_farthest(&mut self) -> Result<(), ContentStoreError> {
-        match self.lookup_farthest()? {
+        let mut farthest_distance = None;
+        for config in self.tier_configs() {
+            if let Some(farthest) = self.lookup_farthest_in(config)? {
+                let distance = self.distance_to_content_id(&farthest.content_id);
+                farthest_distance = farthest_distance.max(Some(distance));
+            }
+        }
+        match farthest_distance {
             None => {
-                if self.config.storage_capacity_bytes == 0 {
+                if self.total_capacity_bytes() == 0 {
                     debug!(
                         Db = %self.config.content_type,
                         "Farthest not found and storage capacity is 0",
@@ -435,10 +1549,11 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
                     self.radius = Distance::MAX;
                 }
             }
-            Some(farthest) => {
-                self.radius = self.distance_to_content_id(&farthest.content_id);
+            Some(distance) => {
+                self.radius = distance;
             }
         }
+        self.radius_usage_bytes = self.usage_stats.total_entry_size_bytes;
         self.metrics.report_radius(self.radius);
         Ok(())
     }
@@ -447,7 +1562,7 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
     /// Returns any content items that were pruned.
     fn prune(&mut self) -> Result<Vec<(TContentKey, RawContentValue)>, ContentStoreError> {
         let mut deleted_content: Vec<(TContentKey, RawContentValue)> = Vec::new();
-        if !self.pruning_strategy.should_prune(&self.usage_stats) {
+        if !self.should_prune() {
             warn!(Db = %self.config.content_type,
                 "Pruning requested but not needed. Skipping");
             return Ok(deleted_content);
@@ -460,8 +1575,21 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
             self.usage_stats.total_entry_size_bytes,
         );
 
+        // The content of the farthest band is evicted by access time, so the access times have to
+        // be up to date. The bands split the radius before pruning, which covers all content.
+        let band_width = match self.config.eviction_policy {
+            StorageEvictionPolicy::Distance => None,
+            StorageEvictionPolicy::DistanceLru => {
+                self.flush_access_times()?;
+                Some(self.radius.big_endian_u32() / DISTANCE_LRU_BAND_COUNT + 1)
+            }
+        };
+
         let conn = self.config.sql_connection_pool.get()?;
-        let mut delete_query = conn.prepare(&sql::delete_farthest(&self.config.content_type))?;
+        let mut delete_query = conn.prepare(&match band_width {
+            None => sql::delete_farthest(&self.config.content_type),
+            Some(_) => sql::delete_least_recently_accessed_in_band(&self.config.content_type),
+        })?;
 
         while self.pruning_strategy.should_prune(&self.usage_stats) {
             let to_delete = self.pruning_strategy.get_pruning_count(&self.usage_stats);
@@ -474,51 +1602,76 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
                 return Ok(deleted_content);
             }
 
+            // The farthest band can have fewer entries than we want to delete, in which case the
+            // rest is deleted from the next band in the following iterations.
+            let band_start = match band_width {
+                None => None,
+                Some(band_width) => self
+                    .lookup_farthest()?
+                    .map(|farthest| farthest.distance_u32 / band_width * band_width),
+            };
+
             let delete_timer = self.metrics.start_process_timer("prune_delete");
-            let deleted_content_result = delete_query
-                .query_map(named_params! { ":limit": to_delete }, |row| {
-                    let key_bytes: Vec<u8> = row.get("content_key")?;
-                    let value_bytes: Vec<u8> = row.get("content_value")?;
-                    let value = RawContentValue::from(value_bytes);
-                    let size: u64 = row.get("content_size")?;
-                    TContentKey::try_from_bytes(key_bytes)
-                        .map(|key| (key, value, size))
-                        .map_err(|e| {
-                            rusqlite::Error::FromSqlConversionFailure(0, Type::Blob, e.into())
-                        })
-                })?
-                .collect::<Result<Vec<(TContentKey, RawContentValue, u64)>, rusqlite::Error>>()?;
+            let deleted_content_result: Vec<(TContentKey, TableRow)> = match band_start {
+                None => delete_query
+                    .query_map(named_params! { ":limit": to_delete }, read_deleted_row)?
+                    .collect::<Result<Vec<_>, rusqlite::Error>>()?,
+                Some(band_start) => delete_query
+                    .query_map(
+                        named_params! { ":limit": to_delete, ":band_start": band_start },
+                        read_deleted_row,
+                    )?
+                    .collect::<Result<Vec<_>, rusqlite::Error>>()?,
+            };
             let pruning_duration = self.metrics.stop_process_timer(delete_timer);
             self.pruning_strategy
                 .observe_pruning_duration(pruning_duration);
 
             let deleted_content_count = deleted_content_result.len() as u64;
-            if to_delete != deleted_content_count {
+            let is_partial_delete_expected = band_start.is_some() && deleted_content_count > 0;
+            if to_delete != deleted_content_count && !is_partial_delete_expected {
                 error!(Db = %self.config.content_type,
                     "Attempted to delete {to_delete} but deleted {deleted_content_count}");
                 self.init_usage_stats()?;
                 break;
             }
 
-            let deleted_content_values = deleted_content_result
-                .iter()
-                .map(|(key, value, _)| (key.clone(), value.clone()))
-                .collect::<Vec<(TContentKey, RawContentValue)>>();
             let deleted_content_size = deleted_content_result
                 .iter()
-                .map(|(_, _, size)| size)
+                .map(|(_, row)| row.content_size)
                 .sum::<u64>();
-            self.usage_stats.entry_count -= to_delete;
+            self.usage_stats.entry_count -= deleted_content_count;
             self.usage_stats.total_entry_size_bytes -= deleted_content_size;
             self.usage_stats.report_metrics(&self.metrics);
-            deleted_content.extend(deleted_content_values);
+            for (_, row) in &deleted_content_result {
+                self.recent_writes
+                    .remove(&B256::from_slice(&row.content_id));
+            }
+            match &mut self.archive {
+                // The evicted content is moved to the archive tier instead of being dropped.
+                Some(archive) => {
+                    archive.insert_rows(deleted_content_result.into_iter().map(|(_, row)| row))?
+                }
+                None => deleted_content.extend(
+                    deleted_content_result
+                        .into_iter()
+                        .map(|(key, row)| (key, RawContentValue::from(row.content_value))),
+                ),
+            }
         }
         // Free connection.
         drop(delete_query);
         drop(conn);
 
-        // Update radius to the current farthest content
-        self.set_radius_to_farthest()?;
+        // Update radius to the current farthest content. With the archive tier, the radius only
+        // shrinks once content is dropped from the archive.
+        let dropped_archived_content = self.prune_archive()?;
+        if self.archive.is_none() || !dropped_archived_content.is_empty() {
+            let old_radius = self.radius;
+            self.set_radius_to_farthest()?;
+            self.record_radius_change(old_radius);
+        }
+        deleted_content.extend(dropped_archived_content);
 
         debug!(Db = %self.config.content_type,
             "Pruning end: count={} capacity={}",
@@ -530,13 +1683,22 @@ impl<TContentKey: OverlayContentKey> IdIndexedV1Store<TContentKey> {
     }
 }
 
-/// Creates table and indexes if they don't already exist.
+/// Returns the current time, in seconds since the Unix epoch.
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Creates table and indexes if they don't already exist. The columns that tables created before
+/// them are missing are added by the schema migrations, see [crate::schema].
 fn maybe_create_table_and_indexes(
     content_type: &ContentType,
     pool: &Pool<SqliteConnectionManager>,
 ) -> Result<(), ContentStoreError> {
-    let conn = pool.get()?;
-    conn.execute_batch(&sql::create_table(content_type))?;
+    pool.get()?
+        .execute_batch(&sql::create_table(content_type))?;
     Ok(())
 }
 
@@ -544,7 +1706,10 @@ fn maybe_create_table_and_indexes(
 mod tests {
     use anyhow::Result;
     use discv5::enr::NodeId;
-    use ethportal_api::{types::network::Subnetwork, IdentityContentKey};
+    use ethportal_api::{
+        types::{cli::DEFAULT_STORAGE_RECENT_WRITES, network::Subnetwork},
+        IdentityContentKey,
+    };
     use rand::Rng;
     use tempfile::TempDir;
 
@@ -572,9 +1737,67 @@ mod tests {
             sql_connection_pool: setup_sql(temp_dir.path()).unwrap(),
             storage_capacity_bytes,
             pruning_config: PruningConfig::default(),
+            verify_on_read: false,
+            eviction_policy: StorageEvictionPolicy::Distance,
+            archive: None,
+            recent_writes_capacity: DEFAULT_STORAGE_RECENT_WRITES,
+        }
+    }
+
+    fn create_config_with_archive(
+        temp_dir: &TempDir,
+        archive_dir: &TempDir,
+        storage_capacity_bytes: u64,
+        archive_capacity_bytes: u64,
+    ) -> IdIndexedV1StoreConfig {
+        IdIndexedV1StoreConfig {
+            archive: Some(ArchiveStorageConfig {
+                storage_capacity_bytes: archive_capacity_bytes,
+                sql_connection_pool: setup_sql(archive_dir.path()).unwrap(),
+            }),
+            ..create_config(temp_dir, storage_capacity_bytes)
         }
     }
 
+    /// Returns whether the content is stored in the archive tier of the store.
+    fn is_archived(
+        store: &IdIndexedV1Store<IdentityContentKey>,
+        content_id: &ContentId,
+    ) -> Result<bool> {
+        let archive = store.archive.as_ref().unwrap();
+        Ok(archive
+            .config
+            .sql_connection_pool
+            .get()?
+            .prepare(&sql::lookup_key(&archive.config.content_type))?
+            .exists(named_params! { ":content_id": content_id.to_vec() })?)
+    }
+
+    /// Creates the store with the archive, and inserts 101 keys, so 6 are moved to the archive.
+    /// Returns the store and the ids of the inserted content.
+    fn create_store_with_archived_content(
+        temp_dir: &TempDir,
+        archive_dir: &TempDir,
+    ) -> Result<(IdIndexedV1Store<IdentityContentKey>, Vec<ContentId>)> {
+        let config = create_config_with_archive(
+            temp_dir,
+            archive_dir,
+            STORAGE_CAPACITY_100_ITEMS,
+            STORAGE_CAPACITY_100_ITEMS,
+        );
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+        let mut ids = vec![];
+        for _ in 0..101 {
+            let (key, value) = generate_key_value(&config, 0x80);
+            assert!(store.insert(&key, value)?.is_empty());
+            ids.push(ContentId::from(key.content_id()));
+        }
+        assert_eq!(store.usage_stats().entry_count, 95);
+        assert_eq!(store.archive_usage_stats().unwrap().entry_count, 6);
+        Ok((store, ids))
+    }
+
     /// Creates content key/value pair with the default size.
     fn generate_key_value(
         config: &IdIndexedV1StoreConfig,
@@ -770,91 +1993,696 @@ mod tests {
     #[test]
     fn create_zero_storage_empty() -> Result<()> {
         let temp_dir = TempDir::new()?;
-        let config = create_config(&temp_dir, /* storage_capacity_bytes= */ 0);
+        let config = create_config(&temp_dir, /* storage_capacity_bytes= */ 0);
+
+        let store = IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config)?;
+
+        assert_eq!(store.usage_stats, UsageStats::default());
+        assert_eq!(store.radius(), Distance::ZERO);
+        Ok(())
+    }
+
+    #[test]
+    fn create_zero_storage_non_empty() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = create_config(&temp_dir, /* storage_capacity_bytes= */ 0);
+
+        // Add 1K entries, more than we would normally prune.
+        create_and_populate_table(&config, 1_000)?;
+        let store = IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config)?;
+
+        // Check that db is empty and distance is ZERO.
+        assert_eq!(store.usage_stats, UsageStats::default());
+        assert_eq!(store.radius(), Distance::ZERO);
+        Ok(())
+    }
+
+    #[test]
+    fn simple_insert_and_lookup() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS);
+
+        // fill 50% of storage with 50 items, 1% each
+        create_and_populate_table(&config, 50)?;
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+
+        let (key, value) = generate_key_value(&config, 0);
+        let id = ContentId::from(key.content_id());
+
+        // Check that content is not stored and save usage stats.
+        assert!(!store.has_content(&id)?);
+        let usage_stats = store.usage_stats();
+
+        store.insert(&key, value.clone())?;
+
+        // Check that lookup works
+        assert!(store.has_content(&id)?);
+        assert_eq!(store.lookup_content_key(&id)?, Some(key));
+        assert_eq!(
+            store.lookup_content_value(&id)?,
+            Some(RawContentValue::from(value))
+        );
+
+        // Check that usage stats are updated
+        assert_eq!(store.usage_stats.entry_count, usage_stats.entry_count + 1);
+        assert!(store.usage_stats.total_entry_size_bytes > usage_stats.total_entry_size_bytes);
+
+        Ok(())
+    }
+
+    #[test]
+    fn lookup_verifies_content_on_read() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = IdIndexedV1StoreConfig {
+            verify_on_read: true,
+            // The content is corrupted in the database, so it has to be read from there.
+            recent_writes_capacity: 0,
+            ..create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS)
+        };
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+
+        let (key, value) = generate_key_value(&config, 0);
+        let id = ContentId::from(key.content_id());
+        store.insert(&key, value.clone())?;
+        assert_eq!(
+            store.lookup_content_value(&id)?,
+            Some(RawContentValue::from(value.clone()))
+        );
+
+        // Corrupt the stored content key, so that it doesn't produce the content id anymore.
+        let (other_key, _) = generate_key_value(&config, 0);
+        config.sql_connection_pool.get()?.execute(
+            &format!(
+                "UPDATE {} SET content_key = :content_key WHERE content_id = :content_id",
+                sql::table_name(&config.content_type)
+            ),
+            named_params! {
+                ":content_key": other_key.to_bytes().to_vec(),
+                ":content_id": id.to_vec(),
+            },
+        )?;
+        assert!(matches!(
+            store.lookup_content_value(&id),
+            Err(ContentStoreError::Corrupted { .. })
+        ));
+
+        // Corrupt the value of other content, whose key still produces its content id.
+        let (value_key, stored_value) = generate_key_value(&config, 0);
+        let value_id = ContentId::from(value_key.content_id());
+        store.insert(&value_key, stored_value.clone())?;
+        let mut corrupted_value = stored_value.to_vec();
+        corrupted_value[0] ^= 1;
+        overwrite_content_value(&config, &value_id, &corrupted_value)?;
+        assert!(matches!(
+            store.lookup_content_value(&value_id),
+            Err(ContentStoreError::Corrupted { .. })
+        ));
+
+        // The value of content stored before the hash was recorded can't be checked.
+        config.sql_connection_pool.get()?.execute(
+            &format!(
+                "UPDATE {} SET value_hash = NULL WHERE content_id = :content_id",
+                sql::table_name(&config.content_type)
+            ),
+            named_params! { ":content_id": value_id.to_vec() },
+        )?;
+        assert_eq!(
+            store.lookup_content_value(&value_id)?,
+            Some(RawContentValue::from(corrupted_value))
+        );
+
+        // Without the check, the corrupted content is returned as is.
+        let store = IdIndexedV1Store::<IdentityContentKey>::create(
+            ContentType::State,
+            IdIndexedV1StoreConfig {
+                verify_on_read: false,
+                ..config
+            },
+        )?;
+        assert_eq!(
+            store.lookup_content_value(&id)?,
+            Some(RawContentValue::from(value))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn simulate_offers() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS);
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+
+        // The first sampled content id is the lowest one.
+        let (_, value) = generate_key_value(&config, 0);
+        store.insert(&IdentityContentKey::new([0; 32]), value)?;
+
+        let info = store.simulate_offers(100)?;
+        assert_eq!(info.sampled, 100);
+        assert_eq!(info.accepted, 99);
+        assert_eq!(info.already_stored, 1);
+        assert_eq!(info.acceptance_rate, 0.99);
+        assert_eq!(info.radius, U256::MAX);
+
+        // About half of the keyspace is within half of the max radius.
+        store.radius = Distance::from(U256::MAX >> 1);
+        let info = store.simulate_offers(1000)?;
+        assert!(
+            (0.45..=0.55).contains(&info.acceptance_rate),
+            "Unexpected acceptance rate: {}",
+            info.acceptance_rate
+        );
+        assert_eq!(info.radius, U256::MAX >> 1);
+
+        store.radius = Distance::ZERO;
+        assert_eq!(store.simulate_offers(100)?.accepted, 0);
+
+        // The simulation doesn't need the store.
+        store.radius = Distance::MAX;
+        let offer_simulation = store.offer_simulation();
+        drop(store);
+        let info = offer_simulation.run(100)?;
+        assert_eq!(info.accepted, 99);
+        assert_eq!(info.already_stored, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn lookup_distance_rank() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS);
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+
+        let mut keys = vec![];
+        for distance in [10, 0, 5] {
+            let (key, value) = generate_key_value(&config, distance);
+            store.insert(&key, value)?;
+            keys.push(key);
+        }
+        let rank_of = |key: &IdentityContentKey| {
+            store.lookup_distance_rank(&ContentId::from(key.content_id()))
+        };
+        assert_eq!(
+            rank_of(&keys[1])?,
+            Some(ContentDistanceRank { rank: 0, total: 3 })
+        );
+        assert_eq!(
+            rank_of(&keys[2])?,
+            Some(ContentDistanceRank { rank: 1, total: 3 })
+        );
+        assert_eq!(
+            rank_of(&keys[0])?,
+            Some(ContentDistanceRank { rank: 2, total: 3 })
+        );
+        let (absent_key, _) = generate_key_value(&config, 0);
+        assert_eq!(rank_of(&absent_key)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn insert_records_meta() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS);
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+
+        let peer = NodeId::random();
+        let (offered_key, value) = generate_key_value(&config, 0);
+        store.insert_with_meta(&offered_key, value, ContentProvenance::Offer, Some(peer))?;
+        let (imported_key, value) = generate_key_value(&config, 0);
+        store.insert_with_meta(&imported_key, value, ContentProvenance::Import, None)?;
+        let (key, value) = generate_key_value(&config, 0);
+        store.insert(&key, value)?;
+
+        let meta_of =
+            |key: &IdentityContentKey| store.lookup_meta(&ContentId::from(key.content_id()));
+        let offered_meta = meta_of(&offered_key)?.unwrap();
+        assert_eq!(offered_meta.provenance, ContentProvenance::Offer);
+        assert_eq!(offered_meta.offered_by, Some(peer));
+        assert!(offered_meta.stored_at.is_some());
+        let imported_meta = meta_of(&imported_key)?.unwrap();
+        assert_eq!(imported_meta.provenance, ContentProvenance::Import);
+        assert_eq!(imported_meta.offered_by, None);
+        assert_eq!(
+            meta_of(&key)?.map(|meta| meta.provenance),
+            Some(ContentProvenance::Unknown)
+        );
+        let (absent_key, _) = generate_key_value(&config, 0);
+        assert_eq!(meta_of(&absent_key)?, None);
+
+        // Only the content with the provenance is listed and counted.
+        assert_eq!(
+            store.paginate(0, 10, Some(ContentProvenance::Offer), None)?,
+            PaginateResult {
+                content_keys: vec![offered_key],
+                entry_count: Some(1),
+            }
+        );
+        assert_eq!(
+            store.paginate(0, 10, Some(ContentProvenance::RpcStore), None)?,
+            PaginateResult {
+                content_keys: vec![],
+                entry_count: Some(0),
+            }
+        );
+        assert_eq!(store.paginate(0, 10, None, None)?.entry_count, Some(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn source_filters_and_deletes_content() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS);
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+
+        let mut bad_import = vec![];
+        for _ in 0..3 {
+            let (key, value) = generate_key_value(&config, 0);
+            store.insert_with_meta(&key, value, ContentProvenance::RpcStore, None)?;
+            assert!(store.set_source(&ContentId::from(key.content_id()), "bad-import")?);
+            bad_import.push(key);
+        }
+        bad_import.sort_by_key(|key| key.to_bytes());
+        let (good_key, value) = generate_key_value(&config, 0);
+        store.insert_with_meta(&good_key, value, ContentProvenance::RpcStore, None)?;
+        assert!(store.set_source(&ContentId::from(good_key.content_id()), "good-import")?);
+        let (unlabeled_key, value) = generate_key_value(&config, 0);
+        store.insert(&unlabeled_key, value)?;
+        let (absent_key, _) = generate_key_value(&config, 0);
+        assert!(!store.set_source(&ContentId::from(absent_key.content_id()), "bad-import")?);
+
+        assert_eq!(
+            store
+                .lookup_meta(&ContentId::from(good_key.content_id()))?
+                .and_then(|meta| meta.source),
+            Some("good-import".to_string())
+        );
+        assert_eq!(
+            store
+                .lookup_meta(&ContentId::from(unlabeled_key.content_id()))?
+                .and_then(|meta| meta.source),
+            None
+        );
+
+        // Only the content with the source is listed and counted, also combined with the
+        // provenance.
+        assert_eq!(
+            store.paginate(0, 10, None, Some("bad-import"))?,
+            PaginateResult {
+                content_keys: bad_import.clone(),
+                entry_count: Some(3),
+            }
+        );
+        assert_eq!(
+            store.paginate(
+                0,
+                10,
+                Some(ContentProvenance::RpcStore),
+                Some("good-import")
+            )?,
+            PaginateResult {
+                content_keys: vec![good_key.clone()],
+                entry_count: Some(1),
+            }
+        );
+        assert_eq!(
+            store
+                .paginate(0, 10, Some(ContentProvenance::Offer), Some("good-import"))?
+                .entry_count,
+            Some(0)
+        );
+
+        // Deleting the content of the source keeps the other content.
+        let mut deleted = store.delete_by_source("bad-import")?;
+        deleted.sort_by_key(|key| key.to_bytes());
+        assert_eq!(deleted, bad_import);
+        assert_eq!(store.usage_stats.entry_count, 2);
+        for key in &bad_import {
+            assert!(store
+                .lookup_content_value(&key.content_id().into())?
+                .is_none());
+        }
+        assert!(store
+            .lookup_content_value(&good_key.content_id().into())?
+            .is_some());
+        assert!(store
+            .lookup_content_value(&unlabeled_key.content_id().into())?
+            .is_some());
+        assert!(store.delete_by_source("bad-import")?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn insert_with_labels() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS);
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+
+        let (key, value) = generate_key_value(&config, 0);
+        let id = ContentId::from(key.content_id());
+        store.insert_with_meta(&key, value.clone(), ContentProvenance::RpcStore, None)?;
+        let meta = store.lookup_meta(&id)?.unwrap();
+        assert_eq!(meta.source, None);
+        assert!(!meta.trusted);
+
+        // Content that is already stored is labeled too.
+        let labels = ContentLabels {
+            source: Some("signed-import".to_string()),
+            trusted: true,
+        };
+        store.insert_with_labels(&key, value, ContentProvenance::RpcStore, None, &labels)?;
+        let meta = store.lookup_meta(&id)?.unwrap();
+        assert_eq!(meta.source, Some("signed-import".to_string()));
+        assert!(meta.trusted);
+
+        // A signed value doesn't make another value, that is already stored under its id, trusted.
+        let (other_key, value) = generate_key_value(&config, 0);
+        let other_id = ContentId::from(other_key.content_id());
+        store.insert_with_meta(&other_key, value.clone(), ContentProvenance::RpcStore, None)?;
+        let mut signed_value = value.to_vec();
+        signed_value.push(0);
+        store.insert_with_labels(
+            &other_key,
+            signed_value.into(),
+            ContentProvenance::RpcStore,
+            None,
+            &labels,
+        )?;
+        let meta = store.lookup_meta(&other_id)?.unwrap();
+        assert!(!meta.trusted);
+        assert_eq!(store.lookup_content_value(&other_id)?, Some(value));
+
+        let (new_key, value) = generate_key_value(&config, 0);
+        store.insert_with_labels(&new_key, value, ContentProvenance::RpcStore, None, &labels)?;
+        let meta = store
+            .lookup_meta(&ContentId::from(new_key.content_id()))?
+            .unwrap();
+        assert_eq!(meta.source, Some("signed-import".to_string()));
+        assert!(meta.trusted);
+
+        Ok(())
+    }
+
+    #[test]
+    fn schema_migration_adds_meta_columns() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS);
+
+        // Create the table as it was before the provenance was recorded.
+        let (key, value) = generate_key_value(&config, 0);
+        let id = key.content_id();
+        let distance_short = config
+            .distance_fn
+            .distance(&config.node_id, &id)
+            .big_endian_u32();
+        let mut conn = config.sql_connection_pool.get()?;
+        conn.execute_batch(&format!(
+            "CREATE TABLE {} (
+                content_id BLOB PRIMARY KEY,
+                content_key BLOB NOT NULL,
+                content_value BLOB NOT NULL,
+                distance_short INTEGER NOT NULL,
+                content_size INTEGER NOT NULL
+            );",
+            sql::table_name(&config.content_type)
+        ))?;
+        conn.execute(
+            &format!(
+                "INSERT INTO {} VALUES (
+                    :content_id, :content_key, :content_value, :distance_short, :content_size
+                )",
+                sql::table_name(&config.content_type)
+            ),
+            named_params! {
+                ":content_id": id.to_vec(),
+                ":content_key": key.to_bytes().to_vec(),
+                ":content_value": value.to_vec(),
+                ":distance_short": distance_short,
+                ":content_size": id.len() + key.to_bytes().len() + value.len(),
+            },
+        )?;
+        // Reopen the database as if the migrations that add the columns weren't applied yet.
+        conn.execute("DELETE FROM schema_version WHERE version >= 2", [])?;
+        crate::schema::migrate(&mut conn)?;
+        drop(conn);
+
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+        assert_eq!(
+            store.lookup_meta(&ContentId::from(id))?,
+            Some(LocalContentMeta {
+                provenance: ContentProvenance::Unknown,
+                offered_by: None,
+                stored_at: None,
+                source: None,
+                trusted: false,
+            })
+        );
+
+        // New content is stored with its meta.
+        let peer = NodeId::random();
+        let (new_key, value) = generate_key_value(&config, 0);
+        store.insert_with_meta(
+            &new_key,
+            value,
+            ContentProvenance::OfferFallback,
+            Some(peer),
+        )?;
+        let new_meta = store
+            .lookup_meta(&ContentId::from(new_key.content_id()))?
+            .unwrap();
+        assert_eq!(new_meta.provenance, ContentProvenance::OfferFallback);
+        assert_eq!(new_meta.offered_by, Some(peer));
+        assert!(new_meta.stored_at.is_some());
+
+        // Creating the store again doesn't fail on the existing columns.
+        drop(store);
+        IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn simple_insert_and_delete() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS);
+
+        // fill 50% of storage with 50 items, 1% each
+        create_and_populate_table(&config, 50)?;
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+
+        let (key, value) = generate_key_value(&config, 0);
+        let id = ContentId::from(key.content_id());
+
+        // Check that content is not stored and save usage stats.
+        assert!(!store.has_content(&id)?);
+        let usage_stats = store.usage_stats();
+
+        store.insert(&key, value)?;
+        // Check that content is stored and usage stats are updated.
+        assert!(store.has_content(&id)?);
+        assert_eq!(store.usage_stats.entry_count, usage_stats.entry_count + 1);
+        assert!(store.usage_stats.total_entry_size_bytes > usage_stats.total_entry_size_bytes);
+
+        store.delete(&id)?;
+        // Check that content is deleted and usage stats are same as before insert.
+        assert!(!store.has_content(&id)?);
+        assert_eq!(store.usage_stats(), usage_stats);
+
+        Ok(())
+    }
+
+    /// Overwrites the content value in the database, bypassing the store.
+    fn overwrite_content_value(
+        config: &IdIndexedV1StoreConfig,
+        content_id: &ContentId,
+        content_value: &[u8],
+    ) -> Result<()> {
+        config.sql_connection_pool.get()?.execute(
+            &format!(
+                "UPDATE {} SET content_value = :content_value WHERE content_id = :content_id",
+                sql::table_name(&config.content_type)
+            ),
+            named_params! {
+                ":content_value": content_value,
+                ":content_id": content_id.to_vec(),
+            },
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn recent_writes_are_served_from_memory() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = IdIndexedV1StoreConfig {
+            recent_writes_capacity: 2,
+            ..create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS)
+        };
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+
+        let mut items = vec![];
+        for _ in 0..3 {
+            let (key, value) = generate_key_value(&config, 0);
+            store.insert(&key, value.clone())?;
+            let id = ContentId::from(key.content_id());
+            overwrite_content_value(&config, &id, &[0])?;
+            items.push((id, value));
+        }
+
+        // The content that is no longer recently stored is still in the database.
+        let (oldest_id, _) = &items[0];
+        assert!(store.has_content(oldest_id)?);
+        assert_eq!(
+            store.lookup_content_value(oldest_id)?,
+            Some(RawContentValue::from(vec![0]))
+        );
+        // The recently stored content is served from memory.
+        for (id, value) in &items[1..] {
+            assert_eq!(store.lookup_content_value(id)?, Some(value.clone()));
+        }
+
+        // The deleted content isn't served from memory.
+        let (newest_id, _) = &items[2];
+        store.delete(newest_id)?;
+        assert_eq!(store.lookup_content_value(newest_id)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pruned_content_is_not_served_from_memory() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS);
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
 
-        let store = IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config)?;
+        let mut dropped_content = vec![];
+        for _ in 0..101 {
+            let (key, value) = generate_key_value(&config, 0x80);
+            dropped_content.extend(store.insert(&key, value)?);
+        }
+        assert_eq!(dropped_content.len(), 6);
+        for (key, _) in dropped_content {
+            let id = ContentId::from(key.content_id());
+            assert_eq!(store.lookup_content_value(&id)?, None);
+        }
 
-        assert_eq!(store.usage_stats, UsageStats::default());
-        assert_eq!(store.radius(), Distance::ZERO);
         Ok(())
     }
 
+    /// Checks that reading recently stored content from memory is faster than reading the content
+    /// that is no longer recently stored from the database.
+    /// Run with `cargo test -p trin-storage recent_writes_lookup_benchmark -- --ignored`.
     #[test]
-    fn create_zero_storage_non_empty() -> Result<()> {
+    #[ignore = "microbenchmark"]
+    fn recent_writes_lookup_benchmark() -> Result<()> {
+        const LOOKUPS: usize = 10_000;
         let temp_dir = TempDir::new()?;
-        let config = create_config(&temp_dir, /* storage_capacity_bytes= */ 0);
+        let config = create_config(&temp_dir, STORAGE_CAPACITY_10000_ITEMS);
+        create_and_populate_table(&config, 5000)?;
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+        let mut ids = vec![];
+        for _ in 0..2 * DEFAULT_STORAGE_RECENT_WRITES {
+            let (key, value) = generate_key_value(&config, 0);
+            store.insert(&key, value)?;
+            ids.push(ContentId::from(key.content_id()));
+        }
+        let (database_ids, memory_ids) = ids.split_at(DEFAULT_STORAGE_RECENT_WRITES);
 
-        // Add 1K entries, more than we would normally prune.
-        create_and_populate_table(&config, 1_000)?;
-        let store = IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config)?;
+        let time_lookups = |ids: &[ContentId]| -> Result<std::time::Duration> {
+            let start = std::time::Instant::now();
+            for id in ids.iter().cycle().take(LOOKUPS) {
+                assert!(store.lookup_content_value(id)?.is_some());
+            }
+            Ok(start.elapsed())
+        };
+        let database_elapsed = time_lookups(database_ids)?;
+        let memory_elapsed = time_lookups(memory_ids)?;
+        assert!(
+            memory_elapsed < database_elapsed,
+            "memory lookups took {memory_elapsed:?}, database lookups took {database_elapsed:?}"
+        );
 
-        // Check that db is empty and distance is ZERO.
-        assert_eq!(store.usage_stats, UsageStats::default());
-        assert_eq!(store.radius(), Distance::ZERO);
         Ok(())
     }
 
     #[test]
-    fn simple_insert_and_lookup() -> Result<()> {
+    fn delete_recomputes_radius() -> Result<()> {
         let temp_dir = TempDir::new()?;
         let config = create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS);
-
-        // fill 50% of storage with 50 items, 1% each
-        create_and_populate_table(&config, 50)?;
         let mut store =
             IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
 
-        let (key, value) = generate_key_value(&config, 0);
-        let id = ContentId::from(key.content_id());
-
-        // Check that content is not stored and save usage stats.
-        assert!(!store.has_content(&id)?);
-        let usage_stats = store.usage_stats();
-
-        store.insert(&key, value.clone())?;
+        // Insert 101 keys, so we prune down to 95 (target capacity) and radius is no longer MAX
+        let mut keys = vec![];
+        for _ in 0..101 {
+            let (key, value) = generate_key_value(&config, 0x80);
+            store.insert(&key, value)?;
+            keys.push(key);
+        }
+        assert_eq!(store.usage_stats.entry_count, 95);
+        let radius = store.radius();
+        assert!(radius < Distance::MAX);
+        let last_change = *store.radius_history().changes().last().unwrap();
+        assert_eq!(last_change.new_radius, radius);
+
+        // Delete keys that are still stored
+        let mut stored_ids = vec![];
+        for key in keys {
+            let id = ContentId::from(key.content_id());
+            if store.has_content(&id)? {
+                stored_ids.push(id);
+            }
+        }
 
-        // Check that lookup works
-        assert!(store.has_content(&id)?);
-        assert_eq!(store.lookup_content_key(&id)?, Some(key));
-        assert_eq!(
-            store.lookup_content_value(&id)?,
-            Some(RawContentValue::from(value))
-        );
+        // Delete 4 items (4% of capacity) and check that radius didn't change
+        for id in &stored_ids[..4] {
+            store.delete(id)?;
+        }
+        assert_eq!(store.radius(), radius);
 
-        // Check that usage stats are updated
-        assert_eq!(store.usage_stats.entry_count, usage_stats.entry_count + 1);
-        assert!(store.usage_stats.total_entry_size_bytes > usage_stats.total_entry_size_bytes);
+        // Delete 1 more item (5% of capacity) and check that radius is recomputed
+        store.delete(&stored_ids[4])?;
+        assert_eq!(store.usage_stats.entry_count, 90);
+        assert_eq!(store.radius(), Distance::MAX);
+        let changes: Vec<_> = store
+            .radius_history()
+            .changes_after(last_change.seq)
+            .collect();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].old_radius, radius);
+        assert_eq!(changes[0].new_radius, Distance::MAX);
 
         Ok(())
     }
 
     #[test]
-    fn simple_insert_and_delete() -> Result<()> {
+    fn recompute_radius() -> Result<()> {
         let temp_dir = TempDir::new()?;
         let config = create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS);
 
-        // fill 50% of storage with 50 items, 1% each
+        // fill 50% of storage with 50 items
         create_and_populate_table(&config, 50)?;
         let mut store =
             IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+        assert_eq!(store.radius(), Distance::MAX);
 
-        let (key, value) = generate_key_value(&config, 0);
-        let id = ContentId::from(key.content_id());
-
-        // Check that content is not stored and save usage stats.
-        assert!(!store.has_content(&id)?);
-        let usage_stats = store.usage_stats();
-
-        store.insert(&key, value)?;
-        // Check that content is stored and usage stats are updated.
-        assert!(store.has_content(&id)?);
-        assert_eq!(store.usage_stats.entry_count, usage_stats.entry_count + 1);
-        assert!(store.usage_stats.total_entry_size_bytes > usage_stats.total_entry_size_bytes);
-
-        store.delete(&id)?;
-        // Check that content is deleted and usage stats are same as before insert.
-        assert!(!store.has_content(&id)?);
-        assert_eq!(store.usage_stats(), usage_stats);
+        // Nothing changed, so radius should stay the same
+        assert!(!store.recompute_radius()?);
+        assert_eq!(store.radius(), Distance::MAX);
 
         Ok(())
     }
@@ -1110,6 +2938,192 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn prune_distance_lru_keeps_read_content() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = IdIndexedV1StoreConfig {
+            eviction_policy: StorageEvictionPolicy::DistanceLru,
+            ..create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS)
+        };
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+
+        // Insert 50 keys that shouldn't be pruned (close distance)
+        for _ in 0..50 {
+            let (key, value) = generate_key_value(&config, 0x10);
+            store.insert(&key, value)?;
+        }
+        // Insert 50 keys that are far, and read half of them
+        let mut read_keys = vec![];
+        for i in 0..50 {
+            let (key, value) = generate_key_value(&config, 0xF0);
+            store.insert(&key, value)?;
+            if i % 2 == 0 {
+                store.lookup_content_value(&ContentId::from(key.content_id()))?;
+                read_keys.push(key);
+            }
+        }
+
+        // Access times are kept in memory until they are needed
+        let count_accessed = || -> Result<u64> {
+            Ok(config.sql_connection_pool.get()?.query_row(
+                &format!(
+                    "SELECT COUNT(*) FROM {} WHERE last_accessed IS NOT NULL",
+                    sql::table_name(&config.content_type)
+                ),
+                [],
+                |row| row.get(0),
+            )?)
+        };
+        assert_eq!(count_accessed()?, 0);
+
+        // Insert one more far key, and check that:
+        // - we pruned down to 95 elements (target capacity)
+        // - only far content that wasn't read is pruned
+        let (key, value) = generate_key_value(&config, 0xF0);
+        let pruned = store.insert(&key, value)?;
+        assert_eq!(store.usage_stats().entry_count, 95);
+        assert_eq!(pruned.len(), 6);
+        for (key, _) in &pruned {
+            assert!(!read_keys.contains(key));
+            assert_eq!(key.content_id()[0], config.node_id.raw()[0] ^ 0xF0);
+        }
+        for key in &read_keys {
+            assert!(store.has_content(&ContentId::from(key.content_id()))?);
+        }
+        assert_eq!(count_accessed()?, read_keys.len() as u64);
+
+        Ok(())
+    }
+
+    #[test]
+    fn prune_distance_lru_keeps_recently_stored_content() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = IdIndexedV1StoreConfig {
+            eviction_policy: StorageEvictionPolicy::DistanceLru,
+            ..create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS)
+        };
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+
+        // Insert 50 far keys that were read long ago, and 50 far keys that were just stored
+        let mut read_keys = vec![];
+        for _ in 0..50 {
+            let (key, value) = generate_key_value(&config, 0xF0);
+            store.insert(&key, value)?;
+            store.lookup_content_value(&ContentId::from(key.content_id()))?;
+            read_keys.push(key);
+        }
+        store.flush_access_times()?;
+        config.sql_connection_pool.get()?.execute(
+            &format!(
+                "UPDATE {} SET last_accessed = 1",
+                sql::table_name(&config.content_type)
+            ),
+            [],
+        )?;
+        for _ in 0..50 {
+            let (key, value) = generate_key_value(&config, 0xF0);
+            store.insert(&key, value)?;
+        }
+
+        // Insert one more far key, and check that only the content read long ago is pruned
+        let (key, value) = generate_key_value(&config, 0xF0);
+        let pruned = store.insert(&key, value)?;
+        assert_eq!(pruned.len(), 6);
+        for (key, _) in &pruned {
+            assert!(read_keys.contains(key));
+        }
+        assert!(store.has_content(&ContentId::from(key.content_id()))?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn access_times_are_written_when_store_is_dropped() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = IdIndexedV1StoreConfig {
+            eviction_policy: StorageEvictionPolicy::DistanceLru,
+            ..create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS)
+        };
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+        let (key, value) = generate_key_value(&config, 0xF0);
+        store.insert(&key, value)?;
+        store.lookup_content_value(&ContentId::from(key.content_id()))?;
+        drop(store);
+
+        let last_accessed: Option<u64> = config.sql_connection_pool.get()?.query_row(
+            &format!(
+                "SELECT last_accessed FROM {} WHERE content_id = ?1",
+                sql::table_name(&config.content_type)
+            ),
+            [key.content_id().to_vec()],
+            |row| row.get(0),
+        )?;
+        assert!(last_accessed.is_some());
+
+        Ok(())
+    }
+
+    /// Replays reads of frequently served content, that is far from the node id, while new far
+    /// content keeps being stored, and returns how many of the reads found the content.
+    fn replay_hot_content_reads(eviction_policy: StorageEvictionPolicy) -> Result<usize> {
+        let temp_dir = TempDir::new()?;
+        let config = IdIndexedV1StoreConfig {
+            eviction_policy,
+            ..create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS)
+        };
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+
+        for _ in 0..50 {
+            let (key, value) = generate_key_value(&config, 0x10);
+            store.insert(&key, value)?;
+        }
+        let mut hot_keys = vec![];
+        for _ in 0..20 {
+            let (key, value) = generate_key_value(&config, 0xFF);
+            store.insert(&key, value)?;
+            hot_keys.push(key);
+        }
+        for _ in 0..30 {
+            let (key, value) = generate_key_value(&config, 0xF0);
+            store.insert(&key, value)?;
+        }
+
+        let mut hits = 0;
+        for _ in 0..20 {
+            for key in &hot_keys {
+                if store
+                    .lookup_content_value(&ContentId::from(key.content_id()))?
+                    .is_some()
+                {
+                    hits += 1;
+                }
+            }
+            for _ in 0..5 {
+                let (key, value) = generate_key_value(&config, 0xF0);
+                match store.insert(&key, value) {
+                    Ok(_) | Err(ContentStoreError::InsufficientRadius { .. }) => {}
+                    Err(err) => return Err(err.into()),
+                }
+            }
+        }
+        Ok(hits)
+    }
+
+    #[test]
+    fn distance_lru_serves_more_hot_content() -> Result<()> {
+        let distance_hits = replay_hot_content_reads(StorageEvictionPolicy::Distance)?;
+        let distance_lru_hits = replay_hot_content_reads(StorageEvictionPolicy::DistanceLru)?;
+
+        // The hot content is the farthest, so it is the first to go when pruning by distance only.
+        assert!(distance_hits < 20 * 20);
+        assert_eq!(distance_lru_hits, 20 * 20);
+        Ok(())
+    }
+
     #[test]
     fn pagination_empty() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -1117,10 +3131,13 @@ mod tests {
         let store = IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config)?;
 
         assert_eq!(
-            store.paginate(/* offset= */ 0, /* limit= */ 10)?,
+            store.paginate(
+                /* offset= */ 0, /* limit= */ 10, /* provenance= */ None,
+                /* source= */ None
+            )?,
             PaginateResult {
                 content_keys: vec![],
-                entry_count: 0,
+                entry_count: Some(0),
             }
         );
         Ok(())
@@ -1145,56 +3162,365 @@ mod tests {
 
         // Paginate in steps of 4, there should be exactly 3 pages
         assert_eq!(
-            store.paginate(/* offset= */ 0, /* limit= */ 4)?,
+            store.paginate(
+                /* offset= */ 0, /* limit= */ 4, /* provenance= */ None,
+                /* source= */ None
+            )?,
             PaginateResult {
                 content_keys: content_keys[0..4].into(),
-                entry_count,
+                entry_count: Some(entry_count),
             }
         );
         assert_eq!(
-            store.paginate(/* offset= */ 4, /* limit= */ 4)?,
+            store.paginate(
+                /* offset= */ 4, /* limit= */ 4, /* provenance= */ None,
+                /* source= */ None
+            )?,
             PaginateResult {
                 content_keys: content_keys[4..8].into(),
-                entry_count,
+                entry_count: Some(entry_count),
             }
         );
         assert_eq!(
-            store.paginate(/* offset= */ 8, /* limit= */ 4)?,
+            store.paginate(
+                /* offset= */ 8, /* limit= */ 4, /* provenance= */ None,
+                /* source= */ None
+            )?,
             PaginateResult {
                 content_keys: content_keys[8..].into(),
-                entry_count,
+                entry_count: Some(entry_count),
             }
         );
         assert_eq!(
-            store.paginate(/* offset= */ 12, /* limit= */ 4)?,
+            store.paginate(
+                /* offset= */ 12, /* limit= */ 4, /* provenance= */ None,
+                /* source= */ None
+            )?,
             PaginateResult {
                 content_keys: vec![],
-                entry_count,
+                entry_count: Some(entry_count),
             }
         );
 
         // Paginate in steps of 5, last page should have only 2
         assert_eq!(
-            store.paginate(/* offset= */ 0, /* limit= */ 5)?,
+            store.paginate(
+                /* offset= */ 0, /* limit= */ 5, /* provenance= */ None,
+                /* source= */ None
+            )?,
             PaginateResult {
                 content_keys: content_keys[0..5].into(),
-                entry_count,
+                entry_count: Some(entry_count),
             }
         );
         assert_eq!(
-            store.paginate(/* offset= */ 5, /* limit= */ 5)?,
+            store.paginate(
+                /* offset= */ 5, /* limit= */ 5, /* provenance= */ None,
+                /* source= */ None
+            )?,
             PaginateResult {
                 content_keys: content_keys[5..10].into(),
-                entry_count,
+                entry_count: Some(entry_count),
             }
         );
         assert_eq!(
-            store.paginate(/* offset= */ 10, /* limit= */ 5)?,
+            store.paginate(
+                /* offset= */ 10, /* limit= */ 5, /* provenance= */ None,
+                /* source= */ None
+            )?,
             PaginateResult {
                 content_keys: content_keys[10..].into(),
-                entry_count,
+                entry_count: Some(entry_count),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn paginate_after_lists_same_keys_as_paginate() -> Result<()> {
+        const PAGE_SIZE: u64 = 1000;
+        let temp_dir = TempDir::new()?;
+        let config = create_config(&temp_dir, 2 * STORAGE_CAPACITY_10000_ITEMS);
+        create_and_populate_table(&config, 10_000)?;
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+
+        let mut offset_keys = HashSet::new();
+        for page in 0..10 {
+            let result = store.paginate(page * PAGE_SIZE, PAGE_SIZE, None, None)?;
+            assert_eq!(result.content_keys.len() as u64, PAGE_SIZE);
+            offset_keys.extend(result.content_keys);
+        }
+
+        let mut cursor_keys = HashSet::new();
+        let mut after = None;
+        let mut pages = 0;
+        loop {
+            let result = store.paginate_after(after.as_ref(), PAGE_SIZE, None, None)?;
+            assert_eq!(result.entry_count, Some(10_000));
+            let Some(last_key) = result.content_keys.last() else {
+                break;
+            };
+            let last_content_id = B256::from(last_key.content_id());
+            // The pages are ordered by content id.
+            assert!(result
+                .content_keys
+                .iter()
+                .all(|key| B256::from(key.content_id()) <= last_content_id));
+            if let Some(after) = &after {
+                assert!(result
+                    .content_keys
+                    .iter()
+                    .all(|key| B256::from(key.content_id()) > **after));
+            }
+            cursor_keys.extend(result.content_keys);
+            after = Some(ContentId::from(last_content_id));
+            pages += 1;
+        }
+
+        assert_eq!(pages, 10);
+        assert_eq!(cursor_keys.len(), 10_000);
+        assert_eq!(cursor_keys, offset_keys);
+
+        // The filters apply to the cursor-based pagination as well.
+        let result = store.paginate_after(None, PAGE_SIZE, Some(ContentProvenance::Offer), None)?;
+        assert!(result.content_keys.is_empty());
+        assert_eq!(result.entry_count, Some(0));
+
+        // The filtered content is only counted for the first page.
+        for _ in 0..2 {
+            let (key, value) = generate_key_value(&config, 0);
+            store.insert_with_meta(&key, value, ContentProvenance::Offer, None)?;
+        }
+        let result = store.paginate_after(None, 1, Some(ContentProvenance::Offer), None)?;
+        assert_eq!(result.entry_count, Some(2));
+        let after = ContentId::from(result.content_keys[0].content_id());
+        let result = store.paginate_after(Some(&after), 1, Some(ContentProvenance::Offer), None)?;
+        assert_eq!(result.content_keys.len(), 1);
+        assert_eq!(result.entry_count, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn export_is_ordered_by_content_id() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS);
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+        let entries: Vec<_> = (0..10).map(|_| generate_key_value(&config, 0)).collect();
+        for (key, value) in entries.iter().rev() {
+            store.insert(key, value.clone())?;
+        }
+
+        // Same content in a different store, inserted in a different order
+        let other_temp_dir = TempDir::new()?;
+        let other_config = IdIndexedV1StoreConfig {
+            node_id: config.node_id,
+            ..create_config(&other_temp_dir, STORAGE_CAPACITY_100_ITEMS)
+        };
+        let mut other_store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, other_config)?;
+        for (key, value) in &entries {
+            other_store.insert(key, value.clone())?;
+        }
+
+        // Exports in batches that don't divide the entry count, so the last one is partial.
+        let export = |store: &IdIndexedV1Store<IdentityContentKey>| -> Result<Vec<u8>> {
+            let mut bytes = vec![];
+            let mut after = None;
+            loop {
+                let batch = store.export_batch(after.as_ref(), 3)?;
+                let Some((last_key, _)) = batch.last() else {
+                    return Ok(bytes);
+                };
+                after = Some(ContentId::from(last_key.content_id()));
+                for (key, value) in &batch {
+                    bytes.extend_from_slice(&key.to_bytes());
+                    bytes.extend_from_slice(value);
+                }
             }
+        };
+        let exported = export(&store)?;
+        assert_eq!(exported, export(&other_store)?);
+
+        let mut expected = entries;
+        expected.sort_by_key(|(key, _)| key.content_id());
+        let expected: Vec<u8> = expected
+            .iter()
+            .flat_map(|(key, value)| [key.to_bytes().to_vec(), value.to_vec()].concat())
+            .collect();
+        assert_eq!(exported, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn entries_within_radius() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS);
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+        let mut entries: Vec<_> = (0..20).map(|_| generate_key_value(&config, 0)).collect();
+        for (key, value) in &entries {
+            store.insert(key, value.clone())?;
+        }
+
+        let peer = NodeId::random();
+        let radius = Distance::from(U256::MAX >> 1);
+        entries.retain(|(key, _)| config.distance_fn.distance(&peer, &key.content_id()) <= radius);
+        entries.sort_by_key(|(key, _)| key.content_id());
+
+        let within_radius = store.entries_within_radius(&peer, radius, /* limit= */ 100)?;
+        assert_eq!(within_radius, entries);
+        let within_radius = store.entries_within_radius(&peer, radius, /* limit= */ 2)?;
+        assert_eq!(within_radius, entries[..2.min(entries.len())]);
+        assert!(store
+            .entries_within_radius(&peer, Distance::ZERO, /* limit= */ 100)?
+            .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn export_batch() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = create_config(&temp_dir, STORAGE_CAPACITY_100_ITEMS);
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+        let mut entries: Vec<_> = (0..10).map(|_| generate_key_value(&config, 0)).collect();
+        for (key, value) in &entries {
+            store.insert(key, value.clone())?;
+        }
+        entries.sort_by_key(|(key, _)| key.content_id());
+
+        let first_batch = store.export_batch(/* after= */ None, /* limit= */ 4)?;
+        assert_eq!(first_batch, entries[0..4]);
+
+        let after = ContentId::from(first_batch[3].0.content_id());
+        let second_batch = store.export_batch(Some(&after), /* limit= */ 10)?;
+        assert_eq!(second_batch, entries[4..]);
+
+        let after = ContentId::from(entries[9].0.content_id());
+        assert!(store
+            .export_batch(Some(&after), /* limit= */ 10)?
+            .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn archive_keeps_evicted_content() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let archive_dir = TempDir::new()?;
+        let (store, ids) = create_store_with_archived_content(&temp_dir, &archive_dir)?;
+
+        // The evicted content is still stored, so the radius doesn't shrink
+        assert_eq!(store.radius(), Distance::MAX);
+        let archived_ids = ids
+            .iter()
+            .filter(|id| is_archived(&store, id).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(archived_ids.len(), 6);
+        for id in &ids {
+            assert!(store.has_content(id)?);
+            assert!(store.lookup_content_key(id)?.is_some());
+            assert!(store.lookup_content_value(id)?.is_some());
+            assert!(store.lookup_meta(id)?.is_some());
+        }
+
+        // The archive is used after a restart
+        let config = store.config.clone();
+        drop(store);
+        let store = IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config)?;
+        assert_eq!(store.archive_usage_stats().unwrap().entry_count, 6);
+        assert!(store.has_content(archived_ids[0])?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn archive_promotes_read_content() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let archive_dir = TempDir::new()?;
+        let (mut store, ids) = create_store_with_archived_content(&temp_dir, &archive_dir)?;
+        let archived_id = ids
+            .iter()
+            .find(|id| is_archived(&store, id).unwrap())
+            .unwrap()
+            .clone();
+
+        // The read content is promoted on the next insert
+        assert!(store.lookup_content_value(&archived_id)?.is_some());
+        assert!(is_archived(&store, &archived_id)?);
+        let (key, value) = generate_key_value(&store.config, 0x10);
+        store.insert(&key, value)?;
+
+        assert!(!is_archived(&store, &archived_id)?);
+        assert!(store.has_content(&archived_id)?);
+        assert_eq!(store.usage_stats().entry_count, 97);
+        assert_eq!(store.archive_usage_stats().unwrap().entry_count, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn archive_delete_removes_from_both() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let archive_dir = TempDir::new()?;
+        let (mut store, ids) = create_store_with_archived_content(&temp_dir, &archive_dir)?;
+        let (archived_ids, primary_ids): (Vec<_>, Vec<_>) = ids
+            .into_iter()
+            .partition(|id| is_archived(&store, id).unwrap());
+
+        store.delete(&archived_ids[0])?;
+        store.delete(&primary_ids[0])?;
+        assert!(!store.has_content(&archived_ids[0])?);
+        assert!(!store.has_content(&primary_ids[0])?);
+        assert_eq!(store.usage_stats().entry_count, 94);
+        assert_eq!(store.archive_usage_stats().unwrap().entry_count, 5);
+
+        // The content of the source is deleted from both tiers
+        assert!(store.set_source(&archived_ids[1], "test")?);
+        assert!(store.set_source(&primary_ids[1], "test")?);
+        assert_eq!(store.delete_by_source("test")?.len(), 2);
+        assert!(!store.has_content(&archived_ids[1])?);
+        assert!(!store.has_content(&primary_ids[1])?);
+        assert_eq!(store.usage_stats().entry_count, 93);
+        assert_eq!(store.archive_usage_stats().unwrap().entry_count, 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn archive_drops_content_once_full() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let archive_dir = TempDir::new()?;
+        let config = create_config_with_archive(
+            &temp_dir,
+            &archive_dir,
+            STORAGE_CAPACITY_100_ITEMS,
+            10 * CONTENT_DEFAULT_SIZE_BYTES,
+        );
+        let mut store =
+            IdIndexedV1Store::<IdentityContentKey>::create(ContentType::State, config.clone())?;
+
+        let mut dropped = vec![];
+        for _ in 0..120 {
+            let (key, value) = generate_key_value(&config, 0x80);
+            dropped.extend(store.insert(&key, value)?);
+        }
+
+        // Both tiers are full, so content is dropped and the radius shrinks
+        assert!(!dropped.is_empty());
+        assert!(store.radius() < Distance::MAX);
+        let archive_usage_stats = store.archive_usage_stats().unwrap();
+        assert!(archive_usage_stats.total_entry_size_bytes <= 10 * CONTENT_DEFAULT_SIZE_BYTES);
+        assert_eq!(
+            store.usage_stats().entry_count + archive_usage_stats.entry_count,
+            120 - dropped.len() as u64
         );
+        for (key, _) in &dropped {
+            assert!(!store.has_content(&ContentId::from(key.content_id()))?);
+        }
 
         Ok(())
     }
@@ -1,10 +1,10 @@
-mod id_indexed_v1;
+pub(crate) mod id_indexed_v1;
 pub mod sql;
 pub mod store;
 mod usage_stats;
 mod utils;
 
-pub use id_indexed_v1::{IdIndexedV1Store, IdIndexedV1StoreConfig};
+pub use id_indexed_v1::{IdIndexedV1Store, IdIndexedV1StoreConfig, OfferSimulation};
 use rusqlite::types::{FromSql, FromSqlError, ValueRef};
 pub use store::VersionedContentStore;
 use strum::{AsRefStr, Display, EnumString};
@@ -16,6 +16,10 @@ pub const STORE_INFO_LOOKUP: &str = "
     WHERE content_type = :content_type
     LIMIT 1";
 
+pub const STORE_INFO_LOOKUP_ALL: &str = "
+    SELECT content_type, version
+    FROM store_info";
+
 // The table management queries
 
 pub const TABLE_EXISTS: &str = "
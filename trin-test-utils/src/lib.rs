@@ -0,0 +1,456 @@
+#![warn(clippy::unwrap_used)]
+#![warn(clippy::uninlined_format_args)]
+
+//! Harness for integration tests that run multiple trin nodes in the same process.
+//!
+//! [TestNetwork::start] spins up the requested number of nodes, each with its own temporary
+//! storage and random port. The first node is used as the bootnode of all the others, and every
+//! pair of nodes is bonded on every enabled subnetwork, so routing tables are populated before the
+//! test starts.
+//!
+//! Nodes are started with [trin::start_trin], as the trin binary starts them. Every long-running
+//! task that it spawns, including the heartbeat and the events handler of each subnetwork, is owned
+//! by its node. [TestNode::shutdown] stops the JSON-RPC server of the node, aborts its tasks and
+//! waits for them to finish, and then drops its subnetworks, which aborts their overlay services.
+//! Dropping the node aborts them as well, without waiting.
+
+use std::{
+    net::{Ipv4Addr, SocketAddr, UdpSocket},
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+
+use alloy::primitives::B256;
+use anyhow::{anyhow, bail};
+use ethportal_api::{
+    types::{
+        cli::TrinConfig,
+        enr::Enr,
+        jsonrpc::{
+            endpoints::{BeaconEndpoint, HistoryEndpoint},
+            request::JsonRpcRequest,
+        },
+        network::Subnetwork,
+    },
+    utils::{bytes::hex_encode, content_signature::PublicKey},
+    BeaconContentKey, ContentValue, HistoryContentKey, OverlayContentKey, RawContentValue,
+};
+use portalnet::discovery::Discovery;
+use rpc::RpcServerHandle;
+use serde_json::Value;
+use tempfile::TempDir;
+use tokio::{
+    sync::mpsc,
+    task::JoinHandle,
+    time::{sleep, Instant},
+};
+use tracing::info;
+use trin_beacon::network::BeaconNetwork;
+use trin_history::network::HistoryNetwork;
+use trin_storage::ContentStore;
+
+/// Storage capacity of every test node, shared between the enabled subnetworks.
+const STORAGE_CAPACITY_MB: u32 = 100;
+
+/// How often the local storage is checked while waiting for gossiped content.
+const GOSSIP_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A network of nodes running in the same process.
+pub struct TestNetwork {
+    pub nodes: Vec<TestNode>,
+}
+
+impl TestNetwork {
+    /// Starts `node_count` nodes with the given subnetworks enabled, and bonds every pair of them.
+    pub async fn start(node_count: usize, subnetworks: &[Subnetwork]) -> anyhow::Result<Self> {
+        if node_count == 0 {
+            bail!("Test network needs at least one node");
+        }
+
+        let mut nodes: Vec<TestNode> = vec![];
+        for _ in 0..node_count {
+            let config = TestNodeConfig {
+                bootnodes: nodes.iter().take(1).map(TestNode::enr).collect(),
+                ..TestNodeConfig::new(subnetworks)
+            };
+            nodes.push(TestNode::start(config).await?);
+        }
+
+        // Bond with all other nodes explicitly, rather than waiting for the background bootnode
+        // pings, so that tests don't depend on their timing.
+        for (i, node) in nodes.iter().enumerate() {
+            for other in &nodes[i + 1..] {
+                node.bond(other).await?;
+            }
+        }
+
+        Ok(Self { nodes })
+    }
+
+    /// Returns the node with the given index.
+    pub fn node(&self, index: usize) -> &TestNode {
+        &self.nodes[index]
+    }
+
+    /// Stops all nodes.
+    pub async fn shutdown(self) {
+        for node in self.nodes {
+            node.shutdown().await;
+        }
+    }
+}
+
+/// Handle to a subnetwork of a test node, for accessing the overlay directly or through the
+/// jsonrpc channel.
+pub struct SubnetworkHandle<TNetwork, TEndpoint> {
+    pub network: Arc<TNetwork>,
+    jsonrpc_tx: mpsc::UnboundedSender<JsonRpcRequest<TEndpoint>>,
+}
+
+impl<TNetwork, TEndpoint> SubnetworkHandle<TNetwork, TEndpoint> {
+    /// Sends the request to the jsonrpc handler of the subnetwork, and waits for the response.
+    pub async fn request(&self, endpoint: TEndpoint) -> Result<Value, String> {
+        let (resp, resp_rx) = mpsc::unbounded_channel();
+        self.send_request(JsonRpcRequest::new(endpoint, resp), resp_rx)
+            .await
+    }
+
+    /// Sends the request with the given request id to the jsonrpc handler of the subnetwork, and
+    /// waits for the response.
+    pub async fn request_with_id(
+        &self,
+        endpoint: TEndpoint,
+        request_id: &str,
+    ) -> Result<Value, String> {
+        let (resp, resp_rx) = mpsc::unbounded_channel();
+        let request = JsonRpcRequest {
+            endpoint,
+            resp,
+            request_id: request_id.to_string(),
+        };
+        self.send_request(request, resp_rx).await
+    }
+
+    async fn send_request(
+        &self,
+        request: JsonRpcRequest<TEndpoint>,
+        mut resp_rx: mpsc::UnboundedReceiver<Result<Value, String>>,
+    ) -> Result<Value, String> {
+        self.jsonrpc_tx
+            .send(request)
+            .map_err(|err| format!("Unable to send jsonrpc request: {err}"))?;
+        resp_rx
+            .recv()
+            .await
+            .ok_or_else(|| "No response from jsonrpc handler".to_string())?
+    }
+}
+
+pub type HistoryHandle = SubnetworkHandle<HistoryNetwork, HistoryEndpoint>;
+pub type BeaconHandle = SubnetworkHandle<BeaconNetwork, BeaconEndpoint>;
+
+/// The configuration of a [TestNode].
+#[derive(Clone, Debug)]
+pub struct TestNodeConfig {
+    pub subnetworks: Vec<Subnetwork>,
+    pub bootnodes: Vec<Enr>,
+    /// The storage capacity, shared between the subnetworks.
+    pub storage_mb: u32,
+    /// The private key of the node, random if `None`.
+    pub private_key: Option<B256>,
+    /// The public keys whose signatures are accepted for content stored over JSON-RPC.
+    pub trusted_content_signers: Vec<PublicKey>,
+}
+
+impl TestNodeConfig {
+    pub fn new(subnetworks: &[Subnetwork]) -> Self {
+        Self {
+            subnetworks: subnetworks.to_vec(),
+            bootnodes: vec![],
+            storage_mb: STORAGE_CAPACITY_MB,
+            private_key: None,
+            trusted_content_signers: vec![],
+        }
+    }
+
+    /// Returns the trin config of the node, which stores its data and serves its IPC endpoint in
+    /// the data dir.
+    fn trin_config(&self, port: u16, data_dir: &Path) -> anyhow::Result<TrinConfig> {
+        let subnetworks = self
+            .subnetworks
+            .iter()
+            .map(Subnetwork::to_cli_arg)
+            .collect::<Vec<_>>()
+            .join(",");
+        let bootnodes = match self.bootnodes.as_slice() {
+            [] => "none".to_string(),
+            bootnodes => bootnodes
+                .iter()
+                .map(Enr::to_base64)
+                .collect::<Vec<_>>()
+                .join(","),
+        };
+        let discovery_port = port.to_string();
+        let external_addr = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port).to_string();
+        let private_key = self.private_key.unwrap_or_else(B256::random).to_string();
+        let storage_mb = self.storage_mb.to_string();
+        let web3_ipc_path = data_dir.join("trin-jsonrpc.ipc");
+        let trusted_content_signers = self
+            .trusted_content_signers
+            .iter()
+            .map(|public_key| hex_encode(public_key.serialize()))
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut args = vec![
+            "trin",
+            "--portal-subnetworks",
+            &subnetworks,
+            "--bootnodes",
+            &bootnodes,
+            "--discovery-port",
+            &discovery_port,
+            "--external-address",
+            &external_addr,
+            "--no-upnp",
+            "--unsafe-private-key",
+            &private_key,
+            "--storage.total",
+            &storage_mb,
+            "--data-dir",
+            path_arg(data_dir)?,
+            "--web3-ipc-path",
+            path_arg(&web3_ipc_path)?,
+        ];
+        if !trusted_content_signers.is_empty() {
+            args.extend(["--trusted-content-signers", &trusted_content_signers]);
+        }
+        Ok(TrinConfig::new_from(args)?)
+    }
+}
+
+/// A node of the [TestNetwork].
+pub struct TestNode {
+    pub discovery: Arc<Discovery>,
+    pub history: Option<HistoryHandle>,
+    pub beacon: Option<BeaconHandle>,
+    rpc_handle: Option<RpcServerHandle>,
+    tasks: Vec<JoinHandle<()>>,
+    // Kept until the node is dropped, as it holds the node's storage.
+    _data_dir: TempDir,
+}
+
+impl TestNode {
+    /// Starts a node with [trin::start_trin], listening on a random port.
+    pub async fn start(config: TestNodeConfig) -> anyhow::Result<Self> {
+        let data_dir = TempDir::new()?;
+        let trin_config = config.trin_config(unused_port()?, data_dir.path())?;
+        let node = trin::start_trin(trin_config)
+            .await
+            .map_err(|err| anyhow!("Unable to start test node: {err}"))?;
+
+        info!(enr = %node.discovery.local_enr(), "Started test node");
+        Ok(Self {
+            discovery: node.discovery,
+            history: node.history.map(|history| SubnetworkHandle {
+                network: history.network,
+                jsonrpc_tx: history.jsonrpc_tx,
+            }),
+            beacon: node.beacon.map(|beacon| SubnetworkHandle {
+                network: beacon.network,
+                jsonrpc_tx: beacon.jsonrpc_tx,
+            }),
+            rpc_handle: Some(node.rpc_handle),
+            tasks: node.tasks,
+            _data_dir: data_dir,
+        })
+    }
+
+    pub fn enr(&self) -> Enr {
+        self.discovery.local_enr()
+    }
+
+    /// Returns the history subnetwork handle, panicking if the subnetwork isn't enabled.
+    pub fn history(&self) -> &HistoryHandle {
+        self.history
+            .as_ref()
+            .expect("History subnetwork should be enabled")
+    }
+
+    /// Returns the beacon subnetwork handle, panicking if the subnetwork isn't enabled.
+    pub fn beacon(&self) -> &BeaconHandle {
+        self.beacon
+            .as_ref()
+            .expect("Beacon subnetwork should be enabled")
+    }
+
+    /// Returns the overlay routing table entries of the subnetwork.
+    pub fn routing_table(&self, subnetwork: Subnetwork) -> Vec<Enr> {
+        match subnetwork {
+            Subnetwork::History => self.history().network.overlay.table_entries_enr(),
+            Subnetwork::Beacon => self.beacon().network.overlay.table_entries_enr(),
+            _ => panic!("Subnetwork {subnetwork} isn't supported by the test harness"),
+        }
+    }
+
+    /// Pings the other node on every enabled subnetwork, adding the nodes to each other's routing
+    /// tables.
+    pub async fn bond(&self, other: &TestNode) -> anyhow::Result<()> {
+        if let Some(history) = &self.history {
+            history.network.overlay.send_ping(other.enr()).await?;
+        }
+        if let Some(beacon) = &self.beacon {
+            beacon.network.overlay.send_ping(other.enr()).await?;
+        }
+        Ok(())
+    }
+
+    /// Stops the JSON-RPC server and all tasks of the node, including the overlay services of its
+    /// subnetworks.
+    pub async fn shutdown(mut self) {
+        if let Some(rpc_handle) = self.rpc_handle.take() {
+            // The server is stopped anyway once its handle is dropped.
+            let _ = rpc_handle.stop();
+        }
+        for task in self.tasks.drain(..) {
+            task.abort();
+            // The task is either finished or cancelled, both of which are fine here.
+            let _ = task.await;
+        }
+        // The tasks no longer hold the subnetworks, so dropping them drops their overlay
+        // protocols, which abort their overlay services.
+        self.history.take();
+        self.beacon.take();
+    }
+}
+
+impl Drop for TestNode {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// Content key whose content can be accessed in the local storage of a [TestNode].
+pub trait TestContentKey: OverlayContentKey + Sized {
+    fn get(node: &TestNode, key: &Self) -> anyhow::Result<Option<RawContentValue>>;
+
+    fn put(node: &TestNode, key: Self, value: RawContentValue) -> anyhow::Result<()>;
+}
+
+impl TestContentKey for HistoryContentKey {
+    fn get(node: &TestNode, key: &Self) -> anyhow::Result<Option<RawContentValue>> {
+        Ok(node.history().network.overlay.store.read().get(key)?)
+    }
+
+    fn put(node: &TestNode, key: Self, value: RawContentValue) -> anyhow::Result<()> {
+        node.history()
+            .network
+            .overlay
+            .store
+            .write()
+            .put(key, value)?;
+        Ok(())
+    }
+}
+
+impl TestContentKey for BeaconContentKey {
+    fn get(node: &TestNode, key: &Self) -> anyhow::Result<Option<RawContentValue>> {
+        Ok(node.beacon().network.overlay.store.read().get(key)?)
+    }
+
+    fn put(node: &TestNode, key: Self, value: RawContentValue) -> anyhow::Result<()> {
+        node.beacon()
+            .network
+            .overlay
+            .store
+            .write()
+            .put(key, value)?;
+        Ok(())
+    }
+}
+
+/// Stores the content in the local storage of the node, bypassing validation.
+pub fn seed_content<V: ContentValue>(
+    node: &TestNode,
+    key: V::TContentKey,
+    value: &V,
+) -> anyhow::Result<()>
+where
+    V::TContentKey: TestContentKey,
+{
+    TestContentKey::put(node, key, value.encode())
+}
+
+/// Waits until the content is present in the local storage of the node, returning it.
+pub async fn wait_for_gossip<K: TestContentKey>(
+    node: &TestNode,
+    key: &K,
+    timeout: Duration,
+) -> anyhow::Result<RawContentValue> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(content) = K::get(node, key)? {
+            return Ok(content);
+        }
+        if Instant::now() >= deadline {
+            bail!("Content {key} wasn't gossiped to the node within {timeout:?}");
+        }
+        sleep(GOSSIP_POLL_INTERVAL).await;
+    }
+}
+
+/// Asserts that the other node is in the node's routing table of the subnetwork.
+pub fn assert_routing_contains(node: &TestNode, other: &TestNode, subnetwork: Subnetwork) {
+    let other_node_id = other.enr().node_id();
+    assert!(
+        node.routing_table(subnetwork)
+            .iter()
+            .any(|enr| enr.node_id() == other_node_id),
+        "Node {} should be in the {subnetwork} routing table of node {}",
+        other_node_id,
+        node.enr().node_id(),
+    );
+}
+
+/// Returns the path as a command line argument.
+fn path_arg(path: &Path) -> anyhow::Result<&str> {
+    path.to_str()
+        .ok_or_else(|| anyhow!("Path {} should be unicode", path.display()))
+}
+
+/// Returns a port that is currently unused, as picked by the OS.
+fn unused_port() -> std::io::Result<u16> {
+    Ok(UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))?
+        .local_addr()?
+        .port())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use tokio::time::timeout;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn shutdown_aborts_overlay_services() {
+        let network = TestNetwork::start(2, &[Subnetwork::History]).await.unwrap();
+        let command_txs: Vec<_> = network
+            .nodes
+            .iter()
+            .map(|node| node.history().network.overlay.command_tx.clone())
+            .collect();
+
+        network.shutdown().await;
+        // The command receiver of each overlay service is dropped along with the service.
+        timeout(Duration::from_secs(1), async {
+            while !command_txs.iter().all(|command_tx| command_tx.is_closed()) {
+                sleep(GOSSIP_POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+}
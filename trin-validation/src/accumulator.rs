@@ -52,8 +52,8 @@ impl PreMergeAccumulator {
         MERGE_BLOCK_NUMBER
     }
 
-    pub(crate) fn get_epoch_index_of_header(&self, header: &Header) -> u64 {
-        header.number / EPOCH_SIZE
+    pub(crate) fn get_epoch_index_of_block(&self, block_number: u64) -> u64 {
+        block_number / EPOCH_SIZE
     }
 
     pub fn construct_proof(
@@ -9,6 +9,8 @@ pub const BYZANTIUM_BLOCK_NUMBER: u64 = 4_370_000;
 pub const HOMESTEAD_BLOCK_NUMBER: u64 = 1_150_000;
 pub const CAPELLA_FORK_EPOCH: u64 = 194_048;
 pub const SLOTS_PER_EPOCH: u64 = 32;
+/// The beacon chain slot of the merge block.
+pub const MERGE_SLOT: u64 = 4_700_013;
 
 /// The default hash of the pre-merge accumulator at the time of the merge block.
 pub const DEFAULT_PRE_MERGE_ACC_HASH: &str =
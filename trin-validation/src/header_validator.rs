@@ -2,17 +2,20 @@ use alloy::primitives::B256;
 use anyhow::anyhow;
 use ethportal_api::{
     consensus::historical_summaries::HistoricalSummaries,
-    types::execution::header_with_proof::{
-        BeaconBlockProof, BlockHeaderProof, HeaderWithProof, HistoricalRootsBlockProof,
-        HistoricalSummariesBlockProof,
+    types::{
+        execution::header_with_proof::{
+            BeaconBlockProof, BlockHeaderProof, HeaderWithProof, HistoricalRootsBlockProof,
+            HistoricalSummariesBlockProof,
+        },
+        portal::{HeaderProofType, HeaderVerificationInfo},
     },
-    Header,
 };
 
 use crate::{
     accumulator::PreMergeAccumulator,
     constants::{
-        CAPELLA_FORK_EPOCH, EPOCH_SIZE, MERGE_BLOCK_NUMBER, SHANGHAI_BLOCK_NUMBER, SLOTS_PER_EPOCH,
+        CAPELLA_FORK_EPOCH, EPOCH_SIZE, MERGE_BLOCK_NUMBER, MERGE_SLOT, SHANGHAI_BLOCK_NUMBER,
+        SLOTS_PER_EPOCH,
     },
     historical_roots_acc::HistoricalRootsAccumulator,
     merkle::proof::verify_merkle_proof,
@@ -40,48 +43,166 @@ impl HeaderValidator {
     }
 
     pub fn validate_header_with_proof(&self, hwp: &HeaderWithProof) -> anyhow::Result<()> {
-        match &hwp.proof {
+        self.check_header_proof(hwp.header.number, hwp.header.hash(), &hwp.proof, None)
+            .map(|_| ())
+    }
+
+    /// Validates the header with its proof, verifying post-Capella proofs against the historical
+    /// summaries. Post-Capella headers are only accepted without verification if no historical
+    /// summaries are given, and rejected if the historical summaries don't cover them.
+    pub fn validate_header_with_proof_and_summaries(
+        &self,
+        hwp: &HeaderWithProof,
+        historical_summaries: Option<&HistoricalSummaries>,
+    ) -> anyhow::Result<()> {
+        self.check_header_proof(
+            hwp.header.number,
+            hwp.header.hash(),
+            &hwp.proof,
+            historical_summaries,
+        )
+        .map(|_| ())
+    }
+
+    /// Validates the header with its proof the same way as
+    /// [Self::validate_header_with_proof_and_summaries], and returns the verdict instead of an
+    /// error.
+    pub fn verify_header_with_proof(
+        &self,
+        hwp: &HeaderWithProof,
+        historical_summaries: Option<&HistoricalSummaries>,
+    ) -> HeaderVerificationInfo {
+        self.verify_header_proof(
+            hwp.header.number,
+            hwp.header.hash(),
+            &hwp.proof,
+            historical_summaries,
+        )
+    }
+
+    /// Returns the verdict of validating the proof of the header with the block number and hash.
+    /// Post-Capella proofs are verified against the historical summaries, if they are given.
+    pub fn verify_header_proof(
+        &self,
+        block_number: u64,
+        header_hash: B256,
+        proof: &BlockHeaderProof,
+        historical_summaries: Option<&HistoricalSummaries>,
+    ) -> HeaderVerificationInfo {
+        let result =
+            self.check_header_proof(block_number, header_hash, proof, historical_summaries);
+        HeaderVerificationInfo {
+            block_number,
+            block_hash: header_hash,
+            proof_type: HeaderProofType::from(proof),
+            accepted: result.is_ok(),
+            proof_verified: matches!(result, Ok(true)),
+            root: self.proof_root(block_number, proof, historical_summaries),
+            error: result.err().map(|err| err.to_string()),
+        }
+    }
+
+    /// Returns the root that the proof is checked against, i.e. the epoch hash of the pre-merge
+    /// accumulator, the historical root or the block summary root of the historical summary, if
+    /// there is one.
+    fn proof_root(
+        &self,
+        block_number: u64,
+        proof: &BlockHeaderProof,
+        historical_summaries: Option<&HistoricalSummaries>,
+    ) -> Option<B256> {
+        match proof {
+            BlockHeaderProof::PreMergeAccumulatorProof(_) => {
+                if block_number > MERGE_BLOCK_NUMBER {
+                    return None;
+                }
+                self.pre_merge_epoch_hash(block_number)
+            }
+            BlockHeaderProof::HistoricalRootsBlockProof(proof) => self
+                .historical_roots_acc
+                .historical_roots
+                .get((proof.slot / EPOCH_SIZE) as usize)
+                .copied(),
+            BlockHeaderProof::HistoricalSummariesBlockProof(proof) => historical_summaries?
+                .get(historical_summary_index(proof.slot))
+                .map(|historical_summary| historical_summary.block_summary_root),
+            BlockHeaderProof::None(_) => None,
+        }
+    }
+
+    /// Looks up the historical epoch hash of the block from the pre-merge accumulator.
+    fn pre_merge_epoch_hash(&self, block_number: u64) -> Option<B256> {
+        let epoch_index = self.pre_merge_acc.get_epoch_index_of_block(block_number);
+        self.pre_merge_acc
+            .historical_epochs
+            .get(epoch_index as usize)
+            .copied()
+    }
+
+    /// Validates the proof of the header with the block number and hash. Returns whether the
+    /// proof was verified, as headers whose proof can't be verified yet are accepted without it.
+    fn check_header_proof(
+        &self,
+        block_number: u64,
+        header_hash: B256,
+        proof: &BlockHeaderProof,
+        historical_summaries: Option<&HistoricalSummaries>,
+    ) -> anyhow::Result<bool> {
+        match proof {
             BlockHeaderProof::PreMergeAccumulatorProof(proof) => {
-                if hwp.header.number > MERGE_BLOCK_NUMBER {
+                if block_number > MERGE_BLOCK_NUMBER {
                     return Err(anyhow!("Invalid proof type found for post-merge header."));
                 }
                 // Look up historical epoch hash for header from pre-merge accumulator
-                let gen_index = calculate_generalized_index(&hwp.header);
-                let epoch_index =
-                    self.pre_merge_acc.get_epoch_index_of_header(&hwp.header) as usize;
-                let epoch_hash = self.pre_merge_acc.historical_epochs[epoch_index];
+                let gen_index = calculate_generalized_index(block_number);
+                let epoch_hash = self
+                    .pre_merge_epoch_hash(block_number)
+                    .ok_or_else(|| anyhow!("Pre-merge accumulator has no epoch for header."))?;
 
                 match verify_merkle_proof(
-                    hwp.header.hash(),
+                    header_hash,
                     &proof.proof,
                     15,
                     gen_index as usize,
                     epoch_hash,
                 ) {
-                    true => Ok(()),
+                    true => Ok(true),
                     false => Err(anyhow!(
                         "Merkle proof validation failed for pre-merge header"
                     )),
                 }
             }
             BlockHeaderProof::None(_) => {
-                if hwp.header.number <= MERGE_BLOCK_NUMBER {
+                if block_number <= MERGE_BLOCK_NUMBER {
                     Err(anyhow!("Missing accumulator proof for pre-merge header."))
                 } else {
-                    // Skip validation for post-merge headers until proof format is finalized
-                    Ok(())
+                    Err(anyhow!("Missing proof for post-merge header."))
                 }
             }
             BlockHeaderProof::HistoricalRootsBlockProof(proof) => self
-                .verify_post_merge_pre_capella_header(hwp.header.number, hwp.header.hash(), proof),
-            BlockHeaderProof::HistoricalSummariesBlockProof(_) => {
-                if hwp.header.number < SHANGHAI_BLOCK_NUMBER {
+                .verify_post_merge_pre_capella_header(block_number, header_hash, proof)
+                .map(|()| true),
+            BlockHeaderProof::HistoricalSummariesBlockProof(proof) => {
+                if block_number < SHANGHAI_BLOCK_NUMBER {
                     return Err(anyhow!(
                         "Invalid HistoricalSummariesBlockProof found for pre-Shanghai header."
                     ));
                 }
-                // TODO: Validation for post-Capella headers is not implemented
-                Ok(())
+                match historical_summaries {
+                    Some(historical_summaries) => {
+                        self.verify_post_capella_header(
+                            block_number,
+                            header_hash,
+                            proof,
+                            historical_summaries,
+                        )?;
+                        Ok(true)
+                    }
+                    None => {
+                        verify_slot_of_block_number(proof.slot, block_number)?;
+                        Ok(false)
+                    }
+                }
             }
         }
     }
@@ -103,6 +224,7 @@ impl HeaderValidator {
                 "Invalid HistoricalRootsBlockProof found for post-Shanghai header."
             ));
         }
+        verify_slot_of_block_number(proof.slot, block_number)?;
 
         // Verify the chain of proofs for post-merge/pre-capella block header
         Self::verify_beacon_block_proof(
@@ -114,8 +236,11 @@ impl HeaderValidator {
         let block_root_index = proof.slot % EPOCH_SIZE;
         let gen_index = 2 * EPOCH_SIZE + block_root_index;
         let historical_root_index = proof.slot / EPOCH_SIZE;
-        let historical_root =
-            self.historical_roots_acc.historical_roots[historical_root_index as usize];
+        let historical_root = *self
+            .historical_roots_acc
+            .historical_roots
+            .get(historical_root_index as usize)
+            .ok_or_else(|| anyhow!("Historical root not found for slot {}", proof.slot))?;
 
         if !verify_merkle_proof(
             proof.beacon_block_root,
@@ -133,19 +258,19 @@ impl HeaderValidator {
     }
 
     /// A method to verify the chain of proofs for post-Capella execution headers.
-    #[allow(dead_code)] // TODO: Remove this when used
     fn verify_post_capella_header(
         &self,
         block_number: u64,
         header_hash: B256,
         proof: &HistoricalSummariesBlockProof,
-        historical_summaries: HistoricalSummaries,
+        historical_summaries: &HistoricalSummaries,
     ) -> anyhow::Result<()> {
         if block_number < SHANGHAI_BLOCK_NUMBER {
             return Err(anyhow!(
                 "Invalid HistoricalSummariesBlockProof found for pre-Shanghai header."
             ));
         }
+        verify_slot_of_block_number(proof.slot, block_number)?;
 
         // Verify the chain of proofs for post-merge/pre-capella block header
         Self::verify_beacon_block_proof(
@@ -156,10 +281,10 @@ impl HeaderValidator {
 
         let block_root_index = proof.slot % EPOCH_SIZE;
         let gen_index = EPOCH_SIZE + block_root_index;
-        let historical_summary_index =
-            (proof.slot - CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH) / EPOCH_SIZE;
-        let historical_summary =
-            historical_summaries[historical_summary_index as usize].block_summary_root;
+        let historical_summary = historical_summaries
+            .get(historical_summary_index(proof.slot))
+            .ok_or_else(|| anyhow!("Historical summaries don't cover slot {}", proof.slot))?
+            .block_summary_root;
 
         if !verify_merkle_proof(
             proof.beacon_block_root,
@@ -211,10 +336,33 @@ impl HeaderValidator {
     }
 }
 
-fn calculate_generalized_index(header: &Header) -> u64 {
+/// Verifies that the slot of a post-merge proof could hold the block: Capella starts at the
+/// Shanghai block, and every block after the merge takes up its own slot.
+fn verify_slot_of_block_number(slot: u64, block_number: u64) -> anyhow::Result<()> {
+    let capella_slot = CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH;
+    if (block_number >= SHANGHAI_BLOCK_NUMBER) != (slot >= capella_slot) {
+        return Err(anyhow!(
+            "Proof slot {slot} is in a different fork than block {block_number}"
+        ));
+    }
+    let min_slot = MERGE_SLOT + block_number.saturating_sub(MERGE_BLOCK_NUMBER);
+    if slot < min_slot {
+        return Err(anyhow!(
+            "Proof slot {slot} is too early for block {block_number}, expected at least {min_slot}"
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the index of the historical summary of the post-Capella slot.
+fn historical_summary_index(slot: u64) -> usize {
+    (slot.saturating_sub(CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH) / EPOCH_SIZE) as usize
+}
+
+fn calculate_generalized_index(block_number: u64) -> u64 {
     // Calculate generalized index for header
     // https://github.com/ethereum/consensus-specs/blob/v0.11.1/ssz/merkle-proofs.md#generalized-merkle-tree-index
-    let hr_index = header.number % EPOCH_SIZE;
+    let hr_index = block_number % EPOCH_SIZE;
     (EPOCH_SIZE * 2 * 2) + (hr_index * 2)
 }
 
@@ -235,7 +383,7 @@ mod test {
             },
         },
         utils::bytes::{hex_decode, hex_encode},
-        HistoryContentKey, OverlayContentKey,
+        Header, HistoryContentKey, OverlayContentKey,
     };
     use rstest::*;
     use serde_json::Value;
@@ -350,18 +498,23 @@ mod test {
         header_validator.validate_header_with_proof(&hwp).unwrap();
     }
 
+    #[rstest]
+    #[case(MERGE_BLOCK_NUMBER + 1)]
+    #[case(SHANGHAI_BLOCK_NUMBER)]
     #[tokio::test]
-    async fn header_validator_validates_post_merge_header_without_proof() {
+    async fn header_validator_invalidates_post_merge_header_without_proof(
+        #[case] block_number: u64,
+    ) {
         let header_validator = get_mainnet_header_validator();
-        let future_height = MERGE_BLOCK_NUMBER + 1;
-        let future_header = generate_random_header(&future_height);
+        let future_header = generate_random_header(&block_number);
         let future_hwp = HeaderWithProof {
             header: future_header,
             proof: BlockHeaderProof::None(SszNone::default()),
         };
-        header_validator
+        let err = header_validator
             .validate_header_with_proof(&future_hwp)
-            .unwrap();
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Missing proof for post-merge header.");
     }
 
     #[tokio::test]
@@ -452,7 +605,7 @@ mod test {
                 block_number,
                 header_hash,
                 &historical_summaries_block_proof,
-                historical_summaries.clone(),
+                &historical_summaries,
             )
             .unwrap();
 
@@ -461,11 +614,261 @@ mod test {
             SHANGHAI_BLOCK_NUMBER - 1,
             header_hash,
             &historical_summaries_block_proof,
-            historical_summaries,
+            &historical_summaries,
         );
         assert!(validator_result.is_err());
     }
 
+    #[tokio::test]
+    async fn header_validator_validate_post_capella_header_against_summaries() {
+        let header_validator = get_mainnet_header_validator();
+        let block_number: u64 = 17034870;
+        let file = fs::read_to_string(format!("./../portal-spec-tests/tests/mainnet/history/headers_with_proof/block_proofs_capella/beacon_block_proof-{block_number}.yaml")).unwrap();
+        let value: serde_yaml::Value = serde_yaml::from_str(&file).unwrap();
+        let header_hash = value
+            .get("execution_block_header")
+            .unwrap()
+            .as_str()
+            .unwrap();
+        let header_hash = B256::from_str(header_hash).unwrap();
+        let mut proof: HistoricalSummariesBlockProof = serde_yaml::from_value(value).unwrap();
+        let historical_summaries_bytes = std::fs::read("./../portal-spec-tests/tests/mainnet/history/headers_with_proof/block_proofs_capella/historical_summaries_at_slot_8953856.ssz"
+        ).expect("cannot load HistoricalSummaries bytes from test file");
+        let historical_summaries = HistoricalSummaries::from_ssz_bytes(&historical_summaries_bytes)
+            .expect("cannot decode HistoricalSummaries bytes");
+
+        let verified = header_validator
+            .check_header_proof(
+                block_number,
+                header_hash,
+                &BlockHeaderProof::HistoricalSummariesBlockProof(proof.clone()),
+                Some(&historical_summaries),
+            )
+            .unwrap();
+        assert!(verified);
+
+        // Without the historical summaries, the header is accepted without verification.
+        let verified = header_validator
+            .check_header_proof(
+                block_number,
+                header_hash,
+                &BlockHeaderProof::HistoricalSummariesBlockProof(proof.clone()),
+                None,
+            )
+            .unwrap();
+        assert!(!verified);
+
+        // Proofs outside of the known historical summaries are rejected.
+        let result = header_validator.check_header_proof(
+            block_number,
+            header_hash,
+            &BlockHeaderProof::HistoricalSummariesBlockProof(proof.clone()),
+            Some(&HistoricalSummaries::default()),
+        );
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Historical summaries don't cover slot"));
+
+        // Proofs whose slot can't hold the block are rejected.
+        for (block_number, slot) in [
+            (block_number, CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH - 1),
+            (block_number + proof.slot, proof.slot),
+        ] {
+            let mut proof = proof.clone();
+            proof.slot = slot;
+            for historical_summaries in [None, Some(&historical_summaries)] {
+                let result = header_validator.check_header_proof(
+                    block_number,
+                    header_hash,
+                    &BlockHeaderProof::HistoricalSummariesBlockProof(proof.clone()),
+                    historical_summaries,
+                );
+                assert!(result.unwrap_err().to_string().starts_with("Proof slot"));
+            }
+        }
+
+        proof.beacon_block_root = B256::random();
+        let result = header_validator.check_header_proof(
+            block_number,
+            header_hash,
+            &BlockHeaderProof::HistoricalSummariesBlockProof(proof),
+            Some(&historical_summaries),
+        );
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn verify_pre_merge_header_with_proof() {
+        let header_validator = get_mainnet_header_validator();
+        let header = get_header(1_000_001);
+        let epoch_accumulator = read_epoch_accumulator_122();
+        let proof = PreMergeAccumulator::construct_proof(&header, &epoch_accumulator).unwrap();
+        let mut hwp = HeaderWithProof {
+            header,
+            proof: BlockHeaderProof::PreMergeAccumulatorProof(PreMergeAccumulatorProof { proof }),
+        };
+
+        let info = header_validator.verify_header_with_proof(&hwp, None);
+        assert_eq!(info.block_number, 1_000_001);
+        assert_eq!(info.block_hash, hwp.header.hash());
+        assert_eq!(info.proof_type, HeaderProofType::PreMergeAccumulator);
+        assert!(info.accepted);
+        assert!(info.proof_verified);
+        assert_eq!(
+            info.root,
+            Some(header_validator.pre_merge_acc.historical_epochs[122])
+        );
+        assert_eq!(info.error, None);
+
+        // A tampered proof is checked against the same root, but rejected.
+        let mut proof = proof;
+        proof.swap(0, 1);
+        hwp.proof = BlockHeaderProof::PreMergeAccumulatorProof(PreMergeAccumulatorProof { proof });
+        let tampered_info = header_validator.verify_header_with_proof(&hwp, None);
+        assert!(!tampered_info.accepted);
+        assert!(!tampered_info.proof_verified);
+        assert_eq!(tampered_info.root, info.root);
+        assert!(tampered_info
+            .error
+            .unwrap()
+            .contains("Merkle proof validation failed"));
+    }
+
+    #[tokio::test]
+    async fn verify_post_merge_pre_capella_header_proof() {
+        let header_validator = get_mainnet_header_validator();
+        let file = fs::read_to_string("./../portal-spec-tests/tests/mainnet/history/headers_with_proof/block_proofs_bellatrix/beacon_block_proof-15539558-cdf9ed89b0c43cda17398dc4da9cfc505e5ccd19f7c39e3b43474180f1051e01.yaml").unwrap();
+        let value: serde_yaml::Value = serde_yaml::from_str(&file).unwrap();
+        let block_number: u64 = 15539558;
+        let header_hash = value
+            .get("execution_block_header")
+            .unwrap()
+            .as_str()
+            .unwrap();
+        let header_hash = B256::from_str(header_hash).unwrap();
+        let mut proof: HistoricalRootsBlockProof = serde_yaml::from_value(value).unwrap();
+
+        let info = header_validator.verify_header_proof(
+            block_number,
+            header_hash,
+            &BlockHeaderProof::HistoricalRootsBlockProof(proof.clone()),
+            None,
+        );
+        assert_eq!(info.proof_type, HeaderProofType::HistoricalRoots);
+        assert!(info.accepted);
+        assert!(info.proof_verified);
+        let historical_root_index = (proof.slot / EPOCH_SIZE) as usize;
+        assert_eq!(
+            info.root,
+            Some(header_validator.historical_roots_acc.historical_roots[historical_root_index])
+        );
+        assert_eq!(info.error, None);
+
+        proof.beacon_block_root = B256::random();
+        let tampered_info = header_validator.verify_header_proof(
+            block_number,
+            header_hash,
+            &BlockHeaderProof::HistoricalRootsBlockProof(proof),
+            None,
+        );
+        assert!(!tampered_info.accepted);
+        assert!(!tampered_info.proof_verified);
+        assert_eq!(tampered_info.root, info.root);
+        assert!(tampered_info.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn verify_post_capella_header_proof() {
+        let header_validator = get_mainnet_header_validator();
+        let block_number: u64 = 17034870;
+        let file = fs::read_to_string(format!("./../portal-spec-tests/tests/mainnet/history/headers_with_proof/block_proofs_capella/beacon_block_proof-{block_number}.yaml")).unwrap();
+        let value: serde_yaml::Value = serde_yaml::from_str(&file).unwrap();
+        let header_hash = value
+            .get("execution_block_header")
+            .unwrap()
+            .as_str()
+            .unwrap();
+        let header_hash = B256::from_str(header_hash).unwrap();
+        let mut proof: HistoricalSummariesBlockProof = serde_yaml::from_value(value).unwrap();
+        let historical_summaries_bytes = std::fs::read("./../portal-spec-tests/tests/mainnet/history/headers_with_proof/block_proofs_capella/historical_summaries_at_slot_8953856.ssz"
+        ).expect("cannot load HistoricalSummaries bytes from test file");
+        let historical_summaries = HistoricalSummaries::from_ssz_bytes(&historical_summaries_bytes)
+            .expect("cannot decode HistoricalSummaries bytes");
+
+        let info = header_validator.verify_header_proof(
+            block_number,
+            header_hash,
+            &BlockHeaderProof::HistoricalSummariesBlockProof(proof.clone()),
+            Some(&historical_summaries),
+        );
+        assert_eq!(info.proof_type, HeaderProofType::HistoricalSummaries);
+        assert!(info.accepted);
+        assert!(info.proof_verified);
+        assert_eq!(
+            info.root,
+            Some(historical_summaries[historical_summary_index(proof.slot)].block_summary_root)
+        );
+        assert_eq!(info.error, None);
+
+        // Without the historical summaries, the header is accepted without verification.
+        let unverified_info = header_validator.verify_header_proof(
+            block_number,
+            header_hash,
+            &BlockHeaderProof::HistoricalSummariesBlockProof(proof.clone()),
+            None,
+        );
+        assert!(unverified_info.accepted);
+        assert!(!unverified_info.proof_verified);
+        assert_eq!(unverified_info.root, None);
+        assert_eq!(unverified_info.error, None);
+
+        // The proof doesn't belong to a pre-Shanghai header.
+        let tampered_info = header_validator.verify_header_proof(
+            SHANGHAI_BLOCK_NUMBER - 1,
+            header_hash,
+            &BlockHeaderProof::HistoricalSummariesBlockProof(proof.clone()),
+            Some(&historical_summaries),
+        );
+        assert!(!tampered_info.accepted);
+        assert!(!tampered_info.proof_verified);
+        assert!(tampered_info.error.unwrap().contains("pre-Shanghai header"));
+
+        // A tampered proof is checked against the same root, but rejected.
+        proof.beacon_block_root = B256::random();
+        let tampered_info = header_validator.verify_header_proof(
+            block_number,
+            header_hash,
+            &BlockHeaderProof::HistoricalSummariesBlockProof(proof),
+            Some(&historical_summaries),
+        );
+        assert!(!tampered_info.accepted);
+        assert!(!tampered_info.proof_verified);
+        assert_eq!(tampered_info.root, info.root);
+        assert!(tampered_info.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn verify_post_merge_header_without_proof() {
+        let header_validator = get_mainnet_header_validator();
+        let header = generate_random_header(&(MERGE_BLOCK_NUMBER + 1));
+        let info = header_validator.verify_header_with_proof(
+            &HeaderWithProof {
+                header,
+                proof: BlockHeaderProof::None(SszNone::default()),
+            },
+            None,
+        );
+        assert_eq!(info.proof_type, HeaderProofType::None);
+        assert!(!info.accepted);
+        assert!(!info.proof_verified);
+        assert_eq!(info.root, None);
+        assert_eq!(
+            info.error.as_deref(),
+            Some("Missing proof for post-merge header.")
+        );
+    }
+
     //
     // Testing utils
     //
@@ -1,11 +1,19 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
 use alloy::primitives::B256;
-use anyhow::anyhow;
+use anyhow::{anyhow, ensure};
 use enr::NodeId;
 use ethportal_api::{
-    consensus::header::BeaconBlockHeader,
+    consensus::{
+        header::BeaconBlockHeader,
+        historical_summaries::{HistoricalSummaries, HistoricalSummariesWithProof},
+    },
     light_client::store::LightClientStore,
     types::{
-        execution::header_with_proof::HeaderWithProof,
+        execution::header_with_proof::{BlockHeaderProof, HeaderWithProof},
         jsonrpc::{
             endpoints::{BeaconEndpoint, HistoryEndpoint, StateEndpoint},
             request::{BeaconJsonRpcRequest, HistoryJsonRpcRequest, StateJsonRpcRequest},
@@ -19,6 +27,10 @@ use tokio::sync::mpsc;
 
 use crate::header_validator::HeaderValidator;
 
+/// The time for which the historical summaries fetched from the beacon network are reused. A new
+/// summary is only appended every 8192 slots, so one epoch is conservative.
+const HISTORICAL_SUMMARIES_CACHE_TTL: Duration = Duration::from_secs(32 * 12);
+
 /// Responsible for dispatching cross-overlay-network requests
 /// for data to perform validation.
 #[derive(Clone, Debug)]
@@ -30,6 +42,31 @@ pub struct HeaderOracle {
     pub beacon_jsonrpc_tx: Option<mpsc::UnboundedSender<BeaconJsonRpcRequest>>,
     pub state_jsonrpc_tx: Option<mpsc::UnboundedSender<StateJsonRpcRequest>>,
     pub header_validator: HeaderValidator,
+    pub historical_summaries_cache: HistoricalSummariesCache,
+}
+
+/// The latest historical summaries fetched from the beacon network, shared between the clones of
+/// the oracle.
+#[derive(Clone, Debug, Default)]
+pub struct HistoricalSummariesCache(Arc<Mutex<Option<(Instant, Arc<HistoricalSummaries>)>>>);
+
+impl HistoricalSummariesCache {
+    fn get(&self) -> Option<Arc<HistoricalSummaries>> {
+        let cache = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        match cache.as_ref() {
+            Some((fetched_at, historical_summaries))
+                if fetched_at.elapsed() < HISTORICAL_SUMMARIES_CACHE_TTL =>
+            {
+                Some(historical_summaries.clone())
+            }
+            _ => None,
+        }
+    }
+
+    fn set(&self, historical_summaries: Arc<HistoricalSummaries>) {
+        *self.0.lock().unwrap_or_else(|err| err.into_inner()) =
+            Some((Instant::now(), historical_summaries));
+    }
 }
 
 impl Default for HeaderOracle {
@@ -46,6 +83,7 @@ impl HeaderOracle {
             beacon_jsonrpc_tx: None,
             state_jsonrpc_tx: None,
             header_validator,
+            historical_summaries_cache: HistoricalSummariesCache::default(),
         }
     }
 
@@ -58,7 +96,7 @@ impl HeaderOracle {
         let content_key = HistoryContentKey::new_block_header_by_hash(block_hash);
         let endpoint = HistoryEndpoint::GetContent(content_key.clone());
         let (resp, mut resp_rx) = mpsc::unbounded_channel::<Result<Value, String>>();
-        let request = HistoryJsonRpcRequest { endpoint, resp };
+        let request = HistoryJsonRpcRequest::new(endpoint, resp);
         let tx = self.history_jsonrpc_tx()?;
         tx.send(request)?;
 
@@ -108,7 +146,7 @@ impl HeaderOracle {
     ) -> anyhow::Result<Enr> {
         let endpoint = HistoryEndpoint::GetEnr(*node_id);
         let (resp, mut resp_rx) = mpsc::unbounded_channel::<Result<Value, String>>();
-        let request = HistoryJsonRpcRequest { endpoint, resp };
+        let request = HistoryJsonRpcRequest::new(endpoint, resp);
         history_jsonrpc_tx.send(request)?;
 
         let enr_value = match resp_rx.recv().await {
@@ -127,7 +165,7 @@ impl HeaderOracle {
     ) -> anyhow::Result<Enr> {
         let endpoint = StateEndpoint::GetEnr(*node_id);
         let (resp, mut resp_rx) = mpsc::unbounded_channel::<Result<Value, String>>();
-        let request = StateJsonRpcRequest { endpoint, resp };
+        let request = StateJsonRpcRequest::new(endpoint, resp);
         state_jsonrpc_tx.send(request)?;
 
         let enr_value = match resp_rx.recv().await {
@@ -146,7 +184,7 @@ impl HeaderOracle {
     ) -> anyhow::Result<Enr> {
         let endpoint = BeaconEndpoint::GetEnr(*node_id);
         let (resp, mut resp_rx) = mpsc::unbounded_channel::<Result<Value, String>>();
-        let request = BeaconJsonRpcRequest { endpoint, resp };
+        let request = BeaconJsonRpcRequest::new(endpoint, resp);
         beacon_jsonrpc_tx.send(request)?;
 
         let enr_value = match resp_rx.recv().await {
@@ -163,7 +201,7 @@ impl HeaderOracle {
     pub async fn get_finalized_state_root(&self) -> anyhow::Result<B256> {
         let endpoint = BeaconEndpoint::FinalizedStateRoot;
         let (resp, mut resp_rx) = mpsc::unbounded_channel::<Result<Value, String>>();
-        let request = BeaconJsonRpcRequest { endpoint, resp };
+        let request = BeaconJsonRpcRequest::new(endpoint, resp);
         let tx = self.beacon_jsonrpc_tx()?;
         tx.send(request)?;
 
@@ -177,11 +215,57 @@ impl HeaderOracle {
         Ok(state_root)
     }
 
+    /// Return the latest historical summaries accepted by the beacon network. They are cached for
+    /// a short time, as they are needed to validate every post-Capella header.
+    pub async fn get_historical_summaries(&self) -> anyhow::Result<Arc<HistoricalSummaries>> {
+        if let Some(historical_summaries) = self.historical_summaries_cache.get() {
+            return Ok(historical_summaries);
+        }
+        let endpoint = BeaconEndpoint::HistoricalSummaries;
+        let (resp, mut resp_rx) = mpsc::unbounded_channel::<Result<Value, String>>();
+        let request = BeaconJsonRpcRequest::new(endpoint, resp);
+        let tx = self.beacon_jsonrpc_tx()?;
+        tx.send(request)?;
+
+        let historical_summaries = match resp_rx.recv().await {
+            Some(val) => val.map_err(|err| anyhow!("Beacon network request error: {err:?}"))?,
+            None => return Err(anyhow!("No response from Beacon network")),
+        };
+
+        let historical_summaries: HistoricalSummariesWithProof =
+            serde_json::from_value(historical_summaries)?;
+
+        let historical_summaries = Arc::new(historical_summaries.historical_summaries);
+        self.historical_summaries_cache
+            .set(historical_summaries.clone());
+        Ok(historical_summaries)
+    }
+
+    /// Return the historical summaries that the header proof has to be verified against, i.e. the
+    /// latest ones for post-Capella proofs and none for the other proofs. Post-Capella proofs
+    /// can't be verified if the beacon network is disabled or hasn't got the historical summaries.
+    pub async fn historical_summaries_for_proof(
+        &self,
+        proof: &BlockHeaderProof,
+    ) -> anyhow::Result<Option<Arc<HistoricalSummaries>>> {
+        let BlockHeaderProof::HistoricalSummariesBlockProof(_) = proof else {
+            return Ok(None);
+        };
+        ensure!(
+            self.beacon_jsonrpc_tx.is_some(),
+            "Beacon network required to validate post-Capella header"
+        );
+        let historical_summaries = self.get_historical_summaries().await.map_err(|err| {
+            anyhow!("Historical summaries required to validate post-Capella header: {err}")
+        })?;
+        Ok(Some(historical_summaries))
+    }
+
     /// Return latest finalized beacon header.
     pub async fn get_finalized_header(&self) -> anyhow::Result<BeaconBlockHeader> {
         let endpoint = BeaconEndpoint::FinalizedHeader;
         let (resp, mut resp_rx) = mpsc::unbounded_channel::<Result<Value, String>>();
-        let request = BeaconJsonRpcRequest { endpoint, resp };
+        let request = BeaconJsonRpcRequest::new(endpoint, resp);
         let tx = self.beacon_jsonrpc_tx()?;
         tx.send(request)?;
 
@@ -199,7 +283,7 @@ impl HeaderOracle {
     pub async fn get_light_client_store(&self) -> anyhow::Result<LightClientStore> {
         let endpoint = BeaconEndpoint::LightClientStore;
         let (resp, mut resp_rx) = mpsc::unbounded_channel::<Result<Value, String>>();
-        let request = BeaconJsonRpcRequest { endpoint, resp };
+        let request = BeaconJsonRpcRequest::new(endpoint, resp);
         let tx = self.beacon_jsonrpc_tx()?;
         tx.send(request)?;
 